@@ -0,0 +1,163 @@
+//! Per-strategy P&L attribution.
+//!
+//! Orders are tagged with the strategy whose signal placed them and their
+//! fills carry the tag. [`StrategyLedger`] keeps a separate position book per
+//! strategy from those fills, so realized and unrealized P&L (and the exposure
+//! counted against `PMENGINE_STRATEGY_EXPOSURE` budgets) can be reported per
+//! strategy next to the engine-wide totals.
+//!
+//! Fills of untagged orders (adopted from the exchange, or saved before
+//! orders were tagged) are booked under [`UNATTRIBUTED`]. Positions corrected
+//! by reconciliation are not re-attributed, so per-strategy books can drift
+//! from the engine's net positions after a correction. With a state directory
+//! the ledger is rebuilt from `fills.jsonl` on startup.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::Decimal;
+
+use crate::position::{Fill, PositionTracker};
+
+/// Ledger entry for fills without a strategy.
+pub const UNATTRIBUTED: &str = "unattributed";
+
+/// P&L of one strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyPnl {
+    pub strategy_id: String,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// Fees paid (already taken out of realized P&L)
+    pub fees: Decimal,
+    /// Notional of the strategy's open positions
+    pub notional: Decimal,
+    /// Fills attributed to the strategy
+    pub fills: u64,
+}
+
+impl StrategyPnl {
+    pub fn total_pnl(&self) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct StrategyBook {
+    positions: PositionTracker,
+    fills: u64,
+}
+
+/// Positions and P&L per strategy.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyLedger {
+    books: BTreeMap<String, StrategyBook>,
+}
+
+impl StrategyLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Book a fill against the strategy that placed its order.
+    pub fn apply_fill(&mut self, fill: &Fill) {
+        let strategy_id = fill.strategy_id.as_deref().unwrap_or(UNATTRIBUTED);
+        let book = self.books.entry(strategy_id.to_string()).or_default();
+        book.positions.get_or_create(&fill.token_id).apply_fill(fill);
+        book.fills += 1;
+    }
+
+    /// Mark every strategy's positions to the latest prices.
+    pub fn update_prices(&mut self, prices: &HashMap<String, Decimal>) {
+        for book in self.books.values_mut() {
+            book.positions.update_prices(prices);
+        }
+    }
+
+    /// Positions attributed to a strategy (None before its first fill).
+    pub fn positions(&self, strategy_id: &str) -> Option<&PositionTracker> {
+        self.books.get(strategy_id).map(|b| &b.positions)
+    }
+
+    /// Strategies (or [`UNATTRIBUTED`]) holding a token, with their size.
+    pub fn holders(&self, token_id: &str) -> Vec<(&str, Decimal)> {
+        self.books
+            .iter()
+            .filter_map(|(strategy_id, book)| {
+                let size = book.positions.get(token_id)?.size;
+                (!size.is_zero()).then_some((strategy_id.as_str(), size))
+            })
+            .collect()
+    }
+
+    /// P&L per strategy, by strategy ID.
+    pub fn report(&self) -> Vec<StrategyPnl> {
+        self.books
+            .iter()
+            .map(|(strategy_id, book)| StrategyPnl {
+                strategy_id: strategy_id.clone(),
+                realized_pnl: book.positions.total_realized_pnl(),
+                unrealized_pnl: book.positions.total_unrealized_pnl(),
+                fees: book.positions.total_fees(),
+                notional: book.positions.total_notional(),
+                fills: book.fills,
+            })
+            .collect()
+    }
+
+    pub fn log(&self) {
+        for pnl in self.report() {
+            tracing::info!(
+                strategy_id = pnl.strategy_id.as_str(),
+                realized_pnl = %pnl.realized_pnl,
+                unrealized_pnl = %pnl.unrealized_pnl,
+                total_pnl = %pnl.total_pnl(),
+                fees = %pnl.fees,
+                notional = %pnl.notional,
+                fills = pnl.fills,
+                "Strategy P&L"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fill(strategy_id: Option<&str>, is_buy: bool, price: Decimal, size: Decimal) -> Fill {
+        Fill {
+            order_id: "o".to_string(),
+            token_id: "token".to_string(),
+            is_buy,
+            price,
+            size,
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: strategy_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_pnl_is_attributed_per_strategy() {
+        let mut ledger = StrategyLedger::new();
+        // Both trade the same token; each keeps its own entry price
+        ledger.apply_fill(&fill(Some("maker"), true, dec!(0.40), dec!(10)));
+        ledger.apply_fill(&fill(Some("taker"), true, dec!(0.60), dec!(10)));
+        ledger.apply_fill(&fill(Some("maker"), false, dec!(0.50), dec!(5)));
+        ledger.apply_fill(&fill(None, true, dec!(0.50), dec!(2)));
+        ledger.update_prices(&HashMap::from([("token".to_string(), dec!(0.50))]));
+
+        let report = ledger.report();
+        let ids: Vec<_> = report.iter().map(|p| p.strategy_id.as_str()).collect();
+        assert_eq!(ids, vec!["maker", "taker", UNATTRIBUTED]);
+
+        let maker = &report[0];
+        assert_eq!((maker.realized_pnl, maker.unrealized_pnl, maker.fills), (dec!(0.5), dec!(0.5), 2));
+        let taker = &report[1];
+        assert_eq!((taker.realized_pnl, taker.unrealized_pnl), (dec!(0), dec!(-1)));
+        assert_eq!(taker.notional, dec!(5));
+        assert_eq!(ledger.positions("maker").unwrap().get("token").unwrap().size, dec!(5));
+        assert!(ledger.positions("idle").is_none());
+    }
+}
@@ -0,0 +1,377 @@
+//! Basket pricing for multi-outcome events.
+//!
+//! In a negRisk event exactly one outcome resolves YES, so a basket holding
+//! one YES share of every outcome always pays out $1. The sum of YES prices
+//! across the basket should therefore be ~1:
+//! - Sum of asks < 1: buying every YES locks in `1 - sum` per basket
+//! - Sum of bids > 1: selling every YES (from a held basket) locks in `sum - 1`
+//!
+//! The NO side of a negRisk event is priced too: through the NegRisk adapter
+//! one NO share of each of `n` outcomes converts into `n - 1` USDC, so buying
+//! them for less locks in the difference (see [`crate::conversion`]).
+//!
+//! The YES and NO tokens of a binary market are the same thing with two
+//! legs: one of them pays $1, so they are priced as a basket too.
+
+use crate::strategy::{MarketInfo, StrategyContext};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// Complement of a binary outcome price (the implied price of the other side).
+pub fn complement(price: Decimal) -> Decimal {
+    Decimal::ONE - price
+}
+
+/// Top-of-book state for one outcome of a basket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasketLeg {
+    /// YES token ID for this outcome
+    pub token_id: String,
+    /// Market question for this outcome (e.g., "Will X win?")
+    pub question: String,
+    /// Best bid price
+    pub bid: Option<Decimal>,
+    /// Best ask price
+    pub ask: Option<Decimal>,
+    /// Size at best bid
+    pub bid_size: Decimal,
+    /// Size at best ask
+    pub ask_size: Decimal,
+}
+
+impl BasketLeg {
+    /// A token's leg, at the top of its book in the context.
+    fn quote(ctx: &StrategyContext, token_id: &str, market: &MarketInfo) -> Self {
+        let book = ctx.order_books.get(token_id);
+        BasketLeg {
+            token_id: token_id.to_string(),
+            question: market.question.clone(),
+            bid: book.and_then(|b| b.best_bid()).map(|l| l.price),
+            ask: book.and_then(|b| b.best_ask()).map(|l| l.price),
+            bid_size: book.map(|b| b.bid_size()).unwrap_or_default(),
+            ask_size: book.map(|b| b.ask_size()).unwrap_or_default(),
+        }
+    }
+
+    /// Mid price (None if either side is missing).
+    pub fn mid(&self) -> Option<Decimal> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / Decimal::TWO),
+            _ => None,
+        }
+    }
+}
+
+/// The YES outcomes of one event, priced together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Basket {
+    /// Parent event slug (the market slug for a YES/NO pair)
+    pub event_slug: String,
+    /// Whether the event is negRisk (outcomes are mutually exclusive)
+    pub neg_risk: bool,
+    /// One leg per outcome, ordered by token ID for determinism
+    pub legs: Vec<BasketLeg>,
+}
+
+impl Basket {
+    /// Build the basket for an event from the context's markets and books.
+    ///
+    /// Returns None if no market in the context belongs to the event.
+    pub fn for_event(ctx: &StrategyContext, event_slug: &str) -> Option<Basket> {
+        let mut legs = BTreeMap::new();
+        let mut neg_risk = false;
+
+        for (token_id, market) in &ctx.markets {
+            if market.event_slug.as_deref() != Some(event_slug) {
+                continue;
+            }
+            neg_risk |= market.neg_risk;
+            legs.insert(token_id.clone(), BasketLeg::quote(ctx, token_id, market));
+        }
+
+        if legs.is_empty() {
+            return None;
+        }
+
+        Some(Basket {
+            event_slug: event_slug.to_string(),
+            neg_risk,
+            legs: legs.into_values().collect(),
+        })
+    }
+
+    /// Build baskets for every negRisk event in the context, ordered by slug.
+    pub fn all_neg_risk(ctx: &StrategyContext) -> Vec<Basket> {
+        let mut slugs: Vec<&str> = ctx
+            .markets
+            .values()
+            .filter(|m| m.neg_risk)
+            .filter_map(|m| m.event_slug.as_deref())
+            .collect();
+        slugs.sort_unstable();
+        slugs.dedup();
+
+        slugs
+            .into_iter()
+            .filter_map(|slug| Self::for_event(ctx, slug))
+            .collect()
+    }
+
+    /// Build the two-leg basket of a token and its sibling (the binary
+    /// market's other outcome).
+    ///
+    /// Returns None unless both tokens are in the context.
+    pub fn for_pair(ctx: &StrategyContext, token_id: &str) -> Option<Basket> {
+        let market = ctx.markets.get(token_id)?;
+        let sibling = market.sibling_token_id.as_deref()?;
+        let other = ctx.markets.get(sibling)?;
+        let mut legs = vec![
+            BasketLeg::quote(ctx, token_id, market),
+            BasketLeg::quote(ctx, sibling, other),
+        ];
+        legs.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+
+        Some(Basket {
+            event_slug: market.slug.clone(),
+            neg_risk: false,
+            legs,
+        })
+    }
+
+    /// Build the pair of every binary market whose two outcomes are both in
+    /// the context, ordered by market slug.
+    pub fn all_pairs(ctx: &StrategyContext) -> Vec<Basket> {
+        let mut pairs: BTreeMap<&str, Basket> = BTreeMap::new();
+        for (token_id, market) in &ctx.markets {
+            if market.sibling_token_id.is_none() || pairs.contains_key(market.slug.as_str()) {
+                continue;
+            }
+            if let Some(pair) = Self::for_pair(ctx, token_id) {
+                pairs.insert(&market.slug, pair);
+            }
+        }
+        pairs.into_values().collect()
+    }
+
+    /// The NO side of an event's basket: the other outcome of each leg's
+    /// market, in the same order.
+    ///
+    /// Returns None unless every NO token is in the context.
+    pub fn no_side(&self, ctx: &StrategyContext) -> Option<Basket> {
+        let legs = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let market = ctx.markets.get(&leg.token_id)?;
+                let no = market.sibling_token_id.as_deref()?;
+                Some(BasketLeg::quote(ctx, no, ctx.markets.get(no)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Basket {
+            event_slug: self.event_slug.clone(),
+            neg_risk: self.neg_risk,
+            legs,
+        })
+    }
+
+    /// Number of outcomes in the basket.
+    pub fn len(&self) -> usize {
+        self.legs.len()
+    }
+
+    /// Whether the basket has no outcomes.
+    pub fn is_empty(&self) -> bool {
+        self.legs.is_empty()
+    }
+
+    /// Cost to buy one share of every outcome at the best ask.
+    ///
+    /// None if any leg has no ask.
+    pub fn sum_asks(&self) -> Option<Decimal> {
+        self.legs.iter().map(|l| l.ask).sum()
+    }
+
+    /// Proceeds from selling one share of every outcome at the best bid.
+    ///
+    /// None if any leg has no bid.
+    pub fn sum_bids(&self) -> Option<Decimal> {
+        self.legs.iter().map(|l| l.bid).sum()
+    }
+
+    /// Sum of mid prices (the market's total implied probability).
+    pub fn sum_mids(&self) -> Option<Decimal> {
+        self.legs.iter().map(|l| l.mid()).sum()
+    }
+
+    /// Edge from buying the basket at the asks (`1 - sum_asks`).
+    ///
+    /// Positive means the basket costs less than its guaranteed $1 payout.
+    pub fn buy_edge(&self) -> Option<Decimal> {
+        self.sum_asks().map(complement)
+    }
+
+    /// Edge from selling the basket at the bids (`sum_bids - 1`).
+    ///
+    /// Positive means a held basket can be sold for more than its $1 payout.
+    pub fn sell_edge(&self) -> Option<Decimal> {
+        self.sum_bids().map(|sum| sum - Decimal::ONE)
+    }
+
+    /// Edge from buying a NO side (see [`Self::no_side`]) at the asks and
+    /// converting it into `n - 1` USDC (`n - 1 - sum_asks`).
+    pub fn conversion_edge(&self) -> Option<Decimal> {
+        let payout = Decimal::from(self.len().saturating_sub(1));
+        self.sum_asks().map(|sum| payout - sum)
+    }
+
+    /// Implied probability of each outcome: mids normalized to sum to 1.
+    ///
+    /// Returns (token_id, probability) pairs in leg order, or None if any leg
+    /// has no mid or the mids sum to zero.
+    pub fn implied_probabilities(&self) -> Option<Vec<(String, Decimal)>> {
+        let total = self.sum_mids()?;
+        if total.is_zero() {
+            return None;
+        }
+
+        self.legs
+            .iter()
+            .map(|l| l.mid().map(|mid| (l.token_id.clone(), mid / total)))
+            .collect()
+    }
+
+    /// Largest basket size fillable at the best asks (min ask size).
+    pub fn max_buy_size(&self) -> Decimal {
+        self.legs
+            .iter()
+            .map(|l| l.ask_size)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Largest basket size fillable at the best bids (min bid size).
+    pub fn max_sell_size(&self) -> Decimal {
+        self.legs
+            .iter()
+            .map(|l| l.bid_size)
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use crate::strategy::MarketInfo;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn make_context(legs: &[(&str, &str, Decimal, Decimal)]) -> StrategyContext {
+        let mut order_books = HashMap::new();
+        let mut markets = HashMap::new();
+
+        for (token_id, event, bid, ask) in legs {
+            let mut book = OrderBook::new(token_id.to_string());
+            book.bids = vec![Level { price: *bid, size: dec!(100) }];
+            book.asks = vec![Level { price: *ask, size: dec!(40) }];
+            order_books.insert(token_id.to_string(), Arc::new(book));
+
+            let info = MarketInfo::new(
+                format!("Will {} win?", token_id),
+                "Yes".to_string(),
+                format!("{}-market", token_id),
+                None,
+            )
+            .with_event(event.to_string(), true);
+            markets.insert(token_id.to_string(), info);
+        }
+
+        StrategyContext::for_test(order_books, markets)
+    }
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(complement(dec!(0.30)), dec!(0.70));
+    }
+
+    #[test]
+    fn test_basket_sums_and_edges() {
+        let ctx = make_context(&[
+            ("a", "election", dec!(0.48), dec!(0.50)),
+            ("b", "election", dec!(0.28), dec!(0.30)),
+            ("c", "election", dec!(0.14), dec!(0.16)),
+            ("x", "other", dec!(0.40), dec!(0.60)),
+        ]);
+
+        let basket = ctx.basket("election").unwrap();
+        assert_eq!(basket.len(), 3);
+        assert!(basket.neg_risk);
+        assert_eq!(basket.sum_asks(), Some(dec!(0.96)));
+        assert_eq!(basket.sum_bids(), Some(dec!(0.90)));
+        assert_eq!(basket.sum_mids(), Some(dec!(0.93)));
+        assert_eq!(basket.buy_edge(), Some(dec!(0.04)));
+        assert_eq!(basket.sell_edge(), Some(dec!(-0.10)));
+        assert_eq!(basket.max_buy_size(), dec!(40));
+        assert_eq!(basket.max_sell_size(), dec!(100));
+
+        assert_eq!(ctx.baskets().len(), 2);
+        assert!(ctx.basket("missing").is_none());
+    }
+
+    #[test]
+    fn test_implied_probabilities_are_normalized() {
+        let ctx = make_context(&[
+            ("a", "e", dec!(0.55), dec!(0.65)),
+            ("b", "e", dec!(0.55), dec!(0.65)),
+        ]);
+
+        let probs = ctx.basket("e").unwrap().implied_probabilities().unwrap();
+        assert_eq!(probs.len(), 2);
+        assert_eq!(probs[0].1, dec!(0.5));
+        assert_eq!(probs.iter().map(|(_, p)| *p).sum::<Decimal>(), dec!(1));
+    }
+
+    #[test]
+    fn test_pairs_price_both_outcomes() {
+        let mut ctx = make_context(&[
+            ("yes", "e", dec!(0.60), dec!(0.62)),
+            ("no", "e", dec!(0.35), dec!(0.36)),
+            ("single", "f", dec!(0.90), dec!(0.91)),
+        ]);
+        let tokens = vec!["yes".to_string(), "no".to_string()];
+        for (idx, token_id) in ["yes", "no"].into_iter().enumerate() {
+            let info = MarketInfo::new("Will it?".into(), token_id.into(), "binary".into(), None)
+                .with_outcomes(idx, tokens.clone());
+            ctx.markets.insert(token_id.to_string(), info);
+        }
+
+        let pairs = ctx.pairs();
+        assert_eq!(pairs.len(), 1);
+        let pair = &pairs[0];
+        assert_eq!(pair.event_slug, "binary");
+        assert!(!pair.neg_risk);
+        assert_eq!(pair.legs.iter().map(|l| l.token_id.as_str()).collect::<Vec<_>>(), ["no", "yes"]);
+        assert_eq!(pair.buy_edge(), Some(dec!(0.02)));
+        assert_eq!(pair.max_buy_size(), dec!(40));
+        assert!(Basket::for_pair(&ctx, "single").is_none());
+    }
+
+    #[test]
+    fn test_missing_quote_voids_sums() {
+        let mut ctx = make_context(&[("a", "e", dec!(0.40), dec!(0.45))]);
+        ctx.markets.insert(
+            "b".to_string(),
+            MarketInfo::new("Will b win?".into(), "Yes".into(), "b".into(), None)
+                .with_event("e".to_string(), true),
+        );
+
+        let basket = ctx.basket("e").unwrap();
+        assert_eq!(basket.len(), 2);
+        assert!(basket.sum_asks().is_none());
+        assert!(basket.implied_probabilities().is_none());
+        assert_eq!(basket.max_buy_size(), dec!(0));
+    }
+}
@@ -0,0 +1,239 @@
+//! Carry analytics for long-dated positions.
+//!
+//! A binary outcome token pays 1 at resolution, so a position bought at 0.97
+//! with 60 days left earns about 3% over two months: roughly 19% a year, and
+//! much less than the headline "97% sure" suggests. For every position held
+//! longer than [`LONG_DATED_AFTER`], the [`CarryReport`] shows the capital
+//! locked, how fast the price has drifted in the position's favour since it
+//! was opened, and the annualized yield still available by holding to
+//! resolution. Positions yielding less than `PMENGINE_MIN_CARRY_APY` are
+//! flagged as parked capital so it can be redeployed.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+use crate::position::Position;
+use crate::safe_math::{add, div, mul, sub};
+
+/// Positions held at least this long are analyzed.
+pub const LONG_DATED_AFTER: Duration = Duration::days(1);
+
+const SECS_PER_DAY: i64 = 86_400;
+const DAYS_PER_YEAR: i64 = 365;
+
+/// Carry of one position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionCarry {
+    pub token_id: String,
+    /// Strategy trading the token (None = e.g. a position from a previous run)
+    pub strategy: Option<String>,
+    /// Cost basis of the position (USDC)
+    pub capital_locked: Decimal,
+    pub days_held: Decimal,
+    /// Days until the market's end date (None = unknown or already past)
+    pub days_to_resolution: Option<Decimal>,
+    /// Return on capital per day held, from price movement since entry
+    pub drift_per_day: Decimal,
+    /// Return left if the position resolves in its favour at the current mark
+    pub remaining_yield: Decimal,
+    /// `remaining_yield` annualized over the time to resolution
+    pub annualized_yield: Option<Decimal>,
+    /// Annualized yield below the configured minimum
+    pub parked: bool,
+}
+
+impl PositionCarry {
+    /// Analyze a position, or None if it is flat, too recent or unpriced.
+    pub fn analyze(
+        position: &Position,
+        strategy: Option<String>,
+        end_date: Option<DateTime<Utc>>,
+        min_apy: Decimal,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let opened_at = position.opened_at?;
+        let held = now - opened_at;
+        if position.size.is_zero() || held < LONG_DATED_AFTER {
+            return None;
+        }
+        let mark = position.last_price.unwrap_or(position.avg_entry_price);
+
+        // A short is analyzed as the complementary token: bought at 1 - entry
+        let (entry, mark) = if position.size > Decimal::ZERO {
+            (position.avg_entry_price, mark)
+        } else {
+            (Decimal::ONE - position.avg_entry_price, Decimal::ONE - mark)
+        };
+        if entry <= Decimal::ZERO || mark <= Decimal::ZERO {
+            return None;
+        }
+
+        let days = |d: Duration| {
+            div(Decimal::from(d.num_seconds()), Decimal::from(SECS_PER_DAY), "carry days")
+        };
+        let days_held = days(held);
+        let capital_locked = mul(position.size.abs(), entry, "carry capital");
+        let drift = div(sub(mark, entry, "carry drift"), entry, "carry drift");
+        let drift_per_day = div(drift, days_held, "carry drift per day");
+        let remaining_yield = div(sub(Decimal::ONE, mark, "carry yield"), mark, "carry yield");
+
+        let days_to_resolution = end_date.map(|end| days(end - now)).filter(|d| *d > Decimal::ZERO);
+        let annualized_yield = days_to_resolution.map(|d| {
+            let per_day = div(remaining_yield, d, "carry annualized yield");
+            mul(per_day, Decimal::from(DAYS_PER_YEAR), "carry annualized yield").round_dp(4)
+        });
+
+        Some(Self {
+            token_id: position.token_id.clone(),
+            strategy,
+            capital_locked: capital_locked.round_dp(2),
+            days_held: days_held.round_dp(2),
+            days_to_resolution: days_to_resolution.map(|d| d.round_dp(2)),
+            drift_per_day: drift_per_day.round_dp(6),
+            remaining_yield: remaining_yield.round_dp(4),
+            annualized_yield,
+            parked: annualized_yield.is_some_and(|y| y < min_apy),
+        })
+    }
+}
+
+/// Carry summary across long-dated positions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CarryReport {
+    /// Analyzed positions, lowest annualized yield first
+    pub positions: Vec<PositionCarry>,
+    /// Capital locked in analyzed positions
+    pub capital_locked: Decimal,
+    /// Capital in positions flagged as parked
+    pub parked_capital: Decimal,
+    /// Capital-weighted annualized yield of positions with a known end date
+    pub avg_annualized_yield: Option<Decimal>,
+}
+
+impl CarryReport {
+    /// Build a report from per-position carry.
+    pub fn new(mut positions: Vec<PositionCarry>) -> Self {
+        // Unknown yields sort last
+        positions.sort_by(|a, b| match (a.annualized_yield, b.annualized_yield) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.token_id.cmp(&b.token_id),
+        });
+
+        let mut capital_locked = Decimal::ZERO;
+        let mut parked_capital = Decimal::ZERO;
+        let mut weighted = Decimal::ZERO;
+        let mut weight = Decimal::ZERO;
+        for carry in &positions {
+            capital_locked = add(capital_locked, carry.capital_locked, "carry capital");
+            if carry.parked {
+                parked_capital = add(parked_capital, carry.capital_locked, "carry parked capital");
+            }
+            if let Some(apy) = carry.annualized_yield {
+                let weighted_apy = mul(apy, carry.capital_locked, "carry weighting");
+                weighted = add(weighted, weighted_apy, "carry weighting");
+                weight = add(weight, carry.capital_locked, "carry weighting");
+            }
+        }
+
+        Self {
+            avg_annualized_yield: (weight > Decimal::ZERO)
+                .then(|| div(weighted, weight, "carry average yield").round_dp(4)),
+            positions,
+            capital_locked,
+            parked_capital,
+        }
+    }
+
+    /// Log the report.
+    pub fn log(&self) {
+        if self.positions.is_empty() {
+            return;
+        }
+        tracing::info!(
+            positions = self.positions.len(),
+            capital_locked = %self.capital_locked,
+            parked_capital = %self.parked_capital,
+            avg_annualized_yield = ?self.avg_annualized_yield.map(|y| y.to_string()),
+            "Position carry"
+        );
+        for carry in self.positions.iter().filter(|c| c.parked) {
+            tracing::warn!(
+                token_id = carry.token_id.as_str(),
+                strategy = carry.strategy.as_deref().unwrap_or(crate::utilization::UNATTRIBUTED),
+                capital_locked = %carry.capital_locked,
+                days_held = %carry.days_held,
+                days_to_resolution = ?carry.days_to_resolution.map(|d| d.to_string()),
+                remaining_yield = %carry.remaining_yield,
+                annualized_yield = ?carry.annualized_yield.map(|y| y.to_string()),
+                drift_per_day = %carry.drift_per_day,
+                "Low-yield position is parking capital"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(size: Decimal, entry: Decimal, mark: Decimal, opened_at: DateTime<Utc>) -> Position {
+        let mut position = Position::new("token".to_string());
+        position.size = size;
+        position.avg_entry_price = entry;
+        position.opened_at = Some(opened_at);
+        position.update_price(mark);
+        position
+    }
+
+    #[test]
+    fn test_sure_bet_far_from_resolution_is_parked() {
+        let now = Utc::now();
+        // Bought 100 at 0.96 ten days ago, now 0.97 with 73 days left
+        let held = position(dec!(100), dec!(0.96), dec!(0.97), now - Duration::days(10));
+        let analyze = |days| {
+            PositionCarry::analyze(&held, None, Some(now + Duration::days(days)), dec!(0.2), now)
+        };
+        let carry = analyze(73).unwrap();
+
+        assert_eq!(carry.capital_locked, dec!(96));
+        assert_eq!(carry.days_held, dec!(10));
+        assert_eq!(carry.remaining_yield, dec!(0.0309));
+        // 3.09% over 73 days = 15.5% a year
+        assert_eq!(carry.annualized_yield, Some(dec!(0.1546)));
+        assert_eq!(carry.drift_per_day, dec!(0.001042));
+        assert!(carry.parked);
+
+        // Same price a week from resolution is a good use of capital
+        assert!(!analyze(7).unwrap().parked);
+
+        // Positions opened today are not long-dated yet
+        let fresh = position(dec!(100), dec!(0.96), dec!(0.97), now - Duration::hours(2));
+        assert!(PositionCarry::analyze(&fresh, None, None, dec!(0.2), now).is_none());
+    }
+
+    #[test]
+    fn test_report_orders_by_yield_and_weights_by_capital() {
+        let now = Utc::now();
+        let opened = now - Duration::days(3);
+        let end = Some(now + Duration::days(365));
+        let analyze = |size, entry, mark| {
+            PositionCarry::analyze(&position(size, entry, mark, opened), None, end, dec!(0.1), now).unwrap()
+        };
+
+        // Long at 0.95 (5.3% left); short at 0.5, marked 0.2 (i.e. long NO at 0.8, 25% left)
+        let report = CarryReport::new(vec![
+            analyze(dec!(-10), dec!(0.5), dec!(0.2)),
+            analyze(dec!(10), dec!(0.95), dec!(0.95)),
+        ]);
+        assert_eq!(report.positions[0].annualized_yield, Some(dec!(0.0526)));
+        assert_eq!(report.positions[1].annualized_yield, Some(dec!(0.25)));
+        assert_eq!(report.positions[1].capital_locked, dec!(5));
+        assert_eq!(report.capital_locked, dec!(14.5));
+        assert_eq!(report.parked_capital, dec!(9.5));
+        // (0.0526 * 9.5 + 0.25 * 5) / 14.5
+        assert_eq!(report.avg_annualized_yield, Some(dec!(0.1207)));
+    }
+}
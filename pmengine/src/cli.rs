@@ -0,0 +1,752 @@
+//! The `pmengine` command line.
+//!
+//! [`main`] is the whole CLI, so that a crate with its own strategies can
+//! ship it unchanged: register them with [`Engine::register_factory`] and
+//! call `pmengine::cli::main()` from its binary, and `pmengine run <name>`
+//! and `pmengine list` see them next to the built-in ones. Like this
+//! crate's binary, it needs `#![recursion_limit = "256"]`.
+
+use crate::{Config, ConfigFile, Engine, GammaClient, MarketFilter, PolymarketClient};
+use clap::{Parser, Subcommand, ValueEnum};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::path::PathBuf;
+use tracing::{info, Level};
+use tracing_subscriber::FmtSubscriber;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "pmengine",
+    about = "Rust HFT engine for Polymarket trading"
+)]
+struct Cli {
+    /// Log level (trace, debug, info, warn, error)
+    #[arg(short, long, default_value = "info", global = true)]
+    log_level: String,
+
+    /// Path to .env file (default: searches for .env in current and parent directories)
+    #[arg(long, global = true)]
+    env_file: Option<PathBuf>,
+
+    /// Path to a TOML config file; environment variables override it
+    /// (default: PMENGINE_CONFIG, or pmengine.toml if present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Output format for command results (json writes a single document to
+    /// stdout and sends logs to stderr)
+    #[arg(short, long, value_enum, default_value = "table", global = true)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Run one or more strategies
+    Run {
+        /// Strategy names to run (e.g., sure_bets market_maker)
+        #[arg(required = true)]
+        strategies: Vec<String>,
+
+        /// Dry run mode - don't place real orders
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+
+        /// Maximum number of ticks before automatic shutdown (0 = unlimited)
+        #[arg(long, default_value = "0")]
+        max_ticks: u64,
+
+        /// Skip WebSocket warmup (useful when WS connection is unavailable)
+        #[arg(long, default_value = "false")]
+        skip_warmup: bool,
+
+        /// Override a strategy parameter, e.g. spread_bps=150 or
+        /// market_maker.spread_bps=150 when running several (repeatable)
+        #[arg(long = "param", value_name = "[STRATEGY.]NAME=VALUE")]
+        params: Vec<String>,
+    },
+
+    /// Record market data to CSV for backtesting (dry-run, places no orders)
+    Record {
+        /// Strategies whose markets to record (e.g., sure_bets)
+        strategies: Vec<String>,
+
+        /// Token ID to record (repeatable)
+        #[arg(long = "token")]
+        tokens: Vec<String>,
+
+        /// Directory to record to (default: PMENGINE_RECORD_DIR, or ./data)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Maximum number of ticks before stopping (0 = until ctrl-c)
+        #[arg(long, default_value = "0")]
+        max_ticks: u64,
+    },
+
+    /// Test Gamma API only (no CLOB auth needed, prints discovered markets and exits)
+    TestGamma,
+
+    /// Find markets on the Gamma API (no CLOB auth needed)
+    Markets {
+        #[command(subcommand)]
+        command: MarketsCommand,
+    },
+
+    /// List available strategies
+    List,
+
+    /// Show current positions for the configured account
+    Positions,
+
+    /// Show open orders for the configured account
+    Orders,
+
+    /// Export the trade journal (signals, risk decisions, orders and fills)
+    Export {
+        /// Export format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+
+        /// Start of the range, inclusive (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End of the range, exclusive (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Journal to export (default: PMENGINE_TRADE_JOURNAL)
+        #[arg(long)]
+        journal: Option<PathBuf>,
+    },
+
+    /// Show a day's P&L report (realized/unrealized P&L, fees, win rate, per strategy)
+    Report {
+        /// UTC day to report, YYYY-MM-DD (default: today)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// State directory holding the reports and fills (default: PMENGINE_STATE_DIR)
+        #[arg(long)]
+        state_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MarketsCommand {
+    /// Search open markets by keyword, tag or category
+    Search {
+        /// Keywords (with --tag or --category, kept markets must mention them)
+        query: Vec<String>,
+
+        /// Gamma tag slug, e.g. crypto or nba (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Gamma category, e.g. Politics
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Minimum lifetime volume in USDC
+        #[arg(long)]
+        min_volume: Option<f64>,
+
+        /// Minimum liquidity in USDC
+        #[arg(long)]
+        min_liquidity: Option<f64>,
+
+        /// Maximum number of markets to list
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable tables and log lines
+    Table,
+    /// Machine-readable JSON
+    Json,
+}
+
+/// Print a command result as pretty JSON on stdout.
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Load .env file, searching in current directory and parent directories up to 3 levels.
+fn load_dotenv(explicit_path: Option<PathBuf>) {
+    if let Some(path) = explicit_path {
+        match dotenvy::from_path(&path) {
+            Ok(_) => eprintln!("[pmengine] Loaded env from: {}", path.display()),
+            Err(e) => eprintln!("[pmengine] Warning: Failed to load {}: {}", path.display(), e),
+        }
+        return;
+    }
+
+    // Search for .env in current directory and up to 3 parent directories
+    let search_paths = [
+        ".env",
+        "../.env",
+        "../../.env",
+        "../../../.env",
+    ];
+
+    for relative_path in search_paths {
+        if let Ok(path) = std::fs::canonicalize(relative_path) {
+            if path.exists() {
+                match dotenvy::from_path(&path) {
+                    Ok(_) => {
+                        eprintln!("[pmengine] Loaded env from: {}", path.display());
+                        return;
+                    }
+                    Err(e) => {
+                        eprintln!("[pmengine] Warning: Found {} but failed to load: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    }
+
+    // Also try the standard dotenvy search (which looks in CWD)
+    if dotenvy::dotenv().is_ok() {
+        eprintln!("[pmengine] Loaded env from current directory");
+    } else {
+        eprintln!("[pmengine] Note: No .env file found (this is OK if env vars are set)");
+    }
+}
+
+/// Load the config file beneath the environment. A missing default
+/// `pmengine.toml` is fine; a missing explicit file is an error.
+fn load_config_file(explicit_path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = match explicit_path.or_else(|| std::env::var_os("PMENGINE_CONFIG").map(PathBuf::from)) {
+        Some(path) => path,
+        None => {
+            let default = PathBuf::from(crate::config_file::DEFAULT_PATH);
+            if !default.exists() {
+                return Ok(());
+            }
+            default
+        }
+    };
+    let file = ConfigFile::load(&path)?;
+    let applied = file.apply_to_env();
+    eprintln!(
+        "[pmengine] Loaded config from: {} ({} of {} settings not overridden by env)",
+        path.display(),
+        applied,
+        file.vars().len()
+    );
+    Ok(())
+}
+
+/// Parse the command line and run the command.
+pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    // Load .env file FIRST, before anything else needs env vars, then the
+    // config file underneath both
+    load_dotenv(cli.env_file.clone());
+    load_config_file(cli.config.clone())?;
+
+    // Set up logging
+    let level = match cli.log_level.to_lowercase().as_str() {
+        "trace" => Level::TRACE,
+        "debug" => Level::DEBUG,
+        "info" => Level::INFO,
+        "warn" => Level::WARN,
+        "error" => Level::ERROR,
+        _ => Level::INFO,
+    };
+
+    // Keep stdout clean for JSON output and exports
+    let builder = FmtSubscriber::builder()
+        .with_max_level(level)
+        .with_target(true)
+        .with_thread_ids(true)
+        .compact();
+    if cli.output == OutputFormat::Json || matches!(cli.command, Some(Commands::Export { .. })) {
+        builder.with_writer(std::io::stderr).init();
+    } else {
+        builder.init();
+    }
+
+    info!("pmengine starting...");
+
+    // Handle commands
+    match cli.command {
+        Some(Commands::TestGamma) => {
+            run_test_gamma(cli.output).await
+        }
+        Some(Commands::Markets { command }) => {
+            let MarketsCommand::Search { query, tags, category, min_volume, min_liquidity, limit } = command;
+            let filter = MarketFilter { min_volume, min_liquidity };
+            run_markets_search(query.join(" "), tags, category, filter, limit, cli.output).await
+        }
+        Some(Commands::List) => {
+            run_list(cli.output)
+        }
+        Some(Commands::Positions) => {
+            run_positions(cli.output).await
+        }
+        Some(Commands::Orders) => {
+            run_orders(cli.output).await
+        }
+        Some(Commands::Run { strategies, dry_run, max_ticks, skip_warmup, params }) => {
+            run_strategies(strategies, dry_run, max_ticks, skip_warmup, params).await
+        }
+        Some(Commands::Record { strategies, tokens, dir, max_ticks }) => {
+            run_record(strategies, tokens, dir, max_ticks).await
+        }
+        Some(Commands::Export { format, from, to, journal }) => {
+            run_export(format, from, to, journal)
+        }
+        Some(Commands::Report { date, state_dir }) => {
+            run_report(date, state_dir, cli.output)
+        }
+        None => {
+            eprintln!("Usage: pmengine <command>");
+            eprintln!();
+            eprintln!("Commands:");
+            eprintln!("  run <strategies...>  Run one or more strategies");
+            eprintln!("  record [strategies]  Record market data to CSV (--token ID, --dir DIR)");
+            eprintln!("  list                 List available strategies");
+            eprintln!("  test-gamma           Test Gamma API (no auth needed)");
+            eprintln!("  markets search <q>   Search markets (--tag, --category, --min-volume, --limit)");
+            eprintln!("  positions            Show account positions");
+            eprintln!("  orders               Show open orders");
+            eprintln!("  export               Export the trade journal (--format csv|json, --from, --to)");
+            eprintln!("  report               Show a day's P&L report (--date YYYY-MM-DD)");
+            eprintln!();
+            eprintln!("Global options:");
+            eprintln!("  --output json|table  Output format (default: table)");
+            eprintln!();
+            eprintln!("Examples:");
+            eprintln!("  pmengine run sure_bets --dry-run");
+            eprintln!("  pmengine run sure_bets market_maker --max-ticks 10");
+            eprintln!("  pmengine run market_maker --param spread_bps=150 --dry-run");
+            eprintln!("  pmengine record sure_bets --dir data");
+            eprintln!("  pmengine list");
+            eprintln!("  pmengine markets search bitcoin --min-liquidity 1000");
+            eprintln!("  pmengine orders --output json");
+            eprintln!("  pmengine export --format csv --from 2026-01-01 --to 2027-01-01 > trades.csv");
+            eprintln!("  pmengine report --date 2026-01-15");
+            Ok(())
+        }
+    }
+}
+
+/// A sure bet candidate in `test-gamma --output json`.
+#[derive(Serialize)]
+struct GammaCandidate {
+    question: String,
+    slug: String,
+    outcome: String,
+    token_id: String,
+    price: Decimal,
+    hours_until_expiry: f64,
+}
+
+async fn run_test_gamma(output: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    info!("Running Gamma API test mode...");
+    let gamma = GammaClient::new();
+
+    // Fetch sure bet candidates: expiring within 2 hours, 95%+ certainty
+    match gamma.fetch_sure_bet_candidates(2.0, dec!(0.95)).await {
+        Ok(markets) => {
+            info!("Found {} sure bet candidates", markets.len());
+            let mut candidates = Vec::new();
+            for market in &markets {
+                if let Some(hours) = market.hours_until_expiry() {
+                    if let Some(idx) = market.highest_certainty_index() {
+                        let price = market.outcome_prices.get(idx).copied().unwrap_or_default();
+                        let outcome = market.outcomes.get(idx).cloned().unwrap_or_default();
+                        if output == OutputFormat::Table {
+                            info!(
+                                "  {} | {} @ {:.2}¢ | {:.1}h left | slug: {}",
+                                market.question,
+                                outcome,
+                                price * dec!(100),
+                                hours,
+                                market.slug
+                            );
+                        }
+                        candidates.push(GammaCandidate {
+                            question: market.question.clone(),
+                            slug: market.slug.clone(),
+                            outcome,
+                            token_id: market.clob_token_ids.get(idx).cloned().unwrap_or_default(),
+                            price,
+                            hours_until_expiry: hours,
+                        });
+                    }
+                }
+            }
+            info!("Gamma API test completed successfully");
+
+            if output == OutputFormat::Json {
+                print_json(&serde_json::json!({ "markets": candidates }))?;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Gamma API test failed: {}", e);
+            Err(e.to_string().into())
+        }
+    }
+}
+
+/// A market outcome in `markets search --output json`.
+#[derive(Serialize)]
+struct MarketOutcome {
+    outcome: String,
+    token_id: String,
+    price: Option<Decimal>,
+}
+
+/// A market in `markets search --output json`.
+#[derive(Serialize)]
+struct MarketListing {
+    question: String,
+    slug: String,
+    event_slug: Option<String>,
+    category: Option<String>,
+    liquidity: Option<f64>,
+    volume: Option<f64>,
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+    outcomes: Vec<MarketOutcome>,
+}
+
+async fn run_markets_search(
+    query: String,
+    tags: Vec<String>,
+    category: Option<String>,
+    filter: MarketFilter,
+    limit: usize,
+    output: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let gamma = GammaClient::new();
+    let query = query.trim().to_lowercase();
+
+    let mut markets = if !tags.is_empty() {
+        let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+        gamma.fetch_by_tags(&tags, filter, limit).await?
+    } else if let Some(category) = &category {
+        gamma.fetch_by_category(category, filter, limit).await?
+    } else if !query.is_empty() {
+        gamma.search_markets(&query, filter, limit).await?
+    } else {
+        return Err("markets search needs a query, --tag or --category".into());
+    };
+    // Tag and category listings aren't keyword searches
+    if (!tags.is_empty() || category.is_some()) && !query.is_empty() {
+        markets.retain(|m| m.question.to_lowercase().contains(&query) || m.slug.contains(&query));
+    }
+
+    if output == OutputFormat::Json {
+        let listings: Vec<MarketListing> = markets
+            .into_iter()
+            .map(|m| MarketListing {
+                outcomes: m
+                    .outcomes
+                    .iter()
+                    .zip(&m.clob_token_ids)
+                    .enumerate()
+                    .map(|(i, (outcome, token_id))| MarketOutcome {
+                        outcome: outcome.clone(),
+                        token_id: token_id.clone(),
+                        price: m.outcome_prices.get(i).copied(),
+                    })
+                    .collect(),
+                question: m.question,
+                slug: m.slug,
+                event_slug: m.event_slug,
+                category: m.category,
+                liquidity: m.liquidity,
+                volume: m.volume,
+                end_date: m.end_date,
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "markets": listings }));
+    }
+
+    println!("Markets ({}):", markets.len());
+    println!();
+    println!("  {:>12} {:>12} {:>8}  QUESTION", "VOLUME", "LIQUIDITY", "YES");
+    for m in &markets {
+        let yes = m.outcome_prices.get(m.yes_index()).map(|p| format!("{:.3}", p)).unwrap_or_default();
+        println!(
+            "  {:>12.0} {:>12.0} {:>8}  {}",
+            m.volume.unwrap_or_default(),
+            m.liquidity.unwrap_or_default(),
+            yes,
+            m.question
+        );
+        println!("  {:>34}  slug: {}", "", m.slug);
+    }
+
+    Ok(())
+}
+
+/// A strategy in `list --output json`.
+#[derive(Serialize)]
+struct StrategyListing<'a> {
+    name: &'a str,
+    requires_market_discovery: bool,
+}
+
+fn run_list(output: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let reg = crate::registry::all();
+    let names: Vec<&String> = reg.keys().collect();
+
+    if output == OutputFormat::Json {
+        let strategies: Vec<_> = names
+            .into_iter()
+            .map(|name| StrategyListing {
+                name,
+                requires_market_discovery: reg[name].requires_market_discovery,
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "strategies": strategies }));
+    }
+
+    println!("Available strategies:");
+    println!();
+
+    for name in names {
+        let info = reg.get(name).unwrap();
+        let discovery = if info.requires_market_discovery {
+            " [market-discovery]"
+        } else {
+            ""
+        };
+        println!("  {}{}", name, discovery);
+    }
+
+    println!();
+    println!("Run with: pmengine run <strategy> [--dry-run] [--max-ticks N]");
+
+    Ok(())
+}
+
+/// Authenticate a client for read-only account queries.
+async fn account_client() -> Result<PolymarketClient, Box<dyn std::error::Error>> {
+    let config = Config::from_env()?;
+    Ok(PolymarketClient::new(&config, false).await?)
+}
+
+async fn run_positions(output: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let client = account_client().await?;
+    let positions = client.positions().await?;
+
+    if output == OutputFormat::Json {
+        return print_json(&serde_json::json!({
+            "address": client.holder().to_string(),
+            "positions": positions,
+        }));
+    }
+
+    println!("Positions for {} ({}):", client.holder(), positions.len());
+    println!();
+    println!(
+        "  {:<12} {:>10} {:>8} {:>8} {:>10} {:>10}  MARKET",
+        "OUTCOME", "SIZE", "AVG", "PRICE", "VALUE", "PNL"
+    );
+    for p in &positions {
+        println!(
+            "  {:<12} {:>10.2} {:>8.3} {:>8.3} {:>10.2} {:>10.2}  {}",
+            p.outcome, p.size, p.avg_price, p.cur_price, p.current_value, p.cash_pnl, p.title
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_orders(output: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let client = account_client().await?;
+    let orders = client.open_orders().await?;
+
+    if output == OutputFormat::Json {
+        return print_json(&serde_json::json!({ "orders": orders }));
+    }
+
+    println!("Open orders ({}):", orders.len());
+    println!();
+    println!(
+        "  {:<4} {:>8} {:>10} {:>10}  {:<12} ORDER ID",
+        "SIDE", "PRICE", "SIZE", "MATCHED", "OUTCOME"
+    );
+    for o in &orders {
+        println!(
+            "  {:<4} {:>8.3} {:>10.2} {:>10.2}  {:<12} {}",
+            format!("{:?}", o.side).to_uppercase(),
+            o.price,
+            o.original_size,
+            o.size_matched,
+            o.outcome,
+            o.order_id
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_strategies(
+    strategy_names: Vec<String>,
+    dry_run: bool,
+    max_ticks: u64,
+    skip_warmup: bool,
+    params: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration from environment
+    let mut config = Config::from_env()?;
+    for spec in &params {
+        config.apply_param_override(&strategy_names, spec)?;
+    }
+    info!("Configuration loaded");
+    info!("  CLOB URL: {}", config.clob_url);
+    info!("  Max position size: ${}", config.max_position_size);
+    info!("  Max total exposure: ${}", config.max_total_exposure);
+    info!("  Tick interval: {}ms", config.tick_interval_ms);
+    if config.watchdog_secs > 0 {
+        info!(
+            "  Watchdog: {}s window{}",
+            config.watchdog_secs,
+            if config.watchdog_restart { ", restart on stall" } else { "" }
+        );
+    }
+
+    // Create and run engine
+    let mut engine = Engine::new(config, dry_run).await?;
+    info!("Engine initialized");
+
+    // Set skip warmup if requested
+    if skip_warmup {
+        engine.set_skip_warmup(true);
+        info!("Warmup skipped (--skip-warmup)");
+    }
+
+    // Load strategies by name
+    engine.load_strategies(&strategy_names)?;
+
+    // Run the main event loop
+    if max_ticks > 0 {
+        info!("Running with max_ticks={}", max_ticks);
+    }
+    engine.run(max_ticks).await?;
+
+    Ok(())
+}
+
+async fn run_record(
+    strategy_names: Vec<String>,
+    tokens: Vec<String>,
+    dir: Option<PathBuf>,
+    max_ticks: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if strategy_names.is_empty() && tokens.is_empty() {
+        return Err("Nothing to record: name strategies whose markets to record or pass --token".into());
+    }
+
+    let mut config = Config::from_env()?;
+    config.record_dir = dir.or(config.record_dir).or_else(|| Some(PathBuf::from("data")));
+
+    // Dry-run: strategies only pick the markets, nothing is sent to the exchange
+    let mut engine = Engine::new(config, true).await?;
+    engine.load_strategies(&strategy_names)?;
+    if !tokens.is_empty() {
+        engine.register_dummy_strategy(tokens).await;
+    }
+    engine.run(max_ticks).await?;
+
+    Ok(())
+}
+
+fn run_export(
+    format: ExportFormat,
+    from: Option<String>,
+    to: Option<String>,
+    journal: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::trade_journal;
+
+    let path = journal
+        .or_else(|| std::env::var_os("PMENGINE_TRADE_JOURNAL").map(PathBuf::from))
+        .ok_or("No trade journal: pass --journal or set PMENGINE_TRADE_JOURNAL")?;
+    let from = from.as_deref().map(trade_journal::parse_time).transpose()?;
+    let to = to.as_deref().map(trade_journal::parse_time).transpose()?;
+    let entries = trade_journal::read(&path, from, to)?;
+    info!("Exporting {} journal entries from {}", entries.len(), path.display());
+
+    match format {
+        ExportFormat::Csv => trade_journal::write_csv(&entries, std::io::stdout().lock())?,
+        ExportFormat::Json => print_json(&serde_json::json!({ "entries": entries }))?,
+    }
+    Ok(())
+}
+
+fn run_report(
+    date: Option<String>,
+    state_dir: Option<PathBuf>,
+    output: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::report::{self, DailyReport, PnlSummary};
+
+    let dir = state_dir
+        .or_else(|| std::env::var_os("PMENGINE_STATE_DIR").map(PathBuf::from))
+        .ok_or("No state directory: pass --state-dir or set PMENGINE_STATE_DIR")?;
+    let date = match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| format!("invalid date {:?} (expected YYYY-MM-DD)", date))?,
+        None => chrono::Utc::now().date_naive(),
+    };
+    // The engine's report if it wrote one, else one from the fill log alone
+    let daily = match report::load(&dir, date)? {
+        Some(daily) => daily,
+        None => {
+            info!("No saved report for {}, building one from the fill log", date);
+            let fills = match crate::store::load_fills(&dir) {
+                Ok(fills) => fills,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(e.into()),
+            };
+            DailyReport::build(date, &fills, &[])
+        }
+    };
+
+    if output == OutputFormat::Json {
+        return print_json(&daily);
+    }
+
+    let generated = daily.generated_at.format("%Y-%m-%d %H:%M UTC");
+    println!("P&L report for {} (generated {}):", daily.date, generated);
+    println!();
+    println!(
+        "  {:<20} {:>10} {:>10} {:>10} {:>8} {:>10} {:>6} {:>8}",
+        "STRATEGY", "REALIZED", "UNREALIZED", "TOTAL", "FEES", "VOLUME", "FILLS", "WIN RATE"
+    );
+    let row = |name: &str, s: &PnlSummary| {
+        let win_rate = s.win_rate().map_or("-".to_string(), |r| format!("{:.0}%", r * 100.0));
+        println!(
+            "  {:<20} {:>10.2} {:>10.2} {:>10.2} {:>8.2} {:>10.2} {:>6} {:>8}",
+            name, s.realized_pnl, s.unrealized_pnl, s.total_pnl(), s.fees, s.volume, s.fills, win_rate
+        );
+    };
+    for (strategy_id, summary) in &daily.strategies {
+        row(strategy_id, summary);
+    }
+    row("total", &daily.total);
+
+    Ok(())
+}
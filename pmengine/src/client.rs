@@ -6,32 +6,66 @@
 //! This client:
 //! 1. Uses the SDK for L1 auth (derive-api-key) and order signing
 //! 2. Handles L2 authenticated requests (POST/DELETE order) ourselves with correct paths
+//!
+//! Order and cancel requests are rate-limited per endpoint (see [`crate::throttle`]).
 
 use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 
 use alloy::hex::ToHexExt;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
 use alloy::signers::local::LocalSigner;
 use alloy::signers::Signer;
+use alloy::sol;
 use base64::engine::general_purpose::URL_SAFE;
 use base64::Engine;
+use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
 use polymarket_client_sdk::auth::Credentials;
 use polymarket_client_sdk::clob::client::{Client, Config as SdkConfig};
-use polymarket_client_sdk::clob::types::{Side as SdkSide, SignatureType};
-use polymarket_client_sdk::POLYGON;
+use polymarket_client_sdk::clob::types::request::{BalanceAllowanceRequest, OrdersRequest};
+use polymarket_client_sdk::clob::types::{Amount, OrderType, Side as SdkSide, SignatureType};
+use polymarket_client_sdk::clob::ws::Client as WsClient;
+use polymarket_client_sdk::ctf::types::{RedeemNegRiskRequest, RedeemPositionsRequest};
+use polymarket_client_sdk::ctf::Client as CtfClient;
+use polymarket_client_sdk::data::types::request::PositionsRequest;
+use polymarket_client_sdk::ws::config::Config as WsConfig;
+use polymarket_client_sdk::{contract_config, POLYGON};
 use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use secrecy::ExposeSecret;
 use sha2::Sha256;
 
 use crate::config::Config;
+use crate::throttle::{Endpoint, Throttle};
 
 #[cfg(feature = "cognito")]
 use std::sync::Arc;
 #[cfg(feature = "cognito")]
 use crate::cognito::CognitoAuth;
 
+/// Added to GTD expirations: the exchange treats an order as expiring a
+/// minute before its signed expiration.
+const GTD_THRESHOLD: chrono::Duration = chrono::Duration::seconds(60);
+
+/// Orders "placed" in dry-run, numbering their fake IDs.
+static DRY_RUN_ORDERS: AtomicU64 = AtomicU64::new(0);
+
+sol! {
+    /// The NegRisk adapter's position conversion (the SDK only binds redemption).
+    #[sol(rpc)]
+    interface INegRiskConverter {
+        function convertPositions(bytes32 marketId, uint256 indexSet, uint256 amount) external;
+    }
+}
+
+/// WebSocket client authenticated for the user channel.
+pub type UserWsClient =
+    WsClient<polymarket_client_sdk::auth::state::Authenticated<polymarket_client_sdk::auth::Normal>>;
+
 /// Authenticated Polymarket client.
 pub struct PolymarketClient {
     /// SDK client for order building/signing
@@ -42,12 +76,20 @@ pub struct PolymarketClient {
     credentials: Credentials,
     /// Signer address (used in L2 headers)
     address: Address,
+    /// Address holding positions (funder if set, otherwise the signer)
+    holder: Address,
     /// HTTP client for L2 requests
     http: reqwest::Client,
     /// Proxy URL base (without /clob/ suffix)
     proxy_url: Option<String>,
     /// Dry run mode
     dry_run: bool,
+    /// CLOB clock minus local clock, from the proxy's `/time` (0 without a proxy)
+    clock_offset_ms: AtomicI64,
+    /// Polygon JSON-RPC endpoint for on-chain transactions
+    rpc_url: String,
+    /// Rate limits of the order and cancel endpoints
+    throttle: Throttle,
     /// Optional Cognito auth for pmproxy multi-tenant auth
     #[cfg(feature = "cognito")]
     cognito_auth: Option<Arc<CognitoAuth>>,
@@ -147,17 +189,64 @@ impl PolymarketClient {
             "Authenticated with Polymarket CLOB"
         );
 
-        Ok(Self {
+        let client = Self {
             inner: client,
             signer,
             credentials,
             address,
+            holder: funder.unwrap_or(address),
             http,
             proxy_url,
             dry_run,
+            clock_offset_ms: AtomicI64::new(0),
+            rpc_url: config.rpc_url.clone(),
+            throttle: Throttle::new(&config.rate_limits),
             #[cfg(feature = "cognito")]
             cognito_auth: None,
-        })
+        };
+        client.sync_clock().await;
+        Ok(client)
+    }
+
+    /// Measure the CLOB clock offset through pmproxy's `/time` so L2
+    /// timestamps are not rejected when the local clock drifts. Does nothing
+    /// without a proxy; on failure the previous offset is kept.
+    pub async fn sync_clock(&self) {
+        let Some(ref proxy) = self.proxy_url else {
+            return;
+        };
+        let url = format!("{}/time", proxy.trim_end_matches('/'));
+
+        let sent_ms = chrono::Utc::now().timestamp_millis();
+        let report = match self.http.get(&url).send().await {
+            Ok(resp) => resp.json::<ProxyTime>().await,
+            Err(e) => Err(e),
+        };
+        let received_ms = chrono::Utc::now().timestamp_millis();
+
+        match report {
+            Ok(report) => {
+                let Some(offset) = report.offset_ms(sent_ms, received_ms) else {
+                    tracing::debug!("Proxy has not measured the CLOB clock yet");
+                    return;
+                };
+                let previous = self.clock_offset_ms.swap(offset, Ordering::Relaxed);
+                if (offset - previous).abs() >= 1000 {
+                    tracing::info!(offset_ms = offset, previous_ms = previous, "CLOB clock offset updated");
+                }
+            }
+            Err(e) => tracing::warn!(url = %url, error = %e, "Failed to read proxy clock"),
+        }
+    }
+
+    /// Current CLOB clock offset used for L2 timestamps.
+    pub fn clock_offset_ms(&self) -> i64 {
+        self.clock_offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Rate limits of the order and cancel endpoints.
+    pub fn throttle(&self) -> &Throttle {
+        &self.throttle
     }
 
     /// Compute L2 HMAC signature for a request.
@@ -178,7 +267,8 @@ impl PolymarketClient {
 
     /// Create L2 auth headers for a request.
     fn create_l2_headers(&self, method: &str, path: &str, body: &str) -> Result<HeaderMap, ClientError> {
-        let timestamp = chrono::Utc::now().timestamp();
+        let now_ms = chrono::Utc::now().timestamp_millis() + self.clock_offset_ms();
+        let timestamp = now_ms.div_euclid(1000);
         let signature = self.compute_l2_signature(timestamp, method, path, body)?;
 
         let mut headers = HeaderMap::new();
@@ -238,6 +328,9 @@ impl PolymarketClient {
         let body = response.text().await
             .map_err(|e| ClientError::OrderError(format!("Failed to read response: {}", e)))?;
 
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(ClientError::RateLimited(format!("HTTP {}: {}", status, body)));
+        }
         if !status.is_success() {
             return Err(ClientError::OrderError(format!("HTTP {}: {}", status, body)));
         }
@@ -246,46 +339,62 @@ impl PolymarketClient {
             .map_err(|e| ClientError::OrderError(format!("JSON parse error: {} (body: {})", e, body)))
     }
 
-    /// Place a limit order.
+    /// Place a limit order that rests until filled or cancelled, or until
+    /// `expires_at` when given. A post-only order is rejected by the exchange
+    /// instead of taking liquidity if it would cross the spread.
     pub async fn place_limit_order(
         &self,
         token_id: &str,
         side: Side,
         price: Decimal,
         size: Decimal,
+        expires_at: Option<DateTime<Utc>>,
+        post_only: bool,
+    ) -> Result<String, ClientError> {
+        let time_in_force = expires_at.map_or(TimeInForce::Gtc, TimeInForce::Gtd);
+        self.place_order(token_id, side, price, size, time_in_force, post_only).await
+    }
+
+    /// Place a limit order with a time in force: FOK and FAK orders only
+    /// take liquidity at `price` or better and never rest on the book, so
+    /// they can't be post-only.
+    pub async fn place_order(
+        &self,
+        token_id: &str,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+        time_in_force: TimeInForce,
+        post_only: bool,
     ) -> Result<String, ClientError> {
         if self.dry_run {
-            let fake_id = format!("dry_run_{}", chrono::Utc::now().timestamp_millis());
+            let fake_id = dry_run_order_id();
             tracing::info!(
                 order_id = %fake_id,
                 token_id = token_id,
                 side = ?side,
                 price = %price,
                 size = %size,
+                time_in_force = ?time_in_force,
+                post_only = post_only,
                 "[DRY RUN] Would place order"
             );
             return Ok(fake_id);
         }
 
-        let sdk_side = match side {
-            Side::Buy => SdkSide::Buy,
-            Side::Sell => SdkSide::Sell,
-        };
-
-        // Parse token_id as U256
-        let token_id_u256 = U256::from_str(token_id)
-            .map_err(|e| ClientError::OrderError(format!("Invalid token_id: {}", e)))?;
-
         // Use SDK to build and sign order
-        let order = self.inner
+        let mut builder = self.inner
             .limit_order()
-            .token_id(token_id_u256)
-            .side(sdk_side)
+            .token_id(parse_token_id(token_id)?)
+            .side(side.into())
             .price(price)
             .size(size)
-            .build()
-            .await
-            .map_err(|e| ClientError::OrderError(e.to_string()))?;
+            .order_type(time_in_force.into())
+            .post_only(post_only);
+        if let TimeInForce::Gtd(expires_at) = time_in_force {
+            builder = builder.expiration(expires_at + GTD_THRESHOLD);
+        }
+        let order = builder.build().await.map_err(|e| ClientError::OrderError(e.to_string()))?;
 
         let signed = self.inner
             .sign(&self.signer, order)
@@ -293,7 +402,10 @@ impl PolymarketClient {
             .map_err(|e| ClientError::OrderError(e.to_string()))?;
 
         // POST using our own L2 auth (with correct path for HMAC)
-        let response: PostOrderResponse = self.l2_post("/order", &signed).await?;
+        let response: PostOrderResponse = self
+            .throttle
+            .call(Endpoint::Order, || self.l2_post("/order", &signed))
+            .await?;
 
         tracing::info!(
             order_id = %response.order_id,
@@ -301,12 +413,81 @@ impl PolymarketClient {
             side = ?side,
             price = %price,
             size = %size,
+            time_in_force = ?time_in_force,
+            post_only = post_only,
             "Order placed"
         );
 
         Ok(response.order_id)
     }
 
+    /// Place a market order for `amount`: USDC to spend when buying, shares
+    /// to sell when selling. It fills against the book at prices no worse
+    /// than `worst_price` (or, if None, the price the SDK finds deep enough in
+    /// the book), in full or not at all (FOK) or as far as possible (FAK).
+    pub async fn place_market_order(
+        &self,
+        token_id: &str,
+        side: Side,
+        amount: Decimal,
+        worst_price: Option<Decimal>,
+        time_in_force: TimeInForce,
+    ) -> Result<String, ClientError> {
+        if !matches!(time_in_force, TimeInForce::Fok | TimeInForce::Fak) {
+            return Err(ClientError::OrderError("Market orders must be FOK or FAK".to_string()));
+        }
+        if self.dry_run {
+            let fake_id = dry_run_order_id();
+            tracing::info!(
+                order_id = %fake_id,
+                token_id = token_id,
+                side = ?side,
+                amount = %amount,
+                worst_price = ?worst_price,
+                time_in_force = ?time_in_force,
+                "[DRY RUN] Would place market order"
+            );
+            return Ok(fake_id);
+        }
+
+        let amount = match side {
+            Side::Buy => Amount::usdc(amount),
+            Side::Sell => Amount::shares(amount),
+        }
+        .map_err(|e| ClientError::OrderError(e.to_string()))?;
+        let mut builder = self.inner
+            .market_order()
+            .token_id(parse_token_id(token_id)?)
+            .side(side.into())
+            .amount(amount)
+            .order_type(time_in_force.into());
+        if let Some(price) = worst_price {
+            builder = builder.price(price);
+        }
+        let order = builder.build().await.map_err(|e| ClientError::OrderError(e.to_string()))?;
+
+        let signed = self.inner
+            .sign(&self.signer, order)
+            .await
+            .map_err(|e| ClientError::OrderError(e.to_string()))?;
+        let response: PostOrderResponse = self
+            .throttle
+            .call(Endpoint::Order, || self.l2_post("/order", &signed))
+            .await?;
+
+        tracing::info!(
+            order_id = %response.order_id,
+            token_id = token_id,
+            side = ?side,
+            amount = %amount.as_inner(),
+            worst_price = ?worst_price,
+            time_in_force = ?time_in_force,
+            "Market order placed"
+        );
+
+        Ok(response.order_id)
+    }
+
     /// Cancel an order.
     pub async fn cancel_order(&self, order_id: &str) -> Result<(), ClientError> {
         if self.dry_run {
@@ -315,10 +496,11 @@ impl PolymarketClient {
         }
 
         // Use SDK for cancel (it should work through proxy since it's a simpler operation)
-        self.inner
-            .cancel_order(order_id)
-            .await
-            .map_err(|e| ClientError::OrderError(e.to_string()))?;
+        self.throttle
+            .call(Endpoint::Cancel, || async {
+                self.inner.cancel_order(order_id).await.map_err(order_error)
+            })
+            .await?;
 
         tracing::info!(order_id = order_id, "Order cancelled");
         Ok(())
@@ -331,21 +513,300 @@ impl PolymarketClient {
             return Ok(());
         }
 
-        self.inner
-            .cancel_orders(order_ids)
-            .await
-            .map_err(|e| ClientError::OrderError(e.to_string()))?;
+        self.throttle
+            .call(Endpoint::CancelBatch, || async {
+                self.inner.cancel_orders(order_ids).await.map_err(order_error)
+            })
+            .await?;
 
         tracing::info!(count = order_ids.len(), "Orders cancelled");
         Ok(())
     }
 
+    /// Cancel every open order on the account, including ones not tracked locally.
+    pub async fn cancel_all_open_orders(&self) -> Result<(), ClientError> {
+        if self.dry_run {
+            tracing::info!("[DRY RUN] Would cancel all open orders");
+            return Ok(());
+        }
+
+        self.throttle
+            .call(Endpoint::CancelAll, || async {
+                self.inner.cancel_all_orders().await.map_err(order_error)
+            })
+            .await?;
+
+        tracing::info!("All open orders cancelled");
+        Ok(())
+    }
+
+    /// Fetch every open order on the account.
+    pub async fn open_orders(&self) -> Result<Vec<OpenOrder>, ClientError> {
+        let request = OrdersRequest::default();
+        let mut orders = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let page = self.inner
+                .orders(&request, cursor)
+                .await
+                .map_err(|e| ClientError::SdkError(e.to_string()))?;
+
+            orders.extend(page.data.into_iter().map(|o| OpenOrder {
+                order_id: o.id,
+                token_id: o.asset_id.to_string(),
+                side: match o.side {
+                    SdkSide::Sell => Side::Sell,
+                    _ => Side::Buy,
+                },
+                price: o.price,
+                original_size: o.original_size,
+                size_matched: o.size_matched,
+                outcome: o.outcome,
+                created_at: o.created_at,
+            }));
+
+            // The CLOB marks the last page with an "LTE=" cursor
+            if page.next_cursor.is_empty() || page.next_cursor == END_CURSOR {
+                break;
+            }
+            cursor = Some(page.next_cursor);
+        }
+
+        Ok(orders)
+    }
+
+    /// Fetch current positions for the account from the Data API.
+    pub async fn positions(&self) -> Result<Vec<AccountPosition>, ClientError> {
+        let request = PositionsRequest::builder().user(self.holder).build();
+        let positions = polymarket_client_sdk::data::Client::default()
+            .positions(&request)
+            .await
+            .map_err(|e| ClientError::SdkError(e.to_string()))?;
+
+        Ok(positions
+            .into_iter()
+            .map(|p| AccountPosition {
+                token_id: p.asset.to_string(),
+                title: p.title,
+                outcome: p.outcome,
+                event_slug: p.event_slug,
+                size: p.size,
+                avg_price: p.avg_price,
+                cur_price: p.cur_price,
+                current_value: p.current_value,
+                cash_pnl: p.cash_pnl,
+                realized_pnl: p.realized_pnl,
+                redeemable: p.redeemable,
+            })
+            .collect())
+    }
+
+    /// Fetch the account's USDC (collateral) balance from the CLOB.
+    pub async fn usdc_balance(&self) -> Result<Decimal, ClientError> {
+        let response = self
+            .inner
+            .balance_allowance(BalanceAllowanceRequest::default())
+            .await
+            .map_err(|e| ClientError::SdkError(e.to_string()))?;
+        // Reported in on-chain units (6 decimals)
+        Ok(response.balance / Decimal::from(1_000_000))
+    }
+
+    /// Taker fee rate of a token in basis points, from the CLOB fee schedule.
+    pub async fn fee_rate_bps(&self, token_id: &str) -> Result<Decimal, ClientError> {
+        let response = self
+            .inner
+            .fee_rate_bps(parse_token_id(token_id)?)
+            .await
+            .map_err(|e| ClientError::SdkError(e.to_string()))?;
+        Ok(Decimal::from(response.base_fee))
+    }
+
+    /// WebSocket client authenticated for the user channel (our order and
+    /// trade events), with the same L2 credentials as REST requests.
+    pub fn user_ws(&self, endpoint: &str) -> Result<UserWsClient, ClientError> {
+        WsClient::new(endpoint, WsConfig::default())
+            .and_then(|ws| ws.authenticate(self.credentials.clone(), self.address))
+            .map_err(|e| ClientError::SdkError(e.to_string()))
+    }
+
+    /// Address that holds the account's positions.
+    pub fn holder(&self) -> Address {
+        self.holder
+    }
+
+    /// Whether the signer holds the positions itself and so can redeem them
+    /// (tokens in a proxy or Safe wallet are redeemed through that wallet).
+    pub fn can_redeem(&self) -> bool {
+        self.holder == self.address
+    }
+
+    /// Redeem the outcome tokens of a resolved market for USDC, waiting for
+    /// the transaction to be mined. Returns its hash.
+    ///
+    /// Standard markets redeem every outcome held through the CTF contract.
+    /// negRisk markets go through the NegRisk adapter, which needs the amount
+    /// of each outcome to redeem (`[yes, no]`).
+    pub async fn redeem_positions(
+        &self,
+        condition_id: &str,
+        neg_risk_amounts: Option<&[Decimal]>,
+    ) -> Result<String, ClientError> {
+        let sdk_error = |e: polymarket_client_sdk::error::Error| ClientError::SdkError(e.to_string());
+        let condition_id = B256::from_str(condition_id)
+            .map_err(|e| ClientError::OrderError(format!("Invalid condition ID: {}", e)))?;
+        let provider = self.wallet_provider()?;
+
+        let transaction_hash = match neg_risk_amounts {
+            Some(amounts) => {
+                let amounts = amounts
+                    .iter()
+                    .map(|amount| token_units(*amount))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let request = RedeemNegRiskRequest::builder()
+                    .condition_id(condition_id)
+                    .amounts(amounts)
+                    .build();
+                let ctf = CtfClient::with_neg_risk(provider, POLYGON).map_err(sdk_error)?;
+                ctf.redeem_neg_risk(&request).await.map_err(sdk_error)?.transaction_hash
+            }
+            None => {
+                let collateral = contract_config(POLYGON, false)
+                    .ok_or_else(|| ClientError::SdkError("No contract config for Polygon".to_string()))?
+                    .collateral;
+                let request = RedeemPositionsRequest::for_binary_market(collateral, condition_id);
+                let ctf = CtfClient::new(provider, POLYGON).map_err(sdk_error)?;
+                ctf.redeem_positions(&request).await.map_err(sdk_error)?.transaction_hash
+            }
+        };
+        Ok(transaction_hash.to_string())
+    }
+
+    /// Convert `amount` NO tokens of each of the questions `question_indices`
+    /// of a negRisk event (its adapter market ID) through the NegRisk
+    /// adapter, waiting for the transaction to be mined. Returns its hash.
+    ///
+    /// NO shares of `k` outcomes become `(k - 1) * amount` USDC plus `amount`
+    /// YES shares of each of the event's other outcomes.
+    pub async fn convert_neg_risk_positions(
+        &self,
+        market_id: &str,
+        question_indices: &[u8],
+        amount: Decimal,
+    ) -> Result<String, ClientError> {
+        let market_id = B256::from_str(market_id)
+            .map_err(|e| ClientError::OrderError(format!("Invalid negRisk market ID: {}", e)))?;
+        let index_set = index_set(question_indices);
+        let amount = token_units(amount)?;
+        let adapter = contract_config(POLYGON, true)
+            .and_then(|config| config.neg_risk_adapter)
+            .ok_or_else(|| ClientError::SdkError("No NegRisk adapter for Polygon".to_string()))?;
+
+        let converter = INegRiskConverter::new(adapter, self.wallet_provider()?);
+        let pending = converter
+            .convertPositions(market_id, index_set, amount)
+            .send()
+            .await
+            .map_err(|e| ClientError::SdkError(format!("Failed to send conversion: {}", e)))?;
+        let receipt = pending
+            .get_receipt()
+            .await
+            .map_err(|e| ClientError::SdkError(format!("Failed to get conversion receipt: {}", e)))?;
+        if !receipt.status() {
+            return Err(ClientError::SdkError(format!(
+                "Conversion reverted: {}",
+                receipt.transaction_hash
+            )));
+        }
+        Ok(receipt.transaction_hash.to_string())
+    }
+
+    /// Provider sending transactions from the signer through `rpc_url`.
+    fn wallet_provider(&self) -> Result<DynProvider, ClientError> {
+        let rpc_url = self
+            .rpc_url
+            .parse()
+            .map_err(|e| ClientError::SdkError(format!("Invalid RPC URL {}: {}", self.rpc_url, e)))?;
+        Ok(ProviderBuilder::new()
+            .wallet(self.signer.clone())
+            .connect_http(rpc_url)
+            .erased())
+    }
+
     /// Check if in dry run mode.
     pub fn is_dry_run(&self) -> bool {
         self.dry_run
     }
 }
 
+/// Clock report from pmproxy's `/time`.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ProxyTime {
+    /// Estimated CLOB clock (None until the proxy has probed it)
+    clob_time_ms: Option<i64>,
+}
+
+impl ProxyTime {
+    /// CLOB clock minus local clock, taking the CLOB time as of the midpoint
+    /// of the local request.
+    fn offset_ms(&self, sent_ms: i64, received_ms: i64) -> Option<i64> {
+        let midpoint_ms = sent_ms + (received_ms - sent_ms) / 2;
+        self.clob_time_ms.map(|clob| clob - midpoint_ms)
+    }
+}
+
+/// An amount of outcome tokens in on-chain units (6 decimals, like USDC).
+fn token_units(amount: Decimal) -> Result<U256, ClientError> {
+    (amount * Decimal::from(1_000_000))
+        .trunc()
+        .to_u128()
+        .map(U256::from)
+        .ok_or_else(|| ClientError::OrderError(format!("Invalid token amount: {}", amount)))
+}
+
+/// NegRisk adapter index set: bit `i` selects question `i` of the event.
+fn index_set(question_indices: &[u8]) -> U256 {
+    question_indices
+        .iter()
+        .fold(U256::ZERO, |set, index| set | (U256::from(1u8) << usize::from(*index)))
+}
+
+/// Cursor the CLOB returns on the last page of a paginated response.
+const END_CURSOR: &str = "LTE=";
+
+/// An open order on the account.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OpenOrder {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub original_size: Decimal,
+    pub size_matched: Decimal,
+    pub outcome: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A position held by the account, as reported by the Data API.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AccountPosition {
+    pub token_id: String,
+    /// Market question
+    pub title: String,
+    pub outcome: String,
+    pub event_slug: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+    pub cur_price: Decimal,
+    pub current_value: Decimal,
+    /// Unrealized P&L in USDC
+    pub cash_pnl: Decimal,
+    pub realized_pnl: Decimal,
+    /// Whether the market has resolved and the position can be redeemed
+    pub redeemable: bool,
+}
+
 /// Response from posting an order.
 #[derive(Debug, serde::Deserialize)]
 struct PostOrderResponse {
@@ -355,18 +816,75 @@ struct PostOrderResponse {
     success: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Side {
     Buy,
     Sell,
 }
 
+impl From<Side> for SdkSide {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Buy => SdkSide::Buy,
+            Side::Sell => SdkSide::Sell,
+        }
+    }
+}
+
+/// How long an order may stay on the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeInForce {
+    /// Good 'til cancelled: rests until filled or cancelled
+    #[default]
+    Gtc,
+    /// Good 'til date: rests until filled, cancelled or the time passes
+    Gtd(DateTime<Utc>),
+    /// Fill or kill: filled in full immediately, or not at all
+    Fok,
+    /// Fill and kill: filled as far as possible immediately, the rest cancelled
+    Fak,
+}
+
+impl From<TimeInForce> for OrderType {
+    fn from(time_in_force: TimeInForce) -> Self {
+        match time_in_force {
+            TimeInForce::Gtc => OrderType::GTC,
+            TimeInForce::Gtd(_) => OrderType::GTD,
+            TimeInForce::Fok => OrderType::FOK,
+            TimeInForce::Fak => OrderType::FAK,
+        }
+    }
+}
+
+/// An SDK error from an order endpoint (a 429 is reported as rate limited).
+fn order_error(e: polymarket_client_sdk::error::Error) -> ClientError {
+    match e.downcast_ref::<polymarket_client_sdk::error::Status>() {
+        Some(status) if status.status_code == StatusCode::TOO_MANY_REQUESTS => {
+            ClientError::RateLimited(e.to_string())
+        }
+        _ => ClientError::OrderError(e.to_string()),
+    }
+}
+
+/// A fake order ID, unique even for orders placed in the same millisecond.
+fn dry_run_order_id() -> String {
+    let sequence = DRY_RUN_ORDERS.fetch_add(1, Ordering::Relaxed);
+    format!("dry_run_{}_{}", chrono::Utc::now().timestamp_millis(), sequence)
+}
+
+fn parse_token_id(token_id: &str) -> Result<U256, ClientError> {
+    U256::from_str(token_id).map_err(|e| ClientError::OrderError(format!("Invalid token_id: {}", e)))
+}
+
 #[derive(Debug)]
 pub enum ClientError {
     InvalidPrivateKey(String),
     AuthError(String),
     SdkError(String),
     OrderError(String),
+    /// The CLOB answered 429 Too Many Requests
+    RateLimited(String),
     WebSocketError(String),
 }
 
@@ -377,9 +895,43 @@ impl std::fmt::Display for ClientError {
             ClientError::AuthError(e) => write!(f, "Authentication error: {}", e),
             ClientError::SdkError(e) => write!(f, "SDK error: {}", e),
             ClientError::OrderError(e) => write!(f, "Order error: {}", e),
+            ClientError::RateLimited(e) => write!(f, "Rate limited: {}", e),
             ClientError::WebSocketError(e) => write!(f, "WebSocket error: {}", e),
         }
     }
 }
 
 impl std::error::Error for ClientError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_time_offset() {
+        let report: ProxyTime = serde_json::from_str(
+            r#"{"server_time_ms": 1000000, "clob_offset_ms": 4000, "clob_time_ms": 1004000}"#,
+        )
+        .unwrap();
+        // Local clock 2s behind the proxy, 100ms round trip
+        assert_eq!(report.offset_ms(997_950, 998_050), Some(6000));
+
+        let unmeasured: ProxyTime =
+            serde_json::from_str(r#"{"server_time_ms": 1000000, "clob_offset_ms": null}"#).unwrap();
+        assert_eq!(unmeasured.offset_ms(0, 0), None);
+    }
+
+    #[test]
+    fn test_token_units() {
+        assert_eq!(token_units(Decimal::new(12_3456789, 7)).unwrap(), U256::from(12_345_678u64));
+        assert_eq!(token_units(Decimal::ZERO).unwrap(), U256::ZERO);
+        assert!(token_units(Decimal::from(-1)).is_err());
+    }
+
+    #[test]
+    fn test_index_set() {
+        assert_eq!(index_set(&[0, 2]), U256::from(0b101u8));
+        assert_eq!(index_set(&[]), U256::ZERO);
+        assert_eq!(index_set(&[255]), U256::from(1u8) << 255);
+    }
+}
@@ -1,6 +1,16 @@
 //! Configuration loaded from environment variables.
 
+use std::collections::HashMap;
 use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::discovery::{DiscoveryConfig, DiscoveryFilter, DiscoverySource};
+use crate::ladder::Ladder;
+use crate::pipeline::Filter;
+use crate::schedule::{Schedule, Window};
+use crate::strategy::StrategyParams;
+use crate::throttle::RateLimits;
 
 /// Engine configuration loaded from environment.
 #[derive(Debug, Clone)]
@@ -17,12 +27,138 @@ pub struct Config {
     pub max_position_size: f64,
     /// Maximum total exposure (in USDC)
     pub max_total_exposure: f64,
+    /// Maximum exposure in one market, complementary outcomes netted (in USDC, 0 = unlimited)
+    pub max_market_exposure: f64,
+    /// Maximum exposure across the markets of one category (in USDC, 0 = unlimited)
+    pub max_category_exposure: f64,
+    /// Fraction of the full Kelly stake strategies size positions to
+    pub kelly_fraction: f64,
+    /// Cap on the USDC balance strategies see (0 = the account balance), and
+    /// the paper balance a dry run starts with (0 = `sizing::PAPER_BANKROLL`)
+    pub bankroll: f64,
     /// Strategy tick interval in milliseconds
     pub tick_interval_ms: u64,
     /// Log level
     pub log_level: String,
     /// Signature type (0=EOA, 1=PolyProxy, 2=GnosisSafe)
     pub signature_type: u8,
+    /// Seconds without a tick or WebSocket update before the watchdog fires (0 = disabled)
+    pub watchdog_secs: u64,
+    /// Whether the watchdog restarts the event loop after a stall
+    pub watchdog_restart: bool,
+    /// File whose existence trips the kill switch (None = not watched)
+    pub kill_file: Option<PathBuf>,
+    /// Whether the kill switch also sells every position at market
+    pub kill_flatten: bool,
+    /// Seconds without a market data update before the WebSocket is reconnected (0 = never)
+    pub ws_stale_secs: u64,
+    /// Seconds without an update before a token's book is stale: its orders are
+    /// cancelled and new signals for it dropped until it updates (0 = disabled)
+    pub book_stale_secs: u64,
+    /// Seconds an exposure reservation may stay unconfirmed before it expires
+    pub reservation_ttl_secs: u64,
+    /// Per-strategy `on_tick` budget in milliseconds (0 = disabled)
+    pub strategy_budget_ms: u64,
+    /// Consecutive over-budget ticks before a strategy is quarantined
+    pub strategy_max_overruns: u32,
+    /// Maximum tokens subscribed by the first market discovery (0 = no cap);
+    /// the rest are subscribed on the next refresh
+    pub cold_start_tokens: usize,
+    /// Signal-to-exchange-ack budget for order placement in milliseconds (0 = disabled)
+    pub order_latency_budget_ms: u64,
+    /// Whether orders acknowledged over the latency budget are cancelled immediately
+    pub cancel_late_orders: bool,
+    /// Minutes of price history fed to strategies at startup (0 = disabled)
+    pub warm_start_minutes: u64,
+    /// Gamma categories each strategy may trade, by strategy ID (lowercase);
+    /// strategies not listed see every market
+    pub strategy_categories: HashMap<String, Vec<String>>,
+    /// Filter stages run in front of each strategy, by strategy ID
+    pub strategy_filters: HashMap<String, Vec<Filter>>,
+    /// Trading windows of each strategy, by strategy ID; strategies not
+    /// listed run around the clock
+    pub strategy_schedules: HashMap<String, Schedule>,
+    /// Maximum exposure per strategy ID (in USDC); strategies not listed are
+    /// only bound by the global limits
+    pub strategy_max_exposure: HashMap<String, f64>,
+    /// Parameter overrides handed to each strategy, by strategy ID
+    pub strategy_params: HashMap<String, StrategyParams>,
+    /// File risk-check decisions are appended to, for parity testing (None = disabled)
+    pub risk_journal: Option<PathBuf>,
+    /// File signals, risk decisions, orders and fills are appended to (None = disabled)
+    pub trade_journal: Option<PathBuf>,
+    /// Annualized yield below which a long-dated position is reported as parked capital
+    pub min_carry_apy: f64,
+    /// Split approved orders across adjacent price levels (None = one order per signal)
+    pub order_ladder: Option<Ladder>,
+    /// Fraction of the wanted size by which a resting order may differ and
+    /// still be kept when a strategy replaces its orders
+    pub replace_tolerance: f64,
+    /// Requests per second allowed on each CLOB order and cancel endpoint
+    pub rate_limits: RateLimits,
+    /// Loss (in USDC) that halts trading until a manual reset
+    pub max_loss: f64,
+    /// Peak-to-trough P&L drop (in USDC) within the drawdown window that halts trading (0 = unchecked)
+    pub max_drawdown: f64,
+    /// Seconds of P&L history the drawdown is measured over
+    pub drawdown_window_secs: u64,
+    /// Loss (in USDC) within the loss-rate window that halts trading (0 = unchecked)
+    pub max_loss_rate: f64,
+    /// Seconds the loss rate is measured over
+    pub loss_rate_window_secs: u64,
+    /// Fraction of a loss limit at which the engine goes reduce-only (0 = no reduce-only stage)
+    pub reduce_only_at: f64,
+    /// Seconds after a drawdown or loss-rate halt before trading resumes with
+    /// halved limits (0 = manual reset only)
+    pub breaker_cooldown_secs: u64,
+    /// Maximum number of open orders (0 = unlimited)
+    pub max_open_orders: usize,
+    /// Maximum number of open orders per token (0 = unlimited)
+    pub max_open_orders_per_token: usize,
+    /// Seconds an order may rest before it is cancelled (0 = no limit)
+    pub max_order_age_secs: u64,
+    /// Default stop-loss: fraction of the entry price a position may lose before it is sold (0 = off)
+    pub stop_loss: f64,
+    /// Default take-profit: fraction of the entry price gained before a position is sold (0 = off)
+    pub take_profit: f64,
+    /// Default time stop: seconds a position is held before it is sold (0 = off)
+    pub max_hold_secs: u64,
+    /// Seconds before its market's end date a position is sold (0 = off)
+    pub exit_before_expiry_secs: u64,
+    /// Only warn about positions near expiry instead of selling them
+    pub expiry_alert_only: bool,
+    /// Lowest price an order may be placed at
+    pub min_price: f64,
+    /// Highest price an order may be placed at
+    pub max_price: f64,
+    /// Maximum distance of an order's price from the mid (or last trade) (0 = unchecked)
+    pub max_price_deviation: f64,
+    /// Maximum order size as a multiple of the visible book depth it trades against (0 = unchecked)
+    pub max_depth_ratio: f64,
+    /// Scenario file of synthetic markets replayed in dry-run (None = disabled)
+    pub synthetic_markets: Option<PathBuf>,
+    /// Directory positions, open orders and fills are persisted to (None = disabled)
+    pub state_dir: Option<PathBuf>,
+    /// Seconds between reconciliations of orders and positions with the exchange (0 = startup only)
+    pub reconcile_secs: u64,
+    /// Whether reconciliation only logs mismatches instead of correcting them
+    pub reconcile_alert_only: bool,
+    /// Seconds between checks for resolved markets to settle (0 = disabled)
+    pub settle_secs: u64,
+    /// Polygon JSON-RPC endpoint redemption transactions are sent through
+    pub rpc_url: String,
+    /// Directory market data is recorded to as CSV (None = disabled)
+    pub record_dir: Option<PathBuf>,
+    /// Book levels per side recorded
+    pub record_depth: usize,
+    /// Minutes per recorded file
+    pub record_rotate_mins: u64,
+    /// Address the external signal webhook listens on (None = disabled)
+    pub webhook_addr: Option<SocketAddr>,
+    /// Secret webhook requests are signed with (required with `webhook_addr`)
+    pub webhook_secret: Option<String>,
+    /// Market discovery sources, filters, refresh interval and token cap
+    pub discovery: DiscoveryConfig,
 }
 
 impl Config {
@@ -56,6 +192,34 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_TOTAL_EXPOSURE"))?;
 
+        let max_market_exposure = env::var("PMENGINE_MAX_MARKET_EXPOSURE")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|e| *e >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_MARKET_EXPOSURE"))?;
+
+        let max_category_exposure = env::var("PMENGINE_MAX_CATEGORY_EXPOSURE")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|e| *e >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_CATEGORY_EXPOSURE"))?;
+
+        let kelly_fraction = env::var("PMENGINE_KELLY_FRACTION")
+            .unwrap_or_else(|_| "0.25".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|f| *f > 0.0 && *f <= 1.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_KELLY_FRACTION"))?;
+
+        let bankroll = env::var("PMENGINE_BANKROLL")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|b| *b >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_BANKROLL"))?;
+
         let tick_interval_ms = env::var("PMENGINE_TICK_INTERVAL_MS")
             .unwrap_or_else(|_| "1000".to_string())
             .parse()
@@ -71,6 +235,311 @@ impl Config {
             .parse()
             .unwrap_or(0);
 
+        let watchdog_secs = env::var("PMENGINE_WATCHDOG_SECS")
+            .unwrap_or_else(|_| "120".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_WATCHDOG_SECS"))?;
+
+        let watchdog_restart = env::var("PMENGINE_WATCHDOG_RESTART")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let kill_file = env::var("PMENGINE_KILL_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let kill_flatten = env::var("PMENGINE_KILL_FLATTEN")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let ws_stale_secs = env::var("PMENGINE_WS_STALE_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_WS_STALE_SECS"))?;
+
+        let book_stale_secs = env::var("PMENGINE_BOOK_STALE_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_BOOK_STALE_SECS"))?;
+
+        let reservation_ttl_secs = env::var("PMENGINE_RESERVATION_TTL_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_RESERVATION_TTL_SECS"))?;
+
+        let strategy_budget_ms = env::var("PMENGINE_STRATEGY_BUDGET_MS")
+            .unwrap_or_else(|_| "250".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_STRATEGY_BUDGET_MS"))?;
+
+        let strategy_max_overruns = env::var("PMENGINE_STRATEGY_MAX_OVERRUNS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_STRATEGY_MAX_OVERRUNS"))?;
+
+        let cold_start_tokens = env::var("PMENGINE_COLD_START_TOKENS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_COLD_START_TOKENS"))?;
+
+        let order_latency_budget_ms = env::var("PMENGINE_ORDER_LATENCY_BUDGET_MS")
+            .unwrap_or_else(|_| "750".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_ORDER_LATENCY_BUDGET_MS"))?;
+
+        let cancel_late_orders = env::var("PMENGINE_CANCEL_LATE_ORDERS")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let warm_start_minutes = env::var("PMENGINE_WARM_START_MINUTES")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_WARM_START_MINUTES"))?;
+
+        let strategy_categories = env::var("PMENGINE_STRATEGY_CATEGORIES")
+            .map(|v| parse_strategy_categories(&v))
+            .unwrap_or_else(|_| Ok(HashMap::new()))?;
+
+        let strategy_filters = env::var("PMENGINE_STRATEGY_FILTERS")
+            .map(|v| parse_strategy_filters(&v))
+            .unwrap_or_else(|_| Ok(HashMap::new()))?;
+
+        let strategy_schedules = env::var("PMENGINE_STRATEGY_SCHEDULE")
+            .map(|v| parse_strategy_schedules(&v))
+            .unwrap_or_else(|_| Ok(HashMap::new()))?;
+
+        let strategy_max_exposure = env::var("PMENGINE_STRATEGY_EXPOSURE")
+            .map(|v| parse_strategy_exposure(&v))
+            .unwrap_or_else(|_| Ok(HashMap::new()))?;
+
+        let strategy_params = env::var("PMENGINE_STRATEGY_PARAMS")
+            .map(|v| parse_strategy_params(&v))
+            .unwrap_or_else(|_| Ok(HashMap::new()))?;
+
+        let risk_journal = env::var("PMENGINE_RISK_JOURNAL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let trade_journal = env::var("PMENGINE_TRADE_JOURNAL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let min_carry_apy = env::var("PMENGINE_MIN_CARRY_APY")
+            .unwrap_or_else(|_| "0.10".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MIN_CARRY_APY"))?;
+
+        let order_ladder = match env::var("PMENGINE_ORDER_LADDER") {
+            Ok(v) if !v.trim().is_empty() => {
+                Some(Ladder::parse(&v).ok_or(ConfigError::InvalidValue("PMENGINE_ORDER_LADDER"))?)
+            }
+            _ => None,
+        };
+
+        let replace_tolerance = env::var("PMENGINE_REPLACE_TOLERANCE")
+            .unwrap_or_else(|_| "0.1".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|t| *t >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_REPLACE_TOLERANCE"))?;
+
+        let rate_limits = match env::var("PMENGINE_RATE_LIMITS") {
+            Ok(v) => RateLimits::parse(&v).ok_or(ConfigError::InvalidValue("PMENGINE_RATE_LIMITS"))?,
+            Err(_) => RateLimits::default(),
+        };
+
+        let max_loss = env::var("PMENGINE_MAX_LOSS")
+            .unwrap_or_else(|_| "25".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_LOSS"))?;
+
+        let max_drawdown = env::var("PMENGINE_MAX_DRAWDOWN")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|d| *d >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_DRAWDOWN"))?;
+
+        let drawdown_window_secs = env::var("PMENGINE_DRAWDOWN_WINDOW_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_DRAWDOWN_WINDOW_SECS"))?;
+
+        let max_loss_rate = env::var("PMENGINE_MAX_LOSS_RATE")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|r| *r >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_LOSS_RATE"))?;
+
+        let loss_rate_window_secs = env::var("PMENGINE_LOSS_RATE_WINDOW_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_LOSS_RATE_WINDOW_SECS"))?;
+
+        let reduce_only_at = env::var("PMENGINE_REDUCE_ONLY_AT")
+            .unwrap_or_else(|_| "0.75".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|f| (0.0..=1.0).contains(f))
+            .ok_or(ConfigError::InvalidValue("PMENGINE_REDUCE_ONLY_AT"))?;
+
+        let breaker_cooldown_secs = env::var("PMENGINE_BREAKER_COOLDOWN_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_BREAKER_COOLDOWN_SECS"))?;
+
+        let max_open_orders = env::var("PMENGINE_MAX_OPEN_ORDERS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_OPEN_ORDERS"))?;
+
+        let max_open_orders_per_token = env::var("PMENGINE_MAX_OPEN_ORDERS_PER_TOKEN")
+            .unwrap_or_else(|_| "6".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_OPEN_ORDERS_PER_TOKEN"))?;
+
+        let max_order_age_secs = env::var("PMENGINE_MAX_ORDER_AGE_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_ORDER_AGE_SECS"))?;
+
+        let stop_loss = env::var("PMENGINE_STOP_LOSS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|f| (0.0..1.0).contains(f))
+            .ok_or(ConfigError::InvalidValue("PMENGINE_STOP_LOSS"))?;
+
+        let take_profit = env::var("PMENGINE_TAKE_PROFIT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|f| *f >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_TAKE_PROFIT"))?;
+
+        let max_hold_secs = env::var("PMENGINE_MAX_HOLD_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MAX_HOLD_SECS"))?;
+
+        let exit_before_expiry_secs = env::var("PMENGINE_EXIT_BEFORE_EXPIRY_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_EXIT_BEFORE_EXPIRY_SECS"))?;
+
+        let expiry_alert_only = env::var("PMENGINE_EXPIRY_ALERT_ONLY")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let min_price: f64 = env::var("PMENGINE_MIN_PRICE")
+            .unwrap_or_else(|_| "0.001".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_MIN_PRICE"))?;
+
+        let max_price = env::var("PMENGINE_MAX_PRICE")
+            .unwrap_or_else(|_| "0.999".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|p| *p >= min_price)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_PRICE"))?;
+
+        let max_price_deviation = env::var("PMENGINE_MAX_PRICE_DEVIATION")
+            .unwrap_or_else(|_| "0.25".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|d| *d >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_PRICE_DEVIATION"))?;
+
+        let max_depth_ratio = env::var("PMENGINE_MAX_DEPTH_RATIO")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .ok()
+            .filter(|r| *r >= 0.0)
+            .ok_or(ConfigError::InvalidValue("PMENGINE_MAX_DEPTH_RATIO"))?;
+
+        let synthetic_markets = env::var("PMENGINE_SYNTHETIC_MARKETS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let state_dir = env::var("PMENGINE_STATE_DIR")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let reconcile_secs = env::var("PMENGINE_RECONCILE_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_RECONCILE_SECS"))?;
+
+        let reconcile_alert_only = env::var("PMENGINE_RECONCILE_ALERT_ONLY")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let settle_secs = env::var("PMENGINE_SETTLE_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_SETTLE_SECS"))?;
+
+        let rpc_url = env::var("PMENGINE_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
+
+        let record_dir = env::var("PMENGINE_RECORD_DIR")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+
+        let record_depth = env::var("PMENGINE_RECORD_DEPTH")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_RECORD_DEPTH"))?;
+
+        let record_rotate_mins = env::var("PMENGINE_RECORD_ROTATE_MINS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_RECORD_ROTATE_MINS"))?;
+
+        let webhook_addr = env::var("PMENGINE_WEBHOOK_ADDR")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| v.trim().parse())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("PMENGINE_WEBHOOK_ADDR"))?;
+
+        let webhook_secret = env::var("PMENGINE_WEBHOOK_SECRET")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if webhook_addr.is_some() && webhook_secret.is_none() {
+            return Err(ConfigError::MissingVar("PMENGINE_WEBHOOK_SECRET"));
+        }
+
+        let defaults = DiscoveryConfig::default();
+        let discovery = DiscoveryConfig {
+            sources: env::var("PMENGINE_DISCOVERY_SOURCES")
+                .map(|v| parse_discovery_sources(&v))
+                .unwrap_or_else(|_| Ok(defaults.sources))?,
+            max_hours_to_expiry: env::var("PMENGINE_DISCOVERY_MAX_HOURS")
+                .map(|v| v.trim().parse::<f64>().ok())
+                .unwrap_or(Some(defaults.max_hours_to_expiry))
+                .filter(|h| *h > 0.0)
+                .ok_or(ConfigError::InvalidValue("PMENGINE_DISCOVERY_MAX_HOURS"))?,
+            filters: env::var("PMENGINE_DISCOVERY_FILTERS")
+                .map(|v| parse_discovery_filters(&v))
+                .unwrap_or_else(|_| Ok(defaults.filters))?,
+            refresh_secs: env::var("PMENGINE_DISCOVERY_REFRESH_SECS")
+                .map(|v| v.trim().parse::<u64>().ok())
+                .unwrap_or(Some(defaults.refresh_secs))
+                .filter(|s| *s > 0)
+                .ok_or(ConfigError::InvalidValue("PMENGINE_DISCOVERY_REFRESH_SECS"))?,
+            max_tokens: env::var("PMENGINE_DISCOVERY_MAX_TOKENS")
+                .unwrap_or_else(|_| defaults.max_tokens.to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("PMENGINE_DISCOVERY_MAX_TOKENS"))?,
+        };
+
         Ok(Self {
             private_key,
             funder_address,
@@ -78,12 +547,78 @@ impl Config {
             ws_url,
             max_position_size,
             max_total_exposure,
+            max_market_exposure,
+            max_category_exposure,
+            kelly_fraction,
+            bankroll,
             tick_interval_ms,
             log_level,
             signature_type,
+            watchdog_secs,
+            watchdog_restart,
+            kill_file,
+            kill_flatten,
+            ws_stale_secs,
+            book_stale_secs,
+            reservation_ttl_secs,
+            strategy_budget_ms,
+            strategy_max_overruns,
+            cold_start_tokens,
+            order_latency_budget_ms,
+            cancel_late_orders,
+            warm_start_minutes,
+            strategy_categories,
+            strategy_filters,
+            strategy_schedules,
+            strategy_max_exposure,
+            strategy_params,
+            risk_journal,
+            trade_journal,
+            min_carry_apy,
+            order_ladder,
+            replace_tolerance,
+            rate_limits,
+            max_loss,
+            max_drawdown,
+            drawdown_window_secs,
+            max_loss_rate,
+            loss_rate_window_secs,
+            reduce_only_at,
+            breaker_cooldown_secs,
+            max_open_orders,
+            max_open_orders_per_token,
+            max_order_age_secs,
+            stop_loss,
+            take_profit,
+            max_hold_secs,
+            exit_before_expiry_secs,
+            expiry_alert_only,
+            min_price,
+            max_price,
+            max_price_deviation,
+            max_depth_ratio,
+            synthetic_markets,
+            state_dir,
+            reconcile_secs,
+            reconcile_alert_only,
+            settle_secs,
+            rpc_url,
+            record_dir,
+            record_depth,
+            record_rotate_mins,
+            webhook_addr,
+            webhook_secret,
+            discovery,
         })
     }
 
+    /// Apply a `--param` override, `[strategy.]name=value`, for one of the
+    /// strategies being run. The strategy can be left out when only one is
+    /// run. Overrides win over `PMENGINE_STRATEGY_PARAMS` and the config file.
+    pub fn apply_param_override(&mut self, strategies: &[String], spec: &str) -> Result<(), ConfigError> {
+        apply_param_override(&mut self.strategy_params, strategies, spec)
+    }
+
     /// Normalize private key (strip 0x prefix if present)
     pub fn private_key_bytes(&self) -> Result<[u8; 32], ConfigError> {
         let key = self.private_key.strip_prefix("0x").unwrap_or(&self.private_key);
@@ -92,10 +627,161 @@ impl Config {
     }
 }
 
+/// Add a `[strategy.]name=value` override to `all`.
+fn apply_param_override(
+    all: &mut HashMap<String, StrategyParams>,
+    strategies: &[String],
+    spec: &str,
+) -> Result<(), ConfigError> {
+    let invalid = || ConfigError::InvalidValue("--param (expected [strategy.]name=value)");
+    let (key, value) = spec.split_once('=').ok_or_else(invalid)?;
+    let (strategy, name) = match key.split_once('.') {
+        Some((strategy, name)) => (strategy.trim(), name.trim()),
+        None if strategies.len() == 1 => (strategies[0].as_str(), key.trim()),
+        None => return Err(invalid()),
+    };
+    if name.is_empty() || value.trim().is_empty() || !strategies.iter().any(|s| s == strategy) {
+        return Err(invalid());
+    }
+    all.entry(strategy.to_string()).or_default().insert(name, value.trim());
+    Ok(())
+}
+
+/// Parse `strategy=category,category;strategy=category`, e.g.
+/// `sure_bets=crypto,sports;market_maker=politics`.
+fn parse_strategy_categories(value: &str) -> Result<HashMap<String, Vec<String>>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_STRATEGY_CATEGORIES");
+    let mut scopes = HashMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (strategy, categories) = entry.split_once('=').ok_or_else(invalid)?;
+        let categories: Vec<String> = categories
+            .split(',')
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if strategy.trim().is_empty() || categories.is_empty() {
+            return Err(invalid());
+        }
+        scopes.insert(strategy.trim().to_string(), categories);
+    }
+    Ok(scopes)
+}
+
+/// Parse `strategy=filter,filter;strategy=filter`, e.g.
+/// `sure_bets=min_liquidity:500,hours_to_expiry:0-48;market_maker=max_spread_bps:300`
+/// (see [`Filter::parse`]).
+fn parse_strategy_filters(value: &str) -> Result<HashMap<String, Vec<Filter>>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_STRATEGY_FILTERS");
+    let mut filters = HashMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (strategy, specs) = entry.split_once('=').ok_or_else(invalid)?;
+        let stages = specs
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Filter::parse(s).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        if strategy.trim().is_empty() || stages.is_empty() {
+            return Err(invalid());
+        }
+        filters.insert(strategy.trim().to_string(), stages);
+    }
+    Ok(filters)
+}
+
+/// Parse `strategy=window,window;strategy=window`, e.g.
+/// `market_maker=mon-fri 08:00-22:00 America/New_York,sat 10:00-14:00;sure_bets=* 22:00-02:00`
+/// (see [`Window::parse`]).
+fn parse_strategy_schedules(value: &str) -> Result<HashMap<String, Schedule>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_STRATEGY_SCHEDULE");
+    let mut schedules = HashMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (strategy, specs) = entry.split_once('=').ok_or_else(invalid)?;
+        let windows = specs
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Window::parse(s).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        if strategy.trim().is_empty() || windows.is_empty() {
+            return Err(invalid());
+        }
+        schedules.insert(strategy.trim().to_string(), Schedule::new(windows));
+    }
+    Ok(schedules)
+}
+
+/// Parse `strategy=limit;strategy=limit`, e.g. `sure_bets=20;market_maker=30`.
+fn parse_strategy_exposure(value: &str) -> Result<HashMap<String, f64>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_STRATEGY_EXPOSURE");
+    let mut limits = HashMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (strategy, limit) = entry.split_once('=').ok_or_else(invalid)?;
+        let limit: f64 = limit.trim().parse().map_err(|_| invalid())?;
+        if strategy.trim().is_empty() || !limit.is_finite() || limit < 0.0 {
+            return Err(invalid());
+        }
+        limits.insert(strategy.trim().to_string(), limit);
+    }
+    Ok(limits)
+}
+
+/// Parse `strategy=NAME:value,NAME:value;strategy=NAME:value`, e.g.
+/// `basket_arb=MIN_EDGE:0.03,MAX_LEGS:8`.
+fn parse_strategy_params(value: &str) -> Result<HashMap<String, StrategyParams>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_STRATEGY_PARAMS");
+    let mut all = HashMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (strategy, specs) = entry.split_once('=').ok_or_else(invalid)?;
+        let mut params = StrategyParams::new();
+        for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (name, value) = spec.split_once(':').ok_or_else(invalid)?;
+            if name.trim().is_empty() || value.trim().is_empty() {
+                return Err(invalid());
+            }
+            params.insert(name.trim(), value.trim());
+        }
+        if strategy.trim().is_empty() || params.is_empty() {
+            return Err(invalid());
+        }
+        all.insert(strategy.trim().to_string(), params);
+    }
+    Ok(all)
+}
+
+/// Parse `source,source`, e.g. `events,series,negrisk`.
+fn parse_discovery_sources(value: &str) -> Result<Vec<DiscoverySource>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue("PMENGINE_DISCOVERY_SOURCES");
+    let mut sources = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let source = DiscoverySource::parse(name).ok_or_else(invalid)?;
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    if sources.is_empty() {
+        return Err(invalid());
+    }
+    Ok(sources)
+}
+
+/// Parse `filter,filter`, e.g. `min_certainty:0.9,min_liquidity:500`
+/// (see [`DiscoveryFilter::parse`]). Empty means no filters.
+fn parse_discovery_filters(value: &str) -> Result<Vec<DiscoveryFilter>, ConfigError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| DiscoveryFilter::parse(s).ok_or(ConfigError::InvalidValue("PMENGINE_DISCOVERY_FILTERS")))
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum ConfigError {
     MissingVar(&'static str),
     InvalidValue(&'static str),
+    /// A config file could not be read or has invalid contents
+    File(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -103,8 +789,109 @@ impl std::fmt::Display for ConfigError {
         match self {
             ConfigError::MissingVar(var) => write!(f, "Missing environment variable: {}", var),
             ConfigError::InvalidValue(var) => write!(f, "Invalid value for: {}", var),
+            ConfigError::File(e) => write!(f, "Invalid config file: {}", e),
         }
     }
 }
 
 impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_strategy_categories() {
+        let scopes = parse_strategy_categories("sure_bets=Crypto, sports; market_maker=politics").unwrap();
+        assert_eq!(scopes["sure_bets"], vec!["crypto", "sports"]);
+        assert_eq!(scopes["market_maker"], vec!["politics"]);
+        assert!(parse_strategy_categories("").unwrap().is_empty());
+        assert!(parse_strategy_categories("sure_bets").is_err());
+        assert!(parse_strategy_categories("sure_bets=").is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy_exposure() {
+        let limits = parse_strategy_exposure("sure_bets=20; market_maker = 7.5").unwrap();
+        assert_eq!(limits["sure_bets"], 20.0);
+        assert_eq!(limits["market_maker"], 7.5);
+        assert!(parse_strategy_exposure("").unwrap().is_empty());
+        assert!(parse_strategy_exposure("sure_bets").is_err());
+        assert!(parse_strategy_exposure("sure_bets=lots").is_err());
+        assert!(parse_strategy_exposure("sure_bets=-1").is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy_params() {
+        let params = parse_strategy_params("basket_arb=min_edge:0.03, MAX_LEGS:8").unwrap();
+        let basket_arb = &params["basket_arb"];
+        assert_eq!(basket_arb.names(), vec!["MAX_LEGS", "MIN_EDGE"]);
+        assert_eq!(basket_arb.get::<usize>("max_legs").unwrap(), Some(8));
+        assert!(parse_strategy_params("basket_arb=MIN_EDGE").is_err());
+        assert!(parse_strategy_params("basket_arb=").is_err());
+    }
+
+    #[test]
+    fn test_param_overrides() {
+        let mut all = parse_strategy_params("market_maker=SPREAD_BPS:100,ORDER_SIZE:5").unwrap();
+        let one = vec!["market_maker".to_string()];
+        apply_param_override(&mut all, &one, "spread_bps=150").unwrap();
+        assert_eq!(all["market_maker"].get::<u32>("SPREAD_BPS").unwrap(), Some(150));
+        assert_eq!(all["market_maker"].get::<u32>("ORDER_SIZE").unwrap(), Some(5));
+
+        // With several strategies the target must be named, and be one of them
+        let two = vec!["market_maker".to_string(), "sure_bets".to_string()];
+        assert!(apply_param_override(&mut all, &two, "max_position=50").is_err());
+        apply_param_override(&mut all, &two, "sure_bets.MIN_CERTAINTY=0.97").unwrap();
+        assert_eq!(all["sure_bets"].names(), vec!["MIN_CERTAINTY"]);
+        assert!(apply_param_override(&mut all, &two, "basket_arb.MIN_EDGE=0.1").is_err());
+        assert!(apply_param_override(&mut all, &one, "spread_bps").is_err());
+        assert!(apply_param_override(&mut all, &one, "spread_bps=").is_err());
+    }
+
+    #[test]
+    fn test_parse_discovery() {
+        let sources = parse_discovery_sources("series, events,series").unwrap();
+        assert_eq!(sources, vec![DiscoverySource::Series, DiscoverySource::Events]);
+        assert!(parse_discovery_sources("").is_err());
+        assert!(parse_discovery_sources("events,markets").is_err());
+
+        let filters = parse_discovery_filters("min_certainty:0.95, categories:crypto").unwrap();
+        assert_eq!(
+            filters,
+            vec![
+                DiscoveryFilter::MinCertainty(rust_decimal_macros::dec!(0.95)),
+                DiscoveryFilter::Categories(vec!["crypto".to_string()]),
+            ]
+        );
+        assert!(parse_discovery_filters(" ").unwrap().is_empty());
+        assert!(parse_discovery_filters("min_certainty:high").is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy_filters() {
+        let value = "sure_bets=min_liquidity:500, hours_to_expiry:0-48; mm=max_spread_bps:300";
+        let filters = parse_strategy_filters(value).unwrap();
+        assert_eq!(
+            filters["sure_bets"],
+            vec![Filter::MinLiquidity(500.0), Filter::HoursToExpiry { min: 0.0, max: 48.0 }]
+        );
+        assert_eq!(filters["mm"].len(), 1);
+        assert!(parse_strategy_filters("sure_bets=min_volume:10").is_err());
+        assert!(parse_strategy_filters("sure_bets=").is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy_schedules() {
+        let value = "mm=mon-fri 08:00-22:00 America/New_York, sat 10:00-14:00; sure_bets=* 22:00-02:00";
+        let schedules = parse_strategy_schedules(value).unwrap();
+        let window = |spec| Window::parse(spec).unwrap();
+        assert_eq!(
+            schedules["mm"],
+            Schedule::new(vec![window("mon-fri 08:00-22:00 America/New_York"), window("sat 10:00-14:00")])
+        );
+        assert_eq!(schedules["sure_bets"], Schedule::new(vec![window("* 22:00-02:00")]));
+        assert!(parse_strategy_schedules("mm=weekdays 08:00-22:00").is_err());
+        assert!(parse_strategy_schedules("mm=").is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Layered TOML configuration file.
+//!
+//! `pmengine --config pmengine.toml` (or `PMENGINE_CONFIG`, or a
+//! `pmengine.toml` in the working directory) supplies settings that would
+//! otherwise come from `PMENGINE_*` environment variables, so deployments can
+//! tune limits and strategy parameters without rebuilding:
+//!
+//! ```toml
+//! [engine]
+//! tick_interval_ms = 500
+//! state_dir = "state"
+//!
+//! [risk]
+//! max_total_exposure = 200
+//!
+//! [discovery]
+//! sources = ["events", "series"]
+//! filters = ["min_certainty:0.9", "min_liquidity:500"]
+//! refresh_secs = 120
+//!
+//! [strategies.basket_arb]
+//! max_exposure = 50
+//! categories = ["politics"]
+//! filters = ["min_liquidity:500"]
+//! schedule = ["mon-fri 08:00-22:00 America/New_York"]
+//! params = { MIN_EDGE = 0.03, MAX_LEGS = 8 }
+//! ```
+//!
+//! Keys of `[engine]` and `[risk]` are the variable names without the
+//! `PMENGINE_` prefix, in lowercase; keys of `[discovery]` fill
+//! `PMENGINE_DISCOVERY_<KEY>` (an empty `filters` list turns the default
+//! certainty filter off). Strategy sections fill
+//! `PMENGINE_STRATEGY_EXPOSURE`, `_CATEGORIES`, `_FILTERS`, `_SCHEDULE` and `_PARAMS`.
+//!
+//! The file is the lowest layer: a variable already set in the environment
+//! (or `.env`) wins. For the per-strategy variables that means an
+//! environment value replaces the file's sections for every strategy.
+//! Secrets (the private key) are only read from the environment.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+use crate::config::ConfigError;
+
+/// Default file looked for in the working directory.
+pub const DEFAULT_PATH: &str = "pmengine.toml";
+
+/// `[engine]` keys.
+const ENGINE_KEYS: &[&str] = &[
+    "funder_address",
+    "clob_url",
+    "ws_url",
+    "tick_interval_ms",
+    "log_level",
+    "signature_type",
+    "watchdog_secs",
+    "watchdog_restart",
+    "kill_file",
+    "kill_flatten",
+    "ws_stale_secs",
+    "book_stale_secs",
+    "strategy_budget_ms",
+    "strategy_max_overruns",
+    "cold_start_tokens",
+    "order_latency_budget_ms",
+    "cancel_late_orders",
+    "replace_tolerance",
+    "rate_limits",
+    "warm_start_minutes",
+    "risk_journal",
+    "trade_journal",
+    "synthetic_markets",
+    "state_dir",
+    "reconcile_secs",
+    "reconcile_alert_only",
+    "settle_secs",
+    "rpc_url",
+    "record_dir",
+    "record_depth",
+    "record_rotate_mins",
+    "webhook_addr",
+];
+
+/// `[risk]` keys.
+const RISK_KEYS: &[&str] = &[
+    "max_position_size",
+    "max_total_exposure",
+    "max_market_exposure",
+    "max_category_exposure",
+    "kelly_fraction",
+    "bankroll",
+    "reservation_ttl_secs",
+    "max_loss",
+    "max_drawdown",
+    "drawdown_window_secs",
+    "max_loss_rate",
+    "loss_rate_window_secs",
+    "reduce_only_at",
+    "breaker_cooldown_secs",
+    "max_open_orders",
+    "max_open_orders_per_token",
+    "max_order_age_secs",
+    "stop_loss",
+    "take_profit",
+    "max_hold_secs",
+    "exit_before_expiry_secs",
+    "expiry_alert_only",
+    "min_price",
+    "max_price",
+    "max_price_deviation",
+    "max_depth_ratio",
+    "min_carry_apy",
+    "order_ladder",
+];
+
+/// Settings read from a config file, as environment variables.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    vars: Vec<(String, String)>,
+}
+
+impl ConfigFile {
+    /// Read and parse a config file.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::File(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&text).map_err(|e| match e {
+            ConfigError::File(e) => ConfigError::File(format!("{}: {}", path.display(), e)),
+            other => other,
+        })
+    }
+
+    /// Parse a config file's contents.
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let doc: DocumentMut = text.parse().map_err(|e| ConfigError::File(format!("{}", e)))?;
+        let mut vars = Vec::new();
+        // Per-strategy settings, joined into one variable each
+        let mut strategy_vars: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+        for (section, item) in doc.iter() {
+            let table = item
+                .as_table_like()
+                .ok_or_else(|| invalid(section, "expected a table"))?;
+            match section {
+                "engine" | "risk" => {
+                    let known = if section == "engine" { ENGINE_KEYS } else { RISK_KEYS };
+                    for (key, item) in table.iter() {
+                        let path = format!("{}.{}", section, key);
+                        if !known.contains(&key) {
+                            return Err(invalid(&path, "unknown key"));
+                        }
+                        let value = item
+                            .as_value()
+                            .and_then(scalar)
+                            .ok_or_else(|| invalid(&path, "expected a value"))?;
+                        vars.push((format!("PMENGINE_{}", key.to_uppercase()), value));
+                    }
+                }
+                "discovery" => {
+                    for (key, item) in table.iter() {
+                        let path = format!("discovery.{}", key);
+                        let value = match key {
+                            "sources" => list(item, &path)?,
+                            "filters" if item.as_array().is_some_and(|a| a.is_empty()) => String::new(),
+                            "filters" => list(item, &path)?,
+                            "max_hours" | "refresh_secs" | "max_tokens" => item
+                                .as_value()
+                                .and_then(scalar)
+                                .ok_or_else(|| invalid(&path, "expected a value"))?,
+                            _ => {
+                                let expected =
+                                    "unknown key (expected sources, filters, max_hours, refresh_secs or max_tokens)";
+                                return Err(invalid(&path, expected));
+                            }
+                        };
+                        vars.push((format!("PMENGINE_DISCOVERY_{}", key.to_uppercase()), value));
+                    }
+                }
+                "strategies" => {
+                    for (strategy, item) in table.iter() {
+                        let settings = item
+                            .as_table_like()
+                            .ok_or_else(|| invalid(&format!("strategies.{}", strategy), "expected a table"))?;
+                        for (var, value) in strategy_settings(strategy, settings)? {
+                            strategy_vars.entry(var).or_default().push(format!("{}={}", strategy, value));
+                        }
+                    }
+                }
+                _ => return Err(invalid(section, "unknown section (expected engine, risk, discovery or strategies)")),
+            }
+        }
+
+        for (var, entries) in strategy_vars {
+            vars.push((var.to_string(), entries.join(";")));
+        }
+        Ok(Self { vars })
+    }
+
+    /// Variables the file sets, as `(name, value)`.
+    pub fn vars(&self) -> &[(String, String)] {
+        &self.vars
+    }
+
+    /// Set the file's variables that aren't already set in the environment.
+    /// Returns how many were set.
+    pub fn apply_to_env(&self) -> usize {
+        let mut applied = 0;
+        for (name, value) in &self.vars {
+            if std::env::var_os(name).is_none() {
+                std::env::set_var(name, value);
+                applied += 1;
+            }
+        }
+        applied
+    }
+}
+
+/// Variables for one `[strategies.<name>]` section, with this strategy's value.
+fn strategy_settings(
+    strategy: &str,
+    settings: &dyn TableLike,
+) -> Result<Vec<(&'static str, String)>, ConfigError> {
+    let mut vars = Vec::new();
+    for (key, item) in settings.iter() {
+        let path = format!("strategies.{}.{}", strategy, key);
+        match key {
+            "max_exposure" => {
+                let value = item
+                    .as_value()
+                    .and_then(scalar)
+                    .ok_or_else(|| invalid(&path, "expected a number"))?;
+                vars.push(("PMENGINE_STRATEGY_EXPOSURE", value));
+            }
+            "categories" => vars.push(("PMENGINE_STRATEGY_CATEGORIES", list(item, &path)?)),
+            "filters" => vars.push(("PMENGINE_STRATEGY_FILTERS", list(item, &path)?)),
+            "schedule" => vars.push(("PMENGINE_STRATEGY_SCHEDULE", list(item, &path)?)),
+            "params" => {
+                let params = item.as_table_like().ok_or_else(|| invalid(&path, "expected a table"))?;
+                let mut specs = Vec::new();
+                for (name, item) in params.iter() {
+                    let value = item
+                        .as_value()
+                        .and_then(scalar)
+                        .ok_or_else(|| invalid(&format!("{}.{}", path, name), "expected a value"))?;
+                    specs.push(format!("{}:{}", name, value));
+                }
+                if !specs.is_empty() {
+                    vars.push(("PMENGINE_STRATEGY_PARAMS", specs.join(",")));
+                }
+            }
+            _ => {
+                let expected = "unknown key (expected max_exposure, categories, filters, schedule or params)";
+                return Err(invalid(&path, expected));
+            }
+        }
+    }
+    Ok(vars)
+}
+
+/// A string, number or boolean as the environment would spell it.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.value().clone()),
+        Value::Integer(i) => Some(i.value().to_string()),
+        Value::Float(f) => Some(f.value().to_string()),
+        Value::Boolean(b) => Some(b.value().to_string()),
+        _ => None,
+    }
+}
+
+/// An array of strings (or a single string), comma-separated.
+fn list(item: &Item, path: &str) -> Result<String, ConfigError> {
+    let value = item.as_value().ok_or_else(|| invalid(path, "expected a list"))?;
+    let values: Option<Vec<String>> = match value {
+        Value::Array(array) => array.iter().map(|v| v.as_str().map(String::from)).collect(),
+        Value::String(s) => Some(vec![s.value().clone()]),
+        _ => None,
+    };
+    values
+        .filter(|v| !v.is_empty())
+        .map(|v| v.join(","))
+        .ok_or_else(|| invalid(path, "expected a list of strings"))
+}
+
+fn invalid(path: &str, reason: &str) -> ConfigError {
+    ConfigError::File(format!("{}: {}", path, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_become_env_vars() {
+        let file = ConfigFile::parse(
+            r#"
+            [engine]
+            tick_interval_ms = 500
+            state_dir = "state"
+            watchdog_restart = true
+
+            [risk]
+            max_total_exposure = 200.5
+
+            [discovery]
+            sources = ["events", "series"]
+            filters = []
+            max_tokens = 400
+
+            [strategies.basket_arb]
+            max_exposure = 50
+            categories = ["politics", "crypto"]
+            params = { MIN_EDGE = 0.03, MAX_LEGS = 8 }
+
+            [strategies.sure_bets]
+            filters = "min_liquidity:500"
+            schedule = ["mon-fri 08:00-22:00 America/New_York", "sat 10:00-14:00"]
+            max_exposure = 20
+            "#,
+        )
+        .unwrap();
+
+        let vars: BTreeMap<&str, &str> = file.vars().iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        assert_eq!(vars["PMENGINE_TICK_INTERVAL_MS"], "500");
+        assert_eq!(vars["PMENGINE_STATE_DIR"], "state");
+        assert_eq!(vars["PMENGINE_WATCHDOG_RESTART"], "true");
+        assert_eq!(vars["PMENGINE_MAX_TOTAL_EXPOSURE"], "200.5");
+        assert_eq!(vars["PMENGINE_DISCOVERY_SOURCES"], "events,series");
+        assert_eq!(vars["PMENGINE_DISCOVERY_FILTERS"], "");
+        assert_eq!(vars["PMENGINE_DISCOVERY_MAX_TOKENS"], "400");
+        assert_eq!(vars["PMENGINE_STRATEGY_EXPOSURE"], "basket_arb=50;sure_bets=20");
+        assert_eq!(vars["PMENGINE_STRATEGY_CATEGORIES"], "basket_arb=politics,crypto");
+        assert_eq!(vars["PMENGINE_STRATEGY_FILTERS"], "sure_bets=min_liquidity:500");
+        assert_eq!(vars["PMENGINE_STRATEGY_PARAMS"], "basket_arb=MIN_EDGE:0.03,MAX_LEGS:8");
+        assert_eq!(
+            vars["PMENGINE_STRATEGY_SCHEDULE"],
+            "sure_bets=mon-fri 08:00-22:00 America/New_York,sat 10:00-14:00"
+        );
+        assert_eq!(vars.len(), 12);
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys_and_bad_values() {
+        for text in [
+            "[engine]\nmax_total_exposure = 5",
+            "[engine]\nprivate_key = \"0xabc\"",
+            "[risk]\nmax_total_exposure = [1, 2]",
+            "[strategy.sure_bets]\nmax_exposure = 5",
+            "[strategies.sure_bets]\nmax_exposures = 5",
+            "[strategies.sure_bets]\ncategories = []",
+            "[discovery]\nmax_certainty = 0.9",
+            "[discovery]\nsources = []",
+            "engine = 5",
+            "[engine",
+        ] {
+            assert!(
+                matches!(ConfigFile::parse(text), Err(ConfigError::File(_))),
+                "accepted: {}",
+                text
+            );
+        }
+        assert_eq!(ConfigFile::parse("").unwrap(), ConfigFile::default());
+    }
+}
@@ -0,0 +1,132 @@
+//! Control of a running engine from outside its event loop.
+//!
+//! [`Engine::control`](crate::Engine::control) hands out an [`EngineControl`]
+//! that can be cloned and moved to other tasks (an admin endpoint, a program
+//! embedding the engine). Its commands are queued and applied by the event
+//! loop between other events, except [`EngineControl::kill`], which trips the
+//! [`KillSwitch`] directly so it works even if the loop is stuck, and
+//! [`EngineControl::journal`], which reads the trade journal file.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+
+use crate::kill_switch::KillSwitch;
+use crate::strategy::StrategyParams;
+use crate::trade_journal::{self, JournalEntry};
+
+/// Queued commands before senders see the queue as full.
+pub const CONTROL_QUEUE: usize = 16;
+
+/// A command for the running engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Switch reduce-only mode on or off
+    ReduceOnly { enabled: bool, reason: String },
+    /// Load a strategy by name, with parameters on top of the configured ones
+    LoadStrategy { name: String, params: StrategyParams },
+    /// Cancel a strategy's orders and remove it
+    UnloadStrategy { strategy_id: String },
+    /// Replace a strategy with a fresh instance, with parameters on top of
+    /// the configured ones
+    RestartStrategy { strategy_id: String, params: StrategyParams },
+}
+
+/// Handle for sending commands to a running engine.
+#[derive(Debug, Clone)]
+pub struct EngineControl {
+    sender: mpsc::Sender<ControlCommand>,
+    kill_switch: Arc<KillSwitch>,
+    /// Trade journal file (None = not journaling)
+    journal: Option<PathBuf>,
+}
+
+impl EngineControl {
+    pub(crate) fn new(
+        sender: mpsc::Sender<ControlCommand>,
+        kill_switch: Arc<KillSwitch>,
+        journal: Option<PathBuf>,
+    ) -> Self {
+        Self { sender, kill_switch, journal }
+    }
+
+    /// Allow only orders that shrink a position (or lift that restriction).
+    /// Returns false if the command could not be queued.
+    pub fn set_reduce_only(&self, enabled: bool, reason: &str) -> bool {
+        self.send(ControlCommand::ReduceOnly { enabled, reason: reason.to_string() })
+    }
+
+    /// Load a strategy by name (as for `load_strategies`) into the running
+    /// engine. Returns false if the command could not be queued.
+    pub fn load_strategy(&self, name: &str, params: StrategyParams) -> bool {
+        self.send(ControlCommand::LoadStrategy { name: name.to_string(), params })
+    }
+
+    /// Cancel a strategy's orders and remove it; the other strategies keep
+    /// trading. Returns false if the command could not be queued.
+    pub fn unload_strategy(&self, strategy_id: &str) -> bool {
+        self.send(ControlCommand::UnloadStrategy { strategy_id: strategy_id.to_string() })
+    }
+
+    /// Unload a strategy and load a fresh instance of it, e.g. with new
+    /// parameters. Returns false if the command could not be queued.
+    pub fn restart_strategy(&self, strategy_id: &str, params: StrategyParams) -> bool {
+        self.send(ControlCommand::RestartStrategy { strategy_id: strategy_id.to_string(), params })
+    }
+
+    /// Trip the kill switch: cancel all orders and stop trading until the
+    /// engine restarts. Returns false if it was already on.
+    pub fn kill(&self, reason: &str) -> bool {
+        self.kill_switch.trigger(reason)
+    }
+
+    /// Journaled signals, risk decisions, orders and fills from `from` to `to`
+    /// (either open). Empty when the engine keeps no trade journal.
+    pub fn journal(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> std::io::Result<Vec<JournalEntry>> {
+        match &self.journal {
+            Some(path) if path.exists() => trade_journal::read(path, from, to),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn send(&self, command: ControlCommand) -> bool {
+        self.sender.try_send(command).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_queued_until_full() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let control = EngineControl::new(sender, Arc::new(KillSwitch::new()), None);
+
+        assert!(control.set_reduce_only(true, "winding down"));
+        assert!(!control.clone().set_reduce_only(false, "queue full"));
+        let command = receiver.try_recv().unwrap();
+        assert_eq!(command, ControlCommand::ReduceOnly { enabled: true, reason: "winding down".to_string() });
+
+        let mut params = StrategyParams::new();
+        params.insert("ORDER_SIZE", "5");
+        assert!(control.restart_strategy("momentum", params.clone()));
+        let command = receiver.try_recv().unwrap();
+        assert_eq!(command, ControlCommand::RestartStrategy { strategy_id: "momentum".to_string(), params });
+
+        drop(receiver);
+        assert!(!control.set_reduce_only(false, "engine gone"));
+
+        // Kills don't wait in the queue
+        assert!(control.kill("operator"));
+        assert!(!control.kill("again"));
+
+        assert_eq!(control.journal(None, None).unwrap(), []);
+    }
+}
@@ -0,0 +1,168 @@
+//! Conversion of NO positions in negRisk events.
+//!
+//! Exactly one outcome of a negRisk event resolves YES, so NO shares of `k`
+//! of its outcomes are worth `k - 1` USDC plus a YES share of each of the
+//! other outcomes, and the NegRisk adapter swaps them for exactly that. A
+//! strategy asks for it with [`Signal::Convert`](crate::strategy::Signal):
+//!
+//! - [`Conversion::plan`] checks that the tokens are NO tokens of distinct
+//!   questions of one event, and the engine that the strategy holds them;
+//! - the engine sends `convertPositions` from the signer through
+//!   `PMENGINE_RPC_URL` (it needs POL for gas); in dry-run the conversion is
+//!   only booked, and when a proxy or Safe wallet holds the tokens it is
+//!   left to be done manually;
+//! - [`Conversion::fills`] books it: the NO shares are sold for the USDC
+//!   they convert into, spread evenly, and the YES shares are bought at 0.
+//!
+//! YES shares of outcomes the engine has no market info for are not booked;
+//! reconciliation picks them up.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::position::Fill;
+use crate::strategy::MarketInfo;
+
+/// A conversion of NO shares of some outcomes of a negRisk event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    /// NegRisk adapter market ID of the event
+    pub market_id: String,
+    /// Questions whose NO shares are converted
+    pub question_indices: Vec<u8>,
+    /// NO tokens converted, by question index
+    pub no_tokens: Vec<String>,
+    /// YES tokens received (the event's other outcomes)
+    pub yes_tokens: Vec<String>,
+    /// Shares converted per NO token (and received per YES token)
+    pub size: Decimal,
+}
+
+impl Conversion {
+    /// Plan converting `size` shares of each of `token_ids`, which must be
+    /// NO tokens of distinct questions of one negRisk event.
+    pub fn plan(
+        token_ids: &[String],
+        size: Decimal,
+        markets: &HashMap<String, MarketInfo>,
+    ) -> Result<Self, String> {
+        if token_ids.is_empty() || size <= Decimal::ZERO {
+            return Err("nothing to convert".to_string());
+        }
+
+        let mut market_id: Option<&str> = None;
+        let mut questions: Vec<(u8, String)> = Vec::new();
+        for token_id in token_ids {
+            let info = markets
+                .get(token_id)
+                .ok_or_else(|| format!("no market info for {}", token_id))?;
+            let (Some(id), Some(index)) = (info.neg_risk_market_id.as_deref(), info.question_index) else {
+                return Err(format!("{} is not in a negRisk event", token_id));
+            };
+            if info.outcome.eq_ignore_ascii_case("yes") {
+                return Err(format!("{} is a YES token", token_id));
+            }
+            if market_id.is_some_and(|m| m != id) {
+                return Err("tokens belong to different events".to_string());
+            }
+            if questions.iter().any(|(i, _)| *i == index) {
+                return Err(format!("question {} is converted twice", index));
+            }
+            market_id = Some(id);
+            questions.push((index, token_id.clone()));
+        }
+        questions.sort();
+        let market_id = market_id.unwrap_or_default().to_string();
+
+        let mut yes_tokens: Vec<(u8, String)> = markets
+            .iter()
+            .filter(|(_, info)| info.neg_risk_market_id.as_deref() == Some(market_id.as_str()))
+            .filter(|(_, info)| info.outcome.eq_ignore_ascii_case("yes"))
+            .filter_map(|(token_id, info)| info.question_index.map(|index| (index, token_id.clone())))
+            .filter(|(index, _)| !questions.iter().any(|(i, _)| i == index))
+            .collect();
+        yes_tokens.sort();
+
+        Ok(Self {
+            market_id,
+            question_indices: questions.iter().map(|(index, _)| *index).collect(),
+            no_tokens: questions.into_iter().map(|(_, token_id)| token_id).collect(),
+            yes_tokens: yes_tokens.into_iter().map(|(_, token_id)| token_id).collect(),
+            size,
+        })
+    }
+
+    /// USDC paid out: `k - 1` per share for NO shares of `k` outcomes.
+    pub fn proceeds(&self) -> Decimal {
+        Decimal::from(self.no_tokens.len() - 1) * self.size
+    }
+
+    /// Fills booking the conversion for `strategy_id`.
+    pub fn fills(&self, strategy_id: &str) -> Vec<Fill> {
+        let order_id = format!("conversion:{}", self.market_id);
+        let now = chrono::Utc::now();
+        let fill = |token_id: &str, is_buy: bool, price: Decimal| Fill {
+            order_id: order_id.clone(),
+            token_id: token_id.to_string(),
+            is_buy,
+            price,
+            size: self.size,
+            timestamp: now,
+            fee: Decimal::ZERO,
+            strategy_id: Some(strategy_id.to_string()),
+        };
+
+        let outcomes = Decimal::from(self.no_tokens.len());
+        let no_price = (outcomes - Decimal::ONE) / outcomes;
+        let sells = self.no_tokens.iter().map(|token_id| fill(token_id, false, no_price));
+        let buys = self.yes_tokens.iter().map(|token_id| fill(token_id, true, Decimal::ZERO));
+        sells.chain(buys).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn markets() -> HashMap<String, MarketInfo> {
+        let mut markets = HashMap::new();
+        for (index, name) in ["alice", "bob", "carol"].iter().enumerate() {
+            for outcome in ["Yes", "No"] {
+                let question = format!("Will {} win?", name);
+                let info = MarketInfo::new(question, outcome.into(), name.to_string(), None)
+                    .with_neg_risk_question(Some("0xelection".to_string()), Some(index as u8));
+                markets.insert(format!("{}-{}", name, outcome.to_lowercase()), info);
+            }
+        }
+        markets.insert("rain-no".into(), MarketInfo::new("Rain?".into(), "No".into(), "rain".into(), None));
+        markets
+    }
+
+    #[test]
+    fn test_conversion_plan_and_fills() {
+        let markets = markets();
+        let tokens = |ids: &[&str]| ids.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        let partial = Conversion::plan(&tokens(&["carol-no", "alice-no"]), dec!(10), &markets).unwrap();
+        assert_eq!(partial.question_indices, vec![0, 2]);
+        assert_eq!(partial.no_tokens, tokens(&["alice-no", "carol-no"]));
+        assert_eq!(partial.yes_tokens, tokens(&["bob-yes"]));
+        assert_eq!(partial.proceeds(), dec!(10));
+
+        // NO shares sold for the USDC, YES shares received at no cost
+        let fills = partial.fills("arb");
+        assert_eq!(fills.len(), 3);
+        assert!(fills[..2].iter().all(|f| !f.is_buy && f.price == dec!(0.5) && f.size == dec!(10)));
+        assert!(fills[2].is_buy && fills[2].price.is_zero() && fills[2].token_id == "bob-yes");
+
+        let full = Conversion::plan(&tokens(&["alice-no", "bob-no", "carol-no"]), dec!(3), &markets).unwrap();
+        assert!(full.yes_tokens.is_empty());
+        assert_eq!(full.proceeds(), dec!(6));
+
+        for bad in [&["alice-yes"][..], &["alice-no", "rain-no"], &["alice-no", "alice-no"], &[]] {
+            assert!(Conversion::plan(&tokens(bad), dec!(1), &markets).is_err());
+        }
+    }
+}
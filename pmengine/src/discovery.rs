@@ -0,0 +1,380 @@
+//! Gamma market discovery: configuration and per-source health.
+//!
+//! Discovery merges markets from the events endpoint, recurring series and
+//! (for basket strategies) negRisk events. Which sources are fetched, how far
+//! ahead, how often and which markets are kept is a [`DiscoveryConfig`]:
+//!
+//! ```text
+//! PMENGINE_DISCOVERY_SOURCES=events,series
+//! PMENGINE_DISCOVERY_FILTERS="min_certainty:0.9,min_liquidity:500"
+//! ```
+//!
+//! Strategies narrow it further with [`Strategy::discovery_filters`]: a market
+//! is kept when it passes the configured filters and the criteria of at least
+//! one strategy.
+//!
+//! A failing source no longer aborts the whole refresh: its failure is
+//! recorded, the markets it returned last time are used in its place, and it
+//! is retried every [`RETRY_INTERVAL`] instead of waiting for the next full
+//! refresh.
+//!
+//! [`Strategy::discovery_filters`]: crate::strategy::Strategy::discovery_filters
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::gamma::GammaMarket;
+use crate::strategy::in_categories;
+
+/// How often failed sources are retried between full refreshes.
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A Gamma endpoint discovery fetches markets from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscoverySource {
+    Events,
+    Series,
+    NegRisk,
+}
+
+impl DiscoverySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscoverySource::Events => "events",
+            DiscoverySource::Series => "series",
+            DiscoverySource::NegRisk => "negrisk",
+        }
+    }
+
+    /// Parse a source name as returned by [`as_str`](Self::as_str).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "events" => Some(DiscoverySource::Events),
+            "series" => Some(DiscoverySource::Series),
+            "negrisk" => Some(DiscoverySource::NegRisk),
+            _ => None,
+        }
+    }
+}
+
+/// A condition a discovered market must meet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscoveryFilter {
+    /// Keep markets whose leading outcome is priced at least this high.
+    MinCertainty(Decimal),
+    /// Keep markets whose leading outcome is priced at most this high.
+    MaxCertainty(Decimal),
+    /// Keep markets with at least this much liquidity (USDC); markets with
+    /// unknown liquidity are kept.
+    MinLiquidity(f64),
+    /// Keep markets with at least this much volume (USDC); markets with
+    /// unknown volume are kept.
+    MinVolume(f64),
+    /// Keep markets in these (lowercase) Gamma categories.
+    Categories(Vec<String>),
+    /// Keep markets expiring within this window (hours from now).
+    HoursToExpiry { min: f64, max: f64 },
+}
+
+impl DiscoveryFilter {
+    /// Parse `name:value`, e.g. `min_certainty:0.9`, `max_certainty:0.85`,
+    /// `min_liquidity:500`, `min_volume:1000`, `categories:crypto|sports` or
+    /// `hours_to_expiry:0-24`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, value) = spec.split_once(':')?;
+        let value = value.trim();
+        match name.trim() {
+            "min_certainty" => value.parse().ok().map(DiscoveryFilter::MinCertainty),
+            "max_certainty" => value.parse().ok().map(DiscoveryFilter::MaxCertainty),
+            "min_liquidity" => value.parse().ok().map(DiscoveryFilter::MinLiquidity),
+            "min_volume" => value.parse().ok().map(DiscoveryFilter::MinVolume),
+            "categories" => {
+                let categories: Vec<String> = value
+                    .split('|')
+                    .map(|c| c.trim().to_lowercase())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                (!categories.is_empty()).then_some(DiscoveryFilter::Categories(categories))
+            }
+            "hours_to_expiry" => {
+                let (min, max) = value.split_once('-')?;
+                let (min, max) = (min.trim().parse().ok()?, max.trim().parse().ok()?);
+                (min <= max).then_some(DiscoveryFilter::HoursToExpiry { min, max })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a market passes this filter.
+    pub fn keep(&self, market: &GammaMarket, now: DateTime<Utc>) -> bool {
+        let leading = || market.outcome_prices.iter().max().copied();
+        match self {
+            DiscoveryFilter::MinCertainty(min) => leading().is_some_and(|p| p >= *min),
+            DiscoveryFilter::MaxCertainty(max) => leading().is_some_and(|p| p <= *max),
+            DiscoveryFilter::MinLiquidity(min) => market.liquidity.is_none_or(|l| l >= *min),
+            DiscoveryFilter::MinVolume(min) => market.volume.is_none_or(|v| v >= *min),
+            DiscoveryFilter::Categories(categories) => {
+                in_categories(market.category.as_deref(), categories)
+            }
+            DiscoveryFilter::HoursToExpiry { min, max } => market.end_date.is_some_and(|end| {
+                let hours = end.signed_duration_since(now).num_seconds() as f64 / 3600.0;
+                hours >= *min && hours <= *max
+            }),
+        }
+    }
+}
+
+/// What discovery fetches, how often, and which markets it keeps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryConfig {
+    /// Endpoints markets are fetched from (negRisk events only when a
+    /// strategy needs baskets)
+    pub sources: Vec<DiscoverySource>,
+    /// How far ahead the events and series endpoints are searched (hours)
+    pub max_hours_to_expiry: f64,
+    /// Filters every discovered market must pass (basket markets excepted)
+    pub filters: Vec<DiscoveryFilter>,
+    /// Seconds between full refreshes
+    pub refresh_secs: u64,
+    /// Most tokens subscribed to in total (0 = unlimited)
+    pub max_tokens: usize,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![DiscoverySource::Events, DiscoverySource::Series, DiscoverySource::NegRisk],
+            max_hours_to_expiry: 72.0,
+            filters: vec![DiscoveryFilter::MinCertainty(dec!(0.90))],
+            refresh_secs: 60,
+            max_tokens: 0,
+        }
+    }
+}
+
+impl DiscoveryConfig {
+    /// Whether a source is fetched.
+    pub fn fetches(&self, source: DiscoverySource) -> bool {
+        self.sources.contains(&source)
+    }
+
+    /// Whether a market passes the configured filters and the criteria of at
+    /// least one strategy. A strategy without criteria takes every market.
+    pub fn keep(
+        &self,
+        market: &GammaMarket,
+        strategy_filters: &[Vec<DiscoveryFilter>],
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.filters.iter().all(|f| f.keep(market, now))
+            && (strategy_filters.is_empty()
+                || strategy_filters
+                    .iter()
+                    .any(|filters| filters.iter().all(|f| f.keep(market, now))))
+    }
+}
+
+/// Fetch history of one source.
+#[derive(Debug, Clone, Default)]
+pub struct SourceHealth {
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Markets from the last successful fetch (reused while the source fails)
+    markets: Vec<GammaMarket>,
+}
+
+/// Health of every discovery source.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryHealth {
+    sources: HashMap<DiscoverySource, SourceHealth>,
+}
+
+impl DiscoveryHealth {
+    /// Record a fetch and return the markets to use: the fetched ones on
+    /// success, the last good ones (possibly none) on failure.
+    pub fn record<E: Display>(
+        &mut self,
+        source: DiscoverySource,
+        result: Result<Vec<GammaMarket>, E>,
+        now: DateTime<Utc>,
+    ) -> Vec<GammaMarket> {
+        let health = self.sources.entry(source).or_default();
+        match result {
+            Ok(markets) => {
+                if health.consecutive_failures > 0 {
+                    tracing::info!(
+                        source = source.as_str(),
+                        failures = health.consecutive_failures,
+                        "Discovery source recovered"
+                    );
+                }
+                health.consecutive_failures = 0;
+                health.last_success = Some(now);
+                health.last_error = None;
+                health.markets = markets.clone();
+                markets
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(e.to_string());
+                tracing::warn!(
+                    source = source.as_str(),
+                    error = %e,
+                    failures = health.consecutive_failures,
+                    reused = health.markets.len(),
+                    last_success = ?health.last_success,
+                    "Discovery source failed, reusing its last markets"
+                );
+                health.markets.clone()
+            }
+        }
+    }
+
+    /// Markets from a source's last successful fetch.
+    pub fn cached(&self, source: DiscoverySource) -> Vec<GammaMarket> {
+        self.sources.get(&source).map(|h| h.markets.clone()).unwrap_or_default()
+    }
+
+    /// Whether a source's last fetch failed.
+    pub fn is_failing(&self, source: DiscoverySource) -> bool {
+        self.sources.get(&source).is_some_and(|h| h.consecutive_failures > 0)
+    }
+
+    /// Whether any source needs a retry.
+    pub fn needs_retry(&self) -> bool {
+        self.sources.values().any(|h| h.consecutive_failures > 0)
+    }
+
+    pub fn get(&self, source: DiscoverySource) -> Option<&SourceHealth> {
+        self.sources.get(&source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(slug: &str) -> GammaMarket {
+        GammaMarket {
+            question: format!("Question for {}?", slug),
+            slug: slug.to_string(),
+            end_date: None,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: Vec::new(),
+            clob_token_ids: Vec::new(),
+            active: true,
+            closed: false,
+            liquidity: None,
+            volume: None,
+            category: None,
+            event_slug: None,
+            neg_risk: false,
+            neg_risk_market_id: None,
+            question_id: None,
+        }
+    }
+
+    fn priced(slug: &str, yes: Decimal, hours: i64, liquidity: Option<f64>) -> GammaMarket {
+        GammaMarket {
+            outcome_prices: vec![yes, Decimal::ONE - yes],
+            end_date: Some(Utc::now() + chrono::Duration::hours(hours)),
+            liquidity,
+            category: Some("Crypto".to_string()),
+            ..market(slug)
+        }
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        assert_eq!(
+            DiscoveryFilter::parse("min_certainty: 0.95"),
+            Some(DiscoveryFilter::MinCertainty(dec!(0.95)))
+        );
+        assert_eq!(
+            DiscoveryFilter::parse("categories:Crypto|sports"),
+            Some(DiscoveryFilter::Categories(vec!["crypto".to_string(), "sports".to_string()]))
+        );
+        assert_eq!(
+            DiscoveryFilter::parse("hours_to_expiry:1-24"),
+            Some(DiscoveryFilter::HoursToExpiry { min: 1.0, max: 24.0 })
+        );
+        assert_eq!(DiscoveryFilter::parse("min_volume:1000"), Some(DiscoveryFilter::MinVolume(1000.0)));
+        assert_eq!(DiscoveryFilter::parse("hours_to_expiry:24-1"), None);
+        assert_eq!(DiscoveryFilter::parse("max_spread_bps:300"), None);
+        assert_eq!(DiscoveryFilter::parse("min_certainty"), None);
+        assert_eq!(DiscoverySource::parse("negrisk"), Some(DiscoverySource::NegRisk));
+        assert_eq!(DiscoverySource::parse("markets"), None);
+    }
+
+    #[test]
+    fn test_config_and_strategy_filters() {
+        let now = Utc::now();
+        let config = DiscoveryConfig::default();
+        let sure = priced("sure", dec!(0.95), 10, Some(2000.0));
+        let thin = priced("thin", dec!(0.93), 10, Some(50.0));
+        let open = priced("open", dec!(0.60), 10, None);
+
+        // The default keeps high-certainty markets only
+        assert!(config.keep(&sure, &[], now));
+        assert!(config.keep(&thin, &[], now));
+        assert!(!config.keep(&open, &[], now));
+
+        // A strategy without criteria takes everything the config keeps
+        let liquid = vec![DiscoveryFilter::MinLiquidity(500.0)];
+        assert!(config.keep(&thin, &[liquid.clone(), Vec::new()], now));
+        assert!(!config.keep(&thin, std::slice::from_ref(&liquid), now));
+        assert!(config.keep(&sure, &[liquid], now));
+
+        // Strategies can't widen what the config filters out
+        let uncertain = vec![DiscoveryFilter::MaxCertainty(dec!(0.85))];
+        assert!(!config.keep(&open, std::slice::from_ref(&uncertain), now));
+        let config = DiscoveryConfig {
+            filters: Vec::new(),
+            ..DiscoveryConfig::default()
+        };
+        assert!(config.keep(&open, std::slice::from_ref(&uncertain), now));
+        assert!(!config.keep(&sure, &[uncertain], now));
+
+        let soon = DiscoveryFilter::HoursToExpiry { min: 0.0, max: 6.0 };
+        assert!(!soon.keep(&sure, now));
+        assert!(soon.keep(&priced("soon", dec!(0.95), 2, None), now));
+        assert!(!soon.keep(&market("undated"), now));
+        assert!(DiscoveryFilter::Categories(vec!["crypto".to_string()]).keep(&sure, now));
+        assert!(!DiscoveryFilter::MinCertainty(dec!(0.5)).keep(&market("unpriced"), now));
+    }
+
+    #[test]
+    fn test_failed_source_reuses_last_markets() {
+        let mut health = DiscoveryHealth::default();
+        let now = Utc::now();
+
+        // Never succeeded: nothing to reuse
+        let markets = health.record(DiscoverySource::Series, Err("timeout"), now);
+        assert!(markets.is_empty());
+        assert!(health.is_failing(DiscoverySource::Series));
+
+        let ok: Result<_, String> = Ok(vec![market("btc-4h")]);
+        assert_eq!(health.record(DiscoverySource::Events, ok, now).len(), 1);
+        let markets = health.record(DiscoverySource::Events, Err("502"), now);
+        assert_eq!(markets[0].slug, "btc-4h");
+
+        let events = health.get(DiscoverySource::Events).unwrap();
+        assert_eq!(events.consecutive_failures, 1);
+        assert_eq!(events.last_error.as_deref(), Some("502"));
+        assert_eq!(events.last_success, Some(now));
+        assert!(health.needs_retry());
+
+        let ok: Result<_, String> = Ok(vec![]);
+        health.record(DiscoverySource::Events, ok.clone(), now);
+        health.record(DiscoverySource::Series, ok, now);
+        assert!(!health.needs_retry());
+        assert!(health.cached(DiscoverySource::Events).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Rolling P&L history for the drawdown circuit breaker.
+//!
+//! The risk manager samples total P&L every tick into a [`PnlWindow`] and
+//! measures two things over it:
+//!
+//! - drawdown: the drop from the highest P&L in the window to the latest
+//!   (`PMENGINE_MAX_DRAWDOWN` over `PMENGINE_DRAWDOWN_WINDOW_SECS`);
+//! - loss rate: how much was lost over a shorter period, from its oldest
+//!   sample to the latest (`PMENGINE_MAX_LOSS_RATE` per
+//!   `PMENGINE_LOSS_RATE_WINDOW_SECS`).
+//!
+//! Crossing `PMENGINE_REDUCE_ONLY_AT` of a limit puts the engine in
+//! reduce-only mode; crossing the limit halts it. See `RiskManager::check_pnl`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use rust_decimal::Decimal;
+
+use crate::safe_math::sub;
+
+/// P&L samples over a trailing window.
+#[derive(Debug, Clone)]
+pub struct PnlWindow {
+    window: Duration,
+    /// (sampled at, total P&L), oldest first
+    samples: VecDeque<(Instant, Decimal)>,
+}
+
+impl PnlWindow {
+    pub fn new(window: Duration) -> Self {
+        Self { window, samples: VecDeque::new() }
+    }
+
+    /// Add a sample, dropping those that fell out of the window.
+    pub fn record(&mut self, now: Instant, pnl: Decimal) {
+        self.samples.push_back((now, pnl));
+        while let Some(&(at, _)) = self.samples.front() {
+            if now.saturating_duration_since(at) <= self.window {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Drop from the window's highest P&L to the latest (zero when empty).
+    pub fn drawdown(&self) -> Decimal {
+        let Some(&(_, latest)) = self.samples.back() else {
+            return Decimal::ZERO;
+        };
+        let peak = self.samples.iter().map(|(_, pnl)| *pnl).max().unwrap_or(latest);
+        sub(peak, latest, "drawdown")
+    }
+
+    /// P&L lost over the last `period` (zero if it was made, or when empty).
+    pub fn loss_over(&self, period: Duration, now: Instant) -> Decimal {
+        let Some(&(_, latest)) = self.samples.back() else {
+            return Decimal::ZERO;
+        };
+        let start = self
+            .samples
+            .iter()
+            .find(|(at, _)| now.saturating_duration_since(*at) <= period)
+            .map_or(latest, |(_, pnl)| *pnl);
+        sub(start, latest, "loss rate").max(Decimal::ZERO)
+    }
+
+    /// Forget the history (after trading resumes).
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_drawdown_and_loss_rate_over_window() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut window = PnlWindow::new(Duration::from_secs(60));
+        assert_eq!(window.drawdown(), dec!(0));
+
+        for (secs, pnl) in [(0, dec!(5)), (10, dec!(12)), (20, dec!(8)), (50, dec!(3))] {
+            window.record(at(secs), pnl);
+        }
+        // Peak 12, now 3
+        assert_eq!(window.drawdown(), dec!(9));
+        // Over the last 30s: from 8 to 3
+        assert_eq!(window.loss_over(Duration::from_secs(30), at(50)), dec!(5));
+
+        // The peak falls out of the window
+        window.record(at(75), dec!(4));
+        assert_eq!(window.drawdown(), dec!(4));
+        assert_eq!(window.loss_over(Duration::from_secs(30), at(75)), dec!(0));
+
+        window.clear();
+        assert_eq!(window.drawdown(), dec!(0));
+    }
+}
@@ -1,13 +1,41 @@
 //! Main event loop for the trading engine.
 
-use crate::client::PolymarketClient;
+use crate::attribution::{StrategyLedger, StrategyPnl, UNATTRIBUTED};
+use crate::carry::{CarryReport, PositionCarry};
+use crate::client::{AccountPosition, OpenOrder, PolymarketClient};
 use crate::config::Config;
-use crate::gamma::{GammaClient, GammaMarket};
-use crate::order::OrderManager;
-use crate::orderbook::MarketDataHub;
+use crate::control::{ControlCommand, EngineControl, CONTROL_QUEUE};
+use crate::conversion::Conversion;
+use crate::discovery::{self, DiscoveryHealth, DiscoverySource};
+use crate::exits::{ExitDefaults, ExitManager, ExitRule};
+use crate::gamma::{self, GammaClient, GammaMarket, MarketDetail, NegRiskEvent};
+use crate::history::HistoryClient;
+use crate::kill_switch::{self, KillSwitch, KillSwitchConfig};
+use crate::market_feed::{self, Backoff, Disconnect, FeedMessage, FeedMetrics};
+use crate::order::{LatencyBudget, OrderError, OrderManager};
+use crate::orderbook::{MarketDataHub, OrderBook};
 use crate::position::{Fill, PositionTracker};
-use crate::risk::{RiskCheckResult, RiskLimits, RiskManager};
-use crate::strategy::{DummyStrategy, MarketInfo, Signal, StrategyContext, StrategyRuntime};
+use crate::priority;
+use crate::reconcile::{self, Reconciliation};
+use crate::registry;
+use crate::recorder::MarketRecorder;
+use crate::report::{self, DailyReport};
+use crate::risk::{MarketReference, RiskCheckResult, RiskLimits, RiskManager};
+use crate::risk_journal::{CheckInputs, RiskDecision, RiskJournal};
+use crate::trade_journal::{JournalEntry, TradeJournal};
+use crate::settlement::{self, Resolution};
+use crate::sizing;
+use crate::store::{EngineState, StateStore, StoredOrder};
+use crate::strategies::StrategyFactory;
+use crate::strategy::{
+    DummyStrategy, MarketInfo, Quarantined, Signal, StrategyContext, StrategyParams, StrategyRuntime,
+    StrategySignal, TickBudget,
+};
+use crate::synthetic::{SyntheticFeed, SyntheticScenario};
+use crate::user_feed::{self, UserEvent};
+use crate::utilization::{TokenExposure, UtilizationReport, UtilizationTracker};
+use crate::watchdog::{self, Heartbeat, LoopActivity, WatchdogConfig};
+use crate::webhook::{self, ExternalEvent};
 
 #[cfg(feature = "cognito")]
 use crate::cognito::create_cognito_auth;
@@ -15,39 +43,109 @@ use crate::cognito::create_cognito_auth;
 use futures::StreamExt;
 use polymarket_client_sdk::clob::ws::Client as WsClient;
 use polymarket_client_sdk::types::U256;
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
-use tokio::time::{interval, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{interval, Instant, Interval};
 
 /// The main trading engine.
 pub struct Engine {
     config: Config,
     client: Arc<PolymarketClient>,
     strategy_runtime: StrategyRuntime,
+    /// Name each strategy was loaded by, by strategy ID (for restarts)
+    strategy_names: HashMap<String, String>,
     order_manager: OrderManager,
     risk_manager: RiskManager,
+    /// Stop-loss, take-profit and time-stop rules of positions
+    exits: ExitManager,
     positions: PositionTracker,
     /// Market data hub with full-depth order books and broadcast channel
     market_data: Arc<MarketDataHub>,
     /// Token IDs we're subscribed to
     subscribed_tokens: Vec<String>,
     fill_receiver: mpsc::Receiver<Fill>,
+    /// Commands from [`EngineControl`] handles
+    control_sender: mpsc::Sender<ControlCommand>,
+    control_receiver: mpsc::Receiver<ControlCommand>,
+    /// Tripped from outside to cancel everything and stop trading
+    kill_switch: Arc<KillSwitch>,
+    /// Whether the loop has stopped trading after the kill switch tripped
+    killed: bool,
     shutdown: bool,
     /// Gamma API client for market discovery
     gamma_client: Option<GammaClient>,
     /// Market metadata by token ID
     market_info: HashMap<String, MarketInfo>,
+    /// Full market detail by token ID (fetched lazily for traded tokens)
+    market_details: HashMap<String, MarketDetail>,
     /// Whether market discovery is enabled
     market_discovery_enabled: bool,
     /// Flag indicating WebSocket needs reconnection due to new market discovery
     ws_needs_reconnect: bool,
     /// Skip warmup period (useful when WS connection is unavailable)
     skip_warmup: bool,
+    /// Event loop progress markers (read by the watchdog)
+    heartbeat: Arc<Heartbeat>,
+    /// Exposure utilization samples (one per trading tick)
+    utilization: UtilizationTracker,
+    /// Whether no market discovery has completed yet (caps initial subscriptions)
+    cold_start: bool,
+    /// Fingerprint of the discovered markets market info was last built from,
+    /// with whether it included baskets and all their outcomes
+    market_fingerprint: Option<(u64, bool, bool)>,
+    /// Risk-check decision journal (None = disabled)
+    risk_journal: Option<RiskJournal>,
+    /// Journal of signals, risk decisions, orders and fills (None = disabled)
+    trade_journal: Option<TradeJournal>,
+    /// Per-source Gamma discovery health and last good results
+    discovery: DiscoveryHealth,
+    /// Scripted synthetic markets (dry-run only, None = disabled)
+    synthetic: Option<SyntheticFeed>,
+    /// Persisted positions, open orders and fills (None = disabled)
+    state_store: Option<StateStore>,
+    /// UTC day the next daily report is for (written once it is over)
+    report_day: chrono::NaiveDate,
+    /// When the last fill was applied (positions aren't reconciled right after one)
+    last_fill: Option<Instant>,
+    /// Account USDC balance as last fetched (None = not yet, or dry-run)
+    usdc_balance: Option<Decimal>,
+    /// Market data recorder, started with the event loop (None = disabled)
+    recorder: Option<MarketRecorder>,
+    /// Market data WebSocket connection counters
+    feed_metrics: FeedMetrics,
+    /// Positions and P&L per strategy
+    attribution: StrategyLedger,
+    /// Tokens whose book is stale (signals for them are dropped)
+    stale_books: HashSet<String>,
+}
+
+/// Event loop state that survives watchdog restarts.
+struct LoopState {
+    tick_timer: Interval,
+    market_refresh_timer: Interval,
+    /// Retries failed discovery sources between full refreshes
+    discovery_retry_timer: Interval,
+    reconcile_timer: Interval,
+    /// Checks held markets for resolution
+    settle_timer: Interval,
+    /// Fills and cancellations of our orders from the user channel
+    user_events: mpsc::Receiver<UserEvent>,
+    /// Signals received by the webhook
+    external_events: mpsc::Receiver<ExternalEvent>,
+    /// Delay before reconnecting market data after a failure
+    ws_backoff: Backoff,
+    shutdown_rx: mpsc::Receiver<()>,
+    last_tick: Instant,
+    tick_count: u64,
+    /// Last WebSocket resync triggered by a crossed/locked book
+    last_book_resync: Option<Instant>,
 }
 
 impl Engine {
@@ -78,9 +176,19 @@ impl Engine {
 
         // Create fill channel
         let (fill_sender, fill_receiver) = mpsc::channel(1000);
+        let (control_sender, control_receiver) = mpsc::channel(CONTROL_QUEUE);
 
         // Create order manager with client
-        let order_manager = OrderManager::new(client.clone(), fill_sender);
+        let mut order_manager = OrderManager::new(client.clone(), fill_sender);
+        if config.order_latency_budget_ms > 0 {
+            order_manager.set_latency_budget(Some(LatencyBudget {
+                budget: Duration::from_millis(config.order_latency_budget_ms),
+                cancel_late: config.cancel_late_orders,
+            }));
+        }
+        if let Some(tolerance) = Decimal::from_f64_retain(config.replace_tolerance) {
+            order_manager.set_replace_tolerance(tolerance);
+        }
 
         // Create risk manager with limits from config
         let risk_limits = RiskLimits {
@@ -88,23 +196,184 @@ impl Engine {
                 .unwrap_or(Decimal::from(50)),
             max_total_exposure: Decimal::from_f64_retain(config.max_total_exposure)
                 .unwrap_or(Decimal::from(50)),
+            max_market_exposure: Decimal::from_f64(config.max_market_exposure).filter(|e| !e.is_zero()),
+            max_category_exposure: Decimal::from_f64(config.max_category_exposure).filter(|e| !e.is_zero()),
             max_order_size: Decimal::from_f64_retain(config.max_total_exposure / 2.0)
                 .unwrap_or(Decimal::from(25)),
-            ..Default::default()
+            reservation_ttl: Duration::from_secs(config.reservation_ttl_secs.max(1)),
+            strategy_max_exposure: config
+                .strategy_max_exposure
+                .iter()
+                .filter_map(|(id, limit)| Some((id.clone(), Decimal::from_f64_retain(*limit)?)))
+                .collect(),
+            max_loss: Decimal::from_f64(config.max_loss).unwrap_or(Decimal::from(25)),
+            max_drawdown: Decimal::from_f64(config.max_drawdown).filter(|d| !d.is_zero()),
+            drawdown_window: Duration::from_secs(config.drawdown_window_secs.max(1)),
+            max_loss_rate: Decimal::from_f64(config.max_loss_rate).filter(|r| !r.is_zero()),
+            loss_rate_window: Duration::from_secs(config.loss_rate_window_secs.max(1)),
+            reduce_only_at: Decimal::from_f64(config.reduce_only_at).filter(|f| !f.is_zero()),
+            breaker_cooldown: (config.breaker_cooldown_secs > 0)
+                .then(|| Duration::from_secs(config.breaker_cooldown_secs)),
+            max_open_orders: config.max_open_orders,
+            max_open_orders_per_token: config.max_open_orders_per_token,
+            min_price: Decimal::from_f64(config.min_price).unwrap_or(Decimal::new(1, 3)),
+            max_price: Decimal::from_f64(config.max_price).unwrap_or(Decimal::new(999, 3)),
+            max_price_deviation: Decimal::from_f64(config.max_price_deviation).filter(|d| !d.is_zero()),
+            max_depth_ratio: Decimal::from_f64(config.max_depth_ratio).filter(|r| !r.is_zero()),
         };
 
         tracing::info!(
             max_position_size = %risk_limits.max_position_size,
             max_total_exposure = %risk_limits.max_total_exposure,
+            max_market_exposure = ?risk_limits.max_market_exposure,
+            max_category_exposure = ?risk_limits.max_category_exposure,
             max_order_size = %risk_limits.max_order_size,
             max_loss = %risk_limits.max_loss,
+            max_drawdown = ?risk_limits.max_drawdown,
+            max_loss_rate = ?risk_limits.max_loss_rate,
+            reduce_only_at = ?risk_limits.reduce_only_at,
+            max_open_orders = risk_limits.max_open_orders,
+            max_open_orders_per_token = risk_limits.max_open_orders_per_token,
+            min_price = %risk_limits.min_price,
+            max_price = %risk_limits.max_price,
+            max_price_deviation = ?risk_limits.max_price_deviation,
+            max_depth_ratio = ?risk_limits.max_depth_ratio,
             "Risk limits configured"
         );
+        for (strategy_id, limit) in &risk_limits.strategy_max_exposure {
+            tracing::info!(
+                strategy_id = strategy_id.as_str(),
+                max_exposure = %limit,
+                "Strategy exposure budget"
+            );
+        }
+
+        let exit_defaults = ExitDefaults {
+            stop_loss: Decimal::from_f64(config.stop_loss).filter(|f| !f.is_zero()),
+            take_profit: Decimal::from_f64(config.take_profit).filter(|f| !f.is_zero()),
+            max_hold: (config.max_hold_secs > 0).then(|| Duration::from_secs(config.max_hold_secs)),
+            before_expiry: (config.exit_before_expiry_secs > 0)
+                .then(|| Duration::from_secs(config.exit_before_expiry_secs)),
+            expiry_alert_only: config.expiry_alert_only,
+        };
+        tracing::info!(
+            stop_loss = ?exit_defaults.stop_loss,
+            take_profit = ?exit_defaults.take_profit,
+            max_hold_secs = config.max_hold_secs,
+            exit_before_expiry_secs = config.exit_before_expiry_secs,
+            expiry_alert_only = config.expiry_alert_only,
+            "Default exit rules"
+        );
+
+        let utilization = UtilizationTracker::new(risk_limits.max_total_exposure);
+        let mut risk_manager = RiskManager::new(risk_limits);
+        let mut positions = PositionTracker::new();
+
+        let (state_store, saved) = match config.state_dir {
+            Some(ref dir) => {
+                let store = StateStore::open(dir).map_err(|e| {
+                    EngineError::ConfigError(format!("Cannot open state directory {}: {}", dir.display(), e))
+                })?;
+                let state = store.load().map_err(|e| {
+                    EngineError::ConfigError(format!("Cannot read saved state in {}: {}", dir.display(), e))
+                })?;
+                (Some(store), state)
+            }
+            None => (None, None),
+        };
+
+        // Start from the saved state (if any), reconciled with the exchange
+        let saved = saved.unwrap_or_default();
+        let state = Self::reconcile_state(&client, saved, dry_run, config.reconcile_alert_only).await;
+        for position in state.positions {
+            positions.restore(position);
+        }
+        for order in state.open_orders {
+            let notional = crate::safe_math::mul(order.remaining(), order.price, "restored order");
+            risk_manager.order_placed(&order.id, &order.token_id, notional, order.strategy_id.as_deref());
+            order_manager.restore(order.into_order());
+        }
+        if state_store.is_some() || !order_manager.active_orders().is_empty() {
+            tracing::info!(
+                path = ?config.state_dir,
+                positions = positions.active_positions().len(),
+                open_orders = order_manager.active_orders().len(),
+                "Trading state restored"
+            );
+        }
 
-        let risk_manager = RiskManager::new(risk_limits);
+        // Rebuild per-strategy P&L from the recorded fills
+        let mut attribution = StrategyLedger::new();
+        if let Some(ref store) = state_store {
+            match store.fills() {
+                Ok(fills) => fills.iter().for_each(|fill| attribution.apply_fill(fill)),
+                Err(e) => tracing::warn!(error = %e, "Cannot read recorded fills, strategy P&L starts empty"),
+            }
+        }
 
         // Create strategy runtime (empty, strategies added via register)
-        let strategy_runtime = StrategyRuntime::new();
+        let mut strategy_runtime = StrategyRuntime::new();
+        strategy_runtime.set_category_scopes(config.strategy_categories.clone());
+        strategy_runtime.set_filters(config.strategy_filters.clone());
+        strategy_runtime.set_schedules(config.strategy_schedules.clone());
+        if config.strategy_budget_ms > 0 {
+            strategy_runtime.set_budget(Some(TickBudget {
+                budget: Duration::from_millis(config.strategy_budget_ms),
+                max_overruns: config.strategy_max_overruns.max(1),
+            }));
+        }
+
+        let risk_journal = match config.risk_journal {
+            Some(ref path) => {
+                let journal = RiskJournal::open(path).map_err(|e| {
+                    EngineError::ConfigError(format!("Cannot open risk journal {}: {}", path.display(), e))
+                })?;
+                tracing::info!(path = %path.display(), "Journaling risk decisions");
+                Some(journal)
+            }
+            None => None,
+        };
+
+        let trade_journal = match config.trade_journal {
+            Some(ref path) => {
+                let journal = TradeJournal::open(path).map_err(|e| {
+                    EngineError::ConfigError(format!("Cannot open trade journal {}: {}", path.display(), e))
+                })?;
+                tracing::info!(path = %path.display(), "Journaling trades");
+                Some(journal)
+            }
+            None => None,
+        };
+
+        let synthetic = match config.synthetic_markets {
+            Some(ref path) => {
+                if !dry_run {
+                    return Err(EngineError::ConfigError(
+                        "PMENGINE_SYNTHETIC_MARKETS requires --dry-run".to_string(),
+                    ));
+                }
+                let scenario = SyntheticScenario::load(path)
+                    .map_err(|e| EngineError::ConfigError(format!("{}: {}", path.display(), e)))?;
+                tracing::info!(
+                    path = %path.display(),
+                    markets = scenario.markets.len(),
+                    "Synthetic markets loaded"
+                );
+                Some(SyntheticFeed::new(scenario))
+            }
+            None => None,
+        };
+
+        let recorder = match config.record_dir {
+            Some(ref dir) => {
+                let rotate = Duration::from_secs(config.record_rotate_mins * 60);
+                let recorder = MarketRecorder::new(dir, config.record_depth, rotate).map_err(|e| {
+                    EngineError::ConfigError(format!("Cannot open record directory {}: {}", dir.display(), e))
+                })?;
+                Some(recorder)
+            }
+            None => None,
+        };
 
         // Create market data hub with broadcast channel
         let market_data = Arc::new(MarketDataHub::new(1000));
@@ -113,21 +382,461 @@ impl Engine {
             config,
             client,
             strategy_runtime,
+            strategy_names: HashMap::new(),
             order_manager,
             risk_manager,
-            positions: PositionTracker::new(),
+            exits: ExitManager::new(exit_defaults),
+            positions,
             market_data,
             subscribed_tokens: Vec::new(),
             fill_receiver,
+            control_sender,
+            control_receiver,
+            kill_switch: Arc::new(KillSwitch::new()),
+            killed: false,
             shutdown: false,
             gamma_client: None,
             market_info: HashMap::new(),
+            market_details: HashMap::new(),
             market_discovery_enabled: false,
             ws_needs_reconnect: false,
             skip_warmup: false,
+            heartbeat: Arc::new(Heartbeat::new()),
+            utilization,
+            cold_start: true,
+            market_fingerprint: None,
+            risk_journal,
+            trade_journal,
+            discovery: DiscoveryHealth::default(),
+            synthetic,
+            state_store,
+            report_day: chrono::Utc::now().date_naive(),
+            last_fill: None,
+            usdc_balance: None,
+            recorder,
+            feed_metrics: FeedMetrics::default(),
+            attribution,
+            stale_books: HashSet::new(),
         })
     }
 
+    /// Reconcile the state the engine starts from (saved, or empty) with the
+    /// exchange's open orders and positions.
+    ///
+    /// In dry-run there is nothing on the exchange to reconcile with, so the
+    /// state is used as is; in alert-only mode mismatches are only logged.
+    async fn reconcile_state(
+        client: &PolymarketClient,
+        state: EngineState,
+        dry_run: bool,
+        alert_only: bool,
+    ) -> EngineState {
+        if dry_run {
+            return state;
+        }
+        let Some((orders, positions)) = Self::fetch_exchange_books(client).await else {
+            return state;
+        };
+        let mut reconciled = state.clone();
+        let result = reconcile::reconcile(&mut reconciled, &orders, positions.as_deref(), None);
+        Self::log_reconciliation(&result, alert_only);
+        if alert_only {
+            state
+        } else {
+            reconciled
+        }
+    }
+
+    /// Reconcile the live order manager and position tracker with the exchange.
+    async fn reconcile_books(&mut self) {
+        let Some((orders, exchange_positions)) = Self::fetch_exchange_books(&self.client).await else {
+            return;
+        };
+        // The Data API lags fills; leave positions alone until it has caught up
+        let settled = self.last_fill.is_none_or(|at| at.elapsed() >= reconcile::GRACE);
+        let exchange_positions = exchange_positions.as_deref().filter(|_| settled);
+
+        let mut books = self.books();
+        let placed_after = chrono::Utc::now() - reconcile::GRACE;
+        let result = reconcile::reconcile(&mut books, &orders, exchange_positions, Some(placed_after));
+        Self::log_reconciliation(&result, self.config.reconcile_alert_only);
+        if result.is_clean() || self.config.reconcile_alert_only {
+            return;
+        }
+
+        for order_id in &result.closed {
+            self.order_manager.close(order_id);
+            self.risk_manager.order_closed(order_id);
+        }
+        for order in books.open_orders.into_iter().filter(|o| result.adopted.contains(&o.id)) {
+            let notional = crate::safe_math::mul(order.remaining(), order.price, "adopted order");
+            self.risk_manager.order_placed(&order.id, &order.token_id, notional, None);
+            self.order_manager.restore(order.into_order());
+        }
+        for position in books
+            .positions
+            .into_iter()
+            .filter(|p| result.corrected_positions.contains(&p.token_id))
+        {
+            self.positions.restore(position);
+        }
+        self.persist_state();
+    }
+
+    /// Apply a fill or cancellation of one of our orders reported on the user channel.
+    ///
+    /// Fills go through the order manager, which sends them down the fill channel.
+    async fn apply_user_event(&mut self, event: UserEvent) {
+        match event {
+            UserEvent::Fill {
+                order_id,
+                trade_id,
+                price,
+                size,
+                fee_rate_bps,
+            } => {
+                // The other side of the trade, or an order placed outside the engine
+                if self.order_manager.get_order(&order_id).is_none() {
+                    return;
+                }
+                tracing::debug!(
+                    order_id = order_id.as_str(),
+                    trade_id = trade_id.as_str(),
+                    "Fill from user channel"
+                );
+                if let Err(e) = self.order_manager.process_fill(&order_id, price, size, fee_rate_bps).await {
+                    tracing::error!(order_id = order_id.as_str(), error = %e, "Failed to process fill");
+                }
+            }
+            UserEvent::Cancelled { order_id } => {
+                if self.order_manager.close(&order_id) {
+                    self.risk_manager.order_closed(&order_id);
+                    tracing::info!(order_id = order_id.as_str(), "Order cancelled on the exchange");
+                    self.persist_state();
+                }
+            }
+        }
+    }
+
+    /// Book a fill: positions, strategy P&L, the fill log, strategies and risk.
+    fn apply_fill(&mut self, fill: &Fill) {
+        tracing::info!(
+            order_id = fill.order_id,
+            token_id = fill.token_id,
+            price = %fill.price,
+            size = %fill.size,
+            "Processing fill"
+        );
+
+        // Update positions
+        self.positions.apply_fill(fill);
+        self.attribution.apply_fill(fill);
+        self.journal_trade(Some(JournalEntry::fill(fill)));
+        self.last_fill = Some(Instant::now());
+        if let Some(ref mut store) = self.state_store {
+            if let Err(e) = store.record_fill(fill) {
+                tracing::error!(error = %e, "Failed to record fill");
+            }
+        }
+
+        // Notify strategies
+        self.strategy_runtime.on_fill(fill);
+
+        // Update risk manager - close tracked order
+        self.risk_manager.order_closed(&fill.order_id);
+
+        // Log current exposure
+        let exposure = self.risk_manager.current_exposure(&self.positions);
+        let remaining = self.risk_manager.remaining_capacity(&self.positions);
+        tracing::info!(
+            exposure = %exposure,
+            remaining_capacity = %remaining,
+            "Exposure after fill"
+        );
+        self.persist_state();
+    }
+
+    /// Fetch the account's USDC balance (nothing to fetch in dry-run).
+    async fn refresh_balance(&mut self) {
+        if self.order_manager.is_dry_run() {
+            return;
+        }
+        match self.client.usdc_balance().await {
+            Ok(balance) => {
+                tracing::debug!(balance = %balance, "USDC balance");
+                self.usdc_balance = Some(balance);
+            }
+            Err(e) => tracing::warn!(
+                error = %e,
+                last_balance = ?self.usdc_balance,
+                "Failed to fetch USDC balance, keeping the last one"
+            ),
+        }
+    }
+
+    /// USDC balance strategies see (see [`sizing::balance`]): the account
+    /// balance capped by `PMENGINE_BANKROLL`, or a paper one in dry-run.
+    fn bankroll(&self) -> Decimal {
+        let cap = Decimal::from_f64(self.config.bankroll).filter(|b| !b.is_zero());
+        sizing::balance(self.usdc_balance, cap, self.order_manager.is_dry_run(), &self.positions)
+    }
+
+    /// Settle positions in markets that have resolved: redeem the winning
+    /// tokens (or flag them for manual redemption) and close the positions at
+    /// their payout.
+    async fn settle_resolved(&mut self) {
+        let mut held: Vec<(String, Decimal)> = self
+            .positions
+            .active_positions()
+            .iter()
+            .map(|p| (p.token_id.clone(), p.size))
+            .collect();
+        // Synthetic tokens have no Gamma market
+        if let Some(feed) = &self.synthetic {
+            held.retain(|(token_id, _)| !feed.is_synthetic(token_id));
+        }
+
+        let gamma = self.gamma_client.get_or_insert_with(GammaClient::new);
+        let mut resolved = Vec::new();
+        for (token_id, size) in held {
+            match gamma.fetch_market_by_token(&token_id).await {
+                Ok(Some(detail)) => {
+                    if let Some(resolution) = Resolution::of(&detail, &token_id) {
+                        resolved.push((resolution, size));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(token_id = token_id.as_str(), error = %e, "Cannot check market resolution")
+                }
+            }
+        }
+
+        for (resolution, size) in resolved {
+            let wins = resolution.payout > Decimal::ZERO && size > Decimal::ZERO;
+            if wins && !self.redeem(&resolution, size).await {
+                continue;
+            }
+            for fill in settlement::settlement_fills(&resolution, size, &self.attribution) {
+                self.apply_fill(&fill);
+            }
+            tracing::info!(
+                token_id = resolution.token_id.as_str(),
+                question = resolution.question.as_str(),
+                payout = %resolution.payout,
+                size = %size,
+                realized_pnl = ?self.positions.get(&resolution.token_id).map(|p| p.realized_pnl),
+                "Settled position in resolved market"
+            );
+        }
+    }
+
+    /// Redeem winning tokens of a resolved market, or flag them for manual
+    /// redemption when the engine can't send the transaction. Returns false
+    /// if redemption failed (it is retried on the next pass).
+    async fn redeem(&self, resolution: &Resolution, size: Decimal) -> bool {
+        let manual = if self.order_manager.is_dry_run() {
+            Some("dry run")
+        } else if !self.client.can_redeem() {
+            Some("tokens are held by the funder wallet")
+        } else {
+            None
+        };
+        if let Some(reason) = manual {
+            tracing::warn!(
+                token_id = resolution.token_id.as_str(),
+                condition_id = resolution.condition_id.as_str(),
+                neg_risk = resolution.neg_risk,
+                size = %size,
+                reason = reason,
+                "Resolved position needs manual redemption"
+            );
+            return true;
+        }
+
+        let amounts = resolution.neg_risk.then(|| resolution.neg_risk_amounts(size));
+        match self
+            .client
+            .redeem_positions(&resolution.condition_id, amounts.as_deref())
+            .await
+        {
+            Ok(tx_hash) => {
+                tracing::info!(
+                    token_id = resolution.token_id.as_str(),
+                    condition_id = resolution.condition_id.as_str(),
+                    size = %size,
+                    tx_hash = tx_hash.as_str(),
+                    "Redeemed resolved position"
+                );
+                true
+            }
+            Err(e) => {
+                tracing::error!(
+                    token_id = resolution.token_id.as_str(),
+                    condition_id = resolution.condition_id.as_str(),
+                    error = %e,
+                    "Failed to redeem resolved position, retrying next pass"
+                );
+                false
+            }
+        }
+    }
+
+    /// Convert a strategy's NO shares in a negRisk event through the NegRisk
+    /// adapter and book the conversion (see [`crate::conversion`]).
+    async fn convert_positions(&mut self, strategy_id: &str, token_ids: &[String], size: Decimal) {
+        let conversion = Conversion::plan(token_ids, size, &self.market_info).and_then(|conversion| {
+            let held = self.attribution.positions(strategy_id);
+            match conversion
+                .no_tokens
+                .iter()
+                .find(|t| held.and_then(|p| p.get(t)).is_none_or(|p| p.size < size))
+            {
+                Some(token_id) => Err(format!("{} is not held", token_id)),
+                None => Ok(conversion),
+            }
+        });
+        let conversion = match conversion {
+            Ok(conversion) => conversion,
+            Err(reason) => {
+                tracing::warn!(
+                    strategy_id = strategy_id,
+                    tokens = ?token_ids,
+                    size = %size,
+                    reason = reason.as_str(),
+                    "Conversion rejected"
+                );
+                return;
+            }
+        };
+
+        if !self.order_manager.is_dry_run() {
+            if !self.client.can_redeem() {
+                tracing::warn!(
+                    strategy_id = strategy_id,
+                    market_id = conversion.market_id.as_str(),
+                    questions = ?conversion.question_indices,
+                    size = %size,
+                    reason = "tokens are held by the funder wallet",
+                    "NO positions need manual conversion"
+                );
+                return;
+            }
+            let converted = self
+                .client
+                .convert_neg_risk_positions(&conversion.market_id, &conversion.question_indices, size)
+                .await;
+            match converted {
+                Ok(tx_hash) => tracing::info!(tx_hash = tx_hash.as_str(), "Conversion mined"),
+                Err(e) => {
+                    tracing::error!(
+                        strategy_id = strategy_id,
+                        market_id = conversion.market_id.as_str(),
+                        error = %e,
+                        "Failed to convert NO positions"
+                    );
+                    return;
+                }
+            }
+        }
+
+        for fill in conversion.fills(strategy_id) {
+            self.apply_fill(&fill);
+        }
+        tracing::info!(
+            strategy_id = strategy_id,
+            market_id = conversion.market_id.as_str(),
+            questions = ?conversion.question_indices,
+            size = %size,
+            proceeds = %conversion.proceeds(),
+            "Converted NO positions"
+        );
+    }
+
+    /// Fetch open orders and (if the Data API answers) positions from the exchange.
+    async fn fetch_exchange_books(
+        client: &PolymarketClient,
+    ) -> Option<(Vec<OpenOrder>, Option<Vec<AccountPosition>>)> {
+        let orders = match client.open_orders().await {
+            Ok(orders) => orders,
+            Err(e) => {
+                tracing::warn!(error = %e, "Cannot fetch open orders, skipping reconciliation");
+                return None;
+            }
+        };
+        let positions = client
+            .positions()
+            .await
+            .inspect_err(|e| tracing::warn!(error = %e, "Cannot fetch positions, reconciling orders only"))
+            .ok();
+        Some((orders, positions))
+    }
+
+    fn log_reconciliation(result: &Reconciliation, alert_only: bool) {
+        if result.is_clean() {
+            tracing::info!(open_orders = result.open_orders.len(), "Books match the exchange");
+        } else if alert_only {
+            tracing::error!(
+                closed_orders = ?result.closed,
+                adopted_orders = ?result.adopted,
+                corrected_positions = ?result.corrected_positions,
+                "Books differ from the exchange (alert only, not corrected)"
+            );
+        } else {
+            tracing::warn!(
+                closed_orders = ?result.closed,
+                adopted_orders = ?result.adopted,
+                corrected_positions = ?result.corrected_positions,
+                "Books differed from the exchange, reconciled"
+            );
+        }
+    }
+
+    /// Positions and open orders as currently tracked.
+    fn books(&self) -> EngineState {
+        EngineState {
+            positions: self.positions.all_positions().cloned().collect(),
+            open_orders: self
+                .order_manager
+                .active_orders()
+                .into_iter()
+                .map(StoredOrder::from)
+                .collect(),
+        }
+    }
+
+    /// Save positions and open orders if they changed.
+    fn persist_state(&mut self) {
+        if self.state_store.is_none() {
+            return;
+        }
+        let state = self.books();
+        if let Some(Err(e)) = self.state_store.as_mut().map(|store| store.save(&state)) {
+            tracing::error!(error = %e, "Failed to persist trading state");
+        }
+    }
+
+    /// Write the daily report of `date` to the state directory, from the
+    /// fill log and the P&L of what is held now.
+    fn write_daily_report(&self, date: chrono::NaiveDate) {
+        let Some(ref store) = self.state_store else {
+            return;
+        };
+        let fills = match store.fills() {
+            Ok(fills) => fills,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to read fills for the daily report");
+                return;
+            }
+        };
+        let daily = DailyReport::build(date, &fills, &self.attribution.report());
+        daily.log();
+        if let Err(e) = report::save(store.dir(), &daily) {
+            tracing::error!(error = %e, date = %date, "Failed to save daily report");
+        }
+    }
+
     /// Enable market discovery with Gamma API.
     ///
     /// This allows the engine to dynamically discover markets and subscribe
@@ -153,13 +862,33 @@ impl Engine {
 
     /// Build market info map from Gamma markets.
     ///
-    /// IMPORTANT: Only adds the HIGH-CERTAINTY token from each market.
-    /// This prevents the strategy from accidentally buying the wrong outcome
-    /// (e.g., buying "No" at 0.05 instead of "Yes" at 0.95).
+    /// IMPORTANT: Only adds the HIGH-CERTAINTY token from each market for
+    /// strategies in general. This prevents them from accidentally buying
+    /// the wrong outcome (e.g., buying "No" at 0.05 instead of "Yes" at 0.95).
+    /// The other outcomes are added as secondary, seen only by strategies
+    /// that receive all outcomes, when one is registered.
     fn build_market_info(&self, markets: &[GammaMarket]) -> HashMap<String, MarketInfo> {
         let mut info_map = HashMap::new();
+        let all_outcomes = self.strategy_runtime.needs_all_outcomes();
 
         for market in markets {
+            if all_outcomes {
+                let outcomes = market.clob_token_ids.iter().zip(&market.outcomes).enumerate();
+                for (idx, (token_id, outcome)) in outcomes {
+                    let info = MarketInfo::with_liquidity(
+                        market.question.clone(),
+                        outcome.clone(),
+                        market.slug.clone(),
+                        market.end_date,
+                        market.liquidity,
+                    )
+                    .with_category(market.category.clone())
+                    .with_outcomes(idx, market.clob_token_ids.clone())
+                    .secondary();
+                    info_map.insert(token_id.clone(), info);
+                }
+            }
+
             // Only add the highest-certainty outcome token
             // This prevents buying the wrong side of a market
             if let Some(high_cert_idx) = market.highest_certainty_index() {
@@ -173,7 +902,9 @@ impl Engine {
                         market.slug.clone(),
                         market.end_date,
                         market.liquidity,
-                    );
+                    )
+                    .with_category(market.category.clone())
+                    .with_outcomes(high_cert_idx, market.clob_token_ids.clone());
 
                     tracing::debug!(
                         question = market.question.as_str(),
@@ -191,74 +922,132 @@ impl Engine {
         info_map
     }
 
-    /// Maximum hours to expiry for market discovery.
-    /// This is a broader window - strategies will do their own time filtering.
-    const MAX_HOURS_TO_EXPIRY: f64 = 72.0;
+    /// Maximum negRisk events to discover for basket strategies.
+    const MAX_BASKET_EVENTS: usize = 50;
 
-    /// Minimum certainty threshold for fetching markets (broad filter).
-    /// Strategies will apply their own stricter filters.
-    const MIN_CERTAINTY: rust_decimal::Decimal = rust_decimal_macros::dec!(0.90);
+    /// How long fetched market detail is reused before being refetched.
+    const MARKET_DETAIL_MAX_AGE: chrono::Duration = chrono::Duration::minutes(15);
+
+    /// Minimum time between WebSocket resyncs triggered by crossed/locked books.
+    const BOOK_RESYNC_COOLDOWN: Duration = Duration::from_secs(30);
 
     /// Refresh markets from Gamma API.
     ///
-    /// This fetches markets from two sources:
-    /// 1. Events endpoint - for general high-certainty expiring markets
+    /// This fetches markets from the configured sources:
+    /// 1. Events endpoint - for general expiring markets
     /// 2. Series endpoint - for recurring markets (BTC 4h, SPX daily, etc.)
     ///
-    /// NOTE: The engine provides ALL markets to strategies. Strategies do their
-    /// own filtering based on keywords, liquidity, certainty thresholds, etc.
-    async fn refresh_markets(&mut self) -> Result<(), EngineError> {
+    /// When a registered strategy needs baskets, the YES tokens of active negRisk
+    /// events are discovered as well.
+    ///
+    /// Markets are kept when they pass the configured discovery filters and
+    /// the discovery criteria of at least one strategy. Strategies still do
+    /// their own filtering based on keywords, liquidity, certainty, etc.
+    async fn refresh_markets(&mut self, retry_only: bool) -> Result<(), EngineError> {
         let gamma = match &self.gamma_client {
             Some(c) => c,
             None => return Ok(()),
         };
-
-        // Fetch from events endpoint (general markets)
-        let event_markets = gamma
-            .fetch_sure_bet_candidates(Self::MAX_HOURS_TO_EXPIRY, Self::MIN_CERTAINTY)
-            .await
-            .map_err(|e| EngineError::SdkError(format!("Gamma API error (events): {}", e)))?;
+        let now = chrono::Utc::now();
+        let max_hours = self.config.discovery.max_hours_to_expiry;
+
+        // A failed source falls back to its last good markets; on a retry,
+        // healthy sources reuse theirs instead of being fetched again.
+        // Certainty is left to the discovery filters.
+        let event_markets = if !self.config.discovery.fetches(DiscoverySource::Events) {
+            Vec::new()
+        } else if retry_only && !self.discovery.is_failing(DiscoverySource::Events) {
+            self.discovery.cached(DiscoverySource::Events)
+        } else {
+            // Fetch from events endpoint (general markets)
+            let result = gamma.fetch_sure_bet_candidates(max_hours, Decimal::ZERO).await;
+            self.discovery.record(DiscoverySource::Events, result, now)
+        };
 
         tracing::info!(
             count = event_markets.len(),
             "Discovered markets from events endpoint"
         );
 
-        // Fetch from series endpoint (recurring markets like BTC 4h, SPX daily)
-        let recurring_markets = gamma
-            .fetch_recurring_markets(Self::MAX_HOURS_TO_EXPIRY, Self::MIN_CERTAINTY)
-            .await
-            .map_err(|e| EngineError::SdkError(format!("Gamma API error (series): {}", e)))?;
+        let recurring_markets = if !self.config.discovery.fetches(DiscoverySource::Series) {
+            Vec::new()
+        } else if retry_only && !self.discovery.is_failing(DiscoverySource::Series) {
+            self.discovery.cached(DiscoverySource::Series)
+        } else {
+            // Fetch from series endpoint (recurring markets like BTC 4h, SPX daily)
+            let result = gamma.fetch_recurring_markets(max_hours, Decimal::ZERO).await;
+            self.discovery.record(DiscoverySource::Series, result, now)
+        };
 
         tracing::info!(
             count = recurring_markets.len(),
             "Discovered markets from recurring series"
         );
 
+        // Nothing fetched and nothing to fall back on: keep the current market info
+        let sources: Vec<DiscoverySource> = [DiscoverySource::Events, DiscoverySource::Series]
+            .into_iter()
+            .filter(|s| self.config.discovery.fetches(*s))
+            .collect();
+        if event_markets.is_empty()
+            && recurring_markets.is_empty()
+            && !sources.is_empty()
+            && sources.iter().all(|s| self.discovery.is_failing(*s))
+        {
+            return Err(EngineError::SdkError(
+                "Gamma API error: every discovery source failed".to_string(),
+            ));
+        }
+
+        let recurring_slugs: HashSet<String> =
+            recurring_markets.iter().map(|m| m.slug.clone()).collect();
+
         // Merge both sources, deduplicating by slug
-        let mut seen_slugs = std::collections::HashSet::new();
+        let mut seen_slugs = HashSet::new();
         let mut markets = Vec::new();
 
-        for market in event_markets.into_iter().chain(recurring_markets.into_iter()) {
+        for market in event_markets.into_iter().chain(recurring_markets) {
             if seen_slugs.insert(market.slug.clone()) {
                 markets.push(market);
             }
         }
 
+        // Keep what the configuration and at least one strategy want
+        let strategy_filters = self.strategy_runtime.discovery_filters();
+        let discovered = markets.len();
+        markets.retain(|m| self.config.discovery.keep(m, &strategy_filters, now));
+
         tracing::info!(
             count = markets.len(),
+            filtered = discovered - markets.len(),
             "Total unique markets discovered"
         );
 
-        // Subscribe ONLY to high-certainty tokens (matching build_market_info logic)
+        // Subscribe ONLY to high-certainty tokens (matching build_market_info logic),
+        // most promising markets first so warmup fills their books first
         let mut new_tokens_found = false;
-
-        for market in &markets {
+        let cold_start_cap = (self.cold_start && self.config.cold_start_tokens > 0)
+            .then_some(self.config.cold_start_tokens);
+        let mut added = 0;
+        let mut deferred = 0;
+        let mut capped = 0;
+
+        for idx in priority::prioritize(&markets, &recurring_slugs, chrono::Utc::now()) {
+            let market = &markets[idx];
             // Only subscribe to the highest-certainty outcome token
             // This matches build_market_info() and prevents wrong-side subscriptions
             if let Some(high_cert_idx) = market.highest_certainty_index() {
                 if let Some(token_id) = market.clob_token_ids.get(high_cert_idx) {
                     if !self.subscribed_tokens.contains(token_id) {
+                        if cold_start_cap.is_some_and(|cap| added >= cap) {
+                            deferred += 1;
+                            continue;
+                        }
+                        if self.token_capacity() == 0 {
+                            capped += 1;
+                            continue;
+                        }
+                        added += 1;
                         self.market_data.init_book(token_id).await;
                         self.subscribed_tokens.push(token_id.clone());
                         new_tokens_found = true;
@@ -273,8 +1062,151 @@ impl Engine {
             }
         }
 
-        // Update market info with ALL markets (strategies filter themselves)
-        self.market_info = self.build_market_info(&markets);
+        if deferred > 0 {
+            tracing::info!(
+                subscribed = added,
+                deferred = deferred,
+                "Cold start: deferring lower-priority markets to the next refresh"
+            );
+        }
+        self.cold_start = false;
+
+        // Every outcome of negRisk events, for basket strategies
+        let needs_baskets = self.strategy_runtime.needs_baskets()
+            && self.config.discovery.fetches(DiscoverySource::NegRisk);
+        let basket_markets = if !needs_baskets {
+            Vec::new()
+        } else if retry_only && !self.discovery.is_failing(DiscoverySource::NegRisk) {
+            self.discovery.cached(DiscoverySource::NegRisk)
+        } else {
+            let result = gamma.fetch_neg_risk_markets(Self::MAX_BASKET_EVENTS).await;
+            self.discovery.record(DiscoverySource::NegRisk, result, now)
+        };
+
+        // Rebuild market info only when the markets (or what strategies take
+        // from them) changed; otherwise just age the expiries
+        let fingerprint = (
+            gamma::fingerprint(markets.iter().chain(&basket_markets)),
+            needs_baskets,
+            self.strategy_runtime.needs_all_outcomes(),
+        );
+        let changed = self.market_fingerprint != Some(fingerprint);
+        if changed {
+            // Update market info with ALL markets (strategies filter themselves)
+            self.market_info = self.build_market_info(&markets);
+            self.market_fingerprint = Some(fingerprint);
+        } else {
+            for info in self.market_info.values_mut() {
+                info.hours_until_expiry = info
+                    .end_date
+                    .map(|end| end.signed_duration_since(now).num_seconds() as f64 / 3600.0);
+            }
+            tracing::debug!("Discovered markets unchanged, keeping market info");
+        }
+
+        if needs_baskets {
+            for market in &basket_markets {
+                let Some(event_slug) = market.event_slug.clone() else {
+                    continue;
+                };
+                let yes_idx = market.yes_index();
+                let Some(token_id) = market.clob_token_ids.get(yes_idx) else {
+                    continue;
+                };
+
+                if !self.subscribed_tokens.contains(token_id) {
+                    if self.token_capacity() == 0 {
+                        capped += 1;
+                        continue;
+                    }
+                    self.market_data.init_book(token_id).await;
+                    self.subscribed_tokens.push(token_id.clone());
+                    new_tokens_found = true;
+                }
+                if !changed {
+                    continue;
+                }
+
+                let outcome = market.outcomes.get(yes_idx).cloned().unwrap_or_default();
+                let info = MarketInfo::with_liquidity(
+                    market.question.clone(),
+                    outcome,
+                    market.slug.clone(),
+                    market.end_date,
+                    market.liquidity,
+                )
+                .with_event(event_slug, market.neg_risk)
+                .with_outcomes(yes_idx, market.clob_token_ids.clone())
+                .with_category(market.category.clone());
+                let question_index = market.neg_risk_question_index();
+                let market_id = market.neg_risk_market_id.clone();
+                self.market_info.insert(
+                    token_id.clone(),
+                    info.with_neg_risk_question(market_id.clone(), question_index),
+                );
+
+                // The NO sides, for strategies that convert them
+                if self.strategy_runtime.needs_all_outcomes() {
+                    let no_tokens = market.clob_token_ids.iter().enumerate().filter(|(i, _)| *i != yes_idx);
+                    for (idx, token_id) in no_tokens {
+                        let info = self.market_info.remove(token_id).unwrap_or_else(|| {
+                            let outcome = market.outcomes.get(idx).cloned().unwrap_or_default();
+                            MarketInfo::with_liquidity(
+                                market.question.clone(),
+                                outcome,
+                                market.slug.clone(),
+                                market.end_date,
+                                market.liquidity,
+                            )
+                            .with_outcomes(idx, market.clob_token_ids.clone())
+                            .with_category(market.category.clone())
+                            .secondary()
+                        });
+                        let info = info.with_neg_risk_question(market_id.clone(), question_index);
+                        self.market_info.insert(token_id.clone(), info);
+                    }
+                }
+            }
+
+            tracing::info!(
+                count = basket_markets.len(),
+                events = NegRiskEvent::group(&basket_markets).len(),
+                "Discovered negRisk basket markets"
+            );
+        }
+
+        // Subscribe to the other outcomes for strategies that receive all of
+        // them, once the market's primary outcome is (cold start defers both)
+        let mut secondary: Vec<String> = self
+            .market_info
+            .iter()
+            .filter(|(token_id, info)| info.secondary && !self.subscribed_tokens.contains(token_id))
+            .filter(|(_, info)| info.outcome_tokens.iter().any(|t| self.subscribed_tokens.contains(t)))
+            .map(|(token_id, _)| token_id.clone())
+            .collect();
+        let room = self.token_capacity();
+        if secondary.len() > room {
+            capped += secondary.len() - room;
+            secondary.truncate(room);
+        }
+        if !secondary.is_empty() {
+            for token_id in &secondary {
+                self.market_data.init_book(token_id).await;
+                self.subscribed_tokens.push(token_id.clone());
+            }
+            new_tokens_found = true;
+            tracing::info!(count = secondary.len(), "Subscribed to secondary outcome tokens");
+        }
+
+        if capped > 0 {
+            tracing::warn!(
+                max_tokens = self.config.discovery.max_tokens,
+                skipped = capped,
+                "Discovery token cap reached, not subscribing to further tokens"
+            );
+        }
+
+        self.insert_synthetic_markets();
 
         tracing::info!(
             token_count = self.subscribed_tokens.len(),
@@ -294,6 +1226,108 @@ impl Engine {
         Ok(())
     }
 
+    /// How many more tokens discovery may subscribe to under
+    /// `PMENGINE_DISCOVERY_MAX_TOKENS`.
+    fn token_capacity(&self) -> usize {
+        match self.config.discovery.max_tokens {
+            0 => usize::MAX,
+            max => max.saturating_sub(self.subscribed_tokens.len()),
+        }
+    }
+
+    /// Add the synthetic scenario's markets to the market info.
+    fn insert_synthetic_markets(&mut self) {
+        if let Some(feed) = &self.synthetic {
+            self.market_info.extend(feed.market_info());
+        }
+    }
+
+    /// Publish synthetic books whose scripted step has changed.
+    async fn publish_synthetic_books(&mut self) {
+        let Some(feed) = &mut self.synthetic else {
+            return;
+        };
+        for book in feed.poll() {
+            let token_id = book.token_id.clone();
+            let health = self.market_data.set_book(book).await;
+            tracing::debug!(token_id = token_id.as_str(), health = ?health, "Synthetic book step");
+        }
+    }
+
+    /// Fetch full market detail for tokens with open positions or orders.
+    ///
+    /// Detail is cached per token and refetched after `MARKET_DETAIL_MAX_AGE`.
+    /// Traded tokens missing from the discovery payloads get a `MarketInfo`
+    /// built from the detail response.
+    async fn enrich_traded_markets(&mut self) {
+        let mut traded: Vec<String> = self
+            .positions
+            .active_positions()
+            .iter()
+            .map(|p| p.token_id.clone())
+            .chain(self.order_manager.active_orders().iter().map(|o| o.token_id.clone()))
+            .collect();
+        traded.sort_unstable();
+        traded.dedup();
+        // Synthetic tokens have no Gamma detail
+        if let Some(feed) = &self.synthetic {
+            traded.retain(|t| !feed.is_synthetic(t));
+        }
+
+        let now = chrono::Utc::now();
+        let gamma = self.gamma_client.get_or_insert_with(GammaClient::new);
+
+        for token_id in traded {
+            let stale = self
+                .market_details
+                .get(&token_id)
+                .is_none_or(|d| now - d.fetched_at > Self::MARKET_DETAIL_MAX_AGE);
+            if !stale {
+                continue;
+            }
+
+            match gamma.fetch_market_by_token(&token_id).await {
+                Ok(Some(found)) => {
+                    if !self.market_info.contains_key(&token_id) {
+                        let market = &found.market;
+                        let idx = market
+                            .clob_token_ids
+                            .iter()
+                            .position(|t| *t == token_id)
+                            .unwrap_or_default();
+                        let mut info = MarketInfo::with_liquidity(
+                            market.question.clone(),
+                            market.outcomes.get(idx).cloned().unwrap_or_default(),
+                            market.slug.clone(),
+                            market.end_date,
+                            market.liquidity,
+                        )
+                        .with_category(market.category.clone());
+                        if let Some(ref event_slug) = market.event_slug {
+                            info = info.with_event(event_slug.clone(), market.neg_risk);
+                        }
+                        self.market_info.insert(token_id.clone(), info);
+                    }
+                    tracing::debug!(token_id = token_id.as_str(), "Fetched market detail");
+                    self.market_details.insert(token_id, found.detail);
+                }
+                Ok(None) => {
+                    tracing::debug!(token_id = token_id.as_str(), "No Gamma market for traded token");
+                }
+                Err(e) => {
+                    tracing::warn!(token_id = token_id.as_str(), error = %e, "Failed to fetch market detail");
+                }
+            }
+        }
+
+        // Discovery rebuilds market_info, so re-attach cached detail every time
+        for (token_id, detail) in &self.market_details {
+            if let Some(info) = self.market_info.get_mut(token_id) {
+                info.detail = Some(detail.clone());
+            }
+        }
+    }
+
     /// Check if running in dry-run mode.
     pub fn is_dry_run(&self) -> bool {
         self.client.is_dry_run()
@@ -316,34 +1350,27 @@ impl Engine {
         self.register_strategy(Box::new(DummyStrategy::new("dummy", tokens))).await;
     }
 
-    /// Load strategies by name from the auto-generated registry.
+    /// Make a strategy of another crate available to `load_strategies` (and
+    /// the CLI's `run` and `list`) under `name`, replacing a built-in one of
+    /// the same name. Call before loading strategies.
+    pub fn register_factory(name: &str, factory: StrategyFactory) {
+        registry::register(name, factory);
+    }
+
+    /// Load strategies by name from the registry.
     ///
-    /// This method looks up strategies in the registry (generated by pmstrat transpile)
-    /// and registers them with the engine.
+    /// This method looks up strategies among the built-in ones (generated by pmstrat
+    /// transpile) and those added with `register_factory`, and registers them with the engine.
+    /// With the `python` feature, a name ending in `.py` loads a pmstrat strategy file.
     pub fn load_strategies(&mut self, names: &[String]) -> Result<(), EngineError> {
-        use crate::strategies::registry;
-
-        let reg = registry();
-
         for name in names {
-            let info = reg.get(name.as_str()).ok_or_else(|| {
-                let available: Vec<_> = reg.keys().collect();
-                tracing::error!(
-                    strategy = name.as_str(),
-                    available = ?available,
-                    "Unknown strategy"
-                );
-                EngineError::UnknownStrategy(name.clone())
-            })?;
+            let (strategy, requires_market_discovery) = self.build_strategy(name, None)?;
 
             // Enable market discovery if required
-            if info.requires_market_discovery {
+            if requires_market_discovery {
                 self.enable_market_discovery();
             }
 
-            // Create and register the strategy
-            let strategy = (info.factory)();
-
             // Initialize order books for subscriptions
             for token_id in strategy.subscriptions() {
                 if !self.subscribed_tokens.contains(&token_id) {
@@ -352,11 +1379,12 @@ impl Engine {
                     self.subscribed_tokens.push(token_id);
                 }
             }
+            self.strategy_names.insert(strategy.id().to_string(), name.clone());
             self.strategy_runtime.register(strategy);
 
             tracing::info!(
                 strategy = name.as_str(),
-                requires_market_discovery = info.requires_market_discovery,
+                requires_market_discovery = requires_market_discovery,
                 "Loaded strategy"
             );
         }
@@ -364,6 +1392,50 @@ impl Engine {
         Ok(())
     }
 
+    /// Create a strategy by name and configure it with its configured
+    /// parameters, then `overrides` on top.
+    fn build_strategy(
+        &self,
+        name: &str,
+        overrides: Option<&StrategyParams>,
+    ) -> Result<(Box<dyn crate::strategy::Strategy>, bool), EngineError> {
+        let (mut strategy, requires_market_discovery) = Self::create_strategy(name)?;
+        for params in [self.config.strategy_params.get(name), overrides].into_iter().flatten() {
+            if params.is_empty() {
+                continue;
+            }
+            strategy
+                .configure(params)
+                .map_err(|e| EngineError::ConfigError(format!("Strategy {}: {}", name, e)))?;
+            tracing::info!(strategy = name, params = ?params.names(), "Strategy parameters overridden");
+        }
+        Ok((strategy, requires_market_discovery))
+    }
+
+    /// Create a strategy by name, with whether it needs market discovery.
+    fn create_strategy(name: &str) -> Result<(Box<dyn crate::strategy::Strategy>, bool), EngineError> {
+        #[cfg(feature = "python")]
+        if name.ends_with(".py") {
+            let strategy: Box<dyn crate::strategy::Strategy> = Box::new(
+                crate::python::PyStrategy::load(std::path::Path::new(name))
+                    .map_err(|e| EngineError::ConfigError(format!("Strategy {}: {}", name, e)))?,
+            );
+            let requires_market_discovery = strategy.subscriptions().is_empty();
+            return Ok((strategy, requires_market_discovery));
+        }
+
+        let info = registry::get(name).ok_or_else(|| {
+            let available: Vec<_> = registry::all().into_keys().collect();
+            tracing::error!(
+                strategy = name,
+                available = ?available,
+                "Unknown strategy"
+            );
+            EngineError::UnknownStrategy(name.to_string())
+        })?;
+        Ok(((info.factory)(), info.requires_market_discovery))
+    }
+
     /// Get a market data subscriber for external consumers.
     pub fn subscribe_market_data(&self) -> async_broadcast::Receiver<crate::orderbook::MarketEvent> {
         self.market_data.subscribe()
@@ -383,42 +1455,1031 @@ impl Engine {
 
         // Get tick interval
         let tick_duration = Duration::from_millis(self.config.tick_interval_ms);
-        let mut tick_timer = interval(tick_duration);
+        let tick_timer = interval(tick_duration);
 
         // Set up ctrl-c handler
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
         tokio::spawn(async move {
             tokio::signal::ctrl_c().await.ok();
             tracing::info!("Received shutdown signal");
             shutdown_tx.send(()).await.ok();
         });
 
-        let mut last_tick = Instant::now();
-        let mut tick_count: u64 = 0;
-
-        // Market discovery timer (60 seconds)
-        let mut market_refresh_timer = interval(Duration::from_secs(60));
+        // Market discovery timer
+        let mut market_refresh_timer = interval(Duration::from_secs(self.config.discovery.refresh_secs.max(1)));
         // Skip the first immediate tick
         market_refresh_timer.tick().await;
-
+        let mut discovery_retry_timer = interval(discovery::RETRY_INTERVAL);
+        discovery_retry_timer.tick().await;
+        // Periodic reconciliation with the exchange (the startup pass ran in `new`)
+        let mut reconcile_timer = interval(Duration::from_secs(self.config.reconcile_secs.max(1)));
+        reconcile_timer.tick().await;
+        // Settlement of resolved markets (first pass right away: they may have resolved while down)
+        let settle_timer = interval(Duration::from_secs(self.config.settle_secs.max(1)));
+
+        // Our fills and cancellations come from the user channel (nothing to hear in dry-run)
+        let (user_tx, user_events) = mpsc::channel(1000);
+        let user_feed = if self.order_manager.is_dry_run() {
+            None
+        } else {
+            match self.client.user_ws(&self.config.ws_url) {
+                Ok(ws) => Some(user_feed::spawn(ws, user_tx)),
+                Err(e) => {
+                    tracing::error!(error = %e, "Cannot connect to the user channel, fills won't be tracked");
+                    None
+                }
+            }
+        };
+
+        // Record from the first book on
+        let recorder = self.recorder.take().map(|recorder| {
+            tracing::info!(
+                path = ?self.config.record_dir,
+                depth = self.config.record_depth,
+                rotate_mins = self.config.record_rotate_mins,
+                "Recording market data"
+            );
+            recorder.spawn(self.market_data.subscribe())
+        });
+
+        // Signals from outside the engine (nothing to hear without a webhook)
+        let (external_tx, external_events) = mpsc::channel(webhook::EVENT_QUEUE);
+        let webhook = match (self.config.webhook_addr, self.config.webhook_secret.as_deref()) {
+            (Some(addr), Some(secret)) => {
+                let listener = TcpListener::bind(addr)
+                    .await
+                    .map_err(|e| EngineError::ConfigError(format!("Cannot listen on {}: {}", addr, e)))?;
+                tracing::info!(%addr, "Listening for external signals");
+                Some(tokio::spawn(webhook::serve(listener, secret.into(), external_tx)))
+            }
+            _ => None,
+        };
+
         // Do initial market discovery if enabled
         if self.market_discovery_enabled {
-            if let Err(e) = self.refresh_markets().await {
+            if let Err(e) = self.refresh_markets(false).await {
                 tracing::warn!(error = %e, "Initial market discovery failed");
             }
             // Clear the reconnect flag - we'll connect WebSocket in the main loop
             self.ws_needs_reconnect = false;
         }
 
+        self.insert_synthetic_markets();
+
+        if self.config.warm_start_minutes > 0 {
+            self.warm_start_strategies().await;
+        }
+
+        self.refresh_balance().await;
+
+        let mut state = LoopState {
+            tick_timer,
+            market_refresh_timer,
+            discovery_retry_timer,
+            reconcile_timer,
+            settle_timer,
+            user_events,
+            external_events,
+            ws_backoff: Backoff::default(),
+            shutdown_rx,
+            last_tick: Instant::now(),
+            tick_count: 0,
+            last_book_resync: None,
+        };
+
+        // Kill switch: cancel (and optionally flatten) out-of-band when tripped
+        let kill_switch = kill_switch::spawn(
+            self.kill_switch.clone(),
+            KillSwitchConfig { file: self.config.kill_file.clone(), flatten: self.config.kill_flatten },
+            self.client.clone(),
+        );
+
+        // Watchdog: detect a stuck loop, cancel orders out-of-band, optionally restart
+        let restart = Arc::new(Notify::new());
+        let watchdog = (self.config.watchdog_secs > 0).then(|| {
+            watchdog::spawn(
+                self.heartbeat.clone(),
+                WatchdogConfig {
+                    // Never shorter than a few ticks, or slow tick intervals look like stalls
+                    window: Duration::from_secs(self.config.watchdog_secs).max(tick_duration * 3),
+                    restart: self.config.watchdog_restart,
+                },
+                self.client.clone(),
+                restart.clone(),
+            )
+        });
+
+        // A restart drops the stuck event loop future (cancelling whatever it was
+        // awaiting) and re-enters it with the same timers and counters
+        let result = loop {
+            tokio::select! {
+                result = self.event_loop(&mut state, max_ticks) => break result,
+                _ = restart.notified() => {
+                    self.heartbeat.restarted();
+                    self.recover_after_stall().await;
+                }
+            }
+        };
+
+        if let Some(handle) = watchdog {
+            handle.abort();
+        }
+        kill_switch.abort();
+        if let Some(handle) = user_feed {
+            handle.abort();
+        }
+        if let Some(handle) = webhook {
+            handle.abort();
+        }
+        if let Some(handle) = recorder {
+            let stats = handle.finish().await;
+            tracing::info!(
+                books = stats.books,
+                trades = stats.trades,
+                dropped = stats.dropped,
+                "Market data recording stopped"
+            );
+        }
+
+        result
+    }
+
+    /// Feed strategies the recent price history of every subscribed token.
+    ///
+    /// Failures only cost the warm start: strategies then warm up on live data.
+    async fn warm_start_strategies(&mut self) {
+        let history = HistoryClient::new(&self.config.clob_url);
+        let window = chrono::Duration::minutes(self.config.warm_start_minutes as i64);
+        let started = Instant::now();
+
+        let fetches = futures::stream::iter(self.subscribed_tokens.clone())
+            .map(|token_id| {
+                let history = &history;
+                async move {
+                    let result = history.fetch(&token_id, window).await;
+                    (token_id, result)
+                }
+            })
+            .buffer_unordered(8)
+            .collect::<Vec<_>>()
+            .await;
+
+        let (mut warmed, mut failed, mut points) = (0usize, 0usize, 0usize);
+        for (token_id, result) in fetches {
+            match result {
+                Ok(prices) if !prices.is_empty() => {
+                    let category = self.market_info.get(&token_id).and_then(|m| m.category.as_deref());
+                    self.strategy_runtime.warm_start(&token_id, category, &prices);
+                    warmed += 1;
+                    points += prices.len();
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    failed += 1;
+                    tracing::debug!(token_id = token_id.as_str(), error = %e, "Price history fetch failed");
+                }
+            }
+        }
+
+        tracing::info!(
+            tokens = warmed,
+            failed = failed,
+            points = points,
+            minutes = self.config.warm_start_minutes,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Strategies warm-started from price history"
+        );
+    }
+
+    /// Reconcile local state after the watchdog restarted the event loop.
+    ///
+    /// The watchdog already cancelled all orders on the exchange; release their
+    /// exposure and mark them cancelled locally.
+    async fn recover_after_stall(&mut self) {
+        let stale = self.release_active_orders();
+        if let Err(e) = self.order_manager.cancel_all_orders().await {
+            tracing::warn!(error = %e, "Failed to reconcile orders after stall");
+        }
+        self.persist_state();
+
+        tracing::warn!(
+            stale_orders = stale,
+            "Event loop restarted by watchdog"
+        );
+    }
+
+    /// Stop trading once the kill switch has tripped. Its task already
+    /// cancelled every order on the exchange; stop the algos, release the
+    /// orders' exposure and cancel them locally (including any placed since).
+    async fn stop_trading(&mut self) {
+        if self.killed {
+            return;
+        }
+        self.killed = true;
+        let parents: Vec<String> = self.order_manager.algo_orders().iter().map(|p| p.id.clone()).collect();
+        for parent_id in parents {
+            self.order_manager.stop_algo(&parent_id, "kill switch");
+        }
+        let orders = self.release_active_orders();
+        if let Err(e) = self.order_manager.cancel_all_orders().await {
+            tracing::error!(error = %e, "Failed to cancel orders after kill switch");
+        }
+        self.persist_state();
+
+        tracing::error!(
+            reason = self.kill_switch.reason().unwrap_or_default(),
+            orders = orders,
+            "Kill switch on, trading stopped until restart"
+        );
+    }
+
+    /// Release the exposure of every active order (before cancelling them
+    /// all). Returns how many there were.
+    fn release_active_orders(&mut self) -> usize {
+        let active: Vec<String> = self
+            .order_manager
+            .active_orders()
+            .iter()
+            .map(|o| o.id.clone())
+            .collect();
+        for order_id in &active {
+            self.risk_manager.order_closed(order_id);
+        }
+        active.len()
+    }
+
+    /// Cancel a token's open orders, closing them in the risk manager.
+    async fn cancel_token_orders(&mut self, token_id: &str) -> Result<usize, OrderError> {
+        let order_ids: Vec<String> = self
+            .order_manager
+            .active_orders_for_token(token_id)
+            .iter()
+            .map(|o| o.id.clone())
+            .collect();
+        let cancelled = self.order_manager.cancel_all(token_id).await?;
+        for order_id in &order_ids {
+            self.risk_manager.order_closed(order_id);
+        }
+        Ok(cancelled)
+    }
+
+    /// Track which tokens' books are stale and cancel their resting orders
+    /// (retried every tick until none are left). Signals for stale tokens are
+    /// dropped by `execute_signals` until their book updates again.
+    async fn guard_stale_books(&mut self) {
+        if self.config.book_stale_secs == 0 {
+            return;
+        }
+        let max_age = Duration::from_secs(self.config.book_stale_secs);
+        let stale: HashSet<String> = self.market_data.stale_books(max_age).await.into_iter().collect();
+
+        for token_id in self.stale_books.difference(&stale) {
+            tracing::info!(token_id = token_id.as_str(), "Order book updating again, trading resumed");
+        }
+        for token_id in stale.difference(&self.stale_books) {
+            tracing::warn!(
+                token_id = token_id.as_str(),
+                stale_secs = self.config.book_stale_secs,
+                "Order book stale, suppressing signals"
+            );
+        }
+
+        for token_id in &stale {
+            if self.order_manager.active_orders_for_token(token_id).is_empty() {
+                continue;
+            }
+            match self.cancel_token_orders(token_id).await {
+                Ok(n) => tracing::warn!(
+                    token_id = token_id.as_str(),
+                    cancelled_orders = n,
+                    "Cancelled orders on stale order book"
+                ),
+                Err(e) => tracing::error!(
+                    token_id = token_id.as_str(),
+                    error = %e,
+                    "Failed to cancel orders on stale order book"
+                ),
+            }
+        }
+        self.stale_books = stale;
+    }
+
+    /// Cancel orders resting longer than `PMENGINE_MAX_ORDER_AGE_SECS`.
+    async fn cancel_expired_orders(&mut self) {
+        if self.config.max_order_age_secs == 0 {
+            return;
+        }
+        let max_age = Duration::from_secs(self.config.max_order_age_secs);
+        let expired: Vec<String> = self
+            .order_manager
+            .expired_orders(max_age, chrono::Utc::now())
+            .into_iter()
+            .map(|o| o.id.clone())
+            .collect();
+        if expired.is_empty() {
+            return;
+        }
+        tracing::info!(
+            orders = expired.len(),
+            max_age_secs = self.config.max_order_age_secs,
+            "Cancelling orders past their maximum age"
+        );
+        self.cancel_orders(&expired).await;
+    }
+
+    async fn cancel_quarantined(&mut self, quarantined: &Quarantined) {
+        let parents: Vec<String> = self
+            .order_manager
+            .algo_orders()
+            .into_iter()
+            .filter(|p| p.strategy_id.as_deref() == Some(quarantined.strategy_id.as_str()))
+            .map(|p| p.id.clone())
+            .collect();
+        for parent_id in parents {
+            let resting = self.order_manager.stop_algo(&parent_id, "strategy quarantined");
+            self.cancel_orders(&resting).await;
+        }
+
+        let mut cancelled = 0;
+        for token_id in &quarantined.tokens {
+            match self.cancel_token_orders(token_id).await {
+                Ok(n) => cancelled += n,
+                Err(e) => tracing::error!(
+                    strategy_id = quarantined.strategy_id.as_str(),
+                    token_id = token_id.as_str(),
+                    error = %e,
+                    "Failed to cancel orders of quarantined strategy"
+                ),
+            }
+        }
+
+        tracing::error!(
+            strategy_id = quarantined.strategy_id.as_str(),
+            tokens = quarantined.tokens.len(),
+            cancelled_orders = cancelled,
+            "Quarantined strategy's orders cancelled"
+        );
+    }
+
+    /// Sample deployed exposure per token for the utilization report.
+    fn sample_utilization(&mut self) {
+        let owners = self.strategy_runtime.token_owners();
+        let mut by_token = self.risk_manager.reserved_by_token();
+        for position in self.positions.active_positions() {
+            *by_token.entry(position.token_id.clone()).or_default() += position.notional();
+        }
+
+        let exposures: Vec<TokenExposure> = by_token
+            .into_iter()
+            .map(|(token_id, notional)| TokenExposure {
+                strategy: owners.get(&token_id).cloned(),
+                category: self.market_info.get(&token_id).and_then(|m| m.category.clone()),
+                notional,
+            })
+            .collect();
+        self.utilization.record(&exposures);
+    }
+
+    /// Market data WebSocket connection counters.
+    pub fn feed_metrics(&self) -> FeedMetrics {
+        self.feed_metrics
+    }
+
+    /// Realized and unrealized P&L per strategy.
+    pub fn strategy_pnl(&self) -> Vec<StrategyPnl> {
+        self.attribution.report()
+    }
+
+    /// Count a dropped market data connection and pick when to reconnect.
+    fn schedule_reconnect(&mut self, backoff: &mut Backoff, reason: Disconnect) -> Instant {
+        self.feed_metrics.record_disconnect(reason);
+        let delay = backoff.next_delay();
+        tracing::warn!(
+            reason = %reason,
+            attempt = backoff.attempt(),
+            delay_ms = delay.as_millis() as u64,
+            tokens = self.subscribed_tokens.len(),
+            "Market data connection lost, reconnecting"
+        );
+        Instant::now() + delay
+    }
+
+    /// Exposure utilization since startup.
+    pub fn utilization_report(&self) -> UtilizationReport {
+        self.utilization.report()
+    }
+
+    /// A token's current market, for the risk manager's sanity checks.
+    async fn market_reference(&self, token_id: &str) -> MarketReference {
+        let last_trade = self.market_data.last_trade(token_id).await.map(|t| t.price);
+        match self.market_data.get_book(token_id).await {
+            Some(book) => MarketReference {
+                mid: book.mid_price(),
+                last_trade,
+                bid_depth: book.bid_depth(),
+                ask_depth: book.ask_depth(),
+            },
+            None => MarketReference { last_trade, ..Default::default() },
+        }
+    }
+
+    /// Append a risk check to the decision journal (if enabled).
+    fn journal_risk_decision(&mut self, signal: &Signal, inputs: CheckInputs, result: &RiskCheckResult) {
+        let Some(ref mut journal) = self.risk_journal else {
+            return;
+        };
+        let Some(decision) = RiskDecision::new(chrono::Utc::now(), signal, inputs, result) else {
+            return;
+        };
+        if let Err(e) = journal.record(&decision) {
+            tracing::warn!(error = %e, "Failed to write risk journal");
+        }
+    }
+
+    /// Append an event to the trade journal (if enabled).
+    fn journal_trade(&mut self, entry: Option<JournalEntry>) {
+        let (Some(journal), Some(entry)) = (&mut self.trade_journal, entry) else {
+            return;
+        };
+        if let Err(e) = journal.record(&entry) {
+            tracing::warn!(error = %e, "Failed to write trade journal");
+        }
+    }
+
+    /// Carry of positions held longer than a day.
+    pub fn carry_report(&self) -> CarryReport {
+        let owners = self.strategy_runtime.token_owners();
+        let min_apy = Decimal::from_f64_retain(self.config.min_carry_apy).unwrap_or_default();
+        let now = chrono::Utc::now();
+        let positions = self
+            .positions
+            .active_positions()
+            .into_iter()
+            .filter_map(|position| {
+                let end_date = self.market_info.get(&position.token_id).and_then(|m| m.end_date);
+                let strategy = owners.get(&position.token_id).cloned();
+                PositionCarry::analyze(position, strategy, end_date, min_apy, now)
+            })
+            .collect();
+        CarryReport::new(positions)
+    }
+
+    /// Access the event loop heartbeat.
+    pub fn heartbeat(&self) -> Arc<Heartbeat> {
+        self.heartbeat.clone()
+    }
+
+    /// Handle for controlling the engine while it runs.
+    pub fn control(&self) -> EngineControl {
+        let journal = self.config.trade_journal.clone();
+        EngineControl::new(self.control_sender.clone(), self.kill_switch.clone(), journal)
+    }
+
+    /// Allow only orders that shrink a position (or lift that restriction).
+    pub fn set_reduce_only(&mut self, enabled: bool, reason: &str) {
+        self.risk_manager.set_reduce_only(enabled, reason);
+    }
+
+    /// Load a strategy by name into the running engine, configured with its
+    /// configured parameters and then `params`. Its new tokens are subscribed
+    /// to on the next WebSocket reconnect.
+    pub async fn load_strategy(&mut self, name: &str, params: &StrategyParams) -> Result<(), EngineError> {
+        let (strategy, requires_market_discovery) = self.build_strategy(name, Some(params))?;
+        if self.strategy_runtime.contains(strategy.id()) {
+            return Err(EngineError::ConfigError(format!("Strategy {} is already loaded", strategy.id())));
+        }
+        if requires_market_discovery && !self.market_discovery_enabled {
+            self.enable_market_discovery();
+        }
+
+        let subscribed = self.subscribed_tokens.len();
+        self.strategy_names.insert(strategy.id().to_string(), name.to_string());
+        self.register_strategy(strategy).await;
+        if self.subscribed_tokens.len() > subscribed {
+            self.ws_needs_reconnect = true;
+        }
+        tracing::info!(strategy = name, "Strategy loaded at runtime");
+        Ok(())
+    }
+
+    /// Cancel a strategy's orders and remove it, unsubscribing from the
+    /// tokens only it needed. Other strategies keep trading.
+    pub async fn unload_strategy(&mut self, strategy_id: &str) -> Result<(), EngineError> {
+        self.retire_strategy(strategy_id, &[]).await
+    }
+
+    /// Replace a strategy with a fresh instance of the strategy it was loaded
+    /// as, configured with its configured parameters and then `params`. A
+    /// strategy that fails to configure leaves the running one in place.
+    pub async fn restart_strategy(
+        &mut self,
+        strategy_id: &str,
+        params: &StrategyParams,
+    ) -> Result<(), EngineError> {
+        let name = self
+            .strategy_names
+            .get(strategy_id)
+            .cloned()
+            .ok_or_else(|| EngineError::UnknownStrategy(strategy_id.to_string()))?;
+        let (strategy, _) = self.build_strategy(&name, Some(params))?;
+
+        self.retire_strategy(strategy_id, &strategy.subscriptions()).await?;
+        self.strategy_names.insert(strategy.id().to_string(), name.clone());
+        self.register_strategy(strategy).await;
+        tracing::info!(strategy = name.as_str(), "Strategy restarted");
+        Ok(())
+    }
+
+    /// Unregister a strategy, cancel its orders and drop the subscriptions
+    /// no one else needs: not another strategy, market discovery, an open
+    /// position or `keep`.
+    async fn retire_strategy(&mut self, strategy_id: &str, keep: &[String]) -> Result<(), EngineError> {
+        let unneeded = self
+            .strategy_runtime
+            .unregister(strategy_id)
+            .ok_or_else(|| EngineError::UnknownStrategy(strategy_id.to_string()))?;
+        self.strategy_names.remove(strategy_id);
+        let cancelled = self.cancel_strategy_orders(strategy_id, "strategy unloaded").await;
+
+        let dropped: Vec<String> = unneeded
+            .into_iter()
+            .filter(|t| !keep.contains(t) && !self.market_info.contains_key(t))
+            .filter(|t| self.positions.get(t).is_none_or(|p| p.size.is_zero()))
+            .collect();
+        for token_id in &dropped {
+            self.market_data.remove_book(token_id).await;
+        }
+        if !dropped.is_empty() {
+            self.subscribed_tokens.retain(|t| !dropped.contains(t));
+            self.ws_needs_reconnect = true;
+        }
+
+        tracing::info!(
+            strategy_id = strategy_id,
+            cancelled_orders = cancelled,
+            unsubscribed = dropped.len(),
+            "Strategy unloaded"
+        );
+        Ok(())
+    }
+
+    /// Stop a strategy's execution algos and cancel its resting orders.
+    /// Returns how many orders were cancelled.
+    async fn cancel_strategy_orders(&mut self, strategy_id: &str, reason: &str) -> usize {
+        let parents: Vec<String> = self
+            .order_manager
+            .algo_orders()
+            .into_iter()
+            .filter(|p| p.strategy_id.as_deref() == Some(strategy_id))
+            .map(|p| p.id.clone())
+            .collect();
+        for parent_id in parents {
+            let resting = self.order_manager.stop_algo(&parent_id, reason);
+            self.cancel_orders(&resting).await;
+        }
+
+        let orders: Vec<String> = self
+            .order_manager
+            .active_orders()
+            .into_iter()
+            .filter(|o| o.strategy_id.as_deref() == Some(strategy_id))
+            .map(|o| o.id.clone())
+            .collect();
+        self.cancel_orders(&orders).await;
+        orders.len()
+    }
+
+    async fn apply_control(&mut self, command: ControlCommand) {
+        let result = match command {
+            ControlCommand::ReduceOnly { enabled, reason } => {
+                self.set_reduce_only(enabled, &reason);
+                Ok(())
+            }
+            ControlCommand::LoadStrategy { name, params } => self.load_strategy(&name, &params).await,
+            ControlCommand::UnloadStrategy { strategy_id } => self.unload_strategy(&strategy_id).await,
+            ControlCommand::RestartStrategy { strategy_id, params } => {
+                self.restart_strategy(&strategy_id, &params).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Control command failed");
+        }
+    }
+
+    /// Run strategy signals through the risk checks and place the approved
+    /// orders (order latency is measured from `signals_at`). Returns whether
+    /// a strategy requested shutdown.
+    async fn execute_signals(
+        &mut self,
+        signals: Vec<StrategySignal>,
+        signals_at: std::time::Instant,
+    ) -> bool {
+        let mut shutdown_requested = false;
+        for StrategySignal { strategy_id, signal } in signals {
+            // Tripped while the strategies ran
+            if self.kill_switch.is_active() {
+                break;
+            }
+            if matches!(signal, Signal::Hold) {
+                continue;
+            }
+
+            if let Signal::ReduceOnly { enabled, reason } = &signal {
+                let reason = format!("{}: {}", strategy_id, reason);
+                self.risk_manager.set_reduce_only(*enabled, &reason);
+                continue;
+            }
+
+            if let Signal::Exit { token_id, stop_loss, take_profit, time_stop } = signal {
+                let rule = ExitRule { stop_loss, take_profit, time_stop };
+                tracing::info!(
+                    strategy_id = strategy_id.as_str(),
+                    token_id = token_id.as_str(),
+                    rule = ?rule,
+                    "Exit rule set"
+                );
+                self.exits.set(&strategy_id, &token_id, rule);
+                continue;
+            }
+
+            // Handle shutdown signal from strategies
+            if let Signal::Shutdown { reason } = &signal {
+                tracing::info!(reason = reason.as_str(), "Strategy requested shutdown");
+                shutdown_requested = true;
+                continue;
+            }
+
+            if let Signal::Replace { token_id, orders } = signal {
+                self.replace_orders(&strategy_id, &token_id, orders, signals_at).await;
+                continue;
+            }
+            if let Signal::Paired { legs } = signal {
+                self.execute_paired(&strategy_id, legs, signals_at).await;
+                continue;
+            }
+            if let Signal::Convert { token_ids, size } = signal {
+                self.convert_positions(&strategy_id, &token_ids, size).await;
+                continue;
+            }
+            if let Signal::Algo { order, algo } = signal {
+                // Children are placed (and risk-checked) by `work_algos`
+                if self.order_manager.start_algo(Some(&strategy_id), *order, algo).is_none() {
+                    tracing::warn!(strategy_id = strategy_id.as_str(), "Algo order is not a BUY or SELL");
+                }
+                continue;
+            }
+            self.execute_order(&strategy_id, signal, true, signals_at).await;
+        }
+        shutdown_requested
+    }
+
+    /// Sell the positions whose exit rule triggers at the current books, or
+    /// whose market is about to end (or just warn about them, in alert-only
+    /// mode). Exits are placed for the strategy that attached the rule, or
+    /// for the largest holder of the position otherwise.
+    async fn execute_exits(&mut self, books: &HashMap<String, Arc<OrderBook>>) {
+        let exits = self.exits.check(&self.positions, books, &self.market_info, chrono::Utc::now());
+        for exit in exits {
+            if exit.alert_only {
+                tracing::warn!(
+                    token_id = exit.token_id.as_str(),
+                    size = %exit.size,
+                    end_date = ?self.market_info.get(&exit.token_id).and_then(|m| m.end_date),
+                    "Position still held near market expiry"
+                );
+                continue;
+            }
+            let strategy_id = exit.strategy_id.clone().unwrap_or_else(|| {
+                let holders = self.attribution.holders(&exit.token_id);
+                let largest = holders.into_iter().max_by_key(|(_, size)| *size);
+                largest.map_or(UNATTRIBUTED, |(id, _)| id).to_string()
+            });
+            tracing::warn!(
+                strategy_id = strategy_id.as_str(),
+                token_id = exit.token_id.as_str(),
+                trigger = %exit.trigger,
+                size = %exit.size,
+                price = %exit.price,
+                "Exit rule triggered"
+            );
+            self.execute_order(&strategy_id, exit.signal(), false, std::time::Instant::now()).await;
+        }
+    }
+
+    /// Replace a strategy's resting orders on a token: orders no longer
+    /// wanted are cancelled, and the missing ones placed like any other order
+    /// (without laddering). Nothing is placed if the cancel fails.
+    async fn replace_orders(
+        &mut self,
+        strategy_id: &str,
+        token_id: &str,
+        orders: Vec<Signal>,
+        signals_at: std::time::Instant,
+    ) {
+        if self.stale_books.contains(token_id) {
+            tracing::debug!(
+                strategy_id = strategy_id,
+                token_id = token_id,
+                "Dropping replace for stale order book"
+            );
+            return;
+        }
+        let plan = match self.order_manager.replace(Some(strategy_id), token_id, &orders).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                tracing::error!(strategy_id = strategy_id, token_id = token_id, error = %e, "Replace failed");
+                return;
+            }
+        };
+        for order_id in &plan.cancel {
+            self.risk_manager.order_closed(order_id);
+        }
+        for order in plan.place {
+            self.execute_order(strategy_id, order, false, signals_at).await;
+        }
+    }
+
+    /// Risk-check an order signal and place it (split across the ladder's
+    /// price levels when `ladder` is set and laddering is on). Returns the
+    /// IDs of the orders placed.
+    async fn execute_order(
+        &mut self,
+        strategy_id: &str,
+        signal: Signal,
+        ladder: bool,
+        signals_at: std::time::Instant,
+    ) -> Vec<String> {
+        let mut placed = Vec::new();
+        if let Some((token_id, ..)) = signal.order_terms() {
+            if self.stale_books.contains(token_id) {
+                tracing::debug!(
+                    strategy_id = strategy_id,
+                    token_id = token_id,
+                    "Dropping signal for stale order book"
+                );
+                return placed;
+            }
+        }
+
+        self.journal_trade(JournalEntry::signal(strategy_id, &signal));
+        if let Some((token_id, ..)) = signal.order_terms() {
+            let reference = self.market_reference(token_id).await;
+            self.risk_manager.set_market_reference(token_id, reference);
+        }
+        let inputs = self.risk_manager.check_inputs(&signal, &self.positions);
+        let checked = self.risk_manager.check_signal(&signal, &self.positions);
+        self.journal_risk_decision(&signal, inputs, &checked);
+        let checked = self.risk_manager.check_strategy_budget(
+            strategy_id,
+            checked,
+            self.attribution.positions(strategy_id),
+        );
+        self.journal_trade(JournalEntry::risk(strategy_id, &signal, &checked));
+
+        match checked {
+            RiskCheckResult::Approved(ref s) | RiskCheckResult::Reduced(ref s, _) => {
+                if let RiskCheckResult::Reduced(_, ref reason) = checked {
+                    tracing::warn!(reason = reason.as_str(), "Signal reduced by risk manager");
+                }
+
+                // Split the quote across price levels if laddering is on
+                let orders = match self.config.order_ladder {
+                    Some(ref order_ladder) if ladder => order_ladder.split(s),
+                    _ => vec![s.clone()],
+                };
+                for order in orders {
+                    // Extract order details for tracking
+                    let Some((token_id, _, price, size)) = order.order_terms() else {
+                        continue;
+                    };
+                    let token_id = token_id.to_string();
+
+                    let notional = price * size;
+
+                    // CRITICAL: Reserve exposure BEFORE placing order
+                    // This prevents race conditions where multiple signals
+                    // pass the risk check in the same tick
+                    let reservation_id = match self.risk_manager.reserve_order_for(
+                        strategy_id,
+                        &order,
+                        &self.positions,
+                    ) {
+                        Some(id) => id,
+                        None => {
+                            tracing::warn!(
+                                token_id = token_id.as_str(),
+                                notional = %notional,
+                                "Skipping order: exposure reservation rejected"
+                            );
+                            continue;
+                        }
+                    };
+
+                    let placed_order = order.clone();
+                    match self.order_manager.execute_since(order, signals_at).await {
+                        Ok(Some(order_id)) => {
+                            self.journal_trade(JournalEntry::order(strategy_id, &order_id, &placed_order));
+                            // Confirm the reservation as an open order
+                            self.order_manager.attribute(&order_id, strategy_id);
+                            self.risk_manager.confirm_reservation(&reservation_id, &order_id);
+                            placed.push(order_id);
+                        }
+                        Ok(None) => {
+                            // Order was not placed (e.g., dry-run mode)
+                            // Release the reservation
+                            self.risk_manager.release_reservation(&reservation_id);
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Order execution failed");
+                            // Release the reservation on failure
+                            self.risk_manager.release_reservation(&reservation_id);
+                        }
+                    }
+                }
+            }
+            RiskCheckResult::Rejected(reason) => {
+                tracing::warn!(reason = reason, "Signal rejected by risk manager");
+            }
+        }
+        placed
+    }
+
+    /// Risk-check the legs of a paired order and place them together, at the
+    /// size every leg is allowed. Nothing is placed if a leg is rejected, its
+    /// book is stale or its exposure can't be reserved.
+    async fn execute_paired(&mut self, strategy_id: &str, legs: Vec<Signal>, signals_at: std::time::Instant) {
+        let mut size: Option<Decimal> = None;
+        for leg in &legs {
+            let limit = !matches!(leg, Signal::Market { .. });
+            let Some((token_id, ..)) = leg.order_terms().filter(|_| limit) else {
+                tracing::warn!(strategy_id = strategy_id, "Paired order leg is not a BUY or SELL");
+                return;
+            };
+            if self.stale_books.contains(token_id) {
+                tracing::debug!(
+                    strategy_id = strategy_id,
+                    token_id = token_id,
+                    "Dropping paired order for stale order book"
+                );
+                return;
+            }
+
+            self.journal_trade(JournalEntry::signal(strategy_id, leg));
+            let reference = self.market_reference(token_id).await;
+            self.risk_manager.set_market_reference(token_id, reference);
+            let inputs = self.risk_manager.check_inputs(leg, &self.positions);
+            let checked = self.risk_manager.check_signal(leg, &self.positions);
+            self.journal_risk_decision(leg, inputs, &checked);
+            let checked = self.risk_manager.check_strategy_budget(
+                strategy_id,
+                checked,
+                self.attribution.positions(strategy_id),
+            );
+            self.journal_trade(JournalEntry::risk(strategy_id, leg, &checked));
+
+            match checked {
+                RiskCheckResult::Approved(ref s) | RiskCheckResult::Reduced(ref s, _) => {
+                    let allowed = s.order_terms().map(|(.., size)| size).unwrap_or_default();
+                    size = Some(size.map_or(allowed, |size| size.min(allowed)));
+                }
+                RiskCheckResult::Rejected(reason) => {
+                    tracing::warn!(reason = reason, "Paired order rejected by risk manager");
+                    return;
+                }
+            }
+        }
+        let Some(size) = size else {
+            return;
+        };
+        let legs: Vec<Signal> = legs.iter().map(|leg| leg.with_size(size)).collect();
+
+        let mut reservations = Vec::with_capacity(legs.len());
+        for leg in &legs {
+            match self.risk_manager.reserve_order_for(strategy_id, leg, &self.positions) {
+                Some(id) => reservations.push(id),
+                None => {
+                    tracing::warn!(
+                        strategy_id = strategy_id,
+                        "Skipping paired order: exposure reservation rejected"
+                    );
+                    for id in &reservations {
+                        self.risk_manager.release_reservation(id);
+                    }
+                    return;
+                }
+            }
+        }
+
+        match self.order_manager.execute_paired(&legs, signals_at).await {
+            Ok(order_ids) => {
+                for ((order_id, reservation_id), leg) in order_ids.iter().zip(&reservations).zip(&legs) {
+                    self.journal_trade(JournalEntry::order(strategy_id, order_id, leg));
+                    self.order_manager.attribute(order_id, strategy_id);
+                    self.risk_manager.confirm_reservation(reservation_id, order_id);
+                }
+            }
+            Err(e) => {
+                tracing::error!(strategy_id = strategy_id, error = %e, "Paired order failed");
+                // Legs whose cancel failed may still rest on the book: keep their exposure
+                let live = e.live_legs();
+                for (leg, (reservation_id, signal)) in reservations.iter().zip(&legs).enumerate() {
+                    match live.iter().find(|(live_leg, _)| *live_leg == leg) {
+                        Some((_, order_id)) => {
+                            self.journal_trade(JournalEntry::order(strategy_id, order_id, signal));
+                            self.order_manager.attribute(order_id, strategy_id);
+                            self.risk_manager.confirm_reservation(reservation_id, order_id);
+                        }
+                        None => self.risk_manager.release_reservation(reservation_id),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Work the parent orders of execution algos: cancel the children a step
+    /// replaces, then place the next child like any other order of the
+    /// strategy. A parent whose child can't be placed (rejected by risk, or
+    /// its book went stale) is stopped.
+    async fn work_algos(&mut self) {
+        let now = std::time::Instant::now();
+        'steps: for (parent_id, step) in self.order_manager.algo_steps(now) {
+            for order_id in &step.cancel {
+                if let Err(e) = self.order_manager.cancel_order(order_id).await {
+                    // Placing the next slice now could double the parent's exposure
+                    tracing::error!(
+                        parent_id = parent_id.as_str(),
+                        order_id = order_id.as_str(),
+                        error = %e,
+                        "Failed to cancel algo child"
+                    );
+                    continue 'steps;
+                }
+                self.risk_manager.order_closed(order_id);
+            }
+            let Some(child) = step.place else {
+                continue;
+            };
+
+            let strategy_id = self
+                .order_manager
+                .algo_order(&parent_id)
+                .and_then(|p| p.strategy_id.clone())
+                .unwrap_or_else(|| UNATTRIBUTED.to_string());
+            let placed = self.execute_order(&strategy_id, child, false, now).await;
+            if placed.is_empty() {
+                let resting = self.order_manager.stop_algo(&parent_id, "child order not placed");
+                self.cancel_orders(&resting).await;
+                continue;
+            }
+            for order_id in &placed {
+                self.order_manager.add_algo_child(&parent_id, order_id);
+            }
+        }
+    }
+
+    /// Cancel orders by ID, releasing their exposure.
+    async fn cancel_orders(&mut self, order_ids: &[String]) {
+        for order_id in order_ids {
+            match self.order_manager.cancel_order(order_id).await {
+                Ok(()) => self.risk_manager.order_closed(order_id),
+                Err(e) => tracing::error!(order_id = order_id.as_str(), error = %e, "Failed to cancel order"),
+            }
+        }
+    }
+
+    /// The event loop proper: connect WebSocket, then process events until shutdown.
+    async fn event_loop(&mut self, state: &mut LoopState, max_ticks: u64) -> Result<(), EngineError> {
+        let LoopState {
+            tick_timer,
+            market_refresh_timer,
+            discovery_retry_timer,
+            reconcile_timer,
+            settle_timer,
+            user_events,
+            external_events,
+            ws_backoff,
+            shutdown_rx,
+            last_tick,
+            tick_count,
+            last_book_resync,
+        } = state;
+
+        let reconcile_enabled = self.config.reconcile_secs > 0 && !self.order_manager.is_dry_run();
+        let settle_enabled = self.config.settle_secs > 0;
+        let stale_after = Duration::from_secs(self.config.ws_stale_secs);
+
         // Use labeled loop to support WebSocket reconnection
         // When new tokens are discovered, we break the inner loop and reconnect
         'reconnect: loop {
+            self.heartbeat.enter(LoopActivity::Connecting);
+
             // Reset WebSocket update count on each reconnection
             let mut ws_update_count: u64 = 0;
 
             // Connect to WebSocket for market data if we have subscriptions
             // Keep ws_client alive since the stream borrows from it
             let ws_client = WsClient::default();
+            let mut subscribe_failed = false;
             let mut ws_stream: Option<Pin<Box<dyn futures::Stream<Item = Result<_, _>> + Send>>> =
                 if !self.subscribed_tokens.is_empty() {
                     let asset_ids: Result<Vec<U256>, _> = self
@@ -429,11 +2490,15 @@ impl Engine {
 
                     match asset_ids {
                         Ok(ids) => {
-                            tracing::info!(count = ids.len(), "Subscribing to orderbook updates");
-                            match ws_client.subscribe_orderbook(ids) {
-                                Ok(stream) => Some(Box::pin(stream)),
+                            tracing::info!(count = ids.len(), "Subscribing to orderbook and trade updates");
+                            match market_feed::subscribe(&ws_client, ids) {
+                                Ok(stream) => {
+                                    self.feed_metrics.connects += 1;
+                                    Some(Box::pin(stream))
+                                }
                                 Err(e) => {
-                                    tracing::error!(error = %e, "Failed to subscribe to orderbook");
+                                    tracing::error!(error = %e, "Failed to subscribe to market data");
+                                    subscribe_failed = true;
                                     None
                                 }
                             }
@@ -448,6 +2513,11 @@ impl Engine {
                     None
                 };
 
+            // After a failure, reconnect once the backoff has passed (the loop keeps running meanwhile)
+            let mut retry_at =
+                subscribe_failed.then(|| self.schedule_reconnect(ws_backoff, Disconnect::SubscribeFailed));
+            let mut last_ws_update = Instant::now();
+
             tracing::info!("Entering event loop");
 
             // Warmup: wait for order books to sync before trading
@@ -456,12 +2526,40 @@ impl Engine {
             let mut warmup_complete = false;
 
             loop {
+                self.heartbeat.enter(LoopActivity::Idle);
+                let retry_discovery = self.market_discovery_enabled && self.discovery.needs_retry();
+
                 tokio::select! {
 
-                    // Market discovery refresh (if enabled)
-                    _ = market_refresh_timer.tick(), if self.market_discovery_enabled => {
-                        if let Err(e) = self.refresh_markets().await {
-                            tracing::warn!(error = %e, "Market discovery refresh failed");
+                    // Market discovery refresh (if enabled) and detail for traded markets
+                    _ = market_refresh_timer.tick() => {
+                        self.heartbeat.enter(LoopActivity::MarketRefresh);
+                        if self.market_discovery_enabled {
+                            if let Err(e) = self.refresh_markets(false).await {
+                                tracing::warn!(error = %e, "Market discovery refresh failed");
+                            }
+                        }
+                        self.enrich_traded_markets().await;
+                        self.client.sync_clock().await;
+                        // Retry a balance fetch that hasn't succeeded yet
+                        if self.usdc_balance.is_none() {
+                            self.refresh_balance().await;
+                        }
+
+                        // Reconcile reserved exposure with the orders actually open
+                        self.risk_manager.audit(&self.order_manager.active_orders());
+
+                        self.utilization.report().log();
+                        self.carry_report().log();
+                        self.feed_metrics.log();
+                        self.client.throttle().log();
+                        self.attribution.log();
+
+                        // Report the day that just ended
+                        let today = chrono::Utc::now().date_naive();
+                        if today > self.report_day {
+                            self.write_daily_report(self.report_day);
+                            self.report_day = today;
                         }
 
                         // Break to reconnect WebSocket if new tokens were discovered
@@ -475,21 +2573,66 @@ impl Engine {
                         }
                     }
 
+                    // Retry discovery sources that failed on the last refresh
+                    _ = discovery_retry_timer.tick(), if retry_discovery => {
+                        self.heartbeat.enter(LoopActivity::MarketRefresh);
+                        if let Err(e) = self.refresh_markets(true).await {
+                            tracing::warn!(error = %e, "Market discovery retry failed");
+                        }
+                        if self.ws_needs_reconnect {
+                            tracing::info!(
+                                token_count = self.subscribed_tokens.len(),
+                                "Reconnecting WebSocket with new tokens"
+                            );
+                            self.ws_needs_reconnect = false;
+                            continue 'reconnect;
+                        }
+                    }
+
+                    // Reconcile orders and positions with the exchange
+                    _ = reconcile_timer.tick(), if reconcile_enabled => {
+                        self.heartbeat.enter(LoopActivity::Reconcile);
+                        self.reconcile_books().await;
+                        self.refresh_balance().await;
+                    }
+
+                    // Redeem and close positions in resolved markets
+                    _ = settle_timer.tick(), if settle_enabled => {
+                        self.heartbeat.enter(LoopActivity::Settle);
+                        self.settle_resolved().await;
+                    }
+
+                    // Our fills and cancellations from the user channel
+                    Some(event) = user_events.recv() => {
+                        self.heartbeat.enter(LoopActivity::Fill);
+                        self.apply_user_event(event).await;
+                    }
+
                     // Tick timer for strategy evaluation
                     _ = tick_timer.tick() => {
-                        tick_count += 1;
+                        self.heartbeat.tick();
+                        self.heartbeat.enter(LoopActivity::Tick);
+
+                        *tick_count += 1;
                         let elapsed = last_tick.elapsed();
-                        last_tick = Instant::now();
+                        *last_tick = Instant::now();
 
-                        tracing::info!(tick = tick_count, elapsed_ms = elapsed.as_millis(), "Tick");
+                        tracing::info!(tick = *tick_count, elapsed_ms = elapsed.as_millis(), "Tick");
 
                         // Check max_ticks limit
-                        if max_ticks > 0 && tick_count >= max_ticks {
-                            tracing::info!(tick_count = tick_count, max_ticks = max_ticks, "Max ticks reached, shutting down");
+                        if max_ticks > 0 && *tick_count >= max_ticks {
+                            tracing::info!(tick_count = *tick_count, max_ticks = max_ticks, "Max ticks reached, shutting down");
+                            self.heartbeat.enter(LoopActivity::Shutdown);
                             self.shutdown().await?;
                             break 'reconnect;
                         }
 
+                        // Nothing is traded once the kill switch trips
+                        if self.kill_switch.is_active() {
+                            self.stop_trading().await;
+                            continue;
+                        }
+
                         // Skip trading during warmup period (unless skip_warmup is set)
                         if !warmup_complete {
                             if self.skip_warmup {
@@ -511,6 +2654,12 @@ impl Engine {
                             }
                         }
 
+                        // Free reservations orphaned by a failed placement
+                        self.risk_manager.expire_reservations();
+                        self.cancel_expired_orders().await;
+
+                        self.sample_utilization();
+
                         // Check P&L for circuit breaker
                         self.risk_manager.check_pnl(&self.positions);
 
@@ -519,94 +2668,60 @@ impl Engine {
                             continue;
                         }
 
+                        self.publish_synthetic_books().await;
+                        self.guard_stale_books().await;
+
+                        // Net complementary outcomes by the latest market info
+                        self.risk_manager.set_markets(&self.market_info);
+
                         // Build strategy context with full-depth order books
+                        // (crossed/locked books are withheld until resynced)
                         let ctx = StrategyContext {
                             timestamp: chrono::Utc::now(),
-                            order_books: self.market_data.get_all_books().await,
+                            order_books: self.market_data.get_valid_books().await,
                             positions: self.positions.clone(),
                             markets: self.market_info.clone(),
                             unrealized_pnl: self.positions.total_unrealized_pnl(),
                             realized_pnl: self.positions.total_realized_pnl(),
-                            // TODO: Fetch actual USDC balance from CTF contract via RPC
-                            usdc_balance: Decimal::ZERO,
+                            usdc_balance: self.bankroll(),
+                            kelly_fraction: Decimal::from_f64(self.config.kelly_fraction)
+                                .unwrap_or(sizing::DEFAULT_KELLY_FRACTION),
+                            last_trades: self.market_data.last_trades().await,
+                            stats: self.market_data.stats().await,
                         };
 
-                        // Run strategies
-                        let signals = self.strategy_runtime.tick(&ctx);
+                        // Exit rules act whether or not the strategies do
+                        self.execute_exits(&ctx.order_books).await;
 
-                        // Process signals through risk manager and execute
-                        let mut shutdown_requested = false;
-                        for signal in signals {
-                            if matches!(signal, Signal::Hold) {
-                                continue;
-                            }
-
-                            // Handle shutdown signal from strategies
-                            if let Signal::Shutdown { reason } = &signal {
-                                tracing::info!(reason = reason.as_str(), "Strategy requested shutdown");
-                                shutdown_requested = true;
-                                continue;
-                            }
+                        // Pull orders of strategies whose trading window just closed
+                        for strategy_id in self.strategy_runtime.update_schedules(ctx.timestamp) {
+                            let cancelled =
+                                self.cancel_strategy_orders(&strategy_id, "outside trading window").await;
+                            tracing::info!(
+                                strategy_id = strategy_id.as_str(),
+                                cancelled_orders = cancelled,
+                                "Orders of paused strategy cancelled"
+                            );
+                        }
 
-                            match self.risk_manager.check_signal(&signal, &self.positions) {
-                                RiskCheckResult::Approved(ref s) | RiskCheckResult::Reduced(ref s, _) => {
-                                    if let RiskCheckResult::Reduced(_, ref reason) = self.risk_manager.check_signal(&signal, &self.positions) {
-                                        tracing::warn!(reason = reason.as_str(), "Signal reduced by risk manager");
-                                    }
+                        // Run strategies (order latency is measured from here)
+                        let signals = self.strategy_runtime.tick(&ctx);
+                        let signals_at = std::time::Instant::now();
 
-                                    // Extract order details for tracking
-                                    let (token_id, price, size) = match s {
-                                        Signal::Buy { token_id, price, size, .. } => (token_id.clone(), *price, *size),
-                                        Signal::Sell { token_id, price, size, .. } => (token_id.clone(), *price, *size),
-                                        _ => continue,
-                                    };
-
-                                    let notional = price * size;
-
-                                    // CRITICAL: Reserve exposure BEFORE placing order
-                                    // This prevents race conditions where multiple signals
-                                    // pass the risk check in the same tick
-                                    let reservation_id = match self.risk_manager.reserve_exposure(
-                                        &token_id,
-                                        notional,
-                                        &self.positions,
-                                    ) {
-                                        Some(id) => id,
-                                        None => {
-                                            tracing::warn!(
-                                                token_id = token_id.as_str(),
-                                                notional = %notional,
-                                                "Skipping order: exposure reservation rejected"
-                                            );
-                                            continue;
-                                        }
-                                    };
-
-                                    match self.order_manager.execute(s.clone()).await {
-                                        Ok(Some(order_id)) => {
-                                            // Confirm the reservation as an open order
-                                            self.risk_manager.confirm_reservation(&reservation_id, &order_id);
-                                        }
-                                        Ok(None) => {
-                                            // Order was not placed (e.g., dry-run mode)
-                                            // Release the reservation
-                                            self.risk_manager.release_reservation(&reservation_id);
-                                        }
-                                        Err(e) => {
-                                            tracing::error!(error = %e, "Order execution failed");
-                                            // Release the reservation on failure
-                                            self.risk_manager.release_reservation(&reservation_id);
-                                        }
-                                    }
-                                }
-                                RiskCheckResult::Rejected(reason) => {
-                                    tracing::warn!(reason = reason, "Signal rejected by risk manager");
-                                }
-                            }
+                        // Pull orders of strategies quarantined for being too slow
+                        for quarantined in self.strategy_runtime.take_quarantined() {
+                            self.cancel_quarantined(&quarantined).await;
                         }
 
+                        // Process signals through risk manager and execute
+                        let shutdown_requested = self.execute_signals(signals, signals_at).await;
+                        self.work_algos().await;
+
+                        self.persist_state();
+
                         // Handle shutdown request from strategies
                         if shutdown_requested {
+                            self.heartbeat.enter(LoopActivity::Shutdown);
                             self.shutdown().await?;
                             break 'reconnect;
                         }
@@ -614,42 +2729,76 @@ impl Engine {
 
                     // Process fills
                     Some(fill) = self.fill_receiver.recv() => {
-                        tracing::info!(
-                            order_id = fill.order_id,
-                            token_id = fill.token_id,
-                            price = %fill.price,
-                            size = %fill.size,
-                            "Processing fill"
-                        );
+                        self.heartbeat.fill();
+                        self.heartbeat.enter(LoopActivity::Fill);
+                        self.apply_fill(&fill);
+                    }
 
-                        // Update positions
-                        self.positions.apply_fill(&fill);
+                    Some(command) = self.control_receiver.recv() => {
+                        self.apply_control(command).await;
+                        // Loading or unloading a strategy changed the subscriptions
+                        if self.ws_needs_reconnect {
+                            tracing::info!(
+                                token_count = self.subscribed_tokens.len(),
+                                "Reconnecting WebSocket with changed strategies"
+                            );
+                            self.ws_needs_reconnect = false;
+                            continue 'reconnect;
+                        }
+                    }
 
-                        // Notify strategies
-                        self.strategy_runtime.on_fill(&fill);
+                    // Signals from the webhook
+                    Some(event) = external_events.recv() => {
+                        self.heartbeat.enter(LoopActivity::External);
+                        if self.handle_external_event(event, warmup_complete).await {
+                            self.heartbeat.enter(LoopActivity::Shutdown);
+                            self.shutdown().await?;
+                            break 'reconnect;
+                        }
+                    }
 
-                        // Update risk manager - close tracked order
-                        self.risk_manager.order_closed(&fill.order_id);
+                    // Reconnect market data once the backoff has passed
+                    _ = tokio::time::sleep_until(retry_at.unwrap_or_else(Instant::now)),
+                        if retry_at.is_some() =>
+                    {
+                        self.feed_metrics.reconnects += 1;
+                        continue 'reconnect;
+                    }
 
-                        // Log current exposure
-                        let exposure = self.risk_manager.current_exposure(&self.positions);
-                        let remaining = self.risk_manager.remaining_capacity(&self.positions);
-                        tracing::info!(
-                            exposure = %exposure,
-                            remaining_capacity = %remaining,
-                            "Exposure after fill"
-                        );
+                    // Market data connected but silent for too long
+                    _ = tokio::time::sleep_until(last_ws_update + stale_after),
+                        if ws_stream.is_some() && !stale_after.is_zero() =>
+                    {
+                        ws_stream = None;
+                        retry_at = Some(self.schedule_reconnect(ws_backoff, Disconnect::Stale));
                     }
 
                     // WebSocket market data
-                    Some(book_result) = async {
+                    book_result = async {
                         match ws_stream.as_mut() {
                             Some(stream) => stream.next().await,
                             None => std::future::pending().await,
                         }
                     } => {
+                        let Some(book_result) = book_result else {
+                            ws_stream = None;
+                            retry_at = Some(self.schedule_reconnect(ws_backoff, Disconnect::Ended));
+                            continue;
+                        };
                         match book_result {
-                            Ok(book) => {
+                            Ok(message) => {
+                                last_ws_update = Instant::now();
+                                ws_backoff.reset();
+                                self.heartbeat.ws_update();
+                                self.heartbeat.enter(LoopActivity::WebSocket);
+
+                                let book = match message {
+                                    FeedMessage::Book(book) => book,
+                                    FeedMessage::Trade(trade) => {
+                                        self.market_data.process_trade(&trade).await;
+                                        continue;
+                                    }
+                                };
                                 ws_update_count += 1;
                                 let token_id = book.asset_id.to_string();
 
@@ -673,18 +2822,64 @@ impl Engine {
                                 );
 
                                 // Process through market data hub (full depth + broadcast)
-                                self.market_data.process_book_update(book).await;
+                                let health = self.market_data.process_book_update(book).await;
+
+                                // A crossed/locked book means we missed updates: resubscribe
+                                // to get fresh snapshots (rate-limited to avoid reconnect loops)
+                                if !health.is_valid() {
+                                    let can_resync = last_book_resync
+                                        .is_none_or(|t| t.elapsed() >= Self::BOOK_RESYNC_COOLDOWN);
+                                    if can_resync {
+                                        tracing::warn!(
+                                            token_id = %token_id,
+                                            health = ?health,
+                                            "Invalid order book, resyncing WebSocket"
+                                        );
+                                        *last_book_resync = Some(Instant::now());
+                                        continue 'reconnect;
+                                    }
+                                }
+
+                                self.stale_books.remove(&token_id);
 
                                 // Update position prices for P&L tracking
-                                if let Some(book) = self.market_data.get_book(&token_id).await {
-                                    if let Some(mid) = book.mid_price() {
-                                        let mut prices = HashMap::new();
-                                        prices.insert(token_id, mid);
-                                        self.positions.update_prices(&prices);
-                                    }
+                                let Some(book) = self.market_data.get_book(&token_id).await else {
+                                    continue;
+                                };
+                                if let Some(mid) = book.mid_price() {
+                                    let mut prices = HashMap::new();
+                                    prices.insert(token_id.clone(), mid);
+                                    self.positions.update_prices(&prices);
+                                    self.attribution.update_prices(&prices);
+                                }
+
+                                // Strategies that opted in react now instead of on the next tick
+                                if !warmup_complete
+                                    || !health.is_valid()
+                                    || self.risk_manager.is_halted()
+                                    || self.kill_switch.is_active()
+                                {
+                                    continue;
+                                }
+                                let market = self.market_info.get(&token_id);
+                                let signals = self.strategy_runtime.book_update(&token_id, &book, market);
+                                let signals_at = std::time::Instant::now();
+                                for quarantined in self.strategy_runtime.take_quarantined() {
+                                    self.cancel_quarantined(&quarantined).await;
+                                }
+                                if signals.is_empty() {
+                                    continue;
+                                }
+                                let shutdown_requested = self.execute_signals(signals, signals_at).await;
+                                self.persist_state();
+                                if shutdown_requested {
+                                    self.heartbeat.enter(LoopActivity::Shutdown);
+                                    self.shutdown().await?;
+                                    break 'reconnect;
                                 }
                             }
                             Err(e) => {
+                                self.feed_metrics.errors += 1;
                                 tracing::error!(error = %e, "WebSocket orderbook error");
                             }
                         }
@@ -692,6 +2887,7 @@ impl Engine {
 
                     // Shutdown signal
                     _ = shutdown_rx.recv() => {
+                        self.heartbeat.enter(LoopActivity::Shutdown);
                         tracing::info!("Shutting down engine");
                         self.shutdown().await?;
                         break 'reconnect;
@@ -703,6 +2899,34 @@ impl Engine {
         Ok(())
     }
 
+    /// Broadcast an event from the webhook and hand it to strategies, unless
+    /// they are still warming up or trading is halted. Returns whether a
+    /// strategy asked to shut down.
+    async fn handle_external_event(&mut self, event: ExternalEvent, warmup_complete: bool) -> bool {
+        self.market_data.publish_external(event.clone()).await;
+        if !warmup_complete || self.risk_manager.is_halted() || self.kill_switch.is_active() {
+            tracing::info!(
+                source = event.source.as_str(),
+                kind = event.kind.as_str(),
+                "External event not traded"
+            );
+            return false;
+        }
+
+        let market = event.token_id.as_ref().and_then(|token_id| self.market_info.get(token_id));
+        let signals = self.strategy_runtime.external_event(&event, market);
+        let signals_at = std::time::Instant::now();
+        for quarantined in self.strategy_runtime.take_quarantined() {
+            self.cancel_quarantined(&quarantined).await;
+        }
+        if signals.is_empty() {
+            return false;
+        }
+        let shutdown_requested = self.execute_signals(signals, signals_at).await;
+        self.persist_state();
+        shutdown_requested
+    }
+
     /// Graceful shutdown: cancel all orders and cleanup.
     async fn shutdown(&mut self) -> Result<(), EngineError> {
         self.shutdown = true;
@@ -711,6 +2935,7 @@ impl Engine {
         let cancelled = self.order_manager.cancel_all_orders().await
             .map_err(|e| EngineError::OrderError(e.to_string()))?;
         tracing::info!(count = cancelled, "Cancelled orders on shutdown");
+        self.persist_state();
 
         // Shutdown strategies
         self.strategy_runtime.shutdown();
@@ -722,8 +2947,35 @@ impl Engine {
             realized_pnl = %realized,
             unrealized_pnl = %unrealized,
             total_pnl = %(realized + unrealized),
+            fees = %self.positions.total_fees(),
             "Final P&L"
         );
+        self.attribution.log();
+        self.write_daily_report(chrono::Utc::now().date_naive());
+
+        let leaks = self.risk_manager.leak_metrics();
+        tracing::info!(
+            expired_reservations = leaks.expired_reservations,
+            expired_notional = %leaks.expired_notional,
+            stale_orders = leaks.stale_orders,
+            stale_order_notional = %leaks.stale_order_notional,
+            untracked_orders = leaks.untracked_orders,
+            audits = leaks.audits,
+            "Exposure leak summary"
+        );
+
+        let latency = self.order_manager.latency_metrics();
+        tracing::info!(
+            orders = latency.orders,
+            violations = latency.violations,
+            cancelled = latency.cancelled,
+            avg_latency_ms = latency.avg_latency().as_millis() as u64,
+            max_latency_ms = latency.max_latency.as_millis() as u64,
+            "Order latency summary"
+        );
+
+        self.utilization.report().log();
+        self.carry_report().log();
 
         Ok(())
     }
@@ -0,0 +1,263 @@
+//! Execution algorithms: one large order worked as a series of child orders.
+//!
+//! A strategy that wants more size than the book shows at once returns
+//! `Signal::Algo` wrapping a BUY or SELL (the parent) and an
+//! [`ExecutionAlgo`]:
+//!
+//! - [`ExecutionAlgo::Twap`] releases the parent in equal slices over a
+//!   duration. When a slice is due, the previous child is cancelled and its
+//!   unfilled size rolls into the new one, so the parent is never more than
+//!   one slice ahead of schedule.
+//! - [`ExecutionAlgo::Iceberg`] shows at most `clip` shares at a time and
+//!   places the next clip once the resting one is filled (or cancelled).
+//!
+//! Child orders keep the parent's price, urgency, post-only and expiry, and
+//! each one goes through the risk checks when placed. The order manager
+//! tracks parents and their children; child fills update positions as usual
+//! and are added up on the parent, which reports its filled size and
+//! average price as one logical order when it finishes. Parents are not
+//! persisted: after a restart their resting children are tracked as plain
+//! orders.
+
+use std::time::{Duration, Instant};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::order::Order;
+use crate::safe_math::{add, div, mul, sub};
+use crate::strategy::Signal;
+
+/// How a parent order is worked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionAlgo {
+    /// Equal slices released evenly over `duration`
+    Twap { duration: Duration, slices: u32 },
+    /// At most `clip` shares resting at a time
+    Iceberg { clip: Decimal },
+}
+
+/// What to do next for a parent order.
+#[derive(Debug, Clone, Default)]
+pub struct AlgoStep {
+    /// Resting children to cancel first
+    pub cancel: Vec<String>,
+    /// Child order to place
+    pub place: Option<Signal>,
+    /// Whether the parent is finished (filled, or every slice worked)
+    pub done: bool,
+}
+
+/// A large order worked as child orders.
+#[derive(Debug, Clone)]
+pub struct ParentOrder {
+    pub id: String,
+    /// Strategy whose signal started it (None = placed outside the engine)
+    pub strategy_id: Option<String>,
+    /// The BUY or SELL being worked
+    pub order: Signal,
+    pub algo: ExecutionAlgo,
+    pub started_at: Instant,
+    /// Child order IDs, oldest first
+    pub children: Vec<String>,
+    /// Filled size across children
+    pub filled: Decimal,
+    /// Filled notional across children
+    pub filled_notional: Decimal,
+    /// TWAP slices released so far
+    slices_released: u32,
+}
+
+impl ParentOrder {
+    /// Start working `order`; None unless it is a BUY or SELL.
+    pub fn new(
+        id: String,
+        strategy_id: Option<String>,
+        order: Signal,
+        algo: ExecutionAlgo,
+        now: Instant,
+    ) -> Option<Self> {
+        if !matches!(order, Signal::Buy { .. } | Signal::Sell { .. }) {
+            return None;
+        }
+        Some(Self {
+            id,
+            strategy_id,
+            order,
+            algo,
+            started_at: now,
+            children: Vec::new(),
+            filled: Decimal::ZERO,
+            filled_notional: Decimal::ZERO,
+            slices_released: 0,
+        })
+    }
+
+    pub fn token_id(&self) -> &str {
+        self.order.order_terms().map(|(token_id, ..)| token_id).unwrap_or_default()
+    }
+
+    /// Total size to work.
+    pub fn size(&self) -> Decimal {
+        self.order.order_terms().map(|(.., size)| size).unwrap_or_default()
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        sub(self.size(), self.filled, "parent remaining").max(Decimal::ZERO)
+    }
+
+    /// Average fill price across children (None before the first fill).
+    pub fn avg_price(&self) -> Option<Decimal> {
+        (!self.filled.is_zero()).then(|| div(self.filled_notional, self.filled, "parent avg price"))
+    }
+
+    /// Add up a child fill.
+    pub fn record_fill(&mut self, price: Decimal, size: Decimal) {
+        self.filled = add(self.filled, size, "parent filled");
+        let notional = mul(price, size, "parent fill notional");
+        self.filled_notional = add(self.filled_notional, notional, "parent notional");
+    }
+
+    /// The next step at `now`, given the children that are still resting.
+    pub fn next_step(&mut self, now: Instant, resting: &[&Order]) -> AlgoStep {
+        let resting: Vec<String> = resting
+            .iter()
+            .filter(|o| o.is_active() && self.children.contains(&o.id))
+            .map(|o| o.id.clone())
+            .collect();
+        if self.remaining().is_zero() {
+            return AlgoStep { done: resting.is_empty(), ..Default::default() };
+        }
+
+        match self.algo {
+            ExecutionAlgo::Twap { duration, slices } => {
+                let slices = slices.max(1);
+                let interval = duration / slices;
+                let elapsed = now.saturating_duration_since(self.started_at);
+                let due = if interval.is_zero() {
+                    slices
+                } else {
+                    ((elapsed.as_millis() / interval.as_millis().max(1)) as u32 + 1).min(slices)
+                };
+                if due <= self.slices_released {
+                    let done = self.slices_released == slices && resting.is_empty();
+                    return AlgoStep { done, ..Default::default() };
+                }
+                self.slices_released = due;
+
+                // Catch up to the schedule, replacing whatever is still resting
+                let scheduled = mul(self.size(), Decimal::from(due), "twap target");
+                let target = div(scheduled, Decimal::from(slices), "twap target");
+                let size = sub(target, self.filled, "twap slice");
+                AlgoStep { cancel: resting, place: self.child(size), done: false }
+            }
+            ExecutionAlgo::Iceberg { clip } => {
+                if !resting.is_empty() {
+                    return AlgoStep::default();
+                }
+                AlgoStep { place: self.child(clip.min(self.remaining())), ..Default::default() }
+            }
+        }
+    }
+
+    /// A child order for `size` shares (None if it rounds to nothing).
+    fn child(&self, size: Decimal) -> Option<Signal> {
+        let size = size.round_dp_with_strategy(2, RoundingStrategy::ToZero);
+        (size > Decimal::ZERO).then(|| self.order.with_size(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderStatus;
+    use crate::strategy::Urgency;
+    use rust_decimal_macros::dec;
+
+    fn buy(size: Decimal) -> Signal {
+        Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.40),
+            size,
+            urgency: Urgency::Medium,
+            post_only: true,
+            expires_at: None,
+        }
+    }
+
+    fn child(id: &str, size: Decimal, status: OrderStatus) -> Order {
+        Order {
+            id: id.to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price: dec!(0.40),
+            size,
+            filled_size: Decimal::ZERO,
+            status,
+            created_at: chrono::Utc::now(),
+            ack_latency: None,
+            over_budget: false,
+            strategy_id: None,
+        }
+    }
+
+    fn size(step: &AlgoStep) -> Option<Decimal> {
+        step.place.as_ref().and_then(|s| s.order_terms()).map(|(.., size)| size)
+    }
+
+    fn idle(step: &AlgoStep) -> bool {
+        step.cancel.is_empty() && step.place.is_none() && !step.done
+    }
+
+    #[test]
+    fn test_twap_catches_up_to_schedule() {
+        let start = Instant::now();
+        let algo = ExecutionAlgo::Twap { duration: Duration::from_secs(60), slices: 3 };
+        let mut parent = ParentOrder::new("algo-1".to_string(), None, buy(dec!(100)), algo, start).unwrap();
+
+        let first = parent.next_step(start, &[]);
+        assert_eq!(size(&first), Some(dec!(33.33)));
+        assert!(matches!(first.place, Some(Signal::Buy { post_only: true, .. })));
+        parent.children.push("c1".to_string());
+        // Nothing more until the next slice is due
+        assert!(idle(&parent.next_step(start + Duration::from_secs(10), &[])));
+
+        // The first child only half filled: it is cancelled and the rest rolls over
+        parent.record_fill(dec!(0.40), dec!(16.67));
+        let resting = child("c1", dec!(33.33), OrderStatus::PartiallyFilled);
+        let second = parent.next_step(start + Duration::from_secs(20), &[&resting]);
+        assert_eq!(second.cancel, vec!["c1".to_string()]);
+        assert_eq!(size(&second), Some(dec!(49.99)));
+        parent.children.push("c2".to_string());
+
+        parent.record_fill(dec!(0.38), dec!(49.99));
+        let last = parent.next_step(start + Duration::from_secs(45), &[]);
+        assert_eq!(size(&last), Some(dec!(33.34)));
+        assert_eq!(parent.avg_price().unwrap().round_dp(4), dec!(0.3850));
+
+        // Every slice released: done once nothing rests
+        let open = child("c3", dec!(33.33), OrderStatus::Open);
+        parent.children.push("c3".to_string());
+        assert!(!parent.next_step(start + Duration::from_secs(90), &[&open]).done);
+        assert!(parent.next_step(start + Duration::from_secs(90), &[]).done);
+    }
+
+    #[test]
+    fn test_iceberg_shows_one_clip_at_a_time() {
+        let now = Instant::now();
+        let algo = ExecutionAlgo::Iceberg { clip: dec!(10) };
+        let mut parent = ParentOrder::new("algo-2".to_string(), None, buy(dec!(25)), algo, now).unwrap();
+
+        assert_eq!(size(&parent.next_step(now, &[])), Some(dec!(10)));
+        parent.children.push("c1".to_string());
+        let resting = child("c1", dec!(10), OrderStatus::Open);
+        assert!(idle(&parent.next_step(now, &[&resting])));
+
+        parent.record_fill(dec!(0.40), dec!(10));
+        parent.record_fill(dec!(0.40), dec!(10));
+        assert_eq!(size(&parent.next_step(now, &[])), Some(dec!(5)));
+        parent.record_fill(dec!(0.40), dec!(5));
+        assert!(parent.next_step(now, &[]).done);
+
+        assert!(ParentOrder::new("x".to_string(), None, Signal::Hold, algo, now).is_none());
+    }
+}
@@ -0,0 +1,352 @@
+//! Engine-level exit rules: stop-loss, take-profit and time stops.
+//!
+//! A strategy attaches exit rules to its position in a token with
+//! [`Signal::Exit`]. Positions without rules of their own get the defaults
+//! of `PMENGINE_STOP_LOSS` and `PMENGINE_TAKE_PROFIT` (fractions of the entry
+//! price) and `PMENGINE_MAX_HOLD_SECS` (from when the position was opened).
+//!
+//! Every tick the engine checks long positions against their best bid and
+//! sells a position whose rule triggers with a FAK market order at no less
+//! than that bid, whether or not the strategy that opened it is still
+//! running. An exit is not resent for [`EXIT_RETRY`], so a fill on its way
+//! isn't sold twice; what a partial fill left is sold on a later tick. A
+//! rule is dropped once the position it was attached to is closed.
+//!
+//! Positions are also closed ahead of resolution: with
+//! `PMENGINE_EXIT_BEFORE_EXPIRY_SECS` set, whatever is still held that long
+//! before its market's end date is sold (or, with `PMENGINE_EXPIRY_ALERT_ONLY`,
+//! flagged once with a warning), whatever rule it has.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::client::TimeInForce;
+use crate::orderbook::OrderBook;
+use crate::position::{Position, PositionTracker};
+use crate::safe_math::{add, mul, sub};
+use crate::strategy::{MarketInfo, Signal};
+
+/// How long after an exit is sent before it is sent again for the same token.
+pub const EXIT_RETRY: Duration = Duration::from_secs(5);
+
+/// When to close a position.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExitRule {
+    /// Sell once the best bid falls to this price
+    pub stop_loss: Option<Decimal>,
+    /// Sell once the best bid rises to this price
+    pub take_profit: Option<Decimal>,
+    /// Sell at this time
+    pub time_stop: Option<DateTime<Utc>>,
+}
+
+impl ExitRule {
+    pub fn is_empty(&self) -> bool {
+        self.stop_loss.is_none() && self.take_profit.is_none() && self.time_stop.is_none()
+    }
+
+    /// What triggers the rule with the best bid at `bid` (None = hold).
+    pub fn trigger(&self, bid: Decimal, now: DateTime<Utc>) -> Option<ExitTrigger> {
+        if self.stop_loss.is_some_and(|stop| bid <= stop) {
+            Some(ExitTrigger::StopLoss)
+        } else if self.take_profit.is_some_and(|target| bid >= target) {
+            Some(ExitTrigger::TakeProfit)
+        } else if self.time_stop.is_some_and(|at| now >= at) {
+            Some(ExitTrigger::TimeStop)
+        } else {
+            None
+        }
+    }
+}
+
+/// Rules for positions without their own, relative to each position.
+#[derive(Debug, Clone, Default)]
+pub struct ExitDefaults {
+    /// Fraction of the entry price lost before selling
+    pub stop_loss: Option<Decimal>,
+    /// Fraction of the entry price gained before selling
+    pub take_profit: Option<Decimal>,
+    /// Longest a position is held
+    pub max_hold: Option<Duration>,
+    /// How long before its market's end date a position is closed
+    pub before_expiry: Option<Duration>,
+    /// Flag positions near expiry instead of selling them
+    pub expiry_alert_only: bool,
+}
+
+impl ExitDefaults {
+    /// When a position in a market ending at `end_date` is closed.
+    fn expiry_stop(&self, end_date: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        Some(end_date? - chrono::Duration::from_std(self.before_expiry?).ok()?)
+    }
+
+    fn rule_for(&self, position: &Position) -> ExitRule {
+        let entry = position.avg_entry_price;
+        ExitRule {
+            stop_loss: self.stop_loss.map(|f| mul(entry, sub(Decimal::ONE, f, "stop-loss"), "stop-loss")),
+            take_profit: self
+                .take_profit
+                .map(|f| mul(entry, add(Decimal::ONE, f, "take-profit"), "take-profit")),
+            time_stop: self
+                .max_hold
+                .and_then(|hold| Some(position.opened_at? + chrono::Duration::from_std(hold).ok()?)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitTrigger {
+    StopLoss,
+    TakeProfit,
+    TimeStop,
+    /// The market ends soon
+    Expiry,
+}
+
+impl fmt::Display for ExitTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExitTrigger::StopLoss => "stop-loss",
+            ExitTrigger::TakeProfit => "take-profit",
+            ExitTrigger::TimeStop => "time stop",
+            ExitTrigger::Expiry => "expiry",
+        })
+    }
+}
+
+/// A position to close.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exit {
+    pub token_id: String,
+    pub size: Decimal,
+    /// Best bid, the lowest price sold at
+    pub price: Decimal,
+    pub trigger: ExitTrigger,
+    /// Strategy that attached the rule (None = a default rule)
+    pub strategy_id: Option<String>,
+    /// Only flag the position, don't sell it
+    pub alert_only: bool,
+}
+
+impl Exit {
+    /// FAK market sell of the position.
+    pub fn signal(&self) -> Signal {
+        Signal::Market {
+            token_id: self.token_id.clone(),
+            is_buy: false,
+            amount: self.size,
+            worst_price: self.price,
+            time_in_force: TimeInForce::Fak,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AttachedRule {
+    rule: ExitRule,
+    strategy_id: String,
+    /// Whether the position has been open since the rule was attached
+    held: bool,
+}
+
+/// Exit rules by token, checked every tick.
+#[derive(Debug, Default)]
+pub struct ExitManager {
+    defaults: ExitDefaults,
+    rules: HashMap<String, AttachedRule>,
+    /// When an exit was last sent, by token
+    sent: HashMap<String, Instant>,
+    /// Tokens already flagged as near expiry
+    flagged: HashSet<String>,
+}
+
+impl ExitManager {
+    pub fn new(defaults: ExitDefaults) -> Self {
+        Self { defaults, ..Default::default() }
+    }
+
+    /// Attach a strategy's rule to a token (an empty rule detaches it).
+    /// A rule can be attached before the position is opened.
+    pub fn set(&mut self, strategy_id: &str, token_id: &str, rule: ExitRule) {
+        if rule.is_empty() {
+            self.rules.remove(token_id);
+            return;
+        }
+        let attached = AttachedRule { rule, strategy_id: strategy_id.to_string(), held: false };
+        self.rules.insert(token_id.to_string(), attached);
+    }
+
+    /// Rule attached to a token (None = the defaults apply).
+    pub fn rule(&self, token_id: &str) -> Option<&ExitRule> {
+        self.rules.get(token_id).map(|a| &a.rule)
+    }
+
+    /// Exits of the long positions whose rule triggers at their best bid, or
+    /// whose market ends within the expiry window.
+    pub fn check(
+        &mut self,
+        positions: &PositionTracker,
+        books: &HashMap<String, Arc<OrderBook>>,
+        markets: &HashMap<String, MarketInfo>,
+        now: DateTime<Utc>,
+    ) -> Vec<Exit> {
+        let is_long = |token_id: &str| positions.get(token_id).is_some_and(|p| p.size > Decimal::ZERO);
+        self.rules.retain(|token_id, attached| {
+            let long = is_long(token_id);
+            let closed = attached.held && !long;
+            attached.held |= long;
+            !closed
+        });
+        self.sent.retain(|token_id, _| is_long(token_id));
+        self.flagged.retain(|token_id| is_long(token_id));
+
+        let mut exits = Vec::new();
+        for position in positions.active_positions().into_iter().filter(|p| p.size > Decimal::ZERO) {
+            let token_id = &position.token_id;
+            let Some(bid) = books.get(token_id).and_then(|b| b.best_bid()).map(|level| level.price) else {
+                continue;
+            };
+            let (rule, strategy_id) = match self.rules.get(token_id) {
+                Some(attached) => (attached.rule.clone(), Some(attached.strategy_id.clone())),
+                None => (self.defaults.rule_for(position), None),
+            };
+            let end_date = markets.get(token_id).and_then(|m| m.end_date);
+            let expiring = self.defaults.expiry_stop(end_date).is_some_and(|at| now >= at);
+            let Some(trigger) = rule.trigger(bid, now).or(expiring.then_some(ExitTrigger::Expiry)) else {
+                continue;
+            };
+            let alert_only = trigger == ExitTrigger::Expiry && self.defaults.expiry_alert_only;
+            if alert_only {
+                // Flagged once per position
+                if !self.flagged.insert(token_id.clone()) {
+                    continue;
+                }
+            } else {
+                if self.sent.get(token_id).is_some_and(|at| at.elapsed() < EXIT_RETRY) {
+                    continue;
+                }
+                self.sent.insert(token_id.clone(), Instant::now());
+            }
+            let (token_id, size) = (token_id.clone(), position.size);
+            exits.push(Exit { token_id, size, price: bid, trigger, strategy_id, alert_only });
+        }
+        exits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::Level;
+    use crate::position::Fill;
+    use rust_decimal_macros::dec;
+
+    fn buy(positions: &mut PositionTracker, token_id: &str, price: Decimal, size: Decimal) {
+        positions.get_or_create(token_id).apply_fill(&Fill {
+            order_id: "1".to_string(),
+            token_id: token_id.to_string(),
+            is_buy: true,
+            price,
+            size,
+            timestamp: Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: None,
+        });
+    }
+
+    fn books(bids: &[(&str, Decimal)]) -> HashMap<String, Arc<OrderBook>> {
+        bids.iter()
+            .map(|(token_id, bid)| {
+                let mut book = OrderBook::new(token_id.to_string());
+                book.bids = vec![Level { price: *bid, size: dec!(100) }];
+                book.asks = vec![Level { price: *bid + dec!(0.02), size: dec!(100) }];
+                (token_id.to_string(), Arc::new(book))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attached_rules_trigger_and_are_dropped_when_closed() {
+        let mut exits = ExitManager::new(ExitDefaults::default());
+        let now = Utc::now();
+        let stop = ExitRule { stop_loss: Some(dec!(0.40)), take_profit: Some(dec!(0.70)), time_stop: None };
+        exits.set("strat", "a", stop);
+        exits.set("strat", "b", ExitRule { time_stop: Some(now), ..Default::default() });
+
+        let mut positions = PositionTracker::new();
+        buy(&mut positions, "a", dec!(0.50), dec!(10));
+        buy(&mut positions, "b", dec!(0.50), dec!(5));
+        let markets = HashMap::new();
+        let before = now - chrono::Duration::seconds(1);
+        assert!(exits.check(&positions, &books(&[("a", dec!(0.45))]), &markets, before).is_empty());
+
+        let bids = books(&[("a", dec!(0.40)), ("b", dec!(0.50))]);
+        let triggered = exits.check(&positions, &bids, &markets, now);
+        assert_eq!(triggered.len(), 2);
+        let a = triggered.iter().find(|e| e.token_id == "a").unwrap();
+        assert_eq!((a.trigger, a.size, a.price), (ExitTrigger::StopLoss, dec!(10), dec!(0.40)));
+        assert_eq!(a.strategy_id.as_deref(), Some("strat"));
+        assert!(triggered.iter().any(|e| e.token_id == "b" && e.trigger == ExitTrigger::TimeStop));
+
+        // Not resent while the first exit may still be filling
+        assert!(exits.check(&positions, &books(&[("a", dec!(0.40))]), &markets, now).is_empty());
+
+        // Closed: the rule goes with the position
+        positions.get_or_create("a").size = Decimal::ZERO;
+        exits.check(&positions, &books(&[]), &markets, now);
+        assert!(exits.rule("a").is_none());
+        assert!(exits.rule("b").is_some());
+    }
+
+    #[test]
+    fn test_defaults_are_relative_to_entry() {
+        let mut exits = ExitManager::new(ExitDefaults {
+            stop_loss: Some(dec!(0.2)),
+            take_profit: Some(dec!(0.5)),
+            ..Default::default()
+        });
+        let mut positions = PositionTracker::new();
+        buy(&mut positions, "a", dec!(0.50), dec!(10));
+        buy(&mut positions, "b", dec!(0.40), dec!(10));
+
+        // a: stop at 0.40, target 0.75; b: stop at 0.32, target 0.60
+        let bids = books(&[("a", dec!(0.45)), ("b", dec!(0.60))]);
+        let triggered = exits.check(&positions, &bids, &HashMap::new(), Utc::now());
+        assert_eq!(triggered.len(), 1);
+        assert_eq!((triggered[0].token_id.as_str(), triggered[0].trigger), ("b", ExitTrigger::TakeProfit));
+        assert_eq!(triggered[0].strategy_id, None);
+        assert!(matches!(triggered[0].signal(), Signal::Market { is_buy: false, .. }));
+    }
+
+    #[test]
+    fn test_positions_are_closed_before_expiry() {
+        let defaults = ExitDefaults { before_expiry: Some(Duration::from_secs(3600)), ..Default::default() };
+        let mut exits = ExitManager::new(defaults.clone());
+        let mut positions = PositionTracker::new();
+        buy(&mut positions, "a", dec!(0.96), dec!(10));
+        buy(&mut positions, "b", dec!(0.96), dec!(10));
+        let now = Utc::now();
+        let ends_at = |hours| {
+            let end_date = now + chrono::Duration::hours(hours);
+            MarketInfo::new(String::new(), String::new(), String::new(), Some(end_date))
+        };
+        let markets = HashMap::from([("a".to_string(), ends_at(2)), ("b".to_string(), ends_at(1))]);
+        let bids = books(&[("a", dec!(0.97)), ("b", dec!(0.97))]);
+
+        let triggered = exits.check(&positions, &bids, &markets, now);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!((triggered[0].token_id.as_str(), triggered[0].trigger), ("b", ExitTrigger::Expiry));
+        assert!(!triggered[0].alert_only);
+
+        // Alert only: flagged once, not sold
+        let mut exits = ExitManager::new(ExitDefaults { expiry_alert_only: true, ..defaults });
+        let triggered = exits.check(&positions, &bids, &markets, now);
+        assert!(triggered.len() == 1 && triggered[0].alert_only);
+        assert!(exits.check(&positions, &bids, &markets, now).is_empty());
+    }
+}
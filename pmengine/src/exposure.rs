@@ -0,0 +1,160 @@
+//! Market-aware exposure for the risk checks.
+//!
+//! The outcome tokens of one market offset each other: a share of every
+//! outcome (YES and NO of a binary market) pays out exactly $1 however the
+//! market resolves. Counting each leg at full notional spends budget on risk
+//! that isn't there, so positions are grouped by market (the `slug` of their
+//! `MarketInfo`) and, when every outcome is held, the smallest holding is
+//! netted out of each leg. Tokens without market info are a market of their
+//! own and are never netted.
+//!
+//! The same grouping shows concentration the per-token limits miss: exposure
+//! per market and per category is limited by `PMENGINE_MAX_MARKET_EXPOSURE`
+//! and `PMENGINE_MAX_CATEGORY_EXPOSURE`.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::position::{Position, PositionTracker};
+use crate::safe_math::{mul, sub, sum};
+use crate::strategy::MarketInfo;
+
+#[derive(Debug, Clone, Default)]
+struct Market {
+    /// Outcome tokens with market info
+    outcomes: Vec<String>,
+    category: Option<String>,
+}
+
+/// Which market (and category) each token belongs to.
+#[derive(Debug, Clone, Default)]
+pub struct MarketMap {
+    /// Token ID -> market slug
+    tokens: HashMap<String, String>,
+    markets: HashMap<String, Market>,
+}
+
+impl MarketMap {
+    /// Group the tokens of the engine's market info by market slug.
+    pub fn new(market_info: &HashMap<String, MarketInfo>) -> Self {
+        let mut map = Self::default();
+        for (token_id, info) in market_info.iter().filter(|(_, info)| !info.slug.is_empty()) {
+            map.tokens.insert(token_id.clone(), info.slug.clone());
+            let market = map.markets.entry(info.slug.clone()).or_default();
+            market.outcomes.push(token_id.clone());
+            market.category = market.category.take().or_else(|| info.category.clone());
+        }
+        map
+    }
+
+    /// Market of a token (the token itself when it has no market info).
+    pub fn market<'a>(&'a self, token_id: &'a str) -> &'a str {
+        self.tokens.get(token_id).map_or(token_id, String::as_str)
+    }
+
+    /// Category of a market (None if unknown).
+    pub fn category(&self, market: &str) -> Option<&str> {
+        self.markets.get(market).and_then(|m| m.category.as_deref())
+    }
+
+    /// Position notional per market, with fully held outcome sets netted out.
+    pub fn position_exposure(&self, positions: &PositionTracker) -> HashMap<String, Decimal> {
+        let mut by_market: HashMap<&str, Vec<&Position>> = HashMap::new();
+        for position in positions.active_positions() {
+            by_market.entry(self.market(&position.token_id)).or_default().push(position);
+        }
+        by_market
+            .into_iter()
+            .map(|(market, held)| (market.to_string(), self.netted(market, &held)))
+            .collect()
+    }
+
+    /// Notional of one market's positions, less the sets of every outcome.
+    fn netted(&self, market: &str, held: &[&Position]) -> Decimal {
+        let outcomes = self.markets.get(market).map_or(1, |m| m.outcomes.len());
+        let longs: Vec<Decimal> = held.iter().map(|p| p.size).filter(|s| *s > Decimal::ZERO).collect();
+        let sets = if outcomes >= 2 && longs.len() == outcomes {
+            longs.iter().copied().min().unwrap_or_default()
+        } else {
+            Decimal::ZERO
+        };
+        sum(
+            held.iter().map(|p| {
+                let size = if p.size > Decimal::ZERO {
+                    sub(p.size, sets, "unhedged size")
+                } else {
+                    p.size.abs()
+                };
+                mul(size, p.last_price.unwrap_or(p.avg_entry_price), "netted notional")
+            }),
+            "market exposure",
+        )
+    }
+
+    /// Shares of a buy of `token_id` that would complete sets with the other
+    /// outcomes held (zero if the token's market isn't known).
+    pub fn hedge_capacity(&self, token_id: &str, positions: &PositionTracker) -> Decimal {
+        let held =
+            |token_id: &str| positions.get(token_id).map_or(Decimal::ZERO, |p| p.size.max(Decimal::ZERO));
+        let Some(market) = self.markets.get(self.market(token_id)).filter(|m| m.outcomes.len() >= 2) else {
+            return Decimal::ZERO;
+        };
+        let others = market
+            .outcomes
+            .iter()
+            .filter(|t| *t != token_id)
+            .map(|t| held(t))
+            .min()
+            .unwrap_or_default();
+        sub(others, held(token_id), "hedge capacity").max(Decimal::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Fill;
+    use rust_decimal_macros::dec;
+
+    fn info(slug: &str, category: &str) -> MarketInfo {
+        MarketInfo::new(String::new(), String::new(), slug.to_string(), None)
+            .with_category(Some(category.to_string()))
+    }
+
+    fn buy(positions: &mut PositionTracker, token_id: &str, price: Decimal, size: Decimal) {
+        positions.get_or_create(token_id).apply_fill(&Fill {
+            order_id: "1".to_string(),
+            token_id: token_id.to_string(),
+            is_buy: true,
+            price,
+            size,
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: None,
+        });
+    }
+
+    #[test]
+    fn test_complementary_outcomes_are_netted() {
+        let market_info = HashMap::from([
+            ("yes".to_string(), info("election", "politics")),
+            ("no".to_string(), info("election", "politics")),
+        ]);
+        let markets = MarketMap::new(&market_info);
+        let mut positions = PositionTracker::new();
+        buy(&mut positions, "yes", dec!(0.60), dec!(30));
+        assert_eq!(markets.hedge_capacity("no", &positions), dec!(30));
+        assert_eq!(markets.hedge_capacity("yes", &positions), dec!(0));
+
+        // 20 sets pay out $1 whatever happens: only 10 YES are at risk
+        buy(&mut positions, "no", dec!(0.38), dec!(20));
+        buy(&mut positions, "other", dec!(0.50), dec!(10));
+        let exposure = markets.position_exposure(&positions);
+        assert_eq!(exposure["election"], dec!(6));
+        assert_eq!(exposure["other"], dec!(5));
+        assert_eq!(markets.hedge_capacity("no", &positions), dec!(10));
+        assert_eq!(markets.category("election"), Some("politics"));
+        assert_eq!(markets.market("other"), "other");
+    }
+}
@@ -0,0 +1,40 @@
+//! Trading fees.
+//!
+//! Polymarket charges the taker of a trade `rate * min(price, 1 - price)` per
+//! share, in USDC, so fees are smallest on near-certain outcomes. The base
+//! rate (in basis points) comes with the trade on the user channel
+//! (`fee_rate_bps`), or from the token's entry in the CLOB fee schedule when
+//! the message leaves it out. Makers pay no fee.
+//!
+//! The fee is booked on each fill and taken out of the position's realized
+//! P&L when it is paid, so every P&L the engine reports is net of fees.
+
+use rust_decimal::Decimal;
+
+use crate::safe_math::{div, mul, sub};
+
+/// Fee on a fill of `size` shares at `price`, at `rate_bps` basis points.
+pub fn fee(rate_bps: Decimal, price: Decimal, size: Decimal) -> Decimal {
+    if rate_bps <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    let rate = div(rate_bps, Decimal::from(10_000), "fee rate");
+    let base = price.min(sub(Decimal::ONE, price, "fee base")).max(Decimal::ZERO);
+    mul(mul(rate, base, "fee per share"), size, "fee")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_fee_is_charged_on_the_cheaper_side() {
+        // 2% on min(0.40, 0.60) per share
+        assert_eq!(fee(dec!(200), dec!(0.40), dec!(100)), dec!(0.8));
+        assert_eq!(fee(dec!(200), dec!(0.60), dec!(100)), dec!(0.8));
+        // Near-certain outcomes pay almost nothing
+        assert_eq!(fee(dec!(200), dec!(0.99), dec!(100)), dec!(0.02));
+        assert_eq!(fee(dec!(0), dec!(0.50), dec!(100)), dec!(0));
+    }
+}
@@ -8,19 +8,215 @@
 //! NOTE: We use the /events endpoint with date filtering to find markets
 //! expiring soon. The /markets endpoint doesn't support date filtering.
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
 use futures::future::join_all;
-use reqwest::Client;
+use governor::{DefaultDirectRateLimiter, Quota};
+use reqwest::header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use reqwest::{Client, StatusCode, Url};
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::str::FromStr;
-use std::sync::Arc;
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::Semaphore;
 
+use crate::market_feed::Backoff;
+
+/// Events fetched per page of a market search.
+const SEARCH_PAGE_SIZE: usize = 100;
+
+/// Most pages one market search fetches.
+const MAX_SEARCH_PAGES: usize = 20;
+
+/// How long a response is served from the cache before it is revalidated.
+pub const DEFAULT_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Cached responses unused for this long are dropped.
+const CACHE_MAX_IDLE: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// First retry delay of a failed request (before jitter).
+const RETRY_BACKOFF_BASE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Longest retry delay, and longest `Retry-After` honored.
+const RETRY_BACKOFF_MAX: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Gamma API client for fetching market metadata.
+///
+/// GET responses are cached by URL: for the cache TTL they are reused
+/// without a request, then revalidated with `If-None-Match` or
+/// `If-Modified-Since` when the API sent an `ETag` or `Last-Modified`, so an
+/// unchanged payload costs a `304 Not Modified` instead of a download.
+///
+/// Requests follow the client's [`RequestPolicy`]: each request has a
+/// timeout, timeouts, connection failures, 5xx and 429 responses are retried
+/// after a jittered backoff, and every fetch of the client (clones included)
+/// draws on one request budget, which a 429 pauses for all of them.
+#[derive(Clone)]
 pub struct GammaClient {
     client: Client,
     base_url: String,
+    cache: Arc<ResponseCache>,
+    policy: RequestPolicy,
+    budget: Arc<RequestBudget>,
+    parse_stats: Arc<Mutex<ParseStats>>,
+}
+
+/// Timeouts, retries and request budget of a [`GammaClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// Timeout of one request
+    pub timeout: std::time::Duration,
+    /// Retries of a request that timed out, failed to connect or got a 5xx
+    /// or 429 before giving up
+    pub max_retries: u32,
+    /// Requests per second across every fetch of the client (0 = unlimited)
+    pub requests_per_second: u32,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        // Under Gamma's /events limit of 500 requests per 10s
+        Self { timeout: std::time::Duration::from_secs(10), max_retries: 3, requests_per_second: 40 }
+    }
+}
+
+/// The request budget shared by every fetch of a client.
+struct RequestBudget {
+    limiter: Option<DefaultDirectRateLimiter>,
+    /// No requests until then (after a 429)
+    paused_until: Mutex<Option<tokio::time::Instant>>,
+}
+
+impl RequestBudget {
+    fn new(requests_per_second: u32) -> Self {
+        let limiter = NonZeroU32::new(requests_per_second)
+            .map(|rate| DefaultDirectRateLimiter::direct(Quota::per_second(rate)));
+        Self { limiter, paused_until: Mutex::new(None) }
+    }
+
+    /// Wait out any 429 pause, then for the budget.
+    async fn acquire(&self) {
+        loop {
+            let paused_until = *self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+            match paused_until {
+                Some(until) if until > tokio::time::Instant::now() => tokio::time::sleep_until(until).await,
+                _ => break,
+            }
+        }
+        if let Some(limiter) = &self.limiter {
+            limiter.until_ready().await;
+        }
+    }
+
+    /// Hold back every request for `delay` after a 429.
+    fn pause(&self, delay: std::time::Duration) {
+        let until = tokio::time::Instant::now() + delay;
+        let mut paused_until = self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        *paused_until = Some(paused_until.map_or(until, |t| t.max(until)));
+    }
+}
+
+/// Why one attempt at a request failed.
+enum SendError {
+    /// Not worth retrying
+    Fatal(GammaError),
+    /// Timed out, failed to connect or got a 5xx
+    Transient(GammaError),
+    /// Got a 429, with how long the API asked to wait
+    RateLimited(GammaError, Option<std::time::Duration>),
+}
+
+/// Timeouts and connection failures are worth retrying, other errors aren't.
+fn classify(e: reqwest::Error) -> SendError {
+    let transient = e.is_timeout() || e.is_connect();
+    let error = GammaError::RequestError(e.to_string());
+    if transient {
+        SendError::Transient(error)
+    } else {
+        SendError::Fatal(error)
+    }
+}
+
+/// `delay` scaled by a random factor in [0.5, 1), so fetchers failing
+/// together don't retry together.
+fn jittered(delay: std::time::Duration) -> std::time::Duration {
+    let random = RandomState::new().build_hasher().finish();
+    delay.mul_f64(0.5 + (random % 1000) as f64 / 2000.0)
+}
+
+/// A cached response body with its validators.
+struct CachedResponse {
+    body: Arc<str>,
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    /// When the body was last downloaded or revalidated
+    checked_at: Instant,
+}
+
+/// Gamma API responses by URL.
+struct ResponseCache {
+    ttl: std::time::Duration,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    fn new(ttl: std::time::Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    fn with_entries<R>(&self, f: impl FnOnce(&mut HashMap<String, CachedResponse>) -> R) -> R {
+        f(&mut self.entries.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// The cached body of `url` while fresh.
+    fn fresh(&self, url: &str) -> Option<Arc<str>> {
+        self.with_entries(|entries| {
+            let cached = entries.get(url)?;
+            (cached.checked_at.elapsed() < self.ttl).then(|| cached.body.clone())
+        })
+    }
+
+    /// Add the validators of a stale cached `url` to `request`.
+    fn conditional(&self, url: &str, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        self.with_entries(|entries| {
+            if let Some(cached) = entries.get(url) {
+                if let Some(etag) = &cached.etag {
+                    request = request.header(IF_NONE_MATCH, etag.clone());
+                }
+                if let Some(last_modified) = &cached.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified.clone());
+                }
+            }
+            request
+        })
+    }
+
+    /// The cached body of `url` after a `304 Not Modified`, fresh again.
+    fn revalidated(&self, url: &str) -> Option<Arc<str>> {
+        self.with_entries(|entries| {
+            let cached = entries.get_mut(url)?;
+            cached.checked_at = Instant::now();
+            Some(cached.body.clone())
+        })
+    }
+
+    fn store(
+        &self,
+        url: &str,
+        body: Arc<str>,
+        etag: Option<HeaderValue>,
+        last_modified: Option<HeaderValue>,
+    ) {
+        self.with_entries(|entries| {
+            entries.retain(|_, cached| cached.checked_at.elapsed() < CACHE_MAX_IDLE);
+            let cached = CachedResponse { body, etag, last_modified, checked_at: Instant::now() };
+            entries.insert(url.to_string(), cached);
+        })
+    }
 }
 
 /// Market data from Gamma API.
@@ -44,8 +240,18 @@ pub struct GammaMarket {
     pub closed: bool,
     /// Total liquidity in USDC (from Gamma API)
     pub liquidity: Option<f64>,
+    /// Lifetime volume in USDC (from Gamma API)
+    pub volume: Option<f64>,
     /// Market category (e.g., "politics", "crypto", "esports", "sports")
     pub category: Option<String>,
+    /// Slug of the parent event (groups the outcomes of a multi-outcome event)
+    pub event_slug: Option<String>,
+    /// Whether the market belongs to a negRisk (mutually exclusive outcomes) event
+    pub neg_risk: bool,
+    /// NegRisk adapter market ID of the parent event (negRisk markets only)
+    pub neg_risk_market_id: Option<String>,
+    /// NegRisk adapter question ID of this market (negRisk markets only)
+    pub question_id: Option<String>,
 }
 
 impl GammaMarket {
@@ -71,14 +277,320 @@ impl GammaMarket {
             .max_by(|(_, a), (_, b)| a.cmp(b))
             .map(|(i, _)| i)
     }
+
+    /// Get the index of the "Yes" outcome (falls back to the first outcome).
+    pub fn yes_index(&self) -> usize {
+        self.outcomes
+            .iter()
+            .position(|o| o.eq_ignore_ascii_case("yes"))
+            .unwrap_or(0)
+    }
+
+    /// Index of this market's question in its negRisk event, the last byte
+    /// of the question ID (the adapter derives question IDs from the market
+    /// ID plus the index).
+    pub fn neg_risk_question_index(&self) -> Option<u8> {
+        let hex = self.question_id.as_deref()?.strip_prefix("0x")?;
+        if hex.len() != 64 {
+            return None;
+        }
+        u8::from_str_radix(&hex[62..], 16).ok()
+    }
+}
+
+/// Hash of everything market info is built from, to tell whether a refresh
+/// of `markets` changed anything.
+pub fn fingerprint<'a>(markets: impl IntoIterator<Item = &'a GammaMarket>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for m in markets {
+        (&m.question, &m.slug, m.end_date, &m.outcomes).hash(&mut hasher);
+        (&m.outcome_prices, &m.clob_token_ids).hash(&mut hasher);
+        (m.active, m.closed, m.liquidity.map(f64::to_bits), m.volume.map(f64::to_bits)).hash(&mut hasher);
+        (&m.category, &m.event_slug, m.neg_risk, &m.neg_risk_market_id, &m.question_id).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// The markets of a negRisk event, one per mutually exclusive outcome.
+///
+/// Exactly one market resolves YES, so the YES prices of an event sum to ~1
+/// and, through the NegRisk adapter, NO shares of `k` of its outcomes convert
+/// into `k - 1` USDC plus a YES share of every other outcome.
+#[derive(Debug, Clone)]
+pub struct NegRiskEvent {
+    /// Event slug
+    pub slug: String,
+    /// NegRisk adapter market ID (what conversions are sent against)
+    pub market_id: Option<String>,
+    /// The event's open markets, by question index where known
+    pub markets: Vec<GammaMarket>,
+}
+
+impl NegRiskEvent {
+    /// Group negRisk markets by parent event, ordered by slug. Markets that
+    /// aren't negRisk or have no event are left out.
+    pub fn group(markets: &[GammaMarket]) -> Vec<NegRiskEvent> {
+        let mut events: BTreeMap<&str, NegRiskEvent> = BTreeMap::new();
+        for market in markets.iter().filter(|m| m.neg_risk) {
+            let Some(slug) = market.event_slug.as_deref() else {
+                continue;
+            };
+            let event = events.entry(slug).or_insert_with(|| NegRiskEvent {
+                slug: slug.to_string(),
+                market_id: None,
+                markets: Vec::new(),
+            });
+            if event.market_id.is_none() {
+                event.market_id = market.neg_risk_market_id.clone();
+            }
+            event.markets.push(market.clone());
+        }
+        for event in events.values_mut() {
+            event.markets.sort_by_key(|m| m.neg_risk_question_index());
+        }
+        events.into_values().collect()
+    }
+
+    /// YES token of each market, in market order.
+    pub fn yes_tokens(&self) -> Vec<&str> {
+        self.markets
+            .iter()
+            .filter_map(|m| m.clob_token_ids.get(m.yes_index()))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Volume and liquidity floors for market searches (in USDC, None = no floor).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketFilter {
+    pub min_volume: Option<f64>,
+    pub min_liquidity: Option<f64>,
+}
+
+impl MarketFilter {
+    /// Whether a market clears both floors (a market without the figure doesn't).
+    pub fn keep(&self, market: &GammaMarket) -> bool {
+        let clears = |min: Option<f64>, value: Option<f64>| {
+            min.is_none_or(|min| value.is_some_and(|value| value >= min))
+        };
+        clears(self.min_volume, market.volume) && clears(self.min_liquidity, market.liquidity)
+    }
+}
+
+/// Market detail from the Gamma API `/markets` endpoint.
+///
+/// The bulk discovery payloads (`/events`, `/series`) only carry what market
+/// selection needs; this is fetched on demand for markets the engine trades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketDetail {
+    /// CTF condition ID
+    pub condition_id: Option<String>,
+    /// Lifetime volume in USDC
+    pub volume: Option<f64>,
+    /// 24h volume in USDC
+    pub volume_24hr: Option<f64>,
+    /// Current best ask - best bid
+    pub spread: Option<f64>,
+    /// Minimum order size (shares)
+    pub order_min_size: Option<f64>,
+    /// Minimum price increment
+    pub tick_size: Option<f64>,
+    /// UMA resolution bond in USDC
+    pub uma_bond: Option<f64>,
+    /// UMA proposer reward in USDC
+    pub uma_reward: Option<f64>,
+    /// Where the resolution outcome is sourced from
+    pub resolution_source: Option<String>,
+    /// When this detail was fetched
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A market together with its full detail.
+#[derive(Debug, Clone)]
+pub struct GammaMarketDetail {
+    pub market: GammaMarket,
+    pub detail: MarketDetail,
+}
+
+/// Why a market in a Gamma payload was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SkipReason {
+    /// A field had the wrong type or format
+    Malformed,
+    /// No outcome prices
+    NoPrices,
+    /// No CLOB token IDs
+    NoTokenIds,
+    /// Outcomes, prices and token IDs differ in count
+    OutcomeMismatch,
+    /// A price outside [0, 1]
+    PriceOutOfRange,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            SkipReason::Malformed => "malformed",
+            SkipReason::NoPrices => "no_prices",
+            SkipReason::NoTokenIds => "no_token_ids",
+            SkipReason::OutcomeMismatch => "outcome_mismatch",
+            SkipReason::PriceOutOfRange => "price_out_of_range",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A market that couldn't be read from a Gamma payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketParseError {
+    /// Market slug, when it could be read
+    pub slug: Option<String>,
+    pub reason: SkipReason,
+    /// What was wrong, for logs
+    pub detail: String,
+}
+
+impl MarketParseError {
+    fn new(slug: Option<String>, reason: SkipReason, detail: impl Into<String>) -> Self {
+        Self { slug, reason, detail: detail.into() }
+    }
+}
+
+impl std::fmt::Display for MarketParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slug = self.slug.as_deref().unwrap_or("<unknown>");
+        write!(f, "market {} skipped ({}): {}", slug, self.reason, self.detail)
+    }
+}
+
+impl std::error::Error for MarketParseError {}
+
+/// Counts of markets read from Gamma payloads and of those skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Markets read
+    pub parsed: usize,
+    /// Markets skipped, by reason
+    pub skipped: BTreeMap<SkipReason, usize>,
+    /// Events skipped whole (their markets aren't counted)
+    pub skipped_events: usize,
+}
+
+impl ParseStats {
+    /// Markets skipped for any reason.
+    pub fn skipped_markets(&self) -> usize {
+        self.skipped.values().sum()
+    }
+
+    fn skip(&mut self, error: &MarketParseError) {
+        tracing::debug!(error = %error, "Skipped Gamma market");
+        *self.skipped.entry(error.reason).or_default() += 1;
+    }
+
+    /// The events of `entries` that parsed, counting the others.
+    fn events<T>(&mut self, entries: Entries<T>) -> Vec<T> {
+        entries
+            .0
+            .into_iter()
+            .filter_map(|entry| {
+                entry
+                    .inspect_err(|e| {
+                        tracing::debug!(slug = ?e.slug, error = e.message.as_str(), "Skipped Gamma event");
+                        self.skipped_events += 1;
+                    })
+                    .ok()
+            })
+            .collect()
+    }
+
+    fn merge(&mut self, other: &ParseStats) {
+        self.parsed += other.parsed;
+        for (reason, count) in &other.skipped {
+            *self.skipped.entry(*reason).or_default() += count;
+        }
+        self.skipped_events += other.skipped_events;
+    }
+}
+
+/// A list read one element at a time, so a malformed element is reported
+/// instead of failing the whole payload.
+#[derive(Debug)]
+struct Entries<T>(Vec<Result<T, EntryError>>);
+
+/// An element of [`Entries`] that didn't deserialize.
+#[derive(Debug)]
+struct EntryError {
+    slug: Option<String>,
+    message: String,
+}
+
+impl<T> Default for Entries<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Entries<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Option::<Vec<serde_json::Value>>::deserialize(deserializer)?.unwrap_or_default();
+        let entries = values
+            .into_iter()
+            .map(|value| {
+                let slug = value.get("slug").and_then(|s| s.as_str()).map(String::from);
+                T::deserialize(value).map_err(|e| EntryError { slug, message: e.to_string() })
+            })
+            .collect();
+        Ok(Self(entries))
+    }
 }
 
 /// Raw event response from Gamma API /events endpoint.
 #[derive(Debug, Deserialize)]
 struct RawGammaEvent {
-    #[serde(rename = "endDate")]
-    end_date: Option<String>,
-    markets: Option<Vec<RawGammaMarket>>,
+    slug: Option<String>,
+    #[serde(rename = "endDate", default, deserialize_with = "gamma_datetime")]
+    end_date: Option<DateTime<Utc>>,
+    #[serde(rename = "negRisk")]
+    neg_risk: Option<bool>,
+    #[serde(rename = "negRiskMarketID")]
+    neg_risk_market_id: Option<String>,
+    #[serde(default)]
+    markets: Entries<RawGammaMarket>,
+}
+
+/// Event-level fields inherited by each market of the event.
+#[derive(Default)]
+struct EventFields {
+    slug: Option<String>,
+    end_date: Option<DateTime<Utc>>,
+    neg_risk: bool,
+    neg_risk_market_id: Option<String>,
+}
+
+impl EventFields {
+    fn of(event: &RawGammaEvent) -> Self {
+        Self {
+            slug: event.slug.clone(),
+            end_date: event.end_date,
+            neg_risk: event.neg_risk.unwrap_or(false),
+            neg_risk_market_id: event.neg_risk_market_id.clone(),
+        }
+    }
+}
+
+/// Raw response from Gamma API /public-search endpoint.
+#[derive(Debug, Deserialize)]
+struct RawSearchResults {
+    #[serde(default)]
+    events: Entries<RawGammaEvent>,
+    pagination: Option<RawPagination>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPagination {
+    #[serde(rename = "hasMore", default)]
+    has_more: bool,
 }
 
 /// Raw series response from Gamma API /series endpoint.
@@ -90,8 +602,10 @@ struct RawGammaSeries {
     title: Option<String>,
     recurrence: Option<String>,
     #[allow(dead_code)]
+    #[serde(default, deserialize_with = "lenient_f64")]
     liquidity: Option<f64>,
-    events: Option<Vec<RawSeriesEvent>>,
+    #[serde(default)]
+    events: Entries<RawSeriesEvent>,
 }
 
 /// Raw event within a series.
@@ -100,12 +614,13 @@ struct RawSeriesEvent {
     slug: Option<String>,
     #[allow(dead_code)]
     title: Option<String>,
-    #[serde(rename = "endDate")]
-    end_date: Option<String>,
+    #[serde(rename = "endDate", default, deserialize_with = "gamma_datetime")]
+    end_date: Option<DateTime<Utc>>,
     #[allow(dead_code)]
     active: Option<bool>,
     closed: Option<bool>,
     #[allow(dead_code)]
+    #[serde(default, deserialize_with = "lenient_f64")]
     liquidity: Option<f64>,
 }
 
@@ -114,19 +629,110 @@ struct RawSeriesEvent {
 struct RawGammaMarket {
     question: Option<String>,
     slug: Option<String>,
-    #[serde(rename = "endDate")]
-    end_date: Option<String>,
-    outcomes: Option<String>,  // JSON-encoded array
-    #[serde(rename = "outcomePrices")]
-    outcome_prices: Option<String>,  // JSON-encoded array
-    #[serde(rename = "clobTokenIds")]
-    clob_token_ids: Option<String>,  // JSON-encoded array
+    #[serde(rename = "endDate", default, deserialize_with = "gamma_datetime")]
+    end_date: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "stringified_array")]
+    outcomes: Vec<String>,
+    #[serde(rename = "outcomePrices", default, deserialize_with = "stringified_array")]
+    outcome_prices: Vec<Decimal>,
+    #[serde(rename = "clobTokenIds", default, deserialize_with = "stringified_array")]
+    clob_token_ids: Vec<String>,
     active: Option<bool>,
     closed: Option<bool>,
-    /// Total liquidity in USDC (as string from API)
-    liquidity: Option<String>,
+    /// Total liquidity in USDC
+    #[serde(default, deserialize_with = "lenient_f64")]
+    liquidity: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    volume: Option<f64>,
     /// Market category
     category: Option<String>,
+    #[serde(rename = "negRisk")]
+    neg_risk: Option<bool>,
+    #[serde(rename = "negRiskMarketID")]
+    neg_risk_market_id: Option<String>,
+    #[serde(rename = "questionID")]
+    question_id: Option<String>,
+}
+
+/// Raw market response from the Gamma API /markets endpoint (full detail).
+#[derive(Debug, Deserialize)]
+struct RawGammaMarketDetail {
+    #[serde(flatten)]
+    market: RawGammaMarket,
+    #[serde(rename = "conditionId")]
+    condition_id: Option<String>,
+    #[serde(rename = "volume24hr", default, deserialize_with = "lenient_f64")]
+    volume_24hr: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    spread: Option<f64>,
+    #[serde(rename = "orderMinSize", default, deserialize_with = "lenient_f64")]
+    order_min_size: Option<f64>,
+    #[serde(rename = "orderPriceMinTickSize", default, deserialize_with = "lenient_f64")]
+    tick_size: Option<f64>,
+    #[serde(rename = "umaBond", default, deserialize_with = "lenient_f64")]
+    uma_bond: Option<f64>,
+    #[serde(rename = "umaReward", default, deserialize_with = "lenient_f64")]
+    uma_reward: Option<f64>,
+    #[serde(rename = "resolutionSource")]
+    resolution_source: Option<String>,
+    /// Parent event(s); only slug and negRisk are used
+    #[serde(default)]
+    events: Entries<RawGammaEvent>,
+}
+
+/// Deserialize a number the API sends either as a JSON number or a string
+/// (an empty string or null is no number).
+fn lenient_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::Number(n)) => Ok(n.as_f64()),
+        Some(serde_json::Value::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(serde_json::Value::String(s)) => s
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("invalid number {:?}", s))),
+        Some(other) => Err(D::Error::custom(format!("expected a number, got {}", other))),
+    }
+}
+
+/// Deserialize an array the API sends JSON-encoded in a string (such as
+/// `"[\"Yes\", \"No\"]"`) or as a plain array (an empty string or null is
+/// an empty array).
+fn stringified_array<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    use serde::de::Error;
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(Vec::new()),
+        Some(serde_json::Value::String(s)) if s.trim().is_empty() => Ok(Vec::new()),
+        Some(serde_json::Value::String(s)) => serde_json::from_str(&s)
+            .map_err(|e| D::Error::custom(format!("invalid JSON-encoded array {:?}: {}", s, e))),
+        Some(array @ serde_json::Value::Array(_)) => serde_json::from_value(array).map_err(D::Error::custom),
+        Some(other) => Err(D::Error::custom(format!("expected an array, got {}", other))),
+    }
+}
+
+/// Deserialize a date in any of the formats the API sends (an empty string
+/// or null is no date).
+fn gamma_datetime<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(s) if s.trim().is_empty() => Ok(None),
+        Some(s) => parse_datetime(&s)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid date {:?}", s))),
+    }
 }
 
 /// Error type for Gamma API operations.
@@ -155,10 +761,7 @@ impl std::error::Error for GammaError {}
 impl GammaClient {
     /// Create a new Gamma client with default base URL.
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-            base_url: "https://gamma-api.polymarket.com".to_string(),
-        }
+        Self::with_base_url("https://gamma-api.polymarket.com")
     }
 
     /// Create a new Gamma client with custom base URL.
@@ -166,9 +769,32 @@ impl GammaClient {
         Self {
             client: Client::new(),
             base_url: base_url.to_string(),
+            cache: Arc::new(ResponseCache::new(DEFAULT_CACHE_TTL)),
+            policy: RequestPolicy::default(),
+            budget: Arc::new(RequestBudget::new(RequestPolicy::default().requests_per_second)),
+            parse_stats: Arc::new(Mutex::new(ParseStats::default())),
         }
     }
 
+    /// Serve cached responses for `ttl` before revalidating them (zero
+    /// revalidates every request).
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache = Arc::new(ResponseCache::new(ttl));
+        self
+    }
+
+    /// Use `policy` for timeouts, retries and the request budget.
+    pub fn with_request_policy(mut self, policy: RequestPolicy) -> Self {
+        self.budget = Arc::new(RequestBudget::new(policy.requests_per_second));
+        self.policy = policy;
+        self
+    }
+
+    /// Markets read and skipped by every fetch of this client so far.
+    pub fn parse_stats(&self) -> ParseStats {
+        self.parse_stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// Fetch events with markets expiring in a time window.
     ///
     /// Uses the /events endpoint with end_date_min and end_date_max filtering.
@@ -178,6 +804,7 @@ impl GammaClient {
         end_date_min: DateTime<Utc>,
         end_date_max: DateTime<Utc>,
         limit: usize,
+        stats: &mut ParseStats,
     ) -> Result<Vec<RawGammaEvent>, GammaError> {
         let batch_size = 100;
         let min_str = end_date_min.format("%Y-%m-%dT%H:%M:%SZ").to_string();
@@ -195,28 +822,10 @@ impl GammaClient {
             self.base_url, batch_size, min_str, max_str
         );
 
-        let response = self
-            .client
-            .get(&first_url)
-            .send()
-            .await
-            .map_err(|e| GammaError::RequestError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(GammaError::RequestError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.status().canonical_reason().unwrap_or("Unknown")
-            )));
-        }
-
-        let first_batch: Vec<RawGammaEvent> = response
-            .json()
-            .await
-            .map_err(|e| GammaError::ParseError(e.to_string()))?;
+        let first_batch: Entries<RawGammaEvent> = self.fetch_json(&first_url).await?;
 
-        if first_batch.is_empty() || first_batch.len() < batch_size {
-            return Ok(first_batch);
+        if first_batch.0.len() < batch_size {
+            return Ok(stats.events(first_batch));
         }
 
         // Fetch remaining pages in parallel
@@ -227,7 +836,7 @@ impl GammaClient {
         for page in 1..num_pages {
             let offset = page * batch_size;
             let sem = semaphore.clone();
-            let client = self.client.clone();
+            let gamma = self.clone();
             let url = format!(
                 "{}/events?closed=false&limit={}&offset={}&order=endDate&ascending=true&end_date_min={}&end_date_max={}",
                 self.base_url, batch_size, offset, min_str, max_str
@@ -235,20 +844,20 @@ impl GammaClient {
 
             futures.push(async move {
                 let _permit = sem.acquire().await.ok()?;
-                let response = client.get(&url).send().await.ok()?;
-                if !response.status().is_success() {
-                    return None;
-                }
-                response.json::<Vec<RawGammaEvent>>().await.ok()
+                gamma
+                    .fetch_json::<Entries<RawGammaEvent>>(&url)
+                    .await
+                    .inspect_err(|e| tracing::warn!(offset, error = %e, "Failed to fetch Gamma events page"))
+                    .ok()
             });
         }
 
         let results = join_all(futures).await;
 
         // Combine all results
-        let mut all_events = first_batch;
+        let mut all_events = stats.events(first_batch);
         for result in results.into_iter().flatten() {
-            all_events.extend(result);
+            all_events.extend(stats.events(result));
             if all_events.len() >= limit {
                 break;
             }
@@ -268,11 +877,17 @@ impl GammaClient {
         min_certainty: Decimal,
     ) -> Result<Vec<GammaMarket>, GammaError> {
         let now = Utc::now();
-        // Look for markets with endDate recently passed (resolving now) or about to pass
-        let end_date_min = now - Duration::hours(3);
-        let end_date_max = now + Duration::hours(max_hours_to_expiry as i64 + 1);
-
-        let events = self.fetch_events_in_window(end_date_min, end_date_max, 500).await?;
+        // Look for markets with endDate recently passed (resolving now) or about to pass.
+        // The window is widened to whole hours so its URLs (and cached responses)
+        // hold for an hour; candidates are filtered by expiry below.
+        let hour = Duration::hours(1);
+        let earliest = now - Duration::hours(3);
+        let latest = now + Duration::hours(max_hours_to_expiry as i64 + 1);
+        let end_date_min = earliest.duration_trunc(hour).unwrap_or(earliest);
+        let end_date_max = latest.duration_trunc(hour).map_or(latest, |end| end + hour);
+
+        let mut stats = ParseStats::default();
+        let events = self.fetch_events_in_window(end_date_min, end_date_max, 500, &mut stats).await?;
 
         tracing::info!(
             event_count = events.len(),
@@ -282,32 +897,19 @@ impl GammaClient {
         let mut candidates = Vec::new();
 
         for event in events {
-            let event_end_date = event.end_date.as_ref();
-
-            if let Some(markets) = event.markets {
-                for raw_market in markets {
-                    // Skip inactive or closed markets
-                    if !raw_market.active.unwrap_or(false) || raw_market.closed.unwrap_or(true) {
-                        continue;
-                    }
-
-                    // Use market end_date, fall back to event end_date
-                    let end_date_str = raw_market.end_date.clone().or_else(|| event_end_date.cloned());
-
-                    if let Ok(market) = self.parse_market_with_end_date(raw_market, end_date_str.as_ref()) {
-                        // Check hours until expiry: must be positive (not yet expired) and within max_hours
-                        if let Some(hours) = market.hours_until_expiry() {
-                            if hours > 0.0 && hours <= max_hours_to_expiry {
-                                // Check for high certainty outcome
-                                if market.has_high_certainty_outcome(min_certainty) {
-                                    candidates.push(market);
-                                }
-                            }
+            for market in self.event_markets(event, &mut stats) {
+                // Check hours until expiry: must be positive (not yet expired) and within max_hours
+                if let Some(hours) = market.hours_until_expiry() {
+                    if hours > 0.0 && hours <= max_hours_to_expiry {
+                        // Check for high certainty outcome
+                        if market.has_high_certainty_outcome(min_certainty) {
+                            candidates.push(market);
                         }
                     }
                 }
             }
         }
+        self.record(&stats, "events");
 
         tracing::info!(
             candidate_count = candidates.len(),
@@ -331,25 +933,7 @@ impl GammaClient {
         // Fetch all series
         let url = format!("{}/series?limit=200", self.base_url);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GammaError::RequestError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(GammaError::RequestError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.status().canonical_reason().unwrap_or("Unknown")
-            )));
-        }
-
-        let series_list: Vec<RawGammaSeries> = response
-            .json()
-            .await
-            .map_err(|e| GammaError::ParseError(e.to_string()))?;
+        let series_list: Vec<RawGammaSeries> = self.fetch_json(&url).await?;
 
         // Filter to recurring series (daily, hourly, etc.)
         let recurring_series: Vec<_> = series_list
@@ -369,31 +953,29 @@ impl GammaClient {
 
         let now = Utc::now();
         let max_end = now + Duration::hours(max_hours_to_expiry as i64 + 1);
+        let mut stats = ParseStats::default();
 
         // Collect all event slugs that need to be fetched
         let mut event_slugs: Vec<String> = Vec::new();
 
-        for series in &recurring_series {
-            if let Some(events) = &series.events {
-                for event in events {
-                    // Skip closed events
-                    if event.closed.unwrap_or(true) {
+        for series in recurring_series {
+            for event in stats.events(series.events) {
+                // Skip closed events
+                if event.closed.unwrap_or(true) {
+                    continue;
+                }
+
+                // Check end date
+                if let Some(end) = event.end_date {
+                    // Must be in the future and within max_hours window
+                    if end <= now || end > max_end {
                         continue;
                     }
 
-                    // Check end date
-                    let end_date = event.end_date.as_ref().and_then(|s| parse_datetime(s));
-                    if let Some(end) = end_date {
-                        // Must be in the future and within max_hours window
-                        if end <= now || end > max_end {
-                            continue;
-                        }
-
-                        // Collect event slug for fetching
-                        let event_slug = event.slug.clone().unwrap_or_default();
-                        if !event_slug.is_empty() {
-                            event_slugs.push(event_slug);
-                        }
+                    // Collect event slug for fetching
+                    let event_slug = event.slug.unwrap_or_default();
+                    if !event_slug.is_empty() {
+                        event_slugs.push(event_slug);
                     }
                 }
             }
@@ -410,19 +992,17 @@ impl GammaClient {
 
         for slug in event_slugs {
             let sem = semaphore.clone();
-            let client = self.client.clone();
-            let base_url = self.base_url.clone();
+            let gamma = self.clone();
 
             futures.push(async move {
                 let _permit = sem.acquire().await.ok()?;
-                let url = format!("{}/events?slug={}", base_url, slug);
-
-                let response = client.get(&url).send().await.ok()?;
-                if !response.status().is_success() {
-                    return None;
-                }
+                let url = format!("{}/events?slug={}", gamma.base_url, slug);
 
-                let events: Vec<RawGammaEvent> = response.json().await.ok()?;
+                let events: Entries<RawGammaEvent> = gamma
+                    .fetch_json(&url)
+                    .await
+                    .inspect_err(|e| tracing::warn!(event = slug.as_str(), error = %e, "Failed to fetch recurring event"))
+                    .ok()?;
                 Some((slug, events))
             });
         }
@@ -435,31 +1015,19 @@ impl GammaClient {
         for result in results.into_iter().flatten() {
             let (event_slug, events) = result;
 
-            for event in events {
-                let event_end_date = event.end_date.as_ref();
-
-                if let Some(raw_markets) = event.markets {
-                    for raw_market in raw_markets {
-                        if !raw_market.active.unwrap_or(false) || raw_market.closed.unwrap_or(true) {
-                            continue;
-                        }
-
-                        let end_date_str = raw_market.end_date.clone().or_else(|| event_end_date.cloned());
-
-                        if let Ok(market) = self.parse_market_with_end_date(raw_market, end_date_str.as_ref()) {
-                            // Check hours until expiry
-                            if let Some(hours) = market.hours_until_expiry() {
-                                if hours > 0.0 && hours <= max_hours_to_expiry {
-                                    if market.has_high_certainty_outcome(min_certainty) {
-                                        candidates.push(market);
-                                    } else {
-                                        tracing::debug!(
-                                            question = market.question.as_str(),
-                                            hours_left = format!("{:.2}", hours).as_str(),
-                                            "Recurring market (below certainty threshold)"
-                                        );
-                                    }
-                                }
+            for event in stats.events(events) {
+                for market in self.event_markets(event, &mut stats) {
+                    // Check hours until expiry
+                    if let Some(hours) = market.hours_until_expiry() {
+                        if hours > 0.0 && hours <= max_hours_to_expiry {
+                            if market.has_high_certainty_outcome(min_certainty) {
+                                candidates.push(market);
+                            } else {
+                                tracing::debug!(
+                                    question = market.question.as_str(),
+                                    hours_left = format!("{:.2}", hours).as_str(),
+                                    "Recurring market (below certainty threshold)"
+                                );
                             }
                         }
                     }
@@ -471,6 +1039,7 @@ impl GammaClient {
                 "Processed recurring event"
             );
         }
+        self.record(&stats, "series");
 
         tracing::info!(
             candidate_count = candidates.len(),
@@ -480,115 +1049,414 @@ impl GammaClient {
         Ok(candidates)
     }
 
+    /// Fetch the markets of active negRisk (multi-outcome) events.
+    ///
+    /// Returns every open market of up to `max_events` of the most-traded
+    /// negRisk events, so the outcomes of each event can be priced as a basket.
+    pub async fn fetch_neg_risk_markets(
+        &self,
+        max_events: usize,
+    ) -> Result<Vec<GammaMarket>, GammaError> {
+        let url = format!(
+            "{}/events?closed=false&active=true&limit={}&order=volume24hr&ascending=false",
+            self.base_url,
+            max_events.min(100)
+        );
+
+        let events: Entries<RawGammaEvent> = self.fetch_json(&url).await?;
+
+        let mut stats = ParseStats::default();
+        let mut markets = Vec::new();
+        let mut event_count = 0;
+
+        for event in stats.events(events) {
+            if !event.neg_risk.unwrap_or(false) {
+                continue;
+            }
+            event_count += 1;
+            markets.extend(self.event_markets(event, &mut stats));
+        }
+        self.record(&stats, "negRisk events");
+
+        tracing::info!(
+            event_count = event_count,
+            market_count = markets.len(),
+            "Fetched negRisk event markets"
+        );
+
+        Ok(markets)
+    }
+
     /// Fetch markets for a specific event by slug.
     #[allow(dead_code)]
     async fn fetch_event_markets(&self, event_slug: &str) -> Result<Vec<GammaMarket>, GammaError> {
         let url = format!("{}/events?slug={}", self.base_url, event_slug);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GammaError::RequestError(e.to_string()))?;
+        let events: Entries<RawGammaEvent> = self.fetch_json(&url).await?;
 
-        if !response.status().is_success() {
-            return Err(GammaError::RequestError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.status().canonical_reason().unwrap_or("Unknown")
-            )));
-        }
-
-        let events: Vec<RawGammaEvent> = response
-            .json()
-            .await
-            .map_err(|e| GammaError::ParseError(e.to_string()))?;
+        let mut stats = ParseStats::default();
+        let markets = stats
+            .events(events)
+            .into_iter()
+            .flat_map(|event| self.event_markets(event, &mut stats))
+            .collect();
+        self.record(&stats, "event");
+        Ok(markets)
+    }
 
+    /// Search open markets by keyword (matched against questions, event
+    /// titles and tags), best matches first. Returns up to `limit` markets
+    /// that pass `filter`, following the result pages as needed.
+    pub async fn search_markets(
+        &self,
+        query: &str,
+        filter: MarketFilter,
+        limit: usize,
+    ) -> Result<Vec<GammaMarket>, GammaError> {
+        let url = format!("{}/public-search", self.base_url);
+        let mut stats = ParseStats::default();
         let mut markets = Vec::new();
 
-        for event in events {
-            let event_end_date = event.end_date.as_ref();
+        for page in 1..=MAX_SEARCH_PAGES {
+            let params = [
+                ("q", query.to_string()),
+                ("events_status", "active".to_string()),
+                ("limit_per_type", SEARCH_PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+            ];
+            let results: RawSearchResults = self.get_json(&url, &params).await?;
+            let has_more = results.pagination.is_some_and(|p| p.has_more);
+            let events = stats.events(results.events);
+            self.collect_markets(events, filter, limit, &mut markets, &mut stats);
+            if !has_more || markets.len() >= limit {
+                break;
+            }
+        }
+        self.record(&stats, "search");
 
-            if let Some(raw_markets) = event.markets {
-                for raw_market in raw_markets {
-                    if !raw_market.active.unwrap_or(false) || raw_market.closed.unwrap_or(true) {
-                        continue;
-                    }
+        tracing::info!(query, market_count = markets.len(), "Searched Gamma markets");
+        Ok(markets)
+    }
 
-                    let end_date_str = raw_market.end_date.clone().or_else(|| event_end_date.cloned());
+    /// Fetch open markets of events carrying any of these Gamma tags (slugs
+    /// such as `crypto` or `nba`), most traded first. Returns up to `limit`
+    /// markets that pass `filter`, following the result pages as needed.
+    pub async fn fetch_by_tags(
+        &self,
+        tags: &[&str],
+        filter: MarketFilter,
+        limit: usize,
+    ) -> Result<Vec<GammaMarket>, GammaError> {
+        let url = format!("{}/events", self.base_url);
+        let mut stats = ParseStats::default();
+        let mut markets = Vec::new();
 
-                    if let Ok(market) = self.parse_market_with_end_date(raw_market, end_date_str.as_ref()) {
-                        markets.push(market);
-                    }
+        'tags: for tag in tags {
+            for page in 0..MAX_SEARCH_PAGES {
+                if markets.len() >= limit {
+                    break 'tags;
+                }
+                let mut params = vec![
+                    ("tag_slug", tag.to_string()),
+                    ("closed", "false".to_string()),
+                    ("active", "true".to_string()),
+                    ("order", "volume24hr".to_string()),
+                    ("ascending", "false".to_string()),
+                    ("limit", SEARCH_PAGE_SIZE.to_string()),
+                    ("offset", (page * SEARCH_PAGE_SIZE).to_string()),
+                ];
+                // Events aggregate their markets, so these only prune whole events
+                if let Some(min) = filter.min_volume {
+                    params.push(("volume_min", min.to_string()));
+                }
+                if let Some(min) = filter.min_liquidity {
+                    params.push(("liquidity_min", min.to_string()));
+                }
+
+                let events: Entries<RawGammaEvent> = self.get_json(&url, &params).await?;
+                let last_page = events.0.len() < SEARCH_PAGE_SIZE;
+                let events = stats.events(events);
+                self.collect_markets(events, filter, limit, &mut markets, &mut stats);
+                if last_page {
+                    break;
                 }
             }
         }
+        self.record(&stats, "tags");
 
+        tracing::info!(tags = ?tags, market_count = markets.len(), "Fetched Gamma markets by tag");
         Ok(markets)
     }
 
-    /// Parse a raw market response into structured data.
-    fn parse_market_with_end_date(
+    /// Fetch open markets in a category (e.g. "Politics", "Crypto"). Gamma's
+    /// categories are top-level tags, so this is [`fetch_by_tags`](Self::fetch_by_tags)
+    /// with the category's slug.
+    pub async fn fetch_by_category(
         &self,
-        raw: RawGammaMarket,
-        fallback_end_date: Option<&String>,
-    ) -> Result<GammaMarket, GammaError> {
-        // Parse outcomes from JSON string
-        let outcomes: Vec<String> = raw
-            .outcomes
-            .as_ref()
-            .and_then(|s| serde_json::from_str(s).ok())
-            .unwrap_or_default();
+        category: &str,
+        filter: MarketFilter,
+        limit: usize,
+    ) -> Result<Vec<GammaMarket>, GammaError> {
+        let slug = category.trim().to_lowercase().replace(' ', "-");
+        self.fetch_by_tags(&[&slug], filter, limit).await
+    }
 
-        // Parse outcome prices from JSON string
-        let outcome_prices: Vec<Decimal> = raw
-            .outcome_prices
-            .as_ref()
-            .and_then(|s| {
-                let strings: Vec<String> = serde_json::from_str(s).ok()?;
-                strings
-                    .iter()
-                    .map(|p| Decimal::from_str(p).ok())
-                    .collect::<Option<Vec<_>>>()
-            })
-            .unwrap_or_default();
+    /// GET an endpoint with query parameters and parse its JSON response.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        params: &[(&str, String)],
+    ) -> Result<T, GammaError> {
+        let url = Url::parse_with_params(url, params).map_err(|e| GammaError::RequestError(e.to_string()))?;
+        self.fetch_json(url.as_str()).await
+    }
 
-        // Skip markets without prices
-        if outcome_prices.is_empty() {
-            return Err(GammaError::InvalidData("No outcome prices".to_string()));
+    /// GET a URL (through the response cache) and parse its JSON response.
+    async fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, GammaError> {
+        let body = self.get(url).await?;
+        serde_json::from_str(&body).map_err(|e| GammaError::ParseError(e.to_string()))
+    }
+
+    /// GET `url`, from the cache while fresh, retrying transient failures
+    /// within the request budget.
+    async fn get(&self, url: &str) -> Result<Arc<str>, GammaError> {
+        if let Some(body) = self.cache.fresh(url) {
+            return Ok(body);
         }
 
-        // Parse CLOB token IDs from JSON string
-        let clob_token_ids: Vec<String> = raw
-            .clob_token_ids
-            .as_ref()
-            .and_then(|s| serde_json::from_str(s).ok())
-            .unwrap_or_default();
+        let mut backoff = Backoff::new(RETRY_BACKOFF_BASE, RETRY_BACKOFF_MAX);
+        loop {
+            self.budget.acquire().await;
+            let (error, retry_after) = match self.send(url).await {
+                Ok(body) => return Ok(body),
+                Err(SendError::Fatal(e)) => return Err(e),
+                Err(SendError::Transient(e)) => (e, None),
+                Err(SendError::RateLimited(e, retry_after)) => (e, Some(retry_after)),
+            };
+            if backoff.attempt() >= self.policy.max_retries {
+                return Err(error);
+            }
+
+            let delay = jittered(backoff.next_delay());
+            tracing::warn!(url, attempt = backoff.attempt(), error = %error, "Gamma request failed, retrying");
+            match retry_after {
+                // Every fetch of the client waits out a 429
+                Some(retry_after) => self.budget.pause(retry_after.map_or(delay, |d| d.min(RETRY_BACKOFF_MAX))),
+                None => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+
+    /// One attempt at GET `url`, revalidating a stale cached response.
+    async fn send(&self, url: &str) -> Result<Arc<str>, SendError> {
+        let request = self.client.get(url).timeout(self.policy.timeout);
+        let response = self.cache.conditional(url, request).send().await.map_err(classify)?;
 
-        // Skip markets without token IDs
-        if clob_token_ids.is_empty() {
-            return Err(GammaError::InvalidData("No token IDs".to_string()));
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            tracing::debug!(url, "Gamma response not modified");
+            return self.cache.revalidated(url).ok_or_else(|| {
+                SendError::Fatal(GammaError::InvalidData("Not Modified without a cached response".to_string()))
+            });
+        }
+        if !status.is_success() {
+            let error = GammaError::RequestError(format!(
+                "HTTP {}: {}",
+                status,
+                status.canonical_reason().unwrap_or("Unknown")
+            ));
+            return Err(if status == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok()?.trim().parse().ok())
+                    .map(std::time::Duration::from_secs);
+                SendError::RateLimited(error, retry_after)
+            } else if status.is_server_error() {
+                SendError::Transient(error)
+            } else {
+                SendError::Fatal(error)
+            });
         }
 
-        // Parse end date (use market's or fallback to event's)
-        let end_date_str = raw.end_date.as_ref().or(fallback_end_date);
-        let end_date = end_date_str.and_then(|s| parse_datetime(s));
+        let etag = response.headers().get(ETAG).cloned();
+        let last_modified = response.headers().get(LAST_MODIFIED).cloned();
+        let body: Arc<str> = response.text().await.map_err(classify)?.into();
+        self.cache.store(url, body.clone(), etag, last_modified);
+        Ok(body)
+    }
+
+    /// Add the open markets of `events` that pass `filter` and aren't listed
+    /// yet, until there are `limit`.
+    fn collect_markets(
+        &self,
+        events: Vec<RawGammaEvent>,
+        filter: MarketFilter,
+        limit: usize,
+        markets: &mut Vec<GammaMarket>,
+        stats: &mut ParseStats,
+    ) {
+        for event in events {
+            for market in self.event_markets(event, stats) {
+                if markets.len() >= limit {
+                    return;
+                }
+                if filter.keep(&market) && !markets.iter().any(|m| m.slug == market.slug) {
+                    markets.push(market);
+                }
+            }
+        }
+    }
+
+    /// The open markets of an event, which inherit its end date, slug and
+    /// negRisk fields. Markets that can't be read are counted in `stats`.
+    fn event_markets(&self, event: RawGammaEvent, stats: &mut ParseStats) -> Vec<GammaMarket> {
+        let event_fields = EventFields::of(&event);
+        let mut markets = Vec::new();
+        for entry in event.markets.0 {
+            let raw = match entry {
+                Ok(raw) => raw,
+                Err(e) => {
+                    stats.skip(&MarketParseError::new(e.slug, SkipReason::Malformed, e.message));
+                    continue;
+                }
+            };
+            if !raw.active.unwrap_or(false) || raw.closed.unwrap_or(true) {
+                continue;
+            }
+            match self.parse_market(raw, &event_fields) {
+                Ok(market) => {
+                    stats.parsed += 1;
+                    markets.push(market);
+                }
+                Err(e) => stats.skip(&e),
+            }
+        }
+        markets
+    }
+
+    /// Log what a fetch skipped and add its counts to the client's.
+    fn record(&self, stats: &ParseStats, source: &str) {
+        if stats.skipped_markets() > 0 || stats.skipped_events > 0 {
+            tracing::warn!(
+                source,
+                parsed = stats.parsed,
+                skipped_markets = stats.skipped_markets(),
+                skipped_events = stats.skipped_events,
+                reasons = ?stats.skipped,
+                "Skipped malformed Gamma data"
+            );
+        }
+        self.parse_stats.lock().unwrap_or_else(|e| e.into_inner()).merge(stats);
+    }
+
+    /// Fetch full detail for the market containing `token_id`.
+    ///
+    /// Returns None if no market has that token.
+    pub async fn fetch_market_by_token(
+        &self,
+        token_id: &str,
+    ) -> Result<Option<GammaMarketDetail>, GammaError> {
+        self.fetch_market_detail(&format!("clob_token_ids={}", token_id))
+            .await
+    }
+
+    /// Fetch full detail for the market with the given slug.
+    ///
+    /// Returns None if no market has that slug.
+    pub async fn fetch_market_by_slug(
+        &self,
+        slug: &str,
+    ) -> Result<Option<GammaMarketDetail>, GammaError> {
+        self.fetch_market_detail(&format!("slug={}", slug)).await
+    }
 
-        // Parse liquidity (comes as string, convert to f64)
-        let liquidity = raw.liquidity.as_ref().and_then(|s| s.parse::<f64>().ok());
+    async fn fetch_market_detail(&self, query: &str) -> Result<Option<GammaMarketDetail>, GammaError> {
+        let url = format!("{}/markets?{}", self.base_url, query);
+
+        let markets: Vec<RawGammaMarketDetail> = self.fetch_json(&url).await?;
+
+        markets
+            .into_iter()
+            .next()
+            .map(|raw| self.parse_market_detail(raw))
+            .transpose()
+    }
+
+    /// Parse a raw /markets response into a market with detail.
+    fn parse_market_detail(&self, raw: RawGammaMarketDetail) -> Result<GammaMarketDetail, GammaError> {
+        let event = raw
+            .events
+            .0
+            .iter()
+            .find_map(|event| event.as_ref().ok())
+            .map(EventFields::of)
+            .unwrap_or_default();
+
+        let detail = MarketDetail {
+            condition_id: raw.condition_id,
+            volume: raw.market.volume,
+            volume_24hr: raw.volume_24hr,
+            spread: raw.spread,
+            order_min_size: raw.order_min_size,
+            tick_size: raw.tick_size,
+            uma_bond: raw.uma_bond,
+            uma_reward: raw.uma_reward,
+            resolution_source: raw.resolution_source.filter(|s| !s.is_empty()),
+            fetched_at: Utc::now(),
+        };
+        let market = self
+            .parse_market(raw.market, &event)
+            .map_err(|e| GammaError::InvalidData(e.to_string()))?;
+
+        Ok(GammaMarketDetail { market, detail })
+    }
+
+    /// Check a raw market and convert it, with the end date, slug and
+    /// negRisk fields of its event as fallbacks.
+    fn parse_market(&self, raw: RawGammaMarket, event: &EventFields) -> Result<GammaMarket, MarketParseError> {
+        let skip = |reason, detail: String| MarketParseError::new(raw.slug.clone(), reason, detail);
+
+        // Skip markets without prices or token IDs
+        if raw.outcome_prices.is_empty() {
+            return Err(skip(SkipReason::NoPrices, "no outcome prices".to_string()));
+        }
+        if raw.clob_token_ids.is_empty() {
+            return Err(skip(SkipReason::NoTokenIds, "no CLOB token IDs".to_string()));
+        }
+        // Prices and token IDs are indexed by outcome
+        let prices = raw.outcome_prices.len();
+        if raw.clob_token_ids.len() != prices || (!raw.outcomes.is_empty() && raw.outcomes.len() != prices) {
+            return Err(skip(
+                SkipReason::OutcomeMismatch,
+                format!(
+                    "{} outcomes, {} prices, {} token IDs",
+                    raw.outcomes.len(),
+                    prices,
+                    raw.clob_token_ids.len()
+                ),
+            ));
+        }
+        if let Some(price) = raw.outcome_prices.iter().find(|p| **p < Decimal::ZERO || **p > Decimal::ONE) {
+            return Err(skip(SkipReason::PriceOutOfRange, format!("price {}", price)));
+        }
 
         Ok(GammaMarket {
             question: raw.question.unwrap_or_default(),
             slug: raw.slug.unwrap_or_default(),
-            end_date,
-            outcomes,
-            outcome_prices,
-            clob_token_ids,
+            end_date: raw.end_date.or(event.end_date),
+            outcomes: raw.outcomes,
+            outcome_prices: raw.outcome_prices,
+            clob_token_ids: raw.clob_token_ids,
             active: raw.active.unwrap_or(false),
             closed: raw.closed.unwrap_or(true),
-            liquidity,
+            liquidity: raw.liquidity,
+            volume: raw.volume,
             category: raw.category,
+            event_slug: event.slug.clone(),
+            neg_risk: raw.neg_risk.unwrap_or(event.neg_risk),
+            neg_risk_market_id: raw.neg_risk_market_id.or_else(|| event.neg_risk_market_id.clone()),
+            question_id: raw.question_id,
         })
     }
 }
@@ -601,15 +1469,23 @@ fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
     }
 
     // Try with Z suffix converted
-    let s_fixed = if s.ends_with('Z') {
-        format!("{}+00:00", &s[..s.len() - 1])
-    } else {
-        s.to_string()
+    let s_fixed = match s.strip_suffix('Z') {
+        Some(stripped) => format!("{}+00:00", stripped),
+        None => s.to_string(),
     };
 
-    DateTime::parse_from_rfc3339(&s_fixed)
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&s_fixed) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    // Postgres-style timestamps ("2025-01-01 12:00:00+00"), then bare dates
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%#z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
         .ok()
-        .map(|dt| dt.with_timezone(&Utc))
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
 }
 
 impl Default for GammaClient {
@@ -635,7 +1511,12 @@ mod tests {
             active: true,
             closed: false,
             liquidity: Some(1000.0),
+            volume: None,
             category: Some("politics".to_string()),
+            event_slug: None,
+            neg_risk: false,
+            neg_risk_market_id: None,
+            question_id: None,
         };
 
         let hours = market.hours_until_expiry().unwrap();
@@ -654,13 +1535,45 @@ mod tests {
             active: true,
             closed: false,
             liquidity: None,
+            volume: None,
             category: None,
+            event_slug: None,
+            neg_risk: false,
+            neg_risk_market_id: None,
+            question_id: None,
         };
 
         assert!(market.has_high_certainty_outcome(dec!(0.95)));
         assert!(!market.has_high_certainty_outcome(dec!(0.96)));
     }
 
+    #[test]
+    fn test_fingerprint_tracks_market_changes() {
+        let market = GammaMarket {
+            question: "Test?".to_string(),
+            slug: "test".to_string(),
+            end_date: None,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: vec![dec!(0.95), dec!(0.05)],
+            clob_token_ids: vec!["123".to_string(), "456".to_string()],
+            active: true,
+            closed: false,
+            liquidity: Some(1000.0),
+            volume: None,
+            category: None,
+            event_slug: None,
+            neg_risk: false,
+            neg_risk_market_id: None,
+            question_id: None,
+        };
+        let mut repriced = market.clone();
+        repriced.outcome_prices = vec![dec!(0.96), dec!(0.04)];
+
+        assert_eq!(fingerprint([&market]), fingerprint([&market.clone()]));
+        assert_ne!(fingerprint([&market]), fingerprint([&repriced]));
+        assert_ne!(fingerprint([&market, &repriced]), fingerprint([&repriced, &market]));
+    }
+
     #[test]
     fn test_highest_certainty_index() {
         let market = GammaMarket {
@@ -673,10 +1586,360 @@ mod tests {
             active: true,
             closed: false,
             liquidity: Some(500.0),
+            volume: None,
             category: Some("crypto".to_string()),
+            event_slug: Some("test-event".to_string()),
+            neg_risk: true,
+            neg_risk_market_id: None,
+            question_id: None,
         };
 
         assert_eq!(market.highest_certainty_index(), Some(1));
+        assert_eq!(market.yes_index(), 0);
+    }
+
+    #[test]
+    fn test_parse_market_detail() {
+        let json = r#"{
+            "question": "Will it rain?",
+            "slug": "will-it-rain",
+            "endDate": "2030-01-01T00:00:00Z",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.62\", \"0.38\"]",
+            "clobTokenIds": "[\"111\", \"222\"]",
+            "active": true,
+            "closed": false,
+            "liquidity": "5000.5",
+            "conditionId": "0xabc",
+            "volume": "123456.78",
+            "volume24hr": 4321.5,
+            "spread": 0.01,
+            "orderMinSize": 5,
+            "orderPriceMinTickSize": 0.001,
+            "umaBond": "500",
+            "umaReward": "5",
+            "resolutionSource": "",
+            "events": [{"slug": "weather", "negRisk": true}]
+        }"#;
+
+        let raw: RawGammaMarketDetail = serde_json::from_str(json).unwrap();
+        let parsed = GammaClient::new().parse_market_detail(raw).unwrap();
+
+        assert_eq!(parsed.market.clob_token_ids, vec!["111", "222"]);
+        assert_eq!(parsed.market.event_slug.as_deref(), Some("weather"));
+        assert!(parsed.market.neg_risk);
+        assert_eq!(parsed.market.liquidity, Some(5000.5));
+        assert_eq!(parsed.detail.condition_id.as_deref(), Some("0xabc"));
+        assert_eq!(parsed.detail.volume, Some(123456.78));
+        assert_eq!(parsed.detail.volume_24hr, Some(4321.5));
+        assert_eq!(parsed.detail.order_min_size, Some(5.0));
+        assert_eq!(parsed.detail.tick_size, Some(0.001));
+        assert_eq!(parsed.detail.uma_bond, Some(500.0));
+        assert!(parsed.detail.resolution_source.is_none());
+    }
+
+    #[test]
+    fn test_group_neg_risk_events() {
+        let market_id = format!("0x{}00", "ab".repeat(31));
+        let market = |slug: &str, event: Option<&str>, index: u8, yes: &str| GammaMarket {
+            question: format!("Will {} win?", slug),
+            slug: slug.to_string(),
+            end_date: None,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: vec![dec!(0.5), dec!(0.5)],
+            clob_token_ids: vec![yes.to_string(), format!("{}-no", yes)],
+            active: true,
+            closed: false,
+            liquidity: None,
+            volume: None,
+            category: None,
+            event_slug: event.map(String::from),
+            neg_risk: event.is_some(),
+            neg_risk_market_id: event.map(|_| market_id.clone()),
+            question_id: event.map(|_| format!("0x{}{:02x}", "ab".repeat(31), index)),
+        };
+        let markets = [
+            market("carol", Some("election"), 2, "c"),
+            market("alice", Some("election"), 0, "a"),
+            market("rain", None, 0, "r"),
+            market("bob", Some("election"), 1, "b"),
+        ];
+        assert_eq!(markets[0].neg_risk_question_index(), Some(2));
+        assert_eq!(markets[2].neg_risk_question_index(), None);
+
+        let events = NegRiskEvent::group(&markets);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].slug, "election");
+        assert_eq!(events[0].market_id.as_deref(), Some(market_id.as_str()));
+        assert_eq!(events[0].yes_tokens(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_search_results_are_filtered_and_deduplicated() {
+        let market = |slug: &str, closed: bool, liquidity: &str, volume: serde_json::Value| {
+            serde_json::json!({
+                "question": format!("{}?", slug),
+                "slug": slug,
+                "outcomes": "[\"Yes\", \"No\"]",
+                "outcomePrices": "[\"0.6\", \"0.4\"]",
+                "clobTokenIds": format!("[\"{}-yes\", \"{}-no\"]", slug, slug),
+                "active": true,
+                "closed": closed,
+                "liquidity": liquidity,
+                "volume": volume,
+            })
+        };
+        let page = serde_json::json!({
+            "events": [
+                {
+                    "slug": "btc-price",
+                    "endDate": "2030-01-01T00:00:00Z",
+                    "markets": [
+                        market("btc-100k", false, "5000", serde_json::json!("250000.5")),
+                        market("btc-200k", false, "50", serde_json::json!(900000)),
+                        market("btc-50k", true, "5000", serde_json::json!(1000000)),
+                    ],
+                },
+                {
+                    "slug": "btc-repeat",
+                    "markets": [market("btc-100k", false, "5000", serde_json::json!(500000))],
+                },
+            ],
+            "pagination": { "hasMore": true, "totalResults": 42 },
+        });
+        let results: RawSearchResults = serde_json::from_value(page).unwrap();
+        assert!(results.pagination.as_ref().unwrap().has_more);
+
+        let client = GammaClient::new();
+        let filter = MarketFilter { min_volume: Some(100000.0), min_liquidity: Some(1000.0) };
+        let mut markets = Vec::new();
+        let mut stats = ParseStats::default();
+        let events = stats.events(results.events);
+        client.collect_markets(events, filter, 10, &mut markets, &mut stats);
+
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].slug, "btc-100k");
+        assert_eq!(markets[0].volume, Some(250000.5));
+        assert_eq!(markets[0].event_slug.as_deref(), Some("btc-price"));
+        assert!(markets[0].end_date.is_some());
+        assert!(MarketFilter::default().keep(&markets[0]));
+        assert!(!MarketFilter { min_volume: Some(1e6), min_liquidity: None }.keep(&markets[0]));
+    }
+
+    #[test]
+    fn test_events_fixture() {
+        let events: Entries<RawGammaEvent> =
+            serde_json::from_str(include_str!("../tests/fixtures/gamma/events.json")).unwrap();
+        let client = GammaClient::new();
+        let mut stats = ParseStats::default();
+        let markets: Vec<GammaMarket> = stats
+            .events(events)
+            .into_iter()
+            .flat_map(|event| client.event_markets(event, &mut stats))
+            .collect();
+
+        let slugs: Vec<&str> = markets.iter().map(|m| m.slug.as_str()).collect();
+        assert_eq!(
+            slugs,
+            [
+                "fed-decreases-interest-rates-by-25-bps-after-december-2025-meeting",
+                "no-change-in-fed-interest-rates-after-december-2025-meeting",
+                "bitcoin-above-100k-on-december-31",
+            ]
+        );
+        assert_eq!(stats.parsed, 3);
+        assert_eq!(stats.skipped_events, 1);
+        assert_eq!(
+            stats.skipped,
+            BTreeMap::from([
+                (SkipReason::Malformed, 1),
+                (SkipReason::NoTokenIds, 1),
+                (SkipReason::OutcomeMismatch, 1),
+                (SkipReason::PriceOutOfRange, 1),
+            ])
+        );
+        assert_eq!(stats.skipped_markets(), 4);
+
+        // Stringified arrays and numbers
+        let fed = &markets[0];
+        assert_eq!(fed.outcomes, ["Yes", "No"]);
+        assert_eq!(fed.outcome_prices, [dec!(0.935), dec!(0.065)]);
+        assert_eq!(fed.clob_token_ids.len(), 2);
+        assert_eq!(fed.liquidity, Some(1204512.5531));
+        assert_eq!(fed.volume, Some(61234567.891234));
+        assert!(fed.neg_risk);
+        assert_eq!(fed.event_slug.as_deref(), Some("fed-decision-in-december"));
+        assert_eq!(fed.neg_risk_question_index(), Some(0));
+        assert_eq!(markets[1].volume, Some(43567812.5));
+        assert_eq!(markets[1].neg_risk_question_index(), Some(1));
+
+        // Plain arrays, and the event's end date when the market has none
+        let btc = &markets[2];
+        assert_eq!(btc.outcome_prices, [dec!(0.41), dec!(0.59)]);
+        assert_eq!(btc.end_date, parse_datetime("2025-12-31T17:00:00Z"));
+        assert!(!btc.neg_risk);
+        assert_eq!(btc.category.as_deref(), Some("Crypto"));
+    }
+
+    #[test]
+    fn test_series_fixture() {
+        let series: Vec<RawGammaSeries> =
+            serde_json::from_str(include_str!("../tests/fixtures/gamma/series.json")).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].recurrence.as_deref(), Some("daily"));
+        assert_eq!(series[0].liquidity, Some(88211.25));
+
+        let mut stats = ParseStats::default();
+        let daily = stats.events(series.into_iter().next().unwrap().events);
+        assert_eq!(stats.skipped_events, 1);
+        let end_dates: Vec<_> = daily.iter().map(|e| e.end_date).collect();
+        assert_eq!(
+            end_dates,
+            [
+                parse_datetime("2025-12-10T17:00:00Z"),
+                parse_datetime("2025-12-09T17:00:00Z"),
+                parse_datetime("2025-12-11T00:00:00Z"),
+            ]
+        );
+        assert_eq!(daily[1].closed, Some(true));
+        assert_eq!(daily[2].liquidity, Some(1500.0));
+    }
+
+    #[test]
+    fn test_market_fixture() {
+        let markets: Vec<RawGammaMarketDetail> =
+            serde_json::from_str(include_str!("../tests/fixtures/gamma/market.json")).unwrap();
+        let parsed = GammaClient::new().parse_market_detail(markets.into_iter().next().unwrap()).unwrap();
+
+        assert_eq!(parsed.market.slug, "fed-decreases-interest-rates-by-25-bps-after-december-2025-meeting");
+        assert_eq!(parsed.market.event_slug.as_deref(), Some("fed-decision-in-december"));
+        assert_eq!(parsed.market.outcome_prices, [dec!(0.935), dec!(0.065)]);
+        assert_eq!(parsed.detail.condition_id, Some(format!("0x{}", "4f".repeat(32))));
+        assert_eq!(parsed.detail.volume_24hr, Some(5123001.25));
+        assert_eq!(parsed.detail.uma_bond, Some(500.0));
+        assert_eq!(parsed.detail.uma_reward, Some(5.0));
+        assert_eq!(parsed.detail.tick_size, Some(0.001));
+        assert!(parsed.detail.resolution_source.is_some());
+    }
+
+    #[test]
+    fn test_field_deserializers() {
+        let market = |fields: serde_json::Value| serde_json::from_value::<RawGammaMarket>(fields);
+
+        let empty = market(serde_json::json!({
+            "outcomes": "",
+            "outcomePrices": null,
+            "liquidity": "",
+            "endDate": "",
+        }))
+        .unwrap();
+        assert!(empty.outcomes.is_empty() && empty.outcome_prices.is_empty() && empty.clob_token_ids.is_empty());
+        assert_eq!((empty.liquidity, empty.end_date), (None, None));
+
+        let numeric = market(serde_json::json!({
+            "outcomePrices": [0.25, "0.75"],
+            "liquidity": 12.5,
+            "volume": " 7 ",
+            "endDate": "2025-12-10 17:00:00+00",
+        }))
+        .unwrap();
+        assert_eq!(numeric.outcome_prices, [dec!(0.25), dec!(0.75)]);
+        assert_eq!((numeric.liquidity, numeric.volume), (Some(12.5), Some(7.0)));
+        assert_eq!(numeric.end_date, parse_datetime("2025-12-10T17:00:00Z"));
+
+        // Bad values are errors, not silently missing fields
+        assert!(market(serde_json::json!({ "outcomes": "[\"Yes\", " })).is_err());
+        assert!(market(serde_json::json!({ "outcomePrices": "[\"abc\"]" })).is_err());
+        assert!(market(serde_json::json!({ "clobTokenIds": 123 })).is_err());
+        assert!(market(serde_json::json!({ "liquidity": "n/a" })).is_err());
+        assert!(market(serde_json::json!({ "endDate": "soon" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_cached_and_revalidated() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Serves `[]` with an ETag, or 304 to a request that presents it
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let revalidated = request.contains("if-none-match: \"v1\"");
+                seen.lock().unwrap().push(revalidated);
+                let response = if revalidated {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nContent-Length: 0\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 2\r\n\r\n[]"
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        let url = format!("{}/events?slug=btc", base_url);
+
+        let fresh = GammaClient::with_base_url(&base_url);
+        for _ in 0..2 {
+            let events: Vec<RawGammaEvent> = fresh.fetch_json(&url).await.unwrap();
+            assert!(events.is_empty());
+        }
+        assert_eq!(*requests.lock().unwrap(), [false]);
+
+        let stale = GammaClient::with_base_url(&base_url).with_cache_ttl(std::time::Duration::ZERO);
+        for _ in 0..2 {
+            let events: Vec<RawGammaEvent> = stale.fetch_json(&url).await.unwrap();
+            assert!(events.is_empty());
+        }
+        assert_eq!(*requests.lock().unwrap(), [false, false, true]);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Fails the first two requests of each path (503, then 429), then serves `[]`
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let attempt = {
+                    let mut seen = seen.lock().unwrap();
+                    seen.push(path.clone());
+                    seen.iter().filter(|p| **p == path).count()
+                };
+                let response = match (path.as_str(), attempt) {
+                    ("/missing", _) => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+                    (_, 1) => "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+                    (_, 2) => "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\n\r\n",
+                    _ => "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n[]",
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = GammaClient::with_base_url(&base_url);
+        let events: Vec<RawGammaEvent> = client.fetch_json(&format!("{}/events", base_url)).await.unwrap();
+        assert!(events.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // Client errors aren't retried
+        assert!(client.fetch_json::<Vec<RawGammaEvent>>(&format!("{}/missing", base_url)).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 4);
+
+        // Nor is anything once the retries run out
+        let policy = RequestPolicy { max_retries: 1, ..RequestPolicy::default() };
+        let impatient = GammaClient::with_base_url(&base_url).with_request_policy(policy);
+        assert!(impatient.fetch_json::<Vec<RawGammaEvent>>(&format!("{}/series", base_url)).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 6);
     }
 
     #[tokio::test]
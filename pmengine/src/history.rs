@@ -0,0 +1,138 @@
+//! Recent price history for warm-starting strategies.
+//!
+//! Indicator-based strategies need a window of prices before their signals
+//! are meaningful. With `PMENGINE_WARM_START_MINUTES` set, the engine fetches
+//! the last N minutes of each subscribed token's price history from the CLOB
+//! `/prices-history` endpoint at startup and hands it to every strategy via
+//! [`Strategy::on_history`](crate::strategy::Strategy::on_history) before the
+//! first tick.
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// One historical price sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PricePoint {
+    pub timestamp: DateTime<Utc>,
+    pub price: Decimal,
+}
+
+/// Raw `/prices-history` response.
+#[derive(Debug, Deserialize)]
+struct RawHistory {
+    #[serde(default)]
+    history: Vec<RawPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPoint {
+    t: i64,
+    p: f64,
+}
+
+/// Client for the CLOB price history endpoint.
+pub struct HistoryClient {
+    client: Client,
+    /// CLOB base URL (with trailing slash)
+    base_url: String,
+}
+
+impl HistoryClient {
+    /// Create a client for a CLOB base URL (e.g. `https://clob.polymarket.com/`
+    /// or the proxy's `/clob/` route).
+    pub fn new(clob_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: format!("{}/", clob_url.trim_end_matches('/')),
+        }
+    }
+
+    /// Fetch a token's prices over the last `window`, oldest first, at
+    /// one-minute fidelity.
+    pub async fn fetch(&self, token_id: &str, window: Duration) -> Result<Vec<PricePoint>, HistoryError> {
+        let end = Utc::now();
+        let start = end - window;
+        let url = format!(
+            "{}prices-history?market={}&startTs={}&endTs={}&fidelity=1",
+            self.base_url,
+            token_id,
+            start.timestamp(),
+            end.timestamp()
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| HistoryError::RequestError(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(HistoryError::RequestError(format!(
+                "prices-history returned {}",
+                response.status()
+            )));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| HistoryError::RequestError(e.to_string()))?;
+        parse_history(&body)
+    }
+}
+
+/// Parse a `/prices-history` body into points sorted oldest first.
+fn parse_history(body: &str) -> Result<Vec<PricePoint>, HistoryError> {
+    let raw: RawHistory = serde_json::from_str(body).map_err(|e| HistoryError::ParseError(e.to_string()))?;
+    let mut points: Vec<PricePoint> = raw
+        .history
+        .into_iter()
+        .filter_map(|p| {
+            Some(PricePoint {
+                timestamp: DateTime::from_timestamp(p.t, 0)?,
+                price: Decimal::from_f64_retain(p.p)?.round_dp(4),
+            })
+        })
+        .collect();
+    points.sort_by_key(|p| p.timestamp);
+    Ok(points)
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    /// HTTP request failed
+    RequestError(String),
+    /// JSON parsing failed
+    ParseError(String),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::RequestError(e) => write!(f, "Request error: {}", e),
+            HistoryError::ParseError(e) => write!(f, "Parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_history_sorts_and_rounds() {
+        let body = r#"{"history": [{"t": 1700000060, "p": 0.515}, {"t": 1700000000, "p": 0.5}]}"#;
+        let points = parse_history(body).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].timestamp.timestamp(), 1_700_000_000);
+        assert_eq!(points[0].price, dec!(0.5));
+        assert_eq!(points[1].price, dec!(0.515));
+
+        assert!(parse_history("{}").unwrap().is_empty());
+        assert!(parse_history("not json").is_err());
+    }
+}
@@ -0,0 +1,198 @@
+//! External kill switch.
+//!
+//! Trading can be stopped from outside the engine in three ways:
+//! - creating the file at `PMENGINE_KILL_FILE` (checked every [`FILE_POLL`]);
+//! - sending the process `SIGUSR1`;
+//! - calling [`EngineControl::kill`](crate::EngineControl::kill).
+//!
+//! Like the watchdog, the switch is watched by a separate task that does not
+//! depend on the event loop. On activation it cancels every open order on the
+//! account directly through the client and, with `PMENGINE_KILL_FLATTEN`,
+//! sells every position the Data API reports with FAK market orders. The event
+//! loop stops running strategies and placing orders as soon as it sees the
+//! switch, and cancels whatever it placed in the meantime; market data, fills
+//! and settlement keep being processed. The switch stays on until the engine
+//! is restarted (and trips again at startup while the kill file exists).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use tokio::sync::Notify;
+
+use crate::client::{PolymarketClient, Side, TimeInForce};
+
+/// How often the kill file is checked for.
+pub const FILE_POLL: Duration = Duration::from_secs(1);
+
+/// Kill switch settings.
+#[derive(Debug, Clone, Default)]
+pub struct KillSwitchConfig {
+    /// File whose existence trips the switch (None = not watched)
+    pub file: Option<PathBuf>,
+    /// Whether positions are sold at market once the switch trips
+    pub flatten: bool,
+}
+
+/// Latched kill switch shared by the engine, its control handles and the
+/// task that acts on it.
+#[derive(Debug, Default)]
+pub struct KillSwitch {
+    /// Why it tripped (unset = not tripped)
+    reason: OnceLock<String>,
+    tripped: Notify,
+}
+
+impl KillSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trip the switch. Returns false if it was already on (the first reason
+    /// is kept).
+    pub fn trigger(&self, reason: &str) -> bool {
+        if self.reason.set(reason.to_string()).is_err() {
+            return false;
+        }
+        self.tripped.notify_one();
+        true
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.reason.get().is_some()
+    }
+
+    /// Why the switch tripped (None while it is off).
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.get().map(String::as_str)
+    }
+}
+
+/// Spawn the task that watches the kill file and `SIGUSR1`, and cancels
+/// (and optionally flattens) everything once the switch trips.
+pub fn spawn(
+    switch: Arc<KillSwitch>,
+    config: KillSwitchConfig,
+    client: Arc<PolymarketClient>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!(file = ?config.file, flatten = config.flatten, "Kill switch armed");
+
+        let trigger = tokio::select! {
+            _ = switch.tripped.notified() => None,
+            _ = kill_file_created(config.file.as_deref()) => Some("kill file"),
+            _ = sigusr1() => Some("SIGUSR1"),
+        };
+        if let Some(reason) = trigger {
+            switch.trigger(reason);
+        }
+
+        tracing::error!(
+            reason = switch.reason().unwrap_or_default(),
+            flatten = config.flatten,
+            "Kill switch activated"
+        );
+        match client.cancel_all_open_orders().await {
+            Ok(()) => tracing::warn!("Kill switch cancelled all open orders"),
+            Err(e) => tracing::error!(error = %e, "Kill switch failed to cancel open orders"),
+        }
+        if config.flatten {
+            flatten_positions(&client).await;
+        }
+    })
+}
+
+/// Resolves once `path` exists (never if there is no path).
+async fn kill_file_created(path: Option<&Path>) {
+    let Some(path) = path else {
+        return std::future::pending().await;
+    };
+    let mut poll = tokio::time::interval(FILE_POLL);
+    loop {
+        poll.tick().await;
+        if path.exists() {
+            return;
+        }
+    }
+}
+
+/// Resolves on `SIGUSR1` (never where it can't be caught).
+async fn sigusr1() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::user_defined1()) {
+            Ok(mut usr1) => {
+                if usr1.recv().await.is_some() {
+                    return;
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Cannot listen for SIGUSR1"),
+        }
+    }
+    std::future::pending().await
+}
+
+/// Sell every position held at market, as far as the books allow.
+async fn flatten_positions(client: &PolymarketClient) {
+    let positions = match client.positions().await {
+        Ok(positions) => positions,
+        Err(e) => {
+            tracing::error!(error = %e, "Kill switch failed to fetch positions, not flattening");
+            return;
+        }
+    };
+    // Resolved markets are redeemed, not sold
+    for position in positions.iter().filter(|p| p.size > Decimal::ZERO && !p.redeemable) {
+        let result = client
+            .place_market_order(&position.token_id, Side::Sell, position.size, None, TimeInForce::Fak)
+            .await;
+        match result {
+            Ok(order_id) => tracing::warn!(
+                order_id = %order_id,
+                token_id = %position.token_id,
+                size = %position.size,
+                "Kill switch sold position"
+            ),
+            Err(e) => tracing::error!(
+                error = %e,
+                token_id = %position.token_id,
+                size = %position.size,
+                "Kill switch failed to sell position"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_latches_first_reason() {
+        let switch = KillSwitch::new();
+        assert!(!switch.is_active());
+        assert!(switch.trigger("operator"));
+        assert!(!switch.trigger("SIGUSR1"));
+        assert!(switch.is_active());
+        assert_eq!(switch.reason(), Some("operator"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_kill_file_trips_once_created() {
+        let path = std::env::temp_dir().join(format!("pmengine-kill-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let waiting = tokio::spawn({
+            let path = path.clone();
+            async move { kill_file_created(Some(&path)).await }
+        });
+        tokio::time::sleep(FILE_POLL * 3).await;
+        assert!(!waiting.is_finished());
+
+        std::fs::write(&path, "").unwrap();
+        tokio::time::timeout(FILE_POLL * 2, waiting).await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+//! Order laddering: one strategy quote placed as several smaller orders.
+//!
+//! A single large resting order shows its full size at one price and only
+//! fills when the market trades through that level. With
+//! `PMENGINE_ORDER_LADDER` set, an approved BUY or SELL is split across
+//! adjacent price levels instead, each rung getting a share of the size:
+//!
+//! ```text
+//! PMENGINE_ORDER_LADDER="0:50,0.01:30,0.02:20"
+//! ```
+//!
+//! places 50% at the quoted price, 30% one cent behind it and 20% two cents
+//! behind (lower for buys, higher for sells), so rungs are never more
+//! aggressive than the quote. High-urgency signals are never laddered, and
+//! rungs below the exchange's minimum order size are folded into the first.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+
+use crate::safe_math::{add, div, mul, sub, sum};
+use crate::strategy::{Signal, Urgency};
+
+/// Smallest order the CLOB accepts (shares).
+pub const MIN_RUNG_SIZE: Decimal = dec!(5);
+
+const MIN_PRICE: Decimal = dec!(0.01);
+const MAX_PRICE: Decimal = dec!(0.99);
+
+/// One level of a ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rung {
+    /// Distance behind the quoted price
+    pub offset: Decimal,
+    /// Relative share of the quoted size
+    pub weight: Decimal,
+}
+
+/// How a quote is split across price levels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ladder {
+    rungs: Vec<Rung>,
+    total_weight: Decimal,
+}
+
+impl Ladder {
+    /// Build a ladder; None if it has no rungs, negative offsets or
+    /// non-positive weights.
+    pub fn new(rungs: Vec<Rung>) -> Option<Self> {
+        if rungs.is_empty() || rungs.iter().any(|r| r.offset < Decimal::ZERO || r.weight <= Decimal::ZERO) {
+            return None;
+        }
+        let total_weight = sum(rungs.iter().map(|r| r.weight), "ladder weight");
+        Some(Self { rungs, total_weight })
+    }
+
+    /// Parse `offset:weight,offset:weight`, e.g. `0:50,0.01:30,0.02:20`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let rungs = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(|r| {
+                let (offset, weight) = r.split_once(':')?;
+                Some(Rung {
+                    offset: offset.trim().parse().ok()?,
+                    weight: weight.trim().parse().ok()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Self::new(rungs)
+    }
+
+    pub fn rungs(&self) -> &[Rung] {
+        &self.rungs
+    }
+
+    /// Split an order signal into one signal per rung. Other signals, and
+    /// orders too small or too urgent to ladder, are returned unchanged.
+    pub fn split(&self, signal: &Signal) -> Vec<Signal> {
+        let (is_buy, price, size, urgency) = match signal {
+            Signal::Buy { price, size, urgency, .. } => (true, *price, *size, *urgency),
+            Signal::Sell { price, size, urgency, .. } => (false, *price, *size, *urgency),
+            _ => return vec![signal.clone()],
+        };
+        if matches!(urgency, Urgency::High | Urgency::Immediate) {
+            return vec![signal.clone()];
+        }
+
+        // Sizes are rounded down to the CLOB's 2 decimals; the remainder and
+        // any undersized or unpriceable rung go to the first rung
+        let size = size.round_dp(2);
+        let mut orders: Vec<(Decimal, Decimal)> = Vec::with_capacity(self.rungs.len());
+        let mut leftover = size;
+        for rung in &self.rungs {
+            let rung_price = if is_buy { price - rung.offset } else { price + rung.offset }.round_dp(2);
+            let share = div(rung.weight, self.total_weight, "ladder share");
+            let rung_size = mul(size, share, "ladder size").round_dp_with_strategy(2, RoundingStrategy::ToZero);
+            let first = orders.is_empty();
+            if !first && (rung_size < MIN_RUNG_SIZE || !(MIN_PRICE..=MAX_PRICE).contains(&rung_price)) {
+                continue;
+            }
+            orders.push((rung_price, rung_size));
+            leftover = sub(leftover, rung_size, "ladder remainder");
+        }
+        if let Some(first) = orders.first_mut() {
+            first.1 = add(first.1, leftover, "ladder remainder");
+        }
+        if orders.len() < 2 {
+            return vec![signal.clone()];
+        }
+
+        orders
+            .into_iter()
+            .map(|(rung_price, rung_size)| {
+                let mut rung = signal.clone();
+                if let Signal::Buy { price, size, .. } | Signal::Sell { price, size, .. } = &mut rung {
+                    (*price, *size) = (rung_price, rung_size);
+                }
+                rung
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(signal: &Signal) -> (Decimal, Decimal) {
+        match signal {
+            Signal::Buy { price, size, .. } | Signal::Sell { price, size, .. } => (*price, *size),
+            _ => panic!("not an order"),
+        }
+    }
+
+    #[test]
+    fn test_parse_ladder() {
+        let ladder = Ladder::parse("0:50, 0.01:30,0.02:20").unwrap();
+        assert_eq!(ladder.rungs().len(), 3);
+        assert_eq!(ladder.rungs()[1], Rung { offset: dec!(0.01), weight: dec!(30) });
+        assert!(Ladder::parse("").is_none());
+        assert!(Ladder::parse("0:50,0.01").is_none());
+        assert!(Ladder::parse("0:0").is_none());
+        assert!(Ladder::parse("-0.01:50").is_none());
+    }
+
+    #[test]
+    fn test_split_steps_away_from_the_quote() {
+        let ladder = Ladder::parse("0:50,0.01:30,0.02:20").unwrap();
+        let buy = Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.55),
+            size: dec!(101),
+            urgency: Urgency::Low,
+            post_only: false,
+            expires_at: None,
+        };
+        let rungs: Vec<_> = ladder.split(&buy).iter().map(order).collect();
+        assert_eq!(
+            rungs,
+            vec![(dec!(0.55), dec!(50.5)), (dec!(0.54), dec!(30.3)), (dec!(0.53), dec!(20.2))]
+        );
+
+        let sell = Signal::Sell {
+            token_id: "token".to_string(),
+            price: dec!(0.98),
+            size: dec!(100),
+            urgency: Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+        // 0.99 is the last valid price; the 1.00 rung folds into the first
+        let rungs: Vec<_> = ladder.split(&sell).iter().map(order).collect();
+        assert_eq!(rungs, vec![(dec!(0.98), dec!(70)), (dec!(0.99), dec!(30))]);
+    }
+
+    #[test]
+    fn test_small_or_urgent_orders_are_not_laddered() {
+        let ladder = Ladder::parse("0:50,0.01:30,0.02:20").unwrap();
+        let buy = |size, urgency| Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.5),
+            size,
+            urgency,
+            post_only: false,
+            expires_at: None,
+        };
+
+        // 20% and 30% of 12 are below the minimum order size
+        let small = buy(dec!(12), Urgency::Medium);
+        assert_eq!(ladder.split(&small).len(), 1);
+        assert_eq!(order(&ladder.split(&small)[0]), (dec!(0.5), dec!(12)));
+
+        // 30% of 20 fits, 20% does not
+        let rungs: Vec<_> = ladder.split(&buy(dec!(20), Urgency::Medium)).iter().map(order).collect();
+        assert_eq!(rungs, vec![(dec!(0.5), dec!(14)), (dec!(0.49), dec!(6))]);
+
+        assert_eq!(ladder.split(&buy(dec!(100), Urgency::High)).len(), 1);
+        assert_eq!(ladder.split(&Signal::Hold).len(), 1);
+    }
+}
@@ -15,34 +15,104 @@
 //!
 //! Strategies generate signals that pass through risk management before execution.
 
+pub mod attribution;
+pub mod basket;
+pub mod carry;
 pub mod client;
 pub mod config;
+pub mod config_file;
+pub mod control;
+pub mod conversion;
+pub mod discovery;
+pub mod drawdown;
 pub mod engine;
+pub mod execution;
+pub mod exits;
+pub mod exposure;
+pub mod fees;
 pub mod gamma;
+pub mod history;
+pub mod kill_switch;
+pub mod ladder;
+pub mod market_feed;
 pub mod order;
 pub mod orderbook;
+pub mod pipeline;
 pub mod position;
+pub mod priority;
+pub mod reconcile;
+pub mod recorder;
+pub mod registry;
+pub mod report;
 pub mod risk;
+pub mod risk_journal;
+pub mod safe_math;
+pub mod schedule;
+pub mod settlement;
+pub mod sizing;
+pub mod store;
 pub mod strategy;
 pub mod strategies;
+pub mod synthetic;
+pub mod throttle;
+pub mod trade_journal;
+pub mod user_feed;
+pub mod utilization;
+pub mod watchdog;
+pub mod webhook;
 
+#[cfg(feature = "ec2")]
+pub mod cli;
 #[cfg(feature = "cognito")]
 pub mod cognito;
+#[cfg(feature = "python")]
+pub mod python;
 
-pub use client::{ClientError, PolymarketClient, Side};
+pub use attribution::{StrategyLedger, StrategyPnl};
+pub use basket::{Basket, BasketLeg};
+pub use carry::{CarryReport, PositionCarry};
+pub use client::{ClientError, PolymarketClient, Side, TimeInForce};
 pub use config::Config;
+pub use config_file::ConfigFile;
+pub use control::{ControlCommand, EngineControl};
+pub use discovery::{DiscoveryHealth, DiscoverySource, SourceHealth};
 pub use engine::Engine;
-pub use gamma::{GammaClient, GammaError, GammaMarket};
+pub use execution::{ExecutionAlgo, ParentOrder};
+pub use gamma::{
+    GammaClient, GammaError, GammaMarket, GammaMarketDetail, MarketDetail, MarketFilter, MarketParseError, ParseStats,
+    RequestPolicy, SkipReason,
+};
+pub use history::{HistoryClient, PricePoint};
+pub use kill_switch::{KillSwitch, KillSwitchConfig};
+pub use ladder::Ladder;
+pub use market_feed::FeedMetrics;
 pub use order::OrderManager;
-pub use orderbook::{Level, MarketDataHub, MarketEvent, OrderBook};
+pub use orderbook::{
+    BookHealth, LastTrade, Level, MarketDataHub, MarketEvent, MarketStats, OrderBook, RollingStats,
+};
+pub use pipeline::{Filter, Pipeline};
 pub use position::{Fill, Position, PositionTracker};
-pub use risk::{RiskLimits, RiskManager};
-pub use strategy::{MarketInfo, Signal, Strategy, StrategyContext, StrategyRuntime, Urgency};
+pub use reconcile::Reconciliation;
+pub use recorder::{MarketRecorder, RecordStats};
+pub use risk::{AuditReport, LeakMetrics, MarketReference, RiskLimits, RiskManager};
+pub use risk_journal::{Decision, RiskDecision, RiskJournal};
+pub use schedule::{Schedule, Window};
+pub use settlement::Resolution;
+pub use store::{EngineState, StateStore};
+pub use strategy::{
+    MarketInfo, Quarantined, Signal, Strategy, StrategyContext, StrategyParams, StrategyRuntime,
+    StrategySignal, TickBudget, Urgency,
+};
+pub use synthetic::{SyntheticFeed, SyntheticScenario};
+pub use throttle::{Endpoint, RateLimits, Throttle, ThrottleMetrics};
+pub use utilization::{BucketUsage, TokenExposure, UtilizationReport, UtilizationTracker};
+pub use watchdog::{Heartbeat, HeartbeatSnapshot, LoopActivity};
+pub use webhook::ExternalEvent;
 
 /// Re-export commonly used types from dependencies
 pub mod prelude {
     pub use crate::{
-        Config, Engine, Fill, GammaClient, GammaMarket, Level, MarketDataHub, MarketEvent,
+        Basket, Config, Engine, Fill, GammaClient, GammaMarket, Level, MarketDataHub, MarketEvent,
         MarketInfo, OrderBook, OrderManager, Position, PositionTracker, RiskLimits,
         RiskManager, Signal, Strategy, StrategyContext, Urgency,
     };
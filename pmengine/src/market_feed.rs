@@ -0,0 +1,181 @@
+//! Reconnection policy and metrics for the market data WebSocket.
+//!
+//! [`subscribe`] streams full book snapshots and last-trade prices for the
+//! subscribed tokens over one market channel connection.
+//!
+//! The engine reconnects (resubscribing every subscribed token) whenever the
+//! book stream ends, the subscription fails, or no update has arrived for
+//! `PMENGINE_WS_STALE_SECS` (default 60, 0 = never considered stale).
+//! Reconnects after a failure wait out an exponential [`Backoff`], from
+//! [`BACKOFF_BASE`] doubling up to [`BACKOFF_MAX`], which resets once a
+//! connection delivers data again. The loop keeps ticking, processing fills
+//! and honouring shutdown while it waits.
+//!
+//! [`FeedMetrics`] counts connections and why they were dropped; the engine
+//! logs them with its periodic reports.
+
+use std::fmt;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use polymarket_client_sdk::clob::ws::types::response::{BookUpdate, LastTradePrice};
+use polymarket_client_sdk::clob::ws::Client as WsClient;
+use polymarket_client_sdk::types::U256;
+
+/// First reconnect delay after a failure.
+pub const BACKOFF_BASE: Duration = Duration::from_secs(1);
+
+/// Longest reconnect delay.
+pub const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// A message from the market data WebSocket.
+#[derive(Debug, Clone)]
+pub enum FeedMessage {
+    /// Full order book snapshot
+    Book(BookUpdate),
+    /// A trade happened
+    Trade(LastTradePrice),
+}
+
+/// Subscribe to the books and last trades of `asset_ids`.
+pub fn subscribe(
+    ws: &WsClient,
+    asset_ids: Vec<U256>,
+) -> polymarket_client_sdk::Result<impl Stream<Item = polymarket_client_sdk::Result<FeedMessage>> + '_> {
+    let books = ws
+        .subscribe_orderbook(asset_ids.clone())?
+        .map(|book| book.map(FeedMessage::Book));
+    let trades = ws
+        .subscribe_last_trade_price(asset_ids)?
+        .map(|trade| trade.map(FeedMessage::Trade));
+    Ok(futures::stream::select(books, trades))
+}
+
+/// Exponential reconnect delay.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self { base, max, attempt: 0 }
+    }
+
+    /// Delay before the next attempt: `base`, then doubling up to `max`.
+    pub fn next_delay(&mut self) -> Duration {
+        let factor = 2u32.saturating_pow(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        self.base.saturating_mul(factor).min(self.max)
+    }
+
+    /// Consecutive failed attempts so far.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Start over after a healthy connection.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(BACKOFF_BASE, BACKOFF_MAX)
+    }
+}
+
+/// Why the market data connection was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disconnect {
+    /// The stream ended (connection closed)
+    Ended,
+    /// No update within the staleness window
+    Stale,
+    /// Subscribing failed
+    SubscribeFailed,
+}
+
+impl fmt::Display for Disconnect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Disconnect::Ended => write!(f, "stream ended"),
+            Disconnect::Stale => write!(f, "no updates"),
+            Disconnect::SubscribeFailed => write!(f, "subscribe failed"),
+        }
+    }
+}
+
+/// Market data connection counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeedMetrics {
+    /// Successful subscriptions (including the first)
+    pub connects: u64,
+    /// Reconnects after a failure
+    pub reconnects: u64,
+    /// Streams that ended
+    pub ended: u64,
+    /// Connections dropped for going stale
+    pub stale: u64,
+    /// Failed subscriptions
+    pub subscribe_failures: u64,
+    /// Errors read from the stream
+    pub errors: u64,
+}
+
+impl FeedMetrics {
+    /// Count a dropped connection.
+    pub fn record_disconnect(&mut self, reason: Disconnect) {
+        match reason {
+            Disconnect::Ended => self.ended += 1,
+            Disconnect::Stale => self.stale += 1,
+            Disconnect::SubscribeFailed => self.subscribe_failures += 1,
+        }
+    }
+
+    pub fn log(&self) {
+        tracing::info!(
+            connects = self.connects,
+            reconnects = self.reconnects,
+            ended = self.ended,
+            stale = self.stale,
+            subscribe_failures = self.subscribe_failures,
+            errors = self.errors,
+            "Market data feed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_to_max_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let delays: Vec<u64> = (0..6).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff.attempt(), 6);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+
+        // Never overflows, however long the outage
+        for _ in 0..100 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_disconnects_are_counted_by_reason() {
+        let mut metrics = FeedMetrics::default();
+        metrics.record_disconnect(Disconnect::Stale);
+        metrics.record_disconnect(Disconnect::Stale);
+        metrics.record_disconnect(Disconnect::Ended);
+        assert_eq!((metrics.stale, metrics.ended, metrics.subscribe_failures), (2, 1, 0));
+    }
+}
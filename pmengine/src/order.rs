@@ -1,11 +1,17 @@
 //! Order management wrapping the Polymarket SDK.
 
-use crate::client::{PolymarketClient, Side};
+use crate::client::{PolymarketClient, Side, TimeInForce};
+use crate::execution::{AlgoStep, ExecutionAlgo, ParentOrder};
+use crate::fees;
 use crate::position::Fill;
-use crate::strategy::{Signal, Urgency};
+use crate::strategy::Signal;
+use futures::future::join_all;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 /// Order state.
@@ -30,6 +36,12 @@ pub struct Order {
     pub filled_size: Decimal,
     pub status: OrderStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Time from signal generation to exchange acknowledgement
+    pub ack_latency: Option<Duration>,
+    /// Whether the acknowledgement came after the latency budget
+    pub over_budget: bool,
+    /// Strategy whose signal placed the order (None = restored or adopted untagged)
+    pub strategy_id: Option<String>,
 }
 
 impl Order {
@@ -40,6 +52,149 @@ impl Order {
     pub fn is_active(&self) -> bool {
         matches!(self.status, OrderStatus::Pending | OrderStatus::Open | OrderStatus::PartiallyFilled)
     }
+
+    /// Whether the order is still active more than `max_age` after it was placed.
+    pub fn is_expired(&self, max_age: Duration, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let age = now.signed_duration_since(self.created_at).to_std().unwrap_or_default();
+        self.is_active() && age > max_age
+    }
+}
+
+/// Signal-to-ack latency budget for order placement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBudget {
+    /// Maximum time from signal generation to exchange acknowledgement
+    pub budget: Duration,
+    /// Cancel orders acknowledged over budget (their price basis is stale)
+    pub cancel_late: bool,
+}
+
+/// Signal-to-ack latency counters for placed orders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyMetrics {
+    /// Orders acknowledged by the exchange
+    pub orders: u64,
+    /// Orders acknowledged over budget
+    pub violations: u64,
+    /// Over-budget orders cancelled right away
+    pub cancelled: u64,
+    /// Sum of signal-to-ack latencies
+    pub total_latency: Duration,
+    /// Slowest signal-to-ack latency
+    pub max_latency: Duration,
+}
+
+impl LatencyMetrics {
+    /// Record an acknowledged order. Returns whether it was over `budget`.
+    pub fn record(&mut self, latency: Duration, budget: Option<Duration>) -> bool {
+        self.orders += 1;
+        self.total_latency += latency;
+        self.max_latency = self.max_latency.max(latency);
+        let over = budget.is_some_and(|b| latency > b);
+        if over {
+            self.violations += 1;
+        }
+        over
+    }
+
+    /// Average signal-to-ack latency.
+    pub fn avg_latency(&self) -> Duration {
+        self.total_latency
+            .checked_div(self.orders.max(1) as u32)
+            .unwrap_or_default()
+    }
+}
+
+/// Default fraction of the wanted size by which a resting order's remaining
+/// size may differ and still be kept by a replace.
+pub const DEFAULT_REPLACE_TOLERANCE: Decimal = dec!(0.1);
+
+/// What a replace does to a token's resting orders.
+#[derive(Debug, Clone, Default)]
+pub struct ReplacePlan {
+    /// Resting orders that already match a wanted order
+    pub keep: Vec<String>,
+    /// Resting orders no longer wanted
+    pub cancel: Vec<String>,
+    /// Wanted orders with no matching resting order
+    pub place: Vec<Signal>,
+}
+
+/// Match resting orders on `token_id` against the buy and sell signals
+/// wanted there. A resting order is kept for a wanted order on the same side
+/// at the same price (to the cent, as orders are placed) when its remaining
+/// size is within `size_tolerance` (a fraction of the wanted size) of the
+/// wanted size. Each resting order is kept for at most one wanted order;
+/// signals of other kinds or for other tokens are ignored.
+pub fn plan_replace(
+    token_id: &str,
+    resting: &[&Order],
+    wanted: &[Signal],
+    size_tolerance: Decimal,
+) -> ReplacePlan {
+    let mut plan = ReplacePlan::default();
+    let mut unmatched: Vec<&Order> = resting.iter().copied().filter(|o| o.token_id == token_id).collect();
+    for signal in wanted {
+        if !matches!(signal, Signal::Buy { .. } | Signal::Sell { .. }) {
+            continue;
+        }
+        let Some((_, is_buy, price, size)) = signal.order_terms().filter(|(t, ..)| *t == token_id) else {
+            continue;
+        };
+        let (price, size) = (price.round_dp(2), size.round_dp(2));
+        let slack = size * size_tolerance;
+        let matching = unmatched
+            .iter()
+            .position(|o| o.is_buy == is_buy && o.price == price && (o.remaining() - size).abs() <= slack);
+        match matching {
+            Some(i) => plan.keep.push(unmatched.swap_remove(i).id.clone()),
+            None => plan.place.push(signal.clone()),
+        }
+    }
+    plan.cancel = unmatched.into_iter().map(|o| o.id.clone()).collect();
+    plan
+}
+
+/// A BUY or SELL signal's limit order, rounded for the exchange.
+struct LimitTerms<'a> {
+    token_id: &'a str,
+    is_buy: bool,
+    price: Decimal,
+    size: Decimal,
+    post_only: bool,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl<'a> LimitTerms<'a> {
+    /// None for signals other than BUY and SELL.
+    fn of(signal: &'a Signal) -> Option<Self> {
+        let (token_id, is_buy, price, size, post_only, expires_at) = match signal {
+            Signal::Buy { token_id, price, size, post_only, expires_at, .. } => {
+                (token_id, true, price, size, post_only, expires_at)
+            }
+            Signal::Sell { token_id, price, size, post_only, expires_at, .. } => {
+                (token_id, false, price, size, post_only, expires_at)
+            }
+            _ => return None,
+        };
+        // Round to 2 decimal places (Polymarket requirement)
+        Some(Self {
+            token_id,
+            is_buy,
+            price: price.round_dp(2),
+            size: size.round_dp(2),
+            post_only: *post_only,
+            expires_at: *expires_at,
+        })
+    }
+
+    async fn place(&self, client: &PolymarketClient) -> Result<String, OrderError> {
+        let side = if self.is_buy { Side::Buy } else { Side::Sell };
+        client
+            .place_limit_order(self.token_id, side, self.price, self.size, self.expires_at, self.post_only)
+            .await
+            .map_err(|e| OrderError::SdkError(e.to_string()))
+    }
 }
 
 /// Order manager wraps the SDK and tracks orders.
@@ -47,6 +202,16 @@ pub struct OrderManager {
     client: Arc<PolymarketClient>,
     orders: HashMap<String, Order>,
     fill_sender: mpsc::Sender<Fill>,
+    /// Signal-to-ack budget (None = latency is only measured)
+    latency_budget: Option<LatencyBudget>,
+    latency: LatencyMetrics,
+    /// Size tolerance for keeping resting orders on a replace
+    replace_tolerance: Decimal,
+    /// Parent orders being worked by an execution algo, by ID
+    algos: BTreeMap<String, ParentOrder>,
+    /// Parent of each child order
+    algo_children: HashMap<String, String>,
+    next_algo_id: u64,
 }
 
 impl OrderManager {
@@ -55,9 +220,31 @@ impl OrderManager {
             client,
             orders: HashMap::new(),
             fill_sender,
+            latency_budget: None,
+            latency: LatencyMetrics::default(),
+            replace_tolerance: DEFAULT_REPLACE_TOLERANCE,
+            algos: BTreeMap::new(),
+            algo_children: HashMap::new(),
+            next_algo_id: 0,
         }
     }
 
+    /// Set how far (as a fraction of the wanted size) a resting order's size
+    /// may be off and still be kept by a replace.
+    pub fn set_replace_tolerance(&mut self, tolerance: Decimal) {
+        self.replace_tolerance = tolerance;
+    }
+
+    /// Enforce a signal-to-ack latency budget on placed orders.
+    pub fn set_latency_budget(&mut self, budget: Option<LatencyBudget>) {
+        self.latency_budget = budget;
+    }
+
+    /// Signal-to-ack latency counters.
+    pub fn latency_metrics(&self) -> &LatencyMetrics {
+        &self.latency
+    }
+
     /// Check if running in dry-run mode.
     pub fn is_dry_run(&self) -> bool {
         self.client.is_dry_run()
@@ -65,6 +252,19 @@ impl OrderManager {
 
     /// Execute a signal by placing/canceling orders.
     pub async fn execute(&mut self, signal: Signal) -> Result<Option<String>, OrderError> {
+        self.execute_since(signal, Instant::now()).await
+    }
+
+    /// Execute a signal generated at `generated_at`.
+    ///
+    /// Placement latency is measured from `generated_at`; an order acknowledged
+    /// over the latency budget is tagged and, with `cancel_late`, cancelled
+    /// right away (returning `Ok(None)` as if it was never placed).
+    pub async fn execute_since(
+        &mut self,
+        signal: Signal,
+        generated_at: Instant,
+    ) -> Result<Option<String>, OrderError> {
         match signal {
             Signal::Hold => Ok(None),
 
@@ -73,64 +273,369 @@ impl OrderManager {
                 Ok(None)
             }
 
-            Signal::Buy { token_id, price, size, urgency } => {
-                self.place_order(&token_id, true, price, size, urgency).await
+            Signal::Buy { .. } | Signal::Sell { .. } => self.place_order(&signal, generated_at).await,
+
+            Signal::Replace { token_id, orders } => {
+                let plan = self.replace(None, &token_id, &orders).await?;
+                for order in &plan.place {
+                    self.place_order(order, generated_at).await?;
+                }
+                Ok(None)
             }
 
-            Signal::Sell { token_id, price, size, urgency } => {
-                self.place_order(&token_id, false, price, size, urgency).await
+            Signal::Market { token_id, is_buy, amount, worst_price, time_in_force } => {
+                self.place_market_order(&token_id, is_buy, amount, worst_price, time_in_force, generated_at)
+                    .await
+            }
+
+            Signal::Paired { legs } => {
+                self.execute_paired(&legs, generated_at).await?;
+                Ok(None)
             }
 
-            // Shutdown is handled by the engine, not the order manager
-            Signal::Shutdown { .. } => Ok(None),
+            // The children are placed step by step by the caller (see `algo_steps`)
+            Signal::Algo { order, algo } => {
+                self.start_algo(None, *order, algo);
+                Ok(None)
+            }
+
+            // Handled by the engine, not the order manager
+            Signal::ReduceOnly { .. }
+            | Signal::Exit { .. }
+            | Signal::Convert { .. }
+            | Signal::Shutdown { .. } => Ok(None),
         }
     }
 
+    /// Place the limit order of a buy or sell signal.
     async fn place_order(
         &mut self,
-        token_id: &str,
-        is_buy: bool,
-        price: Decimal,
-        size: Decimal,
-        _urgency: Urgency,
+        signal: &Signal,
+        generated_at: Instant,
     ) -> Result<Option<String>, OrderError> {
-        // Round to 2 decimal places (Polymarket requirement)
-        let price = price.round_dp(2);
-        let size = size.round_dp(2);
+        let Some(limit) = LimitTerms::of(signal) else {
+            return Ok(None);
+        };
+        let token_id = limit.token_id;
 
         // Skip if size rounds to zero
-        if size.is_zero() {
+        if limit.size.is_zero() {
             tracing::debug!(token_id = token_id, "Order size rounded to zero, skipping");
             return Ok(None);
         }
 
-        let side = if is_buy { Side::Buy } else { Side::Sell };
-
         // Place order via SDK (handles dry-run internally)
+        let order_id = limit.place(&self.client).await?;
+        if let Some(latency) = self.track(&order_id, &limit, generated_at) {
+            let cancel = self.latency_budget.is_some_and(|b| b.cancel_late);
+            tracing::warn!(
+                order_id = order_id.as_str(),
+                token_id = token_id,
+                latency_ms = latency.as_millis() as u64,
+                budget_ms = self.latency_budget.map(|b| b.budget.as_millis() as u64),
+                cancel = cancel,
+                "Order acknowledged over latency budget"
+            );
+            if cancel {
+                match self.cancel_order(&order_id).await {
+                    Ok(()) => {
+                        self.latency.cancelled += 1;
+                        return Ok(None);
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            order_id = order_id.as_str(),
+                            error = %e,
+                            "Failed to cancel late order"
+                        );
+                    }
+                }
+            }
+        }
+        Ok(Some(order_id))
+    }
+
+    /// Place the legs of a paired order (see [`Signal::Paired`]) at once.
+    /// Returns their order IDs, in leg order.
+    ///
+    /// If a leg fails to place, the legs that were placed are cancelled and
+    /// the error returned. Legs whose cancel fails stay tracked and are listed
+    /// in [`OrderError::PartialPair`], as they may still rest on the book.
+    /// Legs acknowledged over the latency budget are never cancelled for it,
+    /// as that would break the pair.
+    pub async fn execute_paired(
+        &mut self,
+        legs: &[Signal],
+        generated_at: Instant,
+    ) -> Result<Vec<String>, OrderError> {
+        let mut terms = Vec::with_capacity(legs.len());
+        for leg in legs {
+            let not_limit = || OrderError::InvalidOrder("paired legs must be BUY or SELL orders".to_string());
+            let limit = LimitTerms::of(leg).ok_or_else(not_limit)?;
+            if limit.size.is_zero() {
+                return Err(OrderError::InvalidOrder(format!("{} leg size rounds to zero", limit.token_id)));
+            }
+            terms.push(limit);
+        }
+
+        let client = Arc::clone(&self.client);
+        let results = join_all(terms.iter().map(|limit| limit.place(&client))).await;
+
+        let mut placed = Vec::with_capacity(results.len());
+        for (limit, result) in terms.iter().zip(&results) {
+            match result {
+                Ok(order_id) => {
+                    self.track(order_id, limit, generated_at);
+                    placed.push(order_id.clone());
+                }
+                Err(e) => {
+                    tracing::error!(token_id = limit.token_id, error = %e, "Paired order leg failed");
+                }
+            }
+        }
+
+        let outcome = settle_pair(results, |order_id| {
+            let client = Arc::clone(&client);
+            async move {
+                client
+                    .cancel_order(&order_id)
+                    .await
+                    .map_err(|e| OrderError::SdkError(e.to_string()))
+            }
+        })
+        .await;
+        if let Err(ref e) = outcome {
+            let live = e.live_legs();
+            for order_id in placed.iter().filter(|id| !live.iter().any(|(_, live_id)| live_id == *id)) {
+                if let Some(order) = self.orders.get_mut(order_id) {
+                    order.status = OrderStatus::Cancelled;
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Track a newly placed limit order, recording its signal-to-ack
+    /// latency. Returns the latency if it was over budget.
+    fn track(&mut self, order_id: &str, limit: &LimitTerms<'_>, generated_at: Instant) -> Option<Duration> {
+        let latency = generated_at.elapsed();
+        let over_budget = self
+            .latency
+            .record(latency, self.latency_budget.map(|b| b.budget));
+
+        let order = Order {
+            id: order_id.to_string(),
+            token_id: limit.token_id.to_string(),
+            is_buy: limit.is_buy,
+            price: limit.price,
+            size: limit.size,
+            filled_size: Decimal::ZERO,
+            status: OrderStatus::Open,
+            created_at: chrono::Utc::now(),
+            ack_latency: Some(latency),
+            over_budget,
+            strategy_id: None,
+        };
+        self.orders.insert(order_id.to_string(), order);
+        over_budget.then_some(latency)
+    }
+
+    /// Place a market order. It is tracked like a limit order until its fills
+    /// (and the cancellation of whatever didn't fill) come back; it is never
+    /// cancelled for being late, as it doesn't rest on the book.
+    async fn place_market_order(
+        &mut self,
+        token_id: &str,
+        is_buy: bool,
+        amount: Decimal,
+        worst_price: Decimal,
+        time_in_force: TimeInForce,
+        generated_at: Instant,
+    ) -> Result<Option<String>, OrderError> {
+        let amount = amount.round_dp(2);
+        let worst_price = worst_price.round_dp(2);
+        if amount.is_zero() || worst_price.is_zero() {
+            tracing::debug!(token_id = token_id, "Market order amount or price rounded to zero, skipping");
+            return Ok(None);
+        }
+
+        let side = if is_buy { Side::Buy } else { Side::Sell };
         let order_id = self
             .client
-            .place_limit_order(token_id, side, price, size)
+            .place_market_order(token_id, side, amount, Some(worst_price), time_in_force)
             .await
             .map_err(|e| OrderError::SdkError(e.to_string()))?;
 
-        // Track order locally
+        let latency = generated_at.elapsed();
+        let over_budget = self
+            .latency
+            .record(latency, self.latency_budget.map(|b| b.budget));
+
         let order = Order {
             id: order_id.clone(),
             token_id: token_id.to_string(),
             is_buy,
-            price,
-            size,
+            price: worst_price,
+            size: if is_buy { amount / worst_price } else { amount },
             filled_size: Decimal::ZERO,
             status: OrderStatus::Open,
             created_at: chrono::Utc::now(),
+            ack_latency: Some(latency),
+            over_budget,
+            strategy_id: None,
         };
-
         self.orders.insert(order_id.clone(), order);
         Ok(Some(order_id))
     }
 
-    /// Cancel all orders for a token.
+    /// Diff the resting orders a strategy (None = untagged orders) has on a
+    /// token against the orders it wants there (see [`plan_replace`]) and
+    /// cancel the ones no longer wanted, in one request. Placing the plan's
+    /// new orders is left to the caller.
+    pub async fn replace(
+        &mut self,
+        strategy_id: Option<&str>,
+        token_id: &str,
+        wanted: &[Signal],
+    ) -> Result<ReplacePlan, OrderError> {
+        let resting: Vec<&Order> = self
+            .orders
+            .values()
+            .filter(|o| o.token_id == token_id && o.is_active() && o.strategy_id.as_deref() == strategy_id)
+            .collect();
+        let plan = plan_replace(token_id, &resting, wanted, self.replace_tolerance);
+
+        if !plan.cancel.is_empty() {
+            let order_refs: Vec<&str> = plan.cancel.iter().map(String::as_str).collect();
+            self.client
+                .cancel_orders(&order_refs)
+                .await
+                .map_err(|e| OrderError::SdkError(e.to_string()))?;
+            for order_id in &plan.cancel {
+                if let Some(order) = self.orders.get_mut(order_id) {
+                    order.status = OrderStatus::Cancelled;
+                }
+            }
+        }
+
+        tracing::debug!(
+            token_id = token_id,
+            strategy_id = strategy_id,
+            kept = plan.keep.len(),
+            cancelled = plan.cancel.len(),
+            placing = plan.place.len(),
+            "Replacing orders"
+        );
+        Ok(plan)
+    }
+
+    /// Start working a large BUY or SELL with an execution algo. Returns the
+    /// parent order's ID (None unless `order` is a BUY or SELL).
+    pub fn start_algo(
+        &mut self,
+        strategy_id: Option<&str>,
+        order: Signal,
+        algo: ExecutionAlgo,
+    ) -> Option<String> {
+        let id = format!("algo-{}", self.next_algo_id + 1);
+        let strategy = strategy_id.map(String::from);
+        let parent = ParentOrder::new(id.clone(), strategy, order, algo, Instant::now())?;
+        self.next_algo_id += 1;
+        tracing::info!(
+            parent_id = id.as_str(),
+            token_id = parent.token_id(),
+            strategy_id = strategy_id,
+            size = %parent.size(),
+            algo = ?algo,
+            "Algo order started"
+        );
+        self.algos.insert(id.clone(), parent);
+        Some(id)
+    }
+
+    /// What each parent order does next at `now`: the children to cancel and
+    /// the child to place, which is left to the caller (who then registers it
+    /// with [`Self::add_algo_child`]). Finished parents are reported and
+    /// dropped; parents with nothing to do are left out.
+    pub fn algo_steps(&mut self, now: Instant) -> Vec<(String, AlgoStep)> {
+        let mut steps = Vec::new();
+        for (id, parent) in &mut self.algos {
+            let resting: Vec<&Order> = parent.children.iter().filter_map(|c| self.orders.get(c)).collect();
+            steps.push((id.clone(), parent.next_step(now, &resting)));
+        }
+        for (id, _) in steps.iter().filter(|(_, step)| step.done) {
+            self.finish_algo(id, "completed");
+        }
+        steps.retain(|(_, step)| !step.done && (step.place.is_some() || !step.cancel.is_empty()));
+        steps
+    }
+
+    /// Record a child order placed for a parent order.
+    pub fn add_algo_child(&mut self, parent_id: &str, order_id: &str) {
+        if let Some(parent) = self.algos.get_mut(parent_id) {
+            parent.children.push(order_id.to_string());
+            self.algo_children.insert(order_id.to_string(), parent_id.to_string());
+        }
+    }
+
+    /// Stop working a parent order. Returns its children still resting, for
+    /// the caller to cancel.
+    pub fn stop_algo(&mut self, parent_id: &str, reason: &str) -> Vec<String> {
+        let Some(parent) = self.finish_algo(parent_id, reason) else {
+            return Vec::new();
+        };
+        parent
+            .children
+            .into_iter()
+            .filter(|id| self.orders.get(id).is_some_and(Order::is_active))
+            .collect()
+    }
+
+    /// Get a parent order by ID.
+    pub fn algo_order(&self, parent_id: &str) -> Option<&ParentOrder> {
+        self.algos.get(parent_id)
+    }
+
+    /// Parent orders being worked.
+    pub fn algo_orders(&self) -> Vec<&ParentOrder> {
+        self.algos.values().collect()
+    }
+
+    /// Drop a parent order, reporting its children's fills as one order.
+    fn finish_algo(&mut self, parent_id: &str, reason: &str) -> Option<ParentOrder> {
+        let parent = self.algos.remove(parent_id)?;
+        for child in &parent.children {
+            self.algo_children.remove(child);
+        }
+        let (_, is_buy, price, size) = parent.order.order_terms()?;
+        tracing::info!(
+            parent_id = parent_id,
+            token_id = parent.token_id(),
+            strategy_id = parent.strategy_id.as_deref(),
+            side = if is_buy { "BUY" } else { "SELL" },
+            price = %price,
+            size = %size,
+            filled = %parent.filled,
+            avg_price = parent.avg_price().map(|p| p.round_dp(4).to_string()),
+            children = parent.children.len(),
+            reason = reason,
+            "Algo order finished"
+        );
+        Some(parent)
+    }
+
+    /// Cancel all orders for a token (stopping the algos working it).
     pub async fn cancel_all(&mut self, token_id: &str) -> Result<usize, OrderError> {
+        let parents: Vec<String> = self
+            .algos
+            .values()
+            .filter(|p| p.token_id() == token_id)
+            .map(|p| p.id.clone())
+            .collect();
+        for parent_id in parents {
+            self.stop_algo(&parent_id, "token orders cancelled");
+        }
+
         let to_cancel: Vec<String> = self
             .orders
             .iter()
@@ -193,8 +698,29 @@ impl OrderManager {
         Ok(count)
     }
 
-    /// Process a fill from the exchange.
-    pub async fn process_fill(&mut self, order_id: &str, price: Decimal, size: Decimal) -> Result<(), OrderError> {
+    /// Process a fill from the exchange, charged `fee_rate_bps` (None = the
+    /// token's rate in the CLOB fee schedule).
+    pub async fn process_fill(
+        &mut self,
+        order_id: &str,
+        price: Decimal,
+        size: Decimal,
+        fee_rate_bps: Option<Decimal>,
+    ) -> Result<(), OrderError> {
+        let Some(token_id) = self.orders.get(order_id).map(|o| o.token_id.clone()) else {
+            return Ok(());
+        };
+        let fee_rate_bps = match fee_rate_bps {
+            Some(rate) => rate,
+            None => self.client.fee_rate_bps(&token_id).await.unwrap_or_else(|e| {
+                tracing::warn!(
+                    token_id = token_id.as_str(),
+                    error = %e,
+                    "Failed to fetch fee rate, booking no fee"
+                );
+                Decimal::ZERO
+            }),
+        };
         if let Some(order) = self.orders.get_mut(order_id) {
             order.filled_size += size;
             if order.filled_size >= order.size {
@@ -210,8 +736,12 @@ impl OrderManager {
                 price,
                 size,
                 timestamp: chrono::Utc::now(),
-                fee: Decimal::ZERO, // TODO: Calculate actual fee
+                fee: fees::fee(fee_rate_bps, price, size),
+                strategy_id: order.strategy_id.clone(),
             };
+            if let Some(parent) = self.algo_children.get(order_id).and_then(|p| self.algos.get_mut(p)) {
+                parent.record_fill(price, size);
+            }
 
             tracing::info!(
                 order_id = order_id,
@@ -219,6 +749,8 @@ impl OrderManager {
                 side = if fill.is_buy { "BUY" } else { "SELL" },
                 price = %fill.price,
                 size = %fill.size,
+                fee = %fill.fee,
+                strategy_id = fill.strategy_id.as_deref(),
                 "Order filled"
             );
 
@@ -227,6 +759,31 @@ impl OrderManager {
         Ok(())
     }
 
+    /// Track an order placed before a restart.
+    pub fn restore(&mut self, order: Order) {
+        self.orders.insert(order.id.clone(), order);
+    }
+
+    /// Tag an order with the strategy whose signal placed it; its fills
+    /// carry the tag.
+    pub fn attribute(&mut self, order_id: &str, strategy_id: &str) {
+        if let Some(order) = self.orders.get_mut(order_id) {
+            order.strategy_id = Some(strategy_id.to_string());
+        }
+    }
+
+    /// Stop tracking an order the exchange no longer lists as open (it was
+    /// filled or cancelled without us seeing it). Returns whether it was active.
+    pub fn close(&mut self, order_id: &str) -> bool {
+        match self.orders.get_mut(order_id) {
+            Some(order) if order.is_active() => {
+                order.status = OrderStatus::Cancelled;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get an order by ID.
     pub fn get_order(&self, order_id: &str) -> Option<&Order> {
         self.orders.get(order_id)
@@ -237,6 +794,11 @@ impl OrderManager {
         self.orders.values().filter(|o| o.is_active()).collect()
     }
 
+    /// Active orders placed more than `max_age` before `now`.
+    pub fn expired_orders(&self, max_age: Duration, now: chrono::DateTime<chrono::Utc>) -> Vec<&Order> {
+        self.orders.values().filter(|o| o.is_expired(max_age, now)).collect()
+    }
+
     /// Get active orders for a token.
     pub fn active_orders_for_token(&self, token_id: &str) -> Vec<&Order> {
         self.orders
@@ -246,11 +808,66 @@ impl OrderManager {
     }
 }
 
+/// Settle the placement results of a pair's legs (in leg order). If a leg
+/// failed, the placed legs are cancelled with `cancel` and the first failure
+/// returned, as [`OrderError::PartialPair`] if any of those cancels failed.
+async fn settle_pair<C, Fut>(results: Vec<Result<String, OrderError>>, mut cancel: C) -> Result<Vec<String>, OrderError>
+where
+    C: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<(), OrderError>>,
+{
+    let mut placed = Vec::with_capacity(results.len());
+    let mut failure = None;
+    for (leg, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(order_id) => placed.push((leg, order_id)),
+            Err(e) => {
+                failure.get_or_insert(e);
+            }
+        }
+    }
+    let Some(cause) = failure else {
+        return Ok(placed.into_iter().map(|(_, order_id)| order_id).collect());
+    };
+
+    // Don't leave half a pair resting
+    let mut live = Vec::new();
+    for (leg, order_id) in placed {
+        if let Err(e) = cancel(order_id.clone()).await {
+            tracing::error!(order_id = order_id.as_str(), error = %e, "Failed to cancel paired order leg");
+            live.push((leg, order_id));
+        }
+    }
+    if live.is_empty() {
+        return Err(cause);
+    }
+    Err(OrderError::PartialPair {
+        cause: Box::new(cause),
+        live,
+    })
+}
+
 #[derive(Debug)]
 pub enum OrderError {
     SdkError(String),
     ChannelClosed,
     InvalidOrder(String),
+    /// A paired order failed and some of its placed legs (leg index, order
+    /// ID) could not be cancelled.
+    PartialPair {
+        cause: Box<OrderError>,
+        live: Vec<(usize, String)>,
+    },
+}
+
+impl OrderError {
+    /// Legs of a failed pair that may still rest on the book (leg index, order ID).
+    pub fn live_legs(&self) -> &[(usize, String)] {
+        match self {
+            OrderError::PartialPair { live, .. } => live,
+            _ => &[],
+        }
+    }
 }
 
 impl std::fmt::Display for OrderError {
@@ -259,8 +876,127 @@ impl std::fmt::Display for OrderError {
             OrderError::SdkError(e) => write!(f, "SDK error: {}", e),
             OrderError::ChannelClosed => write!(f, "Fill channel closed"),
             OrderError::InvalidOrder(e) => write!(f, "Invalid order: {}", e),
+            OrderError::PartialPair { cause, live } => {
+                write!(f, "{} ({} placed legs could not be cancelled)", cause, live.len())
+            }
         }
     }
 }
 
 impl std::error::Error for OrderError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failed_pair_reports_legs_it_could_not_cancel() {
+        let failed = || Err(OrderError::SdkError("rejected".to_string()));
+        let mut cancelled = Vec::new();
+
+        // Leg 1 fails to place, leg 0's cancel fails and leg 2's succeeds
+        let result = settle_pair(vec![Ok("a".to_string()), failed(), Ok("c".to_string())], |order_id| {
+            cancelled.push(order_id.clone());
+            async move {
+                match order_id.as_str() {
+                    "a" => Err(OrderError::SdkError("timeout".to_string())),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .await;
+        assert_eq!(cancelled, ["a", "c"]);
+        let e = result.unwrap_err();
+        assert!(matches!(e, OrderError::PartialPair { ref cause, .. } if matches!(**cause, OrderError::SdkError(_))));
+        assert_eq!(e.live_legs(), [(0, "a".to_string())]);
+
+        // Every cancel succeeds: the placement error as is
+        let result = settle_pair(vec![Ok("a".to_string()), failed()], |_| async { Ok(()) }).await;
+        assert!(matches!(result, Err(OrderError::SdkError(_))));
+
+        let result = settle_pair(vec![Ok("a".to_string()), Ok("b".to_string())], |_| async { Ok(()) }).await;
+        assert_eq!(result.unwrap(), ["a", "b"]);
+    }
+
+    fn resting(id: &str, is_buy: bool, price: Decimal, size: Decimal, filled: Decimal) -> Order {
+        Order {
+            id: id.to_string(),
+            token_id: "token".to_string(),
+            is_buy,
+            price,
+            size,
+            filled_size: filled,
+            status: OrderStatus::Open,
+            created_at: chrono::Utc::now(),
+            ack_latency: None,
+            over_budget: false,
+            strategy_id: None,
+        }
+    }
+
+    #[test]
+    fn test_replace_keeps_matching_orders() {
+        let quote = |is_buy: bool, price, size| {
+            let (token_id, urgency) = ("token".to_string(), crate::strategy::Urgency::Low);
+            if is_buy {
+                Signal::Buy { token_id, price, size, urgency, post_only: true, expires_at: None }
+            } else {
+                Signal::Sell { token_id, price, size, urgency, post_only: true, expires_at: None }
+            }
+        };
+        let bid = resting("bid", true, dec!(0.45), dec!(10), dec!(0.5));
+        let ask = resting("ask", false, dec!(0.55), dec!(10), dec!(0));
+        let stale = resting("stale", false, dec!(0.60), dec!(10), dec!(0));
+
+        // The bid is within 10% of the wanted size; the ask moved a cent
+        let wanted = [quote(true, dec!(0.451), dec!(10)), quote(false, dec!(0.54), dec!(10))];
+        let plan = plan_replace("token", &[&bid, &ask, &stale], &wanted, DEFAULT_REPLACE_TOLERANCE);
+        assert_eq!(plan.keep, vec!["bid".to_string()]);
+        let mut cancel = plan.cancel.clone();
+        cancel.sort();
+        assert_eq!(cancel, vec!["ask".to_string(), "stale".to_string()]);
+        assert_eq!(plan.place.len(), 1);
+        assert!(matches!(plan.place[0], Signal::Sell { price, .. } if price == dec!(0.54)));
+
+        // Too far off the wanted size, or wanted twice: only one order is kept
+        let wanted = [
+            quote(true, dec!(0.45), dec!(20)),
+            quote(false, dec!(0.55), dec!(10)),
+            quote(false, dec!(0.55), dec!(10)),
+        ];
+        let plan = plan_replace("token", &[&bid, &ask], &wanted, DEFAULT_REPLACE_TOLERANCE);
+        assert_eq!((plan.keep, plan.cancel), (vec!["ask".to_string()], vec!["bid".to_string()]));
+        assert_eq!(plan.place.len(), 2);
+
+        // Nothing wanted cancels everything
+        assert_eq!(plan_replace("token", &[&bid], &[], dec!(0)).cancel, vec!["bid".to_string()]);
+    }
+
+    #[test]
+    fn test_orders_expire_after_max_age() {
+        let now = chrono::Utc::now();
+        let mut order = resting("old", true, dec!(0.45), dec!(10), dec!(0));
+        order.created_at = now - chrono::Duration::seconds(90);
+        let max_age = Duration::from_secs(60);
+
+        assert!(order.is_expired(max_age, now));
+        assert!(!order.is_expired(Duration::from_secs(120), now));
+        order.status = OrderStatus::Filled;
+        assert!(!order.is_expired(max_age, now));
+    }
+
+    #[test]
+    fn test_latency_metrics_count_violations() {
+        let mut metrics = LatencyMetrics::default();
+        let budget = Some(Duration::from_millis(750));
+
+        assert!(!metrics.record(Duration::from_millis(200), budget));
+        assert!(metrics.record(Duration::from_millis(900), budget));
+        assert!(!metrics.record(Duration::from_millis(5_000), None));
+
+        assert_eq!(metrics.orders, 3);
+        assert_eq!(metrics.violations, 1);
+        assert_eq!(metrics.max_latency, Duration::from_millis(5_000));
+        assert_eq!(metrics.avg_latency().as_millis(), 2_033);
+    }
+}
@@ -1,13 +1,23 @@
 //! Order book management with full depth tracking.
 //!
-//! Maintains local order book state from WebSocket updates and provides
-//! broadcast channels for market data distribution.
+//! Maintains local order book state and the last trade price per token from
+//! WebSocket updates, and provides broadcast channels for market data
+//! distribution.
+//!
+//! The hub also keeps [`RollingStats`] per token over its last
+//! [`STATS_WINDOW`] valid book updates and trades: realized volatility of the
+//! mid price, order-flow imbalance at the top of the book and the balance of
+//! buy and sell trades. Strategies read them as [`MarketStats`] through
+//! `StrategyContext::stats`.
 
+use crate::webhook::ExternalEvent;
 use async_broadcast::{Receiver, Sender};
-use polymarket_client_sdk::clob::ws::types::response::{BookUpdate, OrderBookLevel};
+use polymarket_client_sdk::clob::ws::types::response::{BookUpdate, LastTradePrice, OrderBookLevel};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// A single price level in the order book.
@@ -26,6 +36,27 @@ impl From<&OrderBookLevel> for Level {
     }
 }
 
+/// Top-of-book consistency state.
+///
+/// A maintained book should never have best bid >= best ask; if it does we
+/// missed an update or the upstream glitched, and derived prices are garbage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookHealth {
+    /// Best bid below best ask (or at least one side empty)
+    Normal,
+    /// Best bid equals best ask
+    Locked,
+    /// Best bid above best ask
+    Crossed,
+}
+
+impl BookHealth {
+    /// Whether prices derived from the book can be trusted.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, BookHealth::Normal)
+    }
+}
+
 /// Full-depth order book for a single token.
 #[derive(Debug, Clone)]
 pub struct OrderBook {
@@ -38,6 +69,8 @@ pub struct OrderBook {
     pub timestamp: i64,
     /// Book hash for validation
     pub hash: Option<String>,
+    /// When the engine last received this book (None = never)
+    pub received_at: Option<Instant>,
 }
 
 impl OrderBook {
@@ -49,6 +82,7 @@ impl OrderBook {
             asks: Vec::new(),
             timestamp: 0,
             hash: None,
+            received_at: None,
         }
     }
 
@@ -58,6 +92,27 @@ impl OrderBook {
         self.asks = update.asks.iter().map(Level::from).collect();
         self.timestamp = update.timestamp;
         self.hash = update.hash.clone();
+        self.received_at = Some(Instant::now());
+    }
+
+    /// Whether no update has been received for longer than `max_age` (or
+    /// ever): its prices may no longer reflect the market.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.received_at.is_none_or(|at| at.elapsed() > max_age)
+    }
+
+    /// Top-of-book consistency check.
+    pub fn health(&self) -> BookHealth {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) if bid.price > ask.price => BookHealth::Crossed,
+            (Some(bid), Some(ask)) if bid.price == ask.price => BookHealth::Locked,
+            _ => BookHealth::Normal,
+        }
+    }
+
+    /// Whether the book is neither crossed nor locked.
+    pub fn is_valid(&self) -> bool {
+        self.health().is_valid()
     }
 
     /// Best bid price and size.
@@ -83,15 +138,36 @@ impl OrderBook {
     }
 
     /// Mid price (average of best bid and ask).
+    /// Returns None if the book is crossed or locked.
     pub fn mid_price(&self) -> Option<Decimal> {
+        if !self.is_valid() {
+            return None;
+        }
         match (self.best_bid(), self.best_ask()) {
             (Some(bid), Some(ask)) => Some((bid.price + ask.price) / Decimal::TWO),
             _ => None,
         }
     }
 
+    /// Microprice: the mid weighted towards the side with less size at the
+    /// top, `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`.
+    /// Returns None if the book is one-sided, crossed or locked.
+    pub fn microprice(&self) -> Option<Decimal> {
+        let mid = self.mid_price()?;
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let size = bid.size + ask.size;
+        if size.is_zero() {
+            return Some(mid);
+        }
+        Some((bid.price * ask.size + ask.price * bid.size) / size)
+    }
+
     /// Spread (best ask - best bid).
+    /// Returns None if the book is crossed or locked.
     pub fn spread(&self) -> Option<Decimal> {
+        if !self.is_valid() {
+            return None;
+        }
         match (self.best_bid(), self.best_ask()) {
             (Some(bid), Some(ask)) => Some(ask.price - bid.price),
             _ => None,
@@ -137,8 +213,11 @@ impl OrderBook {
     }
 
     /// Volume-weighted average price for buying `size` units.
-    /// Returns None if insufficient liquidity.
+    /// Returns None if insufficient liquidity or the book is crossed/locked.
     pub fn vwap_buy(&self, size: Decimal) -> Option<Decimal> {
+        if !self.is_valid() {
+            return None;
+        }
         let mut remaining = size;
         let mut total_cost = Decimal::ZERO;
 
@@ -159,8 +238,11 @@ impl OrderBook {
     }
 
     /// Volume-weighted average price for selling `size` units.
-    /// Returns None if insufficient liquidity.
+    /// Returns None if insufficient liquidity or the book is crossed/locked.
     pub fn vwap_sell(&self, size: Decimal) -> Option<Decimal> {
+        if !self.is_valid() {
+            return None;
+        }
         let mut remaining = size;
         let mut total_proceeds = Decimal::ZERO;
 
@@ -194,6 +276,199 @@ impl OrderBook {
     }
 }
 
+/// The most recent trade in a token (from the last-trade feed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastTrade {
+    pub price: Decimal,
+    /// Trade size (zero when the feed omits it)
+    pub size: Decimal,
+    /// Taker side, "BUY" or "SELL" (empty when the feed omits it)
+    pub side: String,
+    /// Exchange timestamp (Unix milliseconds)
+    pub timestamp: i64,
+}
+
+/// Book updates and trades kept per token for rolling statistics.
+pub const STATS_WINDOW: usize = 120;
+
+/// Top of a valid two-sided book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Quote {
+    bid: Decimal,
+    bid_size: Decimal,
+    ask: Decimal,
+    ask_size: Decimal,
+}
+
+impl Quote {
+    fn of(book: &OrderBook) -> Option<Self> {
+        if !book.is_valid() {
+            return None;
+        }
+        let (bid, ask) = (book.best_bid()?, book.best_ask()?);
+        Some(Self {
+            bid: bid.price,
+            bid_size: bid.size,
+            ask: ask.price,
+            ask_size: ask.size,
+        })
+    }
+
+    fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+
+    /// Order-flow contribution of moving from `prev` to this quote (Cont,
+    /// Kukanov and Stoikov): size added at or above the bid and removed at or
+    /// below the ask counts as buying pressure, and the reverse as selling.
+    fn order_flow_since(&self, prev: &Quote) -> Decimal {
+        let mut flow = Decimal::ZERO;
+        if self.bid >= prev.bid {
+            flow += self.bid_size;
+        }
+        if self.bid <= prev.bid {
+            flow -= prev.bid_size;
+        }
+        if self.ask <= prev.ask {
+            flow -= self.ask_size;
+        }
+        if self.ask >= prev.ask {
+            flow += prev.ask_size;
+        }
+        flow
+    }
+}
+
+/// Short-horizon statistics of one token, from the most recent book updates
+/// and trades (ring buffers of `capacity` entries each).
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    capacity: usize,
+    quotes: VecDeque<Quote>,
+    /// Trade sizes, signed by taker side (buys positive)
+    trades: VecDeque<Decimal>,
+}
+
+impl RollingStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(2),
+            quotes: VecDeque::new(),
+            trades: VecDeque::new(),
+        }
+    }
+
+    /// Record a book update (crossed, locked and one-sided books are skipped).
+    pub fn record_book(&mut self, book: &OrderBook) {
+        if let Some(quote) = Quote::of(book) {
+            push_bounded(&mut self.quotes, quote, self.capacity);
+        }
+    }
+
+    /// Record a trade (trades without a known side are skipped).
+    pub fn record_trade(&mut self, trade: &LastTrade) {
+        let signed = match trade.side.as_str() {
+            "BUY" => trade.size,
+            "SELL" => -trade.size,
+            _ => return,
+        };
+        push_bounded(&mut self.trades, signed, self.capacity);
+    }
+
+    /// Standard deviation of the change in mid price between consecutive
+    /// book updates (None with fewer than three updates).
+    pub fn volatility(&self) -> Option<Decimal> {
+        if self.quotes.len() < 3 {
+            return None;
+        }
+        let changes: Vec<f64> = self
+            .quotes
+            .iter()
+            .zip(self.quotes.iter().skip(1))
+            .filter_map(|(prev, next)| (next.mid() - prev.mid()).to_f64())
+            .collect();
+        let n = changes.len() as f64;
+        let mean = changes.iter().sum::<f64>() / n;
+        let variance = changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Decimal::from_f64_retain(variance.sqrt()).map(|v| v.round_dp(6))
+    }
+
+    /// Net order flow at the top of the book over the window, in shares:
+    /// positive when bids are building or asks being taken (None with fewer
+    /// than two updates).
+    pub fn order_flow_imbalance(&self) -> Option<Decimal> {
+        if self.quotes.len() < 2 {
+            return None;
+        }
+        let flow = self
+            .quotes
+            .iter()
+            .zip(self.quotes.iter().skip(1))
+            .map(|(prev, next)| next.order_flow_since(prev));
+        Some(crate::safe_math::sum(flow, "order flow imbalance"))
+    }
+
+    /// Return of the mid price over the window, `last / first - 1` (None
+    /// with fewer than two updates).
+    pub fn mid_return(&self) -> Option<Decimal> {
+        let (first, last) = (self.quotes.front()?, self.quotes.back()?);
+        if self.quotes.len() < 2 || first.mid().is_zero() {
+            return None;
+        }
+        Some((last.mid() / first.mid() - Decimal::ONE).round_dp(6))
+    }
+
+    /// `(buy volume - sell volume) / total volume` of the window's trades,
+    /// from -1 (all sells) to 1 (all buys). None before any trade.
+    pub fn trade_imbalance(&self) -> Option<Decimal> {
+        let net: Decimal = self.trades.iter().sum();
+        let total: Decimal = self.trades.iter().map(|t| t.abs()).sum();
+        (!total.is_zero()).then(|| net / total)
+    }
+
+    /// The statistics as of now.
+    pub fn snapshot(&self) -> MarketStats {
+        MarketStats {
+            volatility: self.volatility(),
+            order_flow_imbalance: self.order_flow_imbalance(),
+            mid_return: self.mid_return(),
+            trade_imbalance: self.trade_imbalance(),
+            book_updates: self.quotes.len(),
+            trades: self.trades.len(),
+        }
+    }
+}
+
+impl Default for RollingStats {
+    fn default() -> Self {
+        Self::new(STATS_WINDOW)
+    }
+}
+
+fn push_bounded<T>(buffer: &mut VecDeque<T>, value: T, capacity: usize) {
+    if buffer.len() == capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(value);
+}
+
+/// Rolling statistics of a token, as seen by strategies.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketStats {
+    /// Standard deviation of mid-price changes between book updates
+    pub volatility: Option<Decimal>,
+    /// Net top-of-book order flow, in shares (positive = buying pressure)
+    pub order_flow_imbalance: Option<Decimal>,
+    /// Return of the mid price over the window (short-horizon momentum)
+    pub mid_return: Option<Decimal>,
+    /// Buy minus sell trade volume over total volume, from -1 to 1
+    pub trade_imbalance: Option<Decimal>,
+    /// Book updates in the window
+    pub book_updates: usize,
+    /// Trades in the window
+    pub trades: usize,
+}
+
 /// Market data event for broadcast.
 #[derive(Debug, Clone)]
 pub enum MarketEvent {
@@ -210,12 +485,25 @@ pub enum MarketEvent {
         side: String,
         timestamp: i64,
     },
+    /// Book became crossed or locked after an update (resync needed)
+    BookInvalid {
+        token_id: String,
+        health: BookHealth,
+    },
+    /// Signal from outside the engine (from the webhook)
+    External(ExternalEvent),
 }
 
 /// Market data hub - maintains order books and broadcasts updates.
 pub struct MarketDataHub {
     /// Order books by token ID
     books: RwLock<HashMap<String, Arc<OrderBook>>>,
+    /// Tokens whose latest book is crossed or locked
+    invalid_books: RwLock<HashSet<String>>,
+    /// Last trade by token ID
+    last_trades: RwLock<HashMap<String, LastTrade>>,
+    /// Rolling statistics by token ID
+    stats: RwLock<HashMap<String, RollingStats>>,
     /// Broadcast sender for market events
     tx: Sender<MarketEvent>,
     /// Template receiver (clone this for new subscribers)
@@ -230,6 +518,9 @@ impl MarketDataHub {
         tx.set_overflow(true);
         Self {
             books: RwLock::new(HashMap::new()),
+            invalid_books: RwLock::new(HashSet::new()),
+            last_trades: RwLock::new(HashMap::new()),
+            stats: RwLock::new(HashMap::new()),
             tx,
             rx,
         }
@@ -250,8 +541,28 @@ impl MarketDataHub {
         self.books.read().await.clone()
     }
 
+    /// Get all current order books that are neither crossed nor locked.
+    pub async fn get_valid_books(&self) -> HashMap<String, Arc<OrderBook>> {
+        self.books
+            .read()
+            .await
+            .iter()
+            .filter(|(_, book)| book.is_valid())
+            .map(|(token_id, book)| (token_id.clone(), book.clone()))
+            .collect()
+    }
+
+    /// Token IDs whose latest book is crossed or locked.
+    pub async fn invalid_books(&self) -> Vec<String> {
+        self.invalid_books.read().await.iter().cloned().collect()
+    }
+
     /// Process a WebSocket book update.
-    pub async fn process_book_update(&self, update: BookUpdate) {
+    ///
+    /// Returns the health of the updated book. A crossed or locked book is
+    /// flagged invalid and announced with [`MarketEvent::BookInvalid`]; the
+    /// caller is responsible for resyncing.
+    pub async fn process_book_update(&self, update: BookUpdate) -> BookHealth {
         let token_id = update.asset_id.to_string();
 
         // Update or create order book
@@ -271,11 +582,113 @@ impl MarketDataHub {
             new_book
         };
 
+        let health = book.health();
+        self.record_health(&token_id, health).await;
+        self.stats.write().await.entry(token_id.clone()).or_default().record_book(&book);
+
         // Broadcast update
         let _ = self.tx.broadcast(MarketEvent::BookUpdate {
             token_id,
             book,
         }).await;
+
+        health
+    }
+
+    /// Replace a token's book outright (for books not fed by the WebSocket,
+    /// such as synthetic markets).
+    pub async fn set_book(&self, mut book: OrderBook) -> BookHealth {
+        book.received_at = Some(Instant::now());
+        let token_id = book.token_id.clone();
+        let book = Arc::new(book);
+        self.books.write().await.insert(token_id.clone(), book.clone());
+
+        let health = book.health();
+        self.record_health(&token_id, health).await;
+        self.stats.write().await.entry(token_id.clone()).or_default().record_book(&book);
+
+        let _ = self.tx.broadcast(MarketEvent::BookUpdate {
+            token_id,
+            book,
+        }).await;
+
+        health
+    }
+
+    /// Track the validity of a token's book, announcing transitions to invalid.
+    async fn record_health(&self, token_id: &str, health: BookHealth) {
+        if health.is_valid() {
+            if self.invalid_books.write().await.remove(token_id) {
+                tracing::info!(token_id = token_id, "Order book valid again");
+            }
+            return;
+        }
+
+        if self.invalid_books.write().await.insert(token_id.to_string()) {
+            tracing::warn!(token_id = token_id, health = ?health, "Order book crossed/locked, flagged invalid");
+            let _ = self.tx.broadcast(MarketEvent::BookInvalid {
+                token_id: token_id.to_string(),
+                health,
+            }).await;
+        }
+    }
+
+    /// Process a WebSocket last-trade update: remember it as the token's last
+    /// trade and broadcast [`MarketEvent::Trade`]. Updates older than the
+    /// trade already seen are ignored.
+    pub async fn process_trade(&self, update: &LastTradePrice) {
+        let token_id = update.asset_id.to_string();
+        let trade = LastTrade {
+            price: update.price,
+            size: update.size.unwrap_or_default(),
+            side: update.side.map(|side| side.to_string()).unwrap_or_default(),
+            timestamp: update.timestamp,
+        };
+
+        {
+            let mut last_trades = self.last_trades.write().await;
+            if last_trades
+                .get(&token_id)
+                .is_some_and(|last| last.timestamp > trade.timestamp)
+            {
+                return;
+            }
+            last_trades.insert(token_id.clone(), trade.clone());
+        }
+        self.stats.write().await.entry(token_id.clone()).or_default().record_trade(&trade);
+
+        let _ = self.tx.broadcast(MarketEvent::Trade {
+            token_id,
+            price: trade.price,
+            size: trade.size,
+            side: trade.side,
+            timestamp: trade.timestamp,
+        }).await;
+    }
+
+    /// Broadcast an external event to subscribers.
+    pub async fn publish_external(&self, event: ExternalEvent) {
+        let _ = self.tx.broadcast(MarketEvent::External(event)).await;
+    }
+
+    /// Last trade in a token (None before the first one is seen).
+    pub async fn last_trade(&self, token_id: &str) -> Option<LastTrade> {
+        self.last_trades.read().await.get(token_id).cloned()
+    }
+
+    /// Last trade of every token that has traded.
+    pub async fn last_trades(&self) -> HashMap<String, LastTrade> {
+        self.last_trades.read().await.clone()
+    }
+
+    /// Rolling statistics of every token with book updates or trades.
+    pub async fn stats(&self) -> HashMap<String, MarketStats> {
+        self.stats
+            .read()
+            .await
+            .iter()
+            .map(|(token_id, stats)| (token_id.clone(), stats.snapshot()))
+            .collect()
     }
 
     /// Initialize an empty book for a token (for subscriptions).
@@ -286,6 +699,28 @@ impl MarketDataHub {
             .or_insert_with(|| Arc::new(OrderBook::new(token_id.to_string())));
     }
 
+    /// Forget a token no longer subscribed to: its book, last trade and stats.
+    pub async fn remove_book(&self, token_id: &str) {
+        self.books.write().await.remove(token_id);
+        self.invalid_books.write().await.remove(token_id);
+        self.last_trades.write().await.remove(token_id);
+        self.stats.write().await.remove(token_id);
+    }
+
+    /// Tokens whose book hasn't been updated for longer than `max_age`.
+    pub async fn stale_books(&self, max_age: Duration) -> Vec<String> {
+        let mut stale: Vec<String> = self
+            .books
+            .read()
+            .await
+            .iter()
+            .filter(|(_, book)| book.is_stale(max_age))
+            .map(|(token_id, _)| token_id.clone())
+            .collect();
+        stale.sort();
+        stale
+    }
+
     /// Get number of tracked order books.
     pub async fn book_count(&self) -> usize {
         self.books.read().await.len()
@@ -361,6 +796,168 @@ mod tests {
         assert_eq!(book.vwap_buy(dec!(1000)), None);
     }
 
+    fn make_crossed_book() -> OrderBook {
+        let mut book = make_book();
+        book.bids.insert(0, Level { price: dec!(0.52), size: dec!(50) });
+        book
+    }
+
+    #[test]
+    fn test_book_health() {
+        assert_eq!(make_book().health(), BookHealth::Normal);
+        assert_eq!(make_crossed_book().health(), BookHealth::Crossed);
+
+        let mut locked = make_book();
+        locked.bids.insert(0, Level { price: dec!(0.51), size: dec!(50) });
+        assert_eq!(locked.health(), BookHealth::Locked);
+        assert!(!locked.is_valid());
+
+        // One-sided books are not crossed
+        let mut one_sided = make_book();
+        one_sided.asks.clear();
+        assert!(one_sided.is_valid());
+    }
+
+    #[test]
+    fn test_crossed_book_prices_unavailable() {
+        let book = make_crossed_book();
+        assert_eq!(book.mid_price(), None);
+        assert_eq!(book.spread(), None);
+        assert_eq!(book.spread_bps(), None);
+        assert_eq!(book.vwap_buy(dec!(10)), None);
+        assert_eq!(book.vwap_sell(dec!(10)), None);
+        // Raw levels remain accessible for diagnostics
+        assert_eq!(book.best_bid().unwrap().price, dec!(0.52));
+    }
+
+    #[tokio::test]
+    async fn test_hub_flags_invalid_books() {
+        let hub = MarketDataHub::new(16);
+        let mut rx = hub.subscribe();
+
+        hub.record_health("test", BookHealth::Crossed).await;
+        assert_eq!(hub.invalid_books().await, vec!["test".to_string()]);
+        match rx.try_recv() {
+            Ok(MarketEvent::BookInvalid { token_id, health }) => {
+                assert_eq!(token_id, "test");
+                assert_eq!(health, BookHealth::Crossed);
+            }
+            other => panic!("expected BookInvalid, got {:?}", other),
+        }
+
+        // Repeated invalid updates are only announced once
+        hub.record_health("test", BookHealth::Crossed).await;
+        assert!(rx.try_recv().is_err());
+
+        hub.record_health("test", BookHealth::Normal).await;
+        assert!(hub.invalid_books().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hub_tracks_last_trade() {
+        let hub = MarketDataHub::new(16);
+        let mut rx = hub.subscribe();
+        let update = |price: &str, timestamp: i64| -> LastTradePrice {
+            serde_json::from_value(serde_json::json!({
+                "asset_id": "123",
+                "market": "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+                "price": price,
+                "side": "BUY",
+                "size": "25",
+                "timestamp": timestamp.to_string()
+            }))
+            .unwrap()
+        };
+
+        assert!(hub.last_trade("123").await.is_none());
+        hub.process_trade(&update("0.57", 1_700_000_000_000)).await;
+        let expected = LastTrade {
+            price: dec!(0.57),
+            size: dec!(25),
+            side: "BUY".to_string(),
+            timestamp: 1_700_000_000_000,
+        };
+        assert_eq!(hub.last_trade("123").await, Some(expected.clone()));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(MarketEvent::Trade { token_id, price, .. }) if token_id == "123" && price == dec!(0.57)
+        ));
+
+        // An update older than the last trade doesn't replace it
+        hub.process_trade(&update("0.55", 1_699_999_999_000)).await;
+        assert_eq!(hub.last_trades().await["123"], expected);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_microprice_leans_to_thin_side() {
+        let mut book = make_book();
+        assert_eq!(book.microprice(), Some(dec!(0.505)));
+        // Little left on the ask: the next trade is likelier to lift it
+        book.asks[0].size = dec!(25);
+        assert_eq!(book.microprice(), Some(dec!(0.508)));
+        assert_eq!(make_crossed_book().microprice(), None);
+    }
+
+    #[test]
+    fn test_rolling_stats() {
+        let quote = |bid: Decimal, bid_size: Decimal, ask: Decimal, ask_size: Decimal| {
+            let mut book = OrderBook::new("test".to_string());
+            book.bids = vec![Level { price: bid, size: bid_size }];
+            book.asks = vec![Level { price: ask, size: ask_size }];
+            book
+        };
+        let mut stats = RollingStats::new(3);
+        assert_eq!(stats.snapshot(), MarketStats::default());
+
+        stats.record_book(&quote(dec!(0.50), dec!(100), dec!(0.52), dec!(100)));
+        // Bids added at the same price: +20 shares of buying pressure
+        stats.record_book(&quote(dec!(0.50), dec!(120), dec!(0.52), dec!(100)));
+        assert_eq!(stats.order_flow_imbalance(), Some(dec!(20)));
+        assert_eq!(stats.volatility(), None);
+        assert_eq!(stats.mid_return(), Some(dec!(0)));
+        stats.record_book(&make_crossed_book());
+        assert_eq!(stats.snapshot().book_updates, 2);
+
+        // Ask lifted to a new level: the old ask size is gone, 50 new at 0.53
+        stats.record_book(&quote(dec!(0.50), dec!(120), dec!(0.53), dec!(50)));
+        assert_eq!(stats.order_flow_imbalance(), Some(dec!(120)));
+        // Mid moved 0, then 0.005
+        assert_eq!(stats.volatility(), Some(dec!(0.003536)));
+        assert_eq!(stats.mid_return(), Some(dec!(0.009804)));
+
+        // The window drops the oldest update
+        stats.record_book(&quote(dec!(0.50), dec!(120), dec!(0.53), dec!(50)));
+        assert_eq!(stats.order_flow_imbalance(), Some(dec!(100)));
+
+        let trade = |side: &str, size: Decimal| LastTrade {
+            price: dec!(0.51),
+            size,
+            side: side.to_string(),
+            timestamp: 0,
+        };
+        stats.record_trade(&trade("BUY", dec!(30)));
+        stats.record_trade(&trade("SELL", dec!(10)));
+        stats.record_trade(&trade("", dec!(10)));
+        assert_eq!(stats.trade_imbalance(), Some(dec!(0.5)));
+        assert_eq!(stats.snapshot().trades, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_books() {
+        let mut book = make_book();
+        assert!(book.is_stale(Duration::from_secs(60)));
+        book.received_at = Some(Instant::now());
+        assert!(!book.is_stale(Duration::from_secs(60)));
+        book.received_at = Instant::now().checked_sub(Duration::from_secs(61));
+        assert!(book.is_stale(Duration::from_secs(60)));
+
+        let hub = MarketDataHub::new(16);
+        hub.init_book("quiet").await;
+        hub.set_book(make_book()).await;
+        assert_eq!(hub.stale_books(Duration::from_secs(60)).await, vec!["quiet".to_string()]);
+    }
+
     #[test]
     fn test_imbalance() {
         let book = make_book();
@@ -0,0 +1,308 @@
+//! Strategy pipelines: shared filter stages in front of a strategy.
+//!
+//! Most generated strategies start by skipping illiquid markets, wide books
+//! and markets too close to (or too far from) expiry. A [`Pipeline`] runs
+//! declarative [`Filter`] stages over the context first, so the strategy only
+//! sees tokens that pass every stage:
+//!
+//! ```text
+//! PMENGINE_STRATEGY_FILTERS="sure_bets=min_liquidity:500,hours_to_expiry:0-48;mm=max_spread_bps:300"
+//! ```
+//!
+//! Rejected tokens are removed from both `ctx.markets` and `ctx.order_books`.
+//! Filters that need market metadata (liquidity, category, expiry) only apply
+//! to tokens that have it; tokens a strategy subscribes to directly pass them.
+//! Those stages also narrow market discovery, through the pipeline's
+//! [`Strategy::discovery_filters`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+
+use crate::discovery::DiscoveryFilter;
+use crate::history::PricePoint;
+use crate::orderbook::OrderBook;
+use crate::position::Fill;
+use crate::strategy::{in_categories, MarketInfo, Signal, Strategy, StrategyContext, StrategyParams};
+use crate::webhook::ExternalEvent;
+
+/// One filter stage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Keep markets with at least this much Gamma liquidity (USDC); markets
+    /// with unknown liquidity are kept.
+    MinLiquidity(f64),
+    /// Keep markets in these (lowercase) Gamma categories.
+    Categories(Vec<String>),
+    /// Keep tokens whose book spread is at most this many basis points of mid.
+    /// Tokens without a two-sided book are dropped.
+    MaxSpreadBps(Decimal),
+    /// Keep markets expiring within this window (hours from now).
+    HoursToExpiry { min: f64, max: f64 },
+}
+
+impl Filter {
+    /// Parse `name:value`, e.g. `min_liquidity:500`, `categories:crypto|sports`,
+    /// `max_spread_bps:300` or `hours_to_expiry:1-48`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, value) = spec.split_once(':')?;
+        let value = value.trim();
+        match name.trim() {
+            "min_liquidity" => value.parse().ok().map(Filter::MinLiquidity),
+            "categories" => {
+                let categories: Vec<String> = value
+                    .split('|')
+                    .map(|c| c.trim().to_lowercase())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                (!categories.is_empty()).then_some(Filter::Categories(categories))
+            }
+            "max_spread_bps" => value.parse().ok().map(Filter::MaxSpreadBps),
+            "hours_to_expiry" => {
+                let (min, max) = value.split_once('-')?;
+                let (min, max) = (min.trim().parse().ok()?, max.trim().parse().ok()?);
+                (min <= max).then_some(Filter::HoursToExpiry { min, max })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a token passes this stage.
+    pub fn keep(&self, market: Option<&MarketInfo>, book: Option<&OrderBook>) -> bool {
+        match self {
+            Filter::MinLiquidity(min) => market
+                .and_then(|m| m.liquidity)
+                .is_none_or(|liquidity| liquidity >= *min),
+            Filter::Categories(categories) => {
+                market.is_none_or(|m| in_categories(m.category.as_deref(), categories))
+            }
+            Filter::MaxSpreadBps(max) => book
+                .and_then(|b| b.spread_bps())
+                .is_some_and(|spread| spread <= *max),
+            Filter::HoursToExpiry { min, max } => market.is_none_or(|m| {
+                m.hours_until_expiry
+                    .is_some_and(|hours| hours >= *min && hours <= *max)
+            }),
+        }
+    }
+
+    /// The equivalent market discovery filter, for stages that only need
+    /// Gamma metadata.
+    pub fn discovery_filter(&self) -> Option<DiscoveryFilter> {
+        match self {
+            Filter::MinLiquidity(min) => Some(DiscoveryFilter::MinLiquidity(*min)),
+            Filter::Categories(categories) => Some(DiscoveryFilter::Categories(categories.clone())),
+            Filter::MaxSpreadBps(_) => None,
+            Filter::HoursToExpiry { min, max } => Some(DiscoveryFilter::HoursToExpiry { min: *min, max: *max }),
+        }
+    }
+}
+
+/// A strategy behind a chain of filter stages.
+///
+/// The pipeline reports the inner strategy's ID, so category scopes, budgets
+/// and quarantine keep working on it unchanged.
+pub struct Pipeline {
+    filters: Vec<Filter>,
+    strategy: Box<dyn Strategy>,
+}
+
+impl Pipeline {
+    pub fn new(strategy: Box<dyn Strategy>) -> Self {
+        Self {
+            filters: Vec::new(),
+            strategy,
+        }
+    }
+
+    /// Append a filter stage.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Append several filter stages.
+    pub fn filters(mut self, filters: impl IntoIterator<Item = Filter>) -> Self {
+        self.filters.extend(filters);
+        self
+    }
+
+    /// The context limited to tokens that pass every stage.
+    pub fn apply(&self, ctx: &StrategyContext) -> StrategyContext {
+        let passes = |token_id: &String| {
+            let market = ctx.markets.get(token_id);
+            let book = ctx.order_books.get(token_id).map(Arc::as_ref);
+            self.filters.iter().all(|f| f.keep(market, book))
+        };
+
+        let markets = ctx
+            .markets
+            .iter()
+            .filter(|(token_id, _)| passes(token_id))
+            .map(|(token_id, m)| (token_id.clone(), m.clone()))
+            .collect();
+        let order_books = ctx
+            .order_books
+            .iter()
+            .filter(|(token_id, _)| passes(token_id))
+            .map(|(token_id, b)| (token_id.clone(), b.clone()))
+            .collect();
+        StrategyContext {
+            markets,
+            order_books,
+            ..ctx.clone()
+        }
+    }
+}
+
+impl Strategy for Pipeline {
+    fn id(&self) -> &str {
+        self.strategy.id()
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.strategy.subscriptions()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        if self.filters.is_empty() {
+            return self.strategy.on_tick(ctx);
+        }
+        let filtered = self.apply(ctx);
+        self.strategy.on_tick(&filtered)
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        self.strategy.configure(params)
+    }
+
+    fn book_update_interval(&self) -> Option<Duration> {
+        self.strategy.book_update_interval()
+    }
+
+    fn on_book_update(&mut self, token_id: &str, book: &OrderBook) -> Vec<Signal> {
+        self.strategy.on_book_update(token_id, book)
+    }
+
+    fn on_fill(&mut self, fill: &Fill) {
+        self.strategy.on_fill(fill);
+    }
+
+    fn on_external_event(&mut self, event: &ExternalEvent) -> Vec<Signal> {
+        self.strategy.on_external_event(event)
+    }
+
+    fn on_history(&mut self, token_id: &str, history: &[PricePoint]) {
+        self.strategy.on_history(token_id, history);
+    }
+
+    fn on_shutdown(&mut self) {
+        self.strategy.on_shutdown();
+    }
+
+    fn needs_baskets(&self) -> bool {
+        self.strategy.needs_baskets()
+    }
+
+    fn all_outcomes(&self) -> bool {
+        self.strategy.all_outcomes()
+    }
+
+    /// The inner strategy's criteria plus the stages discovery can apply
+    /// itself, so markets every token of would be rejected aren't subscribed.
+    fn discovery_filters(&self) -> Vec<DiscoveryFilter> {
+        let mut filters = self.strategy.discovery_filters();
+        filters.extend(self.filters.iter().filter_map(Filter::discovery_filter));
+        filters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::Level;
+    use chrono::{Duration, Utc};
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn market(liquidity: f64, category: &str, hours: i64) -> MarketInfo {
+        MarketInfo::with_liquidity(
+            "Q?".to_string(),
+            "Yes".to_string(),
+            "q".to_string(),
+            Some(Utc::now() + Duration::hours(hours)),
+            Some(liquidity),
+        )
+        .with_category(Some(category.to_string()))
+    }
+
+    fn book(token_id: &str, bid: Decimal, ask: Decimal) -> Arc<OrderBook> {
+        let mut book = OrderBook::new(token_id.to_string());
+        book.bids = vec![Level { price: bid, size: dec!(100) }];
+        book.asks = vec![Level { price: ask, size: dec!(100) }];
+        Arc::new(book)
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        assert_eq!(Filter::parse("min_liquidity:500"), Some(Filter::MinLiquidity(500.0)));
+        assert_eq!(
+            Filter::parse("categories:Crypto|sports"),
+            Some(Filter::Categories(vec!["crypto".to_string(), "sports".to_string()]))
+        );
+        assert_eq!(Filter::parse("max_spread_bps:300"), Some(Filter::MaxSpreadBps(dec!(300))));
+        assert_eq!(
+            Filter::parse("hours_to_expiry:1-48"),
+            Some(Filter::HoursToExpiry { min: 1.0, max: 48.0 })
+        );
+        assert_eq!(Filter::parse("hours_to_expiry:48-1"), None);
+        assert_eq!(Filter::parse("min_volume:10"), None);
+        assert_eq!(Filter::parse("min_liquidity"), None);
+    }
+
+    #[test]
+    fn test_pipeline_drops_tokens_failing_any_stage() {
+        let mut markets = HashMap::new();
+        markets.insert("good".to_string(), market(5_000.0, "crypto", 24));
+        markets.insert("illiquid".to_string(), market(100.0, "crypto", 24));
+        markets.insert("wide".to_string(), market(5_000.0, "crypto", 24));
+        markets.insert("far".to_string(), market(5_000.0, "crypto", 24 * 30));
+        markets.insert("politics".to_string(), market(5_000.0, "politics", 24));
+
+        let mut order_books = HashMap::new();
+        for token in ["good", "illiquid", "far", "politics"] {
+            order_books.insert(token.to_string(), book(token, dec!(0.50), dec!(0.51)));
+        }
+        order_books.insert("wide".to_string(), book("wide", dec!(0.40), dec!(0.60)));
+        // Subscribed directly, no Gamma metadata
+        order_books.insert("fixed".to_string(), book("fixed", dec!(0.50), dec!(0.51)));
+
+        let ctx = StrategyContext::for_test(order_books, markets);
+
+        let pipeline = Pipeline::new(Box::new(crate::strategy::DummyStrategy::new("dummy", vec![])))
+            .filter(Filter::MinLiquidity(1_000.0))
+            .filter(Filter::Categories(vec!["crypto".to_string()]))
+            .filter(Filter::MaxSpreadBps(dec!(500)))
+            .filter(Filter::HoursToExpiry { min: 0.0, max: 48.0 });
+        let filtered = pipeline.apply(&ctx);
+
+        let mut markets: Vec<&String> = filtered.markets.keys().collect();
+        markets.sort();
+        assert_eq!(markets, vec!["good"]);
+        let mut books: Vec<&String> = filtered.order_books.keys().collect();
+        books.sort();
+        assert_eq!(books, vec!["fixed", "good"]);
+        assert_eq!(pipeline.id(), "dummy");
+
+        // Discovery applies every stage but the spread
+        assert_eq!(
+            pipeline.discovery_filters(),
+            vec![
+                DiscoveryFilter::MinLiquidity(1_000.0),
+                DiscoveryFilter::Categories(vec!["crypto".to_string()]),
+                DiscoveryFilter::HoursToExpiry { min: 0.0, max: 48.0 },
+            ]
+        );
+    }
+}
@@ -1,18 +1,26 @@
 //! Position and P&L tracking.
 
+use crate::safe_math::{add, div, mul, sub, sum};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// A single position in a token.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub token_id: String,
     pub size: Decimal,
     pub avg_entry_price: Decimal,
+    /// Net of fees
     pub realized_pnl: Decimal,
     pub unrealized_pnl: Decimal,
     pub last_price: Option<Decimal>,
+    /// Fees paid on the token's fills (already taken out of realized P&L)
+    #[serde(default)]
+    pub fees: Decimal,
+    /// When the current position (this side, since last flat) was opened
+    #[serde(default)]
+    pub opened_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Position {
@@ -24,22 +32,25 @@ impl Position {
             realized_pnl: Decimal::ZERO,
             unrealized_pnl: Decimal::ZERO,
             last_price: None,
+            fees: Decimal::ZERO,
+            opened_at: None,
         }
     }
 
     /// Apply a fill to this position.
     pub fn apply_fill(&mut self, fill: &Fill) {
         let old_size = self.size;
-        let fill_value = fill.price * fill.size;
+        let fill_value = mul(fill.price, fill.size, "fill value");
 
         if fill.is_buy {
             // Buying: increase position
             if old_size >= Decimal::ZERO {
                 // Adding to long position - update average
-                let old_value = self.avg_entry_price * old_size;
-                let new_size = old_size + fill.size;
+                let old_value = mul(self.avg_entry_price, old_size, "long VWAP");
+                let new_size = add(old_size, fill.size, "long size");
                 if new_size > Decimal::ZERO {
-                    self.avg_entry_price = (old_value + fill_value) / new_size;
+                    let total_value = add(old_value, fill_value, "long VWAP");
+                    self.avg_entry_price = div(total_value, new_size, "long VWAP");
                 }
                 self.size = new_size;
             } else {
@@ -48,9 +59,10 @@ impl Position {
                 let new_long = fill.size - cover_size;
 
                 // Realize P&L on covered portion
-                self.realized_pnl += cover_size * (self.avg_entry_price - fill.price);
+                let pnl = mul(cover_size, sub(self.avg_entry_price, fill.price, "short P&L"), "short P&L");
+                self.realized_pnl = add(self.realized_pnl, pnl, "realized P&L");
 
-                self.size = old_size + fill.size;
+                self.size = add(old_size, fill.size, "position size");
                 if new_long > Decimal::ZERO && self.size > Decimal::ZERO {
                     self.avg_entry_price = fill.price;
                 }
@@ -59,10 +71,11 @@ impl Position {
             // Selling: decrease position
             if old_size <= Decimal::ZERO {
                 // Adding to short position - update average
-                let old_value = self.avg_entry_price * (-old_size);
-                let new_size = old_size - fill.size;
+                let old_value = mul(self.avg_entry_price, -old_size, "short VWAP");
+                let new_size = sub(old_size, fill.size, "short size");
                 if new_size < Decimal::ZERO {
-                    self.avg_entry_price = (old_value + fill_value) / (-new_size);
+                    let total_value = add(old_value, fill_value, "short VWAP");
+                    self.avg_entry_price = div(total_value, -new_size, "short VWAP");
                 }
                 self.size = new_size;
             } else {
@@ -71,23 +84,37 @@ impl Position {
                 let new_short = fill.size - close_size;
 
                 // Realize P&L on closed portion
-                self.realized_pnl += close_size * (fill.price - self.avg_entry_price);
+                let pnl = mul(close_size, sub(fill.price, self.avg_entry_price, "long P&L"), "long P&L");
+                self.realized_pnl = add(self.realized_pnl, pnl, "realized P&L");
 
-                self.size = old_size - fill.size;
+                self.size = sub(old_size, fill.size, "position size");
                 if new_short > Decimal::ZERO && self.size < Decimal::ZERO {
                     self.avg_entry_price = fill.price;
                 }
             }
         }
+
+        // Fees are realized when paid
+        self.fees = add(self.fees, fill.fee, "fees");
+        self.realized_pnl = sub(self.realized_pnl, fill.fee, "realized P&L");
+
+        // Track when the position was opened (or flipped side) for carry analytics
+        if self.size.is_zero() {
+            self.opened_at = None;
+        } else if old_size.is_zero() || old_size.is_sign_negative() != self.size.is_sign_negative() {
+            self.opened_at = Some(fill.timestamp);
+        }
     }
 
     /// Update unrealized P&L with current price.
     pub fn update_price(&mut self, price: Decimal) {
         self.last_price = Some(price);
         if self.size > Decimal::ZERO {
-            self.unrealized_pnl = self.size * (price - self.avg_entry_price);
+            let edge = sub(price, self.avg_entry_price, "unrealized P&L");
+            self.unrealized_pnl = mul(self.size, edge, "unrealized P&L");
         } else if self.size < Decimal::ZERO {
-            self.unrealized_pnl = (-self.size) * (self.avg_entry_price - price);
+            let edge = sub(self.avg_entry_price, price, "unrealized P&L");
+            self.unrealized_pnl = mul(-self.size, edge, "unrealized P&L");
         } else {
             self.unrealized_pnl = Decimal::ZERO;
         }
@@ -95,7 +122,7 @@ impl Position {
 
     /// Get notional value of position.
     pub fn notional(&self) -> Decimal {
-        self.size.abs() * self.last_price.unwrap_or(self.avg_entry_price)
+        mul(self.size.abs(), self.last_price.unwrap_or(self.avg_entry_price), "position notional")
     }
 }
 
@@ -109,6 +136,9 @@ pub struct Fill {
     pub size: Decimal,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub fee: Decimal,
+    /// Strategy whose order was filled (None = untagged order)
+    #[serde(default)]
+    pub strategy_id: Option<String>,
 }
 
 /// Tracks all positions.
@@ -131,6 +161,11 @@ impl PositionTracker {
             .or_insert_with(|| Position::new(token_id.to_string()))
     }
 
+    /// Replace the position of its token (restoring persisted state).
+    pub fn restore(&mut self, position: Position) {
+        self.positions.insert(position.token_id.clone(), position);
+    }
+
     /// Get position for a token (read-only).
     pub fn get(&self, token_id: &str) -> Option<&Position> {
         self.positions.get(token_id)
@@ -145,6 +180,7 @@ impl PositionTracker {
             size = %position.size,
             avg_entry = %position.avg_entry_price,
             realized_pnl = %position.realized_pnl,
+            fees = %position.fees,
             "Position updated"
         );
     }
@@ -160,17 +196,22 @@ impl PositionTracker {
 
     /// Get total realized P&L across all positions.
     pub fn total_realized_pnl(&self) -> Decimal {
-        self.positions.values().map(|p| p.realized_pnl).sum()
+        sum(self.positions.values().map(|p| p.realized_pnl), "total realized P&L")
     }
 
     /// Get total unrealized P&L across all positions.
     pub fn total_unrealized_pnl(&self) -> Decimal {
-        self.positions.values().map(|p| p.unrealized_pnl).sum()
+        sum(self.positions.values().map(|p| p.unrealized_pnl), "total unrealized P&L")
+    }
+
+    /// Get total fees paid across all positions.
+    pub fn total_fees(&self) -> Decimal {
+        sum(self.positions.values().map(|p| p.fees), "total fees")
     }
 
     /// Get total notional exposure.
     pub fn total_notional(&self) -> Decimal {
-        self.positions.values().map(|p| p.notional()).sum()
+        sum(self.positions.values().map(|p| p.notional()), "total notional")
     }
 
     /// Get all positions with non-zero size.
@@ -205,6 +246,7 @@ mod tests {
             size: dec!(10),
             timestamp: chrono::Utc::now(),
             fee: Decimal::ZERO,
+            strategy_id: None,
         });
         assert_eq!(pos.size, dec!(10));
         assert_eq!(pos.avg_entry_price, dec!(0.50));
@@ -218,8 +260,29 @@ mod tests {
             size: dec!(5),
             timestamp: chrono::Utc::now(),
             fee: Decimal::ZERO,
+            strategy_id: None,
         });
         assert_eq!(pos.size, dec!(5));
         assert_eq!(pos.realized_pnl, dec!(0.50)); // 5 * (0.60 - 0.50)
     }
+
+    #[test]
+    fn test_fees_come_out_of_realized_pnl() {
+        let mut positions = PositionTracker::new();
+        for (is_buy, price, fee) in [(true, dec!(0.50), dec!(0.10)), (false, dec!(0.60), dec!(0.08))] {
+            positions.apply_fill(&Fill {
+                order_id: "1".to_string(),
+                token_id: "token1".to_string(),
+                is_buy,
+                price,
+                size: dec!(10),
+                timestamp: chrono::Utc::now(),
+                fee,
+                strategy_id: None,
+            });
+        }
+        // 10 * (0.60 - 0.50) gross, less 0.18 of fees
+        assert_eq!(positions.total_realized_pnl(), dec!(0.82));
+        assert_eq!(positions.total_fees(), dec!(0.18));
+    }
 }
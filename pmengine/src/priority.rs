@@ -0,0 +1,154 @@
+//! Cold-start market prioritization.
+//!
+//! Right after startup nothing can trade until the WebSocket has delivered
+//! order books. Discovered markets are ranked so the books most likely to
+//! produce signals arrive first:
+//! 1. Recurring crypto markets close to expiry (soonest first)
+//! 2. High-liquidity markets (most liquid first)
+//! 3. Everything else (most liquid, then soonest expiry first)
+
+use crate::gamma::GammaMarket;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+/// Recurring markets expiring within this many hours count as near expiry.
+pub const NEAR_EXPIRY_HOURS: f64 = 6.0;
+
+/// Liquidity (USDC) at which a market counts as high-liquidity.
+pub const HIGH_LIQUIDITY: f64 = 10_000.0;
+
+/// Keywords identifying crypto markets whose category is missing.
+const CRYPTO_KEYWORDS: &[&str] = &["bitcoin", "btc", "ethereum", "eth", "solana", "sol", "xrp"];
+
+/// Priority bucket, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PriorityTier {
+    RecurringCryptoNearExpiry,
+    HighLiquidity,
+    Other,
+}
+
+/// Whether a market is about crypto (by category, else by keywords).
+pub fn is_crypto(market: &GammaMarket) -> bool {
+    if let Some(ref category) = market.category {
+        return category.eq_ignore_ascii_case("crypto");
+    }
+    let text = format!("{} {}", market.question, market.slug).to_lowercase();
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| CRYPTO_KEYWORDS.contains(&word))
+}
+
+/// Classify a market; `recurring` = discovered through a recurring series.
+pub fn tier(market: &GammaMarket, recurring: bool, now: DateTime<Utc>) -> PriorityTier {
+    let hours = hours_until(market, now);
+    let near_expiry = hours.is_some_and(|h| (0.0..=NEAR_EXPIRY_HOURS).contains(&h));
+
+    if recurring && near_expiry && is_crypto(market) {
+        PriorityTier::RecurringCryptoNearExpiry
+    } else if market.liquidity.is_some_and(|l| l >= HIGH_LIQUIDITY) {
+        PriorityTier::HighLiquidity
+    } else {
+        PriorityTier::Other
+    }
+}
+
+fn hours_until(market: &GammaMarket, now: DateTime<Utc>) -> Option<f64> {
+    market
+        .end_date
+        .map(|end| end.signed_duration_since(now).num_seconds() as f64 / 3600.0)
+}
+
+/// Indices of `markets` ordered by cold-start priority.
+///
+/// `recurring_slugs` holds the slugs of markets found through recurring series.
+pub fn prioritize(
+    markets: &[GammaMarket],
+    recurring_slugs: &HashSet<String>,
+    now: DateTime<Utc>,
+) -> Vec<usize> {
+    let tiers: Vec<PriorityTier> = markets
+        .iter()
+        .map(|m| tier(m, recurring_slugs.contains(&m.slug), now))
+        .collect();
+
+    let soonest = |a: &GammaMarket, b: &GammaMarket| {
+        let a = hours_until(a, now).unwrap_or(f64::INFINITY);
+        let b = hours_until(b, now).unwrap_or(f64::INFINITY);
+        a.total_cmp(&b)
+    };
+    let most_liquid = |a: &GammaMarket, b: &GammaMarket| {
+        b.liquidity.unwrap_or(0.0).total_cmp(&a.liquidity.unwrap_or(0.0))
+    };
+
+    let mut order: Vec<usize> = (0..markets.len()).collect();
+    order.sort_by(|&i, &j| {
+        let (a, b) = (&markets[i], &markets[j]);
+        tiers[i].cmp(&tiers[j]).then_with(|| match tiers[i] {
+            PriorityTier::RecurringCryptoNearExpiry => soonest(a, b),
+            PriorityTier::HighLiquidity => most_liquid(a, b),
+            PriorityTier::Other => most_liquid(a, b).then_with(|| soonest(a, b)),
+        })
+    });
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn market(slug: &str, category: Option<&str>, hours: i64, liquidity: f64) -> GammaMarket {
+        let now = Utc::now();
+        GammaMarket {
+            question: format!("Question for {}?", slug),
+            slug: slug.to_string(),
+            end_date: Some(now + Duration::hours(hours)),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: Vec::new(),
+            clob_token_ids: Vec::new(),
+            active: true,
+            closed: false,
+            liquidity: Some(liquidity),
+            volume: None,
+            category: category.map(String::from),
+            event_slug: None,
+            neg_risk: false,
+            neg_risk_market_id: None,
+            question_id: None,
+        }
+    }
+
+    #[test]
+    fn test_is_crypto() {
+        assert!(is_crypto(&market("btc-up-or-down", None, 1, 0.0)));
+        assert!(is_crypto(&market("anything", Some("Crypto"), 1, 0.0)));
+        assert!(!is_crypto(&market("election", Some("politics"), 1, 0.0)));
+        assert!(!is_crypto(&market("bethesda-game", None, 1, 0.0)));
+    }
+
+    #[test]
+    fn test_prioritize() {
+        let markets = vec![
+            market("election", Some("politics"), 48, 500.0),
+            market("btc-4h", Some("crypto"), 3, 100.0),
+            market("big-event", Some("sports"), 30, 50_000.0),
+            market("eth-daily", None, 1, 100.0),
+            market("btc-weekly", Some("crypto"), 60, 100.0),
+            market("medium-event", Some("sports"), 20, 20_000.0),
+        ];
+        let recurring: HashSet<String> = ["btc-4h", "eth-daily", "btc-weekly"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let order: Vec<&str> = prioritize(&markets, &recurring, Utc::now())
+            .into_iter()
+            .map(|i| markets[i].slug.as_str())
+            .collect();
+
+        assert_eq!(
+            order,
+            vec!["eth-daily", "btc-4h", "big-event", "medium-event", "election", "btc-weekly"]
+        );
+    }
+}
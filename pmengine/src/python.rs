@@ -0,0 +1,394 @@
+//! Python strategies hosted in the engine (`python` feature).
+//!
+//! [`PyStrategy`] runs a pmstrat strategy file as is, without transpiling
+//! it: the engine embeds CPython, imports the file and calls its `@strategy`
+//! function every tick with a mirrored `pmstrat.Context`, turning the pmstrat
+//! signals it returns into [`Signal`]s. Fills are passed as
+//! `pmstrat.backtest.Fill`s, with the last tick's context, to the `on_fill`
+//! given to the decorator.
+//!
+//! Parameters are the module's upper-case constants (e.g. `MIN_EDGE`);
+//! overrides set them, converted to the constant's type.
+//!
+//! pmstrat must be importable by the embedded interpreter (installed in the
+//! Python the engine was built against, or on `PYTHONPATH`). Strategies run
+//! under the GIL on the event loop, so a slow one slows the loop like a slow
+//! Rust strategy would; the tick budget applies to them too. A Python
+//! exception is logged and the tick produces no signals.
+
+use std::ffi::CString;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList};
+use rust_decimal::Decimal;
+
+use crate::position::Fill;
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
+
+/// A pmstrat strategy run by the embedded Python interpreter.
+pub struct PyStrategy {
+    id: String,
+    tokens: Vec<String>,
+    module: Py<PyModule>,
+    on_tick: PyObject,
+    on_fill: Option<PyObject>,
+    /// `pmstrat.context` (context classes)
+    context: Py<PyModule>,
+    /// Context of the last tick, passed to `on_fill`
+    last_context: Option<PyObject>,
+}
+
+impl PyStrategy {
+    /// Import a Python file and pick its `@strategy` function.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let code = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let module_name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("strategy");
+        let cstring = |s: &str| CString::new(s).map_err(|e| e.to_string());
+        let (code, file_name, module_name) =
+            (cstring(&code)?, cstring(&path.to_string_lossy())?, cstring(module_name)?);
+
+        Python::with_gil(|py| {
+            // Sibling modules of the strategy are importable, as when run by pmstrat
+            if let Some(dir) = path.parent().and_then(|d| d.to_str()) {
+                py.import("sys")?.getattr("path")?.call_method1("insert", (0, dir))?;
+            }
+            let context = py.import("pmstrat.context")?;
+            let module = PyModule::from_code(py, &code, &file_name, &module_name)?;
+
+            let mut meta = None;
+            for (_, value) in module.dict().iter() {
+                if let Ok(found) = value.getattr("_strategy_meta") {
+                    meta = Some(found);
+                    break;
+                }
+            }
+            let Some(meta) = meta else {
+                return Ok(Err(format!("no @strategy function in {}", path.display())));
+            };
+            let on_fill = meta.getattr("on_fill")?;
+
+            Ok(Ok(Self {
+                id: meta.getattr("name")?.extract()?,
+                tokens: meta.getattr("tokens")?.extract()?,
+                module: module.unbind(),
+                on_tick: meta.getattr("on_tick")?.unbind(),
+                on_fill: (!on_fill.is_none()).then(|| on_fill.unbind()),
+                context: context.unbind(),
+                last_context: None,
+            }))
+        })
+        .map_err(|e: PyErr| format!("{}: {}", path.display(), e))?
+    }
+
+    /// Mirror a strategy context as a `pmstrat.Context`.
+    fn mirror_context<'py>(&self, py: Python<'py>, ctx: &StrategyContext) -> PyResult<Bound<'py, PyAny>> {
+        let classes = self.context.bind(py);
+        let dec = |value: Decimal| decimal(py, value);
+        let opt_dec = |value: Option<Decimal>| -> PyResult<PyObject> {
+            value.map_or_else(|| Ok(py.None()), |v| Ok(decimal(py, v)?.unbind()))
+        };
+
+        let books = PyDict::new(py);
+        for (token_id, book) in &ctx.order_books {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("token_id", token_id)?;
+            kwargs.set_item("best_bid", opt_dec(book.best_bid().map(|l| l.price))?)?;
+            kwargs.set_item("best_ask", opt_dec(book.best_ask().map(|l| l.price))?)?;
+            kwargs.set_item("bid_size", dec(book.best_bid().map(|l| l.size).unwrap_or_default())?)?;
+            kwargs.set_item("ask_size", dec(book.best_ask().map(|l| l.size).unwrap_or_default())?)?;
+            books.set_item(token_id, classes.getattr("OrderBookSnapshot")?.call((), Some(&kwargs))?)?;
+        }
+
+        let positions = PyDict::new(py);
+        for position in ctx.positions.all_positions() {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("token_id", &position.token_id)?;
+            kwargs.set_item("size", dec(position.size)?)?;
+            kwargs.set_item("avg_entry_price", dec(position.avg_entry_price)?)?;
+            kwargs.set_item("unrealized_pnl", dec(position.unrealized_pnl)?)?;
+            kwargs.set_item("realized_pnl", dec(position.realized_pnl)?)?;
+            kwargs.set_item("last_price", opt_dec(position.last_price)?)?;
+            kwargs.set_item("fees", dec(position.fees)?)?;
+            positions.set_item(&position.token_id, classes.getattr("Position")?.call((), Some(&kwargs))?)?;
+        }
+
+        let markets = PyDict::new(py);
+        for (token_id, market) in &ctx.markets {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("token_id", token_id)?;
+            kwargs.set_item("question", &market.question)?;
+            kwargs.set_item("outcome", &market.outcome)?;
+            kwargs.set_item("slug", &market.slug)?;
+            let end_date = market.end_date.map(|d| datetime(py, d)).transpose()?;
+            kwargs.set_item("end_date", end_date)?;
+            kwargs.set_item("liquidity", market.liquidity)?;
+            kwargs.set_item("event_slug", &market.event_slug)?;
+            kwargs.set_item("neg_risk", market.neg_risk)?;
+            markets.set_item(token_id, classes.getattr("MarketInfo")?.call((), Some(&kwargs))?)?;
+        }
+
+        let stats = PyDict::new(py);
+        for (token_id, token_stats) in &ctx.stats {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("volatility", opt_dec(token_stats.volatility)?)?;
+            kwargs.set_item("order_flow_imbalance", opt_dec(token_stats.order_flow_imbalance)?)?;
+            kwargs.set_item("mid_return", opt_dec(token_stats.mid_return)?)?;
+            kwargs.set_item("trade_imbalance", opt_dec(token_stats.trade_imbalance)?)?;
+            stats.set_item(token_id, classes.getattr("MarketStats")?.call((), Some(&kwargs))?)?;
+        }
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("timestamp", datetime(py, ctx.timestamp)?)?;
+        kwargs.set_item("books", books)?;
+        kwargs.set_item("positions", positions)?;
+        kwargs.set_item("markets", markets)?;
+        kwargs.set_item("total_realized_pnl", dec(ctx.realized_pnl)?)?;
+        kwargs.set_item("total_unrealized_pnl", dec(ctx.unrealized_pnl)?)?;
+        kwargs.set_item("usdc_balance", dec(ctx.usdc_balance)?)?;
+        kwargs.set_item("kelly_fraction", dec(ctx.kelly_fraction)?)?;
+        kwargs.set_item("stats", stats)?;
+        classes.getattr("Context")?.call((), Some(&kwargs))
+    }
+}
+
+impl Strategy for PyStrategy {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let result = Python::with_gil(|py| -> PyResult<Vec<Signal>> {
+            let context = self.mirror_context(py, ctx)?;
+            let returned = self.on_tick.bind(py).call1((&context,))?;
+            self.last_context = Some(context.unbind());
+            if returned.is_none() {
+                return Ok(Vec::new());
+            }
+            returned.try_iter()?.map(|signal| to_signal(&signal?)).collect()
+        });
+        result.unwrap_or_else(|e| {
+            tracing::error!(strategy_id = self.id.as_str(), error = %e, "Python on_tick failed");
+            Vec::new()
+        })
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        Python::with_gil(|py| {
+            let module = self.module.bind(py);
+            for name in params.names() {
+                let current = match module.getattr(name) {
+                    Ok(current) if name.chars().all(|c| !c.is_lowercase()) => current,
+                    _ => return Err(format!("unknown parameter {} (not a constant of the module)", name)),
+                };
+                let value: String = params.get(name)?.unwrap_or_default();
+                let converted = if current.is_instance_of::<PyBool>() {
+                    PyBool::new(py, value.eq_ignore_ascii_case("true") || value == "1").to_owned().into_any()
+                } else {
+                    current
+                        .get_type()
+                        .call1((value.as_str(),))
+                        .map_err(|_| format!("invalid value for {}: {}", name, value))?
+                };
+                module.setattr(name, converted).map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn on_fill(&mut self, fill: &Fill) {
+        let Some(on_fill) = &self.on_fill else {
+            return;
+        };
+        let result = Python::with_gil(|py| -> PyResult<()> {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("token_id", &fill.token_id)?;
+            kwargs.set_item("side", if fill.is_buy { "BUY" } else { "SELL" })?;
+            kwargs.set_item("price", decimal(py, fill.price)?)?;
+            kwargs.set_item("size", decimal(py, fill.size)?)?;
+            kwargs.set_item("timestamp", datetime(py, fill.timestamp)?)?;
+            kwargs.set_item("fee", decimal(py, fill.fee)?)?;
+            let fill = py.import("pmstrat.backtest")?.getattr("Fill")?.call((), Some(&kwargs))?;
+            let context = self.last_context.as_ref().map(|c| c.clone_ref(py));
+            on_fill.bind(py).call1((context, fill))?;
+            Ok(())
+        });
+        if let Err(e) = result {
+            tracing::error!(strategy_id = self.id.as_str(), error = %e, "Python on_fill failed");
+        }
+    }
+}
+
+fn decimal(py: Python<'_>, value: Decimal) -> PyResult<Bound<'_, PyAny>> {
+    py.import("decimal")?.getattr("Decimal")?.call1((value.to_string(),))
+}
+
+fn datetime(py: Python<'_>, value: DateTime<Utc>) -> PyResult<Bound<'_, PyAny>> {
+    py.import("datetime")?
+        .getattr("datetime")?
+        .call_method1("fromisoformat", (value.to_rfc3339(),))
+}
+
+/// Convert a pmstrat signal (by class name) into a [`Signal`].
+fn to_signal(signal: &Bound<'_, PyAny>) -> PyResult<Signal> {
+    let kind = signal.get_type().name()?.to_string();
+    let token_id = || signal.getattr("token_id")?.extract::<String>();
+    Ok(match kind.as_str() {
+        "Buy" | "Sell" => {
+            let urgency = match signal.getattr("urgency")?.getattr("name")?.extract::<String>()?.as_str() {
+                "LOW" => Urgency::Low,
+                "HIGH" => Urgency::High,
+                "IMMEDIATE" => Urgency::Immediate,
+                _ => Urgency::Medium,
+            };
+            let (token_id, price, size) =
+                (token_id()?, to_decimal(&signal.getattr("price")?)?, to_decimal(&signal.getattr("size")?)?);
+            let post_only = signal.getattr("post_only")?.extract()?;
+            let expires_at = to_datetime(&signal.getattr("expires_at")?)?;
+            if kind == "Buy" {
+                Signal::Buy { token_id, price, size, urgency, post_only, expires_at }
+            } else {
+                Signal::Sell { token_id, price, size, urgency, post_only, expires_at }
+            }
+        }
+        "Cancel" => Signal::Cancel { token_id: token_id()? },
+        "Replace" => Signal::Replace {
+            token_id: token_id()?,
+            orders: signal
+                .getattr("orders")?
+                .downcast::<PyList>()?
+                .iter()
+                .map(|order| to_signal(&order))
+                .collect::<PyResult<_>>()?,
+        },
+        "Hold" => Signal::Hold,
+        "ReduceOnly" => Signal::ReduceOnly {
+            enabled: signal.getattr("enabled")?.extract()?,
+            reason: signal.getattr("reason")?.extract()?,
+        },
+        "Exit" => {
+            let optional = |name: &str| -> PyResult<Option<Decimal>> {
+                let value = signal.getattr(name)?;
+                if value.is_none() {
+                    Ok(None)
+                } else {
+                    to_decimal(&value).map(Some)
+                }
+            };
+            Signal::Exit {
+                token_id: token_id()?,
+                stop_loss: optional("stop_loss")?,
+                take_profit: optional("take_profit")?,
+                time_stop: to_datetime(&signal.getattr("time_stop")?)?,
+            }
+        }
+        "Shutdown" => Signal::Shutdown { reason: signal.getattr("reason")?.extract()? },
+        other => {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!("unsupported signal {}", other)));
+        }
+    })
+}
+
+/// A Python Decimal, int or float as a [`Decimal`].
+fn to_decimal(value: &Bound<'_, PyAny>) -> PyResult<Decimal> {
+    let text = value.str()?.to_string();
+    Decimal::from_str(&text)
+        .or_else(|_| Decimal::from_scientific(&text))
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err(format!("not a decimal: {}", text)))
+}
+
+/// A Python datetime (naive = UTC) or None.
+fn to_datetime(value: &Bound<'_, PyAny>) -> PyResult<Option<DateTime<Utc>>> {
+    if value.is_none() {
+        return Ok(None);
+    }
+    let text: String = value.call_method0("isoformat")?.extract()?;
+    DateTime::parse_from_rfc3339(&text)
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f").map(|d| d.and_utc()))
+        .map(Some)
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err(format!("not a datetime: {}", text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const STRATEGY: &str = r#"
+from decimal import Decimal
+from pmstrat import Buy, Hold, Urgency, strategy
+
+MAX_PRICE = Decimal("0.60")
+FILLS = []
+
+def record_fill(ctx, fill):
+    FILLS.append((fill.side, fill.size))
+
+@strategy(name="py_test", tokens=["t1"], on_fill=record_fill)
+def on_tick(ctx):
+    book = ctx.book("t1")
+    if book is None or book.best_ask is None or book.best_ask > MAX_PRICE:
+        return [Hold()]
+    return [Buy(token_id="t1", price=book.best_ask, size=Decimal(len(FILLS) + 1), urgency=Urgency.HIGH)]
+"#;
+
+    fn context(ask: Decimal) -> StrategyContext {
+        let mut book = OrderBook::new("t1".to_string());
+        book.asks = vec![Level { price: ask, size: dec!(100) }];
+        StrategyContext::for_test(HashMap::from([("t1".to_string(), Arc::new(book))]), HashMap::new())
+    }
+
+    #[test]
+    fn test_python_strategy_runs_live() {
+        Python::with_gil(|py| {
+            let pmstrat = concat!(env!("CARGO_MANIFEST_DIR"), "/../pmstrat");
+            py.import("sys").unwrap().getattr("path").unwrap().call_method1("insert", (0, pmstrat)).unwrap();
+        });
+        let path = std::env::temp_dir().join(format!("pmengine_py_test_{}.py", std::process::id()));
+        std::fs::write(&path, STRATEGY).unwrap();
+        let mut strategy = PyStrategy::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(strategy.id(), "py_test");
+        assert_eq!(strategy.subscriptions(), vec!["t1"]);
+
+        match strategy.on_tick(&context(dec!(0.55))).as_slice() {
+            [Signal::Buy { token_id, price, size, urgency: Urgency::High, .. }] => {
+                assert_eq!((token_id.as_str(), *price, *size), ("t1", dec!(0.55), dec!(1)));
+            }
+            other => panic!("expected a buy, got {:?}", other),
+        }
+        assert!(matches!(strategy.on_tick(&context(dec!(0.65))).as_slice(), [Signal::Hold]));
+
+        // Fills reach on_fill, parameters set the module's constants
+        strategy.on_fill(&Fill {
+            order_id: "o1".to_string(),
+            token_id: "t1".to_string(),
+            is_buy: true,
+            price: dec!(0.55),
+            size: dec!(1),
+            timestamp: Utc::now(),
+            fee: dec!(0),
+            strategy_id: Some("py_test".to_string()),
+        });
+        let mut params = StrategyParams::new();
+        params.insert("MAX_PRICE", "0.70");
+        strategy.configure(&params).unwrap();
+        assert!(matches!(
+            strategy.on_tick(&context(dec!(0.65))).as_slice(),
+            [Signal::Buy { size, .. }] if *size == dec!(2)
+        ));
+
+        params.insert("MISSING", "1");
+        assert!(strategy.configure(&params).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Reconciliation of the engine's books with the exchange.
+//!
+//! The order manager and position tracker are only as good as the fills and
+//! acknowledgements that reach them; a dropped WebSocket message, a cancel
+//! from the Polymarket UI or a crash between placing an order and saving it
+//! all leave them out of step with reality. Reconciliation diffs them against
+//! the CLOB's open orders and the Data API's positions:
+//!
+//! - local orders no longer open on the exchange were filled or cancelled
+//!   without us seeing it and are closed,
+//! - orders open on the exchange that we don't track are adopted,
+//! - positions whose size differs from the Data API take the exchange's size
+//!   and entry price; positions it no longer reports are zeroed. Positions in
+//!   resolved markets (redeemable) are left to settlement.
+//!
+//! It runs on startup (against the restored state, if any) and every
+//! `PMENGINE_RECONCILE_SECS` (default 300, 0 = startup only). With
+//! `PMENGINE_RECONCILE_ALERT_ONLY=true` mismatches are logged but the books
+//! are left alone.
+//!
+//! Orders placed within [`GRACE`] of a periodic pass are kept even if the
+//! exchange doesn't list them yet, and positions are left alone for
+//! [`GRACE`] after a fill, since the Data API lags the matching engine.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::client::{AccountPosition, OpenOrder};
+use crate::position::Position;
+use crate::store::{EngineState, StoredOrder};
+
+/// How long recent orders and fills are given to show up on the exchange.
+pub const GRACE: Duration = Duration::from_secs(30);
+
+/// Outcome of reconciling local state with the exchange.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reconciliation {
+    /// Orders open on the exchange (kept or adopted), with exchange fill progress.
+    pub open_orders: Vec<StoredOrder>,
+    /// Local orders no longer open on the exchange.
+    pub closed: Vec<String>,
+    /// Orders open on the exchange that weren't tracked.
+    pub adopted: Vec<String>,
+    /// Tokens whose position size was corrected from the exchange.
+    pub corrected_positions: Vec<String>,
+}
+
+impl Reconciliation {
+    /// Whether the local state matched the exchange.
+    pub fn is_clean(&self) -> bool {
+        self.closed.is_empty() && self.adopted.is_empty() && self.corrected_positions.is_empty()
+    }
+}
+
+/// Reconcile local state with the exchange's open orders and (if available)
+/// positions, correcting `state` in place.
+///
+/// Local orders created after `placed_after` are too recent to judge and are
+/// kept even if the exchange doesn't list them.
+pub fn reconcile(
+    state: &mut EngineState,
+    exchange_orders: &[OpenOrder],
+    exchange_positions: Option<&[AccountPosition]>,
+    placed_after: Option<DateTime<Utc>>,
+) -> Reconciliation {
+    let local: HashMap<&str, &StoredOrder> = state.open_orders.iter().map(|o| (o.id.as_str(), o)).collect();
+    let mut result = Reconciliation::default();
+
+    for order in exchange_orders {
+        let mut open = StoredOrder::from(order);
+        match local.get(order.order_id.as_str()) {
+            Some(known) => {
+                open.created_at = known.created_at;
+                open.strategy_id = known.strategy_id.clone();
+            }
+            None => result.adopted.push(order.order_id.clone()),
+        }
+        result.open_orders.push(open);
+    }
+    for order in &state.open_orders {
+        if exchange_orders.iter().any(|e| e.order_id == order.id) {
+            continue;
+        }
+        if placed_after.is_some_and(|after| order.created_at > after) {
+            result.open_orders.push(order.clone());
+        } else {
+            result.closed.push(order.id.clone());
+        }
+    }
+
+    if let Some(exchange_positions) = exchange_positions {
+        for held in exchange_positions.iter().filter(|h| !h.redeemable) {
+            let position = match state.positions.iter_mut().find(|p| p.token_id == held.token_id) {
+                Some(position) => position,
+                None => {
+                    state.positions.push(Position::new(held.token_id.clone()));
+                    state.positions.last_mut().expect("just pushed")
+                }
+            };
+            if position.size != held.size {
+                position.size = held.size;
+                position.avg_entry_price = held.avg_price;
+                position.opened_at = position.opened_at.or(Some(Utc::now()));
+                result.corrected_positions.push(held.token_id.clone());
+            }
+        }
+        // Positions the exchange no longer reports were closed or redeemed
+        for position in state.positions.iter_mut() {
+            let held = exchange_positions.iter().any(|h| h.token_id == position.token_id);
+            if !held && !position.size.is_zero() {
+                position.size = Decimal::ZERO;
+                position.opened_at = None;
+                result.corrected_positions.push(position.token_id.clone());
+            }
+        }
+    }
+
+    state.open_orders = result.open_orders.clone();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Side;
+    use rust_decimal_macros::dec;
+
+    fn local(id: &str, token_id: &str, created_at: DateTime<Utc>) -> StoredOrder {
+        StoredOrder {
+            id: id.to_string(),
+            token_id: token_id.to_string(),
+            is_buy: true,
+            price: dec!(0.5),
+            size: dec!(10),
+            filled_size: Decimal::ZERO,
+            created_at,
+            strategy_id: None,
+        }
+    }
+
+    fn exchange(id: &str, token_id: &str, matched: Decimal) -> OpenOrder {
+        OpenOrder {
+            order_id: id.to_string(),
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            price: dec!(0.5),
+            original_size: dec!(10),
+            size_matched: matched,
+            outcome: "Yes".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn held(token_id: &str, size: Decimal, avg_price: Decimal) -> AccountPosition {
+        AccountPosition {
+            token_id: token_id.to_string(),
+            title: String::new(),
+            outcome: "Yes".to_string(),
+            event_slug: String::new(),
+            size,
+            avg_price,
+            cur_price: avg_price,
+            current_value: size * avg_price,
+            cash_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            redeemable: false,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_with_exchange() {
+        let mut stale = Position::new("b".to_string());
+        stale.size = dec!(3);
+        let mut state = EngineState {
+            positions: vec![stale],
+            open_orders: vec![local("kept", "a", Utc::now()), local("gone", "a", Utc::now())],
+        };
+        let orders = [exchange("kept", "a", dec!(4)), exchange("new", "c", dec!(0))];
+        let positions = [held("a", dec!(4), dec!(0.5))];
+
+        let result = reconcile(&mut state, &orders, Some(&positions), None);
+        assert!(!result.is_clean());
+        assert_eq!(result.closed, vec!["gone"]);
+        assert_eq!(result.adopted, vec!["new"]);
+        assert_eq!(result.corrected_positions, vec!["a", "b"]);
+
+        // Fill progress comes from the exchange
+        let kept = state.open_orders.iter().find(|o| o.id == "kept").unwrap();
+        assert_eq!(kept.remaining(), dec!(6));
+        assert_eq!(state.open_orders.len(), 2);
+
+        let a = state.positions.iter().find(|p| p.token_id == "a").unwrap();
+        assert_eq!((a.size, a.avg_entry_price), (dec!(4), dec!(0.5)));
+        let b = state.positions.iter().find(|p| p.token_id == "b").unwrap();
+        assert!(b.size.is_zero());
+
+        // Nothing to correct the second time
+        assert!(reconcile(&mut state, &orders, Some(&positions), None).is_clean());
+    }
+
+    #[test]
+    fn test_recent_orders_are_not_closed() {
+        let now = Utc::now();
+        let placed_after = now - GRACE;
+        let mut state = EngineState {
+            positions: Vec::new(),
+            open_orders: vec![local("old", "a", now - GRACE * 2), local("just_placed", "a", now)],
+        };
+
+        // Positions unavailable: left as they are
+        let result = reconcile(&mut state, &[], None, Some(placed_after));
+        assert_eq!(result.closed, vec!["old"]);
+        assert!(result.corrected_positions.is_empty());
+        let open: Vec<_> = state.open_orders.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(open, vec!["just_placed"]);
+
+        // Once past the grace period it is closed too
+        let result = reconcile(&mut state, &[], None, Some(now + GRACE));
+        assert_eq!(result.closed, vec!["just_placed"]);
+        assert!(state.open_orders.is_empty());
+    }
+
+    #[test]
+    fn test_redeemable_positions_are_left_to_settlement() {
+        // Settled locally, not yet redeemed on-chain
+        let settled = Position::new("won".to_string());
+        let mut state = EngineState {
+            positions: vec![settled],
+            open_orders: Vec::new(),
+        };
+        let mut won = held("won", dec!(10), dec!(0.9));
+        won.redeemable = true;
+        let mut lost = held("lost", dec!(5), dec!(0.1));
+        lost.redeemable = true;
+
+        let result = reconcile(&mut state, &[], Some(&[won, lost]), None);
+        assert!(result.is_clean());
+        assert!(state.positions[0].size.is_zero());
+        assert_eq!(state.positions.len(), 1);
+    }
+}
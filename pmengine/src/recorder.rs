@@ -0,0 +1,299 @@
+//! Market data recorder for building backtest datasets.
+//!
+//! With `PMENGINE_RECORD_DIR` set (or under `pmengine record`), every book
+//! update and trade broadcast by the [`MarketDataHub`](crate::MarketDataHub)
+//! is written as a CSV row to files rotated every
+//! `PMENGINE_RECORD_ROTATE_MINS` (default 60):
+//!
+//! - `books-<start>.csv`: `recorded_at,timestamp,token_id`, then price and
+//!   size of the top `PMENGINE_RECORD_DEPTH` (default 5) bid and ask levels
+//!   (`bid_price_1,bid_size_1,...,ask_price_1,ask_size_1,...`), empty where
+//!   the book is shallower.
+//! - `trades-<start>.csv`: `recorded_at,timestamp,token_id,side,price,size`.
+//!
+//! `<start>` is the UTC start of the file's window (`20260115T1400`) and
+//! timestamps are Unix milliseconds: `recorded_at` is when the engine saw the
+//! event, `timestamp` the exchange's. A restart within a window appends to
+//! its files. Rows are buffered and flushed every second and on shutdown.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_broadcast::{Receiver, RecvError};
+use chrono::{DateTime, Utc};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::orderbook::{Level, MarketEvent, OrderBook};
+
+/// How often buffered rows are flushed to disk.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Rows written by a recorder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordStats {
+    pub books: u64,
+    pub trades: u64,
+    /// Events the recorder fell too far behind to see
+    pub dropped: u64,
+}
+
+/// An open file of one kind for the current window.
+struct Segment {
+    window_start: i64,
+    writer: BufWriter<File>,
+}
+
+/// Writes market events to rotating CSV files.
+pub struct MarketRecorder {
+    dir: PathBuf,
+    depth: usize,
+    rotate_secs: i64,
+    books: Option<Segment>,
+    trades: Option<Segment>,
+    stats: RecordStats,
+}
+
+impl MarketRecorder {
+    /// Record into `dir` (created if missing), keeping `depth` levels per
+    /// side and starting new files every `rotate`.
+    pub fn new(dir: &Path, depth: usize, rotate: Duration) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            depth: depth.max(1),
+            rotate_secs: rotate.as_secs().max(60) as i64,
+            books: None,
+            trades: None,
+            stats: RecordStats::default(),
+        })
+    }
+
+    /// Rows written so far.
+    pub fn stats(&self) -> RecordStats {
+        self.stats
+    }
+
+    /// Record one event. Book validity announcements and external events are
+    /// not recorded.
+    pub fn record(&mut self, event: &MarketEvent) -> std::io::Result<()> {
+        self.record_at(event, Utc::now())
+    }
+
+    fn record_at(&mut self, event: &MarketEvent, now: DateTime<Utc>) -> std::io::Result<()> {
+        match event {
+            MarketEvent::BookUpdate { book, .. } => {
+                let row = book_row(book, self.depth, now.timestamp_millis());
+                let header = book_header(self.depth);
+                let writer = self.segment("books", &header, now)?;
+                writeln!(writer, "{}", row)?;
+                self.stats.books += 1;
+            }
+            MarketEvent::Trade {
+                token_id,
+                price,
+                size,
+                side,
+                timestamp,
+            } => {
+                let writer = self.segment("trades", "recorded_at,timestamp,token_id,side,price,size", now)?;
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    now.timestamp_millis(),
+                    timestamp,
+                    token_id,
+                    side,
+                    price,
+                    size
+                )?;
+                self.stats.trades += 1;
+            }
+            MarketEvent::BookInvalid { .. } | MarketEvent::External(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Flush buffered rows to disk.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        for segment in [self.books.as_mut(), self.trades.as_mut()].into_iter().flatten() {
+            segment.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// The writer for `kind` in the window holding `now`, rotating if needed.
+    fn segment(&mut self, kind: &str, header: &str, now: DateTime<Utc>) -> std::io::Result<&mut BufWriter<File>> {
+        let window_start = now.timestamp().div_euclid(self.rotate_secs) * self.rotate_secs;
+        let slot = if kind == "books" { &mut self.books } else { &mut self.trades };
+        if slot.as_ref().is_none_or(|s| s.window_start != window_start) {
+            if let Some(mut old) = slot.take() {
+                old.writer.flush()?;
+            }
+            let start = DateTime::from_timestamp(window_start, 0).unwrap_or(now);
+            let path = self.dir.join(format!("{}-{}.csv", kind, start.format("%Y%m%dT%H%M")));
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let mut writer = BufWriter::new(file);
+            if writer.get_ref().metadata()?.len() == 0 {
+                writeln!(writer, "{}", header)?;
+            }
+            tracing::debug!(path = %path.display(), "Recording to new file");
+            *slot = Some(Segment { window_start, writer });
+        }
+        Ok(&mut slot.as_mut().expect("segment opened above").writer)
+    }
+
+    /// Record events from `events` on a background task until stopped.
+    pub fn spawn(mut self, mut events: Receiver<MarketEvent>) -> RecorderHandle {
+        let (stop_tx, mut stop_rx) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            let mut flush_timer = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(event) => {
+                            if let Err(e) = self.record(&event) {
+                                tracing::error!(error = %e, "Failed to record market data, recorder stopped");
+                                break;
+                            }
+                        }
+                        Err(RecvError::Overflowed(missed)) => {
+                            tracing::warn!(missed = missed, "Market data recorder fell behind");
+                            self.stats.dropped += missed;
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = flush_timer.tick() => {
+                        if let Err(e) = self.flush() {
+                            tracing::error!(error = %e, "Failed to flush market data, recorder stopped");
+                            break;
+                        }
+                    }
+                    _ = &mut stop_rx => break,
+                }
+            }
+            if let Err(e) = self.flush() {
+                tracing::error!(error = %e, "Failed to flush market data");
+            }
+            self.stats
+        });
+        RecorderHandle { stop: stop_tx, task }
+    }
+}
+
+/// A recorder running on a background task.
+pub struct RecorderHandle {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<RecordStats>,
+}
+
+impl RecorderHandle {
+    /// Stop recording, flush, and return what was written.
+    pub async fn finish(self) -> RecordStats {
+        let _ = self.stop.send(());
+        self.task.await.unwrap_or_default()
+    }
+}
+
+fn book_header(depth: usize) -> String {
+    let mut header = String::from("recorded_at,timestamp,token_id");
+    for side in ["bid", "ask"] {
+        for level in 1..=depth {
+            header.push_str(&format!(",{side}_price_{level},{side}_size_{level}"));
+        }
+    }
+    header
+}
+
+fn book_row(book: &OrderBook, depth: usize, recorded_at: i64) -> String {
+    let mut row = format!("{},{},{}", recorded_at, book.timestamp, book.token_id);
+    for levels in [&book.bids, &book.asks] {
+        for i in 0..depth {
+            match levels.get(i) {
+                Some(Level { price, size }) => row.push_str(&format!(",{},{}", price, size)),
+                None => row.push_str(",,"),
+            }
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn book(token_id: &str, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> MarketEvent {
+        let levels = |l: &[(Decimal, Decimal)]| l.iter().map(|&(price, size)| Level { price, size }).collect();
+        MarketEvent::BookUpdate {
+            token_id: token_id.to_string(),
+            book: Arc::new(OrderBook {
+                token_id: token_id.to_string(),
+                bids: levels(bids),
+                asks: levels(asks),
+                timestamp: 1_700_000_000_000,
+                hash: None,
+                received_at: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_records_rotating_csv() {
+        let dir = std::env::temp_dir().join(format!("pmengine-recorder-{}", std::process::id()));
+        let mut recorder = MarketRecorder::new(&dir, 2, Duration::from_secs(3600)).unwrap();
+        let t0 = DateTime::parse_from_rfc3339("2026-01-15T14:59:00Z").unwrap().with_timezone(&Utc);
+
+        let deep = [(dec!(0.50), dec!(10)), (dec!(0.49), dec!(20)), (dec!(0.48), dec!(30))];
+        recorder.record_at(&book("1", &deep, &[(dec!(0.52), dec!(5))]), t0).unwrap();
+        let trade = MarketEvent::Trade {
+            token_id: "1".to_string(),
+            price: dec!(0.51),
+            size: dec!(3),
+            side: "BUY".to_string(),
+            timestamp: 1_700_000_000_500,
+        };
+        recorder.record_at(&trade, t0).unwrap();
+        let invalid = MarketEvent::BookInvalid {
+            token_id: "1".to_string(),
+            health: crate::orderbook::BookHealth::Crossed,
+        };
+        recorder.record_at(&invalid, t0).unwrap();
+        // The next hour goes to a new file
+        let t1 = t0 + chrono::Duration::minutes(2);
+        recorder.record_at(&book("2", &[], &[]), t1).unwrap();
+        recorder.flush().unwrap();
+        assert_eq!(recorder.stats(), RecordStats { books: 2, trades: 1, dropped: 0 });
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        let books = read("books-20260115T1400.csv");
+        let lines: Vec<_> = books.lines().collect();
+        assert_eq!(
+            lines[0],
+            "recorded_at,timestamp,token_id,bid_price_1,bid_size_1,bid_price_2,bid_size_2,\
+             ask_price_1,ask_size_1,ask_price_2,ask_size_2"
+        );
+        let recorded_at = t0.timestamp_millis();
+        assert_eq!(lines[1], format!("{recorded_at},1700000000000,1,0.50,10,0.49,20,0.52,5,,"));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(read("books-20260115T1500.csv").lines().nth(1).unwrap().matches(',').count(), 10);
+        assert_eq!(
+            read("trades-20260115T1400.csv").lines().nth(1).unwrap(),
+            format!("{recorded_at},1700000000500,1,BUY,0.51,3")
+        );
+
+        // A restarted recorder appends without repeating the header
+        drop(recorder);
+        let mut recorder = MarketRecorder::new(&dir, 2, Duration::from_secs(3600)).unwrap();
+        recorder.record_at(&book("3", &[], &[]), t1).unwrap();
+        recorder.flush().unwrap();
+        let later = read("books-20260115T1500.csv");
+        assert_eq!(later.lines().count(), 3);
+        assert_eq!(later.matches("recorded_at").count(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,71 @@
+//! Strategies by name: the built-in ones and those registered at runtime.
+//!
+//! The built-in strategies come from the generated
+//! [`strategies::registry`](crate::strategies::registry), gated by the
+//! strategy group features. Crates embedding the engine add their own with
+//! [`register`] (or [`Engine::register_factory`](crate::Engine)) before the
+//! strategies are loaded; a registered strategy replaces a built-in one of
+//! the same name.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use crate::strategies::{self, StrategyFactory, StrategyInfo};
+
+/// Strategies registered at runtime, by name.
+static REGISTERED: RwLock<BTreeMap<String, StrategyInfo>> = RwLock::new(BTreeMap::new());
+
+/// Register a strategy under `name`. It needs market discovery when it
+/// subscribes to no tokens of its own, as for generated strategies.
+pub fn register(name: &str, factory: StrategyFactory) {
+    let info = StrategyInfo {
+        factory,
+        requires_market_discovery: factory().subscriptions().is_empty(),
+    };
+    let mut registered = REGISTERED.write().unwrap_or_else(|e| e.into_inner());
+    if registered.insert(name.to_string(), info).is_some() {
+        tracing::warn!(strategy = name, "Strategy registered twice, keeping the last");
+    }
+}
+
+/// Look up a strategy by name, registered ones first.
+pub fn get(name: &str) -> Option<StrategyInfo> {
+    let registered = REGISTERED.read().unwrap_or_else(|e| e.into_inner());
+    registered
+        .get(name)
+        .copied()
+        .or_else(|| strategies::registry().get(name).copied())
+}
+
+/// Every available strategy, by name.
+pub fn all() -> BTreeMap<String, StrategyInfo> {
+    let mut all: BTreeMap<String, StrategyInfo> = strategies::registry()
+        .into_iter()
+        .map(|(name, info)| (name.to_string(), info))
+        .collect();
+    let registered = REGISTERED.read().unwrap_or_else(|e| e.into_inner());
+    all.extend(registered.iter().map(|(name, info)| (name.clone(), *info)));
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::DummyStrategy;
+
+    #[test]
+    fn test_registered_strategies_are_listed() {
+        register("test_discovery", || Box::new(DummyStrategy::new("test_discovery", vec![])));
+        register("test_fixed", || Box::new(DummyStrategy::new("test_fixed", vec!["t1".to_string()])));
+
+        let discovery = get("test_discovery").unwrap();
+        assert!(discovery.requires_market_discovery);
+        assert_eq!((discovery.factory)().id(), "test_discovery");
+        assert!(!get("test_fixed").unwrap().requires_market_discovery);
+        assert!(get("test_missing").is_none());
+
+        let all = all();
+        assert!(all.contains_key("test_discovery") && all.contains_key("test_fixed"));
+        assert!(strategies::registry().keys().all(|name| all.contains_key(*name)));
+    }
+}
@@ -0,0 +1,227 @@
+//! Daily P&L and performance reports.
+//!
+//! With `PMENGINE_STATE_DIR` set, the engine sums up each UTC day of trading
+//! in `reports.jsonl` in the state directory: realized P&L (net of fees), the
+//! unrealized P&L of what was held when the report was made, fees, volume and
+//! win rate, in total and per strategy. A day is reported on the first market
+//! refresh after it ends, and the current day again at shutdown; the latest
+//! report of a day is the one that counts. `pmengine report --date` prints a
+//! day's report, or builds one from `fills.jsonl` (without unrealized P&L) if
+//! the engine didn't write it.
+//!
+//! Realized P&L is put on the day of the fill that realized it, by replaying
+//! the fill log per strategy. A fill that shrinks a position is a closing
+//! trade, and a win if it realized a profit before fees.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::attribution::{StrategyPnl, UNATTRIBUTED};
+use crate::position::{Fill, PositionTracker};
+use crate::safe_math::{add, mul, sub};
+
+const REPORTS_FILE: &str = "reports.jsonl";
+
+/// Trading results over a day, in total or for one strategy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnlSummary {
+    /// Net of fees
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub fees: Decimal,
+    /// Notional of the fills
+    pub volume: Decimal,
+    pub fills: u64,
+    /// Fills that shrank a position
+    pub closing_trades: u64,
+    /// Closing trades that realized a profit before fees
+    pub winning_trades: u64,
+}
+
+impl PnlSummary {
+    pub fn total_pnl(&self) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl
+    }
+
+    /// Share of closing trades won (None without any).
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.closing_trades > 0).then(|| self.winning_trades as f64 / self.closing_trades as f64)
+    }
+
+    fn add_fill(&mut self, fill: &Fill, realized: Decimal, closing: bool) {
+        self.realized_pnl = add(self.realized_pnl, realized, "daily realized P&L");
+        self.fees = add(self.fees, fill.fee, "daily fees");
+        self.volume = add(self.volume, mul(fill.price, fill.size, "fill notional"), "daily volume");
+        self.fills += 1;
+        if closing {
+            self.closing_trades += 1;
+            // Before fees: whether the trade itself was right
+            if add(realized, fill.fee, "gross P&L") > Decimal::ZERO {
+                self.winning_trades += 1;
+            }
+        }
+    }
+}
+
+/// One day's report (one `reports.jsonl` line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    pub generated_at: DateTime<Utc>,
+    pub total: PnlSummary,
+    /// By strategy ID (untagged fills under `unattributed`)
+    pub strategies: BTreeMap<String, PnlSummary>,
+}
+
+impl DailyReport {
+    /// Report of `date` from the fill log (oldest first), with the
+    /// unrealized P&L of each strategy's current positions.
+    pub fn build(date: NaiveDate, fills: &[Fill], unrealized: &[StrategyPnl]) -> Self {
+        let mut books: HashMap<&str, PositionTracker> = HashMap::new();
+        let mut strategies: BTreeMap<String, PnlSummary> = BTreeMap::new();
+        for fill in fills {
+            let strategy_id = fill.strategy_id.as_deref().unwrap_or(UNATTRIBUTED);
+            let position = books.entry(strategy_id).or_default().get_or_create(&fill.token_id);
+            let held = position.size;
+            let realized_before = position.realized_pnl;
+            position.apply_fill(fill);
+            if fill.timestamp.date_naive() != date {
+                continue;
+            }
+            let realized = sub(position.realized_pnl, realized_before, "fill P&L");
+            let closing = if fill.is_buy { held < Decimal::ZERO } else { held > Decimal::ZERO };
+            strategies.entry(strategy_id.to_string()).or_default().add_fill(fill, realized, closing);
+        }
+        for pnl in unrealized.iter().filter(|p| !p.unrealized_pnl.is_zero()) {
+            strategies.entry(pnl.strategy_id.clone()).or_default().unrealized_pnl = pnl.unrealized_pnl;
+        }
+
+        let mut total = PnlSummary::default();
+        for summary in strategies.values() {
+            total.realized_pnl = add(total.realized_pnl, summary.realized_pnl, "daily realized P&L");
+            total.unrealized_pnl = add(total.unrealized_pnl, summary.unrealized_pnl, "daily unrealized P&L");
+            total.fees = add(total.fees, summary.fees, "daily fees");
+            total.volume = add(total.volume, summary.volume, "daily volume");
+            total.fills += summary.fills;
+            total.closing_trades += summary.closing_trades;
+            total.winning_trades += summary.winning_trades;
+        }
+        Self { date, generated_at: Utc::now(), total, strategies }
+    }
+
+    pub fn log(&self) {
+        tracing::info!(
+            date = %self.date,
+            realized_pnl = %self.total.realized_pnl,
+            unrealized_pnl = %self.total.unrealized_pnl,
+            fees = %self.total.fees,
+            volume = %self.total.volume,
+            fills = self.total.fills,
+            win_rate = ?self.total.win_rate(),
+            "Daily report"
+        );
+    }
+}
+
+/// Append a report to the state directory's `reports.jsonl`.
+pub fn save(dir: &Path, report: &DailyReport) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(dir.join(REPORTS_FILE))?;
+    let mut line = serde_json::to_string(report)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())
+}
+
+/// The latest report saved for `date` (None if there is none).
+pub fn load(dir: &Path, date: NaiveDate) -> std::io::Result<Option<DailyReport>> {
+    let file = match File::open(dir.join(REPORTS_FILE)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut latest = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let report: DailyReport =
+            serde_json::from_str(&line).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if report.date == date {
+            latest = Some(report);
+        }
+    }
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn fill(day: u32, strategy_id: &str, is_buy: bool, price: Decimal, size: Decimal) -> Fill {
+        Fill {
+            order_id: "1".to_string(),
+            token_id: format!("{}-token", strategy_id),
+            is_buy,
+            price,
+            size,
+            timestamp: Utc.with_ymd_and_hms(2026, 1, day, 12, 0, 0).unwrap(),
+            fee: dec!(0.01),
+            strategy_id: Some(strategy_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_daily_report_splits_days_and_strategies() {
+        let fills = [
+            fill(1, "maker", true, dec!(0.50), dec!(10)),
+            fill(2, "maker", false, dec!(0.60), dec!(5)),
+            fill(2, "maker", false, dec!(0.45), dec!(5)),
+            fill(2, "taker", true, dec!(0.30), dec!(10)),
+            fill(3, "taker", false, dec!(0.40), dec!(10)),
+        ];
+        let unrealized = [StrategyPnl {
+            strategy_id: "taker".to_string(),
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: dec!(0.5),
+            fees: Decimal::ZERO,
+            notional: dec!(3),
+            fills: 1,
+        }];
+        let date = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        let report = DailyReport::build(date, &fills, &unrealized);
+
+        // maker: +0.50 and -0.25 on its two closing sells, less 0.02 of fees
+        let maker = &report.strategies["maker"];
+        assert_eq!(maker.realized_pnl, dec!(0.23));
+        assert_eq!((maker.fills, maker.closing_trades, maker.winning_trades), (2, 2, 1));
+        assert_eq!(maker.win_rate(), Some(0.5));
+        assert_eq!(maker.volume, dec!(5.25));
+
+        // taker only opened a position that day
+        let taker = &report.strategies["taker"];
+        assert_eq!((taker.realized_pnl, taker.unrealized_pnl), (dec!(-0.01), dec!(0.5)));
+        assert_eq!(taker.closing_trades, 0);
+        assert_eq!(taker.win_rate(), None);
+
+        assert_eq!(report.total.realized_pnl, dec!(0.22));
+        assert_eq!(report.total.fees, dec!(0.03));
+        assert_eq!(report.total.fills, 3);
+        assert_eq!(report.total.total_pnl(), dec!(0.72));
+
+        let dir = std::env::temp_dir().join(format!("pmengine-reports-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load(&dir, date).unwrap(), None);
+        save(&dir, &DailyReport::build(date, &fills[..2], &[])).unwrap();
+        save(&dir, &report).unwrap();
+        assert_eq!(load(&dir, date).unwrap(), Some(report));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,15 +1,26 @@
 //! Risk management and circuit breaker.
 
+use crate::drawdown::PnlWindow;
+use crate::exposure::MarketMap;
+use crate::order::Order;
 use crate::position::PositionTracker;
-use crate::strategy::Signal;
+use crate::risk_journal::CheckInputs;
+use crate::safe_math::{add, div, mul, sub, sum};
+use crate::strategy::{MarketInfo, Signal};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 /// Tracked open order for exposure calculation.
 #[derive(Debug, Clone)]
 pub struct TrackedOrder {
     pub token_id: String,
     pub notional: Decimal,
+    /// Shares of a buy that complete sets with held complementary outcomes
+    /// (not in `notional`)
+    pub hedge_size: Decimal,
+    /// Strategy the exposure counts against (None = untagged)
+    pub strategy_id: Option<String>,
 }
 
 /// Risk limits configuration.
@@ -19,12 +30,47 @@ pub struct RiskLimits {
     pub max_position_size: Decimal,
     /// Maximum total exposure across all positions AND open orders (in USDC)
     pub max_total_exposure: Decimal,
+    /// Maximum exposure in one market, its outcomes netted (None = unchecked)
+    pub max_market_exposure: Option<Decimal>,
+    /// Maximum exposure across the markets of one category (None = unchecked)
+    pub max_category_exposure: Option<Decimal>,
     /// Maximum loss before circuit breaker triggers (in USDC)
     pub max_loss: Decimal,
-    /// Maximum number of open orders
+    /// Peak-to-trough P&L drop within `drawdown_window` that halts trading
+    /// (in USDC; None = unchecked)
+    pub max_drawdown: Option<Decimal>,
+    pub drawdown_window: Duration,
+    /// P&L that may be lost within `loss_rate_window` before trading halts
+    /// (in USDC; None = unchecked)
+    pub max_loss_rate: Option<Decimal>,
+    pub loss_rate_window: Duration,
+    /// Fraction of a loss limit at which only orders that shrink a position
+    /// are allowed (None = halt without a reduce-only stage)
+    pub reduce_only_at: Option<Decimal>,
+    /// Resume trading this long after a drawdown or loss-rate halt, with the
+    /// order, position and exposure limits halved (None = manual reset only)
+    pub breaker_cooldown: Option<Duration>,
+    /// Maximum number of open orders (0 = unlimited)
     pub max_open_orders: usize,
+    /// Maximum number of open orders per token (0 = unlimited)
+    pub max_open_orders_per_token: usize,
     /// Maximum order size (in USDC notional)
     pub max_order_size: Decimal,
+    /// How long a reservation may stay unconfirmed before it is treated as leaked
+    pub reservation_ttl: Duration,
+    /// Maximum exposure (attributed positions + open orders) per strategy ID,
+    /// on top of the global limits; strategies not listed are only bound by those
+    pub strategy_max_exposure: HashMap<String, Decimal>,
+    /// Lowest price an order may be placed at
+    pub min_price: Decimal,
+    /// Highest price an order may be placed at
+    pub max_price: Decimal,
+    /// Maximum distance of an order's price from the token's reference price
+    /// (mid, else last trade); None = unchecked
+    pub max_price_deviation: Option<Decimal>,
+    /// Maximum order size as a multiple of the visible depth on the side the
+    /// order trades against; None = unchecked
+    pub max_depth_ratio: Option<Decimal>,
 }
 
 impl Default for RiskLimits {
@@ -32,13 +78,54 @@ impl Default for RiskLimits {
         Self {
             max_position_size: Decimal::from(50),
             max_total_exposure: Decimal::from(50),
+            max_market_exposure: None,
+            max_category_exposure: None,
             max_loss: Decimal::from(25),
+            max_drawdown: None,
+            drawdown_window: Duration::from_secs(3600),
+            max_loss_rate: None,
+            loss_rate_window: Duration::from_secs(600),
+            reduce_only_at: None,
+            breaker_cooldown: None,
             max_open_orders: 10,
+            max_open_orders_per_token: 6,
             max_order_size: Decimal::from(25),
+            reservation_ttl: Duration::from_secs(30),
+            strategy_max_exposure: HashMap::new(),
+            min_price: Decimal::new(1, 3),
+            max_price: Decimal::new(999, 3),
+            max_price_deviation: None,
+            max_depth_ratio: None,
         }
     }
 }
 
+/// A token's market as the sanity checks see it: where it trades and how
+/// much size its book shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketReference {
+    pub mid: Option<Decimal>,
+    pub last_trade: Option<Decimal>,
+    /// Total size of the bids
+    pub bid_depth: Decimal,
+    /// Total size of the asks
+    pub ask_depth: Decimal,
+}
+
+impl MarketReference {
+    /// Price orders are compared with: the mid, else the last trade.
+    pub fn price(&self) -> Option<Decimal> {
+        self.mid.or(self.last_trade)
+    }
+
+    /// Depth a buy (the asks) or sell (the bids) trades against; None if
+    /// that side of the book is empty.
+    pub fn depth(&self, is_buy: bool) -> Option<Decimal> {
+        let depth = if is_buy { self.ask_depth } else { self.bid_depth };
+        (depth > Decimal::ZERO).then_some(depth)
+    }
+}
+
 /// Result of risk check on a signal.
 #[derive(Debug)]
 pub enum RiskCheckResult {
@@ -55,31 +142,107 @@ pub enum RiskCheckResult {
 pub struct PendingReservation {
     pub token_id: String,
     pub notional: Decimal,
+    pub hedge_size: Decimal,
+    pub strategy_id: Option<String>,
+    pub created_at: Instant,
+}
+
+/// Counters for exposure that was reserved but never properly released.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LeakMetrics {
+    /// Reservations expired after outliving the TTL without confirm/release
+    pub expired_reservations: u64,
+    /// Notional freed by expired reservations
+    pub expired_notional: Decimal,
+    /// Tracked orders dropped by an audit because they are no longer open
+    pub stale_orders: u64,
+    /// Notional freed by dropping stale orders
+    pub stale_order_notional: Decimal,
+    /// Open orders an audit found untracked (and started tracking)
+    pub untracked_orders: u64,
+    /// Number of audits run
+    pub audits: u64,
+}
+
+/// Result of comparing tracked exposure with the actual open orders.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditReport {
+    /// Tracked orders that are no longer open
+    pub stale_orders: Vec<String>,
+    /// Open orders that were not tracked
+    pub untracked_orders: Vec<String>,
+    /// Reserved notional before the audit (open orders + pending)
+    pub reserved_before: Decimal,
+    /// Reserved notional after the audit
+    pub reserved_after: Decimal,
+}
+
+impl AuditReport {
+    /// Whether tracked exposure matched the open orders.
+    pub fn is_clean(&self) -> bool {
+        self.stale_orders.is_empty() && self.untracked_orders.is_empty()
+    }
 }
 
 /// Risk manager enforces trading limits.
 pub struct RiskManager {
     limits: RiskLimits,
+    /// Limits as configured, before any halving after a cooldown
+    base_limits: RiskLimits,
     circuit_breaker_triggered: bool,
+    /// When a drawdown or loss-rate halt started (None = not halted, or
+    /// halted until reset by hand)
+    auto_halted_at: Option<Instant>,
+    /// Reduce-only mode switched on by losses near the limits
+    loss_reduce_only: bool,
+    /// Reduce-only mode switched on by a strategy or the control API
+    manual_reduce_only: bool,
+    /// Recent total P&L, for the drawdown and loss-rate limits
+    pnl_window: PnlWindow,
     /// Open orders tracked by order_id -> TrackedOrder
     open_orders: HashMap<String, TrackedOrder>,
     /// Pending exposure reservations (reserved before order placed, keyed by temp ID)
     pending_reservations: HashMap<String, PendingReservation>,
     /// Counter for generating unique reservation IDs
     reservation_counter: u64,
+    /// Leaked reservation/order counters
+    leaks: LeakMetrics,
+    /// Latest market of each token, for the price and depth sanity checks
+    market_references: HashMap<String, MarketReference>,
+    /// Market and category of each token, for netting and concentration
+    markets: MarketMap,
 }
 
 impl RiskManager {
     pub fn new(limits: RiskLimits) -> Self {
+        let window = limits.drawdown_window.max(limits.loss_rate_window);
         Self {
+            base_limits: limits.clone(),
             limits,
             circuit_breaker_triggered: false,
+            auto_halted_at: None,
+            loss_reduce_only: false,
+            manual_reduce_only: false,
+            pnl_window: PnlWindow::new(window),
             open_orders: HashMap::new(),
             pending_reservations: HashMap::new(),
             reservation_counter: 0,
+            leaks: LeakMetrics::default(),
+            market_references: HashMap::new(),
+            markets: MarketMap::default(),
         }
     }
 
+    /// Update the market a token's orders are sanity-checked against.
+    pub fn set_market_reference(&mut self, token_id: &str, reference: MarketReference) {
+        self.market_references.insert(token_id.to_string(), reference);
+    }
+
+    /// Update which market and category each token belongs to.
+    pub fn set_markets(&mut self, market_info: &HashMap<String, MarketInfo>) {
+        self.markets = MarketMap::new(market_info);
+    }
+
     /// Check if circuit breaker is active.
     pub fn is_halted(&self) -> bool {
         self.circuit_breaker_triggered
@@ -91,23 +254,137 @@ impl RiskManager {
         self.circuit_breaker_triggered = true;
     }
 
-    /// Reset circuit breaker (manual intervention).
+    /// Reset circuit breaker (manual intervention), restoring the configured
+    /// limits.
     pub fn reset_circuit_breaker(&mut self) {
         tracing::warn!("Circuit breaker reset");
         self.circuit_breaker_triggered = false;
+        self.auto_halted_at = None;
+        self.loss_reduce_only = false;
+        self.limits = self.base_limits.clone();
+        self.pnl_window.clear();
+    }
+
+    /// Whether only orders that shrink a position are allowed.
+    pub fn is_reduce_only(&self) -> bool {
+        self.loss_reduce_only || self.manual_reduce_only
+    }
+
+    /// Switch reduce-only mode on or off by hand (losses near the limits
+    /// keep it on regardless).
+    pub fn set_reduce_only(&mut self, enabled: bool, reason: &str) {
+        if enabled == self.manual_reduce_only {
+            return;
+        }
+        if enabled {
+            tracing::warn!(reason, "Reduce-only mode on");
+        } else {
+            tracing::info!(reason, loss_reduce_only = self.loss_reduce_only, "Reduce-only mode off");
+        }
+        self.manual_reduce_only = enabled;
+    }
+
+    /// Limits in force (halved after each cooldown).
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
     }
 
     /// Check P&L and trigger circuit breaker if needed.
     pub fn check_pnl(&mut self, positions: &PositionTracker) {
         let total_pnl = positions.total_realized_pnl() + positions.total_unrealized_pnl();
+        self.check_pnl_at(total_pnl, Instant::now());
+    }
+
+    /// Check total P&L sampled at `now`. Losing more than `max_loss` halts
+    /// trading until a manual reset; a drawdown or loss rate over its limit
+    /// halts it until the cooldown (if any) has passed. Losses within
+    /// `reduce_only_at` of any of those limits switch reduce-only mode on,
+    /// and recovering switches it off again.
+    pub fn check_pnl_at(&mut self, total_pnl: Decimal, now: Instant) {
+        self.pnl_window.record(now, total_pnl);
+        if self.circuit_breaker_triggered {
+            if let (Some(halted_at), Some(cooldown)) = (self.auto_halted_at, self.limits.breaker_cooldown) {
+                if now.saturating_duration_since(halted_at) >= cooldown {
+                    self.resume_after_cooldown();
+                }
+            }
+            return;
+        }
+
         if total_pnl < -self.limits.max_loss {
             self.trigger_circuit_breaker(&format!(
                 "Max loss exceeded: {} < -{}",
                 total_pnl, self.limits.max_loss
             ));
+            return;
+        }
+        let drawdown = self.pnl_window.drawdown();
+        let loss = self.pnl_window.loss_over(self.limits.loss_rate_window, now);
+        let breached = |value: Decimal, limit: Option<Decimal>| limit.filter(|limit| value > *limit);
+        let halt = if let Some(limit) = breached(drawdown, self.limits.max_drawdown) {
+            Some(format!(
+                "Max drawdown exceeded: {} > {} within {}s",
+                drawdown,
+                limit,
+                self.limits.drawdown_window.as_secs()
+            ))
+        } else {
+            breached(loss, self.limits.max_loss_rate).map(|limit| {
+                format!(
+                    "Max loss rate exceeded: {} > {} within {}s",
+                    loss,
+                    limit,
+                    self.limits.loss_rate_window.as_secs()
+                )
+            })
+        };
+        if let Some(reason) = halt {
+            self.trigger_circuit_breaker(&reason);
+            self.auto_halted_at = Some(now);
+            return;
+        }
+
+        let reduce_only = self.limits.reduce_only_at.is_some_and(|at| {
+            let near = |value: Decimal, limit: Decimal| value >= mul(limit, at, "reduce-only threshold");
+            near(-total_pnl, self.limits.max_loss)
+                || self.limits.max_drawdown.is_some_and(|limit| near(drawdown, limit))
+                || self.limits.max_loss_rate.is_some_and(|limit| near(loss, limit))
+        });
+        if reduce_only != self.loss_reduce_only {
+            if reduce_only {
+                tracing::warn!(
+                    total_pnl = %total_pnl,
+                    drawdown = %drawdown,
+                    loss_rate = %loss,
+                    "Losses near the limits, reduce-only mode"
+                );
+            } else {
+                tracing::info!(total_pnl = %total_pnl, "Losses back under the limits, reduce-only mode off");
+            }
+            self.loss_reduce_only = reduce_only;
         }
     }
 
+    /// Lift a drawdown or loss-rate halt with the order, position and
+    /// exposure limits halved.
+    fn resume_after_cooldown(&mut self) {
+        let halve = |limit: &mut Decimal| *limit = div(*limit, Decimal::TWO, "halved limit");
+        halve(&mut self.limits.max_order_size);
+        halve(&mut self.limits.max_position_size);
+        halve(&mut self.limits.max_total_exposure);
+        self.limits.strategy_max_exposure.values_mut().for_each(halve);
+        self.circuit_breaker_triggered = false;
+        self.auto_halted_at = None;
+        self.loss_reduce_only = false;
+        self.pnl_window.clear();
+        tracing::warn!(
+            max_order_size = %self.limits.max_order_size,
+            max_position_size = %self.limits.max_position_size,
+            max_total_exposure = %self.limits.max_total_exposure,
+            "Circuit breaker cooled down, trading resumed with halved limits"
+        );
+    }
+
     /// Check a signal against risk limits.
     pub fn check_signal(&self, signal: &Signal, positions: &PositionTracker) -> RiskCheckResult {
         // Circuit breaker check
@@ -116,50 +393,155 @@ impl RiskManager {
         }
 
         match signal {
-            Signal::Hold | Signal::Cancel { .. } | Signal::Shutdown { .. } => {
-                RiskCheckResult::Approved(signal.clone())
+            // The orders a replace, an algo or a pair places are checked one by one
+            Signal::Hold
+            | Signal::Cancel { .. }
+            | Signal::Replace { .. }
+            | Signal::Algo { .. }
+            | Signal::Paired { .. }
+            | Signal::ReduceOnly { .. }
+            | Signal::Exit { .. }
+            | Signal::Convert { .. }
+            | Signal::Shutdown { .. } => RiskCheckResult::Approved(signal.clone()),
+
+            // A market order is checked as a limit order for its size at the worst price
+            Signal::Buy { .. } | Signal::Sell { .. } | Signal::Market { .. } => {
+                self.check_order(signal, positions)
+            }
+        }
+    }
+
+    /// The state `check_signal` looks at for a signal, for the risk journal.
+    pub fn check_inputs(&self, signal: &Signal, positions: &PositionTracker) -> CheckInputs {
+        let position_size = signal
+            .order_terms()
+            .and_then(|(token_id, ..)| positions.get(token_id))
+            .map(|p| p.size);
+        let reference = signal
+            .order_terms()
+            .and_then(|(token_id, is_buy, ..)| Some((self.market_references.get(token_id)?, is_buy)));
+        let (open_orders, token_open_orders) = signal
+            .order_terms()
+            .map(|(token_id, ..)| self.order_counts(token_id))
+            .unwrap_or_default();
+        let (hedge_size, market_exposure, category_exposure) = match signal.order_terms() {
+            Some((token_id, is_buy, price, size)) => {
+                let (_, hedge_size) = self.order_exposure(token_id, is_buy, price, size, positions);
+                let (market, category) = self.concentration(token_id, positions);
+                (hedge_size, market, category)
             }
+            None => Default::default(),
+        };
+        CheckInputs {
+            position_size,
+            reduce_only: self.is_reduce_only(),
+            open_orders,
+            token_open_orders,
+            reference_price: reference.and_then(|(r, _)| r.price()),
+            book_depth: reference.and_then(|(r, is_buy)| r.depth(is_buy)),
+            hedge_size,
+            market_exposure,
+            category_exposure,
+            position_notional: self.position_exposure(positions),
+            open_order_notional: self.open_order_notional(),
+            halted: self.circuit_breaker_triggered,
+            limits: (&self.limits).into(),
+        }
+    }
+
+    fn check_order(&self, signal: &Signal, positions: &PositionTracker) -> RiskCheckResult {
+        let Some((token_id, is_buy, price, size)) = signal.order_terms() else {
+            return RiskCheckResult::Approved(signal.clone());
+        };
+        let reference = self.market_references.get(token_id);
+        // Size the order is clamped to before the limits, and why
+        let mut clamp: Option<(Decimal, String)> = None;
+
+        // In reduce-only mode an order may only shrink the position
+        if self.is_reduce_only() {
+            let held = positions.get(token_id).map_or(Decimal::ZERO, |p| p.size);
+            let reducing = if is_buy { held < Decimal::ZERO } else { held > Decimal::ZERO };
+            if !reducing {
+                return RiskCheckResult::Rejected(format!(
+                    "Reduce-only mode: order would add to the position in {}",
+                    token_id
+                ));
+            }
+            if size > held.abs() {
+                let reason = format!("Order size reduced from {} to {} (reduce-only)", size, held.abs());
+                clamp = Some((held.abs(), reason));
+            }
+        }
 
-            Signal::Buy { token_id, price, size, urgency } => {
-                self.check_order(token_id, *price, *size, true, *urgency, positions)
+        // Open order count limits
+        let (open_orders, token_open_orders) = self.order_counts(token_id);
+        let max_orders = self.limits.max_open_orders;
+        if max_orders > 0 && open_orders >= max_orders {
+            return RiskCheckResult::Rejected(format!(
+                "Open order limit reached (open: {}, limit: {})",
+                open_orders, max_orders
+            ));
+        }
+        let max_token_orders = self.limits.max_open_orders_per_token;
+        if max_token_orders > 0 && token_open_orders >= max_token_orders {
+            return RiskCheckResult::Rejected(format!(
+                "Open order limit reached for {} (open: {}, limit: {})",
+                token_id, token_open_orders, max_token_orders
+            ));
+        }
+
+        // Fat-finger checks: a price outside the bounds or far from the market
+        if price < self.limits.min_price || price > self.limits.max_price {
+            return RiskCheckResult::Rejected(format!(
+                "Price {} outside bounds [{}, {}]",
+                price, self.limits.min_price, self.limits.max_price
+            ));
+        }
+        let reference_price = reference.and_then(|r| r.price());
+        if let (Some(max_deviation), Some(reference_price)) =
+            (self.limits.max_price_deviation, reference_price)
+        {
+            let deviation = sub(price, reference_price, "price deviation").abs();
+            if deviation > max_deviation {
+                return RiskCheckResult::Rejected(format!(
+                    "Price {} is {} from the market at {} (max deviation {})",
+                    price, deviation, reference_price, max_deviation
+                ));
             }
+        }
 
-            Signal::Sell { token_id, price, size, urgency } => {
-                self.check_order(token_id, *price, *size, false, *urgency, positions)
+        // More size than the book shows is clamped to a multiple of its depth
+        let depth = reference.and_then(|r| r.depth(is_buy));
+        if let (Some(ratio), Some(depth)) = (self.limits.max_depth_ratio, depth) {
+            let size = clamp.as_ref().map_or(size, |(size, _)| *size);
+            let max_size = mul(depth, ratio, "max depth size");
+            if size > max_size {
+                let reason =
+                    format!("Order size reduced from {} to {} (book depth {})", size, max_size, depth);
+                clamp = Some((max_size, reason));
             }
         }
+
+        match clamp {
+            Some((size, reason)) => match self.check_limits(&signal.with_size(size), positions) {
+                RiskCheckResult::Approved(signal) => RiskCheckResult::Reduced(signal, reason),
+                other => other,
+            },
+            None => self.check_limits(signal, positions),
+        }
     }
 
-    fn check_order(
-        &self,
-        token_id: &str,
-        price: Decimal,
-        size: Decimal,
-        is_buy: bool,
-        urgency: crate::strategy::Urgency,
-        positions: &PositionTracker,
-    ) -> RiskCheckResult {
-        let notional = price * size;
+    /// The order size, position and exposure limits.
+    fn check_limits(&self, signal: &Signal, positions: &PositionTracker) -> RiskCheckResult {
+        let Some((token_id, is_buy, price, size)) = signal.order_terms() else {
+            return RiskCheckResult::Approved(signal.clone());
+        };
 
         // Check order size limit
-        if notional > self.limits.max_order_size {
-            let max_size = self.limits.max_order_size / price;
+        if mul(price, size, "order notional") > self.limits.max_order_size {
+            let max_size = div(self.limits.max_order_size, price, "max order size");
             return RiskCheckResult::Reduced(
-                if is_buy {
-                    Signal::Buy {
-                        token_id: token_id.to_string(),
-                        price,
-                        size: max_size,
-                        urgency,
-                    }
-                } else {
-                    Signal::Sell {
-                        token_id: token_id.to_string(),
-                        price,
-                        size: max_size,
-                        urgency,
-                    }
-                },
+                signal.with_size(max_size),
                 format!("Order size reduced from {} to {} (max order size)", size, max_size),
             );
         }
@@ -171,10 +553,14 @@ impl RiskManager {
             } else {
                 pos.size - size
             };
-            let projected_notional = projected_size.abs() * price;
+            let projected_notional = mul(projected_size.abs(), price, "projected notional");
 
             if projected_notional > self.limits.max_position_size {
-                let allowed_change = self.limits.max_position_size / price - pos.size.abs();
+                let allowed_change = sub(
+                    div(self.limits.max_position_size, price, "position limit"),
+                    pos.size.abs(),
+                    "position limit",
+                );
                 if allowed_change <= Decimal::ZERO {
                     return RiskCheckResult::Rejected(format!(
                         "Position limit reached for {}",
@@ -182,56 +568,68 @@ impl RiskManager {
                     ));
                 }
                 return RiskCheckResult::Reduced(
-                    if is_buy {
-                        Signal::Buy {
-                            token_id: token_id.to_string(),
-                            price,
-                            size: allowed_change,
-                            urgency,
-                        }
-                    } else {
-                        Signal::Sell {
-                            token_id: token_id.to_string(),
-                            price,
-                            size: allowed_change,
-                            urgency,
-                        }
-                    },
+                    signal.with_size(allowed_change),
                     format!("Order size reduced to {} (position limit)", allowed_change),
                 );
             }
         }
 
+        // Exposure the order adds: shares completing sets with held outcomes add none
+        let (notional, hedge_size) = self.order_exposure(token_id, is_buy, price, size, positions);
+        let allowed_size =
+            |allowed| add(hedge_size, div(allowed, price, "exposure headroom"), "allowed size");
+
+        // Concentration in the order's market and category
+        let (market_exposure, category_exposure) = self.concentration(token_id, positions);
+        let market = self.markets.market(token_id);
+        let concentration = [
+            (self.limits.max_market_exposure, Some(market_exposure), format!("market {}", market)),
+            (
+                self.limits.max_category_exposure,
+                category_exposure,
+                format!("category {}", self.markets.category(market).unwrap_or_default()),
+            ),
+        ];
+        for (limit, exposure, scope) in concentration {
+            let (Some(limit), Some(exposure)) = (limit, exposure) else {
+                continue;
+            };
+            if add(exposure, notional, "projected concentration") <= limit {
+                continue;
+            }
+            let allowed = sub(limit, exposure, "concentration headroom");
+            if allowed <= Decimal::ZERO {
+                return RiskCheckResult::Rejected(format!(
+                    "Exposure limit reached for {} (exposure: {}, limit: {})",
+                    scope, exposure, limit
+                ));
+            }
+            let allowed_size = allowed_size(allowed);
+            return RiskCheckResult::Reduced(
+                signal.with_size(allowed_size),
+                format!(
+                    "Order size reduced to {} ({} exposure: {}, limit: {})",
+                    allowed_size, scope, exposure, limit
+                ),
+            );
+        }
+
         // Check total exposure limit (positions + open orders + this new order)
-        let position_notional = positions.total_notional();
+        let position_notional = self.position_exposure(positions);
         let open_order_notional = self.open_order_notional();
-        let current_exposure = position_notional + open_order_notional;
+        let current_exposure = add(position_notional, open_order_notional, "current exposure");
 
-        if current_exposure + notional > self.limits.max_total_exposure {
-            let allowed = self.limits.max_total_exposure - current_exposure;
+        if add(current_exposure, notional, "projected exposure") > self.limits.max_total_exposure {
+            let allowed = sub(self.limits.max_total_exposure, current_exposure, "exposure headroom");
             if allowed <= Decimal::ZERO {
                 return RiskCheckResult::Rejected(format!(
                     "Total exposure limit reached (positions: {}, open orders: {}, limit: {})",
                     position_notional, open_order_notional, self.limits.max_total_exposure
                 ));
             }
-            let allowed_size = allowed / price;
+            let allowed_size = allowed_size(allowed);
             return RiskCheckResult::Reduced(
-                if is_buy {
-                    Signal::Buy {
-                        token_id: token_id.to_string(),
-                        price,
-                        size: allowed_size,
-                        urgency,
-                    }
-                } else {
-                    Signal::Sell {
-                        token_id: token_id.to_string(),
-                        price,
-                        size: allowed_size,
-                        urgency,
-                    }
-                },
+                signal.with_size(allowed_size),
                 format!(
                     "Order size reduced to {} (total exposure: {} + {} = {}, limit: {})",
                     allowed_size, position_notional, open_order_notional, current_exposure, self.limits.max_total_exposure
@@ -240,29 +638,22 @@ impl RiskManager {
         }
 
         // All checks passed, return approved signal
-        RiskCheckResult::Approved(if is_buy {
-            Signal::Buy {
-                token_id: token_id.to_string(),
-                price,
-                size,
-                urgency,
-            }
-        } else {
-            Signal::Sell {
-                token_id: token_id.to_string(),
-                price,
-                size,
-                urgency,
-            }
-        })
+        RiskCheckResult::Approved(signal.clone())
     }
 
     /// Track an open order with its notional value.
-    pub fn order_placed(&mut self, order_id: &str, token_id: &str, notional: Decimal) {
+    pub fn order_placed(
+        &mut self,
+        order_id: &str,
+        token_id: &str,
+        notional: Decimal,
+        strategy_id: Option<&str>,
+    ) {
         tracing::debug!(
             order_id = order_id,
             token_id = token_id,
             notional = %notional,
+            strategy_id = strategy_id,
             "Tracking order"
         );
         self.open_orders.insert(
@@ -270,6 +661,8 @@ impl RiskManager {
             TrackedOrder {
                 token_id: token_id.to_string(),
                 notional,
+                hedge_size: Decimal::ZERO,
+                strategy_id: strategy_id.map(String::from),
             },
         );
     }
@@ -288,17 +681,161 @@ impl RiskManager {
 
     /// Get total notional value of open orders (excluding pending reservations).
     pub fn open_order_notional(&self) -> Decimal {
-        self.open_orders.values().map(|o| o.notional).sum()
+        sum(self.open_orders.values().map(|o| o.notional), "open order notional")
     }
 
     /// Get total notional value of pending reservations.
     pub fn pending_reservation_notional(&self) -> Decimal {
-        self.pending_reservations.values().map(|r| r.notional).sum()
+        sum(self.pending_reservations.values().map(|r| r.notional), "reservation notional")
     }
 
     /// Get total reserved exposure (open orders + pending reservations).
     pub fn total_reserved_notional(&self) -> Decimal {
-        self.open_order_notional() + self.pending_reservation_notional()
+        add(self.open_order_notional(), self.pending_reservation_notional(), "reserved notional")
+    }
+
+    /// Reserved notional (open orders + pending reservations) per token.
+    pub fn reserved_by_token(&self) -> HashMap<String, Decimal> {
+        let mut by_token: HashMap<String, Decimal> = HashMap::new();
+        let orders = self.open_orders.values().map(|o| (&o.token_id, o.notional));
+        let pending = self.pending_reservations.values().map(|r| (&r.token_id, r.notional));
+        for (token_id, notional) in orders.chain(pending) {
+            *by_token.entry(token_id.clone()).or_default() += notional;
+        }
+        by_token
+    }
+
+    /// Position notional with complementary outcomes netted (see
+    /// [`crate::exposure`]).
+    pub fn position_exposure(&self, positions: &PositionTracker) -> Decimal {
+        sum(self.markets.position_exposure(positions).into_values(), "position exposure")
+    }
+
+    /// Exposure an order adds and the shares of it that complete sets with
+    /// held complementary outcomes (which add none). Sets already claimed by
+    /// resting buys aren't counted twice; sells count in full.
+    pub fn order_exposure(
+        &self,
+        token_id: &str,
+        is_buy: bool,
+        price: Decimal,
+        size: Decimal,
+        positions: &PositionTracker,
+    ) -> (Decimal, Decimal) {
+        let hedge_size = if is_buy {
+            let orders = self.open_orders.values().map(|o| (&o.token_id, o.hedge_size));
+            let pending = self.pending_reservations.values().map(|r| (&r.token_id, r.hedge_size));
+            let claimed = sum(
+                orders.chain(pending).filter(|(t, _)| *t == token_id).map(|(_, size)| size),
+                "claimed hedge",
+            );
+            let capacity = self.markets.hedge_capacity(token_id, positions);
+            sub(capacity, claimed, "hedge capacity").max(Decimal::ZERO).min(size)
+        } else {
+            Decimal::ZERO
+        };
+        let notional = mul(price, sub(size, hedge_size, "unhedged size"), "order exposure");
+        (notional, hedge_size)
+    }
+
+    /// Exposure of a token's market and of its category (None without one):
+    /// netted positions plus open orders and pending reservations.
+    pub fn concentration(&self, token_id: &str, positions: &PositionTracker) -> (Decimal, Option<Decimal>) {
+        let market = self.markets.market(token_id);
+        let category = self.markets.category(market);
+        let in_category = |m: &str| category.is_some() && self.markets.category(m) == category;
+
+        let mut market_exposure = Decimal::ZERO;
+        let mut category_exposure = Decimal::ZERO;
+        let positions = self.markets.position_exposure(positions);
+        let reserved = self.reserved_by_token();
+        let by_market = positions
+            .iter()
+            .map(|(m, notional)| (m.as_str(), *notional))
+            .chain(reserved.iter().map(|(t, notional)| (self.markets.market(t), *notional)));
+        for (m, notional) in by_market {
+            if m == market {
+                market_exposure = add(market_exposure, notional, "market exposure");
+            }
+            if in_category(m) {
+                category_exposure = add(category_exposure, notional, "category exposure");
+            }
+        }
+        (market_exposure, category.map(|_| category_exposure))
+    }
+
+    /// Reserved notional (open orders + pending reservations) of one strategy.
+    pub fn strategy_reserved_notional(&self, strategy_id: &str) -> Decimal {
+        let orders = self.open_orders.values().map(|o| (&o.strategy_id, o.notional));
+        let pending = self.pending_reservations.values().map(|r| (&r.strategy_id, r.notional));
+        sum(
+            orders
+                .chain(pending)
+                .filter(|(id, _)| id.as_deref() == Some(strategy_id))
+                .map(|(_, notional)| notional),
+            "strategy reserved notional",
+        )
+    }
+
+    /// Exposure of one strategy: its attributed positions (if any) plus its
+    /// open orders and pending reservations.
+    pub fn strategy_exposure(&self, strategy_id: &str, positions: Option<&PositionTracker>) -> Decimal {
+        let position_notional = positions.map_or(Decimal::ZERO, |p| p.total_notional());
+        add(position_notional, self.strategy_reserved_notional(strategy_id), "strategy exposure")
+    }
+
+    /// Apply the strategy's exposure budget to a signal that passed the
+    /// global checks, reducing or rejecting it if the strategy would go over.
+    ///
+    /// `positions` are the positions attributed to the strategy.
+    pub fn check_strategy_budget(
+        &self,
+        strategy_id: &str,
+        checked: RiskCheckResult,
+        positions: Option<&PositionTracker>,
+    ) -> RiskCheckResult {
+        let Some(&limit) = self.limits.strategy_max_exposure.get(strategy_id) else {
+            return checked;
+        };
+        let (signal, reduced) = match checked {
+            RiskCheckResult::Approved(signal) => (signal, None),
+            RiskCheckResult::Reduced(signal, reason) => (signal, Some(reason)),
+            RiskCheckResult::Rejected(_) => return checked,
+        };
+        let (price, size) = match signal.order_terms() {
+            Some((_, _, price, size)) => (price, size),
+            None => {
+                return match reduced {
+                    Some(reason) => RiskCheckResult::Reduced(signal, reason),
+                    None => RiskCheckResult::Approved(signal),
+                };
+            }
+        };
+
+        let exposure = self.strategy_exposure(strategy_id, positions);
+        let notional = mul(price, size, "order notional");
+        if add(exposure, notional, "projected strategy exposure") <= limit {
+            return match reduced {
+                Some(reason) => RiskCheckResult::Reduced(signal, reason),
+                None => RiskCheckResult::Approved(signal),
+            };
+        }
+        let allowed = sub(limit, exposure, "strategy headroom");
+        if allowed <= Decimal::ZERO {
+            return RiskCheckResult::Rejected(format!(
+                "Strategy exposure limit reached for {} (exposure: {}, limit: {})",
+                strategy_id, exposure, limit
+            ));
+        }
+        let allowed_size = div(allowed, price, "strategy headroom");
+        let resized = signal.with_size(allowed_size);
+        RiskCheckResult::Reduced(
+            resized,
+            format!(
+                "Order size reduced to {} (strategy {} exposure: {}, limit: {})",
+                allowed_size, strategy_id, exposure, limit
+            ),
+        )
     }
 
     /// Get total open order count.
@@ -306,6 +843,14 @@ impl RiskManager {
         self.open_orders.len()
     }
 
+    /// Open orders plus pending reservations, in total and for one token.
+    pub fn order_counts(&self, token_id: &str) -> (usize, usize) {
+        let total = self.open_orders.len() + self.pending_reservations.len();
+        let orders = self.open_orders.values().map(|o| &o.token_id);
+        let pending = self.pending_reservations.values().map(|r| &r.token_id);
+        (total, orders.chain(pending).filter(|t| *t == token_id).count())
+    }
+
     /// Reserve exposure BEFORE placing an order.
     ///
     /// Returns a reservation ID if successful, None if exposure limit would be exceeded.
@@ -316,9 +861,32 @@ impl RiskManager {
         token_id: &str,
         notional: Decimal,
         positions: &PositionTracker,
+    ) -> Option<String> {
+        self.reserve(None, token_id, notional, Decimal::ZERO, positions)
+    }
+
+    /// Reserve exposure for an order of `strategy_id`, counting it against
+    /// the strategy's budget until the order closes.
+    pub fn reserve_exposure_for(
+        &mut self,
+        strategy_id: &str,
+        token_id: &str,
+        notional: Decimal,
+        positions: &PositionTracker,
+    ) -> Option<String> {
+        self.reserve(Some(strategy_id), token_id, notional, Decimal::ZERO, positions)
+    }
+
+    fn reserve(
+        &mut self,
+        strategy_id: Option<&str>,
+        token_id: &str,
+        notional: Decimal,
+        hedge_size: Decimal,
+        positions: &PositionTracker,
     ) -> Option<String> {
         // Calculate current exposure including pending reservations
-        let position_notional = positions.total_notional();
+        let position_notional = self.position_exposure(positions);
         let reserved_notional = self.total_reserved_notional();
         let current_exposure = position_notional + reserved_notional;
 
@@ -353,12 +921,28 @@ impl RiskManager {
             PendingReservation {
                 token_id: token_id.to_string(),
                 notional,
+                hedge_size,
+                strategy_id: strategy_id.map(String::from),
+                created_at: Instant::now(),
             },
         );
 
         Some(reservation_id)
     }
 
+    /// Reserve exposure for an order of `strategy_id`: only what it adds
+    /// once complementary outcomes are netted (see [`Self::order_exposure`]).
+    pub fn reserve_order_for(
+        &mut self,
+        strategy_id: &str,
+        order: &Signal,
+        positions: &PositionTracker,
+    ) -> Option<String> {
+        let (token_id, is_buy, price, size) = order.order_terms()?;
+        let (notional, hedge_size) = self.order_exposure(token_id, is_buy, price, size, positions);
+        self.reserve(Some(strategy_id), token_id, notional, hedge_size, positions)
+    }
+
     /// Confirm a reservation after order is successfully placed.
     ///
     /// Converts the pending reservation into a tracked open order.
@@ -377,6 +961,8 @@ impl RiskManager {
                 TrackedOrder {
                     token_id: reservation.token_id,
                     notional: reservation.notional,
+                    hedge_size: reservation.hedge_size,
+                    strategy_id: reservation.strategy_id,
                 },
             );
         } else {
@@ -405,9 +991,111 @@ impl RiskManager {
         }
     }
 
+    /// Drop reservations that were never confirmed or released within the TTL.
+    ///
+    /// A reservation normally lives for the duration of one order placement.
+    /// If the engine errors (or the event loop is restarted) in between, the
+    /// reservation would otherwise block exposure forever.
+    pub fn expire_reservations(&mut self) -> usize {
+        self.expire_reservations_at(Instant::now())
+    }
+
+    fn expire_reservations_at(&mut self, now: Instant) -> usize {
+        let ttl = self.limits.reservation_ttl;
+        let expired: Vec<String> = self
+            .pending_reservations
+            .iter()
+            .filter(|(_, r)| now.saturating_duration_since(r.created_at) > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &expired {
+            if let Some(reservation) = self.pending_reservations.remove(id) {
+                tracing::warn!(
+                    reservation_id = id.as_str(),
+                    token_id = reservation.token_id.as_str(),
+                    notional = %reservation.notional,
+                    age_secs = now.saturating_duration_since(reservation.created_at).as_secs(),
+                    "Expiring leaked exposure reservation"
+                );
+                self.leaks.expired_reservations += 1;
+                self.leaks.expired_notional += reservation.notional;
+            }
+        }
+
+        expired.len()
+    }
+
+    /// Reconcile tracked orders with the orders that are actually open.
+    ///
+    /// Tracked orders that are no longer open are dropped (their exposure was
+    /// leaked by a missed fill/cancel), and open orders that are not tracked
+    /// start being tracked at their remaining notional.
+    pub fn audit(&mut self, open_orders: &[&Order]) -> AuditReport {
+        let reserved_before = self.total_reserved_notional();
+        let open_ids: HashSet<&str> = open_orders.iter().map(|o| o.id.as_str()).collect();
+
+        let stale_orders: Vec<String> = self
+            .open_orders
+            .keys()
+            .filter(|id| !open_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        for id in &stale_orders {
+            if let Some(order) = self.open_orders.remove(id) {
+                self.leaks.stale_orders += 1;
+                self.leaks.stale_order_notional += order.notional;
+            }
+        }
+
+        let mut untracked_orders = Vec::new();
+        for order in open_orders {
+            if !self.open_orders.contains_key(&order.id) {
+                self.open_orders.insert(
+                    order.id.clone(),
+                    TrackedOrder {
+                        token_id: order.token_id.clone(),
+                        notional: mul(order.price, order.remaining(), "open order notional"),
+                        hedge_size: Decimal::ZERO,
+                        strategy_id: order.strategy_id.clone(),
+                    },
+                );
+                untracked_orders.push(order.id.clone());
+            }
+        }
+        self.leaks.untracked_orders += untracked_orders.len() as u64;
+        self.leaks.audits += 1;
+
+        let report = AuditReport {
+            stale_orders,
+            untracked_orders,
+            reserved_before,
+            reserved_after: self.total_reserved_notional(),
+        };
+
+        if report.is_clean() {
+            tracing::debug!(reserved = %report.reserved_after, "Exposure audit clean");
+        } else {
+            tracing::warn!(
+                stale_orders = report.stale_orders.len(),
+                untracked_orders = report.untracked_orders.len(),
+                reserved_before = %report.reserved_before,
+                reserved_after = %report.reserved_after,
+                "Exposure audit corrected tracked orders"
+            );
+        }
+
+        report
+    }
+
+    /// Leaked reservation/order counters since startup.
+    pub fn leak_metrics(&self) -> &LeakMetrics {
+        &self.leaks
+    }
+
     /// Get current exposure (positions + open orders + pending reservations).
     pub fn current_exposure(&self, positions: &PositionTracker) -> Decimal {
-        positions.total_notional() + self.total_reserved_notional()
+        self.position_exposure(positions) + self.total_reserved_notional()
     }
 
     /// Get remaining capacity before hitting exposure limit.
@@ -421,10 +1109,464 @@ impl Clone for RiskManager {
     fn clone(&self) -> Self {
         Self {
             limits: self.limits.clone(),
+            base_limits: self.base_limits.clone(),
             circuit_breaker_triggered: self.circuit_breaker_triggered,
+            auto_halted_at: self.auto_halted_at,
+            loss_reduce_only: self.loss_reduce_only,
+            manual_reduce_only: self.manual_reduce_only,
+            pnl_window: self.pnl_window.clone(),
             open_orders: self.open_orders.clone(),
             pending_reservations: self.pending_reservations.clone(),
             reservation_counter: self.reservation_counter,
+            leaks: self.leaks.clone(),
+            market_references: self.market_references.clone(),
+            markets: self.markets.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderStatus;
+    use rust_decimal_macros::dec;
+
+    fn open_order(id: &str, price: Decimal, size: Decimal) -> Order {
+        Order {
+            id: id.to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price,
+            size,
+            filled_size: Decimal::ZERO,
+            status: OrderStatus::Open,
+            created_at: chrono::Utc::now(),
+            ack_latency: None,
+            over_budget: false,
+            strategy_id: None,
+        }
+    }
+
+    #[test]
+    fn test_market_order_amount_is_scaled_down() {
+        let risk = RiskManager::new(RiskLimits::default());
+        let positions = PositionTracker::new();
+        let market = |amount| Signal::Market {
+            token_id: "token".to_string(),
+            is_buy: true,
+            amount,
+            worst_price: dec!(0.50),
+            time_in_force: crate::client::TimeInForce::Fok,
+        };
+
+        assert!(matches!(risk.check_signal(&market(dec!(20)), &positions), RiskCheckResult::Approved(_)));
+        // 40 USDC at up to 0.50 is 80 shares, over the 25 USDC order limit
+        match risk.check_signal(&market(dec!(40)), &positions) {
+            RiskCheckResult::Reduced(Signal::Market { amount, .. }, _) => assert_eq!(amount, dec!(25)),
+            other => panic!("expected a reduced market order, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reduced_orders_keep_post_only_and_expiry() {
+        let risk = RiskManager::new(RiskLimits::default());
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(5);
+        let quote = Signal::Sell {
+            token_id: "token".to_string(),
+            price: dec!(0.50),
+            size: dec!(80),
+            urgency: crate::strategy::Urgency::Low,
+            post_only: true,
+            expires_at: Some(expires_at),
+        };
+        match risk.check_signal(&quote, &PositionTracker::new()) {
+            RiskCheckResult::Reduced(Signal::Sell { size, post_only, expires_at: at, .. }, _) => {
+                assert_eq!((size, post_only, at), (dec!(50), true, Some(expires_at)));
+            }
+            other => panic!("expected a reduced sell, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unconfirmed_reservation_expires() {
+        let mut risk = RiskManager::new(RiskLimits::default());
+        let positions = PositionTracker::new();
+
+        let leaked = risk.reserve_exposure("token", dec!(20), &positions).unwrap();
+        assert_eq!(risk.pending_reservation_notional(), dec!(20));
+
+        // Still within the TTL
+        assert_eq!(risk.expire_reservations_at(Instant::now()), 0);
+
+        let later = Instant::now() + Duration::from_secs(31);
+        assert_eq!(risk.expire_reservations_at(later), 1);
+        assert_eq!(risk.pending_reservation_notional(), dec!(0));
+        assert_eq!(risk.leak_metrics().expired_reservations, 1);
+        assert_eq!(risk.leak_metrics().expired_notional, dec!(20));
+
+        // Late confirm/release of an expired reservation is harmless
+        risk.release_reservation(&leaked);
+        assert_eq!(risk.total_reserved_notional(), dec!(0));
+    }
+
+    #[test]
+    fn test_audit_reconciles_with_open_orders() {
+        let mut risk = RiskManager::new(RiskLimits::default());
+        risk.order_placed("gone", "token", dec!(10), None);
+        risk.order_placed("live", "token", dec!(5), None);
+
+        let live = open_order("live", dec!(0.50), dec!(10));
+        let unknown = open_order("unknown", dec!(0.40), dec!(10));
+        let report = risk.audit(&[&live, &unknown]);
+
+        assert_eq!(report.stale_orders, vec!["gone".to_string()]);
+        assert_eq!(report.untracked_orders, vec!["unknown".to_string()]);
+        assert_eq!(report.reserved_before, dec!(15));
+        assert_eq!(report.reserved_after, dec!(9));
+        assert_eq!(risk.leak_metrics().stale_orders, 1);
+        assert_eq!(risk.leak_metrics().stale_order_notional, dec!(10));
+
+        // A second audit finds nothing to fix
+        assert!(risk.audit(&[&live, &unknown]).is_clean());
+        assert_eq!(risk.leak_metrics().audits, 2);
+    }
+
+    #[test]
+    fn test_strategy_budget_caps_exposure() {
+        let limits = RiskLimits {
+            strategy_max_exposure: HashMap::from([("capped".to_string(), dec!(10))]),
+            ..Default::default()
+        };
+        let mut risk = RiskManager::new(limits);
+        let positions = PositionTracker::new();
+        let buy = |size| Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.50),
+            size,
+            urgency: crate::strategy::Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+
+        // Within budget, and strategies without a budget pass untouched
+        let checked = risk.check_strategy_budget("capped", RiskCheckResult::Approved(buy(dec!(10))), None);
+        assert!(matches!(checked, RiskCheckResult::Approved(_)));
+        let checked = risk.check_strategy_budget("free", RiskCheckResult::Approved(buy(dec!(100))), None);
+        assert!(matches!(checked, RiskCheckResult::Approved(_)));
+
+        // Open orders and attributed positions count against the budget
+        risk.reserve_exposure_for("capped", "token", dec!(4), &positions).unwrap();
+        risk.order_placed("other", "token", dec!(20), Some("free"));
+        let mut held = PositionTracker::new();
+        held.get_or_create("token").apply_fill(&crate::position::Fill {
+            order_id: "1".to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price: dec!(0.50),
+            size: dec!(8),
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: Some("capped".to_string()),
+        });
+        assert_eq!(risk.strategy_exposure("capped", Some(&held)), dec!(8));
+
+        let checked = RiskCheckResult::Approved(buy(dec!(10)));
+        let checked = risk.check_strategy_budget("capped", checked, Some(&held));
+        match checked {
+            RiskCheckResult::Reduced(Signal::Buy { size, .. }, _) => assert_eq!(size, dec!(4)),
+            other => panic!("expected reduction, got {:?}", other),
+        }
+
+        risk.order_placed("more", "token", dec!(2), Some("capped"));
+        let checked = RiskCheckResult::Approved(buy(dec!(1)));
+        let checked = risk.check_strategy_budget("capped", checked, Some(&held));
+        assert!(matches!(checked, RiskCheckResult::Rejected(_)));
+    }
+
+    #[test]
+    fn test_fat_finger_prices_and_sizes() {
+        let limits = RiskLimits {
+            max_price_deviation: Some(dec!(0.10)),
+            max_depth_ratio: Some(dec!(2)),
+            ..Default::default()
+        };
+        let mut risk = RiskManager::new(limits);
+        let positions = PositionTracker::new();
+        let buy = |price, size| Signal::Buy {
+            token_id: "token".to_string(),
+            price,
+            size,
+            urgency: crate::strategy::Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+
+        let check = |risk: &RiskManager, price, size| risk.check_signal(&buy(price, size), &positions);
+
+        // Out of bounds, whatever the market
+        assert!(matches!(check(&risk, dec!(1.5), dec!(1)), RiskCheckResult::Rejected(_)));
+        // No market known yet: nothing to compare with
+        assert!(matches!(check(&risk, dec!(0.99), dec!(5)), RiskCheckResult::Approved(_)));
+
+        let reference = MarketReference {
+            mid: Some(dec!(0.05)),
+            last_trade: Some(dec!(0.06)),
+            bid_depth: dec!(100),
+            ask_depth: dec!(20),
+        };
+        risk.set_market_reference("token", reference);
+        let inputs = risk.check_inputs(&buy(dec!(0.99), dec!(5)), &positions);
+        assert_eq!((inputs.reference_price, inputs.book_depth), (Some(dec!(0.05)), Some(dec!(20))));
+        match check(&risk, dec!(0.99), dec!(5)) {
+            RiskCheckResult::Rejected(reason) => assert!(reason.contains("max deviation"), "{}", reason),
+            other => panic!("expected a rejected buy, got {:?}", other),
+        }
+
+        // Within range but more than twice the 20 asks showing: clamped to 40
+        match check(&risk, dec!(0.10), dec!(100)) {
+            RiskCheckResult::Reduced(signal, reason) => {
+                assert_eq!(signal.order_terms().map(|(.., size)| size), Some(dec!(40)));
+                assert!(reason.contains("book depth"), "{}", reason);
+            }
+            other => panic!("expected a reduced buy, got {:?}", other),
+        }
+        // The clamped size still goes through the order size limit
+        risk.set_market_reference("token", MarketReference { ask_depth: dec!(200), ..reference });
+        match check(&risk, dec!(0.10), dec!(1000)) {
+            RiskCheckResult::Reduced(signal, reason) => {
+                assert_eq!(signal.order_terms().map(|(.., size)| size), Some(dec!(250)));
+                assert!(reason.contains("max order size"), "{}", reason);
+            }
+            other => panic!("expected a reduced buy, got {:?}", other),
+        }
+
+        // A one-sided book falls back to the last trade
+        risk.set_market_reference("token", MarketReference { mid: None, ..reference });
+        let inputs = risk.check_inputs(&buy(dec!(0.10), dec!(1)), &positions);
+        assert_eq!(inputs.reference_price, Some(dec!(0.06)));
+        assert!(matches!(check(&risk, dec!(0.10), dec!(1)), RiskCheckResult::Approved(_)));
+    }
+
+    #[test]
+    fn test_open_order_counts_are_limited() {
+        let limits = RiskLimits { max_open_orders: 3, max_open_orders_per_token: 2, ..Default::default() };
+        let mut risk = RiskManager::new(limits);
+        let positions = PositionTracker::new();
+        let buy = |token_id: &str| Signal::Buy {
+            token_id: token_id.to_string(),
+            price: dec!(0.50),
+            size: dec!(1),
+            urgency: crate::strategy::Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+
+        risk.order_placed("a1", "a", dec!(1), None);
+        // A pending reservation counts as an order
+        risk.reserve_exposure("a", dec!(1), &positions).unwrap();
+        assert_eq!(risk.order_counts("a"), (2, 2));
+        match risk.check_signal(&buy("a"), &positions) {
+            RiskCheckResult::Rejected(reason) => assert!(reason.contains("for a"), "{}", reason),
+            other => panic!("expected a rejected buy, got {:?}", other),
+        }
+        assert!(matches!(risk.check_signal(&buy("b"), &positions), RiskCheckResult::Approved(_)));
+
+        risk.order_placed("b1", "b", dec!(1), None);
+        let inputs = risk.check_inputs(&buy("c"), &positions);
+        assert_eq!((inputs.open_orders, inputs.token_open_orders), (3, 0));
+        assert!(matches!(risk.check_signal(&buy("c"), &positions), RiskCheckResult::Rejected(_)));
+        risk.order_closed("a1");
+        assert!(matches!(risk.check_signal(&buy("c"), &positions), RiskCheckResult::Approved(_)));
+    }
+
+    #[test]
+    fn test_drawdown_goes_reduce_only_then_halts_and_cools_down() {
+        let limits = RiskLimits {
+            max_drawdown: Some(dec!(10)),
+            drawdown_window: Duration::from_secs(3600),
+            reduce_only_at: Some(dec!(0.5)),
+            breaker_cooldown: Some(Duration::from_secs(300)),
+            strategy_max_exposure: HashMap::from([("capped".to_string(), dec!(20))]),
+            ..Default::default()
+        };
+        let mut risk = RiskManager::new(limits);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        risk.check_pnl_at(dec!(8), at(0));
+        risk.check_pnl_at(dec!(4), at(60));
+        assert!(!risk.is_reduce_only());
+        // 6 off the peak: past half the drawdown limit
+        risk.check_pnl_at(dec!(2), at(120));
+        assert!(risk.is_reduce_only() && !risk.is_halted());
+        risk.check_pnl_at(dec!(5), at(180));
+        assert!(!risk.is_reduce_only());
+
+        risk.check_pnl_at(dec!(-3), at(240));
+        assert!(risk.is_halted());
+        // Still cooling down, however P&L moves
+        risk.check_pnl_at(dec!(8), at(500));
+        assert!(risk.is_halted());
+
+        risk.check_pnl_at(dec!(-3), at(540));
+        assert!(!risk.is_halted() && !risk.is_reduce_only());
+        assert_eq!(risk.limits().max_order_size, dec!(12.5));
+        assert_eq!(risk.limits().max_total_exposure, dec!(25));
+        assert_eq!(risk.limits().strategy_max_exposure["capped"], dec!(10));
+        // The loss before the halt doesn't count against the fresh window
+        risk.check_pnl_at(dec!(-4), at(600));
+        assert!(!risk.is_reduce_only());
+
+        risk.reset_circuit_breaker();
+        assert_eq!(risk.limits().max_order_size, dec!(25));
+    }
+
+    #[test]
+    fn test_loss_rate_halt_cools_down_but_max_loss_does_not() {
+        let limits = RiskLimits {
+            max_loss_rate: Some(dec!(5)),
+            loss_rate_window: Duration::from_secs(600),
+            breaker_cooldown: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let mut risk = RiskManager::new(limits);
+        let start = Instant::now();
+
+        // Losing 6 within ten minutes halts, then cools down
+        risk.check_pnl_at(dec!(0), start);
+        risk.check_pnl_at(dec!(-6), start + Duration::from_secs(300));
+        assert!(risk.is_halted());
+        risk.check_pnl_at(dec!(-6), start + Duration::from_secs(360));
+        assert!(!risk.is_halted());
+
+        // The absolute loss limit does not cool down
+        risk.check_pnl_at(dec!(-30), start + Duration::from_secs(400));
+        assert!(risk.is_halted());
+        risk.check_pnl_at(dec!(-30), start + Duration::from_secs(10_000));
+        assert!(risk.is_halted());
+    }
+
+    #[test]
+    fn test_reduce_only_allows_shrinking_positions() {
+        let limits = RiskLimits { reduce_only_at: Some(dec!(0.5)), ..Default::default() };
+        let mut risk = RiskManager::new(limits);
+        // 15 lost: past half of the 25 max loss
+        risk.check_pnl_at(dec!(-15), Instant::now());
+        assert!(risk.is_reduce_only());
+
+        let mut positions = PositionTracker::new();
+        positions.get_or_create("token").apply_fill(&crate::position::Fill {
+            order_id: "1".to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price: dec!(0.50),
+            size: dec!(20),
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: None,
+        });
+        let order = |is_buy, size| {
+            let (token_id, price) = ("token".to_string(), dec!(0.50));
+            let urgency = crate::strategy::Urgency::Medium;
+            if is_buy {
+                Signal::Buy { token_id, price, size, urgency, post_only: false, expires_at: None }
+            } else {
+                Signal::Sell { token_id, price, size, urgency, post_only: false, expires_at: None }
+            }
+        };
+
+        let check = |is_buy, size| risk.check_signal(&order(is_buy, size), &positions);
+        assert!(risk.check_inputs(&order(true, dec!(1)), &positions).reduce_only);
+        assert!(matches!(check(true, dec!(1)), RiskCheckResult::Rejected(_)));
+        assert!(matches!(check(false, dec!(5)), RiskCheckResult::Approved(_)));
+        match check(false, dec!(30)) {
+            RiskCheckResult::Reduced(signal, reason) => {
+                assert_eq!(signal.order_terms().map(|(.., size)| size), Some(dec!(20)));
+                assert!(reason.contains("reduce-only"), "{}", reason);
+            }
+            other => panic!("expected a reduced sell, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_manual_reduce_only_outlasts_losses() {
+        let limits = RiskLimits { reduce_only_at: Some(dec!(0.5)), ..Default::default() };
+        let mut risk = RiskManager::new(limits);
+        let now = Instant::now();
+
+        risk.set_reduce_only(true, "winding down");
+        risk.check_pnl_at(dec!(5), now);
+        assert!(risk.is_reduce_only());
+
+        // Losses keep it on after it is switched off by hand
+        risk.check_pnl_at(dec!(-15), now);
+        risk.set_reduce_only(false, "done");
+        assert!(risk.is_reduce_only());
+        risk.check_pnl_at(dec!(0), now);
+        assert!(!risk.is_reduce_only());
+    }
+
+    #[test]
+    fn test_complementary_outcomes_net_and_concentration_is_limited() {
+        let limits = RiskLimits {
+            max_total_exposure: dec!(20),
+            max_market_exposure: Some(dec!(19)),
+            max_category_exposure: Some(dec!(25)),
+            ..Default::default()
+        };
+        let mut risk = RiskManager::new(limits);
+        let info = |slug: &str| {
+            MarketInfo::new(String::new(), String::new(), slug.to_string(), None)
+                .with_category(Some("politics".to_string()))
+        };
+        risk.set_markets(&HashMap::from([
+            ("yes".to_string(), info("election")),
+            ("no".to_string(), info("election")),
+            ("senate".to_string(), info("senate")),
+        ]));
+        let mut positions = PositionTracker::new();
+        positions.get_or_create("yes").apply_fill(&crate::position::Fill {
+            order_id: "1".to_string(),
+            token_id: "yes".to_string(),
+            is_buy: true,
+            price: dec!(0.60),
+            size: dec!(30),
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: None,
+        });
+        let buy = |token_id: &str, price, size| Signal::Buy {
+            token_id: token_id.to_string(),
+            price,
+            size,
+            urgency: crate::strategy::Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+
+        // Counted gross, 20 NO would take exposure to 25.6; they only complete sets
+        let hedge = buy("no", dec!(0.38), dec!(20));
+        assert!(matches!(risk.check_signal(&hedge, &positions), RiskCheckResult::Approved(_)));
+        risk.reserve_order_for("s", &hedge, &positions).unwrap();
+        assert_eq!(risk.pending_reservation_notional(), dec!(0));
+
+        // The 10 sets left to complete are free, the rest counts against the market
+        match risk.check_signal(&buy("no", dec!(0.38), dec!(30)), &positions) {
+            RiskCheckResult::Reduced(signal, reason) => {
+                let size = signal.order_terms().unwrap().3;
+                assert!(size > dec!(12.6) && size < dec!(12.7), "{}", size);
+                assert!(reason.contains("market election"), "{}", reason);
+            }
+            other => panic!("expected a reduced order, got {:?}", other),
+        }
+
+        // Another politics market fills up the category
+        match risk.check_signal(&buy("senate", dec!(0.40), dec!(20)), &positions) {
+            RiskCheckResult::Reduced(signal, reason) => {
+                assert_eq!(signal.order_terms().unwrap().3, dec!(17.5));
+                assert!(reason.contains("category politics"), "{}", reason);
+            }
+            other => panic!("expected a reduced order, got {:?}", other),
         }
     }
 }
@@ -0,0 +1,257 @@
+//! Journal of risk-manager decisions for live/backtest parity testing.
+//!
+//! With `PMENGINE_RISK_JOURNAL` set, every order signal's risk check is
+//! appended to that file as one JSON line holding the inputs the check saw
+//! (signal, position, exposure, market, limits) and its outcome. The pmstrat
+//! backtester writes the same schema, and `pmstrat parity <journal>` replays
+//! a live journal through the backtester's risk checks and reports every
+//! decision that differs.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::risk::{RiskCheckResult, RiskLimits};
+use crate::strategy::Signal;
+
+/// Outcome of a risk check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Approved,
+    Reduced,
+    Rejected,
+}
+
+/// Limits in force for a check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalLimits {
+    pub max_order_size: Decimal,
+    pub max_position_size: Decimal,
+    pub max_total_exposure: Decimal,
+    pub max_market_exposure: Option<Decimal>,
+    pub max_category_exposure: Option<Decimal>,
+    pub max_open_orders: usize,
+    pub max_open_orders_per_token: usize,
+    pub min_price: Decimal,
+    pub max_price: Decimal,
+    pub max_price_deviation: Option<Decimal>,
+    pub max_depth_ratio: Option<Decimal>,
+}
+
+impl From<&RiskLimits> for JournalLimits {
+    fn from(limits: &RiskLimits) -> Self {
+        Self {
+            max_order_size: limits.max_order_size,
+            max_position_size: limits.max_position_size,
+            max_total_exposure: limits.max_total_exposure,
+            max_market_exposure: limits.max_market_exposure,
+            max_category_exposure: limits.max_category_exposure,
+            max_open_orders: limits.max_open_orders,
+            max_open_orders_per_token: limits.max_open_orders_per_token,
+            min_price: limits.min_price,
+            max_price: limits.max_price,
+            max_price_deviation: limits.max_price_deviation,
+            max_depth_ratio: limits.max_depth_ratio,
+        }
+    }
+}
+
+/// One risk check: inputs and outcome (one journal line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskDecision {
+    pub timestamp: DateTime<Utc>,
+    /// `live` or `backtest`
+    pub source: String,
+    pub token_id: String,
+    /// `BUY` or `SELL`
+    pub side: String,
+    pub price: Decimal,
+    pub size: Decimal,
+    /// Position in the token when checked (None = no position tracked)
+    pub position_size: Option<Decimal>,
+    /// Reduce-only mode active
+    pub reduce_only: bool,
+    /// Open orders (and pending reservations) when checked
+    pub open_orders: usize,
+    /// Of those, orders in this token
+    pub token_open_orders: usize,
+    /// Mid (else last trade) of the token when checked (None = unknown)
+    pub reference_price: Option<Decimal>,
+    /// Book depth the order trades against when checked (None = unknown)
+    pub book_depth: Option<Decimal>,
+    /// Shares of the order completing sets with held complementary outcomes
+    pub hedge_size: Decimal,
+    /// Exposure of the token's market when checked
+    pub market_exposure: Decimal,
+    /// Exposure of the market's category when checked (None = no category)
+    pub category_exposure: Option<Decimal>,
+    /// Notional of all positions when checked, complementary outcomes netted
+    pub position_notional: Decimal,
+    /// Notional of tracked open orders when checked
+    pub open_order_notional: Decimal,
+    /// Circuit breaker active
+    pub halted: bool,
+    pub limits: JournalLimits,
+    pub decision: Decision,
+    /// Size let through (None = rejected)
+    pub approved_size: Option<Decimal>,
+    pub reason: Option<String>,
+}
+
+/// Inputs of a risk check, captured before it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckInputs {
+    pub position_size: Option<Decimal>,
+    pub reduce_only: bool,
+    pub open_orders: usize,
+    pub token_open_orders: usize,
+    pub reference_price: Option<Decimal>,
+    pub book_depth: Option<Decimal>,
+    pub hedge_size: Decimal,
+    pub market_exposure: Decimal,
+    pub category_exposure: Option<Decimal>,
+    pub position_notional: Decimal,
+    pub open_order_notional: Decimal,
+    pub halted: bool,
+    pub limits: JournalLimits,
+}
+
+impl RiskDecision {
+    /// Record for an order signal, or None for signals that are not orders.
+    pub fn new(
+        timestamp: DateTime<Utc>,
+        signal: &Signal,
+        inputs: CheckInputs,
+        result: &RiskCheckResult,
+    ) -> Option<Self> {
+        let (token_id, is_buy, price, size) = signal.order_terms()?;
+        let side = if is_buy { "BUY" } else { "SELL" };
+        let approved_size = |s: &Signal| s.order_terms().map(|(.., size)| size);
+        let (decision, approved_size, reason) = match result {
+            RiskCheckResult::Approved(s) => (Decision::Approved, approved_size(s), None),
+            RiskCheckResult::Reduced(s, reason) => (Decision::Reduced, approved_size(s), Some(reason.clone())),
+            RiskCheckResult::Rejected(reason) => (Decision::Rejected, None, Some(reason.clone())),
+        };
+
+        Some(Self {
+            timestamp,
+            source: "live".to_string(),
+            token_id: token_id.to_string(),
+            side: side.to_string(),
+            price,
+            size,
+            position_size: inputs.position_size,
+            reduce_only: inputs.reduce_only,
+            open_orders: inputs.open_orders,
+            token_open_orders: inputs.token_open_orders,
+            reference_price: inputs.reference_price,
+            book_depth: inputs.book_depth,
+            hedge_size: inputs.hedge_size,
+            market_exposure: inputs.market_exposure,
+            category_exposure: inputs.category_exposure,
+            position_notional: inputs.position_notional,
+            open_order_notional: inputs.open_order_notional,
+            halted: inputs.halted,
+            limits: inputs.limits,
+            decision,
+            approved_size,
+            reason,
+        })
+    }
+}
+
+/// Append-only JSONL journal of risk decisions.
+pub struct RiskJournal {
+    writer: BufWriter<File>,
+}
+
+impl RiskJournal {
+    /// Open (or create) a journal file for appending.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Append a decision. Lines are flushed immediately so a crash loses at
+    /// most the decision being written.
+    pub fn record(&mut self, decision: &RiskDecision) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, decision)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::PositionTracker;
+    use crate::risk::RiskManager;
+    use crate::strategy::Urgency;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_reduced_decision_is_journaled_with_inputs() {
+        let risk = RiskManager::new(RiskLimits::default());
+        let positions = PositionTracker::new();
+        let signal = Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.5),
+            size: dec!(100),
+            urgency: Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+
+        let inputs = risk.check_inputs(&signal, &positions);
+        let result = risk.check_signal(&signal, &positions);
+        let decision = RiskDecision::new(Utc::now(), &signal, inputs, &result).unwrap();
+        assert_eq!(decision.decision, Decision::Reduced);
+        assert_eq!(decision.approved_size, Some(dec!(50)));
+        assert_eq!(decision.position_size, None);
+        assert_eq!(decision.limits.max_order_size, dec!(25));
+
+        let dir = std::env::temp_dir().join(format!("pmengine-journal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("risk.jsonl");
+        let mut journal = RiskJournal::open(&path).unwrap();
+        journal.record(&decision).unwrap();
+        journal.record(&decision).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["decision"], "reduced");
+        assert_eq!(line["approved_size"], "50");
+        assert_eq!(serde_json::from_str::<RiskDecision>(lines[1]).unwrap(), decision);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_non_order_signals_are_not_journaled() {
+        let inputs = CheckInputs {
+            position_size: None,
+            reduce_only: false,
+            open_orders: 0,
+            token_open_orders: 0,
+            reference_price: None,
+            book_depth: None,
+            hedge_size: Decimal::ZERO,
+            market_exposure: Decimal::ZERO,
+            category_exposure: None,
+            position_notional: Decimal::ZERO,
+            open_order_notional: Decimal::ZERO,
+            halted: false,
+            limits: (&RiskLimits::default()).into(),
+        };
+        let result = RiskCheckResult::Approved(Signal::Hold);
+        assert!(RiskDecision::new(Utc::now(), &Signal::Hold, inputs, &result).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+//! Guarded decimal arithmetic for P&L, VWAP and exposure computations.
+//!
+//! `rust_decimal` operators panic on overflow and division by zero, and
+//! silently round (possibly to zero) once the 28 digits of precision are
+//! exhausted. The helpers here never panic: an overflowing result is clamped
+//! to the largest representable value of the right sign, a division by zero
+//! yields zero, and a product that underflows to zero is reported. Every
+//! downgrade logs a structured warning naming the computation and is counted
+//! (see [`downgrades`]) so it shows up next to the risk numbers it affected.
+
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Decimal places quotients are rounded to, leaving headroom for the
+/// multiplications that usually follow (e.g. `avg_entry_price * size`).
+pub const WORKING_SCALE: u32 = 16;
+
+static DOWNGRADES: AtomicU64 = AtomicU64::new(0);
+
+/// What went wrong in a guarded operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Downgrade {
+    /// Result exceeded the representable range and was clamped.
+    Overflow,
+    /// Non-zero operands produced zero (precision exhausted).
+    Underflow,
+    /// Division by zero; the result was replaced by zero.
+    DivisionByZero,
+}
+
+impl std::fmt::Display for Downgrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Downgrade::Overflow => write!(f, "overflow"),
+            Downgrade::Underflow => write!(f, "precision exhausted"),
+            Downgrade::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+/// Number of downgraded results since startup.
+pub fn downgrades() -> u64 {
+    DOWNGRADES.load(Ordering::Relaxed)
+}
+
+fn report(downgrade: Downgrade, op: &'static str, context: &str, lhs: Decimal, rhs: Decimal) {
+    DOWNGRADES.fetch_add(1, Ordering::Relaxed);
+    tracing::warn!(
+        %downgrade,
+        op,
+        context,
+        lhs = %lhs,
+        rhs = %rhs,
+        "Decimal arithmetic downgraded"
+    );
+}
+
+/// Largest representable value with the given sign.
+fn clamp(negative: bool) -> Decimal {
+    if negative {
+        Decimal::MIN
+    } else {
+        Decimal::MAX
+    }
+}
+
+/// `lhs + rhs`, clamped on overflow.
+pub fn add(lhs: Decimal, rhs: Decimal, context: &str) -> Decimal {
+    lhs.checked_add(rhs).unwrap_or_else(|| {
+        report(Downgrade::Overflow, "add", context, lhs, rhs);
+        clamp(lhs.is_sign_negative())
+    })
+}
+
+/// `lhs - rhs`, clamped on overflow.
+pub fn sub(lhs: Decimal, rhs: Decimal, context: &str) -> Decimal {
+    lhs.checked_sub(rhs).unwrap_or_else(|| {
+        report(Downgrade::Overflow, "sub", context, lhs, rhs);
+        clamp(lhs.is_sign_negative())
+    })
+}
+
+/// `lhs * rhs`, clamped on overflow; reports a product rounded to zero.
+pub fn mul(lhs: Decimal, rhs: Decimal, context: &str) -> Decimal {
+    match lhs.checked_mul(rhs) {
+        Some(product) => {
+            if product.is_zero() && !lhs.is_zero() && !rhs.is_zero() {
+                report(Downgrade::Underflow, "mul", context, lhs, rhs);
+            }
+            product
+        }
+        None => {
+            report(Downgrade::Overflow, "mul", context, lhs, rhs);
+            clamp(lhs.is_sign_negative() != rhs.is_sign_negative())
+        }
+    }
+}
+
+/// `lhs / rhs` rounded to [`WORKING_SCALE`]; zero on division by zero,
+/// clamped on overflow.
+pub fn div(lhs: Decimal, rhs: Decimal, context: &str) -> Decimal {
+    if rhs.is_zero() {
+        report(Downgrade::DivisionByZero, "div", context, lhs, rhs);
+        return Decimal::ZERO;
+    }
+    match lhs.checked_div(rhs) {
+        Some(quotient) => {
+            let quotient = quotient.round_dp(WORKING_SCALE);
+            if quotient.is_zero() && !lhs.is_zero() {
+                report(Downgrade::Underflow, "div", context, lhs, rhs);
+            }
+            quotient
+        }
+        None => {
+            report(Downgrade::Overflow, "div", context, lhs, rhs);
+            clamp(lhs.is_sign_negative() != rhs.is_sign_negative())
+        }
+    }
+}
+
+/// Sum of `values`, clamped on overflow.
+pub fn sum(values: impl IntoIterator<Item = Decimal>, context: &str) -> Decimal {
+    values.into_iter().fold(Decimal::ZERO, |acc, v| add(acc, v, context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_in_range_results_are_exact() {
+        assert_eq!(mul(dec!(0.55), dec!(100), "t"), dec!(55.00));
+        assert_eq!(add(dec!(1.5), dec!(-2), "t"), dec!(-0.5));
+        assert_eq!(div(dec!(10), dec!(4), "t"), dec!(2.5));
+        assert_eq!(div(dec!(1), dec!(3), "t"), dec!(0.3333333333333333));
+        assert_eq!(sum([dec!(1), dec!(2), dec!(3)], "t"), dec!(6));
+    }
+
+    #[test]
+    fn test_downgrades_clamp_and_count() {
+        let before = downgrades();
+        assert_eq!(mul(Decimal::MAX, dec!(2), "t"), Decimal::MAX);
+        assert_eq!(mul(Decimal::MAX, dec!(-2), "t"), Decimal::MIN);
+        assert_eq!(add(Decimal::MIN, dec!(-1), "t"), Decimal::MIN);
+        assert_eq!(sub(Decimal::MAX, dec!(-1), "t"), Decimal::MAX);
+        assert_eq!(div(dec!(5), Decimal::ZERO, "t"), Decimal::ZERO);
+        assert_eq!(div(Decimal::MAX, dec!(0.1), "t"), Decimal::MAX);
+        assert!(mul(dec!(0.000000000000001), dec!(0.000000000000001), "t").is_zero());
+        assert!(downgrades() - before >= 7);
+    }
+}
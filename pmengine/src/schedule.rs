@@ -0,0 +1,130 @@
+//! Trading windows: when a strategy may run.
+//!
+//! A strategy with a schedule only runs inside one of its windows, each a
+//! range of weekdays and a time of day in a timezone:
+//!
+//! ```text
+//! PMENGINE_STRATEGY_SCHEDULE="market_maker=mon-fri 08:00-22:00 America/New_York;sure_bets=* 22:00-02:00"
+//! ```
+//!
+//! Outside its windows the strategy's signals are suppressed and its orders
+//! cancelled (see [`StrategyRuntime::update_schedules`](crate::StrategyRuntime)).
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// A weekly trading window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    /// First and last weekday (None = every day); `fri-mon` wraps the week
+    days: Option<(Weekday, Weekday)>,
+    start: NaiveTime,
+    /// End of the window (exclusive); before `start` it falls on the next
+    /// day, equal to `start` the window spans the whole day
+    end: NaiveTime,
+    tz: Tz,
+}
+
+impl Window {
+    /// Parse `days start-end [timezone]`, e.g. `mon-fri 09:30-16:00 America/New_York`,
+    /// `sat 00:00-00:00` or `* 22:00-02:00` (UTC unless a timezone is given).
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.split_whitespace();
+        let days = match parts.next()? {
+            "*" => None,
+            days => {
+                let (first, last) = days.split_once('-').unwrap_or((days, days));
+                Some((first.parse().ok()?, last.parse().ok()?))
+            }
+        };
+        let (start, end) = parts.next()?.split_once('-')?;
+        let start = NaiveTime::parse_from_str(start, "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(end, "%H:%M").ok()?;
+        let tz = match parts.next() {
+            Some(tz) => tz.parse().ok()?,
+            None => Tz::UTC,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { days, start, end, tz })
+    }
+
+    /// Whether `now` falls in the window. A window past midnight belongs to
+    /// the day it starts on.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.tz);
+        let (day, time) = (local.weekday(), local.time());
+        if self.start == self.end {
+            self.on(day)
+        } else if self.start < self.end {
+            self.on(day) && time >= self.start && time < self.end
+        } else {
+            (self.on(day) && time >= self.start) || (self.on(day.pred()) && time < self.end)
+        }
+    }
+
+    fn on(&self, day: Weekday) -> bool {
+        let Some((first, last)) = self.days else {
+            return true;
+        };
+        let (first, last) = (first.num_days_from_monday(), last.num_days_from_monday());
+        let day = day.num_days_from_monday();
+        if first <= last {
+            (first..=last).contains(&day)
+        } else {
+            day >= first || day <= last
+        }
+    }
+}
+
+/// The windows a strategy may run in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    windows: Vec<Window>,
+}
+
+impl Schedule {
+    pub fn new(windows: Vec<Window>) -> Self {
+        Self { windows }
+    }
+
+    /// Whether `now` falls in any window.
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.windows.iter().any(|w| w.contains(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_windows_by_day_time_and_timezone() {
+        // 2024-06-07 is a Friday; New York is UTC-4 in June
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 6, d, h, m, 0).unwrap();
+
+        let session = Window::parse("mon-fri 09:30-16:00 America/New_York").unwrap();
+        assert!(session.contains(at(7, 13, 30)));
+        assert!(!session.contains(at(7, 13, 29)));
+        assert!(!session.contains(at(7, 20, 0)));
+        assert!(!session.contains(at(8, 14, 0)));
+
+        let overnight = Window::parse("fri 22:00-02:00").unwrap();
+        assert!(overnight.contains(at(7, 23, 0)));
+        assert!(overnight.contains(at(8, 1, 59)));
+        assert!(!overnight.contains(at(8, 2, 0)));
+        assert!(!overnight.contains(at(7, 1, 0)));
+
+        let weekend = Schedule::new(vec![Window::parse("sat-sun 00:00-00:00").unwrap()]);
+        assert!(weekend.is_open(at(9, 12, 0)));
+        assert!(!weekend.is_open(at(10, 0, 0)));
+        assert!(Window::parse("fri-mon 00:00-00:00").unwrap().contains(at(10, 12, 0)));
+
+        assert!(Window::parse("* 09:00-17:00 Mars/Olympus").is_none());
+        assert!(Window::parse("weekdays 09:00-17:00").is_none());
+        assert!(Window::parse("mon 9-17").is_none());
+        assert!(Window::parse("mon 09:00-17:00 UTC extra").is_none());
+    }
+}
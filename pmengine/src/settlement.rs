@@ -0,0 +1,195 @@
+//! Settlement of positions in resolved markets.
+//!
+//! Once a market resolves its tokens stop trading: each winning token pays
+//! 1 USDC, but only after it is redeemed on-chain through the CTF contract
+//! (the NegRisk adapter for negRisk markets), and losing tokens pay nothing.
+//! Every `PMENGINE_SETTLE_SECS` (default 300, 0 = disabled) the engine looks
+//! up the Gamma market of each open position and, once it is closed with one
+//! outcome priced at 1 and the rest at 0:
+//!
+//! - redeems winning tokens, sending the transaction from the signer through
+//!   `PMENGINE_RPC_URL` (it needs POL for gas); in dry-run, or when a proxy
+//!   or Safe wallet holds the tokens, the position is flagged for manual
+//!   redemption instead;
+//! - closes the position at its payout with [`settlement_fills`], moving its
+//!   P&L from unrealized to realized for the engine and for each strategy.
+//!
+//! A failed redemption leaves the position open for the next pass. Markets
+//! resolved 50-50 are not settled automatically. Reconciliation leaves
+//! positions the Data API reports as redeemable to settlement.
+
+use rust_decimal::Decimal;
+
+use crate::attribution::{StrategyLedger, UNATTRIBUTED};
+use crate::gamma::GammaMarketDetail;
+use crate::position::Fill;
+
+/// How a held token's market resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    pub token_id: String,
+    /// CTF condition ID of the market
+    pub condition_id: String,
+    /// USDC paid per token (1 for the winning outcome, 0 otherwise)
+    pub payout: Decimal,
+    /// Index of the token's outcome in the market
+    pub outcome_index: usize,
+    /// Whether the market is redeemed through the NegRisk adapter
+    pub neg_risk: bool,
+    /// Market question
+    pub question: String,
+}
+
+impl Resolution {
+    /// The resolution of `token_id`'s market (None while it is unresolved).
+    pub fn of(detail: &GammaMarketDetail, token_id: &str) -> Option<Self> {
+        let market = &detail.market;
+        let outcome_index = market.clob_token_ids.iter().position(|t| t == token_id)?;
+        let prices = &market.outcome_prices;
+        let decided = prices.len() == market.clob_token_ids.len()
+            && prices.iter().all(|p| p.is_zero() || *p == Decimal::ONE)
+            && prices.iter().filter(|p| **p == Decimal::ONE).count() == 1;
+        if !market.closed || !decided {
+            return None;
+        }
+        Some(Self {
+            token_id: token_id.to_string(),
+            condition_id: detail.detail.condition_id.clone()?,
+            payout: prices[outcome_index],
+            outcome_index,
+            neg_risk: market.neg_risk,
+            question: market.question.clone(),
+        })
+    }
+
+    /// Amounts per outcome to redeem `size` tokens through the NegRisk adapter.
+    pub fn neg_risk_amounts(&self, size: Decimal) -> Vec<Decimal> {
+        let mut amounts = vec![Decimal::ZERO; 2.max(self.outcome_index + 1)];
+        amounts[self.outcome_index] = size;
+        amounts
+    }
+}
+
+/// Fills closing a position of `size` at the resolution payout: one per
+/// strategy holding the token in `ledger`, and an untagged fill for whatever
+/// of the engine's position the strategies don't account for.
+pub fn settlement_fills(resolution: &Resolution, size: Decimal, ledger: &StrategyLedger) -> Vec<Fill> {
+    let order_id = format!("settlement:{}", resolution.condition_id);
+    let fill = |strategy_id: Option<&str>, held: Decimal| Fill {
+        order_id: order_id.clone(),
+        token_id: resolution.token_id.clone(),
+        is_buy: held < Decimal::ZERO,
+        price: resolution.payout,
+        size: held.abs(),
+        timestamp: chrono::Utc::now(),
+        fee: Decimal::ZERO,
+        strategy_id: strategy_id.map(String::from),
+    };
+
+    let holders = ledger.holders(&resolution.token_id);
+    let attributed: Decimal = holders.iter().map(|(_, held)| *held).sum();
+    let mut fills: Vec<Fill> = holders
+        .into_iter()
+        .map(|(strategy_id, held)| fill(Some(strategy_id).filter(|id| *id != UNATTRIBUTED), held))
+        .collect();
+    let rest = size - attributed;
+    if !rest.is_zero() {
+        fills.push(fill(None, rest));
+    }
+    fills
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamma::{GammaMarket, MarketDetail};
+    use crate::position::PositionTracker;
+    use rust_decimal_macros::dec;
+
+    fn detail(closed: bool, prices: Vec<Decimal>) -> GammaMarketDetail {
+        GammaMarketDetail {
+            market: GammaMarket {
+                question: "Will it rain?".to_string(),
+                slug: "will-it-rain".to_string(),
+                end_date: None,
+                outcomes: vec!["Yes".to_string(), "No".to_string()],
+                outcome_prices: prices,
+                clob_token_ids: vec!["yes".to_string(), "no".to_string()],
+                active: false,
+                closed,
+                liquidity: None,
+                volume: None,
+                category: None,
+                event_slug: None,
+                neg_risk: true,
+                neg_risk_market_id: None,
+                question_id: None,
+            },
+            detail: MarketDetail {
+                condition_id: Some("0xabc".to_string()),
+                volume: None,
+                volume_24hr: None,
+                spread: None,
+                order_min_size: None,
+                tick_size: None,
+                uma_bond: None,
+                uma_reward: None,
+                resolution_source: None,
+                fetched_at: chrono::Utc::now(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_resolution_needs_a_closed_decided_market() {
+        let resolved = detail(true, vec![dec!(0), dec!(1)]);
+        let no = Resolution::of(&resolved, "no").unwrap();
+        assert_eq!((no.payout, no.outcome_index, no.neg_risk), (dec!(1), 1, true));
+        assert_eq!(no.neg_risk_amounts(dec!(7)), vec![dec!(0), dec!(7)]);
+        assert_eq!(Resolution::of(&resolved, "yes").unwrap().payout, dec!(0));
+        assert!(Resolution::of(&resolved, "other").is_none());
+
+        assert!(Resolution::of(&detail(false, vec![dec!(0), dec!(1)]), "no").is_none());
+        assert!(Resolution::of(&detail(true, vec![dec!(0.02), dec!(0.98)]), "no").is_none());
+        assert!(Resolution::of(&detail(true, vec![dec!(0.5), dec!(0.5)]), "no").is_none());
+    }
+
+    #[test]
+    fn test_settlement_realizes_pnl_per_strategy() {
+        let buy = |strategy_id: Option<&str>, size: Decimal| Fill {
+            order_id: "o".to_string(),
+            token_id: "no".to_string(),
+            is_buy: true,
+            price: dec!(0.90),
+            size,
+            timestamp: chrono::Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: strategy_id.map(String::from),
+        };
+        let mut positions = PositionTracker::new();
+        let mut ledger = StrategyLedger::new();
+        for fill in [buy(Some("sure_bets"), dec!(10)), buy(None, dec!(2))] {
+            positions.apply_fill(&fill);
+            ledger.apply_fill(&fill);
+        }
+        // Shares the ledger doesn't know about (e.g. corrected by reconciliation)
+        positions.get_or_create("no").size += dec!(3);
+
+        let resolution = Resolution::of(&detail(true, vec![dec!(0), dec!(1)]), "no").unwrap();
+        let fills = settlement_fills(&resolution, dec!(15), &ledger);
+        assert_eq!(fills.len(), 3);
+        assert_eq!(fills[0].strategy_id.as_deref(), Some("sure_bets"));
+        assert!(fills.iter().all(|f| !f.is_buy && f.price == dec!(1) && f.order_id == "settlement:0xabc"));
+        for fill in &fills {
+            positions.apply_fill(fill);
+            ledger.apply_fill(fill);
+        }
+
+        let position = positions.get("no").unwrap();
+        assert!(position.size.is_zero());
+        assert_eq!(position.realized_pnl, dec!(1.5));
+        let report = ledger.report();
+        assert_eq!(report[0].strategy_id, "sure_bets");
+        assert_eq!((report[0].realized_pnl, report[0].notional), (dec!(1.0), dec!(0)));
+    }
+}
@@ -0,0 +1,151 @@
+//! Bankroll management: Kelly-criterion position sizing.
+//!
+//! An outcome token bought at `price` pays $1 if it wins. With an estimated
+//! win probability `p`, the stake that maximizes long-run growth (the Kelly
+//! criterion) is the fraction `(p - price) / (1 - price)` of the bankroll, and
+//! nothing at all without an edge (`p <= price`). Full Kelly is only optimal
+//! when `p` is exact: an overestimated edge makes it ruinous, so stakes are
+//! scaled down by a fraction (`PMENGINE_KELLY_FRACTION`, a quarter by
+//! default).
+//!
+//! The bankroll is the equity strategies see in their context: the USDC
+//! balance (`PMENGINE_BANKROLL` caps it) plus the marked value of what is
+//! held, since Kelly targets are compared with the size already held. In
+//! dry-run the balance is a paper one, starting at `PMENGINE_BANKROLL` or
+//! [`PAPER_BANKROLL`]. Strategies size through
+//! [`StrategyContext::kelly_size`](crate::StrategyContext::kelly_size).
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::position::PositionTracker;
+use crate::safe_math::{add, div, mul, sub};
+
+/// Fraction of the full Kelly stake used by default.
+pub const DEFAULT_KELLY_FRACTION: Decimal = dec!(0.25);
+
+/// USDC a dry run starts with when `PMENGINE_BANKROLL` is unset.
+pub const PAPER_BANKROLL: Decimal = dec!(1000);
+
+/// USDC balance strategies see. Live, the account `balance` as last fetched,
+/// capped by `cap` (which stands in for it until a fetch succeeds). In
+/// dry-run, a paper balance starting at `cap` (or [`PAPER_BANKROLL`]), less
+/// what the paper positions cost and plus what they realized.
+pub fn balance(balance: Option<Decimal>, cap: Option<Decimal>, dry_run: bool, positions: &PositionTracker) -> Decimal {
+    if dry_run {
+        let start = cap.unwrap_or(PAPER_BANKROLL);
+        let pnl = add(positions.total_realized_pnl(), positions.total_unrealized_pnl(), "paper P&L");
+        let equity = add(start, pnl, "paper equity");
+        return sub(equity, positions.total_notional(), "paper balance").max(Decimal::ZERO);
+    }
+    match (balance, cap) {
+        (Some(balance), Some(cap)) => balance.min(cap),
+        (Some(balance), None) => balance,
+        (None, cap) => cap.unwrap_or_default(),
+    }
+}
+
+/// Fraction of the bankroll full Kelly stakes on a token bought at `price`
+/// that wins with `probability` (zero without an edge).
+pub fn kelly_fraction(probability: Decimal, price: Decimal) -> Decimal {
+    if price <= Decimal::ZERO || price >= Decimal::ONE || probability <= price {
+        return Decimal::ZERO;
+    }
+    let edge = sub(probability.min(Decimal::ONE), price, "kelly edge");
+    div(edge, sub(Decimal::ONE, price, "kelly payout"), "kelly fraction")
+}
+
+/// USDC to stake: the Kelly fraction of `bankroll`, scaled by `fraction`.
+pub fn kelly_stake(probability: Decimal, price: Decimal, bankroll: Decimal, fraction: Decimal) -> Decimal {
+    let fraction = fraction.clamp(Decimal::ZERO, Decimal::ONE);
+    let scaled = mul(kelly_fraction(probability, price), fraction, "scaled kelly fraction");
+    mul(scaled, bankroll.max(Decimal::ZERO), "kelly stake")
+}
+
+/// Shares to hold: [`kelly_stake`] at `price`.
+pub fn kelly_size(probability: Decimal, price: Decimal, bankroll: Decimal, fraction: Decimal) -> Decimal {
+    div(kelly_stake(probability, price, bankroll, fraction), price, "kelly size")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use crate::position::Fill;
+    use crate::strategies::SureBets;
+    use crate::strategy::{MarketInfo, Signal, Strategy, StrategyContext};
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_kelly_sizes_by_edge() {
+        // 99.5% to win at 0.96: stake 87.5% of the bankroll at full Kelly
+        assert_eq!(kelly_fraction(dec!(0.995), dec!(0.96)), dec!(0.875));
+        assert_eq!(kelly_stake(dec!(0.995), dec!(0.96), dec!(1000), dec!(0.25)), dec!(218.75));
+        assert_eq!(kelly_size(dec!(0.7), dec!(0.5), dec!(100), dec!(1)), dec!(80));
+
+        // A smaller edge stakes less
+        assert!(kelly_stake(dec!(0.995), dec!(0.98), dec!(1000), dec!(0.25)) < dec!(218.75));
+
+        // No edge, no bet
+        assert_eq!(kelly_fraction(dec!(0.5), dec!(0.5)), dec!(0));
+        assert_eq!(kelly_size(dec!(0.4), dec!(0.5), dec!(100), dec!(1)), dec!(0));
+        assert_eq!(kelly_size(dec!(0.9), dec!(0), dec!(100), dec!(1)), dec!(0));
+        assert_eq!(kelly_size(dec!(0.9), dec!(0.5), dec!(-100), dec!(1)), dec!(0));
+    }
+
+    #[test]
+    fn test_balance_live_and_paper() {
+        let mut positions = PositionTracker::new();
+        assert_eq!(balance(Some(dec!(500)), Some(dec!(200)), false, &positions), dec!(200));
+        assert_eq!(balance(Some(dec!(500)), None, false, &positions), dec!(500));
+        assert_eq!(balance(None, None, false, &positions), dec!(0));
+
+        // Paper money, less what the paper positions cost
+        assert_eq!(balance(None, None, true, &positions), PAPER_BANKROLL);
+        assert_eq!(balance(None, Some(dec!(300)), true, &positions), dec!(300));
+        positions.apply_fill(&Fill {
+            order_id: "o1".to_string(),
+            token_id: "t".to_string(),
+            is_buy: true,
+            price: dec!(0.5),
+            size: dec!(100),
+            timestamp: Utc::now(),
+            fee: dec!(0),
+            strategy_id: None,
+        });
+        positions.get_or_create("t").update_price(dec!(0.6));
+        assert_eq!(balance(None, None, true, &positions), dec!(950));
+    }
+
+    #[test]
+    fn test_dry_run_sure_bets_sizes_orders() {
+        let mut book = OrderBook::new("t".to_string());
+        book.bids = vec![Level { price: dec!(0.95), size: dec!(500) }];
+        book.asks = vec![Level { price: dec!(0.96), size: dec!(500) }];
+        let market = MarketInfo::with_liquidity(
+            "Will it happen?".to_string(),
+            "Yes".to_string(),
+            "will-it-happen".to_string(),
+            Some(Utc::now() + Duration::hours(12)),
+            Some(5_000.0),
+        );
+        let positions = PositionTracker::new();
+        let ctx = StrategyContext {
+            usdc_balance: balance(None, None, true, &positions),
+            positions,
+            ..StrategyContext::for_test(
+                HashMap::from([("t".to_string(), Arc::new(book))]),
+                HashMap::from([("t".to_string(), market)]),
+            )
+        };
+
+        let signals = SureBets::new().on_tick(&ctx);
+        assert!(
+            matches!(signals.as_slice(), [Signal::Buy { size, .. }] if *size > dec!(0)),
+            "no order: {:?}",
+            signals
+        );
+    }
+}
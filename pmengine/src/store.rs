@@ -0,0 +1,253 @@
+//! Persistent store for positions, open orders and fills.
+//!
+//! With `PMENGINE_STATE_DIR` set, the engine keeps its trading state in that
+//! directory so a restart (or crash) doesn't leave it blind to its exposure:
+//!
+//! - `state.json`: positions and open orders, rewritten (via a temporary file
+//!   and rename, so it is never half-written) whenever they change.
+//! - `fills.jsonl`: every fill, appended as one JSON line.
+//! - `reports.jsonl`: daily P&L reports (see [`crate::report`]).
+//!
+//! On startup the engine restores `state.json` and reconciles it with the
+//! exchange (see [`crate::reconcile`]) before trading.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{OpenOrder, Side};
+use crate::order::{Order, OrderStatus};
+use crate::position::{Fill, Position};
+
+const STATE_FILE: &str = "state.json";
+const FILLS_FILE: &str = "fills.jsonl";
+
+/// An open order as persisted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredOrder {
+    pub id: String,
+    pub token_id: String,
+    pub is_buy: bool,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub filled_size: Decimal,
+    pub created_at: DateTime<Utc>,
+    /// Strategy that placed the order
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+}
+
+impl StoredOrder {
+    /// Unfilled size.
+    pub fn remaining(&self) -> Decimal {
+        self.size - self.filled_size
+    }
+
+    /// Tracked order to hand back to the order manager.
+    pub fn into_order(self) -> Order {
+        let status = if self.filled_size.is_zero() {
+            OrderStatus::Open
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        Order {
+            id: self.id,
+            token_id: self.token_id,
+            is_buy: self.is_buy,
+            price: self.price,
+            size: self.size,
+            filled_size: self.filled_size,
+            status,
+            created_at: self.created_at,
+            ack_latency: None,
+            over_budget: false,
+            strategy_id: self.strategy_id,
+        }
+    }
+}
+
+impl From<&Order> for StoredOrder {
+    fn from(order: &Order) -> Self {
+        Self {
+            id: order.id.clone(),
+            token_id: order.token_id.clone(),
+            is_buy: order.is_buy,
+            price: order.price,
+            size: order.size,
+            filled_size: order.filled_size,
+            created_at: order.created_at,
+            strategy_id: order.strategy_id.clone(),
+        }
+    }
+}
+
+impl From<&OpenOrder> for StoredOrder {
+    fn from(order: &OpenOrder) -> Self {
+        Self {
+            id: order.order_id.clone(),
+            token_id: order.token_id.clone(),
+            is_buy: order.side == Side::Buy,
+            price: order.price,
+            size: order.original_size,
+            filled_size: order.size_matched,
+            created_at: order.created_at,
+            strategy_id: None,
+        }
+    }
+}
+
+/// Positions and open orders (`state.json`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EngineState {
+    pub positions: Vec<Position>,
+    pub open_orders: Vec<StoredOrder>,
+}
+
+/// Directory holding the persisted state.
+pub struct StateStore {
+    dir: PathBuf,
+    fills: BufWriter<File>,
+    /// Last state written, to skip unchanged rewrites
+    last_saved: Option<String>,
+}
+
+impl StateStore {
+    /// Open (or create) a state directory.
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let fills = OpenOptions::new().create(true).append(true).open(dir.join(FILLS_FILE))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            fills: BufWriter::new(fills),
+            last_saved: None,
+        })
+    }
+
+    /// Load the saved state (None if nothing was saved yet).
+    pub fn load(&self) -> std::io::Result<Option<EngineState>> {
+        let path = self.dir.join(STATE_FILE);
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Save the state if it changed since the last save. Returns whether it was written.
+    pub fn save(&mut self, state: &EngineState) -> std::io::Result<bool> {
+        let json = serde_json::to_string_pretty(state)?;
+        if self.last_saved.as_deref() == Some(json.as_str()) {
+            return Ok(false);
+        }
+        let tmp = self.dir.join(format!("{}.tmp", STATE_FILE));
+        {
+            let mut file = File::create(&tmp)?;
+            file.write_all(json.as_bytes())?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp, self.dir.join(STATE_FILE))?;
+        self.last_saved = Some(json);
+        Ok(true)
+    }
+
+    /// Append a fill. Lines are flushed immediately so a crash loses at most
+    /// the fill being written.
+    pub fn record_fill(&mut self, fill: &Fill) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.fills, fill)?;
+        self.fills.write_all(b"\n")?;
+        self.fills.flush()
+    }
+
+    /// Every recorded fill, oldest first.
+    pub fn fills(&self) -> std::io::Result<Vec<Fill>> {
+        load_fills(&self.dir)
+    }
+
+    /// The state directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Every fill recorded in a state directory, oldest first.
+pub fn load_fills(dir: &Path) -> std::io::Result<Vec<Fill>> {
+    let file = File::open(dir.join(FILLS_FILE))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|line| {
+            serde_json::from_str(&line?).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn stored(id: &str, token_id: &str, filled: Decimal) -> StoredOrder {
+        StoredOrder {
+            id: id.to_string(),
+            token_id: token_id.to_string(),
+            is_buy: true,
+            price: dec!(0.5),
+            size: dec!(10),
+            filled_size: filled,
+            created_at: Utc::now(),
+            strategy_id: Some("sure_bets".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_state_round_trips_and_fills_append() {
+        let dir = std::env::temp_dir().join(format!("pmengine-store-{}", std::process::id()));
+        let mut position = Position::new("token".to_string());
+        position.size = dec!(5);
+        position.avg_entry_price = dec!(0.4);
+        let state = EngineState {
+            positions: vec![position],
+            open_orders: vec![stored("o1", "token", dec!(2))],
+        };
+
+        let mut store = StateStore::open(&dir).unwrap();
+        assert_eq!(store.load().unwrap(), None);
+        assert!(store.save(&state).unwrap());
+        assert!(!store.save(&state).unwrap(), "unchanged state is not rewritten");
+
+        let fill = Fill {
+            order_id: "o1".to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price: dec!(0.5),
+            size: dec!(2),
+            timestamp: Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: Some("sure_bets".to_string()),
+        };
+        store.record_fill(&fill).unwrap();
+        store.record_fill(&fill).unwrap();
+
+        // A restarted engine sees the same state and fills
+        let reopened = StateStore::open(&dir).unwrap();
+        let loaded = reopened.load().unwrap().unwrap();
+        assert_eq!(loaded.open_orders, state.open_orders);
+        assert_eq!(loaded.positions[0].size, dec!(5));
+        let fills = reopened.fills().unwrap();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].strategy_id.as_deref(), Some("sure_bets"));
+
+        let order = loaded.open_orders[0].clone().into_order();
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.remaining(), dec!(8));
+        assert_eq!(order.strategy_id.as_deref(), Some("sure_bets"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,309 @@
+//! Basket arbitrage on negRisk (multi-outcome) events.
+//!
+//! Exactly one outcome of a negRisk event resolves YES, so one YES share of
+//! every outcome pays $1. When the asks sum to less than $1 (minus a safety
+//! margin) the strategy buys the full basket; when a held basket can be sold
+//! at bids summing to more than $1 it sells it back.
+
+use crate::basket::Basket;
+use crate::position::Fill;
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Minimum edge per $1 basket before trading (covers fees and leg risk).
+const MIN_EDGE: Decimal = dec!(0.02);
+/// Fewest outcomes an event needs to be traded as a basket.
+const MIN_LEGS: usize = 2;
+/// Most outcomes to trade (more legs = more leg risk).
+const MAX_LEGS: usize = 12;
+/// Largest basket to trade per tick (shares per leg).
+const MAX_BASKET_SIZE: Decimal = dec!(20);
+/// Most complete baskets to hold per event.
+const MAX_BASKETS_HELD: Decimal = dec!(100);
+/// Smallest order worth sending (shares per leg).
+const MIN_ORDER_SIZE: Decimal = dec!(5);
+
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasketArbParams {
+    pub min_edge: Decimal,
+    pub min_legs: usize,
+    pub max_legs: usize,
+    pub max_basket_size: Decimal,
+    pub max_baskets_held: Decimal,
+    pub min_order_size: Decimal,
+}
+
+impl Default for BasketArbParams {
+    fn default() -> Self {
+        Self {
+            min_edge: MIN_EDGE,
+            min_legs: MIN_LEGS,
+            max_legs: MAX_LEGS,
+            max_basket_size: MAX_BASKET_SIZE,
+            max_baskets_held: MAX_BASKETS_HELD,
+            min_order_size: MIN_ORDER_SIZE,
+        }
+    }
+}
+
+pub struct BasketArb {
+    id: String,
+    tokens: Vec<String>,
+    params: BasketArbParams,
+}
+
+impl BasketArb {
+    pub fn new() -> Self {
+        Self {
+            id: "basket_arb".to_string(),
+            tokens: vec![],
+            params: BasketArbParams::default(),
+        }
+    }
+
+    pub fn params(&self) -> &BasketArbParams {
+        &self.params
+    }
+
+    /// Number of complete baskets held (min position across legs).
+    fn baskets_held(ctx: &StrategyContext, basket: &Basket) -> Decimal {
+        basket
+            .legs
+            .iter()
+            .map(|l| ctx.positions.get(&l.token_id).map(|p| p.size).unwrap_or_default())
+            .min()
+            .unwrap_or_default()
+    }
+
+    fn basket_signals(&self, ctx: &StrategyContext, basket: &Basket) -> Vec<Signal> {
+        let p = &self.params;
+        if !basket.neg_risk || basket.len() < p.min_legs || basket.len() > p.max_legs {
+            return vec![];
+        }
+
+        let held = Self::baskets_held(ctx, basket);
+
+        // Unwind a held basket when the bids pay more than the $1 payout
+        if let Some(edge) = basket.sell_edge() {
+            let size = held.min(basket.max_sell_size()).min(p.max_basket_size);
+            if edge >= p.min_edge && size >= p.min_order_size {
+                tracing::info!(
+                    event = basket.event_slug.as_str(),
+                    legs = basket.len(),
+                    edge = %edge,
+                    size = %size,
+                    "Selling basket above $1"
+                );
+                return basket
+                    .legs
+                    .iter()
+                    .filter_map(|l| {
+                        l.bid.map(|price| Signal::Sell {
+                            token_id: l.token_id.clone(),
+                            price,
+                            size,
+                            urgency: Urgency::High,
+                            post_only: false,
+                            expires_at: None,
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        // Buy the basket when the asks cost less than the $1 payout
+        if let Some(edge) = basket.buy_edge() {
+            let room = (p.max_baskets_held - held).max(Decimal::ZERO);
+            let size = basket.max_buy_size().min(p.max_basket_size).min(room);
+            if edge >= p.min_edge && size >= p.min_order_size {
+                tracing::info!(
+                    event = basket.event_slug.as_str(),
+                    legs = basket.len(),
+                    edge = %edge,
+                    size = %size,
+                    "Buying basket below $1"
+                );
+                return basket
+                    .legs
+                    .iter()
+                    .filter_map(|l| {
+                        l.ask.map(|price| Signal::Buy {
+                            token_id: l.token_id.clone(),
+                            price,
+                            size,
+                            urgency: Urgency::High,
+                            post_only: false,
+                            expires_at: None,
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        vec![]
+    }
+}
+
+impl Default for BasketArb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for BasketArb {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let signals: Vec<Signal> = ctx
+            .baskets()
+            .iter()
+            .flat_map(|basket| self.basket_signals(ctx, basket))
+            .collect();
+
+        if signals.is_empty() {
+            vec![Signal::Hold]
+        } else {
+            signals
+        }
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "MAX_BASKETS_HELD",
+            "MAX_BASKET_SIZE",
+            "MAX_LEGS",
+            "MIN_EDGE",
+            "MIN_LEGS",
+            "MIN_ORDER_SIZE",
+        ])?;
+        let p = &mut self.params;
+        p.min_edge = params.get("MIN_EDGE")?.unwrap_or(p.min_edge);
+        p.min_legs = params.get("MIN_LEGS")?.unwrap_or(p.min_legs);
+        p.max_legs = params.get("MAX_LEGS")?.unwrap_or(p.max_legs);
+        p.max_basket_size = params.get("MAX_BASKET_SIZE")?.unwrap_or(p.max_basket_size);
+        p.max_baskets_held = params.get("MAX_BASKETS_HELD")?.unwrap_or(p.max_baskets_held);
+        p.min_order_size = params.get("MIN_ORDER_SIZE")?.unwrap_or(p.min_order_size);
+        Ok(())
+    }
+
+    fn on_fill(&mut self, _fill: &Fill) {}
+    fn on_shutdown(&mut self) {}
+
+    fn needs_baskets(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use crate::position::PositionTracker;
+    use crate::strategy::MarketInfo;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn make_context(quotes: &[(&str, Decimal, Decimal)], held: Decimal) -> StrategyContext {
+        let mut order_books = HashMap::new();
+        let mut markets = HashMap::new();
+        let mut positions = PositionTracker::new();
+
+        for (token_id, bid, ask) in quotes {
+            let mut book = OrderBook::new(token_id.to_string());
+            book.bids = vec![Level { price: *bid, size: dec!(50) }];
+            book.asks = vec![Level { price: *ask, size: dec!(50) }];
+            order_books.insert(token_id.to_string(), Arc::new(book));
+            markets.insert(
+                token_id.to_string(),
+                MarketInfo::new(token_id.to_string(), "Yes".into(), token_id.to_string(), None)
+                    .with_event("event".to_string(), true),
+            );
+            if held > Decimal::ZERO {
+                positions.get_or_create(token_id).size = held;
+            }
+        }
+
+        StrategyContext {
+            positions,
+            ..StrategyContext::for_test(order_books, markets)
+        }
+    }
+
+    #[test]
+    fn test_buys_underpriced_basket() {
+        let ctx = make_context(
+            &[("a", dec!(0.45), dec!(0.47)), ("b", dec!(0.45), dec!(0.47))],
+            dec!(0),
+        );
+        let signals = BasketArb::new().on_tick(&ctx);
+
+        assert_eq!(signals.len(), 2);
+        assert!(signals
+            .iter()
+            .all(|s| matches!(s, Signal::Buy { size, .. } if *size == MAX_BASKET_SIZE)));
+    }
+
+    #[test]
+    fn test_holds_fairly_priced_basket() {
+        let ctx = make_context(
+            &[("a", dec!(0.48), dec!(0.50)), ("b", dec!(0.48), dec!(0.50))],
+            dec!(0),
+        );
+        let signals = BasketArb::new().on_tick(&ctx);
+        assert!(matches!(signals.as_slice(), [Signal::Hold]));
+    }
+
+    #[test]
+    fn test_sells_held_basket_above_one() {
+        let ctx = make_context(
+            &[("a", dec!(0.53), dec!(0.55)), ("b", dec!(0.53), dec!(0.55))],
+            dec!(10),
+        );
+        let signals = BasketArb::new().on_tick(&ctx);
+
+        assert_eq!(signals.len(), 2);
+        assert!(signals
+            .iter()
+            .all(|s| matches!(s, Signal::Sell { size, .. } if *size == dec!(10))));
+    }
+
+    #[test]
+    fn test_configure_overrides_params() {
+        let ctx = make_context(
+            &[("a", dec!(0.45), dec!(0.47)), ("b", dec!(0.45), dec!(0.47))],
+            dec!(0),
+        );
+        let mut params = StrategyParams::new();
+        params.insert("max_basket_size", "8");
+        params.insert("MIN_EDGE", "0.1");
+        let mut strategy = BasketArb::new();
+        strategy.configure(&params).unwrap();
+        assert_eq!(strategy.params().max_basket_size, dec!(8));
+        assert_eq!(strategy.params().max_legs, MAX_LEGS);
+
+        // A 6% edge is below the raised minimum
+        assert!(matches!(strategy.on_tick(&ctx).as_slice(), [Signal::Hold]));
+
+        params.insert("MIN_EDGE", "0.05");
+        strategy.configure(&params).unwrap();
+        let signals = strategy.on_tick(&ctx);
+        assert!(signals
+            .iter()
+            .all(|s| matches!(s, Signal::Buy { size, .. } if *size == dec!(8))));
+
+        let mut typo = StrategyParams::new();
+        typo.insert("MIN_EDGES", "0.05");
+        assert!(BasketArb::new().configure(&typo).is_err());
+        let mut invalid = StrategyParams::new();
+        invalid.insert("MAX_LEGS", "many");
+        assert!(BasketArb::new().configure(&invalid).is_err());
+    }
+}
@@ -1,7 +1,10 @@
 //! Auto-generated from Python strategy: dynamic_market_maker
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
-use crate::strategy::{Signal, Strategy, StrategyContext, Urgency};
+// Generated code mirrors the Python source structure rather than idiomatic Rust
+#![allow(clippy::needless_return, clippy::collapsible_if, clippy::redundant_field_names, clippy::assign_op_pattern)]
+
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
 use crate::position::Fill;
 #[allow(unused_imports)]
 use rust_decimal::Decimal;
@@ -21,9 +24,46 @@ const SPREAD_BPS: Decimal = dec!(150);
 const SKEW_FACTOR: Decimal = dec!(0.001);
 const MIN_EDGE: Decimal = dec!(0.005);
 
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicMarketMakerParams {
+    pub min_liquidity: f64,
+    pub min_price: Decimal,
+    pub max_price: Decimal,
+    pub min_spread_pct: Decimal,
+    pub max_spread_pct: Decimal,
+    pub min_hours_to_expiry: f64,
+    pub max_tokens: i64,
+    pub max_position: Decimal,
+    pub order_size: Decimal,
+    pub spread_bps: Decimal,
+    pub skew_factor: Decimal,
+    pub min_edge: Decimal,
+}
+
+impl Default for DynamicMarketMakerParams {
+    fn default() -> Self {
+        Self {
+            min_liquidity: MIN_LIQUIDITY,
+            min_price: MIN_PRICE,
+            max_price: MAX_PRICE,
+            min_spread_pct: MIN_SPREAD_PCT,
+            max_spread_pct: MAX_SPREAD_PCT,
+            min_hours_to_expiry: MIN_HOURS_TO_EXPIRY,
+            max_tokens: MAX_TOKENS,
+            max_position: MAX_POSITION,
+            order_size: ORDER_SIZE,
+            spread_bps: SPREAD_BPS,
+            skew_factor: SKEW_FACTOR,
+            min_edge: MIN_EDGE,
+        }
+    }
+}
+
 pub struct DynamicMarketMaker {
     id: String,
     tokens: Vec<String>,
+    params: DynamicMarketMakerParams,
 }
 
 impl DynamicMarketMaker {
@@ -31,8 +71,13 @@ impl DynamicMarketMaker {
         Self {
             id: "dynamic_market_maker".to_string(),
             tokens: vec![],
+            params: DynamicMarketMakerParams::default(),
         }
     }
+
+    pub fn params(&self) -> &DynamicMarketMakerParams {
+        &self.params
+    }
 }
 
 impl Default for DynamicMarketMaker {
@@ -54,21 +99,21 @@ impl Strategy for DynamicMarketMaker {
         let mut signals = vec![];
         let mut tokens_quoted = 0;
         for (token_id, market) in ctx.markets.iter() {
-            if tokens_quoted >= MAX_TOKENS {
+            if tokens_quoted >= self.params.max_tokens {
                 break;
             }
             let liquidity = match market.liquidity {
                 Some(v) => v,
                 None => continue,
             };
-            if liquidity < MIN_LIQUIDITY {
+            if liquidity < self.params.min_liquidity {
                 continue;
             }
             let hours_left = match market.hours_until_expiry {
                 Some(v) => v,
                 None => continue,
             };
-            if hours_left < MIN_HOURS_TO_EXPIRY {
+            if hours_left < self.params.min_hours_to_expiry {
                 continue;
             }
             let book = match ctx.order_books.get(token_id) {
@@ -84,18 +129,18 @@ impl Strategy for DynamicMarketMaker {
                 None => continue,
             };
             let mid = (bid + ask) / dec!(2);
-            if mid < MIN_PRICE {
+            if mid < self.params.min_price {
                 continue;
             }
-            if mid > MAX_PRICE {
+            if mid > self.params.max_price {
                 continue;
             }
             let market_spread = ask - bid;
             let spread_pct = market_spread / mid;
-            if spread_pct < MIN_SPREAD_PCT {
+            if spread_pct < self.params.min_spread_pct {
                 continue;
             }
-            if spread_pct > MAX_SPREAD_PCT {
+            if spread_pct > self.params.max_spread_pct {
                 continue;
             }
             let position = ctx.positions.get(token_id);
@@ -103,12 +148,12 @@ impl Strategy for DynamicMarketMaker {
             if let Some(position) = position {
                 position_size = position.size;
             }
-            let half_spread_pct = SPREAD_BPS / dec!(20000);
+            let half_spread_pct = self.params.spread_bps / dec!(20000);
             let half_spread = mid * half_spread_pct;
-            let skew = position_size * SKEW_FACTOR;
+            let skew = position_size * self.params.skew_factor;
             let mut my_bid = (mid - half_spread) - skew;
             let mut my_ask = (mid + half_spread) - skew;
-            if my_ask - my_bid < MIN_EDGE * dec!(2) {
+            if my_ask - my_bid < self.params.min_edge * dec!(2) {
                 continue;
             }
             if my_bid < dec!(0.01) {
@@ -117,35 +162,67 @@ impl Strategy for DynamicMarketMaker {
             if my_ask > dec!(0.99) {
                 my_ask = dec!(0.99);
             }
-            let can_buy = position_size < MAX_POSITION;
-            let neg_max_position = dec!(0) - MAX_POSITION;
+            let can_buy = position_size < self.params.max_position;
+            let neg_max_position = dec!(0) - self.params.max_position;
             let can_sell = position_size > neg_max_position;
-            let mut buy_size = ORDER_SIZE;
-            let remaining_buy = MAX_POSITION - position_size;
+            let mut buy_size = self.params.order_size;
+            let remaining_buy = self.params.max_position - position_size;
             if remaining_buy < buy_size {
                 buy_size = remaining_buy;
             }
-            let mut sell_size = ORDER_SIZE;
-            let remaining_sell = MAX_POSITION + position_size;
+            let mut sell_size = self.params.order_size;
+            let remaining_sell = self.params.max_position + position_size;
             if remaining_sell < sell_size {
                 sell_size = remaining_sell;
             }
-            signals.push(Signal::Cancel { token_id: token_id.to_string() });
+            let mut quotes = vec![];
             if can_buy {
                 if buy_size > dec!(0) {
-                    signals.push(Signal::Buy { token_id: token_id.to_string(), price: my_bid, size: buy_size, urgency: Urgency::Low });
+                    quotes.push(Signal::Buy { token_id: token_id.to_string(), price: my_bid, size: buy_size, urgency: Urgency::Low, post_only: true, expires_at: None });
                 }
             }
             if can_sell {
                 if sell_size > dec!(0) {
-                    signals.push(Signal::Sell { token_id: token_id.to_string(), price: my_ask, size: sell_size, urgency: Urgency::Low });
+                    quotes.push(Signal::Sell { token_id: token_id.to_string(), price: my_ask, size: sell_size, urgency: Urgency::Low, post_only: true, expires_at: None });
                 }
             }
+            signals.push(Signal::Replace { token_id: token_id.to_string(), orders: quotes });
             tokens_quoted = tokens_quoted + 1;
         }
         return if !signals.is_empty() { signals } else { vec![Signal::Hold] };
     }
 
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "MAX_POSITION",
+            "MAX_PRICE",
+            "MAX_SPREAD_PCT",
+            "MAX_TOKENS",
+            "MIN_EDGE",
+            "MIN_HOURS_TO_EXPIRY",
+            "MIN_LIQUIDITY",
+            "MIN_PRICE",
+            "MIN_SPREAD_PCT",
+            "ORDER_SIZE",
+            "SKEW_FACTOR",
+            "SPREAD_BPS",
+        ])?;
+        let p = &mut self.params;
+        p.min_liquidity = params.get("MIN_LIQUIDITY")?.unwrap_or(p.min_liquidity);
+        p.min_price = params.get("MIN_PRICE")?.unwrap_or(p.min_price);
+        p.max_price = params.get("MAX_PRICE")?.unwrap_or(p.max_price);
+        p.min_spread_pct = params.get("MIN_SPREAD_PCT")?.unwrap_or(p.min_spread_pct);
+        p.max_spread_pct = params.get("MAX_SPREAD_PCT")?.unwrap_or(p.max_spread_pct);
+        p.min_hours_to_expiry = params.get("MIN_HOURS_TO_EXPIRY")?.unwrap_or(p.min_hours_to_expiry);
+        p.max_tokens = params.get("MAX_TOKENS")?.unwrap_or(p.max_tokens);
+        p.max_position = params.get("MAX_POSITION")?.unwrap_or(p.max_position);
+        p.order_size = params.get("ORDER_SIZE")?.unwrap_or(p.order_size);
+        p.spread_bps = params.get("SPREAD_BPS")?.unwrap_or(p.spread_bps);
+        p.skew_factor = params.get("SKEW_FACTOR")?.unwrap_or(p.skew_factor);
+        p.min_edge = params.get("MIN_EDGE")?.unwrap_or(p.min_edge);
+        Ok(())
+    }
+
     fn on_fill(&mut self, _fill: &Fill) {}
     fn on_shutdown(&mut self) {}
 }
@@ -1,7 +1,10 @@
 //! Auto-generated from Python strategy: market_maker
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
-use crate::strategy::{Signal, Strategy, StrategyContext, Urgency};
+// Generated code mirrors the Python source structure rather than idiomatic Rust
+#![allow(clippy::needless_return, clippy::collapsible_if, clippy::redundant_field_names, clippy::assign_op_pattern)]
+
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
 use crate::position::Fill;
 #[allow(unused_imports)]
 use rust_decimal::Decimal;
@@ -15,9 +18,32 @@ const MAX_POSITION: Decimal = dec!(100);
 const ORDER_SIZE: Decimal = dec!(10);
 const MIN_EDGE: Decimal = dec!(0.005);
 
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketMakerParams {
+    pub spread_bps: Decimal,
+    pub skew_factor: Decimal,
+    pub max_position: Decimal,
+    pub order_size: Decimal,
+    pub min_edge: Decimal,
+}
+
+impl Default for MarketMakerParams {
+    fn default() -> Self {
+        Self {
+            spread_bps: SPREAD_BPS,
+            skew_factor: SKEW_FACTOR,
+            max_position: MAX_POSITION,
+            order_size: ORDER_SIZE,
+            min_edge: MIN_EDGE,
+        }
+    }
+}
+
 pub struct MarketMaker {
     id: String,
     tokens: Vec<String>,
+    params: MarketMakerParams,
 }
 
 impl MarketMaker {
@@ -25,8 +51,13 @@ impl MarketMaker {
         Self {
             id: "market_maker".to_string(),
             tokens: vec!["21742633143463906290569050155826241533067272736897614950488156847949938836455".to_string()],
+            params: MarketMakerParams::default(),
         }
     }
+
+    pub fn params(&self) -> &MarketMakerParams {
+        &self.params
+    }
 }
 
 impl Default for MarketMaker {
@@ -65,12 +96,12 @@ impl Strategy for MarketMaker {
         if let Some(position) = position {
             position_size = position.size;
         }
-        let half_spread_pct = SPREAD_BPS / dec!(20000);
+        let half_spread_pct = self.params.spread_bps / dec!(20000);
         let half_spread = mid * half_spread_pct;
-        let skew = position_size * SKEW_FACTOR;
+        let skew = position_size * self.params.skew_factor;
         let mut my_bid = (mid - half_spread) - skew;
         let mut my_ask = (mid + half_spread) - skew;
-        if my_ask - my_bid < MIN_EDGE * dec!(2) {
+        if my_ask - my_bid < self.params.min_edge * dec!(2) {
             return vec![Signal::Hold];
         }
         if my_bid < dec!(0.01) {
@@ -79,32 +110,50 @@ impl Strategy for MarketMaker {
         if my_ask > dec!(0.99) {
             my_ask = dec!(0.99);
         }
-        signals.push(Signal::Cancel { token_id: token_id.to_string() });
-        let can_buy = position_size < MAX_POSITION;
-        let can_sell = position_size > -MAX_POSITION;
-        let mut buy_size = ORDER_SIZE;
-        let remaining_buy = MAX_POSITION - position_size;
+        let can_buy = position_size < self.params.max_position;
+        let can_sell = position_size > -self.params.max_position;
+        let mut buy_size = self.params.order_size;
+        let remaining_buy = self.params.max_position - position_size;
         if remaining_buy < buy_size {
             buy_size = remaining_buy;
         }
-        let mut sell_size = ORDER_SIZE;
-        let remaining_sell = MAX_POSITION + position_size;
+        let mut sell_size = self.params.order_size;
+        let remaining_sell = self.params.max_position + position_size;
         if remaining_sell < sell_size {
             sell_size = remaining_sell;
         }
+        let mut quotes = vec![];
         if can_buy {
             if buy_size > dec!(0) {
-                signals.push(Signal::Buy { token_id: token_id.to_string(), price: my_bid, size: buy_size, urgency: Urgency::Low });
+                quotes.push(Signal::Buy { token_id: token_id.to_string(), price: my_bid, size: buy_size, urgency: Urgency::Low, post_only: true, expires_at: None });
             }
         }
         if can_sell {
             if sell_size > dec!(0) {
-                signals.push(Signal::Sell { token_id: token_id.to_string(), price: my_ask, size: sell_size, urgency: Urgency::Low });
+                quotes.push(Signal::Sell { token_id: token_id.to_string(), price: my_ask, size: sell_size, urgency: Urgency::Low, post_only: true, expires_at: None });
             }
         }
+        signals.push(Signal::Replace { token_id: token_id.to_string(), orders: quotes });
         return signals;
     }
 
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "MAX_POSITION",
+            "MIN_EDGE",
+            "ORDER_SIZE",
+            "SKEW_FACTOR",
+            "SPREAD_BPS",
+        ])?;
+        let p = &mut self.params;
+        p.spread_bps = params.get("SPREAD_BPS")?.unwrap_or(p.spread_bps);
+        p.skew_factor = params.get("SKEW_FACTOR")?.unwrap_or(p.skew_factor);
+        p.max_position = params.get("MAX_POSITION")?.unwrap_or(p.max_position);
+        p.order_size = params.get("ORDER_SIZE")?.unwrap_or(p.order_size);
+        p.min_edge = params.get("MIN_EDGE")?.unwrap_or(p.min_edge);
+        Ok(())
+    }
+
     fn on_fill(&mut self, _fill: &Fill) {}
     fn on_shutdown(&mut self) {}
 }
@@ -1,56 +1,118 @@
 //! Auto-generated strategy registry - DO NOT EDIT MANUALLY
 //! Regenerate with: pmstrat transpile --all
 
+#[cfg(feature = "arbitrage")]
+mod basket_arb;
+#[cfg(feature = "market-making")]
 mod dynamic_market_maker;
+#[cfg(feature = "market-making")]
 mod market_maker;
+#[cfg(feature = "directional")]
+mod momentum;
+#[cfg(feature = "arbitrage")]
+mod neg_risk_arb;
+#[cfg(feature = "diagnostics")]
 mod order_test;
+#[cfg(feature = "arbitrage")]
+mod pair_arb;
+#[cfg(feature = "diagnostics")]
 mod spread_watcher;
+#[cfg(feature = "directional")]
 mod sure_bets;
 
 use std::collections::HashMap;
 use crate::strategy::Strategy;
 
+#[cfg(feature = "arbitrage")]
+pub use basket_arb::BasketArb;
+#[cfg(feature = "market-making")]
 pub use dynamic_market_maker::DynamicMarketMaker;
+#[cfg(feature = "market-making")]
 pub use market_maker::MarketMaker;
+#[cfg(feature = "directional")]
+pub use momentum::Momentum;
+#[cfg(feature = "arbitrage")]
+pub use neg_risk_arb::NegRiskArb;
+#[cfg(feature = "diagnostics")]
 pub use order_test::OrderTest;
+#[cfg(feature = "arbitrage")]
+pub use pair_arb::PairArb;
+#[cfg(feature = "diagnostics")]
 pub use spread_watcher::SpreadWatcher;
+#[cfg(feature = "directional")]
 pub use sure_bets::SureBets;
 
+/// Factory function creating a new instance of a strategy.
+pub type StrategyFactory = fn() -> Box<dyn Strategy>;
+
 /// Information about a strategy in the registry.
+#[derive(Clone, Copy)]
 pub struct StrategyInfo {
     /// Factory function to create a new instance of the strategy.
-    pub factory: fn() -> Box<dyn Strategy>,
+    pub factory: StrategyFactory,
     /// Whether this strategy requires market discovery (empty tokens list).
     pub requires_market_discovery: bool,
 }
 
 /// Returns the strategy registry - a map of strategy names to their info.
 ///
-/// This function is called by the engine to look up strategies by name.
-/// The registry is auto-generated by `pmstrat transpile --all`.
+/// Only strategies of the enabled strategy group features are included;
+/// the engine looks strategies up through [`crate::registry`], which adds
+/// those registered at runtime. The registry is auto-generated by
+/// `pmstrat transpile --all`.
 pub fn registry() -> HashMap<&'static str, StrategyInfo> {
+    #[allow(unused_mut)]
     let mut m = HashMap::new();
 
+    #[cfg(feature = "arbitrage")]
+    m.insert("basket_arb", StrategyInfo {
+        factory: || Box::new(basket_arb::BasketArb::new()),
+        requires_market_discovery: true,
+    });
+
+    #[cfg(feature = "market-making")]
     m.insert("dynamic_market_maker", StrategyInfo {
         factory: || Box::new(dynamic_market_maker::DynamicMarketMaker::new()),
         requires_market_discovery: true,
     });
 
+    #[cfg(feature = "market-making")]
     m.insert("market_maker", StrategyInfo {
         factory: || Box::new(market_maker::MarketMaker::new()),
         requires_market_discovery: false,
     });
 
+    #[cfg(feature = "directional")]
+    m.insert("momentum", StrategyInfo {
+        factory: || Box::new(momentum::Momentum::new()),
+        requires_market_discovery: true,
+    });
+
+    #[cfg(feature = "arbitrage")]
+    m.insert("neg_risk_arb", StrategyInfo {
+        factory: || Box::new(neg_risk_arb::NegRiskArb::new()),
+        requires_market_discovery: true,
+    });
+
+    #[cfg(feature = "diagnostics")]
     m.insert("order_test", StrategyInfo {
         factory: || Box::new(order_test::OrderTest::new()),
         requires_market_discovery: false,
     });
 
+    #[cfg(feature = "arbitrage")]
+    m.insert("pair_arb", StrategyInfo {
+        factory: || Box::new(pair_arb::PairArb::new()),
+        requires_market_discovery: true,
+    });
+
+    #[cfg(feature = "diagnostics")]
     m.insert("spread_watcher", StrategyInfo {
         factory: || Box::new(spread_watcher::SpreadWatcher::new()),
         requires_market_discovery: false,
     });
 
+    #[cfg(feature = "directional")]
     m.insert("sure_bets", StrategyInfo {
         factory: || Box::new(sure_bets::SureBets::new()),
         requires_market_discovery: true,
@@ -0,0 +1,392 @@
+//! Short-horizon momentum (breakout) strategy.
+//!
+//! Buys a token whose mid price has risen by at least `MOMENTUM` over the
+//! recent book updates (the rolling `mid_return`), when the trade feed
+//! confirms it: recent trades are mostly buys and the last one, taken within
+//! `MAX_TRADE_AGE_SECS`, was a buy. Each position gets a trailing stop that
+//! the engine enforces (see [`crate::exits`]): `TRAIL` below the highest bid
+//! seen since entry, raised as the bid rises and never lowered, plus a time
+//! stop after `MAX_HOLD_SECS`.
+
+use crate::discovery::DiscoveryFilter;
+use crate::position::Fill;
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Mid-price return over the stats window needed to enter.
+const MOMENTUM: Decimal = dec!(0.03);
+/// Share of recent trade volume that must be buys, from -1 to 1.
+const MIN_TRADE_IMBALANCE: Decimal = dec!(0.3);
+/// Book updates the return must be measured over.
+const MIN_BOOK_UPDATES: usize = 20;
+/// Oldest the last trade may be, in seconds.
+const MAX_TRADE_AGE_SECS: i64 = 60;
+/// Entry price band (skips near-certain and long-shot markets).
+const MIN_PRICE: Decimal = dec!(0.10);
+const MAX_PRICE: Decimal = dec!(0.85);
+/// Shares bought per entry.
+const ORDER_SIZE: Decimal = dec!(10);
+/// Most positions held at once.
+const MAX_POSITIONS: usize = 5;
+/// Distance of the trailing stop below the highest bid since entry.
+const TRAIL: Decimal = dec!(0.05);
+/// Longest a position is held, in seconds.
+const MAX_HOLD_SECS: i64 = 3600;
+/// Wait before entering the same token again, in seconds.
+const COOLDOWN_SECS: i64 = 300;
+
+pub struct Momentum {
+    id: String,
+    tokens: Vec<String>,
+    params: MomentumParams,
+    /// When each token was last entered
+    entries: HashMap<String, DateTime<Utc>>,
+    /// Trailing stop of each position held
+    trails: HashMap<String, Trail>,
+}
+
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MomentumParams {
+    pub momentum: Decimal,
+    pub min_trade_imbalance: Decimal,
+    pub min_book_updates: usize,
+    pub max_trade_age_secs: i64,
+    pub min_price: Decimal,
+    pub max_price: Decimal,
+    pub order_size: Decimal,
+    pub max_positions: usize,
+    pub trail: Decimal,
+    pub max_hold_secs: i64,
+    pub cooldown_secs: i64,
+}
+
+impl Default for MomentumParams {
+    fn default() -> Self {
+        Self {
+            momentum: MOMENTUM,
+            min_trade_imbalance: MIN_TRADE_IMBALANCE,
+            min_book_updates: MIN_BOOK_UPDATES,
+            max_trade_age_secs: MAX_TRADE_AGE_SECS,
+            min_price: MIN_PRICE,
+            max_price: MAX_PRICE,
+            order_size: ORDER_SIZE,
+            max_positions: MAX_POSITIONS,
+            trail: TRAIL,
+            max_hold_secs: MAX_HOLD_SECS,
+            cooldown_secs: COOLDOWN_SECS,
+        }
+    }
+}
+
+/// A position's trailing stop.
+#[derive(Debug, Clone, PartialEq)]
+struct Trail {
+    /// Highest bid since entry (the entry price to start with)
+    peak: Decimal,
+    /// Stop last sent to the engine (None until the first one)
+    stop: Option<Decimal>,
+    opened_at: DateTime<Utc>,
+}
+
+impl Momentum {
+    pub fn new() -> Self {
+        Self {
+            id: "momentum".to_string(),
+            tokens: vec![],
+            params: MomentumParams::default(),
+            entries: HashMap::new(),
+            trails: HashMap::new(),
+        }
+    }
+
+    pub fn params(&self) -> &MomentumParams {
+        &self.params
+    }
+
+    /// Whether a token is breaking out: rising mid confirmed by buying.
+    fn breaking_out(&self, ctx: &StrategyContext, token_id: &str) -> bool {
+        let p = &self.params;
+        let Some(stats) = ctx.stats(token_id) else {
+            return false;
+        };
+        let rising = stats.book_updates >= p.min_book_updates
+            && stats.mid_return.is_some_and(|r| r >= p.momentum);
+        let bought = stats.trade_imbalance.is_some_and(|i| i >= p.min_trade_imbalance);
+        let last_buy = ctx.last_trade(token_id).is_some_and(|trade| {
+            let age_ms = ctx.timestamp.timestamp_millis() - trade.timestamp;
+            trade.side == "BUY" && age_ms <= p.max_trade_age_secs * 1000
+        });
+        rising && bought && last_buy
+    }
+
+    fn entry_signals(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let p = self.params.clone();
+        let cooldown = Duration::seconds(p.cooldown_secs);
+        let mut open = self.trails.len();
+        let mut tokens: Vec<&String> = ctx.markets.keys().collect();
+        tokens.sort();
+
+        let mut signals = Vec::new();
+        for token_id in tokens {
+            if open >= p.max_positions {
+                break;
+            }
+            let held = ctx.positions.get(token_id).is_some_and(|pos| pos.size > Decimal::ZERO);
+            let cooling = self.entries.get(token_id).is_some_and(|at| ctx.timestamp - *at < cooldown);
+            if held || cooling || !self.breaking_out(ctx, token_id) {
+                continue;
+            }
+            let Some(ask) = ctx.order_books.get(token_id).and_then(|b| b.best_ask()).map(|l| l.price) else {
+                continue;
+            };
+            if ask < p.min_price || ask > p.max_price {
+                continue;
+            }
+
+            tracing::info!(
+                token_id = token_id.as_str(),
+                mid_return = ?ctx.mid_return(token_id),
+                trade_imbalance = ?ctx.trade_imbalance(token_id),
+                ask = %ask,
+                "Momentum breakout"
+            );
+            self.entries.insert(token_id.clone(), ctx.timestamp);
+            open += 1;
+            signals.push(Signal::Buy {
+                token_id: token_id.clone(),
+                price: ask,
+                size: p.order_size,
+                urgency: Urgency::High,
+                post_only: false,
+                expires_at: None,
+            });
+        }
+        signals
+    }
+
+    /// Raise the trailing stop of each position held to `trail` below its
+    /// highest bid, dropping positions that were closed.
+    fn trailing_exits(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let p = &self.params;
+        self.trails
+            .retain(|token_id, _| ctx.positions.get(token_id).is_some_and(|pos| pos.size > Decimal::ZERO));
+
+        let mut signals = Vec::new();
+        for (token_id, trail) in &mut self.trails {
+            if let Some(bid) = ctx.order_books.get(token_id).and_then(|b| b.best_bid()) {
+                trail.peak = trail.peak.max(bid.price);
+            }
+            let stop = (trail.peak - p.trail).max(Decimal::ZERO);
+            if trail.stop.is_some_and(|sent| sent >= stop) {
+                continue;
+            }
+            trail.stop = Some(stop);
+            signals.push(Signal::Exit {
+                token_id: token_id.clone(),
+                stop_loss: Some(stop),
+                take_profit: None,
+                time_stop: Some(trail.opened_at + Duration::seconds(p.max_hold_secs)),
+            });
+        }
+        signals
+    }
+}
+
+impl Default for Momentum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for Momentum {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let mut signals = self.trailing_exits(ctx);
+        signals.extend(self.entry_signals(ctx));
+
+        if signals.is_empty() {
+            vec![Signal::Hold]
+        } else {
+            signals
+        }
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "COOLDOWN_SECS",
+            "MAX_HOLD_SECS",
+            "MAX_POSITIONS",
+            "MAX_PRICE",
+            "MAX_TRADE_AGE_SECS",
+            "MIN_BOOK_UPDATES",
+            "MIN_PRICE",
+            "MIN_TRADE_IMBALANCE",
+            "MOMENTUM",
+            "ORDER_SIZE",
+            "TRAIL",
+        ])?;
+        let p = &mut self.params;
+        p.momentum = params.get("MOMENTUM")?.unwrap_or(p.momentum);
+        p.min_trade_imbalance = params.get("MIN_TRADE_IMBALANCE")?.unwrap_or(p.min_trade_imbalance);
+        p.min_book_updates = params.get("MIN_BOOK_UPDATES")?.unwrap_or(p.min_book_updates);
+        p.max_trade_age_secs = params.get("MAX_TRADE_AGE_SECS")?.unwrap_or(p.max_trade_age_secs);
+        p.min_price = params.get("MIN_PRICE")?.unwrap_or(p.min_price);
+        p.max_price = params.get("MAX_PRICE")?.unwrap_or(p.max_price);
+        p.order_size = params.get("ORDER_SIZE")?.unwrap_or(p.order_size);
+        p.max_positions = params.get("MAX_POSITIONS")?.unwrap_or(p.max_positions);
+        p.trail = params.get("TRAIL")?.unwrap_or(p.trail);
+        p.max_hold_secs = params.get("MAX_HOLD_SECS")?.unwrap_or(p.max_hold_secs);
+        p.cooldown_secs = params.get("COOLDOWN_SECS")?.unwrap_or(p.cooldown_secs);
+        Ok(())
+    }
+
+    /// Start trailing a position on its first buy fill.
+    fn on_fill(&mut self, fill: &Fill) {
+        if fill.is_buy && fill.strategy_id.as_deref() == Some(self.id.as_str()) {
+            self.trails.entry(fill.token_id.clone()).or_insert(Trail {
+                peak: fill.price,
+                stop: None,
+                opened_at: fill.timestamp,
+            });
+        }
+    }
+
+    fn on_shutdown(&mut self) {}
+
+    /// Discovery subscribes to a market's leading outcome, which is only
+    /// bought while priced at most `MAX_PRICE`.
+    fn discovery_filters(&self) -> Vec<DiscoveryFilter> {
+        vec![DiscoveryFilter::MaxCertainty(self.params.max_price)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{LastTrade, Level, MarketStats, OrderBook};
+    use crate::position::PositionTracker;
+    use crate::strategy::MarketInfo;
+    use std::sync::Arc;
+
+    fn make_context(bid: Decimal, ask: Decimal, mid_return: Decimal, held: Decimal) -> StrategyContext {
+        let now = Utc::now();
+        let mut book = OrderBook::new("tok".to_string());
+        book.bids = vec![Level { price: bid, size: dec!(100) }];
+        book.asks = vec![Level { price: ask, size: dec!(100) }];
+        let stats = MarketStats {
+            mid_return: Some(mid_return),
+            trade_imbalance: Some(dec!(0.6)),
+            book_updates: 40,
+            trades: 12,
+            ..Default::default()
+        };
+        let trade = LastTrade {
+            price: ask,
+            size: dec!(25),
+            side: "BUY".to_string(),
+            timestamp: now.timestamp_millis() - 5_000,
+        };
+        let mut positions = PositionTracker::new();
+        if held > Decimal::ZERO {
+            positions.get_or_create("tok").size = held;
+        }
+
+        let order_books = HashMap::from([("tok".to_string(), Arc::new(book))]);
+        let markets = HashMap::from([(
+            "tok".to_string(),
+            MarketInfo::new("Will it?".into(), "Yes".into(), "market".into(), None),
+        )]);
+        StrategyContext {
+            timestamp: now,
+            positions,
+            last_trades: HashMap::from([("tok".to_string(), trade)]),
+            stats: HashMap::from([("tok".to_string(), stats)]),
+            ..StrategyContext::for_test(order_books, markets)
+        }
+    }
+
+    fn fill(strategy: &mut Momentum, price: Decimal) {
+        strategy.on_fill(&Fill {
+            order_id: "1".to_string(),
+            token_id: "tok".to_string(),
+            is_buy: true,
+            price,
+            size: ORDER_SIZE,
+            timestamp: Utc::now(),
+            fee: Decimal::ZERO,
+            strategy_id: Some("momentum".to_string()),
+        });
+    }
+
+    fn stop(signals: &[Signal]) -> Option<Decimal> {
+        signals.iter().find_map(|s| match s {
+            Signal::Exit { stop_loss, time_stop, .. } => {
+                assert!(time_stop.is_some());
+                *stop_loss
+            }
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_holds_without_a_breakout() {
+        let mut strategy = Momentum::new();
+
+        // Flat market
+        let flat = make_context(dec!(0.49), dec!(0.50), dec!(0.01), dec!(0));
+        assert!(matches!(strategy.on_tick(&flat).as_slice(), [Signal::Hold]));
+
+        // Rising, but not confirmed by the trade feed or measured over too few updates
+        let unconfirmed: [fn(&mut StrategyContext); 4] = [
+            |ctx| ctx.stats.get_mut("tok").unwrap().trade_imbalance = Some(dec!(-0.2)),
+            |ctx| ctx.last_trades.get_mut("tok").unwrap().side = "SELL".to_string(),
+            |ctx| ctx.last_trades.get_mut("tok").unwrap().timestamp -= (MAX_TRADE_AGE_SECS + 1) * 1000,
+            |ctx| ctx.stats.get_mut("tok").unwrap().book_updates = MIN_BOOK_UPDATES - 1,
+        ];
+        for unconfirm in unconfirmed {
+            let mut ctx = make_context(dec!(0.54), dec!(0.55), dec!(0.05), dec!(0));
+            unconfirm(&mut ctx);
+            assert!(matches!(strategy.on_tick(&ctx).as_slice(), [Signal::Hold]));
+        }
+        assert!(strategy.entries.is_empty());
+    }
+
+    #[test]
+    fn test_enters_breakouts_once_per_cooldown() {
+        let mut strategy = Momentum::new();
+        let breakout = make_context(dec!(0.54), dec!(0.55), dec!(0.05), dec!(0));
+        match strategy.on_tick(&breakout).as_slice() {
+            [Signal::Buy { price, size, .. }] => assert_eq!((*price, *size), (dec!(0.55), ORDER_SIZE)),
+            other => panic!("expected a buy, got {:?}", other),
+        }
+        assert!(matches!(strategy.on_tick(&breakout).as_slice(), [Signal::Hold]));
+    }
+
+    #[test]
+    fn test_trailing_stop_exit() {
+        let mut strategy = Momentum::new();
+
+        // Filled: the stop trails the highest bid and is never lowered
+        fill(&mut strategy, dec!(0.55));
+        let held = |bid: Decimal| make_context(bid, bid + dec!(0.01), dec!(0), ORDER_SIZE);
+        assert_eq!(stop(&strategy.on_tick(&held(dec!(0.54)))), Some(dec!(0.50)));
+        assert_eq!(stop(&strategy.on_tick(&held(dec!(0.60)))), Some(dec!(0.55)));
+        assert!(matches!(strategy.on_tick(&held(dec!(0.57))).as_slice(), [Signal::Hold]));
+        assert_eq!(stop(&strategy.on_tick(&held(dec!(0.62)))), Some(dec!(0.57)));
+
+        // Closed: the trail is dropped
+        strategy.on_tick(&make_context(dec!(0.50), dec!(0.51), dec!(0), dec!(0)));
+        assert!(strategy.trails.is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! NO-side arbitrage on negRisk (multi-outcome) events.
+//!
+//! Through the NegRisk adapter one NO share of each of an event's `n`
+//! outcomes converts into `n - 1` USDC. When the NO asks sum to less than
+//! that, minus the taker fees on every leg (and a safety margin), the
+//! strategy buys the NO side of every outcome as a paired order, and
+//! converts the complete sets it holds into USDC on the next tick. This is
+//! the mirror of `basket_arb`: it trades events whose YES bids sum to more
+//! than $1 without having to hold the YES basket first.
+
+use crate::basket::Basket;
+use crate::fees;
+use crate::position::Fill;
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Minimum edge per set after fees before trading (covers leg risk and gas).
+const MIN_EDGE: Decimal = dec!(0.02);
+/// Taker fee rate assumed on every leg, in basis points.
+const FEE_RATE_BPS: Decimal = dec!(0);
+/// Fewest outcomes an event needs to be traded.
+const MIN_LEGS: usize = 2;
+/// Most outcomes to trade (more legs = more leg risk).
+const MAX_LEGS: usize = 12;
+/// Largest set to buy per tick (shares per leg).
+const MAX_SET_SIZE: Decimal = dec!(20);
+/// Smallest order or conversion worth sending (shares per leg).
+const MIN_ORDER_SIZE: Decimal = dec!(5);
+
+pub struct NegRiskArb {
+    id: String,
+    tokens: Vec<String>,
+    params: NegRiskArbParams,
+}
+
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NegRiskArbParams {
+    pub min_edge: Decimal,
+    pub fee_rate_bps: Decimal,
+    pub min_legs: usize,
+    pub max_legs: usize,
+    pub max_set_size: Decimal,
+    pub min_order_size: Decimal,
+}
+
+impl Default for NegRiskArbParams {
+    fn default() -> Self {
+        Self {
+            min_edge: MIN_EDGE,
+            fee_rate_bps: FEE_RATE_BPS,
+            min_legs: MIN_LEGS,
+            max_legs: MAX_LEGS,
+            max_set_size: MAX_SET_SIZE,
+            min_order_size: MIN_ORDER_SIZE,
+        }
+    }
+}
+
+impl NegRiskArb {
+    pub fn new() -> Self {
+        Self {
+            id: "neg_risk_arb".to_string(),
+            tokens: vec![],
+            params: NegRiskArbParams::default(),
+        }
+    }
+
+    pub fn params(&self) -> &NegRiskArbParams {
+        &self.params
+    }
+
+    /// Edge per set of NO shares bought at the asks and converted, net of
+    /// the taker fee on each leg.
+    fn net_edge(&self, no_side: &Basket) -> Option<Decimal> {
+        let edge = no_side.conversion_edge()?;
+        let fees: Decimal = no_side
+            .legs
+            .iter()
+            .filter_map(|l| l.ask)
+            .map(|ask| fees::fee(self.params.fee_rate_bps, ask, Decimal::ONE))
+            .sum();
+        Some(edge - fees)
+    }
+
+    fn event_signal(&self, ctx: &StrategyContext, basket: &Basket) -> Option<Signal> {
+        let p = &self.params;
+        if !basket.neg_risk || basket.len() < p.min_legs || basket.len() > p.max_legs {
+            return None;
+        }
+        let no_side = basket.no_side(ctx)?;
+        let token_ids: Vec<String> = no_side.legs.iter().map(|l| l.token_id.clone()).collect();
+
+        // Convert the complete sets already held
+        let held = token_ids
+            .iter()
+            .map(|t| ctx.positions.get(t).map(|p| p.size).unwrap_or_default())
+            .min()
+            .unwrap_or_default();
+        if held >= p.min_order_size {
+            tracing::info!(event = basket.event_slug.as_str(), size = %held, "Converting NO set");
+            return Some(Signal::Convert { token_ids, size: held });
+        }
+
+        let edge = self.net_edge(&no_side)?;
+        let size = no_side.max_buy_size().min(p.max_set_size);
+        if edge < p.min_edge || size < p.min_order_size {
+            return None;
+        }
+
+        tracing::info!(
+            event = basket.event_slug.as_str(),
+            legs = no_side.len(),
+            asks = ?no_side.sum_asks(),
+            edge = %edge,
+            size = %size,
+            "Buying NO set below its conversion value"
+        );
+        let legs = no_side
+            .legs
+            .iter()
+            .filter_map(|l| {
+                l.ask.map(|price| Signal::Buy {
+                    token_id: l.token_id.clone(),
+                    price,
+                    size,
+                    urgency: Urgency::High,
+                    post_only: false,
+                    expires_at: None,
+                })
+            })
+            .collect();
+        Some(Signal::Paired { legs })
+    }
+}
+
+impl Default for NegRiskArb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for NegRiskArb {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let signals: Vec<Signal> = ctx
+            .baskets()
+            .iter()
+            .filter_map(|basket| self.event_signal(ctx, basket))
+            .collect();
+
+        if signals.is_empty() {
+            vec![Signal::Hold]
+        } else {
+            signals
+        }
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "FEE_RATE_BPS",
+            "MAX_LEGS",
+            "MAX_SET_SIZE",
+            "MIN_EDGE",
+            "MIN_LEGS",
+            "MIN_ORDER_SIZE",
+        ])?;
+        let p = &mut self.params;
+        p.min_edge = params.get("MIN_EDGE")?.unwrap_or(p.min_edge);
+        p.fee_rate_bps = params.get("FEE_RATE_BPS")?.unwrap_or(p.fee_rate_bps);
+        p.min_legs = params.get("MIN_LEGS")?.unwrap_or(p.min_legs);
+        p.max_legs = params.get("MAX_LEGS")?.unwrap_or(p.max_legs);
+        p.max_set_size = params.get("MAX_SET_SIZE")?.unwrap_or(p.max_set_size);
+        p.min_order_size = params.get("MIN_ORDER_SIZE")?.unwrap_or(p.min_order_size);
+        Ok(())
+    }
+
+    fn on_fill(&mut self, _fill: &Fill) {}
+    fn on_shutdown(&mut self) {}
+
+    fn needs_baskets(&self) -> bool {
+        true
+    }
+
+    fn all_outcomes(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use crate::position::PositionTracker;
+    use crate::strategy::MarketInfo;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    /// A three-outcome event, with the asks of each outcome's NO token.
+    fn make_context(no_asks: [Decimal; 3], held: Decimal) -> StrategyContext {
+        let mut order_books = HashMap::new();
+        let mut markets = HashMap::new();
+        let mut positions = PositionTracker::new();
+
+        for (name, no_ask) in ["a", "b", "c"].into_iter().zip(no_asks) {
+            let tokens = vec![format!("{}-yes", name), format!("{}-no", name)];
+            for (idx, (token_id, ask)) in tokens.iter().zip([Decimal::ONE - no_ask, no_ask]).enumerate() {
+                let mut book = OrderBook::new(token_id.clone());
+                book.bids = vec![Level { price: ask - dec!(0.01), size: dec!(50) }];
+                book.asks = vec![Level { price: ask, size: dec!(50) }];
+                order_books.insert(token_id.clone(), Arc::new(book));
+                let mut info = MarketInfo::new(format!("Will {} win?", name), "Yes".into(), name.into(), None)
+                    .with_outcomes(idx, tokens.clone());
+                if idx == 0 {
+                    info = info.with_event("election".into(), true);
+                } else {
+                    info.outcome = "No".into();
+                    info = info.secondary();
+                }
+                markets.insert(token_id.clone(), info);
+            }
+            if held > Decimal::ZERO {
+                positions.get_or_create(&tokens[1]).size = held;
+            }
+        }
+
+        StrategyContext {
+            positions,
+            ..StrategyContext::for_test(order_books, markets)
+        }
+    }
+
+    #[test]
+    fn test_buys_and_converts_no_sets() {
+        // NO asks sum to 1.95 for a 2 USDC conversion
+        let cheap = make_context([dec!(0.55), dec!(0.70), dec!(0.70)], dec!(0));
+        let mut strategy = NegRiskArb::new();
+        match strategy.on_tick(&cheap).as_slice() {
+            [Signal::Paired { legs }] => {
+                let tokens: Vec<&str> = legs.iter().filter_map(|l| l.order_terms()).map(|t| t.0).collect();
+                assert_eq!(tokens, ["a-no", "b-no", "c-no"]);
+                assert!(legs.iter().filter_map(|l| l.order_terms()).all(|t| t.1 && t.3 == MAX_SET_SIZE));
+            }
+            other => panic!("expected a paired buy, got {:?}", other),
+        }
+
+        // Fairly priced, or cheap only before fees
+        let fair = make_context([dec!(0.60), dec!(0.70), dec!(0.70)], dec!(0));
+        assert!(matches!(strategy.on_tick(&fair).as_slice(), [Signal::Hold]));
+        let mut params = StrategyParams::new();
+        params.insert("FEE_RATE_BPS", "500");
+        strategy.configure(&params).unwrap();
+        assert!(matches!(strategy.on_tick(&cheap).as_slice(), [Signal::Hold]));
+
+        // Complete sets held are converted
+        let held = make_context([dec!(0.60), dec!(0.70), dec!(0.70)], dec!(12));
+        match NegRiskArb::new().on_tick(&held).as_slice() {
+            [Signal::Convert { token_ids, size }] => {
+                assert_eq!(token_ids, &["a-no", "b-no", "c-no"]);
+                assert_eq!(*size, dec!(12));
+            }
+            other => panic!("expected a conversion, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_holds_without_edge() {
+        let mut strategy = NegRiskArb::new();
+        // Sums to the 2 USDC conversion, then 0.01 under it (below MIN_EDGE)
+        for no_asks in [[dec!(0.60), dec!(0.70), dec!(0.70)], [dec!(0.59), dec!(0.70), dec!(0.70)]] {
+            let ctx = make_context(no_asks, dec!(0));
+            assert!(matches!(strategy.on_tick(&ctx).as_slice(), [Signal::Hold]));
+        }
+    }
+
+    #[test]
+    fn test_holds_without_an_outcome_book() {
+        let mut ctx = make_context([dec!(0.55), dec!(0.70), dec!(0.70)], dec!(0));
+        ctx.order_books.remove("b-no");
+        assert!(matches!(NegRiskArb::new().on_tick(&ctx).as_slice(), [Signal::Hold]));
+    }
+}
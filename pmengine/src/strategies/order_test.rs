@@ -52,6 +52,8 @@ impl Strategy for OrderTest {
             price: dec!(0.01),
             size: dec!(5),
             urgency: Urgency::Low,
+            post_only: false,
+            expires_at: None,
         }]
     }
 
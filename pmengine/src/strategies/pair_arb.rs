@@ -0,0 +1,274 @@
+//! YES+NO arbitrage on binary markets.
+//!
+//! One of a binary market's two outcome tokens pays $1, so holding one share
+//! of each is worth exactly $1 at resolution. When the best asks of the two
+//! sum to less than $1 minus the taker fees on both legs (and a safety
+//! margin), the strategy buys both at once as a paired order and holds the
+//! pair to resolution.
+
+use crate::basket::Basket;
+use crate::fees;
+use crate::position::Fill;
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Minimum edge per $1 pair after fees before trading (covers leg risk).
+const MIN_EDGE: Decimal = dec!(0.01);
+/// Taker fee rate assumed on both legs, in basis points.
+const FEE_RATE_BPS: Decimal = dec!(0);
+/// Largest pair to buy per tick (shares per leg).
+const MAX_PAIR_SIZE: Decimal = dec!(20);
+/// Most complete pairs to hold per market.
+const MAX_PAIRS_HELD: Decimal = dec!(100);
+/// Smallest order worth sending (shares per leg).
+const MIN_ORDER_SIZE: Decimal = dec!(5);
+
+pub struct PairArb {
+    id: String,
+    tokens: Vec<String>,
+    params: PairArbParams,
+}
+
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairArbParams {
+    pub min_edge: Decimal,
+    pub fee_rate_bps: Decimal,
+    pub max_pair_size: Decimal,
+    pub max_pairs_held: Decimal,
+    pub min_order_size: Decimal,
+}
+
+impl Default for PairArbParams {
+    fn default() -> Self {
+        Self {
+            min_edge: MIN_EDGE,
+            fee_rate_bps: FEE_RATE_BPS,
+            max_pair_size: MAX_PAIR_SIZE,
+            max_pairs_held: MAX_PAIRS_HELD,
+            min_order_size: MIN_ORDER_SIZE,
+        }
+    }
+}
+
+impl PairArb {
+    pub fn new() -> Self {
+        Self {
+            id: "pair_arb".to_string(),
+            tokens: vec![],
+            params: PairArbParams::default(),
+        }
+    }
+
+    pub fn params(&self) -> &PairArbParams {
+        &self.params
+    }
+
+    /// Edge per pair bought at the asks, net of the taker fee on each leg.
+    fn net_edge(&self, pair: &Basket) -> Option<Decimal> {
+        let edge = pair.buy_edge()?;
+        let fees: Decimal = pair
+            .legs
+            .iter()
+            .filter_map(|l| l.ask)
+            .map(|ask| fees::fee(self.params.fee_rate_bps, ask, Decimal::ONE))
+            .sum();
+        Some(edge - fees)
+    }
+
+    fn pair_signal(&self, ctx: &StrategyContext, pair: &Basket) -> Option<Signal> {
+        let p = &self.params;
+        let edge = self.net_edge(pair)?;
+        if edge < p.min_edge {
+            return None;
+        }
+
+        let held = pair
+            .legs
+            .iter()
+            .map(|l| ctx.positions.get(&l.token_id).map(|p| p.size).unwrap_or_default())
+            .min()
+            .unwrap_or_default();
+        let room = (p.max_pairs_held - held).max(Decimal::ZERO);
+        let size = pair.max_buy_size().min(p.max_pair_size).min(room);
+        if size < p.min_order_size {
+            return None;
+        }
+
+        tracing::info!(
+            market = pair.event_slug.as_str(),
+            asks = ?pair.sum_asks(),
+            edge = %edge,
+            size = %size,
+            "Buying YES+NO pair below $1"
+        );
+        let legs = pair
+            .legs
+            .iter()
+            .filter_map(|l| {
+                l.ask.map(|price| Signal::Buy {
+                    token_id: l.token_id.clone(),
+                    price,
+                    size,
+                    urgency: Urgency::High,
+                    post_only: false,
+                    expires_at: None,
+                })
+            })
+            .collect();
+        Some(Signal::Paired { legs })
+    }
+}
+
+impl Default for PairArb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for PairArb {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let signals: Vec<Signal> = ctx
+            .pairs()
+            .iter()
+            .filter_map(|pair| self.pair_signal(ctx, pair))
+            .collect();
+
+        if signals.is_empty() {
+            vec![Signal::Hold]
+        } else {
+            signals
+        }
+    }
+
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "FEE_RATE_BPS",
+            "MAX_PAIRS_HELD",
+            "MAX_PAIR_SIZE",
+            "MIN_EDGE",
+            "MIN_ORDER_SIZE",
+        ])?;
+        let p = &mut self.params;
+        p.min_edge = params.get("MIN_EDGE")?.unwrap_or(p.min_edge);
+        p.fee_rate_bps = params.get("FEE_RATE_BPS")?.unwrap_or(p.fee_rate_bps);
+        p.max_pair_size = params.get("MAX_PAIR_SIZE")?.unwrap_or(p.max_pair_size);
+        p.max_pairs_held = params.get("MAX_PAIRS_HELD")?.unwrap_or(p.max_pairs_held);
+        p.min_order_size = params.get("MIN_ORDER_SIZE")?.unwrap_or(p.min_order_size);
+        Ok(())
+    }
+
+    fn on_fill(&mut self, _fill: &Fill) {}
+    fn on_shutdown(&mut self) {}
+
+    fn all_outcomes(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::{Level, OrderBook};
+    use crate::position::PositionTracker;
+    use crate::strategy::MarketInfo;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn make_context(yes_ask: Decimal, no_ask: Decimal, held: Decimal) -> StrategyContext {
+        let mut order_books = HashMap::new();
+        let mut markets = HashMap::new();
+        let mut positions = PositionTracker::new();
+
+        let tokens = vec!["yes".to_string(), "no".to_string()];
+        for (idx, (token_id, ask)) in [("yes", yes_ask), ("no", no_ask)].into_iter().enumerate() {
+            let mut book = OrderBook::new(token_id.to_string());
+            book.bids = vec![Level { price: ask - dec!(0.02), size: dec!(50) }];
+            book.asks = vec![Level { price: ask, size: dec!(50) }];
+            order_books.insert(token_id.to_string(), Arc::new(book));
+            markets.insert(
+                token_id.to_string(),
+                MarketInfo::new("Will it?".into(), token_id.into(), "market".into(), None)
+                    .with_outcomes(idx, tokens.clone()),
+            );
+            if held > Decimal::ZERO {
+                positions.get_or_create(token_id).size = held;
+            }
+        }
+
+        StrategyContext {
+            positions,
+            ..StrategyContext::for_test(order_books, markets)
+        }
+    }
+
+    fn pair_size(signals: &[Signal]) -> Option<Decimal> {
+        match signals {
+            [Signal::Paired { legs }] => {
+                assert_eq!(legs.len(), 2);
+                let sizes: Vec<Decimal> = legs.iter().filter_map(|l| l.order_terms()).map(|t| t.3).collect();
+                assert!(sizes.iter().all(|size| *size == sizes[0]));
+                Some(sizes[0])
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_buys_both_legs_below_one() {
+        let cheap = make_context(dec!(0.60), dec!(0.37), dec!(0));
+        let mut strategy = PairArb::new();
+        assert_eq!(pair_size(&strategy.on_tick(&cheap)), Some(MAX_PAIR_SIZE));
+
+        // Only up to the pairs it may hold
+        let ctx = make_context(dec!(0.60), dec!(0.37), MAX_PAIRS_HELD - dec!(8));
+        assert_eq!(pair_size(&PairArb::new().on_tick(&ctx)), Some(dec!(8)));
+    }
+
+    #[test]
+    fn test_holds_when_asks_sum_to_one_after_fees() {
+        let mut strategy = PairArb::new();
+        let fair = make_context(dec!(0.60), dec!(0.40), dec!(0));
+        assert!(matches!(strategy.on_tick(&fair).as_slice(), [Signal::Hold]));
+        let rich = make_context(dec!(0.62), dec!(0.41), dec!(0));
+        assert!(matches!(strategy.on_tick(&rich).as_slice(), [Signal::Hold]));
+
+        // Cheap only before fees
+        let cheap = make_context(dec!(0.60), dec!(0.37), dec!(0));
+        let mut params = StrategyParams::new();
+        params.insert("FEE_RATE_BPS", "500");
+        strategy.configure(&params).unwrap();
+        assert!(matches!(strategy.on_tick(&cheap).as_slice(), [Signal::Hold]));
+    }
+
+    #[test]
+    fn test_holds_without_no_book() {
+        let mut ctx = make_context(dec!(0.60), dec!(0.37), dec!(0));
+        ctx.order_books.remove("no");
+        assert!(matches!(PairArb::new().on_tick(&ctx).as_slice(), [Signal::Hold]));
+    }
+
+    #[test]
+    fn test_size_capped_by_thinner_leg() {
+        let mut ctx = make_context(dec!(0.60), dec!(0.37), dec!(0));
+        let mut book = (*ctx.order_books["no"]).clone();
+        book.asks[0].size = dec!(7);
+        ctx.order_books.insert("no".to_string(), Arc::new(book));
+        assert_eq!(pair_size(&PairArb::new().on_tick(&ctx)), Some(dec!(7)));
+
+        // Thinner than the smallest order worth sending
+        let mut book = (*ctx.order_books["no"]).clone();
+        book.asks[0].size = MIN_ORDER_SIZE - dec!(1);
+        ctx.order_books.insert("no".to_string(), Arc::new(book));
+        assert!(matches!(PairArb::new().on_tick(&ctx).as_slice(), [Signal::Hold]));
+    }
+}
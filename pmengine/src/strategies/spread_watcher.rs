@@ -1,6 +1,9 @@
 //! Auto-generated from Python strategy: spread_watcher
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
+// Generated code mirrors the Python source structure rather than idiomatic Rust
+#![allow(clippy::needless_return, clippy::collapsible_if, clippy::redundant_field_names, clippy::assign_op_pattern)]
+
 use crate::strategy::{Signal, Strategy, StrategyContext, Urgency};
 use crate::position::Fill;
 #[allow(unused_imports)]
@@ -54,7 +57,7 @@ impl Strategy for SpreadWatcher {
         let spread = ask - bid;
         if spread > dec!(0.50) {
             let mid = (bid + ask) / dec!(2);
-            signals.push(Signal::Buy { token_id: token.to_string(), price: mid, size: dec!(1), urgency: Urgency::Low });
+            signals.push(Signal::Buy { token_id: token.to_string(), price: mid, size: dec!(1), urgency: Urgency::Low, post_only: false, expires_at: None });
         }
         return signals;
     }
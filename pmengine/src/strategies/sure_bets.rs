@@ -1,7 +1,10 @@
 //! Auto-generated from Python strategy: sure_bets
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
-use crate::strategy::{Signal, Strategy, StrategyContext, Urgency};
+// Generated code mirrors the Python source structure rather than idiomatic Rust
+#![allow(clippy::needless_return, clippy::collapsible_if, clippy::redundant_field_names, clippy::assign_op_pattern)]
+
+use crate::strategy::{Signal, Strategy, StrategyContext, StrategyParams, Urgency};
 use crate::position::Fill;
 #[allow(unused_imports)]
 use rust_decimal::Decimal;
@@ -16,11 +19,43 @@ const MAX_POSITION_SIZE: Decimal = dec!(100);
 const MIN_ORDER_SIZE: Decimal = dec!(10);
 const MAX_SINGLE_ORDER: Decimal = dec!(50);
 const MIN_EXPECTED_RETURN: Decimal = dec!(0.01);
+const WIN_PROBABILITY: Decimal = dec!(0.995);
 const EXCLUDE_KEYWORDS: &[&str] = &["dota", "counter-strike", "valorant", "league of legends", "overwatch", "csgo", "cs2", "lol", "pubg", "fortnite", "rocket league", "starcraft", "kill handicap", "map handicap", "game handicap", "games total", "bo3", "bo5", "esports", "e-sports", " vs ", " vs. ", " fc", " afc", " cf", "united fc", "city fc", "o/u 2.5", "o/u 3.5", "o/u 4.5", "o/u 1.5", "o/u 0.5", "over/under", "over 0.5", "over 1.5", "over 2.5", "over 3.5", "over 4.5", "under 0.5", "under 1.5", "under 2.5", "under 3.5", "under 4.5", "premier league", "epl", "champions league", "la liga", "bundesliga", "serie a", "ligue 1", "eredivisie", "championship", "league one", "league two", "copa america", "euros", "euro 2024", "euro 2025", "world cup", "nfl", "nba", "mlb", "nhl", "mls", "ufc", "wwe", "ncaa", "super bowl", "stanley cup", "world series", "fifa", "olympics", "tennis", "golf", "boxing", "mma", "f1", "nascar", "cricket", "rugby", "atp", "wta", "pga"];
 
+/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SureBetsParams {
+    pub min_certainty: Decimal,
+    pub max_certainty: Decimal,
+    pub max_hours_to_expiry: f64,
+    pub min_liquidity: f64,
+    pub max_position_size: Decimal,
+    pub min_order_size: Decimal,
+    pub max_single_order: Decimal,
+    pub min_expected_return: Decimal,
+    pub win_probability: Decimal,
+}
+
+impl Default for SureBetsParams {
+    fn default() -> Self {
+        Self {
+            min_certainty: MIN_CERTAINTY,
+            max_certainty: MAX_CERTAINTY,
+            max_hours_to_expiry: MAX_HOURS_TO_EXPIRY,
+            min_liquidity: MIN_LIQUIDITY,
+            max_position_size: MAX_POSITION_SIZE,
+            min_order_size: MIN_ORDER_SIZE,
+            max_single_order: MAX_SINGLE_ORDER,
+            min_expected_return: MIN_EXPECTED_RETURN,
+            win_probability: WIN_PROBABILITY,
+        }
+    }
+}
+
 pub struct SureBets {
     id: String,
     tokens: Vec<String>,
+    params: SureBetsParams,
 }
 
 impl SureBets {
@@ -28,8 +63,13 @@ impl SureBets {
         Self {
             id: "sure_bets".to_string(),
             tokens: vec![],
+            params: SureBetsParams::default(),
         }
     }
+
+    pub fn params(&self) -> &SureBetsParams {
+        &self.params
+    }
 }
 
 impl Default for SureBets {
@@ -62,7 +102,7 @@ impl Strategy for SureBets {
             }
             let liquidity = market.liquidity;
             if let Some(liquidity) = liquidity {
-                if liquidity < MIN_LIQUIDITY {
+                if liquidity < self.params.min_liquidity {
                     continue;
                 }
             }
@@ -76,7 +116,7 @@ impl Strategy for SureBets {
             if hours_left < 0.0 {
                 continue;
             }
-            if hours_left > MAX_HOURS_TO_EXPIRY {
+            if hours_left > self.params.max_hours_to_expiry {
                 continue;
             }
             let book = match ctx.order_books.get(token_id) {
@@ -87,14 +127,14 @@ impl Strategy for SureBets {
                 Some(v) => v.price,
                 None => continue,
             };
-            if ask_price < MIN_CERTAINTY {
+            if ask_price < self.params.min_certainty {
                 continue;
             }
-            if ask_price > MAX_CERTAINTY {
+            if ask_price > self.params.max_certainty {
                 continue;
             }
             let expected_return = (dec!(1.00) - ask_price) / ask_price;
-            if expected_return < MIN_EXPECTED_RETURN {
+            if expected_return < self.params.min_expected_return {
                 continue;
             }
             let position = ctx.positions.get(token_id);
@@ -102,26 +142,55 @@ impl Strategy for SureBets {
             if let Some(position) = position {
                 current_size = position.size;
             }
-            if current_size >= MAX_POSITION_SIZE {
+            let mut target = ctx.kelly_size(self.params.win_probability, ask_price);
+            if self.params.max_position_size < target {
+                target = self.params.max_position_size;
+            }
+            if current_size >= target {
                 continue;
             }
-            let remaining = MAX_POSITION_SIZE - current_size;
+            let remaining = target - current_size;
             let ask_size = book.ask_size();
             let mut size = remaining;
             if ask_size < size {
                 size = ask_size;
             }
-            if MAX_SINGLE_ORDER < size {
-                size = MAX_SINGLE_ORDER;
+            if self.params.max_single_order < size {
+                size = self.params.max_single_order;
             }
-            if size < MIN_ORDER_SIZE {
+            if size < self.params.min_order_size {
                 continue;
             }
-            signals.push(Signal::Buy { token_id: token_id.to_string(), price: ask_price, size: size, urgency: Urgency::Medium });
+            signals.push(Signal::Buy { token_id: token_id.to_string(), price: ask_price, size: size, urgency: Urgency::Medium, post_only: false, expires_at: None });
         }
         return if !signals.is_empty() { signals } else { vec![Signal::Hold] };
     }
 
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        params.check_known(&[
+            "MAX_CERTAINTY",
+            "MAX_HOURS_TO_EXPIRY",
+            "MAX_POSITION_SIZE",
+            "MAX_SINGLE_ORDER",
+            "MIN_CERTAINTY",
+            "MIN_EXPECTED_RETURN",
+            "MIN_LIQUIDITY",
+            "MIN_ORDER_SIZE",
+            "WIN_PROBABILITY",
+        ])?;
+        let p = &mut self.params;
+        p.min_certainty = params.get("MIN_CERTAINTY")?.unwrap_or(p.min_certainty);
+        p.max_certainty = params.get("MAX_CERTAINTY")?.unwrap_or(p.max_certainty);
+        p.max_hours_to_expiry = params.get("MAX_HOURS_TO_EXPIRY")?.unwrap_or(p.max_hours_to_expiry);
+        p.min_liquidity = params.get("MIN_LIQUIDITY")?.unwrap_or(p.min_liquidity);
+        p.max_position_size = params.get("MAX_POSITION_SIZE")?.unwrap_or(p.max_position_size);
+        p.min_order_size = params.get("MIN_ORDER_SIZE")?.unwrap_or(p.min_order_size);
+        p.max_single_order = params.get("MAX_SINGLE_ORDER")?.unwrap_or(p.max_single_order);
+        p.min_expected_return = params.get("MIN_EXPECTED_RETURN")?.unwrap_or(p.min_expected_return);
+        p.win_probability = params.get("WIN_PROBABILITY")?.unwrap_or(p.win_probability);
+        Ok(())
+    }
+
     fn on_fill(&mut self, _fill: &Fill) {}
     fn on_shutdown(&mut self) {}
 }
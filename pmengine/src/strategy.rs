@@ -1,18 +1,32 @@
 //! Strategy trait and runtime for trading strategies.
 
-use crate::orderbook::OrderBook;
+use crate::basket::Basket;
+use crate::client::TimeInForce;
+use crate::discovery::DiscoveryFilter;
+use crate::execution::ExecutionAlgo;
+use crate::gamma::MarketDetail;
+use crate::history::PricePoint;
+use crate::orderbook::{LastTrade, MarketStats, OrderBook};
+use crate::pipeline::{Filter, Pipeline};
 use crate::position::{Fill, PositionTracker};
+use crate::safe_math::add;
+use crate::schedule::Schedule;
+use crate::sizing;
+use crate::webhook::ExternalEvent;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Urgency level for order execution.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Urgency {
     /// Post-only limit order, willing to wait
     Low,
     /// Standard limit order
+    #[default]
     Medium,
     /// Aggressive limit order, cross spread if needed
     High,
@@ -20,37 +34,118 @@ pub enum Urgency {
     Immediate,
 }
 
-impl Default for Urgency {
-    fn default() -> Self {
-        Urgency::Medium
-    }
-}
-
 /// Trading signal generated by a strategy.
 #[derive(Debug, Clone)]
 pub enum Signal {
-    /// Buy signal
+    /// Buy signal, placed as a limit order. A `post_only` order is rejected
+    /// rather than taking liquidity; with `expires_at` the exchange cancels
+    /// it at that time if it is still resting (good 'til date).
     Buy {
         token_id: String,
         price: Decimal,
         size: Decimal,
         urgency: Urgency,
+        post_only: bool,
+        expires_at: Option<DateTime<Utc>>,
     },
-    /// Sell signal
+    /// Sell signal, placed like a buy
     Sell {
         token_id: String,
         price: Decimal,
         size: Decimal,
         urgency: Urgency,
+        post_only: bool,
+        expires_at: Option<DateTime<Utc>>,
+    },
+    /// Market order, filled right away against the book and never left
+    /// resting: `amount` is the USDC to spend when buying, or the shares to
+    /// sell when selling, at prices no worse than `worst_price` (which also
+    /// sizes the order for risk checks). `time_in_force` is `Fok` (all or
+    /// nothing) or `Fak` (whatever fills, the rest is cancelled).
+    Market {
+        token_id: String,
+        is_buy: bool,
+        amount: Decimal,
+        worst_price: Decimal,
+        time_in_force: TimeInForce,
     },
     /// Cancel existing orders for a token
     Cancel { token_id: String },
+    /// The buy and sell orders the strategy wants resting on a token in
+    /// place of its current ones: orders already resting at the same price
+    /// and about the same size are kept (and keep their queue priority), the
+    /// others are cancelled and the missing ones placed. Orders are placed as
+    /// given, without laddering.
+    Replace { token_id: String, orders: Vec<Signal> },
+    /// A large BUY or SELL worked over time as smaller child orders (TWAP
+    /// slices or iceberg clips, see [`crate::execution`]). Each child is
+    /// risk-checked when it is placed.
+    Algo { order: Box<Signal>, algo: ExecutionAlgo },
+    /// BUY and SELL orders that only make sense together, such as both
+    /// outcomes of a market: every leg is risk-checked, all are placed at the
+    /// size the most constrained leg allows (or none if one is rejected), and
+    /// they go out concurrently. If a leg fails to place, the legs that were
+    /// placed are cancelled.
+    Paired { legs: Vec<Signal> },
+    /// Convert `size` held NO shares of each of `token_ids` (outcomes of one
+    /// negRisk event) through the NegRisk adapter: NO shares of `k` outcomes
+    /// become `(k - 1) * size` USDC plus `size` YES shares of every other
+    /// outcome of the event (see [`crate::conversion`]).
+    Convert { token_ids: Vec<String>, size: Decimal },
     /// No action
     Hold,
+    /// Switch the engine's reduce-only mode on or off: while on, only orders
+    /// that shrink a position are placed (for winding positions down)
+    ReduceOnly { enabled: bool, reason: String },
+    /// Attach exit rules to the position in a token (see [`crate::exits`]):
+    /// it is sold once the best bid falls to `stop_loss` or rises to
+    /// `take_profit`, or at `time_stop`. All None detaches them.
+    Exit {
+        token_id: String,
+        stop_loss: Option<Decimal>,
+        take_profit: Option<Decimal>,
+        time_stop: Option<DateTime<Utc>>,
+    },
     /// Request graceful shutdown with a reason
     Shutdown { reason: String },
 }
 
+impl Signal {
+    /// Token, side (true = buy), price and size in shares of an order signal
+    /// (a market order at its worst price). None for other signals.
+    pub fn order_terms(&self) -> Option<(&str, bool, Decimal, Decimal)> {
+        match self {
+            Signal::Buy { token_id, price, size, .. } => Some((token_id, true, *price, *size)),
+            Signal::Sell { token_id, price, size, .. } => Some((token_id, false, *price, *size)),
+            Signal::Market { token_id, is_buy, amount, worst_price, .. } => {
+                let size = if *is_buy && !worst_price.is_zero() { *amount / *worst_price } else { *amount };
+                Some((token_id, *is_buy, *worst_price, size))
+            }
+            _ => None,
+        }
+    }
+
+    /// This order signal for `size` shares instead (other signals unchanged).
+    pub fn with_size(&self, size: Decimal) -> Signal {
+        let mut signal = self.clone();
+        match &mut signal {
+            Signal::Buy { size: s, .. } | Signal::Sell { size: s, .. } => *s = size,
+            Signal::Market { is_buy, amount, worst_price, .. } => {
+                *amount = if *is_buy { size * *worst_price } else { size };
+            }
+            _ => {}
+        }
+        signal
+    }
+}
+
+/// A signal with the strategy that generated it.
+#[derive(Debug, Clone)]
+pub struct StrategySignal {
+    pub strategy_id: String,
+    pub signal: Signal,
+}
+
 /// Market metadata from Gamma API.
 ///
 /// This provides information about the market that a token belongs to,
@@ -69,6 +164,29 @@ pub struct MarketInfo {
     pub hours_until_expiry: Option<f64>,
     /// Total liquidity in USDC (from Gamma API)
     pub liquidity: Option<f64>,
+    /// Slug of the parent event (shared by all outcomes of a multi-outcome event)
+    pub event_slug: Option<String>,
+    /// Whether the parent event is negRisk (exactly one outcome resolves YES)
+    pub neg_risk: bool,
+    /// Full market detail (volume, min order size, UMA bond, ...), fetched
+    /// lazily for tokens the engine is trading
+    pub detail: Option<MarketDetail>,
+    /// Market category (e.g., "politics", "crypto")
+    pub category: Option<String>,
+    /// Index of this token's outcome among the market's outcomes
+    pub outcome_index: Option<usize>,
+    /// Token IDs of all the market's outcomes, by outcome index (empty if unknown)
+    pub outcome_tokens: Vec<String>,
+    /// Token of the other outcome of a binary market
+    pub sibling_token_id: Option<String>,
+    /// Whether only strategies that receive all outcomes see this token (it
+    /// is not the market's highest-certainty outcome, see
+    /// [`Strategy::all_outcomes`])
+    pub secondary: bool,
+    /// NegRisk adapter market ID of the parent event (negRisk markets only)
+    pub neg_risk_market_id: Option<String>,
+    /// Index of the market's question in its negRisk event
+    pub question_index: Option<u8>,
 }
 
 impl MarketInfo {
@@ -103,12 +221,66 @@ impl MarketInfo {
             end_date,
             hours_until_expiry,
             liquidity,
+            event_slug: None,
+            neg_risk: false,
+            detail: None,
+            category: None,
+            outcome_index: None,
+            outcome_tokens: Vec::new(),
+            sibling_token_id: None,
+            secondary: false,
+            neg_risk_market_id: None,
+            question_index: None,
+        }
+    }
+
+    /// Attach the parent event, making this token part of the event's basket.
+    pub fn with_event(mut self, event_slug: String, neg_risk: bool) -> Self {
+        self.event_slug = Some(event_slug);
+        self.neg_risk = neg_risk;
+        self
+    }
+
+    /// Attach this token's outcome index and the tokens of all the market's
+    /// outcomes (the sibling token too, for a binary market).
+    pub fn with_outcomes(mut self, index: usize, tokens: Vec<String>) -> Self {
+        if let [first, second] = tokens.as_slice() {
+            self.sibling_token_id = [second, first].get(index).map(|t| t.to_string());
         }
+        self.outcome_index = Some(index);
+        self.outcome_tokens = tokens;
+        self
+    }
+
+    /// Attach the negRisk adapter market ID and question index, which
+    /// conversions of the token are sent against.
+    pub fn with_neg_risk_question(mut self, market_id: Option<String>, index: Option<u8>) -> Self {
+        self.neg_risk_market_id = market_id;
+        self.question_index = index;
+        self
+    }
+
+    /// Only show this token to strategies that receive all outcomes.
+    pub fn secondary(mut self) -> Self {
+        self.secondary = true;
+        self
+    }
+
+    /// Attach the market category.
+    pub fn with_category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Attach full market detail.
+    pub fn with_detail(mut self, detail: MarketDetail) -> Self {
+        self.detail = Some(detail);
+        self
     }
 }
 
 /// Context provided to strategies for decision making.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct StrategyContext {
     /// Current timestamp
     pub timestamp: DateTime<Utc>,
@@ -122,10 +294,184 @@ pub struct StrategyContext {
     pub unrealized_pnl: Decimal,
     /// Total realized P&L
     pub realized_pnl: Decimal,
-    /// Available USDC balance for trading
+    /// Available USDC balance for trading (a paper balance in dry-run)
     pub usdc_balance: Decimal,
+    /// Fraction of the full Kelly stake [`Self::kelly_size`] sizes to
+    pub kelly_fraction: Decimal,
+    /// Last trade by token ID (tokens that have traded since startup)
+    pub last_trades: HashMap<String, LastTrade>,
+    /// Rolling volatility and order-flow statistics by token ID
+    pub stats: HashMap<String, MarketStats>,
+}
+
+impl StrategyContext {
+    /// Last trade in a token, if one has been seen.
+    pub fn last_trade(&self, token_id: &str) -> Option<&LastTrade> {
+        self.last_trades.get(token_id)
+    }
+
+    /// Whether a token's book is missing or hasn't been updated for longer
+    /// than `max_age`. The engine stops trading stale tokens on its own (see
+    /// `PMENGINE_BOOK_STALE_SECS`); strategies can use a tighter bound.
+    pub fn is_stale(&self, token_id: &str, max_age: Duration) -> bool {
+        self.order_books.get(token_id).is_none_or(|book| book.is_stale(max_age))
+    }
+
+    /// Rolling statistics of a token's recent book updates and trades.
+    pub fn stats(&self, token_id: &str) -> Option<&MarketStats> {
+        self.stats.get(token_id)
+    }
+
+    /// Realized mid-price volatility of a token (see [`MarketStats`]).
+    pub fn volatility(&self, token_id: &str) -> Option<Decimal> {
+        self.stats(token_id)?.volatility
+    }
+
+    /// Net top-of-book order flow of a token, in shares.
+    pub fn order_flow_imbalance(&self, token_id: &str) -> Option<Decimal> {
+        self.stats(token_id)?.order_flow_imbalance
+    }
+
+    /// Return of a token's mid price over its recent book updates.
+    pub fn mid_return(&self, token_id: &str) -> Option<Decimal> {
+        self.stats(token_id)?.mid_return
+    }
+
+    /// Buy versus sell volume of a token's recent trades, from -1 to 1.
+    pub fn trade_imbalance(&self, token_id: &str) -> Option<Decimal> {
+        self.stats(token_id)?.trade_imbalance
+    }
+
+    /// USDC balance plus the marked value of held positions.
+    pub fn equity(&self) -> Decimal {
+        add(self.usdc_balance, self.positions.total_notional(), "equity")
+    }
+
+    /// Shares of a token to hold when buying at `price` with an estimated win
+    /// `probability`: fractional Kelly on the equity (see [`crate::sizing`]).
+    pub fn kelly_size(&self, probability: Decimal, price: Decimal) -> Decimal {
+        sizing::kelly_size(probability, price, self.equity(), self.kelly_fraction)
+    }
+
+    /// Price the YES outcomes of an event as a basket.
+    pub fn basket(&self, event_slug: &str) -> Option<Basket> {
+        Basket::for_event(self, event_slug)
+    }
+
+    /// All negRisk event baskets in this context.
+    pub fn baskets(&self) -> Vec<Basket> {
+        Basket::all_neg_risk(self)
+    }
+
+    /// Both outcomes of every binary market in this context, each priced as
+    /// a two-leg basket.
+    pub fn pairs(&self) -> Vec<Basket> {
+        Basket::all_pairs(self)
+    }
+
+    /// This context with `markets` limited to the given (lowercase) Gamma
+    /// categories. Markets without a category are dropped.
+    pub fn restricted_to(&self, categories: &[String]) -> StrategyContext {
+        let markets = self
+            .markets
+            .iter()
+            .filter(|(_, m)| in_categories(m.category.as_deref(), categories))
+            .map(|(token_id, m)| (token_id.clone(), m.clone()))
+            .collect();
+        StrategyContext {
+            markets,
+            ..self.clone()
+        }
+    }
+
+    /// This context without the secondary outcomes of markets (see
+    /// [`MarketInfo::secondary`]).
+    pub fn primary_outcomes(&self) -> StrategyContext {
+        let markets = self
+            .markets
+            .iter()
+            .filter(|(_, m)| !m.secondary)
+            .map(|(token_id, m)| (token_id.clone(), m.clone()))
+            .collect();
+        StrategyContext {
+            markets,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+impl StrategyContext {
+    /// Context for unit tests: `order_books` and `markets` as of now, no
+    /// positions, a 1000 USDC balance and the default Kelly fraction.
+    pub fn for_test(order_books: HashMap<String, Arc<OrderBook>>, markets: HashMap<String, MarketInfo>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            order_books,
+            markets,
+            usdc_balance: Decimal::from(1000),
+            kelly_fraction: sizing::DEFAULT_KELLY_FRACTION,
+            ..Default::default()
+        }
+    }
 }
 
+/// Whether a market category is one of `categories` (case-insensitive).
+pub(crate) fn in_categories(category: Option<&str>, categories: &[String]) -> bool {
+    category.is_some_and(|c| categories.iter().any(|allowed| c.eq_ignore_ascii_case(allowed)))
+}
+
+/// Strategy parameter overrides by name (e.g. `MIN_EDGE`), from
+/// `PMENGINE_STRATEGY_PARAMS` or a config file's `[strategies.<name>.params]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StrategyParams {
+    values: HashMap<String, String>,
+}
+
+impl StrategyParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter (names are matched case-insensitively).
+    pub fn insert(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_uppercase(), value.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Parameter names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.values.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    /// A parameter parsed as `T` (None if not set).
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.values.get(&name.to_uppercase()) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid value for {}: {}", name, value)),
+            None => Ok(None),
+        }
+    }
+
+    /// Error if any parameter is not one of `known`.
+    pub fn check_known(&self, known: &[&str]) -> Result<(), String> {
+        match self.names().into_iter().find(|name| !known.contains(name)) {
+            Some(unknown) => Err(format!(
+                "unknown parameter {} (expected one of {})",
+                unknown,
+                known.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+}
 
 /// Trait for implementing trading strategies.
 pub trait Strategy: Send + Sync {
@@ -139,29 +485,272 @@ pub trait Strategy: Send + Sync {
     /// Returns signals for order management.
     fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal>;
 
+    /// Called once before the first tick with parameter overrides from the
+    /// configuration. Strategies whose parameters are compiled in reject any.
+    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {
+        match params.names().first() {
+            Some(name) => Err(format!("parameter {} is not tunable at runtime", name)),
+            None => Ok(()),
+        }
+    }
+
+    /// How often to call [`on_book_update`](Strategy::on_book_update) per
+    /// token at most. None (the default) opts out: the strategy only runs
+    /// on ticks.
+    fn book_update_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called as soon as a subscribed token's book changes, between ticks,
+    /// for strategies that opt in with `book_update_interval`. Returns
+    /// signals like `on_tick`; the last tick's context is not refreshed.
+    fn on_book_update(&mut self, _token_id: &str, _book: &OrderBook) -> Vec<Signal> {
+        Vec::new()
+    }
+
     /// Called when an order is filled.
     fn on_fill(&mut self, _fill: &Fill) {}
 
+    /// Called with signals from outside the engine (news classifiers,
+    /// alerts) received by the webhook, between ticks. Returns signals like
+    /// `on_tick`; the last tick's context is not refreshed.
+    fn on_external_event(&mut self, _event: &ExternalEvent) -> Vec<Signal> {
+        Vec::new()
+    }
+
+    /// Called at startup with a token's recent price history (oldest first),
+    /// when warm start is enabled, so indicators can be primed before the
+    /// first tick.
+    fn on_history(&mut self, _token_id: &str, _history: &[PricePoint]) {}
+
     /// Called on shutdown for cleanup.
     fn on_shutdown(&mut self) {}
+
+    /// Whether this strategy prices multi-outcome baskets.
+    ///
+    /// When true and market discovery is enabled, the engine also discovers
+    /// negRisk events and subscribes to the YES token of every outcome.
+    fn needs_baskets(&self) -> bool {
+        false
+    }
+
+    /// Whether this strategy receives every outcome of discovered markets.
+    ///
+    /// Discovery only picks a market's highest-certainty outcome, so that
+    /// strategies don't buy the wrong side. When true, the engine also
+    /// subscribes to the market's other outcomes and this strategy (only)
+    /// sees them in `ctx.markets`, for arbitrage, hedging or exiting through
+    /// the opposite side. Outcomes are linked through
+    /// [`MarketInfo::outcome_tokens`] and [`MarketInfo::sibling_token_id`].
+    fn all_outcomes(&self) -> bool {
+        false
+    }
+
+    /// Markets this strategy wants market discovery to find.
+    ///
+    /// Discovery keeps a market when it passes the configured filters and
+    /// every filter of at least one strategy; an empty list (the default)
+    /// takes every market the configuration keeps.
+    fn discovery_filters(&self) -> Vec<DiscoveryFilter> {
+        Vec::new()
+    }
+}
+
+/// Per-strategy `on_tick` time budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickBudget {
+    /// Maximum time a single `on_tick` call should take
+    pub budget: Duration,
+    /// Consecutive over-budget ticks before the strategy is quarantined
+    pub max_overruns: u32,
+}
+
+/// A strategy that was just quarantined for exceeding its tick budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quarantined {
+    pub strategy_id: String,
+    /// Tokens the strategy traded that no active strategy still trades
+    /// (their orders should be cancelled)
+    pub tokens: Vec<String>,
+}
+
+/// Timing and quarantine state for one strategy.
+#[derive(Debug, Default)]
+struct StrategyHealth {
+    /// Consecutive over-budget ticks
+    overruns: u32,
+    quarantined: bool,
+    /// Outside the strategy's trading windows
+    off_schedule: bool,
+    /// Tokens the strategy has emitted orders for
+    tokens: HashSet<String>,
+    /// Last `on_book_update` call per token
+    book_updates: HashMap<String, Instant>,
+}
+
+impl StrategyHealth {
+    /// Remember the tokens a strategy trades.
+    fn record(&mut self, signal: &Signal) {
+        let token_id = match signal {
+            Signal::Replace { token_id, .. } => Some(token_id.as_str()),
+            Signal::Algo { order, .. } => order.order_terms().map(|(token_id, ..)| token_id),
+            _ => signal.order_terms().map(|(token_id, ..)| token_id),
+        };
+        if let Some(token_id) = token_id {
+            if !self.tokens.contains(token_id) {
+                self.tokens.insert(token_id.to_string());
+            }
+        }
+    }
+
+    /// Count a call against the budget, returning whether the strategy has
+    /// just been quarantined.
+    fn check_budget(&mut self, strategy_id: &str, elapsed: Duration, budget: Option<TickBudget>) -> bool {
+        let Some(budget) = budget else {
+            return false;
+        };
+        if elapsed <= budget.budget {
+            self.overruns = 0;
+            return false;
+        }
+
+        self.overruns += 1;
+        tracing::warn!(
+            strategy_id = strategy_id,
+            elapsed_ms = elapsed.as_millis() as u64,
+            budget_ms = budget.budget.as_millis() as u64,
+            overruns = self.overruns,
+            "Strategy exceeded tick budget"
+        );
+        if self.overruns < budget.max_overruns {
+            return false;
+        }
+        tracing::error!(
+            strategy_id = strategy_id,
+            overruns = self.overruns,
+            "Strategy quarantined: repeatedly exceeded tick budget"
+        );
+        self.quarantined = true;
+        true
+    }
 }
 
 /// Runtime for executing multiple strategies.
 pub struct StrategyRuntime {
     strategies: Vec<Box<dyn Strategy>>,
+    /// Health of each strategy (same order as `strategies`)
+    health: Vec<StrategyHealth>,
+    /// Tick budget (None = strategies are not timed)
+    budget: Option<TickBudget>,
+    /// Strategies quarantined since the last `take_quarantined`
+    newly_quarantined: Vec<Quarantined>,
+    /// Gamma categories each strategy may trade, by strategy ID
+    category_scopes: HashMap<String, Vec<String>>,
+    /// Filter stages run in front of each strategy, by strategy ID
+    filters: HashMap<String, Vec<Filter>>,
+    /// Trading windows of each strategy, by strategy ID
+    schedules: HashMap<String, Schedule>,
 }
 
 impl StrategyRuntime {
     pub fn new() -> Self {
         Self {
             strategies: Vec::new(),
+            health: Vec::new(),
+            budget: None,
+            newly_quarantined: Vec::new(),
+            category_scopes: HashMap::new(),
+            filters: HashMap::new(),
+            schedules: HashMap::new(),
         }
     }
 
-    /// Register a strategy.
+    /// Enforce a per-strategy tick budget.
+    pub fn set_budget(&mut self, budget: Option<TickBudget>) {
+        self.budget = budget;
+    }
+
+    /// Restrict strategies to Gamma categories (by strategy ID). Listed
+    /// strategies only see markets of their categories in `ctx.markets`.
+    pub fn set_category_scopes(&mut self, scopes: HashMap<String, Vec<String>>) {
+        self.category_scopes = scopes;
+    }
+
+    /// Put filter stages in front of strategies (by strategy ID). Applies to
+    /// strategies registered afterwards.
+    pub fn set_filters(&mut self, filters: HashMap<String, Vec<Filter>>) {
+        self.filters = filters;
+    }
+
+    /// Limit strategies to trading windows (by strategy ID), enforced from
+    /// the next `update_schedules`.
+    pub fn set_schedules(&mut self, schedules: HashMap<String, Schedule>) {
+        self.schedules = schedules;
+    }
+
+    /// Check every scheduled strategy against its trading windows at `now`.
+    /// A strategy outside them is skipped until one opens again. Returns the
+    /// strategies that just left their windows (their orders should be
+    /// cancelled).
+    pub fn update_schedules(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut closed = Vec::new();
+        for (strategy, health) in self.strategies.iter().zip(&mut self.health) {
+            let Some(schedule) = self.schedules.get(strategy.id()) else {
+                continue;
+            };
+            let off_schedule = !schedule.is_open(now);
+            if off_schedule == health.off_schedule {
+                continue;
+            }
+            health.off_schedule = off_schedule;
+            if off_schedule {
+                tracing::info!(strategy_id = strategy.id(), "Trading window closed, strategy paused");
+                closed.push(strategy.id().to_string());
+            } else {
+                tracing::info!(strategy_id = strategy.id(), "Trading window open, strategy resumed");
+            }
+        }
+        closed
+    }
+
+    /// Register a strategy, wrapped in a [`Pipeline`] if filters are configured for it.
     pub fn register(&mut self, strategy: Box<dyn Strategy>) {
         tracing::info!(strategy_id = strategy.id(), "Registering strategy");
+        let strategy: Box<dyn Strategy> = match self.filters.get(strategy.id()) {
+            Some(filters) if !filters.is_empty() => {
+                tracing::info!(strategy_id = strategy.id(), ?filters, "Strategy filters configured");
+                Box::new(Pipeline::new(strategy).filters(filters.iter().cloned()))
+            }
+            _ => strategy,
+        };
         self.strategies.push(strategy);
+        self.health.push(StrategyHealth::default());
+    }
+
+    /// Shut down and remove a strategy, leaving the others untouched.
+    /// Returns the tokens it subscribed to that no remaining strategy does
+    /// (None if no strategy has that ID).
+    pub fn unregister(&mut self, strategy_id: &str) -> Option<Vec<String>> {
+        let idx = self.strategies.iter().position(|s| s.id() == strategy_id)?;
+        let mut strategy = self.strategies.remove(idx);
+        self.health.remove(idx);
+        tracing::info!(strategy_id = strategy_id, "Unregistering strategy");
+        strategy.on_shutdown();
+
+        let remaining = self.all_subscriptions();
+        let mut unneeded: Vec<String> = strategy
+            .subscriptions()
+            .into_iter()
+            .filter(|t| !remaining.contains(t))
+            .collect();
+        unneeded.sort();
+        unneeded.dedup();
+        Some(unneeded)
+    }
+
+    /// Whether a strategy with this ID is registered.
+    pub fn contains(&self, strategy_id: &str) -> bool {
+        self.strategies.iter().any(|s| s.id() == strategy_id)
     }
 
     /// Get all token subscriptions from all strategies.
@@ -176,19 +765,236 @@ impl StrategyRuntime {
         subs
     }
 
-    /// Run all strategies and collect signals.
-    pub fn tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+    /// Run all active strategies and collect signals, tagged with the
+    /// strategy that generated them.
+    ///
+    /// With a budget set, each `on_tick` is timed; a strategy that overruns
+    /// `max_overruns` ticks in a row is quarantined and skipped from then on.
+    pub fn tick(&mut self, ctx: &StrategyContext) -> Vec<StrategySignal> {
         let mut all_signals = Vec::new();
-        for strategy in &mut self.strategies {
-            let signals = strategy.on_tick(ctx);
+        let mut quarantined_now = Vec::new();
+        for (idx, (strategy, health)) in self.strategies.iter_mut().zip(&mut self.health).enumerate() {
+            if health.quarantined || health.off_schedule {
+                continue;
+            }
+
+            let mut scoped = self.category_scopes.get(strategy.id()).map(|c| ctx.restricted_to(c));
+            if !strategy.all_outcomes() && ctx.markets.values().any(|m| m.secondary) {
+                scoped = Some(scoped.as_ref().unwrap_or(ctx).primary_outcomes());
+            }
+            let view = scoped.as_ref().unwrap_or(ctx);
+
+            let started = Instant::now();
+            let signals = strategy.on_tick(view);
+            let elapsed = started.elapsed();
+
             for signal in signals {
                 tracing::debug!(strategy_id = strategy.id(), ?signal, "Strategy signal");
-                all_signals.push(signal);
+                health.record(&signal);
+                all_signals.push(StrategySignal {
+                    strategy_id: strategy.id().to_string(),
+                    signal,
+                });
+            }
+
+            if health.check_budget(strategy.id(), elapsed, self.budget) {
+                quarantined_now.push(idx);
+            }
+        }
+
+        self.hand_over(quarantined_now);
+        all_signals
+    }
+
+    /// Run `on_book_update` for a token whose book just changed, on every
+    /// active strategy that opted in, may trade the token's market and last
+    /// saw this token at least its `book_update_interval` ago. Category
+    /// scopes and filter stages apply as on ticks; `market` is None for
+    /// tokens without Gamma metadata. Calls count against the tick budget.
+    pub fn book_update(
+        &mut self,
+        token_id: &str,
+        book: &OrderBook,
+        market: Option<&MarketInfo>,
+    ) -> Vec<StrategySignal> {
+        let mut all_signals = Vec::new();
+        let mut quarantined_now = Vec::new();
+        for (idx, (strategy, health)) in self.strategies.iter_mut().zip(&mut self.health).enumerate() {
+            let Some(interval) = strategy.book_update_interval() else {
+                continue;
+            };
+            if health.quarantined
+                || health.off_schedule
+                || health.book_updates.get(token_id).is_some_and(|last| last.elapsed() < interval)
+            {
+                continue;
+            }
+            let in_scope = self.category_scopes.get(strategy.id()).is_none_or(|categories| {
+                market.is_none_or(|m| in_categories(m.category.as_deref(), categories))
+            }) && (strategy.all_outcomes() || market.is_none_or(|m| !m.secondary));
+            let filtered = self
+                .filters
+                .get(strategy.id())
+                .is_some_and(|filters| !filters.iter().all(|f| f.keep(market, Some(book))));
+            if !in_scope || filtered {
+                continue;
+            }
+
+            health.book_updates.insert(token_id.to_string(), Instant::now());
+            let started = Instant::now();
+            let signals = strategy.on_book_update(token_id, book);
+            let elapsed = started.elapsed();
+
+            for signal in signals {
+                tracing::debug!(strategy_id = strategy.id(), token_id, ?signal, "Strategy signal");
+                health.record(&signal);
+                all_signals.push(StrategySignal {
+                    strategy_id: strategy.id().to_string(),
+                    signal,
+                });
+            }
+
+            if health.check_budget(strategy.id(), elapsed, self.budget) {
+                quarantined_now.push(idx);
             }
         }
+
+        self.hand_over(quarantined_now);
         all_signals
     }
 
+    /// Run `on_external_event` on the active strategy the event names, or on
+    /// every active one. An event about a token only reaches strategies
+    /// allowed to trade its market (`market`, None for tokens without Gamma
+    /// metadata). Calls count against the tick budget.
+    pub fn external_event(
+        &mut self,
+        event: &ExternalEvent,
+        market: Option<&MarketInfo>,
+    ) -> Vec<StrategySignal> {
+        let mut all_signals = Vec::new();
+        let mut quarantined_now = Vec::new();
+        for (idx, (strategy, health)) in self.strategies.iter_mut().zip(&mut self.health).enumerate() {
+            if health.quarantined
+                || health.off_schedule
+                || event.strategy_id.as_deref().is_some_and(|id| id != strategy.id())
+            {
+                continue;
+            }
+            let in_scope = self.category_scopes.get(strategy.id()).is_none_or(|categories| {
+                market.is_none_or(|m| in_categories(m.category.as_deref(), categories))
+            }) && (strategy.all_outcomes() || market.is_none_or(|m| !m.secondary));
+            if !in_scope {
+                continue;
+            }
+
+            let started = Instant::now();
+            let signals = strategy.on_external_event(event);
+            let elapsed = started.elapsed();
+
+            for signal in signals {
+                tracing::debug!(
+                    strategy_id = strategy.id(),
+                    source = event.source.as_str(),
+                    ?signal,
+                    "Strategy signal"
+                );
+                health.record(&signal);
+                all_signals.push(StrategySignal {
+                    strategy_id: strategy.id().to_string(),
+                    signal,
+                });
+            }
+
+            if health.check_budget(strategy.id(), elapsed, self.budget) {
+                quarantined_now.push(idx);
+            }
+        }
+
+        self.hand_over(quarantined_now);
+        all_signals
+    }
+
+    /// Queue newly quarantined strategies for `take_quarantined`, with the
+    /// tokens no active strategy still trades.
+    fn hand_over(&mut self, quarantined_now: Vec<usize>) {
+        if quarantined_now.is_empty() {
+            return;
+        }
+        let active: HashSet<&String> = self
+            .health
+            .iter()
+            .filter(|h| !h.quarantined)
+            .flat_map(|h| &h.tokens)
+            .collect();
+        for idx in quarantined_now {
+            let mut tokens: Vec<String> = self.health[idx]
+                .tokens
+                .iter()
+                .filter(|t| !active.contains(t))
+                .cloned()
+                .collect();
+            tokens.sort();
+            self.newly_quarantined.push(Quarantined {
+                strategy_id: self.strategies[idx].id().to_string(),
+                tokens,
+            });
+        }
+    }
+
+    /// Drain strategies quarantined since the last call.
+    pub fn take_quarantined(&mut self) -> Vec<Quarantined> {
+        std::mem::take(&mut self.newly_quarantined)
+    }
+
+    /// Whether a strategy has been quarantined.
+    pub fn is_quarantined(&self, strategy_id: &str) -> bool {
+        self.strategies
+            .iter()
+            .zip(&self.health)
+            .any(|(s, h)| h.quarantined && s.id() == strategy_id)
+    }
+
+    /// Strategy trading each token, by the token's first trader in
+    /// registration order.
+    pub fn token_owners(&self) -> HashMap<String, String> {
+        let mut owners = HashMap::new();
+        for (strategy, health) in self.strategies.iter().zip(&self.health) {
+            for token_id in &health.tokens {
+                owners
+                    .entry(token_id.clone())
+                    .or_insert_with(|| strategy.id().to_string());
+            }
+        }
+        owners
+    }
+
+    /// Whether any registered strategy needs negRisk event baskets.
+    pub fn needs_baskets(&self) -> bool {
+        self.strategies.iter().any(|s| s.needs_baskets())
+    }
+
+    /// Whether any registered strategy receives every outcome of markets.
+    pub fn needs_all_outcomes(&self) -> bool {
+        self.strategies.iter().any(|s| s.all_outcomes())
+    }
+
+    /// Discovery criteria of every registered strategy.
+    pub fn discovery_filters(&self) -> Vec<Vec<DiscoveryFilter>> {
+        self.strategies.iter().map(|s| s.discovery_filters()).collect()
+    }
+
+    /// Feed a token's price history to every strategy allowed to trade the
+    /// token's category.
+    pub fn warm_start(&mut self, token_id: &str, category: Option<&str>, history: &[PricePoint]) {
+        for strategy in &mut self.strategies {
+            let allowed = self.category_scopes.get(strategy.id());
+            if allowed.is_none_or(|categories| in_categories(category, categories)) {
+                strategy.on_history(token_id, history);
+            }
+        }
+    }
+
     /// Notify all strategies of a fill.
     pub fn on_fill(&mut self, fill: &Fill) {
         for strategy in &mut self.strategies {
@@ -253,3 +1059,364 @@ impl Strategy for DummyStrategy {
         vec![Signal::Hold]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    /// Strategy that buys one token and takes `delay` per tick.
+    struct Slow {
+        id: String,
+        token: String,
+        delay: Duration,
+    }
+
+    impl Strategy for Slow {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn subscriptions(&self) -> Vec<String> {
+            vec![self.token.clone()]
+        }
+
+        fn on_tick(&mut self, _ctx: &StrategyContext) -> Vec<Signal> {
+            std::thread::sleep(self.delay);
+            vec![Signal::Buy {
+                token_id: self.token.clone(),
+                price: dec!(0.5),
+                size: dec!(10),
+                urgency: Urgency::Medium,
+                post_only: false,
+                expires_at: None,
+            }]
+        }
+    }
+
+    fn strategy(id: &str, token: &str, delay_ms: u64) -> Box<dyn Strategy> {
+        Box::new(Slow {
+            id: id.to_string(),
+            token: token.to_string(),
+            delay: Duration::from_millis(delay_ms),
+        })
+    }
+
+    fn empty_context() -> StrategyContext {
+        StrategyContext::for_test(HashMap::new(), HashMap::new())
+    }
+
+    #[test]
+    fn test_slow_strategy_is_quarantined() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.set_budget(Some(TickBudget {
+            budget: Duration::from_millis(20),
+            max_overruns: 2,
+        }));
+        runtime.register(strategy("fast", "shared", 0));
+        runtime.register(strategy("slow", "shared", 40));
+        runtime.register(strategy("slow_only", "own", 40));
+        let ctx = empty_context();
+
+        assert_eq!(runtime.tick(&ctx).len(), 3);
+        assert!(runtime.take_quarantined().is_empty());
+
+        // Second consecutive overrun quarantines both slow strategies
+        runtime.tick(&ctx);
+        let quarantined = runtime.take_quarantined();
+        assert_eq!(quarantined.len(), 2);
+        // The shared token is still traded by "fast", so only "own" is handed over
+        assert!(quarantined[0].tokens.is_empty());
+        assert_eq!(quarantined[1].tokens, vec!["own".to_string()]);
+        assert!(runtime.is_quarantined("slow"));
+        assert!(!runtime.is_quarantined("fast"));
+
+        // Quarantined strategies are skipped
+        assert_eq!(runtime.tick(&ctx).len(), 1);
+        assert!(runtime.take_quarantined().is_empty());
+    }
+
+    #[test]
+    fn test_no_budget_never_quarantines() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.register(strategy("slow", "token", 5));
+        let ctx = empty_context();
+
+        for _ in 0..3 {
+            runtime.tick(&ctx);
+        }
+        assert!(runtime.take_quarantined().is_empty());
+        assert!(!runtime.is_quarantined("slow"));
+    }
+
+    /// Strategy that buys every market it can see.
+    struct BuyAll(&'static str);
+
+    impl Strategy for BuyAll {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        fn subscriptions(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+            ctx.markets
+                .keys()
+                .map(|token_id| Signal::Buy {
+                    token_id: token_id.clone(),
+                    price: dec!(0.5),
+                    size: dec!(1),
+                    urgency: Urgency::Low,
+                    post_only: false,
+                    expires_at: None,
+                })
+                .collect()
+        }
+    }
+
+    /// [`BuyAll`] receiving every outcome of markets.
+    struct BuyAllOutcomes(BuyAll);
+
+    impl Strategy for BuyAllOutcomes {
+        fn id(&self) -> &str {
+            self.0.id()
+        }
+
+        fn subscriptions(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+            self.0.on_tick(ctx)
+        }
+
+        fn all_outcomes(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_secondary_outcomes_are_opt_in() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.register(Box::new(BuyAll("primary")));
+        assert!(!runtime.needs_all_outcomes());
+        runtime.register(Box::new(BuyAllOutcomes(BuyAll("all"))));
+        assert!(runtime.needs_all_outcomes());
+
+        let tokens = vec!["yes".to_string(), "no".to_string()];
+        let mut ctx = empty_context();
+        let yes = MarketInfo::new(String::new(), "Yes".to_string(), "m".to_string(), None)
+            .with_outcomes(0, tokens.clone());
+        assert_eq!(yes.sibling_token_id.as_deref(), Some("no"));
+        ctx.markets.insert("yes".to_string(), yes);
+        let no = MarketInfo::new(String::new(), "No".to_string(), "m".to_string(), None)
+            .with_outcomes(1, tokens)
+            .secondary();
+        assert_eq!(no.sibling_token_id.as_deref(), Some("yes"));
+        ctx.markets.insert("no".to_string(), no);
+
+        let signals = runtime.tick(&ctx);
+        let traded = |id: &str| {
+            let mut tokens: Vec<&str> = signals
+                .iter()
+                .filter(|s| s.strategy_id == id)
+                .filter_map(|s| s.signal.order_terms().map(|t| t.0))
+                .collect();
+            tokens.sort();
+            tokens
+        };
+        assert_eq!(traded("primary"), ["yes"]);
+        assert_eq!(traded("all"), ["no", "yes"]);
+    }
+
+    #[test]
+    fn test_category_scopes_limit_markets() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.set_category_scopes(HashMap::from([("scoped".to_string(), vec!["crypto".to_string()])]));
+        runtime.register(Box::new(BuyAll("scoped")));
+        runtime.register(Box::new(BuyAll("global")));
+
+        let mut ctx = empty_context();
+        for (token_id, category) in [("btc", Some("Crypto")), ("nfl", Some("Sports")), ("misc", None)] {
+            let info = MarketInfo::new(String::new(), "Yes".to_string(), token_id.to_string(), None)
+                .with_category(category.map(String::from));
+            ctx.markets.insert(token_id.to_string(), info);
+        }
+
+        let signals = runtime.tick(&ctx);
+        assert_eq!(signals.len(), 4);
+        let scoped = signals.iter().filter(|s| s.strategy_id == "scoped").count();
+        assert_eq!(scoped, 1);
+        let owners = runtime.token_owners();
+        assert_eq!(owners["btc"], "scoped");
+        assert_eq!(owners["nfl"], "global");
+        assert_eq!(owners["misc"], "global");
+    }
+
+    /// Strategy that sells into every book update it is sent, and holds on
+    /// external events.
+    struct Reactive {
+        id: &'static str,
+        interval: Option<Duration>,
+    }
+
+    impl Strategy for Reactive {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn subscriptions(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn on_tick(&mut self, _ctx: &StrategyContext) -> Vec<Signal> {
+            Vec::new()
+        }
+
+        fn book_update_interval(&self) -> Option<Duration> {
+            self.interval
+        }
+
+        fn on_book_update(&mut self, token_id: &str, _book: &OrderBook) -> Vec<Signal> {
+            vec![Signal::Sell {
+                token_id: token_id.to_string(),
+                price: dec!(0.5),
+                size: dec!(1),
+                urgency: Urgency::High,
+                post_only: false,
+                expires_at: None,
+            }]
+        }
+
+        fn on_external_event(&mut self, _event: &ExternalEvent) -> Vec<Signal> {
+            vec![Signal::Hold]
+        }
+    }
+
+    #[test]
+    fn test_market_order_terms() {
+        let buy = Signal::Market {
+            token_id: "t".to_string(),
+            is_buy: true,
+            amount: dec!(10),
+            worst_price: dec!(0.40),
+            time_in_force: TimeInForce::Fak,
+        };
+        assert_eq!(buy.order_terms(), Some(("t", true, dec!(0.40), dec!(25))));
+        assert!(matches!(buy.with_size(dec!(5)), Signal::Market { amount, .. } if amount == dec!(2)));
+
+        // A sell's amount is already in shares
+        let sell = Signal::Market {
+            token_id: "t".to_string(),
+            is_buy: false,
+            amount: dec!(10),
+            worst_price: dec!(0.40),
+            time_in_force: TimeInForce::Fok,
+        };
+        assert_eq!(sell.order_terms(), Some(("t", false, dec!(0.40), dec!(10))));
+        assert!(matches!(sell.with_size(dec!(5)), Signal::Market { amount, .. } if amount == dec!(5)));
+        assert_eq!(Signal::Hold.order_terms(), None);
+    }
+
+    #[test]
+    fn test_book_updates_reach_opted_in_strategies_throttled() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.set_category_scopes(HashMap::from([("scoped".to_string(), vec!["crypto".to_string()])]));
+        let interval = Duration::from_millis(50);
+        runtime.register(Box::new(Reactive { id: "fast", interval: Some(Duration::ZERO) }));
+        runtime.register(Box::new(Reactive { id: "slow", interval: Some(interval) }));
+        runtime.register(Box::new(Reactive { id: "scoped", interval: Some(Duration::ZERO) }));
+        runtime.register(Box::new(Reactive { id: "ticks_only", interval: None }));
+
+        let book = OrderBook::new("btc".to_string());
+        let sports = MarketInfo::new(String::new(), "Yes".to_string(), "nfl".to_string(), None)
+            .with_category(Some("Sports".to_string()));
+        let ids = |signals: Vec<StrategySignal>| -> Vec<String> {
+            signals.into_iter().map(|s| s.strategy_id).collect()
+        };
+
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["fast", "slow", "scoped"]);
+        // "slow" is throttled per token, "scoped" can't trade sports markets
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["fast", "scoped"]);
+        assert_eq!(ids(runtime.book_update("nfl", &book, Some(&sports))), vec!["fast", "slow"]);
+
+        std::thread::sleep(interval);
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["fast", "slow", "scoped"]);
+        assert_eq!(runtime.token_owners()["nfl"], "fast");
+    }
+
+    #[test]
+    fn test_external_events_reach_named_or_scoped_strategies() {
+        let mut runtime = StrategyRuntime::new();
+        runtime.set_category_scopes(HashMap::from([("scoped".to_string(), vec!["crypto".to_string()])]));
+        runtime.register(Box::new(Reactive { id: "global", interval: None }));
+        runtime.register(Box::new(Reactive { id: "scoped", interval: None }));
+
+        let sports = MarketInfo::new(String::new(), "Yes".to_string(), "nfl".to_string(), None)
+            .with_category(Some("Sports".to_string()));
+        let ids = |signals: Vec<StrategySignal>| -> Vec<String> {
+            signals.into_iter().map(|s| s.strategy_id).collect()
+        };
+        let mut event = ExternalEvent {
+            source: "news".to_string(),
+            kind: "headline".to_string(),
+            token_id: None,
+            strategy_id: None,
+            data: serde_json::Value::Null,
+            received_at: Utc::now(),
+        };
+
+        assert_eq!(ids(runtime.external_event(&event, None)), vec!["global", "scoped"]);
+        // "scoped" can't trade sports markets
+        event.token_id = Some("nfl".to_string());
+        assert_eq!(ids(runtime.external_event(&event, Some(&sports))), vec!["global"]);
+        event.strategy_id = Some("scoped".to_string());
+        assert!(runtime.external_event(&event, Some(&sports)).is_empty());
+        event.token_id = None;
+        assert_eq!(ids(runtime.external_event(&event, None)), vec!["scoped"]);
+    }
+
+    #[test]
+    fn test_strategies_pause_outside_their_schedule() {
+        let window = |spec| Schedule::new(vec![crate::schedule::Window::parse(spec).unwrap()]);
+        let mut runtime = StrategyRuntime::new();
+        runtime.set_schedules(HashMap::from([("day".to_string(), window("* 09:00-17:00"))]));
+        runtime.register(Box::new(Reactive { id: "day", interval: Some(Duration::ZERO) }));
+        runtime.register(Box::new(Reactive { id: "always", interval: Some(Duration::ZERO) }));
+
+        let book = OrderBook::new("btc".to_string());
+        let ids = |signals: Vec<StrategySignal>| -> Vec<String> {
+            signals.into_iter().map(|s| s.strategy_id).collect()
+        };
+        let at = |hour| Utc.with_ymd_and_hms(2024, 6, 7, hour, 0, 0).unwrap();
+
+        assert!(runtime.update_schedules(at(10)).is_empty());
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["day", "always"]);
+        assert_eq!(runtime.update_schedules(at(17)), vec!["day"]);
+        assert!(runtime.update_schedules(at(18)).is_empty());
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["always"]);
+        assert!(runtime.update_schedules(at(9)).is_empty());
+        assert_eq!(ids(runtime.book_update("btc", &book, None)), vec!["day", "always"]);
+    }
+
+    #[test]
+    fn test_unregister_keeps_shared_subscriptions() {
+        let tokens = |ids: &[&str]| ids.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let mut runtime = StrategyRuntime::new();
+        runtime.register(Box::new(DummyStrategy::new("a", tokens(&["t2", "t1"]))));
+        runtime.register(Box::new(DummyStrategy::new("b", tokens(&["t2", "t3"]))));
+
+        assert_eq!(runtime.unregister("a"), Some(tokens(&["t1"])));
+        assert!(!runtime.contains("a") && runtime.contains("b"));
+        assert_eq!(runtime.all_subscriptions(), tokens(&["t2", "t3"]));
+        assert_eq!(runtime.unregister("a"), None);
+
+        // The remaining strategy keeps running
+        runtime.tick(&empty_context());
+        runtime.register(Box::new(DummyStrategy::new("a", tokens(&["t1"]))));
+        assert!(runtime.contains("a"));
+    }
+}
@@ -0,0 +1,306 @@
+//! Operator-defined synthetic markets for observing strategies in dry-run.
+//!
+//! With `PMENGINE_SYNTHETIC_MARKETS` pointing at a scenario file, a dry-run
+//! engine adds the scenario's markets to the ones it discovers and replays
+//! each market's scripted book on the event loop's clock, so strategies can
+//! be watched reacting to crafted situations (a spread collapsing, certainty
+//! flipping) without waiting for them to happen live:
+//!
+//! ```json
+//! {
+//!   "markets": [{
+//!     "token_id": "synthetic-flip",
+//!     "question": "Will the synthetic market resolve YES?",
+//!     "hours_to_expiry": 2,
+//!     "category": "crypto",
+//!     "loop_secs": 120,
+//!     "script": [
+//!       {"at_secs": 0,  "bids": [["0.94", "500"]], "asks": [["0.96", "500"]]},
+//!       {"at_secs": 30, "bids": [["0.95", "500"]], "asks": [["0.951", "500"]]},
+//!       {"at_secs": 60, "bids": [["0.08", "500"]], "asks": [["0.10", "500"]]}
+//!     ]
+//!   }]
+//! }
+//! ```
+//!
+//! Synthetic tokens never reach the exchange: they are not subscribed on the
+//! WebSocket, and the engine refuses to load a scenario unless it is dry-run.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::orderbook::{Level, OrderBook};
+use crate::strategy::MarketInfo;
+
+/// Markets and scripted books loaded from a scenario file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyntheticScenario {
+    pub markets: Vec<SyntheticMarket>,
+}
+
+/// One synthetic market and its book script.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyntheticMarket {
+    pub token_id: String,
+    pub question: String,
+    #[serde(default = "default_outcome")]
+    pub outcome: String,
+    /// Defaults to the token ID
+    pub slug: Option<String>,
+    /// Expiry relative to when the scenario starts (None = no end date)
+    pub hours_to_expiry: Option<f64>,
+    pub liquidity: Option<f64>,
+    pub category: Option<String>,
+    /// Restart the script after this many seconds (None = hold the last step)
+    pub loop_secs: Option<u64>,
+    /// Book states in time order
+    pub script: Vec<BookStep>,
+}
+
+/// Book state from `at_secs` until the next step.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookStep {
+    pub at_secs: u64,
+    /// `[price, size]` pairs
+    #[serde(default)]
+    pub bids: Vec<(Decimal, Decimal)>,
+    #[serde(default)]
+    pub asks: Vec<(Decimal, Decimal)>,
+}
+
+fn default_outcome() -> String {
+    "Yes".to_string()
+}
+
+impl SyntheticScenario {
+    /// Load and validate a scenario file.
+    pub fn load(path: &Path) -> Result<Self, SyntheticError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| SyntheticError::Io(e.to_string()))?;
+        let scenario: Self =
+            serde_json::from_str(&contents).map_err(|e| SyntheticError::Parse(e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<(), SyntheticError> {
+        let mut seen = HashSet::new();
+        for market in &self.markets {
+            let invalid = |reason: &str| SyntheticError::Invalid(format!("{}: {}", market.token_id, reason));
+            if !seen.insert(market.token_id.as_str()) {
+                return Err(invalid("duplicate token ID"));
+            }
+            if market.script.is_empty() {
+                return Err(invalid("empty script"));
+            }
+            if market.script.windows(2).any(|w| w[0].at_secs >= w[1].at_secs) {
+                return Err(invalid("script steps must be in increasing at_secs order"));
+            }
+            if market.loop_secs.is_some_and(|l| market.script.iter().any(|s| s.at_secs >= l)) {
+                return Err(invalid("loop_secs must be after the last step"));
+            }
+            let prices = market.script.iter().flat_map(|s| s.bids.iter().chain(&s.asks));
+            for (price, size) in prices {
+                if *price <= Decimal::ZERO || *price >= Decimal::ONE || *size <= Decimal::ZERO {
+                    return Err(invalid("levels need a price in (0, 1) and a positive size"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Market info for every synthetic token, with expiries relative to `started`.
+    pub fn market_info(&self, started: DateTime<Utc>) -> Vec<(String, MarketInfo)> {
+        self.markets
+            .iter()
+            .map(|m| {
+                let end_date = m
+                    .hours_to_expiry
+                    .map(|h| started + chrono::Duration::seconds((h * 3600.0) as i64));
+                let info = MarketInfo::with_liquidity(
+                    m.question.clone(),
+                    m.outcome.clone(),
+                    m.slug.clone().unwrap_or_else(|| m.token_id.clone()),
+                    end_date,
+                    m.liquidity,
+                )
+                .with_category(m.category.clone());
+                (m.token_id.clone(), info)
+            })
+            .collect()
+    }
+}
+
+impl SyntheticMarket {
+    /// Index of the step in force `elapsed` into the scenario.
+    fn step_at(&self, elapsed: Duration) -> Option<usize> {
+        let mut secs = elapsed.as_secs();
+        if let Some(period) = self.loop_secs.filter(|p| *p > 0) {
+            secs %= period;
+        }
+        self.script.iter().rposition(|s| s.at_secs <= secs)
+    }
+}
+
+/// Replays a scenario's book scripts.
+pub struct SyntheticFeed {
+    scenario: SyntheticScenario,
+    started: Instant,
+    started_at: DateTime<Utc>,
+    /// Step last published per market
+    published: Vec<Option<usize>>,
+}
+
+impl SyntheticFeed {
+    /// Start the scenario clock.
+    pub fn new(scenario: SyntheticScenario) -> Self {
+        let published = vec![None; scenario.markets.len()];
+        Self {
+            scenario,
+            started: Instant::now(),
+            started_at: Utc::now(),
+            published,
+        }
+    }
+
+    pub fn scenario(&self) -> &SyntheticScenario {
+        &self.scenario
+    }
+
+    /// Market info for every synthetic token.
+    pub fn market_info(&self) -> Vec<(String, MarketInfo)> {
+        self.scenario.market_info(self.started_at)
+    }
+
+    pub fn is_synthetic(&self, token_id: &str) -> bool {
+        self.scenario.markets.iter().any(|m| m.token_id == token_id)
+    }
+
+    /// Books whose scripted step changed since the last poll.
+    pub fn poll(&mut self) -> Vec<OrderBook> {
+        self.books_due(self.started.elapsed())
+    }
+
+    /// Books whose scripted step changed, `elapsed` into the scenario.
+    pub fn books_due(&mut self, elapsed: Duration) -> Vec<OrderBook> {
+        let timestamp = Utc::now().timestamp_millis();
+        let mut books = Vec::new();
+        for (market, published) in self.scenario.markets.iter().zip(&mut self.published) {
+            let step = market.step_at(elapsed);
+            if step == *published {
+                continue;
+            }
+            *published = step;
+            let Some(step) = step.map(|i| &market.script[i]) else {
+                continue;
+            };
+
+            let levels = |pairs: &[(Decimal, Decimal)]| -> Vec<Level> {
+                pairs.iter().map(|(price, size)| Level { price: *price, size: *size }).collect()
+            };
+            let mut book = OrderBook::new(market.token_id.clone());
+            book.bids = levels(&step.bids);
+            book.asks = levels(&step.asks);
+            book.bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+            book.asks.sort_by_key(|l| l.price);
+            book.timestamp = timestamp;
+            books.push(book);
+        }
+        books
+    }
+}
+
+/// Errors loading a synthetic scenario.
+#[derive(Debug)]
+pub enum SyntheticError {
+    Io(String),
+    Parse(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for SyntheticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyntheticError::Io(e) => write!(f, "Cannot read scenario: {}", e),
+            SyntheticError::Parse(e) => write!(f, "Invalid scenario JSON: {}", e),
+            SyntheticError::Invalid(e) => write!(f, "Invalid scenario: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SyntheticError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const SCENARIO: &str = r#"{
+        "markets": [{
+            "token_id": "synthetic-flip",
+            "question": "Will it flip?",
+            "hours_to_expiry": 2,
+            "loop_secs": 90,
+            "script": [
+                {"at_secs": 0, "bids": [["0.93", "100"], ["0.94", "500"]], "asks": [["0.96", 500]]},
+                {"at_secs": 30, "bids": [["0.95", "500"]], "asks": [["0.951", "500"]]},
+                {"at_secs": 60, "bids": [["0.08", "500"]], "asks": [["0.10", "500"]]}
+            ]
+        }]
+    }"#;
+
+    fn scenario(json: &str) -> Result<SyntheticScenario, SyntheticError> {
+        let scenario: SyntheticScenario =
+            serde_json::from_str(json).map_err(|e| SyntheticError::Parse(e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    #[test]
+    fn test_script_replays_and_loops() {
+        let mut feed = SyntheticFeed::new(scenario(SCENARIO).unwrap());
+
+        let books = feed.books_due(Duration::from_secs(0));
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].best_bid().unwrap().price, dec!(0.94));
+        assert_eq!(books[0].spread(), Some(dec!(0.02)));
+
+        // Unchanged step is not republished
+        assert!(feed.books_due(Duration::from_secs(10)).is_empty());
+
+        let books = feed.books_due(Duration::from_secs(31));
+        assert_eq!(books[0].spread(), Some(dec!(0.001)));
+        let books = feed.books_due(Duration::from_secs(61));
+        assert_eq!(books[0].best_bid().unwrap().price, dec!(0.08));
+
+        // 95s loops back to the first step
+        let books = feed.books_due(Duration::from_secs(95));
+        assert_eq!(books[0].best_ask().unwrap().price, dec!(0.96));
+    }
+
+    #[test]
+    fn test_market_info_expiry_is_relative_to_start() {
+        let scenario = scenario(SCENARIO).unwrap();
+        let started = Utc::now();
+        let info = scenario.market_info(started);
+        assert_eq!(info[0].0, "synthetic-flip");
+        assert_eq!(info[0].1.outcome, "Yes");
+        assert_eq!(info[0].1.slug, "synthetic-flip");
+        assert_eq!(info[0].1.end_date, Some(started + chrono::Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_invalid_scenarios_are_rejected() {
+        let market = |script: &str| {
+            format!(r#"{{"markets": [{{"token_id": "t", "question": "q", "script": {}}}]}}"#, script)
+        };
+        assert!(scenario(&market("[]")).is_err());
+        assert!(scenario(&market(r#"[{"at_secs": 10}, {"at_secs": 5}]"#)).is_err());
+        assert!(scenario(&market(r#"[{"at_secs": 0, "bids": [["1.2", "10"]]}]"#)).is_err());
+        assert!(scenario(&market(r#"[{"at_secs": 0, "bids": [["0.5", "10"]]}]"#)).is_ok());
+    }
+}
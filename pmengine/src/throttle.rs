@@ -0,0 +1,313 @@
+//! Client-side rate limiting of the CLOB order endpoints.
+//!
+//! Polymarket rate-limits order placement and cancellation, and a client
+//! that keeps hammering an endpoint after a 429 can get its API key banned.
+//! Every order and cancel request the client sends goes through a
+//! [`Throttle`], with a budget per endpoint in requests per second:
+//!
+//! ```text
+//! PMENGINE_RATE_LIMITS="order:50,cancel:40,cancel_batch:20,cancel_all:5"
+//! ```
+//!
+//! (the defaults; endpoints left out keep theirs, 0 = unlimited). A request
+//! over budget waits until the budget allows it rather than failing, so a
+//! burst of signals is queued and spread out. A 429 response pauses the
+//! endpoint for an exponential [`Backoff`] (from [`RATE_LIMIT_BACKOFF_BASE`]
+//! doubling up to [`RATE_LIMIT_BACKOFF_MAX`]) and the request is retried, up
+//! to [`MAX_RETRIES`] times; the backoff resets after a successful request.
+//! Dry-run requests are never sent, so they are not throttled.
+
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use governor::{DefaultDirectRateLimiter, Quota};
+use tokio::time::Instant;
+
+use crate::client::ClientError;
+use crate::market_feed::Backoff;
+
+/// First pause after a 429.
+pub const RATE_LIMIT_BACKOFF_BASE: Duration = Duration::from_millis(500);
+
+/// Longest pause after repeated 429s.
+pub const RATE_LIMIT_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Retries of a request rejected with a 429 before giving up.
+pub const MAX_RETRIES: u32 = 3;
+
+/// A rate-limited CLOB endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    /// `POST /order`
+    Order,
+    /// `DELETE /order`
+    Cancel,
+    /// `DELETE /orders`
+    CancelBatch,
+    /// `DELETE /cancel-all`
+    CancelAll,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 4] =
+        [Endpoint::Order, Endpoint::Cancel, Endpoint::CancelBatch, Endpoint::CancelAll];
+
+    /// Name used in `PMENGINE_RATE_LIMITS` and logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Endpoint::Order => "order",
+            Endpoint::Cancel => "cancel",
+            Endpoint::CancelBatch => "cancel_batch",
+            Endpoint::CancelAll => "cancel_all",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Requests per second allowed per endpoint (0 = unlimited).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    per_second: [u32; 4],
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        // Well under the CLOB's sustained limits
+        Self { per_second: [50, 40, 20, 5] }
+    }
+}
+
+impl RateLimits {
+    /// Parse `endpoint:rate,endpoint:rate`, e.g. `order:20,cancel_all:1`,
+    /// over the defaults.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut limits = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, rate) = entry.split_once(':')?;
+            let endpoint = Endpoint::ALL.into_iter().find(|e| e.name() == name.trim())?;
+            limits.per_second[endpoint.index()] = rate.trim().parse().ok()?;
+        }
+        Some(limits)
+    }
+
+    pub fn get(&self, endpoint: Endpoint) -> u32 {
+        self.per_second[endpoint.index()]
+    }
+}
+
+/// Request counters of one endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThrottleMetrics {
+    /// Requests sent (including retries)
+    pub requests: u64,
+    /// Requests that had to wait for the budget
+    pub queued: u64,
+    /// 429 responses
+    pub rate_limited: u64,
+    /// Requests given up on after repeated 429s
+    pub failed: u64,
+}
+
+#[derive(Debug)]
+struct EndpointState {
+    backoff: Backoff,
+    /// No requests until then (after a 429)
+    paused_until: Option<Instant>,
+    metrics: ThrottleMetrics,
+}
+
+/// Per-endpoint request budgets and 429 backoff.
+#[derive(Debug)]
+pub struct Throttle {
+    limiters: Vec<Option<DefaultDirectRateLimiter>>,
+    state: Mutex<Vec<EndpointState>>,
+}
+
+impl Throttle {
+    pub fn new(limits: &RateLimits) -> Self {
+        let limiters = Endpoint::ALL
+            .iter()
+            .map(|e| NonZeroU32::new(limits.get(*e)))
+            .map(|rate| rate.map(|rate| DefaultDirectRateLimiter::direct(Quota::per_second(rate))))
+            .collect();
+        let state = Endpoint::ALL
+            .iter()
+            .map(|_| EndpointState {
+                backoff: Backoff::new(RATE_LIMIT_BACKOFF_BASE, RATE_LIMIT_BACKOFF_MAX),
+                paused_until: None,
+                metrics: ThrottleMetrics::default(),
+            })
+            .collect();
+        Self { limiters, state: Mutex::new(state) }
+    }
+
+    /// Send a request to `endpoint` within its budget, retrying it after a
+    /// backoff when it is rejected with a 429.
+    pub async fn call<T, F, Fut>(&self, endpoint: Endpoint, mut request: F) -> Result<T, ClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut retries = 0;
+        loop {
+            self.acquire(endpoint).await;
+            match request().await {
+                Err(ClientError::RateLimited(e)) => {
+                    let delay = self.rate_limited(endpoint);
+                    if retries == MAX_RETRIES {
+                        self.with_state(endpoint, |s| s.metrics.failed += 1);
+                        return Err(ClientError::RateLimited(e));
+                    }
+                    retries += 1;
+                    tracing::warn!(
+                        endpoint = endpoint.name(),
+                        retry = retries,
+                        delay_ms = delay.as_millis() as u64,
+                        "CLOB rate limit hit, backing off"
+                    );
+                }
+                result => {
+                    if result.is_ok() {
+                        self.with_state(endpoint, |s| s.backoff.reset());
+                    }
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Wait out any 429 pause, then for the endpoint's budget.
+    async fn acquire(&self, endpoint: Endpoint) {
+        let paused_until = |s: &mut EndpointState| s.paused_until.filter(|t| *t > Instant::now());
+        while let Some(until) = self.with_state(endpoint, paused_until) {
+            tokio::time::sleep_until(until).await;
+        }
+        if let Some(limiter) = &self.limiters[endpoint.index()] {
+            if limiter.check().is_err() {
+                self.with_state(endpoint, |s| s.metrics.queued += 1);
+                limiter.until_ready().await;
+            }
+        }
+        self.with_state(endpoint, |s| s.metrics.requests += 1);
+    }
+
+    /// Pause the endpoint after a 429, returning for how long.
+    fn rate_limited(&self, endpoint: Endpoint) -> Duration {
+        self.with_state(endpoint, |s| {
+            s.metrics.rate_limited += 1;
+            let delay = s.backoff.next_delay();
+            s.paused_until = Some(Instant::now() + delay);
+            delay
+        })
+    }
+
+    fn with_state<R>(&self, endpoint: Endpoint, f: impl FnOnce(&mut EndpointState) -> R) -> R {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state[endpoint.index()])
+    }
+
+    /// Request counters of an endpoint.
+    pub fn metrics(&self, endpoint: Endpoint) -> ThrottleMetrics {
+        self.with_state(endpoint, |s| s.metrics)
+    }
+
+    pub fn log(&self) {
+        for endpoint in Endpoint::ALL {
+            let metrics = self.metrics(endpoint);
+            if metrics == ThrottleMetrics::default() {
+                continue;
+            }
+            tracing::info!(
+                endpoint = endpoint.name(),
+                requests = metrics.requests,
+                queued = metrics.queued,
+                rate_limited = metrics.rate_limited,
+                failed = metrics.failed,
+                "CLOB request throttle"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_parse_rate_limits() {
+        let limits = RateLimits::parse("order:20, cancel_all:0").unwrap();
+        assert_eq!(limits.get(Endpoint::Order), 20);
+        assert_eq!(limits.get(Endpoint::CancelAll), 0);
+        assert_eq!(limits.get(Endpoint::Cancel), RateLimits::default().get(Endpoint::Cancel));
+        assert_eq!(RateLimits::parse(""), Some(RateLimits::default()));
+
+        assert!(RateLimits::parse("orders:20").is_none());
+        assert!(RateLimits::parse("order:-1").is_none());
+        assert!(RateLimits::parse("order").is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_requests_back_off_and_retry() {
+        let throttle = Throttle::new(&RateLimits::default());
+        let attempts = AtomicU32::new(0);
+        let started = Instant::now();
+
+        // Two 429s, then accepted
+        let result = throttle
+            .call(Endpoint::Order, || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(ClientError::RateLimited("HTTP 429".to_string())),
+                    n => Ok(n),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 2);
+        // Paused 500ms, then 1s
+        assert!(started.elapsed() >= Duration::from_millis(1500));
+        let metrics = throttle.metrics(Endpoint::Order);
+        assert_eq!((metrics.requests, metrics.rate_limited, metrics.failed), (3, 2, 0));
+
+        // Always rejected: given up on after the retries
+        let result: Result<(), _> = throttle
+            .call(Endpoint::Cancel, || async { Err(ClientError::RateLimited("HTTP 429".to_string())) })
+            .await;
+        assert!(matches!(result, Err(ClientError::RateLimited(_))));
+        let metrics = throttle.metrics(Endpoint::Cancel);
+        assert_eq!((metrics.requests, metrics.failed), (MAX_RETRIES as u64 + 1, 1));
+
+        // Other errors are not retried
+        let result: Result<(), _> = throttle
+            .call(Endpoint::CancelAll, || async { Err(ClientError::OrderError("HTTP 400".to_string())) })
+            .await;
+        assert!(matches!(result, Err(ClientError::OrderError(_))));
+        assert_eq!(throttle.metrics(Endpoint::CancelAll).requests, 1);
+    }
+
+    #[test]
+    fn test_requests_over_budget_are_queued() {
+        let throttle = Throttle::new(&RateLimits::parse("cancel_batch:2").unwrap());
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let started = std::time::Instant::now();
+        runtime.block_on(async {
+            for _ in 0..3 {
+                throttle.call(Endpoint::CancelBatch, || async { Ok(()) }).await.unwrap();
+            }
+        });
+        // The third request waited for the budget to refill
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert_eq!(throttle.metrics(Endpoint::CancelBatch).queued, 1);
+    }
+}
@@ -0,0 +1,311 @@
+//! Trade journal: every order signal, risk decision, order and fill.
+//!
+//! With `PMENGINE_TRADE_JOURNAL` set, the engine appends one JSON line per
+//! event to that file, timestamped and attributed to the strategy behind it:
+//!
+//! - `signal`: an order signal as the strategy (or an exit rule, or an
+//!   execution algo) sent it;
+//! - `risk`: the risk manager's decision on it, with the reason when it was
+//!   reduced or rejected;
+//! - `order`: an order placed for it, with the exchange's order ID;
+//! - `fill`: a fill of one of those orders, with its fee.
+//!
+//! The journal is for strategy review and tax reporting; restarts are
+//! recovered from the state directory, not from here. A running engine's
+//! journal is read with [`EngineControl::journal`](crate::EngineControl::journal),
+//! and `pmengine export --format csv|json --from --to` writes a time range of
+//! it out.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::position::Fill;
+use crate::risk::RiskCheckResult;
+use crate::strategy::Signal;
+
+/// Header of the CSV export.
+pub const CSV_HEADER: &str = "timestamp,kind,strategy_id,token_id,side,price,size,order_id,fee,detail";
+
+/// What a journal entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Signal,
+    Risk,
+    Order,
+    Fill,
+}
+
+impl EntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::Signal => "signal",
+            EntryKind::Risk => "risk",
+            EntryKind::Order => "order",
+            EntryKind::Fill => "fill",
+        }
+    }
+}
+
+/// One journaled event (one journal line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+    pub kind: EntryKind,
+    /// Strategy behind the event (None = untagged order)
+    pub strategy_id: Option<String>,
+    pub token_id: String,
+    /// `BUY` or `SELL`
+    pub side: String,
+    pub price: Decimal,
+    /// Size signalled, let through by risk, ordered or filled
+    pub size: Decimal,
+    /// Exchange order ID (orders and fills)
+    pub order_id: Option<String>,
+    /// Fee paid (fills)
+    pub fee: Option<Decimal>,
+    /// Signal type, or the risk decision and its reason
+    pub detail: Option<String>,
+}
+
+impl JournalEntry {
+    /// An order signal (None for signals that are not orders).
+    pub fn signal(strategy_id: &str, signal: &Signal) -> Option<Self> {
+        let kind = match signal {
+            Signal::Market { .. } => "market",
+            Signal::Algo { .. } => "algo",
+            _ => "limit",
+        };
+        Self::for_order(EntryKind::Signal, strategy_id, signal, Some(kind.to_string()))
+    }
+
+    /// The risk decision on an order signal, at the size let through.
+    pub fn risk(strategy_id: &str, signal: &Signal, result: &RiskCheckResult) -> Option<Self> {
+        match result {
+            RiskCheckResult::Approved(s) => {
+                Self::for_order(EntryKind::Risk, strategy_id, s, Some("approved".to_string()))
+            }
+            RiskCheckResult::Reduced(s, reason) => {
+                Self::for_order(EntryKind::Risk, strategy_id, s, Some(format!("reduced: {}", reason)))
+            }
+            RiskCheckResult::Rejected(reason) => {
+                let detail = Some(format!("rejected: {}", reason));
+                let entry = Self::for_order(EntryKind::Risk, strategy_id, signal, detail)?;
+                Some(Self { size: Decimal::ZERO, ..entry })
+            }
+        }
+    }
+
+    /// An order placed on the exchange.
+    pub fn order(strategy_id: &str, order_id: &str, order: &Signal) -> Option<Self> {
+        let mut entry = Self::for_order(EntryKind::Order, strategy_id, order, None)?;
+        entry.order_id = Some(order_id.to_string());
+        Some(entry)
+    }
+
+    pub fn fill(fill: &Fill) -> Self {
+        Self {
+            timestamp: fill.timestamp,
+            kind: EntryKind::Fill,
+            strategy_id: fill.strategy_id.clone(),
+            token_id: fill.token_id.clone(),
+            side: if fill.is_buy { "BUY" } else { "SELL" }.to_string(),
+            price: fill.price,
+            size: fill.size,
+            order_id: Some(fill.order_id.clone()),
+            fee: Some(fill.fee),
+            detail: None,
+        }
+    }
+
+    fn for_order(
+        kind: EntryKind,
+        strategy_id: &str,
+        signal: &Signal,
+        detail: Option<String>,
+    ) -> Option<Self> {
+        let (token_id, is_buy, price, size) = signal.order_terms()?;
+        Some(Self {
+            timestamp: Utc::now(),
+            kind,
+            strategy_id: Some(strategy_id.to_string()),
+            token_id: token_id.to_string(),
+            side: if is_buy { "BUY" } else { "SELL" }.to_string(),
+            price,
+            size,
+            order_id: None,
+            fee: None,
+            detail,
+        })
+    }
+
+    /// The entry as a CSV row (see [`CSV_HEADER`]).
+    pub fn csv_row(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        [
+            self.timestamp.to_rfc3339(),
+            self.kind.as_str().to_string(),
+            optional(self.strategy_id.clone()),
+            self.token_id.clone(),
+            self.side.clone(),
+            self.price.to_string(),
+            self.size.to_string(),
+            optional(self.order_id.clone()),
+            optional(self.fee.map(|f| f.to_string())),
+            optional(self.detail.clone()),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+/// Quote a CSV field if it needs it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Append-only JSONL trade journal.
+pub struct TradeJournal {
+    writer: BufWriter<File>,
+}
+
+impl TradeJournal {
+    /// Open (or create) a journal file for appending.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Append an entry, flushed immediately.
+    pub fn record(&mut self, entry: &JournalEntry) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+/// Entries of a journal file from `from` (inclusive) to `to` (exclusive),
+/// either bound open.
+pub fn read(
+    path: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> std::io::Result<Vec<JournalEntry>> {
+    let mut entries = Vec::new();
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: JournalEntry = serde_json::from_str(&line).map_err(|e| {
+            let message = format!("{} line {}: {}", path.display(), number + 1, e);
+            std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+        })?;
+        if from.is_some_and(|from| entry.timestamp < from) || to.is_some_and(|to| entry.timestamp >= to) {
+            continue;
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Write entries as CSV, header first.
+pub fn write_csv<W: Write>(entries: &[JournalEntry], mut writer: W) -> std::io::Result<()> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+    for entry in entries {
+        writeln!(writer, "{}", entry.csv_row())?;
+    }
+    writer.flush()
+}
+
+/// A range bound: an RFC 3339 timestamp, or a date (its midnight UTC).
+pub fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| format!("invalid time {:?} (expected RFC 3339 or YYYY-MM-DD)", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Urgency;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_journal_round_trips_and_exports() {
+        let path = std::env::temp_dir().join(format!("pmengine-trades-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let signal = Signal::Buy {
+            token_id: "token".to_string(),
+            price: dec!(0.50),
+            size: dec!(20),
+            urgency: Urgency::Medium,
+            post_only: false,
+            expires_at: None,
+        };
+        let reduced = RiskCheckResult::Reduced(signal.with_size(dec!(10)), "exposure, limit".to_string());
+        let fill = Fill {
+            order_id: "order-1".to_string(),
+            token_id: "token".to_string(),
+            is_buy: true,
+            price: dec!(0.50),
+            size: dec!(10),
+            timestamp: Utc::now() + chrono::Duration::seconds(1),
+            fee: dec!(0.1),
+            strategy_id: Some("strat".to_string()),
+        };
+
+        let mut journal = TradeJournal::open(&path).unwrap();
+        let entries = [
+            JournalEntry::signal("strat", &signal).unwrap(),
+            JournalEntry::risk("strat", &signal, &reduced).unwrap(),
+            JournalEntry::order("strat", "order-1", &signal.with_size(dec!(10))).unwrap(),
+            JournalEntry::fill(&fill),
+        ];
+        for entry in &entries {
+            journal.record(entry).unwrap();
+        }
+        assert!(JournalEntry::signal("strat", &Signal::Hold).is_none());
+
+        let read_back = read(&path, None, None).unwrap();
+        assert_eq!(read_back, entries);
+        assert_eq!(read_back[1].size, dec!(10));
+        let fills = read(&path, Some(fill.timestamp), None).unwrap();
+        assert_eq!(fills.iter().map(|e| e.kind).collect::<Vec<_>>(), [EntryKind::Fill]);
+        assert_eq!(read(&path, None, Some(entries[0].timestamp)).unwrap(), []);
+
+        let mut csv = Vec::new();
+        write_csv(&read_back, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[2].ends_with(",risk,strat,token,BUY,0.50,10,,,\"reduced: exposure, limit\""));
+        assert!(lines[4].ends_with(",fill,strat,token,BUY,0.50,10,order-1,0.1,"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_time_accepts_dates() {
+        assert_eq!(parse_time("2026-01-15").unwrap().to_rfc3339(), "2026-01-15T00:00:00+00:00");
+        let time = parse_time("2026-01-15T12:30:00+02:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-01-15T10:30:00+00:00");
+        assert!(parse_time("yesterday").is_err());
+    }
+}
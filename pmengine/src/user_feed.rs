@@ -0,0 +1,241 @@
+//! Fills and cancellations from the authenticated user WebSocket channel.
+//!
+//! The market channel only carries books; whether our own orders were matched
+//! or cancelled comes from the user channel. [`spawn`] subscribes to it for
+//! every market and forwards:
+//!
+//! - each trade an order of ours took part in, as the taker (`taker_order_id`)
+//!   or a maker (`maker_orders`), as [`UserEvent::Fill`], with the fee rate
+//!   the taker paid (makers pay none);
+//! - each cancellation of an order, as [`UserEvent::Cancelled`].
+//!
+//! The engine hands fills to `OrderManager::process_fill`, which ignores orders
+//! it doesn't track (the other side of the trade) and sends the rest down the
+//! fill channel that updates positions, strategies and risk.
+//!
+//! A trade is reported again as it moves from `MATCHED` to `MINED` and
+//! `CONFIRMED`; it is forwarded once, on the first message seen. A trade that
+//! later fails on chain is logged but not reversed: reconciliation with the
+//! exchange corrects the position.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use futures::StreamExt;
+use polymarket_client_sdk::clob::ws::types::response::OrderMessageType;
+use polymarket_client_sdk::clob::ws::{TradeMessage, WsMessage};
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::client::UserWsClient;
+
+/// Delay before resubscribing after the user stream ends or fails.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Trade IDs remembered for de-duplication.
+const SEEN_TRADES: usize = 10_000;
+
+/// An update to one of our orders.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserEvent {
+    /// Part or all of an order was matched.
+    Fill {
+        order_id: String,
+        trade_id: String,
+        price: Decimal,
+        size: Decimal,
+        /// Fee rate paid, in basis points (None = the token's rate in the
+        /// fee schedule)
+        fee_rate_bps: Option<Decimal>,
+    },
+    /// An order was cancelled.
+    Cancelled { order_id: String },
+}
+
+/// Turns user channel messages into [`UserEvent`]s, once per trade.
+#[derive(Debug, Default)]
+pub struct UserEventMapper {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl UserEventMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events for a user channel message (none for messages already seen or
+    /// that don't change an order).
+    pub fn map(&mut self, message: &WsMessage) -> Vec<UserEvent> {
+        match message {
+            WsMessage::Trade(trade) => self.fills(trade),
+            WsMessage::Order(order) if order.msg_type == Some(OrderMessageType::Cancellation) => {
+                vec![UserEvent::Cancelled {
+                    order_id: order.id.clone(),
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn fills(&mut self, trade: &TradeMessage) -> Vec<UserEvent> {
+        if !self.first_sighting(&trade.id) {
+            tracing::debug!(trade_id = trade.id.as_str(), status = ?trade.status, "Trade status update");
+            return Vec::new();
+        }
+
+        let fill = |order_id: &str, price, size, fee_rate_bps| UserEvent::Fill {
+            order_id: order_id.to_string(),
+            trade_id: trade.id.clone(),
+            price,
+            size,
+            fee_rate_bps,
+        };
+        let mut fills: Vec<UserEvent> = trade
+            .taker_order_id
+            .iter()
+            .map(|order_id| fill(order_id, trade.price, trade.size, trade.fee_rate_bps))
+            .collect();
+        fills.extend(
+            trade
+                .maker_orders
+                .iter()
+                .map(|maker| fill(&maker.order_id, maker.price, maker.matched_amount, Some(Decimal::ZERO))),
+        );
+        fills
+    }
+
+    /// Remember a trade ID, returning whether it is new.
+    fn first_sighting(&mut self, trade_id: &str) -> bool {
+        if !self.seen.insert(trade_id.to_string()) {
+            return false;
+        }
+        self.order.push_back(trade_id.to_string());
+        if self.order.len() > SEEN_TRADES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Subscribe to the user channel and forward our order events until the
+/// receiver is dropped, resubscribing whenever the stream ends.
+pub fn spawn(ws: UserWsClient, events: mpsc::Sender<UserEvent>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut mapper = UserEventMapper::new();
+        loop {
+            match ws.subscribe_user_events(Vec::new()) {
+                Ok(stream) => {
+                    tracing::info!("Subscribed to user order and trade events");
+                    let mut stream = Box::pin(stream);
+                    while let Some(message) = stream.next().await {
+                        let message = match message {
+                            Ok(message) => message,
+                            Err(e) => {
+                                tracing::warn!(error = %e, "User channel error");
+                                continue;
+                            }
+                        };
+                        for event in mapper.map(&message) {
+                            if events.send(event).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    tracing::warn!("User channel stream ended, resubscribing");
+                }
+                Err(e) => tracing::error!(error = %e, "Failed to subscribe to user channel"),
+            }
+            if events.is_closed() {
+                return;
+            }
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const MARKET: &str = "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1";
+
+    fn trade(status: &str) -> WsMessage {
+        serde_json::from_value(serde_json::json!({
+            "event_type": "trade",
+            "id": "trade-1",
+            "market": MARKET,
+            "asset_id": "123",
+            "side": "BUY",
+            "size": "10",
+            "price": "0.57",
+            "status": status,
+            "taker_order_id": "taker-order",
+            "maker_orders": [
+                {"asset_id": "123", "matched_amount": "4", "order_id": "maker-a",
+                 "outcome": "Yes", "owner": "9180014b-33c8-9240-a14b-bdca11c0a465", "price": "0.57"},
+                {"asset_id": "456", "matched_amount": "6", "order_id": "maker-b",
+                 "outcome": "No", "owner": "9180014b-33c8-9240-a14b-bdca11c0a465", "price": "0.43"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trades_become_fills_once() {
+        let mut mapper = UserEventMapper::new();
+        let fills = mapper.map(&trade("MATCHED"));
+        assert_eq!(fills.len(), 3);
+        assert_eq!(
+            fills[0],
+            UserEvent::Fill {
+                order_id: "taker-order".to_string(),
+                trade_id: "trade-1".to_string(),
+                price: dec!(0.57),
+                size: dec!(10),
+                fee_rate_bps: None,
+            }
+        );
+        assert!(matches!(
+            &fills[2],
+            UserEvent::Fill { order_id, price, size, fee_rate_bps, .. }
+                if order_id == "maker-b" && *price == dec!(0.43) && *size == dec!(6)
+                    && *fee_rate_bps == Some(dec!(0))
+        ));
+
+        // Later statuses of the same trade are not fills again
+        assert!(mapper.map(&trade("MINED")).is_empty());
+        assert!(mapper.map(&trade("CONFIRMED")).is_empty());
+    }
+
+    #[test]
+    fn test_cancellations() {
+        let order = |kind: &str| -> WsMessage {
+            serde_json::from_value(serde_json::json!({
+                "event_type": "order",
+                "id": "order-1",
+                "market": MARKET,
+                "asset_id": "123",
+                "side": "SELL",
+                "price": "0.57",
+                "type": kind,
+                "original_size": "10",
+                "size_matched": "0"
+            }))
+            .unwrap()
+        };
+        let mut mapper = UserEventMapper::new();
+        assert_eq!(
+            mapper.map(&order("CANCELLATION")),
+            vec![UserEvent::Cancelled {
+                order_id: "order-1".to_string()
+            }]
+        );
+        assert!(mapper.map(&order("PLACEMENT")).is_empty());
+        assert!(mapper.map(&order("UPDATE")).is_empty());
+    }
+}
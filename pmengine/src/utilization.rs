@@ -0,0 +1,261 @@
+//! Capital-efficiency tracking.
+//!
+//! Each tick the engine samples how much of `max_total_exposure` is deployed
+//! (positions + open orders + pending reservations), attributed to the
+//! strategy trading each token and the market's category. The resulting
+//! [`UtilizationReport`] shows average/peak deployment and how often capital
+//! sat idle, so exposure limits and allocations can be tuned with data.
+
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Bucket name for exposure not attributable to a strategy or category.
+pub const UNATTRIBUTED: &str = "unattributed";
+
+/// Exposure held on one token at sample time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenExposure {
+    /// Strategy trading the token (None = e.g. a position from a previous run)
+    pub strategy: Option<String>,
+    /// Market category from Gamma (None = unknown)
+    pub category: Option<String>,
+    pub notional: Decimal,
+}
+
+/// Running totals for one strategy or category.
+#[derive(Debug, Clone, Default)]
+struct Bucket {
+    total: Decimal,
+    peak: Decimal,
+}
+
+/// Accumulates exposure samples.
+#[derive(Debug, Clone)]
+pub struct UtilizationTracker {
+    capacity: Decimal,
+    samples: u64,
+    /// Samples with nothing deployed
+    idle_samples: u64,
+    total_deployed: Decimal,
+    peak_deployed: Decimal,
+    by_strategy: HashMap<String, Bucket>,
+    by_category: HashMap<String, Bucket>,
+}
+
+impl UtilizationTracker {
+    /// Create a tracker for the given exposure limit.
+    pub fn new(capacity: Decimal) -> Self {
+        Self {
+            capacity,
+            samples: 0,
+            idle_samples: 0,
+            total_deployed: Decimal::ZERO,
+            peak_deployed: Decimal::ZERO,
+            by_strategy: HashMap::new(),
+            by_category: HashMap::new(),
+        }
+    }
+
+    /// Record one sample of per-token exposure.
+    pub fn record(&mut self, exposures: &[TokenExposure]) {
+        let mut strategies: HashMap<&str, Decimal> = HashMap::new();
+        let mut categories: HashMap<&str, Decimal> = HashMap::new();
+        let mut deployed = Decimal::ZERO;
+
+        for exposure in exposures.iter().filter(|e| e.notional > Decimal::ZERO) {
+            deployed += exposure.notional;
+            *strategies
+                .entry(exposure.strategy.as_deref().unwrap_or(UNATTRIBUTED))
+                .or_default() += exposure.notional;
+            *categories
+                .entry(exposure.category.as_deref().unwrap_or(UNATTRIBUTED))
+                .or_default() += exposure.notional;
+        }
+
+        self.samples += 1;
+        if deployed.is_zero() {
+            self.idle_samples += 1;
+        }
+        self.total_deployed += deployed;
+        self.peak_deployed = self.peak_deployed.max(deployed);
+
+        Self::add(&mut self.by_strategy, strategies);
+        Self::add(&mut self.by_category, categories);
+    }
+
+    fn add(buckets: &mut HashMap<String, Bucket>, sample: HashMap<&str, Decimal>) {
+        for (name, notional) in sample {
+            let bucket = buckets.entry(name.to_string()).or_default();
+            bucket.total += notional;
+            bucket.peak = bucket.peak.max(notional);
+        }
+    }
+
+    /// Summarize the samples recorded so far.
+    pub fn report(&self) -> UtilizationReport {
+        let samples = Decimal::from(self.samples.max(1));
+        let avg_deployed = self.total_deployed / samples;
+        let ratio = |v: Decimal| {
+            if self.capacity > Decimal::ZERO {
+                (v / self.capacity).round_dp(4)
+            } else {
+                Decimal::ZERO
+            }
+        };
+
+        let breakdown = |buckets: &HashMap<String, Bucket>| {
+            let mut usage: Vec<BucketUsage> = buckets
+                .iter()
+                .map(|(name, b)| {
+                    let avg = (b.total / samples).round_dp(2);
+                    BucketUsage {
+                        name: name.clone(),
+                        avg_deployed: avg,
+                        peak_deployed: b.peak,
+                        avg_utilization: ratio(avg),
+                    }
+                })
+                .collect();
+            usage.sort_by(|a, b| b.avg_deployed.cmp(&a.avg_deployed).then(a.name.cmp(&b.name)));
+            usage
+        };
+
+        UtilizationReport {
+            samples: self.samples,
+            capacity: self.capacity,
+            avg_deployed: avg_deployed.round_dp(2),
+            peak_deployed: self.peak_deployed,
+            avg_utilization: ratio(avg_deployed),
+            peak_utilization: ratio(self.peak_deployed),
+            avg_idle: (self.capacity - avg_deployed).max(Decimal::ZERO).round_dp(2),
+            idle_samples: self.idle_samples,
+            by_strategy: breakdown(&self.by_strategy),
+            by_category: breakdown(&self.by_category),
+        }
+    }
+}
+
+/// Deployment of one strategy or category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketUsage {
+    pub name: String,
+    /// Average notional deployed (over all samples)
+    pub avg_deployed: Decimal,
+    /// Largest notional deployed in a single sample
+    pub peak_deployed: Decimal,
+    /// `avg_deployed` as a fraction of capacity
+    pub avg_utilization: Decimal,
+}
+
+/// Exposure utilization summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtilizationReport {
+    /// Number of samples (ticks) recorded
+    pub samples: u64,
+    /// Exposure limit (`max_total_exposure`)
+    pub capacity: Decimal,
+    /// Average notional deployed
+    pub avg_deployed: Decimal,
+    /// Largest notional deployed in a single sample
+    pub peak_deployed: Decimal,
+    /// `avg_deployed` as a fraction of capacity
+    pub avg_utilization: Decimal,
+    /// `peak_deployed` as a fraction of capacity
+    pub peak_utilization: Decimal,
+    /// Average capacity left unused
+    pub avg_idle: Decimal,
+    /// Samples with nothing deployed at all
+    pub idle_samples: u64,
+    /// Per-strategy deployment, largest first
+    pub by_strategy: Vec<BucketUsage>,
+    /// Per-category deployment, largest first
+    pub by_category: Vec<BucketUsage>,
+}
+
+impl UtilizationReport {
+    /// Log the report.
+    pub fn log(&self) {
+        tracing::info!(
+            samples = self.samples,
+            capacity = %self.capacity,
+            avg_deployed = %self.avg_deployed,
+            peak_deployed = %self.peak_deployed,
+            avg_utilization = %self.avg_utilization,
+            peak_utilization = %self.peak_utilization,
+            avg_idle = %self.avg_idle,
+            idle_samples = self.idle_samples,
+            "Exposure utilization"
+        );
+        for usage in &self.by_strategy {
+            tracing::info!(
+                strategy = usage.name.as_str(),
+                avg_deployed = %usage.avg_deployed,
+                peak_deployed = %usage.peak_deployed,
+                avg_utilization = %usage.avg_utilization,
+                "Exposure utilization by strategy"
+            );
+        }
+        for usage in &self.by_category {
+            tracing::info!(
+                category = usage.name.as_str(),
+                avg_deployed = %usage.avg_deployed,
+                peak_deployed = %usage.peak_deployed,
+                avg_utilization = %usage.avg_utilization,
+                "Exposure utilization by category"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn exposure(strategy: Option<&str>, category: Option<&str>, notional: Decimal) -> TokenExposure {
+        TokenExposure {
+            strategy: strategy.map(String::from),
+            category: category.map(String::from),
+            notional,
+        }
+    }
+
+    #[test]
+    fn test_utilization_report() {
+        let mut tracker = UtilizationTracker::new(dec!(100));
+
+        tracker.record(&[]);
+        tracker.record(&[
+            exposure(Some("sure_bets"), Some("politics"), dec!(30)),
+            exposure(Some("sure_bets"), Some("crypto"), dec!(10)),
+            exposure(None, None, dec!(20)),
+        ]);
+        tracker.record(&[exposure(Some("sure_bets"), Some("crypto"), dec!(30))]);
+
+        let report = tracker.report();
+        assert_eq!(report.samples, 3);
+        assert_eq!(report.idle_samples, 1);
+        assert_eq!(report.avg_deployed, dec!(30));
+        assert_eq!(report.peak_deployed, dec!(60));
+        assert_eq!(report.avg_utilization, dec!(0.3));
+        assert_eq!(report.peak_utilization, dec!(0.6));
+        assert_eq!(report.avg_idle, dec!(70));
+
+        assert_eq!(report.by_strategy[0].name, "sure_bets");
+        assert_eq!(report.by_strategy[0].avg_deployed, dec!(23.33));
+        assert_eq!(report.by_strategy[0].peak_deployed, dec!(40));
+        assert_eq!(report.by_strategy[1].name, UNATTRIBUTED);
+
+        let crypto = report.by_category.iter().find(|u| u.name == "crypto").unwrap();
+        assert_eq!(crypto.peak_deployed, dec!(30));
+        assert_eq!(report.by_category.len(), 3);
+    }
+
+    #[test]
+    fn test_empty_tracker_reports_fully_idle() {
+        let report = UtilizationTracker::new(dec!(50)).report();
+        assert_eq!(report.samples, 0);
+        assert_eq!(report.avg_utilization, Decimal::ZERO);
+        assert_eq!(report.avg_idle, dec!(50));
+    }
+}
@@ -0,0 +1,316 @@
+//! Event loop watchdog.
+//!
+//! The main loop records a [`Heartbeat`] as it works (ticks, WebSocket updates,
+//! and which branch it is currently in). A separate task checks that the
+//! heartbeat keeps advancing; if neither a tick nor a WebSocket update happens
+//! within the configured window it:
+//! 1. Logs diagnostics (current activity, last event timestamps, runtime stats)
+//! 2. Cancels all open orders directly through the client (a side channel that
+//!    does not depend on the stuck loop)
+//! 3. Optionally signals the engine to restart its event loop
+
+use crate::client::PolymarketClient;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// What the event loop is currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LoopActivity {
+    /// Waiting in `select!` for the next event
+    Idle = 0,
+    /// Connecting/subscribing to the WebSocket
+    Connecting = 1,
+    /// Running strategies and executing signals
+    Tick = 2,
+    /// Refreshing markets from the Gamma API
+    MarketRefresh = 3,
+    /// Applying a fill
+    Fill = 4,
+    /// Processing a WebSocket order book update
+    WebSocket = 5,
+    /// Cancelling orders and shutting down
+    Shutdown = 6,
+    /// Reconciling orders and positions with the exchange
+    Reconcile = 7,
+    /// Settling positions in resolved markets
+    Settle = 8,
+    /// Handing an external event to strategies
+    External = 9,
+}
+
+impl LoopActivity {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            1 => LoopActivity::Connecting,
+            2 => LoopActivity::Tick,
+            3 => LoopActivity::MarketRefresh,
+            4 => LoopActivity::Fill,
+            5 => LoopActivity::WebSocket,
+            6 => LoopActivity::Shutdown,
+            7 => LoopActivity::Reconcile,
+            8 => LoopActivity::Settle,
+            9 => LoopActivity::External,
+            _ => LoopActivity::Idle,
+        }
+    }
+}
+
+/// Sentinel for "never happened".
+const NEVER: u64 = u64::MAX;
+
+/// Progress markers shared between the event loop and the watchdog.
+///
+/// Timestamps are stored as milliseconds since the heartbeat was created.
+#[derive(Debug)]
+pub struct Heartbeat {
+    started: Instant,
+    last_tick_ms: AtomicU64,
+    last_ws_ms: AtomicU64,
+    last_fill_ms: AtomicU64,
+    tick_count: AtomicU64,
+    ws_updates: AtomicU64,
+    restarts: AtomicU64,
+    activity: AtomicU8,
+    activity_since_ms: AtomicU64,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_tick_ms: AtomicU64::new(NEVER),
+            last_ws_ms: AtomicU64::new(NEVER),
+            last_fill_ms: AtomicU64::new(NEVER),
+            tick_count: AtomicU64::new(0),
+            ws_updates: AtomicU64::new(0),
+            restarts: AtomicU64::new(0),
+            activity: AtomicU8::new(LoopActivity::Idle as u8),
+            activity_since_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    /// Record that the loop started working on `activity`.
+    pub fn enter(&self, activity: LoopActivity) {
+        self.activity.store(activity as u8, Ordering::Relaxed);
+        self.activity_since_ms.store(self.now_ms(), Ordering::Relaxed);
+    }
+
+    /// Record a tick.
+    pub fn tick(&self) {
+        self.tick_count.fetch_add(1, Ordering::Relaxed);
+        self.last_tick_ms.store(self.now_ms(), Ordering::Relaxed);
+    }
+
+    /// Record a WebSocket update.
+    pub fn ws_update(&self) {
+        self.ws_updates.fetch_add(1, Ordering::Relaxed);
+        self.last_ws_ms.store(self.now_ms(), Ordering::Relaxed);
+    }
+
+    /// Record a fill.
+    pub fn fill(&self) {
+        self.last_fill_ms.store(self.now_ms(), Ordering::Relaxed);
+    }
+
+    /// Record a watchdog-triggered restart.
+    pub fn restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Read the current state.
+    pub fn snapshot(&self) -> HeartbeatSnapshot {
+        self.snapshot_at(Instant::now())
+    }
+
+    fn snapshot_at(&self, now: Instant) -> HeartbeatSnapshot {
+        let now_ms = now.saturating_duration_since(self.started).as_millis() as u64;
+        let ago = |ms: u64| (ms != NEVER).then(|| Duration::from_millis(now_ms.saturating_sub(ms)));
+
+        let last_tick = ago(self.last_tick_ms.load(Ordering::Relaxed));
+        let last_ws_update = ago(self.last_ws_ms.load(Ordering::Relaxed));
+        let activity_since = self.activity_since_ms.load(Ordering::Relaxed);
+
+        // The loop has made progress since whichever came last: a tick, a WS
+        // update, or (if neither happened yet) the heartbeat being created
+        let since_progress = match (last_tick, last_ws_update) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => Duration::from_millis(now_ms),
+        };
+
+        HeartbeatSnapshot {
+            activity: LoopActivity::from_u8(self.activity.load(Ordering::Relaxed)),
+            activity_for: Duration::from_millis(now_ms.saturating_sub(activity_since)),
+            last_tick,
+            last_ws_update,
+            last_fill: ago(self.last_fill_ms.load(Ordering::Relaxed)),
+            since_progress,
+            tick_count: self.tick_count.load(Ordering::Relaxed),
+            ws_updates: self.ws_updates.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Point-in-time view of a [`Heartbeat`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartbeatSnapshot {
+    /// What the loop is doing right now
+    pub activity: LoopActivity,
+    /// How long it has been doing it
+    pub activity_for: Duration,
+    /// Time since the last tick (None = no tick yet)
+    pub last_tick: Option<Duration>,
+    /// Time since the last WebSocket update (None = none yet)
+    pub last_ws_update: Option<Duration>,
+    /// Time since the last fill (None = none yet)
+    pub last_fill: Option<Duration>,
+    /// Time since the last tick or WebSocket update
+    pub since_progress: Duration,
+    pub tick_count: u64,
+    pub ws_updates: u64,
+    pub restarts: u64,
+}
+
+/// Watchdog settings.
+#[derive(Debug, Clone, Copy)]
+pub struct WatchdogConfig {
+    /// Maximum time without a tick or WebSocket update before the loop is
+    /// considered stuck
+    pub window: Duration,
+    /// Whether to restart the event loop after a stall
+    pub restart: bool,
+}
+
+/// Spawn the watchdog task.
+///
+/// `restart` is notified when a stall is detected and `config.restart` is set.
+pub fn spawn(
+    heartbeat: Arc<Heartbeat>,
+    config: WatchdogConfig,
+    client: Arc<PolymarketClient>,
+    restart: Arc<Notify>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let check_interval = (config.window / 4).max(Duration::from_millis(100));
+        let mut ticker = tokio::time::interval(check_interval);
+        // Only act once per stall; re-arm when the loop makes progress again
+        let mut fired = false;
+
+        tracing::info!(
+            window_secs = config.window.as_secs(),
+            restart = config.restart,
+            "Watchdog started"
+        );
+
+        loop {
+            ticker.tick().await;
+
+            let snapshot = heartbeat.snapshot();
+            if snapshot.since_progress < config.window {
+                fired = false;
+                continue;
+            }
+            if fired {
+                continue;
+            }
+            fired = true;
+
+            dump_diagnostics(&snapshot, config.window);
+
+            match client.cancel_all_open_orders().await {
+                Ok(()) => tracing::warn!("Watchdog cancelled all open orders"),
+                Err(e) => tracing::error!(error = %e, "Watchdog failed to cancel open orders"),
+            }
+
+            if config.restart {
+                tracing::warn!("Watchdog restarting event loop");
+                restart.notify_one();
+            }
+        }
+    })
+}
+
+fn dump_diagnostics(snapshot: &HeartbeatSnapshot, window: Duration) {
+    let metrics = tokio::runtime::Handle::current().metrics();
+
+    tracing::error!(
+        since_progress_ms = snapshot.since_progress.as_millis() as u64,
+        window_ms = window.as_millis() as u64,
+        activity = ?snapshot.activity,
+        activity_for_ms = snapshot.activity_for.as_millis() as u64,
+        last_tick_ms_ago = ?snapshot.last_tick.map(|d| d.as_millis()),
+        last_ws_update_ms_ago = ?snapshot.last_ws_update.map(|d| d.as_millis()),
+        last_fill_ms_ago = ?snapshot.last_fill.map(|d| d.as_millis()),
+        tick_count = snapshot.tick_count,
+        ws_updates = snapshot.ws_updates,
+        restarts = snapshot.restarts,
+        runtime_workers = metrics.num_workers(),
+        runtime_alive_tasks = metrics.num_alive_tasks(),
+        runtime_global_queue = metrics.global_queue_depth(),
+        "Watchdog: event loop stalled"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_heartbeat_counts_from_creation() {
+        let hb = Heartbeat::new();
+        let snap = hb.snapshot_at(hb.started + Duration::from_secs(5));
+
+        assert_eq!(snap.activity, LoopActivity::Idle);
+        assert_eq!(snap.since_progress, Duration::from_secs(5));
+        assert!(snap.last_tick.is_none());
+        assert!(snap.last_ws_update.is_none());
+    }
+
+    #[test]
+    fn test_progress_tracks_latest_tick_or_ws_update() {
+        let hb = Heartbeat::new();
+        hb.tick();
+        hb.ws_update();
+        hb.enter(LoopActivity::Tick);
+
+        let snap = hb.snapshot_at(Instant::now() + Duration::from_secs(3));
+        assert_eq!(snap.tick_count, 1);
+        assert_eq!(snap.ws_updates, 1);
+        assert_eq!(snap.activity, LoopActivity::Tick);
+        assert!(snap.since_progress >= Duration::from_secs(3));
+        assert!(snap.since_progress < Duration::from_secs(4));
+        assert!(snap.activity_for >= Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_activity_round_trips() {
+        for activity in [
+            LoopActivity::Idle,
+            LoopActivity::Connecting,
+            LoopActivity::Tick,
+            LoopActivity::MarketRefresh,
+            LoopActivity::Fill,
+            LoopActivity::WebSocket,
+            LoopActivity::Shutdown,
+            LoopActivity::Reconcile,
+            LoopActivity::Settle,
+            LoopActivity::External,
+        ] {
+            assert_eq!(LoopActivity::from_u8(activity as u8), activity);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! Webhook for external signals.
+//!
+//! With `PMENGINE_WEBHOOK_ADDR` set, the engine accepts `POST /events`
+//! requests carrying an [`ExternalEvent`] as JSON, e.g. from a news
+//! classifier or a relayed TradingView alert, so that strategies can act on
+//! information the order books don't carry. Requests are signed with
+//! `PMENGINE_WEBHOOK_SECRET`:
+//!
+//! - `X-Pmengine-Timestamp` holds the Unix time of the request in seconds;
+//!   requests more than [`MAX_SKEW_SECS`] off are refused, so a captured
+//!   request can't be replayed later;
+//! - `X-Pmengine-Signature` holds `sha256=` and the hex HMAC-SHA256 of
+//!   `{timestamp}.{body}` (see [`sign`]).
+//!
+//! Accepted events are queued for the event loop, which broadcasts them as
+//! [`MarketEvent::External`](crate::orderbook::MarketEvent) and hands them
+//! to the strategies' `on_external_event` (only to the one named in
+//! `strategy_id`, if set). The listener is a minimal HTTP/1.1 server: one
+//! request per connection, bodies up to [`MAX_BODY`] bytes.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+/// Largest request body accepted, in bytes.
+pub const MAX_BODY: usize = 64 * 1024;
+/// Largest request head (request line and headers) accepted, in bytes.
+const MAX_HEAD: usize = 8 * 1024;
+/// How far a request's timestamp may be from the engine's clock.
+pub const MAX_SKEW_SECS: i64 = 300;
+/// Time allowed to send a whole request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events waiting for the event loop before requests are turned away.
+pub const EVENT_QUEUE: usize = 256;
+
+const TIMESTAMP_HEADER: &str = "x-pmengine-timestamp";
+const SIGNATURE_HEADER: &str = "x-pmengine-signature";
+
+/// A signal from outside the engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalEvent {
+    /// Where the event comes from (e.g. "news", "tradingview")
+    pub source: String,
+    /// What happened, as the sender names it (e.g. "headline", "buy")
+    pub kind: String,
+    /// Token the event is about, if any
+    #[serde(default)]
+    pub token_id: Option<String>,
+    /// Strategy to deliver the event to (None = every strategy)
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+    /// Free-form payload
+    #[serde(default)]
+    pub data: serde_json::Value,
+    /// When the engine received the event
+    #[serde(skip_deserializing, default = "Utc::now")]
+    pub received_at: DateTime<Utc>,
+}
+
+/// Why a request was turned away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    BadRequest(String),
+    Unauthorized(&'static str),
+    NotFound,
+    MethodNotAllowed,
+    TooLarge,
+    /// The event queue is full
+    Busy,
+}
+
+impl Rejection {
+    /// HTTP status code and reason phrase.
+    fn status(&self) -> (u16, &'static str) {
+        match self {
+            Rejection::BadRequest(_) => (400, "Bad Request"),
+            Rejection::Unauthorized(_) => (401, "Unauthorized"),
+            Rejection::NotFound => (404, "Not Found"),
+            Rejection::MethodNotAllowed => (405, "Method Not Allowed"),
+            Rejection::TooLarge => (413, "Payload Too Large"),
+            Rejection::Busy => (503, "Service Unavailable"),
+        }
+    }
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::BadRequest(reason) => write!(f, "bad request: {}", reason),
+            Rejection::Unauthorized(reason) => write!(f, "unauthorized: {}", reason),
+            other => write!(f, "{}", other.status().1),
+        }
+    }
+}
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    /// Header values by lowercased name
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Signature of a request body sent at `timestamp` (Unix seconds), as the
+/// `X-Pmengine-Signature` header value.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    format!("sha256={}", hex::encode(mac(secret, timestamp, body).finalize().into_bytes()))
+}
+
+/// Check a request's timestamp and signature headers against its body.
+pub fn verify(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Result<(), Rejection> {
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| Rejection::Unauthorized("invalid timestamp"))?;
+    if (now.timestamp() - timestamp).abs() > MAX_SKEW_SECS {
+        return Err(Rejection::Unauthorized("stale timestamp"));
+    }
+    let signature = signature
+        .trim()
+        .strip_prefix("sha256=")
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+        .ok_or(Rejection::Unauthorized("malformed signature"))?;
+    mac(secret, timestamp, body)
+        .verify_slice(&signature)
+        .map_err(|_| Rejection::Unauthorized("bad signature"))
+}
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Authenticate a request and parse the event it carries.
+fn accept(request: &Request, secret: &str, now: DateTime<Utc>) -> Result<ExternalEvent, Rejection> {
+    if request.path != "/events" {
+        return Err(Rejection::NotFound);
+    }
+    if request.method != "POST" {
+        return Err(Rejection::MethodNotAllowed);
+    }
+    let header = |name: &'static str| {
+        request
+            .headers
+            .get(name)
+            .map(String::as_str)
+            .ok_or(Rejection::Unauthorized("missing signature headers"))
+    };
+    verify(secret, header(TIMESTAMP_HEADER)?, header(SIGNATURE_HEADER)?, &request.body, now)?;
+
+    let mut event: ExternalEvent =
+        serde_json::from_slice(&request.body).map_err(|e| Rejection::BadRequest(e.to_string()))?;
+    event.received_at = now;
+    Ok(event)
+}
+
+/// Read one request: the head up to the blank line, then `Content-Length`
+/// bytes of body.
+async fn read_request<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Request, Rejection> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD {
+            return Err(Rejection::TooLarge);
+        }
+        let n = reader
+            .read(&mut chunk)
+            .await
+            .map_err(|e| Rejection::BadRequest(e.to_string()))?;
+        if n == 0 {
+            return Err(Rejection::BadRequest("connection closed".to_string()));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let mut body = buf.split_off(head_end + 4);
+    let head = std::str::from_utf8(&buf[..head_end])
+        .map_err(|_| Rejection::BadRequest("request head is not UTF-8".to_string()))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(Rejection::BadRequest("malformed request line".to_string()));
+    };
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length: usize = match headers.get("content-length") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| Rejection::BadRequest("invalid Content-Length".to_string()))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(Rejection::TooLarge);
+    }
+    if body.len() < length {
+        let start = body.len();
+        body.resize(length, 0);
+        reader
+            .read_exact(&mut body[start..])
+            .await
+            .map_err(|e| Rejection::BadRequest(e.to_string()))?;
+    }
+    body.truncate(length);
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.split('?').next().unwrap_or_default().to_string(),
+        headers,
+        body,
+    })
+}
+
+/// Accept connections until the engine stops, queueing authenticated
+/// events on `events`.
+pub async fn serve(listener: TcpListener, secret: Arc<str>, events: mpsc::Sender<ExternalEvent>) {
+    loop {
+        let (mut stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!(error = %e, "Webhook accept failed");
+                continue;
+            }
+        };
+        let secret = secret.clone();
+        let events = events.clone();
+        tokio::spawn(async move {
+            let result = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+                Ok(request) => request.and_then(|r| accept(&r, &secret, Utc::now())),
+                Err(_) => Err(Rejection::BadRequest("timed out".to_string())),
+            };
+            let result = result.and_then(|event| {
+                tracing::info!(
+                    %peer,
+                    source = event.source.as_str(),
+                    kind = event.kind.as_str(),
+                    "External event received"
+                );
+                events.try_send(event).map_err(|_| Rejection::Busy)
+            });
+            let (status, reason) = match &result {
+                Ok(()) => (202, "Accepted"),
+                Err(rejection) => {
+                    tracing::warn!(%peer, %rejection, "Webhook request rejected");
+                    rejection.status()
+                }
+            };
+            let response = format!(
+                "HTTP/1.1 {} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status, reason
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_request(secret: &str, timestamp: i64, body: &str) -> String {
+        format!(
+            "POST /events HTTP/1.1\r\nHost: localhost\r\nX-Pmengine-Timestamp: {}\r\n\
+             X-Pmengine-Signature: {}\r\nContent-Length: {}\r\n\r\n{}",
+            timestamp,
+            sign(secret, timestamp, body.as_bytes()),
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_signed_requests_are_accepted() {
+        let now = Utc::now();
+        let body = r#"{"source":"news","kind":"headline","token_id":"t1","data":{"score":0.9}}"#;
+        let raw = raw_request("s3cret", now.timestamp(), body);
+        let request = read_request(&mut raw.as_bytes()).await.unwrap();
+        assert_eq!(request.path, "/events");
+
+        let event = accept(&request, "s3cret", now).unwrap();
+        assert_eq!((event.source.as_str(), event.kind.as_str()), ("news", "headline"));
+        assert_eq!(event.token_id.as_deref(), Some("t1"));
+        assert_eq!(event.strategy_id, None);
+        assert_eq!(event.data["score"], 0.9);
+        assert_eq!(event.received_at, now);
+
+        // Wrong secret, replayed later, or tampered with
+        assert!(matches!(accept(&request, "other", now), Err(Rejection::Unauthorized(_))));
+        let later = now + chrono::Duration::seconds(MAX_SKEW_SECS + 1);
+        assert_eq!(accept(&request, "s3cret", later), Err(Rejection::Unauthorized("stale timestamp")));
+        let mut tampered = request.clone();
+        tampered.body = body.replace("0.9", "0.1").into_bytes();
+        assert_eq!(accept(&tampered, "s3cret", now), Err(Rejection::Unauthorized("bad signature")));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_requests_are_rejected() {
+        let now = Utc::now();
+        let unsigned = "POST /events HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+        let request = read_request(&mut unsigned.as_bytes()).await.unwrap();
+        assert!(matches!(accept(&request, "s", now), Err(Rejection::Unauthorized(_))));
+
+        let not_json = raw_request("s", now.timestamp(), "buy!");
+        let request = read_request(&mut not_json.as_bytes()).await.unwrap();
+        assert!(matches!(accept(&request, "s", now), Err(Rejection::BadRequest(_))));
+
+        let get = "GET /events HTTP/1.1\r\n\r\n";
+        let request = read_request(&mut get.as_bytes()).await.unwrap();
+        assert_eq!(accept(&request, "s", now), Err(Rejection::MethodNotAllowed));
+
+        let huge = format!("POST /events HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert_eq!(read_request(&mut huge.as_bytes()).await, Err(Rejection::TooLarge));
+    }
+}
@@ -63,10 +63,14 @@ pub fn create_context_with_markets(
         unrealized_pnl: dec!(0),
         realized_pnl: dec!(0),
         usdc_balance: dec!(10000),
+        kelly_fraction: dec!(0.25),
+        last_trades: HashMap::new(),
+        stats: HashMap::new(),
     }
 }
 
-/// Count the number of each signal type in a list of signals.
+/// Count the number of each signal type in a list of signals. A replace
+/// counts as a cancel plus the orders it wants.
 ///
 /// Returns: (cancels, buys, sells, holds)
 pub fn count_signal_types(signals: &[Signal]) -> (usize, usize, usize, usize) {
@@ -77,6 +81,12 @@ pub fn count_signal_types(signals: &[Signal]) -> (usize, usize, usize, usize) {
     for signal in signals {
         match signal {
             Signal::Cancel { .. } => cancels += 1,
+            Signal::Replace { orders, .. } => {
+                let (_, b, s, _) = count_signal_types(orders);
+                cancels += 1;
+                buys += b;
+                sells += s;
+            }
             Signal::Buy { .. } => buys += 1,
             Signal::Sell { .. } => sells += 1,
             Signal::Hold => holds += 1,
@@ -1,6 +1,7 @@
 //! Auto-generated integration tests for dynamic_market_maker
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
+#![cfg(feature = "market-making")]
 
 mod fixtures;
 
@@ -1,6 +1,7 @@
 //! Auto-generated integration tests for market_maker
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
+#![cfg(feature = "market-making")]
 
 use pmengine::strategies::MarketMaker;
 use pmengine::strategy::Strategy;
@@ -1,6 +1,7 @@
 //! Auto-generated integration tests for spread_watcher
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
+#![cfg(feature = "diagnostics")]
 
 use pmengine::strategies::SpreadWatcher;
 use pmengine::strategy::Strategy;
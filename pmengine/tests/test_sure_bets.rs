@@ -1,13 +1,13 @@
 //! Auto-generated integration tests for sure_bets
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
+#![cfg(feature = "directional")]
 
 mod fixtures;
 
 use fixtures::*;
 use pmengine::strategies::SureBets;
 use pmengine::strategy::Strategy;
-use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 
@@ -0,0 +1,260 @@
+//! Operator endpoints under `/admin` (cache purge, tenant burst credits,
+//! shadow traffic statistics, WebSocket usage, maintenance mode).
+//!
+//! Disabled unless `PMPROXY_ADMIN_TOKEN` is set; requests must then carry
+//! `Authorization: Bearer <PMPROXY_ADMIN_TOKEN>`. Tenant JWTs are not
+//! accepted here. The IP allow/deny lists (and the per-IP rate limit) apply
+//! before the token is checked, see [`client_ip_guard`].
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{ConnectInfo, Path, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::auth::extract_bearer_token;
+use crate::error::{error_response, AuthError, ProxyError};
+use crate::maintenance::MaintenanceState;
+use crate::{check_client_ip, ProxyState};
+
+/// Body of `POST /admin/cache/purge`. With neither field set the whole cache
+/// is purged.
+#[derive(Debug, Default, Deserialize)]
+pub struct PurgeRequest {
+    /// Purge entries whose path starts with this prefix (e.g. `/gamma/markets`).
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Purge entries carrying this tag (e.g. `markets` or `markets/123`).
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Result of a purge.
+#[derive(Debug, Serialize)]
+pub struct PurgeResponse {
+    pub purged: usize,
+    pub remaining: usize,
+}
+
+/// Body of `POST /admin/tenants/{tenant_id}/credits`.
+#[derive(Debug, Deserialize)]
+pub struct GrantCreditsRequest {
+    /// Extra requests allowed once the tenant's bucket is empty.
+    pub requests: u64,
+    /// How long the grant lasts (seconds).
+    pub ttl_secs: u64,
+}
+
+/// Result of a revocation.
+#[derive(Debug, Serialize)]
+pub struct RevokeCreditsResponse {
+    pub tenant_id: String,
+    pub revoked: u64,
+}
+
+/// Middleware for the `/admin` routes: reject clients the IP lists block
+/// before they can try admin tokens.
+pub async fn client_ip_guard(State(state): State<Arc<ProxyState>>, req: Request, next: Next) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    if let Err(e) = check_client_ip(&state, req.headers(), peer) {
+        warn!(peer = ?peer, "Rejected admin request from blocked IP");
+        return e.into_response();
+    }
+    next.run(req).await
+}
+
+/// Reject the request unless the admin API is enabled and the token matches.
+fn reject_unauthorized(state: &ProxyState, headers: &HeaderMap) -> Option<Response> {
+    let Some(ref expected) = state.admin_token else {
+        return Some(ProxyError::NotFound { path: "/admin".to_string() }.into_response());
+    };
+    authorize(expected, headers).err().map(IntoResponse::into_response)
+}
+
+/// Check the request carries the admin bearer token.
+fn authorize(expected: &str, headers: &HeaderMap) -> Result<(), AuthError> {
+    let auth_header = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    let token = extract_bearer_token(auth_header)?;
+    if !constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+        warn!("Rejected admin request with invalid token");
+        return Err(AuthError::InvalidToken("invalid admin token".to_string()));
+    }
+    Ok(())
+}
+
+/// `409` for endpoints whose feature is switched off.
+fn disabled(error: &str, message: &str) -> Response {
+    error_response(StatusCode::CONFLICT, error, message, serde_json::Value::Null)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `POST /admin/cache/purge` - drop cached Gamma responses by prefix or tag.
+pub async fn purge_cache_handler(
+    State(state): State<Arc<ProxyState>>,
+    headers: HeaderMap,
+    body: Option<Json<PurgeRequest>>,
+) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    let Some(ref cache) = state.cache else {
+        return disabled(
+            "cache_disabled",
+            "Response caching is not enabled (PMPROXY_GAMMA_CACHE_SECS)",
+        );
+    };
+
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+    let purged = match (&request.prefix, &request.tag) {
+        (Some(prefix), None) => cache.purge_prefix(prefix),
+        (None, Some(tag)) => cache.purge_tag(tag),
+        (Some(prefix), Some(tag)) => cache.purge_prefix(prefix) + cache.purge_tag(tag),
+        (None, None) => cache.purge_all(),
+    };
+
+    info!(
+        prefix = ?request.prefix,
+        tag = ?request.tag,
+        purged = purged,
+        "Cache purged via admin API"
+    );
+
+    Json(PurgeResponse {
+        purged,
+        remaining: cache.len(),
+    })
+    .into_response()
+}
+
+const CREDITS_DISABLED: &str = "Burst credits require authentication (PMPROXY_AUTH_ENABLED)";
+
+/// `GET /admin/tenants/{tenant_id}/credits` - a tenant's unexpired burst credits.
+pub async fn get_credits_handler(
+    State(state): State<Arc<ProxyState>>,
+    Path(tenant_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    let Some(ref credits) = state.credits else {
+        return disabled("credits_disabled", CREDITS_DISABLED);
+    };
+
+    Json(credits.balance(&tenant_id)).into_response()
+}
+
+/// `POST /admin/tenants/{tenant_id}/credits` - grant temporary burst credits.
+pub async fn grant_credits_handler(
+    State(state): State<Arc<ProxyState>>,
+    Path(tenant_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<GrantCreditsRequest>,
+) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    let Some(ref credits) = state.credits else {
+        return disabled("credits_disabled", CREDITS_DISABLED);
+    };
+    if request.requests == 0 || request.ttl_secs == 0 {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "bad_request",
+            "requests and ttl_secs must be positive",
+            serde_json::Value::Null,
+        );
+    }
+
+    let balance = credits.grant(&tenant_id, request.requests, Duration::from_secs(request.ttl_secs));
+    (StatusCode::CREATED, Json(balance)).into_response()
+}
+
+/// `DELETE /admin/tenants/{tenant_id}/credits` - revoke all of a tenant's credits.
+pub async fn revoke_credits_handler(
+    State(state): State<Arc<ProxyState>>,
+    Path(tenant_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    let Some(ref credits) = state.credits else {
+        return disabled("credits_disabled", CREDITS_DISABLED);
+    };
+
+    let revoked = credits.revoke(&tenant_id);
+    Json(RevokeCreditsResponse { tenant_id, revoked }).into_response()
+}
+
+/// `GET /admin/mirror` - shadow traffic statistics per route.
+pub async fn mirror_report_handler(State(state): State<Arc<ProxyState>>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    Json(state.mirror.report()).into_response()
+}
+
+/// `GET /admin/websockets` - open WebSocket connections and subscriptions per tenant.
+pub async fn websocket_report_handler(State(state): State<Arc<ProxyState>>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    let Some(ref websockets) = state.websockets else {
+        return disabled(
+            "websockets_disabled",
+            "WebSocket accounting requires authentication (PMPROXY_AUTH_ENABLED)",
+        );
+    };
+    Json(websockets.report()).into_response()
+}
+
+/// `GET /admin/maintenance` - current maintenance settings.
+pub async fn get_maintenance_handler(State(state): State<Arc<ProxyState>>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    Json(state.maintenance.get()).into_response()
+}
+
+/// `PUT /admin/maintenance` - replace the read-only flag and disabled routes.
+pub async fn set_maintenance_handler(
+    State(state): State<Arc<ProxyState>>,
+    headers: HeaderMap,
+    Json(request): Json<MaintenanceState>,
+) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    if let Err(e) = state.maintenance.set(request) {
+        let message = e.to_string();
+        return error_response(StatusCode::BAD_REQUEST, "bad_request", &message, serde_json::Value::Null);
+    }
+    Json(state.maintenance.get()).into_response()
+}
+
+/// `DELETE /admin/maintenance` - re-enable every route.
+pub async fn clear_maintenance_handler(State(state): State<Arc<ProxyState>>, headers: HeaderMap) -> Response {
+    if let Some(rejection) = reject_unauthorized(&state, &headers) {
+        return rejection;
+    }
+    state
+        .maintenance
+        .set(MaintenanceState::default())
+        .expect("empty maintenance state is valid");
+    Json(state.maintenance.get()).into_response()
+}
@@ -0,0 +1,68 @@
+use clap::{Parser, Subcommand};
+use pmproxy::audit::{read_batches, verify};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "pmproxy-audit", about = "Inspect pmproxy audit exports")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Verify the hash chain (and signatures) of exported audit batches.
+    ///
+    /// Point it at a local copy of the export, e.g. after
+    /// `aws s3 sync s3://bucket/prefix ./audit`.
+    Verify {
+        /// Batch files or directories (searched recursively)
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Signing key; every batch must carry a valid signature
+        #[arg(long, env = "PMPROXY_AUDIT_SIGNING_KEY", hide_env_values = true)]
+        key: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let Command::Verify { paths, key } = Args::parse().command;
+
+    let mut batches = Vec::new();
+    for path in &paths {
+        match read_batches(path) {
+            Ok(b) => batches.extend(b),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if batches.is_empty() {
+        eprintln!("error: no audit batches found");
+        return ExitCode::FAILURE;
+    }
+
+    match verify(&batches, key.as_deref().map(str::as_bytes)) {
+        Ok(report) => {
+            println!(
+                "OK: {} stream(s), {} batch(es), {} record(s){}",
+                report.streams,
+                report.batches,
+                report.records,
+                if report.signed {
+                    ", signatures valid"
+                } else {
+                    " (signatures not checked)"
+                }
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("FAILED: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -3,14 +3,14 @@
 //! Handles JWKS fetching, caching, and JWT validation.
 
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 
 use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
-use crate::config::{ProxyConfig, TenantTier};
+use crate::config::{ProxyConfig, TenantTier, TierTable};
 use crate::error::AuthError;
 
 /// JWKS (JSON Web Key Set) response from Cognito.
@@ -35,7 +35,26 @@ struct Jwk {
 /// Cached JWKS with TTL.
 struct CachedJwks {
     keys: HashMap<String, DecodingKey>,
-    fetched_at: Instant,
+    /// Wall-clock time, so the age stays right across a Lambda environment's freezes
+    /// (the monotonic clock stops while it is frozen).
+    fetched_at: SystemTime,
+}
+
+impl CachedJwks {
+    fn age(&self) -> Duration {
+        self.fetched_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Freshness of the JWKS cache.
+#[derive(Debug, Clone, Serialize)]
+pub struct JwksStatus {
+    /// Number of cached signing keys.
+    pub key_count: usize,
+    /// Seconds since the keys were fetched (None = never fetched).
+    pub age_secs: Option<u64>,
+    /// Whether the keys are within the cache TTL.
+    pub fresh: bool,
 }
 
 /// JWKS cache that fetches and caches keys from Cognito.
@@ -126,19 +145,47 @@ impl JwksCache {
         let mut cache = self.cache.write().await;
         *cache = Some(CachedJwks {
             keys,
-            fetched_at: Instant::now(),
+            fetched_at: SystemTime::now(),
         });
 
         Ok(())
     }
 
+    /// Report cache age and key count (for readiness checks).
+    pub async fn status(&self) -> JwksStatus {
+        let cache = self.cache.read().await;
+        match *cache {
+            Some(ref cached) => {
+                let age = cached.age();
+                JwksStatus {
+                    key_count: cached.keys.len(),
+                    age_secs: Some(age.as_secs()),
+                    fresh: age < self.cache_ttl,
+                }
+            }
+            None => JwksStatus {
+                key_count: 0,
+                age_secs: None,
+                fresh: false,
+            },
+        }
+    }
+
+    /// Refresh the cache if it is empty or past its TTL.
+    pub async fn refresh_if_stale(&self) -> Result<(), AuthError> {
+        if self.status().await.fresh {
+            return Ok(());
+        }
+        self.refresh_cache().await
+    }
+
     /// Get a decoding key by key ID, refreshing cache if needed.
     async fn get_key(&self, kid: &str) -> Result<DecodingKey, AuthError> {
         // Check if cache is valid
         {
             let cache = self.cache.read().await;
             if let Some(ref cached) = *cache {
-                if cached.fetched_at.elapsed() < self.cache_ttl {
+                if cached.age() < self.cache_ttl {
                     if let Some(key) = cached.keys.get(kid) {
                         return Ok(key.clone());
                     }
@@ -244,6 +291,10 @@ pub struct CognitoClaims {
     /// Custom claim: Tenant tier for rate limiting.
     #[serde(rename = "custom:tenant_tier", default)]
     pub tenant_tier: Option<String>,
+
+    /// Cognito groups the user belongs to (for route authorization).
+    #[serde(rename = "cognito:groups", default)]
+    pub groups: Vec<String>,
 }
 
 impl CognitoClaims {
@@ -252,11 +303,11 @@ impl CognitoClaims {
         &self.sub
     }
 
-    /// Get the tenant tier, defaulting to Free.
-    pub fn tier(&self) -> TenantTier {
+    /// Resolve the tenant tier against a tier table, defaulting to the table's first tier.
+    pub fn tier(&self, tiers: &TierTable) -> TenantTier {
         self.tenant_tier
             .as_ref()
-            .map(|t| TenantTier::from_str(t))
+            .map(|t| tiers.resolve(t))
             .unwrap_or_default()
     }
 }
@@ -268,14 +319,21 @@ pub struct AuthenticatedTenant {
     pub tenant_id: String,
     /// Tenant tier for rate limiting.
     pub tier: TenantTier,
+    /// Name of the resolved tier (for logging).
+    pub tier_name: String,
+    /// Cognito groups (from the `cognito:groups` claim).
+    pub groups: Vec<String>,
 }
 
-impl From<CognitoClaims> for AuthenticatedTenant {
-    fn from(claims: CognitoClaims) -> Self {
-        let tier = claims.tier();
+impl AuthenticatedTenant {
+    /// Build tenant info from validated claims, resolving the tier against `tiers`.
+    pub fn from_claims(claims: CognitoClaims, tiers: &TierTable) -> Self {
+        let tier = claims.tier(tiers);
         Self {
             tenant_id: claims.sub,
             tier,
+            tier_name: tiers.get(tier).name.clone(),
+            groups: claims.groups,
         }
     }
 }
@@ -318,6 +376,7 @@ mod tests {
 
     #[test]
     fn test_cognito_claims_tier() {
+        let tiers = TierTable::default();
         let claims = CognitoClaims {
             sub: "user-123".to_string(),
             exp: 0,
@@ -326,8 +385,13 @@ mod tests {
             client_id: None,
             username: None,
             tenant_tier: Some("pro".to_string()),
+            groups: vec!["traders".to_string()],
         };
-        assert_eq!(claims.tier(), TenantTier::Pro);
+        assert_eq!(claims.tier(&tiers), tiers.resolve("pro"));
+
+        let tenant = AuthenticatedTenant::from_claims(claims, &tiers);
+        assert_eq!(tenant.tier_name, "pro");
+        assert_eq!(tenant.groups, vec!["traders"]);
 
         let claims_no_tier = CognitoClaims {
             sub: "user-123".to_string(),
@@ -337,7 +401,14 @@ mod tests {
             client_id: None,
             username: None,
             tenant_tier: None,
+            groups: Vec::new(),
         };
-        assert_eq!(claims_no_tier.tier(), TenantTier::Free);
+        assert_eq!(claims_no_tier.tier(&tiers), TenantTier::DEFAULT);
+
+        let parsed: CognitoClaims = serde_json::from_str(
+            r#"{"sub": "u", "exp": 0, "iss": "i", "token_use": "access", "cognito:groups": ["traders"]}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.groups, vec!["traders"]);
     }
 }
@@ -0,0 +1,150 @@
+//! Request body size limits.
+//!
+//! Bodies are buffered before being forwarded upstream, so each route gets a
+//! maximum size. A `Content-Length` over the limit is rejected before any of
+//! the body is read; bodies without one are read up to the limit and rejected
+//! as soon as they exceed it.
+//!
+//! Bodies are opaque bytes: binary, compressed and multipart payloads are
+//! forwarded unchanged with their `Content-Type` and `Content-Encoding`.
+//! Once buffered, the body's framing is the proxy's, so [`frame`] sets an
+//! exact `Content-Length` and drops `Expect: 100-continue`.
+
+use std::collections::HashMap;
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, HeaderValue};
+use http_body_util::LengthLimitError;
+
+use crate::error::{ConfigError, RequestError};
+
+/// Default maximum request body size (1 MiB).
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Maximum request body size, with per-route overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// Limit for routes without an override.
+    pub default: usize,
+    /// Limits by route prefix (`clob`, `gamma`, `chain`).
+    pub routes: HashMap<String, usize>,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_MAX_BODY_BYTES,
+            routes: HashMap::new(),
+        }
+    }
+}
+
+impl BodyLimits {
+    /// Limit for a route prefix.
+    pub fn for_route(&self, route: &str) -> usize {
+        self.routes.get(route).copied().unwrap_or(self.default)
+    }
+
+    /// Read a request body, enforcing `limit`.
+    pub async fn read(headers: &HeaderMap, body: Body, limit: usize) -> Result<Bytes, RequestError> {
+        let declared = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        if declared.is_some_and(|len| len > limit as u64) {
+            return Err(RequestError::PayloadTooLarge { limit });
+        }
+
+        axum::body::to_bytes(body, limit).await.map_err(|e| {
+            let e = e.into_inner();
+            if e.downcast_ref::<LengthLimitError>().is_some() {
+                RequestError::PayloadTooLarge { limit }
+            } else {
+                RequestError::BadBody(e.to_string())
+            }
+        })
+    }
+}
+
+/// Describe a buffered body in the upstream request headers.
+///
+/// The exact length replaces whatever the client declared (chunked requests
+/// have none), and `Expect` is dropped because the body has already been read.
+pub fn frame(headers: &mut HeaderMap, body: &Bytes) {
+    headers.remove(header::EXPECT);
+    if body.is_empty() {
+        headers.remove(header::CONTENT_LENGTH);
+    } else {
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    }
+}
+
+/// Parse per-route limits, e.g. `clob=65536,chain=262144`.
+pub fn parse_route_limits(list: &str) -> Result<HashMap<String, usize>, ConfigError> {
+    list.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|entry| {
+            let invalid = || ConfigError::Invalid(format!("invalid route body limit '{}'", entry.trim()));
+            let (route, limit) = entry.split_once('=').ok_or_else(invalid)?;
+            let route = route.trim().trim_matches('/');
+            if route.is_empty() {
+                return Err(invalid());
+            }
+            let limit = limit.trim().parse().map_err(|_| invalid())?;
+            Ok((route.to_string(), limit))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route_limits() {
+        let routes = parse_route_limits("clob=65536, /chain=1024,").unwrap();
+        assert_eq!(routes.get("clob"), Some(&65536));
+        assert_eq!(routes.get("chain"), Some(&1024));
+
+        assert!(parse_route_limits("clob").is_err());
+        assert!(parse_route_limits("clob=lots").is_err());
+        assert!(parse_route_limits("=10").is_err());
+
+        let limits = BodyLimits {
+            default: 100,
+            routes,
+        };
+        assert_eq!(limits.for_route("clob"), 65536);
+        assert_eq!(limits.for_route("gamma"), 100);
+    }
+
+    #[tokio::test]
+    async fn test_read_enforces_limit() {
+        let body = BodyLimits::read(&HeaderMap::new(), Body::from("hello"), 5).await.unwrap();
+        assert_eq!(&body[..], b"hello");
+
+        // No Content-Length: rejected while reading
+        let err = BodyLimits::read(&HeaderMap::new(), Body::from("hello!"), 5).await;
+        assert!(matches!(err, Err(RequestError::PayloadTooLarge { limit: 5 })));
+
+        // Declared length over the limit: rejected up front
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_LENGTH, "1000000".parse().unwrap());
+        let err = BodyLimits::read(&headers, Body::empty(), 5).await;
+        assert!(matches!(err, Err(RequestError::PayloadTooLarge { limit: 5 })));
+    }
+
+    #[test]
+    fn test_frame_sets_exact_length() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::EXPECT, "100-continue".parse().unwrap());
+        headers.insert(header::CONTENT_TYPE, "application/octet-stream".parse().unwrap());
+        frame(&mut headers, &Bytes::from_static(&[0x1f, 0x8b, 0xff, 0x00]));
+        assert_eq!(headers[header::CONTENT_LENGTH], "4");
+        assert_eq!(headers[header::CONTENT_TYPE], "application/octet-stream");
+        assert!(!headers.contains_key(header::EXPECT));
+
+        frame(&mut headers, &Bytes::new());
+        assert!(!headers.contains_key(header::CONTENT_LENGTH));
+    }
+}
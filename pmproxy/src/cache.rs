@@ -0,0 +1,226 @@
+//! Response cache for Gamma market metadata.
+//!
+//! Enabled with `PMPROXY_GAMMA_CACHE_SECS` (0 = off). Successful `GET`
+//! responses on the `gamma` route are cached by path and query for that many
+//! seconds. Every entry is tagged with the resource it came from
+//! (`markets`, `markets/123`, `events`, ...) so it can be purged by tag or
+//! path prefix through the admin API, and a non-`GET` request to a Gamma
+//! resource drops the cached entries of that resource.
+
+use std::time::{Duration, Instant};
+
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use dashmap::DashMap;
+use tracing::debug;
+
+/// Route whose responses are cached.
+pub const CACHED_ROUTE: &str = "gamma";
+
+/// A cached upstream response.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    tags: Vec<String>,
+    stored_at: Instant,
+}
+
+impl CachedResponse {
+    /// Rebuild the response, marked as a cache hit.
+    pub fn to_response(&self) -> Response {
+        let mut response = Response::builder().status(self.status);
+        for (name, value) in self.headers.iter() {
+            response = response.header(name, value);
+        }
+        response
+            .header("X-Cache", "HIT")
+            .body(Body::from(self.body.clone()))
+            .unwrap()
+    }
+}
+
+/// Tags for a route-relative path, e.g. `markets/123` → `markets`, `markets/123`.
+pub fn tags_for(path: &str) -> Vec<String> {
+    let mut segments = path.trim_matches('/').split('/').filter(|s| !s.is_empty());
+    let Some(resource) = segments.next() else {
+        return Vec::new();
+    };
+
+    let mut tags = vec![resource.to_string()];
+    if let Some(id) = segments.next() {
+        tags.push(format!("{}/{}", resource, id));
+    }
+    tags
+}
+
+/// TTL cache of upstream responses keyed by request path and query.
+pub struct ResponseCache {
+    entries: DashMap<String, CachedResponse>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// Cache key for a request (full proxy path plus query).
+    pub fn key(path: &str, query: &str) -> String {
+        if query.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}?{}", path, query)
+        }
+    }
+
+    /// Get a fresh entry.
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let entry = self.entries.get(key)?;
+        if entry.stored_at.elapsed() <= self.ttl {
+            return Some(entry.clone());
+        }
+        drop(entry);
+        self.entries.remove(key);
+        None
+    }
+
+    /// Store a response. Only `200 OK` responses are cached; when the cache
+    /// is full, expired entries are evicted first and the response is
+    /// skipped if that frees nothing.
+    pub fn insert(&self, key: String, route_path: &str, status: StatusCode, headers: HeaderMap, body: Bytes) {
+        if status != StatusCode::OK {
+            return;
+        }
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(&key) {
+            self.evict_expired();
+            if self.entries.len() >= self.max_entries {
+                debug!(key = %key, "Response cache full, not caching");
+                return;
+            }
+        }
+
+        self.entries.insert(
+            key,
+            CachedResponse {
+                status,
+                headers,
+                body,
+                tags: tags_for(route_path),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Remove entries whose key starts with `prefix`. Returns the number removed.
+    pub fn purge_prefix(&self, prefix: &str) -> usize {
+        self.purge(|key, _| key.starts_with(prefix))
+    }
+
+    /// Remove entries carrying `tag`. Returns the number removed.
+    pub fn purge_tag(&self, tag: &str) -> usize {
+        self.purge(|_, entry| entry.tags.iter().any(|t| t == tag))
+    }
+
+    /// Remove every entry. Returns the number removed.
+    pub fn purge_all(&self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+
+    /// Drop the cached entries of the resource a write request touched.
+    pub fn invalidate_for_write(&self, route_path: &str) -> usize {
+        tags_for(route_path)
+            .first()
+            .map(|resource| self.purge_tag(resource))
+            .unwrap_or(0)
+    }
+
+    fn purge(&self, matches: impl Fn(&str, &CachedResponse) -> bool) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|key, entry| !matches(key, entry));
+        before.saturating_sub(self.entries.len())
+    }
+
+    /// Remove expired entries.
+    pub fn evict_expired(&self) {
+        self.entries.retain(|_, entry| entry.stored_at.elapsed() <= self.ttl);
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(cache: &ResponseCache, path: &str) {
+        cache.insert(
+            ResponseCache::key(&format!("/gamma/{}", path), ""),
+            path,
+            StatusCode::OK,
+            HeaderMap::new(),
+            Bytes::from_static(b"{}"),
+        );
+    }
+
+    #[test]
+    fn test_tags_for() {
+        assert_eq!(tags_for("markets"), vec!["markets"]);
+        assert_eq!(tags_for("/markets/123/"), vec!["markets", "markets/123"]);
+        assert_eq!(tags_for("events/abc/tags"), vec!["events", "events/abc"]);
+        assert!(tags_for("").is_empty());
+    }
+
+    #[test]
+    fn test_purge_by_prefix_and_tag() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 100);
+        store(&cache, "markets/1");
+        store(&cache, "markets/2");
+        store(&cache, "events/1");
+        assert!(cache.get("/gamma/markets/1").is_some());
+
+        assert_eq!(cache.purge_tag("markets/1"), 1);
+        assert!(cache.get("/gamma/markets/1").is_none());
+
+        assert_eq!(cache.purge_prefix("/gamma/events"), 1);
+        assert_eq!(cache.len(), 1);
+
+        // A write to any market drops every cached market
+        assert_eq!(cache.invalidate_for_write("markets/9"), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_only_fresh_ok_responses_are_served() {
+        let cache = ResponseCache::new(Duration::ZERO, 1);
+        cache.insert("/gamma/x".into(), "x", StatusCode::NOT_FOUND, HeaderMap::new(), Bytes::new());
+        assert!(cache.is_empty());
+
+        store(&cache, "markets");
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("/gamma/markets").is_none());
+
+        // Full cache: expired entries make room
+        store(&cache, "a");
+        std::thread::sleep(Duration::from_millis(5));
+        store(&cache, "b");
+        assert_eq!(cache.len(), 1);
+    }
+}
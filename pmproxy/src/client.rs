@@ -0,0 +1,466 @@
+//! Typed client for bots calling pmproxy.
+//!
+//! Handles what every consumer otherwise reimplements: Cognito login and
+//! token refresh (`USER_PASSWORD_AUTH`, talking to the Cognito JSON API
+//! directly so no AWS SDK is needed), the proxy's route prefixes, and backing
+//! off on `429` responses.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), pmproxy::error::ClientError> {
+//! use pmproxy::client::{ProxyClient, Upstream};
+//!
+//! // PMPROXY_URL, PMPROXY_COGNITO_CLIENT_ID, PMPROXY_USERNAME, PMPROXY_PASSWORD
+//! let client = ProxyClient::from_env()?;
+//! let markets: serde_json::Value = client.get_json(Upstream::Gamma, "/markets?limit=5").await?;
+//! let me = client.whoami().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Rate-limited requests are retried with exponential backoff (honouring
+//! `Retry-After`) up to `max_retries` times. A `429` whose `Retry-After` is
+//! longer than the backoff cap, e.g. an exhausted daily quota, is returned as
+//! [`ClientError::RateLimited`] right away.
+
+use std::time::{Duration, Instant};
+
+use axum::http::{header, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::error::ClientError;
+
+/// Refresh tokens this long before they expire.
+const REFRESH_BEFORE_EXPIRY: Duration = Duration::from_secs(300);
+
+/// Upstream API behind a proxy route prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upstream {
+    Clob,
+    Gamma,
+    Data,
+    Chain,
+}
+
+impl Upstream {
+    /// Route prefix on the proxy.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Upstream::Clob => "clob",
+            Upstream::Gamma => "gamma",
+            Upstream::Data => "data",
+            Upstream::Chain => "chain",
+        }
+    }
+}
+
+/// Cognito user credentials.
+#[derive(Clone)]
+pub struct CognitoLogin {
+    pub client_id: String,
+    pub username: String,
+    pub password: String,
+    /// AWS region of the user pool.
+    pub region: String,
+}
+
+impl std::fmt::Debug for CognitoLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CognitoLogin")
+            .field("client_id", &self.client_id)
+            .field("username", &self.username)
+            .field("region", &self.region)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Instant,
+}
+
+/// `InitiateAuth` response (only the fields used).
+#[derive(Debug, Deserialize)]
+struct InitiateAuthResponse {
+    #[serde(rename = "AuthenticationResult")]
+    result: Option<AuthenticationResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthenticationResult {
+    #[serde(rename = "AccessToken")]
+    access_token: String,
+    #[serde(rename = "RefreshToken")]
+    refresh_token: Option<String>,
+    #[serde(rename = "ExpiresIn")]
+    expires_in: u64,
+}
+
+/// Client for a pmproxy deployment.
+pub struct ProxyClient {
+    http: reqwest::Client,
+    base_url: String,
+    login: Option<CognitoLogin>,
+    /// Cognito endpoint override (tests, VPC endpoints).
+    cognito_endpoint: Option<String>,
+    token: Mutex<Option<Token>>,
+    max_retries: u32,
+    max_backoff: Duration,
+}
+
+impl ProxyClient {
+    /// Client for a proxy without authentication.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            login: None,
+            cognito_endpoint: None,
+            token: Mutex::new(None),
+            max_retries: 3,
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Log in to Cognito with `login` and send its access token.
+    pub fn with_login(mut self, login: CognitoLogin) -> Self {
+        self.login = Some(login);
+        self
+    }
+
+    /// Retries on `429` before giving up (default 3).
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Longest wait between retries (default 30s).
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Use a custom HTTP client (timeouts, proxies).
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Send Cognito requests to `endpoint` instead of the regional endpoint.
+    pub fn with_cognito_endpoint(mut self, endpoint: &str) -> Self {
+        self.cognito_endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Build from `PMPROXY_URL` and, if `PMPROXY_COGNITO_CLIENT_ID` is set,
+    /// `PMPROXY_USERNAME`, `PMPROXY_PASSWORD` and `PMPROXY_COGNITO_REGION`
+    /// (default us-east-1), the same variables pmengine reads.
+    pub fn from_env() -> Result<Self, ClientError> {
+        let var = |name: &'static str| std::env::var(name).map_err(|_| ClientError::MissingConfig(name));
+        let client = Self::new(&var("PMPROXY_URL")?);
+
+        let Ok(client_id) = std::env::var("PMPROXY_COGNITO_CLIENT_ID") else {
+            return Ok(client);
+        };
+        Ok(client.with_login(CognitoLogin {
+            client_id,
+            username: var("PMPROXY_USERNAME")?,
+            password: var("PMPROXY_PASSWORD")?,
+            region: std::env::var("PMPROXY_COGNITO_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+        }))
+    }
+
+    /// Full proxy URL of `path` on `upstream`.
+    pub fn url(&self, upstream: Upstream, path: &str) -> String {
+        format!("{}/{}/{}", self.base_url, upstream.prefix(), path.trim_start_matches('/'))
+    }
+
+    /// `GET` and decode a JSON response.
+    pub async fn get_json<T: DeserializeOwned>(&self, upstream: Upstream, path: &str) -> Result<T, ClientError> {
+        let response = self.send(Method::GET, &self.url(upstream, path), None).await?;
+        decode(response).await
+    }
+
+    /// `POST` a JSON body and decode the JSON response.
+    pub async fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        upstream: Upstream,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let body = serde_json::to_vec(body).map_err(|e| ClientError::Decode(e.to_string()))?;
+        let response = self.send(Method::POST, &self.url(upstream, path), Some(body)).await?;
+        decode(response).await
+    }
+
+    /// The caller's tenant, tier, limits and remaining quota (`GET /whoami`).
+    pub async fn whoami(&self) -> Result<serde_json::Value, ClientError> {
+        let response = self
+            .send(Method::GET, &format!("{}/whoami", self.base_url), None)
+            .await?;
+        decode(response).await
+    }
+
+    /// Send a request with authentication and `429` backoff. Non-success
+    /// responses other than `429` are returned as they are.
+    pub async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response, ClientError> {
+        let mut attempt = 0;
+        let mut reauthenticated = false;
+        loop {
+            let mut request = self.http.request(method.clone(), url);
+            if let Some(token) = self.access_token().await? {
+                request = request.bearer_auth(token);
+            }
+            if let Some(ref body) = body {
+                request = request
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(body.clone());
+            }
+
+            let response = request.send().await?;
+            match response.status() {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = retry_after(&response);
+                    let wait = retry_after.unwrap_or_else(|| backoff(attempt));
+                    if attempt >= self.max_retries || wait > self.max_backoff {
+                        return Err(ClientError::RateLimited { retry_after });
+                    }
+                    debug!(url = %url, attempt = attempt, wait_ms = wait.as_millis() as u64, "Rate limited, backing off");
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                // The token may have been revoked or expired early: log in again once
+                StatusCode::UNAUTHORIZED if self.login.is_some() && !reauthenticated => {
+                    warn!(url = %url, "Proxy rejected token, logging in again");
+                    *self.token.lock().await = None;
+                    reauthenticated = true;
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    /// Current access token, logging in or refreshing as needed (None without a login).
+    async fn access_token(&self) -> Result<Option<String>, ClientError> {
+        let Some(ref login) = self.login else {
+            return Ok(None);
+        };
+        let mut token = self.token.lock().await;
+        if let Some(ref t) = *token {
+            if Instant::now() + REFRESH_BEFORE_EXPIRY < t.expires_at {
+                return Ok(Some(t.access_token.clone()));
+            }
+        }
+
+        let refresh_token = token.as_ref().and_then(|t| t.refresh_token.clone());
+        let fresh = match refresh_token {
+            Some(refresh) => match self.refresh(login, &refresh).await {
+                Ok(t) => t,
+                Err(e) => {
+                    debug!(error = %e, "Token refresh failed, logging in again");
+                    self.authenticate(login).await?
+                }
+            },
+            None => self.authenticate(login).await?,
+        };
+        let access_token = fresh.access_token.clone();
+        *token = Some(fresh);
+        Ok(Some(access_token))
+    }
+
+    async fn authenticate(&self, login: &CognitoLogin) -> Result<Token, ClientError> {
+        info!(username = %login.username, "Logging in to Cognito");
+        let params = serde_json::json!({ "USERNAME": login.username, "PASSWORD": login.password });
+        self.initiate_auth(login, "USER_PASSWORD_AUTH", params, None).await
+    }
+
+    async fn refresh(&self, login: &CognitoLogin, refresh_token: &str) -> Result<Token, ClientError> {
+        debug!("Refreshing Cognito token");
+        let params = serde_json::json!({ "REFRESH_TOKEN": refresh_token });
+        // Cognito does not return a new refresh token; keep the old one
+        self.initiate_auth(login, "REFRESH_TOKEN_AUTH", params, Some(refresh_token))
+            .await
+    }
+
+    async fn initiate_auth(
+        &self,
+        login: &CognitoLogin,
+        flow: &str,
+        params: serde_json::Value,
+        refresh_token: Option<&str>,
+    ) -> Result<Token, ClientError> {
+        let endpoint = self
+            .cognito_endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://cognito-idp.{}.amazonaws.com", login.region));
+        let body = serde_json::json!({
+            "AuthFlow": flow,
+            "ClientId": login.client_id,
+            "AuthParameters": params,
+        });
+
+        let response = self
+            .http
+            .post(format!("{}/", endpoint))
+            .header(header::CONTENT_TYPE, "application/x-amz-json-1.1")
+            .header("X-Amz-Target", "AWSCognitoIdentityProviderService.InitiateAuth")
+            .body(body.to_string())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(ClientError::Login(format!("{}: {}", status, text.trim())));
+        }
+
+        let parsed: InitiateAuthResponse = decode(response).await?;
+        let result = parsed
+            .result
+            .ok_or_else(|| ClientError::Login("no AuthenticationResult (challenge required?)".to_string()))?;
+        Ok(Token {
+            access_token: result.access_token,
+            refresh_token: result.refresh_token.or_else(|| refresh_token.map(String::from)),
+            expires_at: Instant::now() + Duration::from_secs(result.expires_in),
+        })
+    }
+}
+
+/// Decode a JSON response, turning error statuses into [`ClientError::Status`].
+async fn decode<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, ClientError> {
+    let status = response.status();
+    let bytes = response.bytes().await?;
+    if !status.is_success() {
+        return Err(ClientError::Status {
+            status: status.as_u16(),
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+        });
+    }
+    serde_json::from_slice(&bytes).map_err(|e| ClientError::Decode(e.to_string()))
+}
+
+/// `Retry-After` in seconds, if present.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Exponential backoff: 250ms, 500ms, 1s, ...
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(250u64.saturating_mul(1 << attempt.min(10)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use axum::{routing::post, Router};
+
+    /// Serve `router` on an ephemeral port, returning its base URL.
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_urls_and_backoff() {
+        let client = ProxyClient::new("https://proxy.example.com/");
+        assert_eq!(
+            client.url(Upstream::Gamma, "/markets?limit=5"),
+            "https://proxy.example.com/gamma/markets?limit=5"
+        );
+        assert_eq!(client.url(Upstream::Clob, "book"), "https://proxy.example.com/clob/book");
+        assert_eq!(backoff(0), Duration::from_millis(250));
+        assert_eq!(backoff(2), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_login_and_rate_limit_backoff() {
+        let logins = Arc::new(AtomicU32::new(0));
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let cognito = {
+            let logins = logins.clone();
+            Router::new().route(
+                "/",
+                post(move || {
+                    logins.fetch_add(1, Ordering::SeqCst);
+                    async {
+                        r#"{"AuthenticationResult": {"AccessToken": "tok", "RefreshToken": "r", "ExpiresIn": 3600}}"#
+                    }
+                }),
+            )
+        };
+        let proxy = {
+            let calls = calls.clone();
+            Router::new().route(
+                "/gamma/markets",
+                axum::routing::get(move |headers: axum::http::HeaderMap| {
+                    let n = calls.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        assert_eq!(headers["authorization"], "Bearer tok");
+                        if n == 0 {
+                            (StatusCode::TOO_MANY_REQUESTS, [("retry-after", "0")], "{}")
+                        } else {
+                            (StatusCode::OK, [("retry-after", "0")], r#"[{"id": 1}]"#)
+                        }
+                    }
+                }),
+            )
+        };
+
+        let client = ProxyClient::new(&serve(proxy).await)
+            .with_cognito_endpoint(&serve(cognito).await)
+            .with_login(CognitoLogin {
+                client_id: "client".to_string(),
+                username: "bot".to_string(),
+                password: "pw".to_string(),
+                region: "us-east-1".to_string(),
+            });
+
+        let markets: serde_json::Value = client.get_json(Upstream::Gamma, "/markets").await.unwrap();
+        assert_eq!(markets[0]["id"], 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The token is cached across requests
+        let _: serde_json::Value = client.get_json(Upstream::Gamma, "/markets").await.unwrap();
+        assert_eq!(logins.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_is_not_waited_out() {
+        let proxy = Router::new().route(
+            "/clob/book",
+            axum::routing::get(|| async { (StatusCode::TOO_MANY_REQUESTS, [("retry-after", "86400")], "{}") }),
+        );
+        let client = ProxyClient::new(&serve(proxy).await);
+
+        let err = client
+            .get_json::<serde_json::Value>(Upstream::Clob, "/book")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ClientError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(86_400)
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+//! Clock skew reporting for clients that sign requests.
+//!
+//! Polymarket rejects L2-signed requests whose `POLY_TIMESTAMP` is too far
+//! from its own clock. The proxy probes the CLOB's `/time` in the background
+//! and `GET /time` (no auth) reports both clocks:
+//!
+//! ```json
+//! {"server_time_ms": 1767620000123, "clob_offset_ms": -850, "clob_time_ms": 1767619999273,
+//!  "rtt_ms": 42, "checked_at": 1767619990}
+//! ```
+//!
+//! `clob_offset_ms` is CLOB time minus proxy time, measured at the midpoint
+//! of the probe's round trip (the CLOB reports whole seconds, so it is only
+//! accurate to about a second). A client estimates CLOB time as its own clock
+//! plus `clob_time_ms` minus its own time at the midpoint of its `/time` call.
+
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// CLOB endpoint returning its Unix time in seconds.
+pub const CLOB_TIME_URL: &str = "https://clob.polymarket.com/time";
+
+/// Timeout for a single probe request.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Offset from the most recent successful probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClockSample {
+    offset_ms: i64,
+    rtt_ms: u64,
+    /// Unix timestamp (seconds) of the probe.
+    checked_at: u64,
+}
+
+/// Response of `GET /time`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeReport {
+    /// Proxy clock, Unix milliseconds.
+    pub server_time_ms: i64,
+    /// CLOB clock minus proxy clock (None until a probe succeeds).
+    pub clob_offset_ms: Option<i64>,
+    /// Estimated CLOB clock, Unix milliseconds.
+    pub clob_time_ms: Option<i64>,
+    /// Round trip of the probe the offset comes from.
+    pub rtt_ms: Option<u64>,
+    /// Unix timestamp (seconds) of that probe.
+    pub checked_at: Option<u64>,
+    /// Error of the last probe, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Background prober of the CLOB clock.
+pub struct ClockProbe {
+    client: reqwest::Client,
+    url: String,
+    latest: RwLock<Option<ClockSample>>,
+    last_error: RwLock<Option<String>>,
+}
+
+impl ClockProbe {
+    /// Create a prober for a `/time` endpoint (normally [`CLOB_TIME_URL`]).
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(PROBE_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.into(),
+            latest: RwLock::new(None),
+            last_error: RwLock::new(None),
+        }
+    }
+
+    /// Measure the upstream clock once; the previous offset is kept on failure.
+    pub async fn probe(&self) -> Result<i64, String> {
+        let sent_ms = unix_millis();
+        let started = Instant::now();
+        let result = async {
+            let resp = self.client.get(&self.url).send().await.map_err(|e| e.to_string())?;
+            if !resp.status().is_success() {
+                return Err(format!("HTTP {}", resp.status()));
+            }
+            let body = resp.text().await.map_err(|e| e.to_string())?;
+            parse_upstream_millis(&body).ok_or_else(|| format!("unexpected /time body: {}", body.trim()))
+        }
+        .await;
+        let rtt = started.elapsed();
+
+        match result {
+            Ok(upstream_ms) => {
+                let midpoint_ms = sent_ms + (rtt.as_millis() / 2) as i64;
+                let sample = ClockSample {
+                    offset_ms: upstream_ms - midpoint_ms,
+                    rtt_ms: rtt.as_millis() as u64,
+                    checked_at: (sent_ms / 1000) as u64,
+                };
+                debug!(offset_ms = sample.offset_ms, rtt_ms = sample.rtt_ms, "CLOB clock probed");
+                *self.latest.write().unwrap() = Some(sample);
+                *self.last_error.write().unwrap() = None;
+                Ok(sample.offset_ms)
+            }
+            Err(e) => {
+                warn!(url = %self.url, error = %e, "CLOB clock probe failed");
+                *self.last_error.write().unwrap() = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Current proxy time and the last measured CLOB offset.
+    pub fn report(&self) -> TimeReport {
+        let now_ms = unix_millis();
+        let sample = *self.latest.read().unwrap();
+        TimeReport {
+            server_time_ms: now_ms,
+            clob_offset_ms: sample.map(|s| s.offset_ms),
+            clob_time_ms: sample.map(|s| now_ms + s.offset_ms),
+            rtt_ms: sample.map(|s| s.rtt_ms),
+            checked_at: sample.map(|s| s.checked_at),
+            error: self.last_error.read().unwrap().clone(),
+        }
+    }
+
+    /// Start the background probe loop.
+    pub fn spawn(self: &std::sync::Arc<Self>, interval: Duration) {
+        let probe = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                probe.probe().await.ok();
+            }
+        });
+    }
+}
+
+impl Default for ClockProbe {
+    fn default() -> Self {
+        Self::new(CLOB_TIME_URL)
+    }
+}
+
+/// Upstream time in Unix milliseconds from a `/time` body: a bare number
+/// (seconds, or milliseconds if that large), optionally quoted.
+fn parse_upstream_millis(body: &str) -> Option<i64> {
+    let value: f64 = body.trim().trim_matches('"').parse().ok()?;
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+    let millis = if value >= 1e12 { value } else { value * 1000.0 };
+    Some(millis.round() as i64)
+}
+
+fn unix_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_upstream_time() {
+        assert_eq!(parse_upstream_millis("1767620000\n"), Some(1_767_620_000_000));
+        assert_eq!(parse_upstream_millis("\"1767620000\""), Some(1_767_620_000_000));
+        assert_eq!(parse_upstream_millis("1767620000123"), Some(1_767_620_000_123));
+        assert_eq!(parse_upstream_millis("{\"time\": 1}"), None);
+        assert_eq!(parse_upstream_millis("-5"), None);
+    }
+
+    #[tokio::test]
+    async fn test_probe_measures_offset() {
+        // Upstream clock running 30s ahead
+        let failing = Arc::new(AtomicBool::new(false));
+        let fail = failing.clone();
+        let upstream = Router::new().route(
+            "/time",
+            get(move || {
+                let fail = fail.load(Ordering::Relaxed);
+                async move {
+                    if fail {
+                        return Err(StatusCode::SERVICE_UNAVAILABLE);
+                    }
+                    Ok(((unix_millis() + 30_000) / 1000).to_string())
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let probe = ClockProbe::new(format!("http://{}/time", addr));
+        assert_eq!(probe.report().clob_offset_ms, None);
+
+        let offset = probe.probe().await.unwrap();
+        // Whole-second resolution upstream
+        assert!((28_900..=30_100).contains(&offset), "offset {}", offset);
+        let report = probe.report();
+        assert_eq!(report.clob_offset_ms, Some(offset));
+        assert_eq!(report.clob_time_ms, Some(report.server_time_ms + offset));
+        assert!(report.error.is_none());
+
+        // A failed probe keeps the last offset and reports the error
+        failing.store(true, Ordering::Relaxed);
+        assert!(probe.probe().await.is_err());
+        let report = probe.report();
+        assert_eq!(report.clob_offset_ms, Some(offset));
+        assert!(report.error.unwrap().contains("503"));
+    }
+}
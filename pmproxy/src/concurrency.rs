@@ -0,0 +1,165 @@
+//! Per-tenant concurrency limiting.
+//!
+//! Rate limiting bounds how often a tenant may start requests; this bounds how
+//! many may be in flight at once, so a single tenant can't hold dozens of slow
+//! upstream calls open.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+use crate::config::TenantTier;
+use crate::error::AuthError;
+use crate::tiers::TierRegistry;
+
+/// A tenant's semaphore together with the limit it was built from.
+#[derive(Clone)]
+struct TenantSlots {
+    semaphore: Arc<Semaphore>,
+    limit: u32,
+}
+
+/// Per-tenant in-flight request limiter.
+pub struct TenantConcurrencyLimiter {
+    /// Map of tenant_id -> semaphore.
+    slots: DashMap<String, TenantSlots>,
+    /// Tier definitions (hot-reloadable).
+    tiers: TierRegistry,
+}
+
+impl TenantConcurrencyLimiter {
+    /// Create a new per-tenant concurrency limiter.
+    pub fn new(tiers: TierRegistry) -> Self {
+        Self {
+            slots: DashMap::new(),
+            tiers,
+        }
+    }
+
+    /// Get or create the semaphore for a tenant.
+    ///
+    /// If the tier's limit changed, a fresh semaphore replaces the old one;
+    /// permits already handed out keep draining against the old semaphore.
+    fn get_or_create(&self, tenant_id: &str, limit: u32) -> Arc<Semaphore> {
+        if let Some(entry) = self.slots.get(tenant_id) {
+            if entry.limit == limit {
+                return entry.semaphore.clone();
+            }
+        }
+
+        let mut entry = self
+            .slots
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| TenantSlots {
+                semaphore: Arc::new(Semaphore::new(limit as usize)),
+                limit,
+            });
+        if entry.limit != limit {
+            *entry = TenantSlots {
+                semaphore: Arc::new(Semaphore::new(limit as usize)),
+                limit,
+            };
+        }
+        entry.semaphore.clone()
+    }
+
+    /// Reserve an in-flight slot for a request.
+    ///
+    /// Returns `Ok(None)` if the tenant's tier has no concurrency cap, a permit
+    /// to hold for the lifetime of the request otherwise, or
+    /// `Err(AuthError::TooManyConcurrentRequests)` if all slots are taken.
+    pub fn acquire(
+        &self,
+        tenant_id: &str,
+        tier: TenantTier,
+    ) -> Result<Option<OwnedSemaphorePermit>, AuthError> {
+        let Some(limit) = self.tiers.snapshot().get(tier).max_concurrent_requests else {
+            return Ok(None);
+        };
+
+        match self.get_or_create(tenant_id, limit).try_acquire_owned() {
+            Ok(permit) => Ok(Some(permit)),
+            Err(_) => {
+                debug!(tenant_id = %tenant_id, limit = limit, "Concurrency limit exceeded");
+                Err(AuthError::TooManyConcurrentRequests)
+            }
+        }
+    }
+
+    /// Number of requests currently in flight for a tenant.
+    pub fn in_flight(&self, tenant_id: &str) -> usize {
+        self.slots
+            .get(tenant_id)
+            .map(|e| e.limit as usize - e.semaphore.available_permits())
+            .unwrap_or(0)
+    }
+
+    /// Get the number of tracked tenants (for monitoring).
+    pub fn tenant_count(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiers::TierTable;
+
+    fn limiter() -> (TenantConcurrencyLimiter, TierRegistry) {
+        let table = TierTable::from_json(
+            r#"[{"name": "free", "rpm": 60, "burst": 10, "max_concurrent_requests": 2},
+                {"name": "unlimited", "rpm": 60, "burst": 10}]"#,
+        )
+        .unwrap();
+        let registry = TierRegistry::new(table);
+        (TenantConcurrencyLimiter::new(registry.clone()), registry)
+    }
+
+    #[test]
+    fn test_rejects_when_slots_exhausted() {
+        let (limiter, _) = limiter();
+
+        let first = limiter.acquire("t1", TenantTier::DEFAULT).unwrap();
+        let _second = limiter.acquire("t1", TenantTier::DEFAULT).unwrap();
+        assert_eq!(limiter.in_flight("t1"), 2);
+        assert!(matches!(
+            limiter.acquire("t1", TenantTier::DEFAULT),
+            Err(AuthError::TooManyConcurrentRequests)
+        ));
+
+        // Other tenants have their own slots
+        assert!(limiter.acquire("t2", TenantTier::DEFAULT).is_ok());
+
+        // Finishing a request frees its slot
+        drop(first);
+        assert!(limiter.acquire("t1", TenantTier::DEFAULT).is_ok());
+    }
+
+    #[test]
+    fn test_uncapped_tier_is_not_tracked() {
+        let (limiter, registry) = limiter();
+        let tier = registry.snapshot().resolve("unlimited");
+
+        for _ in 0..10 {
+            assert!(limiter.acquire("t1", tier).unwrap().is_none());
+        }
+        assert_eq!(limiter.tenant_count(), 0);
+    }
+
+    #[test]
+    fn test_applies_reloaded_limit() {
+        let (limiter, registry) = limiter();
+        let _held = limiter.acquire("t1", TenantTier::DEFAULT).unwrap();
+
+        registry.replace(
+            TierTable::from_json(r#"[{"name": "free", "rpm": 60, "burst": 10, "concurrency": 5}]"#)
+                .unwrap(),
+        );
+
+        for _ in 0..5 {
+            assert!(limiter.acquire("t1", TenantTier::DEFAULT).unwrap().is_some());
+        }
+    }
+}
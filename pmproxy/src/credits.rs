@@ -0,0 +1,294 @@
+//! Temporary per-tenant burst credits.
+//!
+//! A grant (e.g. 500 extra requests for the next hour) is spent only when the
+//! tenant's token bucket is empty, so a customer running an occasional batch
+//! job can go over its tier's rate without a tier change. Grants are issued
+//! through the admin API and optionally persisted to a JSON file
+//! (`PMPROXY_CREDITS_FILE`) next to quota usage so they survive restarts.
+//! Credits do not bypass daily/monthly quotas or concurrency limits.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::error::ConfigError;
+
+/// One burst-credit grant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreditGrant {
+    /// Requests granted.
+    pub granted: u64,
+    /// Requests still available.
+    pub remaining: u64,
+    /// Unix time (seconds) the grant expires.
+    pub expires_at: u64,
+}
+
+/// A tenant's unexpired credits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CreditBalance {
+    pub tenant_id: String,
+    /// Requests available across all grants.
+    pub remaining: u64,
+    /// Grants, soonest expiry first.
+    pub grants: Vec<CreditGrant>,
+}
+
+/// Burst-credit grants by tenant.
+pub struct CreditLedger {
+    grants: DashMap<String, Vec<CreditGrant>>,
+    /// File to persist grants to (None = in-memory only).
+    store: Option<PathBuf>,
+    /// Whether grants changed since the last flush.
+    dirty: AtomicBool,
+}
+
+impl CreditLedger {
+    /// Create an in-memory ledger.
+    pub fn new() -> Self {
+        Self {
+            grants: DashMap::new(),
+            store: None,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a ledger persisted to `path`, restoring any saved grants.
+    pub fn with_store(path: PathBuf) -> Self {
+        let ledger = Self {
+            grants: DashMap::new(),
+            store: Some(path),
+            dirty: AtomicBool::new(false),
+        };
+
+        match ledger.load() {
+            Ok(count) => info!(tenants = count, "Restored burst credits"),
+            Err(e) => warn!(error = %e, "Failed to restore burst credits, starting fresh"),
+        }
+
+        ledger
+    }
+
+    /// Grant `requests` extra requests to a tenant for `ttl`.
+    pub fn grant(&self, tenant_id: &str, requests: u64, ttl: Duration) -> CreditBalance {
+        self.grant_at(tenant_id, requests, ttl, SystemTime::now())
+    }
+
+    fn grant_at(&self, tenant_id: &str, requests: u64, ttl: Duration, now: SystemTime) -> CreditBalance {
+        let secs = unix_secs(now);
+        {
+            let mut grants = self.grants.entry(tenant_id.to_string()).or_default();
+            grants.retain(|g| g.expires_at > secs && g.remaining > 0);
+            grants.push(CreditGrant {
+                granted: requests,
+                remaining: requests,
+                expires_at: secs + ttl.as_secs(),
+            });
+            grants.sort_by_key(|g| g.expires_at);
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!(tenant_id = %tenant_id, requests = requests, ttl_secs = ttl.as_secs(), "Granted burst credits");
+        self.balance_at(tenant_id, now)
+    }
+
+    /// Spend one credit (from the grant expiring soonest). Returns false if the
+    /// tenant has none left.
+    pub fn consume(&self, tenant_id: &str) -> bool {
+        self.consume_at(tenant_id, SystemTime::now())
+    }
+
+    fn consume_at(&self, tenant_id: &str, now: SystemTime) -> bool {
+        let Some(mut grants) = self.grants.get_mut(tenant_id) else {
+            return false;
+        };
+        let secs = unix_secs(now);
+        let Some(grant) = grants
+            .iter_mut()
+            .find(|g| g.expires_at > secs && g.remaining > 0)
+        else {
+            return false;
+        };
+
+        grant.remaining -= 1;
+        self.dirty.store(true, Ordering::Relaxed);
+        debug!(tenant_id = %tenant_id, remaining = grant.remaining, "Spent burst credit");
+        true
+    }
+
+    /// Unexpired credits of a tenant.
+    pub fn balance(&self, tenant_id: &str) -> CreditBalance {
+        self.balance_at(tenant_id, SystemTime::now())
+    }
+
+    fn balance_at(&self, tenant_id: &str, now: SystemTime) -> CreditBalance {
+        let secs = unix_secs(now);
+        let grants: Vec<CreditGrant> = self
+            .grants
+            .get(tenant_id)
+            .map(|g| {
+                g.iter()
+                    .filter(|g| g.expires_at > secs && g.remaining > 0)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        CreditBalance {
+            tenant_id: tenant_id.to_string(),
+            remaining: grants.iter().map(|g| g.remaining).sum(),
+            grants,
+        }
+    }
+
+    /// Remove all of a tenant's credits. Returns the requests revoked.
+    pub fn revoke(&self, tenant_id: &str) -> u64 {
+        let revoked = self.balance(tenant_id).remaining;
+        if self.grants.remove(tenant_id).is_some() {
+            self.dirty.store(true, Ordering::Relaxed);
+            info!(tenant_id = %tenant_id, revoked = revoked, "Revoked burst credits");
+        }
+        revoked
+    }
+
+    /// Drop expired and spent grants.
+    pub fn evict_expired(&self) {
+        let secs = unix_secs(SystemTime::now());
+        let before = self.grants.len();
+        self.grants.retain(|_, grants| {
+            grants.retain(|g| g.expires_at > secs && g.remaining > 0);
+            !grants.is_empty()
+        });
+        if self.grants.len() != before {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Write grants to the store if they changed. No-op without a store.
+    pub fn flush(&self) -> Result<(), ConfigError> {
+        let Some(ref path) = self.store else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let snapshot: HashMap<String, Vec<CreditGrant>> = self
+            .grants
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| ConfigError::Invalid(format!("burst credits: {}", e)))?;
+
+        // Write-then-rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        let write = std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, path));
+        write.map_err(|e| {
+            self.dirty.store(true, Ordering::Relaxed);
+            ConfigError::Io {
+                path: path.display().to_string(),
+                source: e,
+            }
+        })
+    }
+
+    /// Periodically drop expired grants and flush to the store.
+    pub fn spawn_flusher(self: &std::sync::Arc<Self>, interval: Duration) {
+        let ledger = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                ledger.evict_expired();
+                if let Err(e) = ledger.flush() {
+                    warn!(error = %e, "Failed to persist burst credits");
+                }
+            }
+        });
+    }
+
+    fn load(&self) -> Result<usize, ConfigError> {
+        let Some(ref path) = self.store else {
+            return Ok(0);
+        };
+        if !path.exists() {
+            return Ok(0);
+        }
+
+        let grants = read_grants(path)?;
+        let count = grants.len();
+        for (tenant_id, g) in grants {
+            self.grants.insert(tenant_id, g);
+        }
+        Ok(count)
+    }
+}
+
+impl Default for CreditLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_grants(path: &Path) -> Result<HashMap<String, Vec<CreditGrant>>, ConfigError> {
+    let json = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    serde_json::from_str(&json).map_err(|e| ConfigError::Invalid(format!("burst credits: {}", e)))
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_credits_spent_soonest_expiry_first_and_expire() {
+        let ledger = CreditLedger::new();
+        ledger.grant_at("t1", 2, Duration::from_secs(3600), at(1_000));
+        ledger.grant_at("t1", 1, Duration::from_secs(60), at(1_000));
+        assert_eq!(ledger.balance_at("t1", at(1_000)).remaining, 3);
+
+        // The 60s grant is spent first
+        assert!(ledger.consume_at("t1", at(1_010)));
+        assert_eq!(ledger.balance_at("t1", at(1_010)).grants.len(), 1);
+
+        // After an hour everything has expired
+        assert!(ledger.consume_at("t1", at(2_000)));
+        assert!(!ledger.consume_at("t1", at(4_601)));
+        assert!(!ledger.consume_at("t2", at(1_000)));
+
+        assert_eq!(ledger.revoke("t1"), 0);
+    }
+
+    #[test]
+    fn test_credits_persist_across_restarts() {
+        let path = std::env::temp_dir().join(format!("pmproxy-credits-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let ledger = CreditLedger::with_store(path.clone());
+        ledger.grant("t1", 500, Duration::from_secs(3600));
+        assert!(ledger.consume("t1"));
+        ledger.flush().unwrap();
+
+        let restored = CreditLedger::with_store(path.clone());
+        assert_eq!(restored.balance("t1").remaining, 499);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
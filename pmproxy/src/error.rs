@@ -1,12 +1,19 @@
-//! Error types for authentication and rate limiting.
+//! Error types for authentication, rate limiting, request handling, configuration,
+//! auditing, and the proxy client.
+//!
+//! Errors the proxy generates itself render one JSON envelope:
+//! `{"error": code, "message": ..., "request_id": ...}` plus error-specific fields.
 
 use axum::{
     body::Body,
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
+use serde_json::{json, Map, Value};
 use thiserror::Error;
 
+use crate::quota::QuotaPeriod;
+
 /// Authentication and authorization errors.
 #[derive(Debug, Error)]
 pub enum AuthError {
@@ -26,6 +33,34 @@ pub enum AuthError {
     #[error("Rate limit exceeded")]
     RateLimited,
 
+    /// Client IP is denied by the allow/deny lists.
+    #[error("IP address not allowed")]
+    IpBlocked,
+
+    /// Tenant is not in any Cognito group the route requires.
+    #[error("Not authorized for this route")]
+    Forbidden { required_groups: Vec<String> },
+
+    /// Tenant already has the maximum number of requests in flight.
+    #[error("Too many concurrent requests")]
+    TooManyConcurrentRequests,
+
+    /// Tenant already has the maximum number of WebSocket connections open.
+    #[error("Too many WebSocket connections")]
+    TooManyWebSocketConnections,
+
+    /// Subscribing would take the tenant over its subscribed asset cap.
+    #[error("WebSocket subscription limit of {limit} assets exceeded")]
+    SubscriptionLimitExceeded { limit: u32 },
+
+    /// Cumulative request quota for the current period is used up.
+    #[error("{} request quota exceeded", period.as_str())]
+    QuotaExceeded {
+        period: QuotaPeriod,
+        /// Seconds until the quota period resets.
+        retry_after_secs: u64,
+    },
+
     /// Failed to fetch JWKS from Cognito.
     #[error("Failed to fetch JWKS: {0}")]
     JwksFetchError(String),
@@ -42,15 +77,7 @@ impl IntoResponse for AuthError {
                 StatusCode::UNAUTHORIZED,
                 // Don't leak internal details in production
                 if cfg!(debug_assertions) {
-                    return Response::builder()
-                        .status(StatusCode::UNAUTHORIZED)
-                        .header("Content-Type", "application/json")
-                        .header("WWW-Authenticate", "Bearer realm=\"pmproxy\"")
-                        .body(Body::from(format!(
-                            r#"{{"error":"invalid_token","message":"{}"}}"#,
-                            msg
-                        )))
-                        .unwrap();
+                    msg.as_str()
                 } else {
                     "Invalid authentication token"
                 },
@@ -63,30 +90,291 @@ impl IntoResponse for AuthError {
                 StatusCode::TOO_MANY_REQUESTS,
                 "Rate limit exceeded. Please slow down.",
             ),
+            AuthError::IpBlocked => (
+                StatusCode::FORBIDDEN,
+                "Access denied for this IP address",
+            ),
+            AuthError::Forbidden { .. } => (
+                StatusCode::FORBIDDEN,
+                "This route requires membership in one of the listed groups",
+            ),
+            AuthError::TooManyConcurrentRequests => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many concurrent requests. Wait for in-flight requests to complete.",
+            ),
+            AuthError::TooManyWebSocketConnections => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many WebSocket connections. Close one before opening another.",
+            ),
+            AuthError::SubscriptionLimitExceeded { .. } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Subscribed asset limit reached. Unsubscribe from some assets first.",
+            ),
+            AuthError::QuotaExceeded { period, .. } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                match period {
+                    QuotaPeriod::Daily => "Daily request quota exceeded. Resets at 00:00 UTC.",
+                    QuotaPeriod::Monthly => {
+                        "Monthly request quota exceeded. Resets on the 1st at 00:00 UTC."
+                    }
+                },
+            ),
             AuthError::JwksFetchError(_) => (
                 StatusCode::SERVICE_UNAVAILABLE,
                 "Authentication service temporarily unavailable",
             ),
         };
+        let extra = match &self {
+            AuthError::Forbidden { required_groups } => json!({ "required_groups": required_groups }),
+            AuthError::SubscriptionLimitExceeded { limit } => json!({ "limit": limit }),
+            _ => Value::Null,
+        };
 
-        let body = format!(r#"{{"error":"{}","message":"{}"}}"#, error_code(&self), message);
-
-        Response::builder()
-            .status(status)
-            .header("Content-Type", "application/json")
-            .header(
-                "WWW-Authenticate",
-                match &self {
-                    AuthError::RateLimited => "Bearer realm=\"pmproxy\", error=\"rate_limited\"",
-                    AuthError::ExpiredToken => {
-                        "Bearer realm=\"pmproxy\", error=\"invalid_token\", error_description=\"Token expired\""
-                    }
-                    _ => "Bearer realm=\"pmproxy\"",
-                },
-            )
-            .body(Body::from(body))
-            .unwrap()
+        let mut response = error_response(status, error_code(&self), message, extra);
+        let headers = response.headers_mut();
+        if let AuthError::QuotaExceeded { retry_after_secs, .. } = &self {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(*retry_after_secs));
+        }
+        headers.insert(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static(match &self {
+                AuthError::RateLimited => "Bearer realm=\"pmproxy\", error=\"rate_limited\"",
+                AuthError::QuotaExceeded { .. } => "Bearer realm=\"pmproxy\", error=\"quota_exceeded\"",
+                AuthError::ExpiredToken => {
+                    "Bearer realm=\"pmproxy\", error=\"invalid_token\", error_description=\"Token expired\""
+                }
+                _ => "Bearer realm=\"pmproxy\"",
+            }),
+        );
+        response
+    }
+}
+
+/// Errors reading the client request itself.
+#[derive(Debug, Error)]
+pub enum RequestError {
+    /// Request body exceeds the route's size limit.
+    #[error("Request body exceeds {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+
+    /// Request body could not be read.
+    #[error("Failed to read request body: {0}")]
+    BadBody(String),
+
+    /// The route is switched off (maintenance mode or kill switch).
+    #[error("Route {path} is disabled")]
+    RouteDisabled { path: String, reason: Option<String> },
+
+    /// Order payload failed validation.
+    #[error("Invalid order: {0}")]
+    InvalidOrder(String),
+}
+
+impl IntoResponse for RequestError {
+    fn into_response(self) -> Response {
+        match &self {
+            RequestError::PayloadTooLarge { limit } => error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                &format!("Request body exceeds the {} byte limit", limit),
+                json!({ "limit_bytes": limit }),
+            ),
+            RequestError::BadBody(_) => error_response(
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+                "Failed to read request body",
+                Value::Null,
+            ),
+            RequestError::RouteDisabled { path, reason } => error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "route_disabled",
+                reason.as_deref().unwrap_or("Route temporarily disabled for maintenance"),
+                json!({ "path": path }),
+            ),
+            RequestError::InvalidOrder(message) => error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_order",
+                message,
+                Value::Null,
+            ),
+        }
+    }
+}
+
+/// Errors forwarding a request upstream.
+///
+/// Wraps [`AuthError`] and [`RequestError`] so every failure on the proxy path
+/// renders the same JSON envelope.
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    /// No route matches the request path.
+    #[error("No route for {path}")]
+    NotFound { path: String },
+
+    /// The upstream could not be reached or returned an unreadable response.
+    #[error("{0}")]
+    BadGateway(String),
+
+    /// The upstream did not answer within the route's timeout.
+    #[error("{0}")]
+    Timeout(String),
+
+    /// The proxy could not build the upstream request.
+    #[error("{0}")]
+    Internal(String),
+
+    #[error(transparent)]
+    Request(#[from] RequestError),
+
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+}
+
+impl ProxyError {
+    /// Classify a failed upstream call (timeouts are 504, everything else 502).
+    pub fn upstream(e: &reqwest::Error, message: impl Into<String>) -> Self {
+        if e.is_timeout() {
+            ProxyError::Timeout(message.into())
+        } else {
+            ProxyError::BadGateway(message.into())
+        }
+    }
+}
+
+impl IntoResponse for ProxyError {
+    fn into_response(self) -> Response {
+        match self {
+            ProxyError::NotFound { path } => error_response(
+                StatusCode::NOT_FOUND,
+                "not_found",
+                "No route matches this path",
+                json!({ "path": path }),
+            ),
+            ProxyError::BadGateway(message) => {
+                error_response(StatusCode::BAD_GATEWAY, "bad_gateway", &message, Value::Null)
+            }
+            ProxyError::Timeout(message) => {
+                error_response(StatusCode::GATEWAY_TIMEOUT, "gateway_timeout", &message, Value::Null)
+            }
+            ProxyError::Internal(message) => {
+                error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", &message, Value::Null)
+            }
+            ProxyError::Request(e) => e.into_response(),
+            ProxyError::Auth(e) => e.into_response(),
+        }
+    }
+}
+
+/// Header carrying the request ID (the client's, or one generated by the proxy).
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Fields of a JSON error body, kept on the response so the request ID can
+/// be added once known.
+#[derive(Debug, Clone)]
+struct ErrorEnvelope(Map<String, Value>);
+
+/// JSON error response: `{"error": code, "message": ..., ...extra}`.
+///
+/// Every error the proxy generates itself uses this envelope; [`with_request_id`]
+/// adds `request_id`.
+pub(crate) fn error_response(status: StatusCode, code: &str, message: &str, extra: Value) -> Response {
+    let mut fields = Map::new();
+    fields.insert("error".to_string(), code.into());
+    fields.insert("message".to_string(), message.into());
+    if let Value::Object(extra) = extra {
+        fields.extend(extra);
+    }
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(Value::Object(fields.clone()).to_string()))
+        .unwrap();
+    response.extensions_mut().insert(ErrorEnvelope(fields));
+    response
+}
+
+/// Tag a response with its request ID: the `X-Request-Id` header and, for
+/// proxy-generated errors, `request_id` in the JSON body.
+pub fn with_request_id(mut response: Response, request_id: &str) -> Response {
+    let Ok(value) = HeaderValue::from_str(request_id) else {
+        return response;
+    };
+    if let Some(ErrorEnvelope(mut fields)) = response.extensions_mut().remove::<ErrorEnvelope>() {
+        fields.insert("request_id".to_string(), request_id.into());
+        *response.body_mut() = Body::from(Value::Object(fields).to_string());
+        response.headers_mut().remove(header::CONTENT_LENGTH);
     }
+    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    response
+}
+
+/// Configuration loading errors.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// Failed to read a config file.
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Config contents are malformed or inconsistent.
+    #[error("Invalid config: {0}")]
+    Invalid(String),
+}
+
+/// Errors returned by [`crate::client::ProxyClient`].
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// A required environment variable is not set.
+    #[error("Missing environment variable: {0}")]
+    MissingConfig(&'static str),
+
+    /// Cognito login or token refresh failed.
+    #[error("Cognito login failed: {0}")]
+    Login(String),
+
+    /// Still rate limited after retrying, or told to wait longer than the backoff cap.
+    #[error("Rate limited by proxy (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<std::time::Duration> },
+
+    /// The proxy or upstream returned an error status.
+    #[error("Request failed with status {status}: {body}")]
+    Status { status: u16, body: String },
+
+    /// The response body was not the expected JSON.
+    #[error("Failed to decode response: {0}")]
+    Decode(String),
+
+    /// Connection or protocol error.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Audit export and verification errors.
+#[derive(Debug, Error)]
+pub enum AuditError {
+    /// Failed to read or write an audit file.
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Upload to the object store failed.
+    #[error("Audit export failed: {0}")]
+    Export(String),
+
+    /// A batch could not be parsed.
+    #[error("Malformed audit batch {path}: {message}")]
+    Malformed { path: String, message: String },
+
+    /// Hash chain, sequence, or signature check failed.
+    #[error("Audit verification failed: {0}")]
+    Tampered(String),
 }
 
 /// Get a machine-readable error code.
@@ -96,6 +384,12 @@ fn error_code(error: &AuthError) -> &'static str {
         AuthError::InvalidToken(_) => "invalid_token",
         AuthError::ExpiredToken => "expired_token",
         AuthError::RateLimited => "rate_limited",
+        AuthError::IpBlocked => "ip_blocked",
+        AuthError::Forbidden { .. } => "forbidden",
+        AuthError::TooManyConcurrentRequests => "concurrency_limited",
+        AuthError::TooManyWebSocketConnections => "websocket_limited",
+        AuthError::SubscriptionLimitExceeded { .. } => "subscription_limited",
+        AuthError::QuotaExceeded { .. } => "quota_exceeded",
         AuthError::JwksFetchError(_) => "service_unavailable",
     }
 }
@@ -122,9 +416,89 @@ mod tests {
             get_status(AuthError::RateLimited),
             StatusCode::TOO_MANY_REQUESTS
         );
+        assert_eq!(get_status(AuthError::IpBlocked), StatusCode::FORBIDDEN);
+        assert_eq!(
+            get_status(AuthError::Forbidden { required_groups: vec!["traders".to_string()] }),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            get_status(AuthError::TooManyConcurrentRequests),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            get_status(AuthError::TooManyWebSocketConnections),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            get_status(AuthError::SubscriptionLimitExceeded { limit: 50 }),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            get_status(AuthError::QuotaExceeded {
+                period: QuotaPeriod::Daily,
+                retry_after_secs: 60,
+            }),
+            StatusCode::TOO_MANY_REQUESTS
+        );
         assert_eq!(
             get_status(AuthError::JwksFetchError("test".to_string())),
             StatusCode::SERVICE_UNAVAILABLE
         );
     }
+
+    #[tokio::test]
+    async fn test_errors_share_one_envelope() {
+        let body = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<Value>(&bytes).unwrap()
+        };
+
+        let timeout = ProxyError::Timeout("Upstream error: timed out".into());
+        let response = with_request_id(timeout.into_response(), "req-1");
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-1");
+        assert_eq!(
+            body(response).await,
+            json!({"error": "gateway_timeout", "message": "Upstream error: timed out", "request_id": "req-1"})
+        );
+
+        // Auth errors keep their extra fields and headers
+        let forbidden = AuthError::Forbidden { required_groups: vec!["traders".to_string()] };
+        let response = with_request_id(ProxyError::from(forbidden).into_response(), "req-2");
+        assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+        let forbidden = body(response).await;
+        assert_eq!(forbidden["error"], "forbidden");
+        assert_eq!(forbidden["required_groups"], json!(["traders"]));
+        assert_eq!(forbidden["request_id"], "req-2");
+
+        let too_large = RequestError::PayloadTooLarge { limit: 16 }.into_response();
+        let too_large = body(with_request_id(too_large, "req-3")).await;
+        assert_eq!(too_large["limit_bytes"], 16);
+        assert_eq!(too_large["request_id"], "req-3");
+
+        // Responses that are not proxy errors are only tagged with the header
+        let upstream = Response::new(Body::from("upstream body"));
+        let response = with_request_id(upstream, "req-4");
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-4");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"upstream body");
+    }
+
+    #[test]
+    fn test_quota_exceeded_response() {
+        let response = AuthError::QuotaExceeded {
+            period: QuotaPeriod::Monthly,
+            retry_after_secs: 3600,
+        }
+        .into_response();
+
+        assert_eq!(response.headers()["Retry-After"], "3600");
+        assert_eq!(
+            error_code(&AuthError::QuotaExceeded {
+                period: QuotaPeriod::Daily,
+                retry_after_secs: 1,
+            }),
+            "quota_exceeded"
+        );
+    }
 }
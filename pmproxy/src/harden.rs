@@ -0,0 +1,131 @@
+//! Response hardening for routes whose upstreams were not built to face clients.
+//!
+//! A route with `"harden"` (see [`crate::routes`]) has its upstream responses
+//! cleaned up before they reach the client:
+//!
+//! ```json
+//! {"prefix": "private", "upstream": "https://api.example.com",
+//!  "harden": {"strip_cookies": true, "security_headers": true, "hide_banners": true}}
+//! ```
+//!
+//! - `strip_cookies`: drop `Set-Cookie`, so upstream sessions never reach clients.
+//! - `security_headers`: add `X-Content-Type-Options: nosniff`, and
+//!   `Strict-Transport-Security` when the client connected over TLS.
+//! - `hide_banners`: drop `Server` and `X-Powered-By`.
+//!
+//! Every part is on unless switched off, so `"harden": {}` enables all of
+//! them. Hardening runs after the route's response header rules.
+
+use axum::http::{header, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+/// Default `Strict-Transport-Security` max-age (one year).
+const DEFAULT_HSTS_MAX_AGE_SECS: u64 = 31_536_000;
+
+/// Headers identifying the upstream's software.
+const BANNERS: &[&str] = &["server", "x-powered-by"];
+
+/// Response hardening of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseHardening {
+    #[serde(default = "enabled")]
+    pub strip_cookies: bool,
+    #[serde(default = "enabled")]
+    pub security_headers: bool,
+    #[serde(default = "enabled")]
+    pub hide_banners: bool,
+    /// `Strict-Transport-Security` max-age (0 = don't send it).
+    #[serde(default = "default_hsts_max_age_secs")]
+    pub hsts_max_age_secs: u64,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_hsts_max_age_secs() -> u64 {
+    DEFAULT_HSTS_MAX_AGE_SECS
+}
+
+impl Default for ResponseHardening {
+    fn default() -> Self {
+        Self {
+            strip_cookies: true,
+            security_headers: true,
+            hide_banners: true,
+            hsts_max_age_secs: DEFAULT_HSTS_MAX_AGE_SECS,
+        }
+    }
+}
+
+impl ResponseHardening {
+    /// Harden response headers; `tls` is whether the client used HTTPS.
+    pub fn apply(&self, headers: &mut HeaderMap, tls: bool) {
+        if self.strip_cookies {
+            headers.remove(header::SET_COOKIE);
+        }
+        if self.hide_banners {
+            for name in BANNERS {
+                headers.remove(*name);
+            }
+        }
+        if self.security_headers {
+            headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+            if tls && self.hsts_max_age_secs > 0 {
+                let hsts = format!("max-age={}", self.hsts_max_age_secs);
+                // Built from a number, so always valid
+                if let Ok(v) = HeaderValue::from_str(&hsts) {
+                    headers.insert(header::STRICT_TRANSPORT_SECURITY, v);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.append(header::SET_COOKIE, "session=abc".parse().unwrap());
+        headers.append(header::SET_COOKIE, "tracking=1".parse().unwrap());
+        headers.insert(header::SERVER, "nginx/1.18.0".parse().unwrap());
+        headers.insert("x-powered-by", "Express".parse().unwrap());
+        headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_hardening_strips_and_adds_headers() {
+        let hardening: ResponseHardening = serde_json::from_str("{}").unwrap();
+        assert_eq!(hardening, ResponseHardening::default());
+
+        let mut headers = upstream_headers();
+        hardening.apply(&mut headers, true);
+        assert!(headers.get(header::SET_COOKIE).is_none());
+        assert!(headers.get(header::SERVER).is_none());
+        assert!(headers.get("x-powered-by").is_none());
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::STRICT_TRANSPORT_SECURITY], "max-age=31536000");
+        assert_eq!(headers[header::CONTENT_TYPE], "application/json");
+
+        // No HSTS over plain HTTP
+        let mut headers = upstream_headers();
+        hardening.apply(&mut headers, false);
+        assert!(headers.get(header::STRICT_TRANSPORT_SECURITY).is_none());
+    }
+
+    #[test]
+    fn test_hardening_parts_can_be_switched_off() {
+        let hardening: ResponseHardening =
+            serde_json::from_str(r#"{"strip_cookies": false, "hide_banners": false, "hsts_max_age_secs": 0}"#)
+                .unwrap();
+        let mut headers = upstream_headers();
+        hardening.apply(&mut headers, true);
+        assert_eq!(headers.get_all(header::SET_COOKIE).iter().count(), 2);
+        assert_eq!(headers[header::SERVER], "nginx/1.18.0");
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert!(headers.get(header::STRICT_TRANSPORT_SECURITY).is_none());
+    }
+}
@@ -0,0 +1,223 @@
+//! Readiness probing for upstream APIs and the JWKS cache.
+//!
+//! `/health` only says the process is up. `/health/ready` reports whether the
+//! proxy can actually serve traffic: each upstream is probed in the background
+//! and, with auth enabled, the JWKS cache must hold fresh signing keys.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::auth::{JwksCache, JwksStatus};
+
+/// Upstreams probed for readiness (name, probe URL).
+pub const UPSTREAMS: &[(&str, &str)] = &[
+    ("clob", "https://clob.polymarket.com/"),
+    ("gamma", "https://gamma-api.polymarket.com/"),
+    ("data", "https://data-api.polymarket.com/"),
+    ("chain", "https://polygon-rpc.com/"),
+];
+
+/// Timeout for a single probe request.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of the most recent probe of one upstream.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamStatus {
+    pub name: &'static str,
+    pub url: &'static str,
+    /// Whether the upstream answered with a non-5xx response.
+    pub reachable: bool,
+    /// HTTP status of the probe response (None if the request failed).
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// Transport error or 5xx reason.
+    pub error: Option<String>,
+    /// Unix timestamp (seconds) of the probe.
+    pub checked_at: u64,
+}
+
+/// Readiness report served by `/health/ready`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    /// "ready" or "degraded".
+    pub status: &'static str,
+    pub upstreams: Vec<UpstreamStatus>,
+    /// JWKS cache state (None if auth is disabled).
+    pub jwks: Option<JwksStatus>,
+}
+
+impl ReadinessReport {
+    /// Whether every upstream is reachable and the JWKS cache (if any) is fresh.
+    pub fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+}
+
+/// Background prober that keeps the latest upstream status.
+pub struct HealthProber {
+    client: reqwest::Client,
+    jwks_cache: Option<Arc<JwksCache>>,
+    /// Latest results, with the instant they were taken.
+    latest: RwLock<Option<(Instant, Vec<UpstreamStatus>)>>,
+    /// Results older than this are re-probed on demand.
+    max_age: Duration,
+}
+
+impl HealthProber {
+    /// Create a prober; `interval` is how often the background task probes.
+    pub fn new(jwks_cache: Option<Arc<JwksCache>>, interval: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(PROBE_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            jwks_cache,
+            latest: RwLock::new(None),
+            max_age: interval * 2,
+        }
+    }
+
+    /// Probe a single upstream.
+    async fn probe(client: reqwest::Client, name: &'static str, url: &'static str) -> UpstreamStatus {
+        let started = Instant::now();
+        let result = client.get(url).send().await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let (reachable, status, error) = match result {
+            Ok(resp) if resp.status().is_server_error() => {
+                (false, Some(resp.status().as_u16()), Some(resp.status().to_string()))
+            }
+            // Any other response means the upstream is up (e.g. the RPC
+            // endpoint answers GET with 405)
+            Ok(resp) => (true, Some(resp.status().as_u16()), None),
+            Err(e) => (false, None, Some(e.to_string())),
+        };
+
+        if !reachable {
+            warn!(upstream = name, error = ?error, "Upstream probe failed");
+        }
+
+        UpstreamStatus {
+            name,
+            url,
+            reachable,
+            status,
+            latency_ms,
+            error,
+            checked_at: unix_now(),
+        }
+    }
+
+    /// Probe every upstream and refresh the JWKS cache if it has gone stale.
+    pub async fn probe_all(&self) -> Vec<UpstreamStatus> {
+        let mut probes = tokio::task::JoinSet::new();
+        for &(name, url) in UPSTREAMS {
+            probes.spawn(Self::probe(self.client.clone(), name, url));
+        }
+        let mut results: Vec<UpstreamStatus> = probes.join_all().await;
+        results.sort_by_key(|r| UPSTREAMS.iter().position(|(n, _)| *n == r.name));
+
+        debug!(
+            reachable = results.iter().filter(|r| r.reachable).count(),
+            total = results.len(),
+            "Upstream probe complete"
+        );
+
+        if let Some(ref cache) = self.jwks_cache {
+            if let Err(e) = cache.refresh_if_stale().await {
+                warn!(error = %e, "JWKS refresh failed during health probe");
+            }
+        }
+
+        self.record(results.clone());
+        results
+    }
+
+    pub(crate) fn record(&self, results: Vec<UpstreamStatus>) {
+        *self.latest.write().unwrap() = Some((Instant::now(), results));
+    }
+
+    /// Build the readiness report, probing inline if the last results are
+    /// missing or stale (e.g. the background task is not running).
+    pub async fn report(&self) -> ReadinessReport {
+        let cached = self
+            .latest
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|(at, _)| at.elapsed() <= self.max_age)
+            .map(|(_, results)| results.clone());
+        let upstreams = match cached {
+            Some(results) => results,
+            None => self.probe_all().await,
+        };
+
+        let jwks = match self.jwks_cache {
+            Some(ref cache) => Some(cache.status().await),
+            None => None,
+        };
+
+        let ready = upstreams.iter().all(|u| u.reachable)
+            && jwks.as_ref().is_none_or(|j| j.fresh);
+
+        ReadinessReport {
+            status: if ready { "ready" } else { "degraded" },
+            upstreams,
+            jwks,
+        }
+    }
+
+    /// Start the background probe loop.
+    pub fn spawn(self: &Arc<Self>, interval: Duration) {
+        let prober = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                prober.probe_all().await;
+            }
+        });
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn status(name: &'static str, reachable: bool) -> UpstreamStatus {
+        UpstreamStatus {
+            name,
+            url: "https://example.invalid/",
+            reachable,
+            status: reachable.then_some(200),
+            latency_ms: 1,
+            error: None,
+            checked_at: unix_now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_uses_recent_results() {
+        let prober = HealthProber::new(None, Duration::from_secs(30));
+
+        prober.record(vec![status("clob", true), status("gamma", true)]);
+        let report = prober.report().await;
+        assert!(report.is_ready());
+        assert!(report.jwks.is_none());
+
+        prober.record(vec![status("clob", true), status("gamma", false)]);
+        let report = prober.report().await;
+        assert_eq!(report.status, "degraded");
+        assert_eq!(report.upstreams.len(), 2);
+    }
+}
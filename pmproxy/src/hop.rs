@@ -0,0 +1,129 @@
+//! Hop-by-hop header handling (RFC 7230 §6.1).
+//!
+//! Hop-by-hop headers describe a single connection and must not be forwarded
+//! by a proxy: the fixed set below plus every header named in `Connection`.
+//! Two edge cases:
+//! - `TE`: only `trailers` is meaningful end to end (gRPC and HTTP/2 rely on
+//!   it), so `TE: trailers` survives on requests and everything else is dropped.
+//! - `Upgrade`: the proxy does not tunnel protocol switches, so `Upgrade` is
+//!   always stripped and the request is forwarded as a plain HTTP request.
+
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+
+/// Headers that are hop-by-hop regardless of `Connection`.
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "proxy-connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Which way the headers are travelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hop {
+    /// Client → upstream.
+    Request,
+    /// Upstream → client.
+    Response,
+}
+
+/// Header names nominated by `Connection` (and legacy `Proxy-Connection`).
+fn nominated(headers: &HeaderMap) -> Vec<HeaderName> {
+    headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .chain(headers.get_all("proxy-connection").iter())
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|token| HeaderName::from_bytes(token.trim().as_bytes()).ok())
+        .collect()
+}
+
+/// Whether a `TE` header asks for trailers.
+fn accepts_trailers(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::TE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|token| {
+            let coding = token.split(';').next().unwrap_or("").trim();
+            coding.eq_ignore_ascii_case("trailers")
+        })
+}
+
+/// Remove hop-by-hop headers in place.
+///
+/// Must run before the proxy adds its own headers (`X-Forwarded-*`, route
+/// rules) so a client cannot nominate those away through `Connection`.
+pub fn strip(headers: &mut HeaderMap, direction: Hop) {
+    let keep_trailers = direction == Hop::Request && accepts_trailers(headers);
+
+    for name in nominated(headers) {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP {
+        headers.remove(*name);
+    }
+
+    if keep_trailers {
+        headers.insert(header::TE, HeaderValue::from_static("trailers"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_strips_connection_nominated_headers() {
+        let mut map = headers(&[
+            ("connection", "keep-alive, X-Internal-Trace"),
+            ("connection", "x-debug"),
+            ("keep-alive", "timeout=5"),
+            ("x-internal-trace", "abc"),
+            ("x-debug", "1"),
+            ("transfer-encoding", "chunked"),
+            ("content-type", "application/json"),
+        ]);
+        strip(&mut map, Hop::Response);
+
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key("content-type"));
+    }
+
+    #[test]
+    fn test_te_and_upgrade() {
+        let mut map = headers(&[
+            ("te", "gzip;q=0.5, trailers"),
+            ("connection", "Upgrade, TE"),
+            ("upgrade", "websocket"),
+        ]);
+        strip(&mut map, Hop::Request);
+        assert_eq!(map.get("te").unwrap(), "trailers");
+        assert!(!map.contains_key("upgrade"));
+        assert!(!map.contains_key("connection"));
+
+        // Without trailers, or on responses, TE is dropped entirely
+        let mut map = headers(&[("te", "gzip")]);
+        strip(&mut map, Hop::Request);
+        assert!(map.is_empty());
+
+        let mut map = headers(&[("te", "trailers")]);
+        strip(&mut map, Hop::Response);
+        assert!(map.is_empty());
+    }
+}
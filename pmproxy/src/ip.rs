@@ -0,0 +1,409 @@
+//! Client IP resolution, allow/deny lists, and per-IP rate limiting.
+//!
+//! With auth disabled the proxy has no tenant identity, so the client IP is
+//! the only key available for throttling. Behind a load balancer (e.g. ALB)
+//! the socket peer is the balancer itself; set `PMPROXY_TRUSTED_PROXY_HOPS`
+//! to take the client address from `X-Forwarded-For` instead.
+
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU32;
+use std::str::FromStr;
+
+use axum::http::{header, HeaderMap, HeaderValue};
+use governor::{clock::DefaultClock, state::keyed::DefaultKeyedStateStore, Quota, RateLimiter};
+use tracing::debug;
+
+use crate::error::{AuthError, ConfigError};
+
+/// An IP address or CIDR block (e.g. `10.0.0.0/8`, `2001:db8::/32`, `1.2.3.4`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRule {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRule {
+    /// Whether `ip` falls inside this block.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRule {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || ConfigError::Invalid(format!("invalid IP or CIDR '{}'", s));
+
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network = IpAddr::from_str(addr).map_err(|_| invalid())?;
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p.parse::<u8>().map_err(|_| invalid())?,
+            None => max_prefix,
+        };
+        if prefix > max_prefix {
+            return Err(invalid());
+        }
+
+        Ok(Self { network, prefix })
+    }
+}
+
+/// Parse a comma-separated list of IPs/CIDR blocks.
+pub fn parse_ip_rules(list: &str) -> Result<Vec<IpRule>, ConfigError> {
+    list.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(IpRule::from_str)
+        .collect()
+}
+
+/// Allow/deny lists. The deny list wins; an empty allow list allows everyone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpAccessList {
+    pub allow: Vec<IpRule>,
+    pub deny: Vec<IpRule>,
+}
+
+impl IpAccessList {
+    /// Whether any rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Check whether `ip` may use the proxy.
+    pub fn check(&self, ip: IpAddr) -> Result<(), AuthError> {
+        if self.deny.iter().any(|r| r.contains(ip)) {
+            debug!(ip = %ip, "IP is on the deny list");
+            return Err(AuthError::IpBlocked);
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|r| r.contains(ip)) {
+            debug!(ip = %ip, "IP is not on the allow list");
+            return Err(AuthError::IpBlocked);
+        }
+        Ok(())
+    }
+}
+
+/// Resolve the client IP for a request.
+///
+/// With `trusted_hops == 0` the socket peer is used. Otherwise the client is
+/// the `trusted_hops`-th address from the right of `X-Forwarded-For` (each
+/// trusted proxy appends the address it received the request from). Falls
+/// back to the peer if the header is missing or too short.
+pub fn client_ip(headers: &HeaderMap, peer: Option<SocketAddr>, trusted_hops: usize) -> Option<IpAddr> {
+    if trusted_hops > 0 {
+        let forwarded = parse_xff(headers);
+        if forwarded.len() >= trusted_hops {
+            return Some(forwarded[forwarded.len() - trusted_hops]);
+        }
+    }
+
+    peer.map(peer_ip)
+}
+
+/// All valid `X-Forwarded-For` entries, left to right, with IPv4-mapped IPv6
+/// addresses in their IPv4 form. Unparseable entries are skipped.
+fn parse_xff(headers: &HeaderMap) -> Vec<IpAddr> {
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|s| IpAddr::from_str(s.trim()).ok())
+        .map(|ip| ip.to_canonical())
+        .collect()
+}
+
+/// The socket peer's address (IPv4-mapped IPv6 in its IPv4 form, as a
+/// dual-stack listener reports IPv4 clients).
+fn peer_ip(peer: SocketAddr) -> IpAddr {
+    peer.ip().to_canonical()
+}
+
+/// Client address information forwarded to the upstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardedFor {
+    /// Originating client first, then each proxy on the way (ending with the
+    /// peer that connected to us).
+    pub chain: Vec<IpAddr>,
+    /// Scheme the client used (`http` or `https`).
+    pub proto: String,
+}
+
+impl ForwardedFor {
+    /// Build the forwarding chain for a request.
+    ///
+    /// Only the `trusted_hops` rightmost `X-Forwarded-For` entries (and a
+    /// trusted `X-Forwarded-Proto`) are kept; anything further left was
+    /// supplied by the client and could be spoofed. `default_proto` is the
+    /// scheme of our own listener.
+    pub fn from_request(
+        headers: &HeaderMap,
+        peer: Option<SocketAddr>,
+        trusted_hops: usize,
+        default_proto: &str,
+    ) -> Self {
+        let mut chain = Vec::new();
+        let mut proto = None;
+
+        if trusted_hops > 0 {
+            let forwarded = parse_xff(headers);
+            if forwarded.len() >= trusted_hops {
+                chain.extend_from_slice(&forwarded[forwarded.len() - trusted_hops..]);
+                proto = headers
+                    .get("x-forwarded-proto")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.split(',').next_back())
+                    .map(|v| v.trim().to_ascii_lowercase())
+                    .filter(|v| v == "http" || v == "https");
+            }
+        }
+        if let Some(peer) = peer {
+            chain.push(peer_ip(peer));
+        }
+
+        Self {
+            chain,
+            proto: proto.unwrap_or_else(|| default_proto.to_string()),
+        }
+    }
+
+    /// Replace any client-supplied forwarding headers with our own
+    /// `X-Forwarded-For`, `X-Forwarded-Proto` and `Forwarded` (RFC 7239).
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        headers.remove("x-forwarded-for");
+        headers.remove("x-forwarded-proto");
+        headers.remove(header::FORWARDED);
+        if self.chain.is_empty() {
+            return;
+        }
+
+        let xff = self
+            .chain
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let forwarded = self
+            .chain
+            .iter()
+            .enumerate()
+            .map(|(i, ip)| {
+                let node = match ip {
+                    IpAddr::V4(v4) => v4.to_string(),
+                    IpAddr::V6(v6) => format!("\"[{}]\"", v6),
+                };
+                if i == 0 {
+                    format!("for={};proto={}", node, self.proto)
+                } else {
+                    format!("for={}", node)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Built from IP addresses and a validated scheme, so always valid
+        if let Ok(v) = HeaderValue::from_str(&xff) {
+            headers.insert("x-forwarded-for", v);
+        }
+        if let Ok(v) = HeaderValue::from_str(&self.proto) {
+            headers.insert("x-forwarded-proto", v);
+        }
+        if let Ok(v) = HeaderValue::from_str(&forwarded) {
+            headers.insert(header::FORWARDED, v);
+        }
+    }
+}
+
+/// Per-IP token bucket limiter for unauthenticated mode.
+pub struct IpRateLimiter {
+    limiter: RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock>,
+}
+
+impl IpRateLimiter {
+    /// Create a limiter allowing `rpm` requests per minute with `burst` capacity per IP.
+    pub fn new(rpm: u32, burst: u32) -> Self {
+        let quota = Quota::per_minute(NonZeroU32::new(rpm).unwrap_or(NonZeroU32::MIN))
+            .allow_burst(NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN));
+
+        Self {
+            limiter: RateLimiter::keyed(quota),
+        }
+    }
+
+    /// Check if a request from `ip` should be allowed.
+    pub fn check(&self, ip: IpAddr) -> Result<(), AuthError> {
+        self.limiter.check_key(&ip).map_err(|_| {
+            debug!(ip = %ip, "IP rate limit exceeded");
+            AuthError::RateLimited
+        })
+    }
+
+    /// Drop state for IPs whose buckets have fully refilled.
+    pub fn cleanup_stale(&self) {
+        self.limiter.retain_recent();
+        self.limiter.shrink_to_fit();
+    }
+
+    /// Number of tracked IPs (for monitoring).
+    pub fn ip_count(&self) -> usize {
+        self.limiter.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ip_rule_parsing_and_matching() {
+        let rule: IpRule = "10.0.0.0/8".parse().unwrap();
+        assert!(rule.contains(ip("10.1.2.3")));
+        assert!(!rule.contains(ip("11.0.0.1")));
+
+        let single: IpRule = "1.2.3.4".parse().unwrap();
+        assert!(single.contains(ip("1.2.3.4")));
+        assert!(!single.contains(ip("1.2.3.5")));
+
+        // IPv4-mapped IPv6 addresses match IPv4 rules
+        assert!(rule.contains(ip("::ffff:10.0.0.1")));
+
+        let v6: IpRule = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains(ip("2001:db8::1")));
+        assert!(!v6.contains(ip("10.0.0.1")));
+
+        let any: IpRule = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(ip("8.8.8.8")));
+
+        assert!("10.0.0.0/33".parse::<IpRule>().is_err());
+        assert!("not-an-ip".parse::<IpRule>().is_err());
+        assert_eq!(parse_ip_rules(" 1.2.3.4, 10.0.0.0/8 ,").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_access_list() {
+        let open = IpAccessList::default();
+        assert!(open.check(ip("1.2.3.4")).is_ok());
+
+        let list = IpAccessList {
+            allow: parse_ip_rules("10.0.0.0/8").unwrap(),
+            deny: parse_ip_rules("10.0.0.66").unwrap(),
+        };
+        assert!(list.check(ip("10.0.0.1")).is_ok());
+        assert!(matches!(list.check(ip("10.0.0.66")), Err(AuthError::IpBlocked)));
+        assert!(matches!(list.check(ip("192.168.0.1")), Err(AuthError::IpBlocked)));
+    }
+
+    #[test]
+    fn test_client_ip_honors_trusted_hops() {
+        let peer: SocketAddr = "172.16.0.5:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 203.0.113.7"),
+        );
+
+        // Untrusted header is ignored
+        assert_eq!(client_ip(&headers, Some(peer), 0), Some(ip("172.16.0.5")));
+        // One proxy (ALB): the last entry is the address ALB saw
+        assert_eq!(client_ip(&headers, Some(peer), 1), Some(ip("203.0.113.7")));
+        assert_eq!(client_ip(&headers, Some(peer), 2), Some(ip("6.6.6.6")));
+        // Header shorter than the hop count falls back to the peer
+        assert_eq!(client_ip(&headers, Some(peer), 3), Some(ip("172.16.0.5")));
+        assert_eq!(client_ip(&HeaderMap::new(), None, 1), None);
+    }
+
+    #[test]
+    fn test_ipv4_mapped_addresses_are_canonical() {
+        let peer: SocketAddr = "[::ffff:172.16.0.5]:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static(" ::ffff:203.0.113.7 ,bogus"),
+        );
+        headers.append("x-forwarded-for", HeaderValue::from_static("10.0.0.1"));
+        assert_eq!(parse_xff(&headers), vec![ip("203.0.113.7"), ip("10.0.0.1")]);
+
+        // Both paths agree on the peer and on forwarded entries
+        assert_eq!(client_ip(&headers, Some(peer), 0), Some(ip("172.16.0.5")));
+        assert_eq!(client_ip(&headers, Some(peer), 2), Some(ip("203.0.113.7")));
+        let chain = ForwardedFor::from_request(&headers, Some(peer), 2, "http").chain;
+        assert_eq!(chain, vec![ip("203.0.113.7"), ip("10.0.0.1"), ip("172.16.0.5")]);
+    }
+
+    #[test]
+    fn test_forwarded_for_drops_untrusted_entries() {
+        let peer: SocketAddr = "172.16.0.5:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 203.0.113.7"),
+        );
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("https"));
+        headers.insert(header::FORWARDED, HeaderValue::from_static("for=6.6.6.6"));
+
+        // Untrusted: only the peer, our own scheme
+        let untrusted = ForwardedFor::from_request(&headers, Some(peer), 0, "http");
+        assert_eq!(untrusted.chain, vec![ip("172.16.0.5")]);
+        assert_eq!(untrusted.proto, "http");
+
+        // Behind one proxy (ALB): spoofed entry dropped, ALB's scheme kept
+        let alb = ForwardedFor::from_request(&headers, Some(peer), 1, "http");
+        assert_eq!(alb.chain, vec![ip("203.0.113.7"), ip("172.16.0.5")]);
+        assert_eq!(alb.proto, "https");
+
+        alb.apply(&mut headers);
+        assert_eq!(headers["x-forwarded-for"], "203.0.113.7, 172.16.0.5");
+        assert_eq!(headers["x-forwarded-proto"], "https");
+        assert_eq!(
+            headers[header::FORWARDED],
+            "for=203.0.113.7;proto=https, for=172.16.0.5"
+        );
+    }
+
+    #[test]
+    fn test_forwarded_quotes_ipv6() {
+        let peer: SocketAddr = "[2001:db8::1]:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        ForwardedFor::from_request(&headers.clone(), Some(peer), 0, "http").apply(&mut headers);
+        assert_eq!(headers[header::FORWARDED], "for=\"[2001:db8::1]\";proto=http");
+
+        // Nothing known about the client: forwarding headers are stripped
+        headers.insert("x-forwarded-for", HeaderValue::from_static("6.6.6.6"));
+        ForwardedFor::from_request(&headers.clone(), None, 0, "http").apply(&mut headers);
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn test_ip_rate_limiter() {
+        let limiter = IpRateLimiter::new(60, 2);
+
+        assert!(limiter.check(ip("1.1.1.1")).is_ok());
+        assert!(limiter.check(ip("1.1.1.1")).is_ok());
+        assert!(limiter.check(ip("1.1.1.1")).is_err());
+
+        // Separate bucket per IP
+        assert!(limiter.check(ip("2.2.2.2")).is_ok());
+        assert_eq!(limiter.ip_count(), 2);
+    }
+}
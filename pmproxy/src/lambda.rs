@@ -1,5 +1,9 @@
-use lambda_http::{run, tracing, Error};
-use pmproxy::{build_router, config::ProxyConfig, ProxyState};
+use lambda_http::{
+    lambda_runtime::{self, LambdaEvent},
+    request::LambdaRequest,
+    service_fn, tower::Service, tracing, Adapter, Error,
+};
+use pmproxy::{build_router, config::ProxyConfig, warm, ProxyState};
 use std::sync::Arc;
 
 #[tokio::main]
@@ -12,20 +16,36 @@ async fn main() -> Result<(), Error> {
     // Create state with or without auth
     let state = Arc::new(ProxyState::with_auth(&config).map_err(|e| Error::from(e.to_string()))?);
 
-    // Pre-fetch JWKS if auth is enabled
+    // Fetch JWKS and connect to the upstreams during init, which provisioned
+    // concurrency runs before any traffic arrives
     if config.auth_enabled {
         tracing::info!(
             cognito_region = %config.cognito_region,
             cognito_pool_id = %config.cognito_pool_id,
             "Authentication enabled, fetching JWKS..."
         );
-
-        if let Err(e) = state.prefetch_jwks().await {
-            tracing::warn!(error = %e, "Failed to pre-fetch JWKS (will retry on first request)");
-        }
+    }
+    if state.warm().await.jwks_fresh == Some(false) {
+        tracing::warn!("Failed to pre-fetch JWKS (will retry on first request)");
     }
 
-    let app = build_router(state);
+    state.spawn_background_tasks(&config);
 
-    run(app).await
+    let app = build_router(state.clone());
+
+    // HTTP events go to the router; warmer events only refresh the warm state
+    lambda_runtime::run(service_fn(move |event: LambdaEvent<serde_json::Value>| {
+        let state = state.clone();
+        let app = app.clone();
+        async move {
+            if warm::is_warmer_event(&event.payload) {
+                return Ok::<_, Error>(serde_json::to_value(state.warm().await)?);
+            }
+            let payload: LambdaRequest = serde_json::from_value(event.payload)?;
+            let request = LambdaEvent::new(payload, event.context);
+            let response = Adapter::from(app).call(request).await?;
+            Ok(serde_json::to_value(response)?)
+        }
+    }))
+    .await
 }
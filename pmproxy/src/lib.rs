@@ -7,30 +7,84 @@
 //! Authorization: Bearer <token>
 //! ```
 //!
-//! The proxy validates the JWT, extracts the tenant ID, applies rate limiting based on
-//! the tenant's tier, and then forwards the request to the upstream Polymarket API.
+//! The proxy validates the JWT, extracts the tenant ID, applies rate, concurrency, and
+//! daily/monthly quota limits based on the tenant's tier, and then forwards the request to
+//! the upstream Polymarket API.
 
+pub mod admin;
+pub mod audit;
 pub mod auth;
+pub mod body;
+pub mod cache;
+pub mod clock;
+pub mod client;
+pub mod concurrency;
+pub mod credits;
 pub mod config;
 pub mod error;
+pub mod harden;
+pub mod health;
+pub mod hop;
+pub mod ip;
+pub mod maintenance;
+pub mod mirror;
+pub mod order_check;
+pub mod quota;
 pub mod ratelimit;
+pub mod request_id;
+pub mod routes;
+pub mod s3;
+pub mod sigv4;
+pub mod stats;
+pub mod stream;
+pub mod tiers;
+#[cfg(feature = "ec2")]
+pub mod tls;
+pub mod upstream_auth;
+pub mod warm;
+pub mod websocket;
+pub mod whoami;
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum::{
     body::Body,
-    extract::{Request, State},
-    http::{header, StatusCode},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
     Router,
 };
-use tracing::{debug, error, info};
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::{debug, error, info, warn};
 
+use audit::{AuditRecord, AuditTrail};
 use auth::{extract_bearer_token, AuthenticatedTenant, JwksCache};
-use config::ProxyConfig;
-use error::AuthError;
+use body::BodyLimits;
+use cache::ResponseCache;
+use concurrency::TenantConcurrencyLimiter;
+use credits::CreditLedger;
+use config::{ProxyConfig, UpstreamTuning};
+use error::{AuthError, ProxyError};
+use clock::ClockProbe;
+use health::HealthProber;
+use stats::UpstreamStats;
+use hop::Hop;
+use ip::{ForwardedFor, IpAccessList, IpRateLimiter};
+use maintenance::Maintenance;
+use mirror::MirrorTracker;
+use quota::QuotaTracker;
 use ratelimit::TenantRateLimiter;
+use routes::{Route, RouteTable, RouteTimeouts};
+use tiers::TierRegistry;
+use websocket::WebSocketLimiter;
+
+/// Upstream request timeout for routes without their own.
+const UPSTREAM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Default interval between upstream health probes.
+const DEFAULT_HEALTH_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
 
 /// Shared proxy state.
 #[derive(Clone)]
@@ -41,47 +95,313 @@ pub struct ProxyState {
     pub jwks_cache: Option<Arc<JwksCache>>,
     /// Per-tenant rate limiter (None if auth disabled).
     pub rate_limiter: Option<Arc<TenantRateLimiter>>,
+    /// Per-tenant in-flight request limiter (None if auth disabled).
+    pub concurrency: Option<Arc<TenantConcurrencyLimiter>>,
+    /// Per-tenant WebSocket connection and subscription limiter (None if auth disabled).
+    pub websockets: Option<Arc<WebSocketLimiter>>,
+    /// Per-tenant daily/monthly quota tracker (None if auth disabled).
+    pub quota: Option<Arc<QuotaTracker>>,
+    /// Per-tenant burst credits spent by the rate limiter (None if auth disabled).
+    pub credits: Option<Arc<CreditLedger>>,
     /// Whether authentication is enabled.
     pub auth_enabled: bool,
+    /// Tenant tier definitions (hot-reloadable).
+    pub tiers: TierRegistry,
+    /// Client IP allow/deny lists.
+    pub ip_access: IpAccessList,
+    /// Per-IP rate limiter (only when auth is disabled and an IP limit is configured).
+    pub ip_limiter: Option<Arc<IpRateLimiter>>,
+    /// Number of trusted proxies appending to X-Forwarded-For.
+    pub trusted_proxy_hops: usize,
+    /// Whether the listener terminates TLS itself.
+    pub tls_listener: bool,
+    /// Upstream and JWKS readiness prober.
+    pub health: Arc<HealthProber>,
+    /// CLOB clock offset prober for `/time`.
+    pub clock: Arc<ClockProbe>,
+    /// Per-upstream latency and error ratios for `/stats`.
+    pub stats: Arc<UpstreamStats>,
+    /// Maximum request body size per route.
+    pub body_limits: BodyLimits,
+    /// Reject malformed `POST /clob/order` payloads.
+    pub validate_orders: bool,
+    /// How long a streamed response may go without a chunk before it is closed.
+    pub stream_idle_timeout: std::time::Duration,
+    /// Upstream routes and their header rules.
+    pub routes: Arc<RouteTable>,
+    /// Connection pool and keepalive settings of upstream clients.
+    pub upstream_tuning: UpstreamTuning,
+    /// Clients for routes with their own connect/read timeouts or HTTP/2 prior
+    /// knowledge, built on first use.
+    pub route_clients: Arc<dashmap::DashMap<(RouteTimeouts, bool), reqwest::Client>>,
+    /// Shadow traffic sampling and statistics.
+    pub mirror: Arc<MirrorTracker>,
+    /// Runtime route kill switches.
+    pub maintenance: Arc<Maintenance>,
+    /// Gamma response cache (None if caching is disabled).
+    pub cache: Option<Arc<ResponseCache>>,
+    /// Bearer token for the `/admin` endpoints (None = admin API disabled).
+    pub admin_token: Option<String>,
+    /// Hash-chained audit trail of proxied requests (None if auditing is disabled).
+    pub audit: Option<Arc<AuditTrail>>,
 }
 
 impl ProxyState {
     /// Create new proxy state without authentication.
     pub fn new() -> Result<Self, reqwest::Error> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+        let upstream_tuning = UpstreamTuning::default();
+        let client = upstream_tuning
+            .apply(reqwest::Client::builder())
+            .timeout(UPSTREAM_TIMEOUT)
             .build()?;
         Ok(Self {
             client,
             jwks_cache: None,
             rate_limiter: None,
+            concurrency: None,
+            websockets: None,
+            quota: None,
+            credits: None,
             auth_enabled: false,
+            tiers: TierRegistry::default(),
+            ip_access: IpAccessList::default(),
+            ip_limiter: None,
+            trusted_proxy_hops: 0,
+            tls_listener: false,
+            health: Arc::new(HealthProber::new(None, DEFAULT_HEALTH_PROBE_INTERVAL)),
+            clock: Arc::new(ClockProbe::default()),
+            stats: Arc::new(UpstreamStats::default()),
+            body_limits: BodyLimits::default(),
+            validate_orders: false,
+            stream_idle_timeout: stream::DEFAULT_IDLE_TIMEOUT,
+            routes: Arc::new(RouteTable::default()),
+            upstream_tuning,
+            route_clients: Arc::default(),
+            mirror: Arc::new(MirrorTracker::default()),
+            maintenance: Arc::new(Maintenance::new()),
+            cache: None,
+            admin_token: None,
+            audit: None,
         })
     }
 
     /// Create new proxy state with authentication.
     pub fn with_auth(config: &ProxyConfig) -> Result<Self, reqwest::Error> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+        let client = config
+            .upstream_tuning
+            .apply(reqwest::Client::builder())
+            .timeout(UPSTREAM_TIMEOUT)
             .build()?;
 
+        let tiers = match config.tiers_file {
+            Some(ref path) => TierRegistry::with_source(config.tiers.clone(), path.clone()),
+            None => TierRegistry::new(config.tiers.clone()),
+        };
+        let health_interval = std::time::Duration::from_secs(config.health_probe_secs.max(1));
+        let cache = (config.gamma_cache_secs > 0).then(|| {
+            Arc::new(ResponseCache::new(
+                std::time::Duration::from_secs(config.gamma_cache_secs),
+                config.cache_max_entries,
+            ))
+        });
+
+        let audit = config.audit_export.clone().map(|sink| {
+            Arc::new(AuditTrail::new(
+                sink,
+                config.audit_signing_key.as_deref().map(str::as_bytes),
+            ))
+        });
+
         if config.auth_enabled {
+            let quota = match config.quota_file {
+                Some(ref path) => QuotaTracker::with_store(path.clone()),
+                None => QuotaTracker::new(),
+            };
+
+            let credits = Arc::new(match config.credits_file {
+                Some(ref path) => CreditLedger::with_store(path.clone()),
+                None => CreditLedger::new(),
+            });
+
+            let jwks_cache = Arc::new(JwksCache::new(config));
+            let health = HealthProber::new(Some(jwks_cache.clone()), health_interval);
+
             Ok(Self {
                 client,
-                jwks_cache: Some(Arc::new(JwksCache::new(config))),
-                rate_limiter: Some(Arc::new(TenantRateLimiter::new(config))),
+                jwks_cache: Some(jwks_cache),
+                rate_limiter: Some(Arc::new(
+                    TenantRateLimiter::new(config, tiers.clone()).with_credits(credits.clone()),
+                )),
+                concurrency: Some(Arc::new(TenantConcurrencyLimiter::new(tiers.clone()))),
+                websockets: Some(Arc::new(WebSocketLimiter::new(tiers.clone()))),
+                quota: Some(Arc::new(quota)),
+                credits: Some(credits),
                 auth_enabled: true,
+                tiers,
+                ip_access: config.ip_access.clone(),
+                ip_limiter: None,
+                trusted_proxy_hops: config.trusted_proxy_hops,
+                tls_listener: config.tls_listener,
+                health: Arc::new(health),
+                clock: Arc::new(ClockProbe::default()),
+                stats: Arc::new(UpstreamStats::new(config.error_budget)),
+                body_limits: config.body_limits.clone(),
+                validate_orders: config.validate_orders,
+                stream_idle_timeout: std::time::Duration::from_secs(config.stream_idle_secs.max(1)),
+                routes: Arc::new(config.routes.clone()),
+                upstream_tuning: config.upstream_tuning,
+                route_clients: Arc::default(),
+                mirror: Arc::new(MirrorTracker::default()),
+                maintenance: Arc::new(Maintenance::new()),
+                cache: cache.clone(),
+                admin_token: config.admin_token.clone(),
+                audit: audit.clone(),
             })
         } else {
             Ok(Self {
                 client,
                 jwks_cache: None,
                 rate_limiter: None,
+                concurrency: None,
+                websockets: None,
+                quota: None,
+                credits: None,
                 auth_enabled: false,
+                tiers,
+                ip_access: config.ip_access.clone(),
+                ip_limiter: config
+                    .ip_rate_limit_rpm
+                    .map(|rpm| Arc::new(IpRateLimiter::new(rpm, config.ip_rate_limit_burst))),
+                trusted_proxy_hops: config.trusted_proxy_hops,
+                tls_listener: config.tls_listener,
+                health: Arc::new(HealthProber::new(None, health_interval)),
+                clock: Arc::new(ClockProbe::default()),
+                stats: Arc::new(UpstreamStats::new(config.error_budget)),
+                body_limits: config.body_limits.clone(),
+                validate_orders: config.validate_orders,
+                stream_idle_timeout: std::time::Duration::from_secs(config.stream_idle_secs.max(1)),
+                routes: Arc::new(config.routes.clone()),
+                upstream_tuning: config.upstream_tuning,
+                route_clients: Arc::default(),
+                mirror: Arc::new(MirrorTracker::default()),
+                maintenance: Arc::new(Maintenance::new()),
+                cache: cache.clone(),
+                admin_token: config.admin_token.clone(),
+                audit: audit.clone(),
             })
         }
     }
 
+    /// HTTP client for a route: the shared client unless the route sets its
+    /// own connect or read timeout or speaks HTTP/2 with prior knowledge.
+    fn client_for(&self, route: &Route) -> Result<reqwest::Client, reqwest::Error> {
+        if !route.timeouts.needs_client() && !route.http2_prior_knowledge {
+            return Ok(self.client.clone());
+        }
+        // The total timeout is set per request; routes sharing the rest share a client
+        let key = (RouteTimeouts { total: None, ..route.timeouts }, route.http2_prior_knowledge);
+        if let Some(client) = self.route_clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let (timeouts, http2_prior_knowledge) = key;
+        let mut builder = self
+            .upstream_tuning
+            .apply(reqwest::Client::builder())
+            .timeout(UPSTREAM_TIMEOUT);
+        if let Some(connect) = timeouts.connect {
+            builder = builder.connect_timeout(connect);
+        }
+        if let Some(read) = timeouts.read {
+            builder = builder.read_timeout(read);
+        }
+        if http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        let client = builder.build()?;
+        self.route_clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Start background maintenance tasks (tier file watcher, quota and credit persistence,
+    /// rate limiter and response cache cleanup, upstream health and clock probing, audit export).
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn_background_tasks(&self, config: &ProxyConfig) {
+        self.health
+            .spawn(std::time::Duration::from_secs(config.health_probe_secs.max(1)));
+        self.clock
+            .spawn(std::time::Duration::from_secs(config.clock_probe_secs.max(1)));
+        self.tiers
+            .spawn_watcher(std::time::Duration::from_secs(config.tiers_reload_secs.max(1)));
+        if let Some(ref quota) = self.quota {
+            quota.spawn_flusher(std::time::Duration::from_secs(config.quota_flush_secs.max(1)));
+        }
+        if let Some(ref credits) = self.credits {
+            credits.spawn_flusher(std::time::Duration::from_secs(config.quota_flush_secs.max(1)));
+        }
+        if let Some(ref audit) = self.audit {
+            audit.spawn_exporter(
+                self.client.clone(),
+                std::time::Duration::from_secs(config.audit_export_secs.max(1)),
+            );
+        }
+        if let Some(ref cache) = self.cache {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    cache.evict_expired();
+                }
+            });
+        }
+        if let Some(ref limiter) = self.rate_limiter {
+            let limiter = limiter.clone();
+            let idle_ttl = std::time::Duration::from_secs(config.rate_limit_idle_secs);
+            let max_tenants = config.rate_limit_max_tenants;
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    limiter.cleanup_stale(idle_ttl, max_tenants);
+                }
+            });
+        }
+        if let Some(ref limiter) = self.ip_limiter {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    limiter.cleanup_stale();
+                }
+            });
+        }
+    }
+
+    /// Flush state that must survive a restart (quota usage, burst credits).
+    pub fn persist(&self) {
+        if let Some(ref quota) = self.quota {
+            if let Err(e) = quota.flush() {
+                error!(error = %e, "Failed to persist quota usage");
+            }
+        }
+        if let Some(ref credits) = self.credits {
+            if let Err(e) = credits.flush() {
+                error!(error = %e, "Failed to persist burst credits");
+            }
+        }
+    }
+
+    /// Export audit records still pending (call on shutdown).
+    pub async fn export_audit(&self) {
+        if let Some(ref audit) = self.audit {
+            if let Err(e) = audit.export(&self.client).await {
+                error!(error = %e, "Failed to export final audit batch");
+            }
+        }
+    }
+
     /// Pre-fetch JWKS if authentication is enabled.
     pub async fn prefetch_jwks(&self) -> Result<(), error::AuthError> {
         if let Some(ref cache) = self.jwks_cache {
@@ -99,10 +419,34 @@ impl Default for ProxyState {
 
 /// Build the proxy router with shared state.
 pub fn build_router(state: Arc<ProxyState>) -> Router {
+    let admin = Router::new()
+        .route("/admin/cache/purge", post(admin::purge_cache_handler))
+        .route("/admin/mirror", get(admin::mirror_report_handler))
+        .route("/admin/websockets", get(admin::websocket_report_handler))
+        .route(
+            "/admin/maintenance",
+            get(admin::get_maintenance_handler)
+                .put(admin::set_maintenance_handler)
+                .delete(admin::clear_maintenance_handler),
+        )
+        .route(
+            "/admin/tenants/{tenant_id}/credits",
+            get(admin::get_credits_handler)
+                .post(admin::grant_credits_handler)
+                .delete(admin::revoke_credits_handler),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), admin::client_ip_guard));
+
     Router::new()
         .route("/health", get(health_handler))
+        .route("/health/ready", get(readiness_handler))
         .route("/badge", get(badge_handler))
+        .route("/time", get(time_handler))
+        .route("/stats", get(stats_handler))
+        .route("/whoami", get(whoami::whoami_handler))
+        .merge(admin)
         .fallback(proxy_handler)
+        .layer(axum::middleware::from_fn(request_id::middleware))
         .with_state(state)
 }
 
@@ -115,6 +459,37 @@ pub async fn health_handler() -> impl IntoResponse {
         .unwrap()
 }
 
+/// Clock endpoint (no auth required).
+///
+/// Reports the proxy's time and the CLOB's measured offset so signing
+/// clients can correct their own clock skew.
+pub async fn time_handler(State(state): State<Arc<ProxyState>>) -> impl IntoResponse {
+    // Measure inline until the background probe has succeeded once
+    if state.clock.report().clob_offset_ms.is_none() {
+        state.clock.probe().await.ok();
+    }
+    axum::Json(state.clock.report())
+}
+
+/// Per-upstream latency and error budget report (no auth required).
+pub async fn stats_handler(State(state): State<Arc<ProxyState>>) -> impl IntoResponse {
+    axum::Json(state.stats.report())
+}
+
+/// Readiness endpoint (no auth required).
+///
+/// Reports per-upstream reachability and JWKS cache freshness; returns 503
+/// unless everything is healthy.
+pub async fn readiness_handler(State(state): State<Arc<ProxyState>>) -> impl IntoResponse {
+    let report = state.health.report().await;
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, axum::Json(report))
+}
+
 /// Shields.io badge endpoint for server status.
 pub async fn badge_handler() -> impl IntoResponse {
     Response::builder()
@@ -126,11 +501,38 @@ pub async fn badge_handler() -> impl IntoResponse {
         .unwrap()
 }
 
+/// Apply IP allow/deny lists and, in unauthenticated mode, the per-IP rate limit.
+pub(crate) fn check_client_ip(
+    state: &ProxyState,
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+) -> Result<(), AuthError> {
+    if state.ip_access.is_empty() && state.ip_limiter.is_none() {
+        return Ok(());
+    }
+
+    let Some(ip) = ip::client_ip(headers, peer, state.trusted_proxy_hops) else {
+        // Fail closed when an allow list is configured but the client is unknown
+        if !state.ip_access.allow.is_empty() {
+            return Err(AuthError::IpBlocked);
+        }
+        return Ok(());
+    };
+
+    state.ip_access.check(ip)?;
+    if let Some(ref limiter) = state.ip_limiter {
+        limiter.check(ip)?;
+    }
+    Ok(())
+}
+
 /// Authenticate request if auth is enabled.
+///
+/// Returns the tenant along with its in-flight slot (if its tier caps concurrency).
 async fn authenticate(
     state: &ProxyState,
     auth_header: Option<&str>,
-) -> Result<Option<AuthenticatedTenant>, AuthError> {
+) -> Result<Option<(AuthenticatedTenant, Option<OwnedSemaphorePermit>)>, AuthError> {
     if !state.auth_enabled {
         return Ok(None);
     }
@@ -144,14 +546,26 @@ async fn authenticate(
         .ok_or_else(|| AuthError::JwksFetchError("Auth enabled but JWKS cache not initialized".to_string()))?;
 
     let claims = jwks_cache.validate_token(token).await?;
-    let tenant = AuthenticatedTenant::from(claims);
+    let tiers = state.tiers.snapshot();
+    let tenant = AuthenticatedTenant::from_claims(claims, &tiers);
 
     // Check rate limit
     if let Some(ref limiter) = state.rate_limiter {
-        limiter.check(&tenant.tenant_id, tenant.tier)?;
+        limiter.acquire(&tenant.tenant_id, tenant.tier).await?;
+    }
+
+    // Reserve an in-flight slot
+    let slot = match state.concurrency {
+        Some(ref limiter) => limiter.acquire(&tenant.tenant_id, tenant.tier)?,
+        None => None,
+    };
+
+    // Check cumulative quota (only requests that passed the limits above count)
+    if let Some(ref quota) = state.quota {
+        quota.check(&tenant.tenant_id, tiers.get(tenant.tier))?;
     }
 
-    Ok(Some(tenant))
+    Ok(Some((tenant, slot)))
 }
 
 /// Core proxy handler - authenticates (if enabled) and forwards requests to upstream APIs.
@@ -159,6 +573,7 @@ pub async fn proxy_handler(
     State(state): State<Arc<ProxyState>>,
     req: Request,
 ) -> impl IntoResponse {
+    let started = std::time::SystemTime::now();
     let uri = req.uri().clone();
     let method = req.method().clone();
     let headers = req.headers().clone();
@@ -166,29 +581,50 @@ pub async fn proxy_handler(
     let path = uri.path();
     let query = uri.query().unwrap_or("");
 
+    // Check client IP before doing any auth work
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    if let Err(e) = check_client_ip(&state, &headers, peer) {
+        return e.into_response();
+    }
+
+    // Switched-off routes are rejected before spending auth or rate-limit work
+    if let Err(e) = state.maintenance.check(&method, path) {
+        warn!(method = %method, path = %path, "Rejected request to disabled route");
+        return e.into_response();
+    }
+
     // Authenticate if enabled
     let auth_header = headers
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok());
 
-    let tenant = match authenticate(&state, auth_header).await {
-        Ok(t) => t,
+    // The in-flight slot is held until the upstream response has been read (or, for
+    // streamed responses, relayed in full)
+    let (tenant, slot) = match authenticate(&state, auth_header).await {
+        Ok(Some((t, slot))) => (Some(t), slot),
+        Ok(None) => (None, None),
         Err(e) => {
             return e.into_response();
         }
     };
 
     // Log with tenant info if available
+    let client_ip = ip::client_ip(&headers, peer, state.trusted_proxy_hops);
     if let Some(ref t) = tenant {
         info!(
             tenant_id = %t.tenant_id,
-            tier = ?t.tier,
+            tier = %t.tier_name,
+            client_ip = ?client_ip,
             method = %method,
             path = %path,
             "Proxying authenticated request"
         );
     } else {
         info!(
+            client_ip = ?client_ip,
             method = %method,
             path = %path,
             query = %if query.is_empty() { "" } else { query },
@@ -196,57 +632,161 @@ pub async fn proxy_handler(
         );
     }
 
+    // Scheme the client used (trusted X-Forwarded-Proto or our own listener)
+    let listener_scheme = if state.tls_listener { "https" } else { "http" };
+    let scheme = uri.scheme_str().unwrap_or(listener_scheme);
+    let forwarded_for = ForwardedFor::from_request(&headers, peer, state.trusted_proxy_hops, scheme);
+    let client_tls = forwarded_for.proto == "https";
+
     // Determine upstream based on path prefix
-    let (upstream_base, upstream_path) = if path == "/clob" {
-        ("https://clob.polymarket.com", "")
-    } else if let Some(rest) = path.strip_prefix("/clob/") {
-        ("https://clob.polymarket.com", rest)
-    } else if path == "/gamma" {
-        ("https://gamma-api.polymarket.com", "")
-    } else if let Some(rest) = path.strip_prefix("/gamma/") {
-        ("https://gamma-api.polymarket.com", rest)
-    } else if path == "/chain" {
-        ("https://polygon-rpc.com", "")
-    } else if let Some(rest) = path.strip_prefix("/chain/") {
-        ("https://polygon-rpc.com", rest)
-    } else {
+    let audit = |status: StatusCode| {
+        record_audit(&state, tenant.as_ref(), client_ip, &method, path, status, started)
+    };
+
+    let Some((route, upstream_path)) = state.routes.resolve(path) else {
         error!("Unknown path prefix: {}", path);
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body(Body::from("Not found"))
-            .unwrap();
+        audit(StatusCode::NOT_FOUND);
+        return ProxyError::NotFound { path: path.to_string() }.into_response();
     };
+    // Group-restricted routes (closed to everyone when there is no tenant to check)
+    if let Some(groups) = route.required_groups(&method, upstream_path) {
+        let allowed = tenant.as_ref().is_some_and(|t| t.groups.iter().any(|g| groups.contains(g)));
+        if !allowed {
+            warn!(
+                tenant_id = ?tenant.as_ref().map(|t| &t.tenant_id),
+                method = %method,
+                path = %path,
+                "Caller not in a required group"
+            );
+            audit(StatusCode::FORBIDDEN);
+            return AuthError::Forbidden { required_groups: groups.to_vec() }.into_response();
+        }
+    }
 
-    // Build upstream URL
-    let upstream_url = if query.is_empty() {
-        format!("{}/{}", upstream_base, upstream_path)
-    } else {
-        format!("{}/{}?{}", upstream_base, upstream_path, query)
+    // Canary routing is sticky per tenant (or client IP without auth)
+    let sticky_key = tenant
+        .as_ref()
+        .map(|t| t.tenant_id.clone())
+        .or_else(|| client_ip.map(|ip| ip.to_string()));
+    let upstream_base = route.select_upstream(sticky_key.as_deref());
+
+    // Serve cacheable Gamma reads from the cache; writes invalidate the resource
+    let cache = state.cache.as_ref().filter(|_| route.prefix == cache::CACHED_ROUTE);
+    let cache_key = match cache {
+        Some(cache) if method == Method::GET => {
+            let key = ResponseCache::key(path, query);
+            if let Some(hit) = cache.get(&key) {
+                debug!(key = %key, "Cache hit");
+                audit(hit.status);
+                let mut response = hit.to_response();
+                if let Some(hardening) = route.harden {
+                    hardening.apply(response.headers_mut(), client_tls);
+                }
+                return response;
+            }
+            Some(key)
+        }
+        Some(cache) => {
+            let purged = cache.invalidate_for_write(upstream_path);
+            if purged > 0 {
+                info!(path = %path, purged = purged, "Invalidated cached responses after write");
+            }
+            None
+        }
+        None => None,
     };
 
+    // Build upstream URL (a bare prefix maps to the upstream base itself, so an upstream
+    // with a path like `/neg-risk` isn't given a trailing slash)
+    let mut upstream_url = upstream_base.to_string();
+    if !upstream_path.is_empty() {
+        upstream_url.push('/');
+        upstream_url.push_str(upstream_path);
+    }
+    if !query.is_empty() {
+        upstream_url.push('?');
+        upstream_url.push_str(query);
+    }
+
     debug!("Upstream URL: {}", upstream_url);
 
-    // Read request body
-    let body = match axum::body::to_bytes(req.into_body(), usize::MAX).await {
+    // Read request body (bounded by the route's size limit)
+    let limit = state.body_limits.for_route(&route.prefix);
+    let body = match BodyLimits::read(&headers, req.into_body(), limit).await {
         Ok(b) => b,
         Err(e) => {
-            error!("Failed to read request body: {}", e);
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(Body::from("Bad request"))
-                .unwrap();
+            error!(route = %route.prefix, error = %e, "Rejected request body");
+            let response = e.into_response();
+            audit(response.status());
+            return response;
         }
     };
+    if state.validate_orders && order_check::applies(&method, &route.prefix, upstream_path) {
+        if let Err(e) = order_check::validate(&body) {
+            warn!(tenant = ?tenant.as_ref().map(|t| &t.tenant_id), error = %e, "Rejected order");
+            let response = e.into_response();
+            audit(response.status());
+            return response;
+        }
+    }
 
-    let mut upstream_req = state.client.request(method.clone(), &upstream_url);
+    let client = match state.client_for(route) {
+        Ok(client) => client,
+        Err(e) => {
+            error!(route = %route.prefix, error = %e, "Failed to build upstream client");
+            audit(StatusCode::INTERNAL_SERVER_ERROR);
+            return ProxyError::Internal("Upstream client unavailable".to_string()).into_response();
+        }
+    };
+    let mut upstream_req = client.request(method.clone(), &upstream_url);
 
-    // Forward all headers except Host and Authorization (reqwest sets Host automatically,
-    // and we don't forward our auth to upstream)
-    for (name, value) in headers.iter() {
-        let name_str = name.as_str();
-        if name_str == "host" || name_str == "authorization" {
-            continue;
+    // Forward all end-to-end headers except Host and Authorization (reqwest sets Host
+    // automatically, and we don't forward our auth to upstream), then apply the route's
+    // header rules
+    let mut forwarded = headers.clone();
+    hop::strip(&mut forwarded, Hop::Request);
+    forwarded.remove(header::HOST);
+    forwarded.remove(header::AUTHORIZATION);
+    forwarded_for.apply(&mut forwarded);
+    // The shadow gets neither the client's credentials nor the route's header
+    // rules (which may inject secrets meant for the primary upstream)
+    let shadow_headers = route.mirror.as_ref().map(|_| {
+        let mut shadow_headers = mirror::shadow_headers(&forwarded);
+        body::frame(&mut shadow_headers, &body);
+        shadow_headers
+    });
+    route.rewrite_request(&mut forwarded);
+    body::frame(&mut forwarded, &body);
+
+    // Mirror a share of the route's traffic to its shadow upstream (fire-and-forget)
+    let shadow = route
+        .mirror
+        .as_ref()
+        .filter(|m| m.mirrors(&method) && state.mirror.sample(&route.prefix, m))
+        .and_then(|m| {
+            let shadow_url = format!("{}{}", m.upstream, &upstream_url[upstream_base.len()..]);
+            let mut shadow_req = client.request(method.clone(), shadow_url).headers(shadow_headers.unwrap_or_default());
+            if let Some(total) = route.timeouts.total {
+                shadow_req = shadow_req.timeout(total);
+            }
+            if !body.is_empty() {
+                shadow_req = shadow_req.body(body.clone());
+            }
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            state.mirror.spawn(&route.prefix, shadow_req, rx).then_some(tx)
+        });
+
+    // Authenticate the proxy itself to the upstream (never mirrored or returned)
+    if let Some(ref auth) = route.auth {
+        if let Err(e) = auth.apply(&method, &upstream_url, &body, &mut forwarded) {
+            error!(route = %route.prefix, error = %e, "Upstream authentication failed");
+            audit(StatusCode::BAD_GATEWAY);
+            return ProxyError::BadGateway("Upstream authentication unavailable".to_string()).into_response();
         }
+    }
+
+    for (name, value) in forwarded.iter() {
+        let name_str = name.as_str();
 
         // Restore original casing for POLY_* headers
         let header_name = match name_str {
@@ -267,38 +807,62 @@ pub async fn proxy_handler(
         upstream_req = upstream_req.body(body);
     }
 
+    // The route's latency budget replaces the default timeout
+    if let Some(total) = route.timeouts.total {
+        upstream_req = upstream_req.timeout(total);
+    }
+
+    // Event streams stay open indefinitely; the idle timeout ends stalled ones
+    if stream::accepts_event_stream(&headers) {
+        upstream_req = upstream_req.timeout(stream::MAX_EVENT_STREAM_DURATION);
+    }
+
     // Send request
+    let sent = std::time::Instant::now();
     let upstream_resp = match upstream_req.send().await {
         Ok(r) => r,
         Err(e) => {
             error!("Upstream request failed: {}", e);
-            return Response::builder()
-                .status(StatusCode::BAD_GATEWAY)
-                .body(Body::from(format!("Upstream error: {}", e)))
-                .unwrap();
+            state.stats.record(&route.prefix, upstream_base, sent.elapsed(), true);
+            if let Some(tx) = shadow {
+                tx.send(None).ok();
+            }
+            let response = ProxyError::upstream(&e, format!("Upstream error: {}", e)).into_response();
+            audit(response.status());
+            return response;
         }
     };
 
     // Build response
     let status = upstream_resp.status();
     debug!("Upstream status: {}", status);
+    state.stats.record(&route.prefix, upstream_base, sent.elapsed(), status.is_server_error());
+    if let Some(tx) = shadow {
+        tx.send(Some((status, sent.elapsed()))).ok();
+    }
 
     let mut response = Response::builder().status(status);
 
-    // Forward response headers (skip hop-by-hop headers)
-    for (name, value) in upstream_resp.headers().iter() {
-        let name_str = name.as_str();
-        // Skip hop-by-hop headers
-        if name_str != "connection"
-            && name_str != "transfer-encoding"
-            && name_str != "keep-alive"
-            && name_str != "proxy-authenticate"
-            && name_str != "proxy-authorization"
-            && name_str != "trailer"
-            && name_str != "upgrade"
-        {
-            response = response.header(name, value);
-        }
+    // Forward response headers (skip hop-by-hop headers) after the route's rules
+    let streaming = stream::is_streaming(upstream_resp.headers());
+    let mut returned = upstream_resp.headers().clone();
+    hop::strip(&mut returned, Hop::Response);
+    route.rewrite_response(&mut returned);
+    if let Some(hardening) = route.harden {
+        hardening.apply(&mut returned, client_tls);
+    }
+    for (name, value) in returned.iter() {
+        response = response.header(name, value);
+    }
+
+    // Relay event streams and chunked bodies as they arrive (cached responses are
+    // still buffered so they can be stored)
+    if streaming && cache_key.is_none() {
+        debug!(path = %path, "Streaming upstream response");
+        audit(status);
+        return response
+            .body(stream::relay(upstream_resp, state.stream_idle_timeout, slot))
+            .unwrap();
     }
 
     // Forward response body
@@ -306,16 +870,48 @@ pub async fn proxy_handler(
         Ok(b) => b,
         Err(e) => {
             error!("Failed to read upstream response: {}", e);
-            return Response::builder()
-                .status(StatusCode::BAD_GATEWAY)
-                .body(Body::from("Failed to read response"))
-                .unwrap();
+            let response = ProxyError::upstream(&e, "Failed to read response").into_response();
+            audit(response.status());
+            return response;
         }
     };
 
+    if let (Some(cache), Some(key)) = (cache, cache_key) {
+        cache.insert(key, upstream_path, status, returned, body_bytes.clone());
+        response = response.header("X-Cache", "MISS");
+    }
+
+    audit(status);
     response.body(Body::from(body_bytes)).unwrap()
 }
 
+/// Append a proxied request to the audit trail (if enabled).
+fn record_audit(
+    state: &ProxyState,
+    tenant: Option<&AuthenticatedTenant>,
+    client_ip: Option<std::net::IpAddr>,
+    method: &Method,
+    path: &str,
+    status: StatusCode,
+    started: std::time::SystemTime,
+) {
+    let Some(ref audit) = state.audit else {
+        return;
+    };
+    audit.record(AuditRecord {
+        timestamp_ms: started
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        tenant_id: tenant.map(|t| t.tenant_id.clone()),
+        client_ip: client_ip.map(|ip| ip.to_string()),
+        method: method.to_string(),
+        path: path.to_string(),
+        status: status.as_u16(),
+        latency_ms: started.elapsed().unwrap_or_default().as_millis() as u64,
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -336,14 +932,7 @@ mod tests {
 
     #[test]
     fn test_proxy_state_with_auth_disabled() {
-        let config = ProxyConfig {
-            auth_enabled: false,
-            cognito_region: "us-east-1".to_string(),
-            cognito_pool_id: "test".to_string(),
-            cognito_client_id: None,
-            rate_limit_rpm: 100,
-            rate_limit_burst: 20,
-        };
+        let config = ProxyConfig::for_tests(false);
 
         let state = ProxyState::with_auth(&config).unwrap();
         assert!(!state.auth_enabled);
@@ -351,20 +940,723 @@ mod tests {
         assert!(state.rate_limiter.is_none());
     }
 
+    #[test]
+    fn test_ip_limiter_only_without_auth() {
+        let open = ProxyConfig {
+            ip_rate_limit_rpm: Some(30),
+            ..ProxyConfig::for_tests(false)
+        };
+        assert!(ProxyState::with_auth(&open).unwrap().ip_limiter.is_some());
+
+        let authed = ProxyConfig {
+            ip_rate_limit_rpm: Some(30),
+            ..ProxyConfig::for_tests(true)
+        };
+        assert!(ProxyState::with_auth(&authed).unwrap().ip_limiter.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_denied_ip_is_rejected() {
+        let config = ProxyConfig {
+            ip_access: IpAccessList {
+                allow: Vec::new(),
+                deny: ip::parse_ip_rules("203.0.113.0/24").unwrap(),
+            },
+            trusted_proxy_hops: 1,
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let req = Request::builder()
+            .uri("/gamma/markets")
+            .header("x-forwarded-for", "203.0.113.9")
+            .body(Body::empty())
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let config = ProxyConfig {
+            body_limits: BodyLimits {
+                default: 1024,
+                routes: body::parse_route_limits("clob=16").unwrap(),
+            },
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/clob/order")
+            .header("content-length", "17")
+            .body(Body::from("x".repeat(17)))
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "payload_too_large");
+        assert_eq!(error["limit_bytes"], 16);
+    }
+
+    #[tokio::test]
+    async fn test_group_restricted_route_is_closed_without_auth() {
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(
+                r#"[{"prefix": "clob", "upstream": "http://127.0.0.1:9",
+                     "authorize": [{"methods": ["POST"], "path": "order", "groups": ["traders"]}]}]"#,
+            )
+            .unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/clob/order")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = proxy_handler(State(state.clone()), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["required_groups"], serde_json::json!(["traders"]));
+
+        // Requests no rule matches still go upstream (unreachable here)
+        let req = Request::builder().uri("/clob/book").body(Body::empty()).unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_order_is_rejected_before_upstream() {
+        let config = ProxyConfig {
+            validate_orders: true,
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        // Fat-fingered BUY at 1.50
+        let order = r#"{"order": {"tokenId": "123", "makerAmount": "15000000", "takerAmount": "10000000",
+            "side": "BUY"}, "owner": "key", "orderType": "GTC"}"#;
+        let req = Request::builder()
+            .method("POST")
+            .uri("/clob/order")
+            .body(Body::from(order))
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "invalid_order");
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_is_relayed_unbuffered() {
+        use http_body_util::BodyExt;
+
+        // Upstream sends one event, then stalls
+        let upstream = Router::new().route(
+            "/events",
+            get(|| async {
+                let events = futures_util::stream::unfold(0, |n| async move {
+                    if n > 0 {
+                        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                    }
+                    Some((Ok::<_, std::io::Error>("data: 1\n\n"), n + 1))
+                });
+                ([(header::CONTENT_TYPE, "text/event-stream")], Body::from_stream(events))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&format!(r#"[{{"prefix": "sse", "upstream": "http://{}"}}]"#, addr))
+                .unwrap(),
+            stream_idle_secs: 1,
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let req = Request::builder()
+            .uri("/sse/events")
+            .header("accept", "text/event-stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut body = response.into_body();
+        let first = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        assert_eq!(first, "data: 1\n\n");
+        // The stalled stream is closed by the idle timeout
+        assert!(body.frame().await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_binary_and_multipart_bodies_pass_through_unchanged() {
+        // Upstream echoes the body and the headers describing it
+        let upstream = Router::new().route(
+            "/echo",
+            post(|headers: HeaderMap, body: axum::body::Bytes| async move {
+                let mut echoed = HeaderMap::new();
+                for name in [header::CONTENT_TYPE, header::CONTENT_ENCODING, header::CONTENT_LENGTH] {
+                    if let Some(value) = headers.get(&name) {
+                        let echo: header::HeaderName = format!("x-echo-{}", name).parse().unwrap();
+                        echoed.insert(echo, value.clone());
+                    }
+                }
+                let expect = headers.contains_key(header::EXPECT) as u16;
+                echoed.insert("x-echo-expect", header::HeaderValue::from(expect));
+                (echoed, body)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let routes = format!(r#"[{{"prefix": "chain", "upstream": "http://{}"}}]"#, addr);
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            body_limits: BodyLimits {
+                default: 1024,
+                routes: Default::default(),
+            },
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        // Gzip-compressed payload: not valid UTF-8
+        let gzip: Vec<u8> = [0x1f, 0x8b, 0x08, 0x00].into_iter().chain((0..=255).rev()).collect();
+        let boundary = "pmproxy-boundary";
+        let mut multipart = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"blob\"; filename=\"b.bin\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n",
+            b = boundary
+        )
+        .into_bytes();
+        multipart.extend_from_slice(&[0x00, 0xff, 0xfe, 0x80, 0x0d, 0x0a]);
+        multipart.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let cases = [
+            ("application/octet-stream", Some("gzip"), gzip),
+            (&*format!("multipart/form-data; boundary={}", boundary), None, multipart),
+        ];
+        for (content_type, encoding, payload) in cases {
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri("/chain/echo")
+                .header(header::CONTENT_TYPE, content_type)
+                .header(header::CONTENT_LENGTH, payload.len())
+                .header(header::EXPECT, "100-continue");
+            if let Some(encoding) = encoding {
+                req = req.header(header::CONTENT_ENCODING, encoding);
+            }
+            let req = req.body(Body::from(payload.clone())).unwrap();
+            let response = proxy_handler(State(state.clone()), req).await.into_response();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let headers = response.headers().clone();
+            assert_eq!(headers["x-echo-content-type"], content_type);
+            assert_eq!(headers["x-echo-content-length"], payload.len().to_string().as_str());
+            assert_eq!(headers.get("x-echo-content-encoding").map(|v| v.to_str().unwrap()), encoding);
+            assert_eq!(headers["x-echo-expect"], "0");
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(&body[..], &payload[..]);
+        }
+
+        // Chunked upload (no Content-Length) is forwarded with its exact length
+        let chunks = [vec![0xffu8; 10], vec![0x00; 5]].map(Ok::<_, std::io::Error>);
+        let chunks = futures_util::stream::iter(chunks);
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/chain/echo")
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(Body::from_stream(chunks))
+            .unwrap();
+        let response = proxy_handler(State(state.clone()), req).await.into_response();
+        assert_eq!(response.headers()["x-echo-content-length"], "15");
+
+        // Over the limit: 413 before the upstream sees anything
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/chain/echo")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+            .header(header::EXPECT, "100-continue")
+            .body(Body::from(vec![0xffu8; 2048]))
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_errors_carry_request_id() {
+        // Nothing listens on the upstream port
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = closed.local_addr().unwrap();
+        drop(closed);
+
+        let routes = format!(r#"[{{"prefix": "clob", "upstream": "http://{}"}}]"#, upstream);
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let app = build_router(Arc::new(ProxyState::with_auth(&config).unwrap()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("http://{}/nowhere", addr))
+            .header("x-request-id", "client-trace-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()["x-request-id"], "client-trace-1");
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["error"], "not_found");
+        assert_eq!(error["request_id"], "client-trace-1");
+
+        // Without a usable client ID the proxy generates one
+        let response = client.get(format!("http://{}/clob/book", addr)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let request_id = response.headers()["x-request-id"].to_str().unwrap().to_string();
+        let error: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(error["error"], "bad_gateway");
+        assert_eq!(error["request_id"], request_id.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_sigv4_route_signs_what_the_upstream_receives() {
+        // Upstream echoes what it received so the signature can be checked against it
+        let upstream = Router::new().route(
+            "/prod/{*rest}",
+            post(|headers: HeaderMap, uri: axum::http::Uri, body: axum::body::Bytes| async move {
+                let received = serde_json::json!({
+                    "host": headers[header::HOST].to_str().unwrap(),
+                    "path": uri.path(),
+                    "query": uri.query().unwrap_or(""),
+                    "authorization": headers[header::AUTHORIZATION].to_str().unwrap(),
+                    "security_token": headers["x-amz-security-token"].to_str().unwrap(),
+                    "body": String::from_utf8_lossy(&body),
+                });
+                axum::Json(received)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let routes = format!(
+            r#"[{{"prefix": "internal", "upstream": "http://{}/prod",
+                  "auth": {{"type": "sigv4", "region": "us-east-1", "access_key_id": "AKIDEXAMPLE",
+                            "secret_access_key": "secret", "session_token": "role-session"}}}}]"#,
+            addr
+        );
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/internal/orders/a%20b?b=2&a=1")
+            .header(header::AUTHORIZATION, "Bearer client-token")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"size":1}"#))
+            .unwrap();
+        let response = proxy_handler(State(state), req).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let received: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(received["security_token"], "role-session");
+
+        // Re-sign what arrived; one of the last few seconds must match
+        let credentials = sigv4::Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: Some("role-session".to_string()),
+        };
+        let request = sigv4::Request {
+            method: "POST",
+            host: received["host"].as_str().unwrap(),
+            path: received["path"].as_str().unwrap(),
+            query: received["query"].as_str().unwrap(),
+            payload: received["body"].as_str().unwrap().as_bytes(),
+        };
+        let now = std::time::SystemTime::now();
+        let matches = (0..5).any(|secs| {
+            let at = now - std::time::Duration::from_secs(secs);
+            sigv4::sign(&credentials, "us-east-1", "execute-api", &request, at).authorization
+                == received["authorization"].as_str().unwrap()
+        });
+        assert!(matches, "signature does not cover the request as received: {}", received);
+    }
+
+    #[tokio::test]
+    async fn test_hardened_route_cleans_up_upstream_headers() {
+        let upstream = Router::new().route(
+            "/page",
+            get(|| async {
+                (
+                    [
+                        (header::SET_COOKIE, "session=upstream"),
+                        (header::SERVER, "nginx/1.18.0"),
+                        (axum::http::HeaderName::from_static("x-powered-by"), "Express"),
+                    ],
+                    "ok",
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let routes = format!(
+            r#"[{{"prefix": "private", "upstream": "http://{addr}", "harden": {{}}}},
+                {{"prefix": "plain", "upstream": "http://{addr}"}}]"#
+        );
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let get_uri = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = proxy_handler(State(state.clone()), get_uri("https://proxy.example.com/private/page"))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert!(headers.get(header::SET_COOKIE).is_none());
+        assert!(headers.get(header::SERVER).is_none());
+        assert!(headers.get("x-powered-by").is_none());
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert!(headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+        // Plain HTTP: no HSTS
+        let response = proxy_handler(State(state.clone()), get_uri("/private/page")).await.into_response();
+        assert!(response.headers().get(header::STRICT_TRANSPORT_SECURITY).is_none());
+
+        // Routes without hardening pass headers through
+        let response = proxy_handler(State(state), get_uri("/plain/page")).await.into_response();
+        assert_eq!(response.headers()[header::SET_COOKIE], "session=upstream");
+        assert_eq!(response.headers()[header::SERVER], "nginx/1.18.0");
+    }
+
+    #[tokio::test]
+    async fn test_data_and_negrisk_routes_forward_headers() {
+        // Upstream echoes the request line and headers it received
+        let echo = |uri: axum::http::Uri, headers: HeaderMap| async move {
+            let header = |name: &str| headers.get(name).map(|v| v.to_str().unwrap().to_string());
+            axum::Json(serde_json::json!({
+                "uri": uri.to_string(),
+                "poly_address": header("poly_address"),
+                "poly_api_key": header("poly_api_key"),
+                "poly_signature": header("poly_signature"),
+                "authorization": header("authorization"),
+                "request_id": header("x-request-id"),
+                "accept": header("accept"),
+            }))
+        };
+        let upstream = Router::new()
+            .route("/positions", get(echo))
+            .route("/neg-risk", get(echo));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        // Built-in routes, pointed at the local upstream
+        let definitions = routes::builtin_routes()
+            .into_iter()
+            .map(|mut def| {
+                def.upstream = def
+                    .upstream
+                    .replace("https://data-api.polymarket.com", &format!("http://{}", addr))
+                    .replace("https://clob.polymarket.com", &format!("http://{}", addr));
+                def
+            })
+            .collect();
+        let config = ProxyConfig {
+            routes: RouteTable::new(definitions).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let get_uri = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .header(header::AUTHORIZATION, "Bearer client-token")
+                .header(header::ACCEPT, "application/json")
+                .header("x-request-id", "trace-1")
+                .header("POLY_ADDRESS", "0xabc")
+                .header("POLY_API_KEY", "key")
+                .header("POLY_SIGNATURE", "sig")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let received = |response: Response| async move {
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = proxy_handler(State(state.clone()), get_uri("/data/positions?user=0xabc"))
+            .await
+            .into_response();
+        let data = received(response).await;
+        assert_eq!(data["uri"], "/positions?user=0xabc");
+        assert_eq!(data["accept"], "application/json");
+        assert_eq!(data["request_id"], "trace-1");
+        assert_eq!(data["poly_address"], "0xabc");
+        // CLOB credentials never reach the public data-api, nor does the proxy's own auth
+        assert!(data["poly_api_key"].is_null());
+        assert!(data["poly_signature"].is_null());
+        assert!(data["authorization"].is_null());
+
+        // A bare prefix maps onto the upstream's path without a trailing slash
+        let response = proxy_handler(State(state), get_uri("/negrisk?token_id=123")).await.into_response();
+        let negrisk = received(response).await;
+        assert_eq!(negrisk["uri"], "/neg-risk?token_id=123");
+        assert_eq!(negrisk["poly_api_key"], "key");
+        assert_eq!(negrisk["poly_signature"], "sig");
+        assert!(negrisk["authorization"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_route_latency_budget_returns_gateway_timeout() {
+        let upstream = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    "late"
+                }),
+            )
+            .route("/fast", get(|| async { "ok" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let routes = format!(
+            r#"[{{"prefix": "clob", "upstream": "http://{}",
+                  "timeouts": {{"connect_ms": 500, "total_ms": 200}}}}]"#,
+            addr
+        );
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+
+        let get_path = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+        let started = std::time::Instant::now();
+        let response = proxy_handler(State(state.clone()), get_path("/clob/slow")).await.into_response();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+
+        let response = proxy_handler(State(state.clone()), get_path("/clob/fast")).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.route_clients.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_apply_ip_lists() {
+        let config = ProxyConfig {
+            admin_token: Some("s3cret".to_string()),
+            ip_access: IpAccessList {
+                allow: Vec::new(),
+                deny: ip::parse_ip_rules("203.0.113.0/24").unwrap(),
+            },
+            trusted_proxy_hops: 1,
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = build_router(state).into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let admin = |forwarded_for: &'static str| {
+            client
+                .get(format!("http://{}/admin/mirror", addr))
+                .header("authorization", "Bearer s3cret")
+                .header("x-forwarded-for", forwarded_for)
+                .send()
+        };
+        // A denied IP is turned away even with the right token
+        assert_eq!(admin("203.0.113.9").await.unwrap().status(), StatusCode::FORBIDDEN);
+        assert_eq!(admin("198.51.100.1").await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_cache_purge() {
+        let config = ProxyConfig {
+            gamma_cache_secs: 60,
+            admin_token: Some("s3cret".to_string()),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let cache = state.cache.clone().unwrap();
+        for path in ["markets/1", "markets/2", "events/1"] {
+            cache.insert(
+                ResponseCache::key(&format!("/gamma/{}", path), ""),
+                path,
+                StatusCode::OK,
+                HeaderMap::new(),
+                axum::body::Bytes::from_static(b"[]"),
+            );
+        }
+        let bearer = |token: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+            headers
+        };
+        let by_tag = || {
+            Some(axum::Json(admin::PurgeRequest {
+                tag: Some("markets".to_string()),
+                ..Default::default()
+            }))
+        };
+
+        let response = admin::purge_cache_handler(State(state.clone()), bearer("wrong"), by_tag()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(cache.len(), 3);
+
+        let response = admin::purge_cache_handler(State(state), bearer("s3cret"), by_tag()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(result["purged"], 2);
+        assert_eq!(result["remaining"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_admin_burst_credits() {
+        let config = ProxyConfig {
+            admin_token: Some("s3cret".to_string()),
+            ..ProxyConfig::for_tests(true)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        let tenant = || axum::extract::Path("batch-tenant".to_string());
+
+        let grant = admin::GrantCreditsRequest {
+            requests: 500,
+            ttl_secs: 3600,
+        };
+        let response =
+            admin::grant_credits_handler(State(state.clone()), tenant(), headers.clone(), axum::Json(grant))
+                .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Exhaust the free tier's burst; credits cover the overflow
+        let limiter = state.rate_limiter.clone().unwrap();
+        for _ in 0..15 {
+            assert!(limiter.check("batch-tenant", config::TenantTier::DEFAULT).is_ok());
+        }
+
+        let response = admin::get_credits_handler(State(state.clone()), tenant(), headers.clone()).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let balance: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(balance["remaining"], 495);
+
+        let response = admin::revoke_credits_handler(State(state), tenant(), headers).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let revoked: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(revoked["revoked"], 495);
+    }
+
+    #[tokio::test]
+    async fn test_admin_maintenance_disables_routes() {
+        let config = ProxyConfig {
+            admin_token: Some("s3cret".to_string()),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = Arc::new(ProxyState::with_auth(&config).unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+
+        let settings: maintenance::MaintenanceState = serde_json::from_str(
+            r#"{"disabled": [{"path": "/clob/order", "methods": ["POST"], "reason": "incident"}]}"#,
+        )
+        .unwrap();
+        let response =
+            admin::set_maintenance_handler(State(state.clone()), headers.clone(), axum::Json(settings)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/clob/order")
+            .body(Body::empty())
+            .unwrap();
+        let response = proxy_handler(State(state.clone()), request).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "route_disabled");
+        assert_eq!(error["message"], "incident");
+
+        let response = admin::clear_maintenance_handler(State(state.clone()), headers).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!state.maintenance.get().is_active());
+    }
+
+    #[tokio::test]
+    async fn test_whoami_requires_auth() {
+        let whoami = |state: Arc<ProxyState>| async move {
+            let request = Request::builder().uri("/whoami").body(Body::empty()).unwrap();
+            whoami::whoami_handler(State(state), request).await.status()
+        };
+
+        let state = Arc::new(ProxyState::with_auth(&ProxyConfig::for_tests(false)).unwrap());
+        assert_eq!(whoami(state).await, StatusCode::NOT_FOUND);
+
+        let state = Arc::new(ProxyState::with_auth(&ProxyConfig::for_tests(true)).unwrap());
+        assert_eq!(whoami(state).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_requires_fresh_jwks() {
+        let state = Arc::new(ProxyState::with_auth(&ProxyConfig::for_tests(true)).unwrap());
+        state.health.record(vec![
+            health::tests::status("clob", true),
+            health::tests::status("gamma", true),
+        ]);
+
+        // Upstreams are fine but the JWKS cache was never populated
+        let response = readiness_handler(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["status"], "degraded");
+        assert_eq!(report["jwks"]["fresh"], false);
+        assert_eq!(report["upstreams"][0]["reachable"], true);
+    }
+
     #[test]
     fn test_proxy_state_with_auth_enabled() {
         let config = ProxyConfig {
-            auth_enabled: true,
-            cognito_region: "us-east-1".to_string(),
-            cognito_pool_id: "us-east-1_test123".to_string(),
             cognito_client_id: Some("client123".to_string()),
-            rate_limit_rpm: 100,
-            rate_limit_burst: 20,
+            ..ProxyConfig::for_tests(true)
         };
 
         let state = ProxyState::with_auth(&config).unwrap();
         assert!(state.auth_enabled);
         assert!(state.jwks_cache.is_some());
         assert!(state.rate_limiter.is_some());
+        assert!(state.concurrency.is_some());
+        assert!(state.quota.is_some());
     }
 }
@@ -1,6 +1,8 @@
 use clap::Parser;
-use pmproxy::{build_router, config::ProxyConfig, ProxyState};
+use pmproxy::{build_router, config::ProxyConfig, tls::TlsFiles, ProxyState};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -21,6 +23,22 @@ struct Args {
     /// Log level
     #[arg(short, long, default_value = "info")]
     log_level: String,
+
+    /// Run without tenant authentication, even if PMPROXY_AUTH_ENABLED is set
+    #[arg(long)]
+    no_auth: bool,
+
+    /// PEM certificate chain; serve HTTPS instead of HTTP (requires --tls-key)
+    #[arg(long, env = "PMPROXY_TLS_CERT", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, env = "PMPROXY_TLS_KEY", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Check the certificate and key for changes this often (0 = never reload)
+    #[arg(long, env = "PMPROXY_TLS_RELOAD_SECS", default_value = "60")]
+    tls_reload_secs: u64,
 }
 
 #[tokio::main]
@@ -44,7 +62,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
 
     // Load configuration
-    let config = ProxyConfig::from_env();
+    let mut config = ProxyConfig::from_env();
+    if args.no_auth && config.auth_enabled {
+        warn!("--no-auth overrides PMPROXY_AUTH_ENABLED, authentication disabled");
+        config.auth_enabled = false;
+    }
+
+    let tls = args.tls_cert.zip(args.tls_key).map(|(cert, key)| TlsFiles { cert, key });
+    config.tls_listener = tls.is_some();
 
     // Create state with or without auth
     let state = Arc::new(ProxyState::with_auth(&config)?);
@@ -62,29 +87,121 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let app = build_router(state);
+    state.spawn_background_tasks(&config);
+
+    let app = build_router(state.clone());
 
     let addr = format!("{}:{}", args.host, args.port);
-    info!("pmproxy starting on http://{}", addr);
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!("pmproxy starting on {}://{}", scheme, addr);
     info!("  Routes:");
     info!("    /health   → Health check (no auth)");
+    info!("    /health/ready → Upstream/JWKS readiness (no auth)");
     info!("    /clob/*   → https://clob.polymarket.com/*");
     info!("    /gamma/*  → https://gamma-api.polymarket.com/*");
+    info!("    /data/*   → https://data-api.polymarket.com/*");
+    info!("    /negrisk/* → https://clob.polymarket.com/neg-risk/*");
     info!("    /chain/*  → https://polygon-rpc.com");
     if config.auth_enabled {
         info!("  Authentication: ENABLED (Cognito JWT)");
         info!("    Region: {}", config.cognito_region);
         info!("    Pool ID: {}", config.cognito_pool_id);
         info!("    Rate limits:");
-        info!("      Free: 60 rpm, burst 10");
-        info!("      Pro: 300 rpm, burst 50");
-        info!("      Enterprise: 1000 rpm, burst 100");
+        for tier in state.tiers.snapshot().iter() {
+            let mut quotas = String::new();
+            if let Some(q) = tier.daily_quota {
+                quotas.push_str(&format!(", {} requests/day", q));
+            }
+            if let Some(q) = tier.monthly_quota {
+                quotas.push_str(&format!(", {} requests/month", q));
+            }
+            if let Some(n) = tier.max_concurrent_requests {
+                quotas.push_str(&format!(", {} in flight", n));
+            }
+            info!(
+                "      {}: {} rpm, burst {}{}",
+                tier.name, tier.requests_per_minute, tier.burst_size, quotas
+            );
+        }
+        if let Some(ref path) = config.tiers_file {
+            info!("    Tiers file: {} (hot reload)", path.display());
+        }
+        if let Some(ref path) = config.quota_file {
+            info!("    Quota usage file: {}", path.display());
+        }
     } else {
         info!("  Authentication: DISABLED");
+        if let Some(rpm) = config.ip_rate_limit_rpm {
+            info!(
+                "    Per-IP rate limit: {} rpm, burst {}",
+                rpm, config.ip_rate_limit_burst
+            );
+        }
+    }
+    if !config.ip_access.is_empty() {
+        info!(
+            "  IP rules: {} allowed, {} denied",
+            config.ip_access.allow.len(),
+            config.ip_access.deny.len()
+        );
     }
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    let make_service = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+    if let Some(files) = tls {
+        let tls_config = files.load().await?;
+        if args.tls_reload_secs > 0 {
+            files.spawn_watcher(tls_config.clone(), Duration::from_secs(args.tls_reload_secs));
+        }
+        info!("  TLS: {} (reload every {}s)", files.cert.display(), args.tls_reload_secs);
+
+        let handle = axum_server::Handle::new();
+        let shutdown = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            shutdown.graceful_shutdown(None);
+        });
+        let addr = tokio::net::lookup_host(&addr)
+            .await?
+            .next()
+            .ok_or_else(|| format!("cannot resolve {}", addr))?;
+        axum_server::bind_rustls(addr, tls_config)
+            .handle(handle)
+            .serve(make_service)
+            .await?;
+    } else {
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        axum::serve(listener, make_service)
+            .with_graceful_shutdown(shutdown_signal())
+            .await?;
+    }
+
+    // Save quota usage and burst credits so they survive the restart
+    state.persist();
+    state.export_audit().await;
 
     Ok(())
 }
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutting down");
+}
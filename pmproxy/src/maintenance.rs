@@ -0,0 +1,176 @@
+//! Maintenance mode and per-route kill switches.
+//!
+//! Operators can switch routes off at runtime through `PUT /admin/maintenance`,
+//! e.g. to block order placement during an incident while market data keeps
+//! flowing:
+//!
+//! ```json
+//! {"disabled": [{"path": "/clob/order", "methods": ["POST", "DELETE"],
+//!                "reason": "Exchange incident, order entry paused"}]}
+//! ```
+//!
+//! `read_only: true` rejects every write (anything but GET, HEAD and OPTIONS)
+//! on all routes. Blocked requests get a `503` with a JSON body naming the
+//! reason. The switches are kept in memory only and reset on restart.
+
+use std::sync::RwLock;
+
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::error::{ConfigError, RequestError};
+
+/// A path prefix switched off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisabledRoute {
+    /// Path prefix as seen by clients, e.g. `/clob/order`.
+    pub path: String,
+    /// Methods blocked (empty = all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl DisabledRoute {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        let prefix = self.path.trim_end_matches('/');
+        let path_matches = prefix.is_empty()
+            || path == prefix
+            || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'));
+        path_matches
+            && (self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method.as_str())))
+    }
+}
+
+/// Current maintenance settings (body of `GET`/`PUT /admin/maintenance`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    /// Reject all writes on every route.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Reason reported for read-only rejections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub disabled: Vec<DisabledRoute>,
+}
+
+impl MaintenanceState {
+    fn validate(&self) -> Result<(), ConfigError> {
+        for route in &self.disabled {
+            if !route.path.starts_with('/') {
+                return Err(ConfigError::Invalid(format!(
+                    "disabled path '{}' must start with '/'",
+                    route.path
+                )));
+            }
+            if let Some(bad) = route.methods.iter().find(|m| Method::from_bytes(m.as_bytes()).is_err()) {
+                return Err(ConfigError::Invalid(format!("invalid method '{}'", bad)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any switch is on.
+    pub fn is_active(&self) -> bool {
+        self.read_only || !self.disabled.is_empty()
+    }
+}
+
+/// Runtime kill switches shared by all requests.
+#[derive(Debug, Default)]
+pub struct Maintenance {
+    state: RwLock<MaintenanceState>,
+}
+
+impl Maintenance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current settings.
+    pub fn get(&self) -> MaintenanceState {
+        self.state.read().unwrap().clone()
+    }
+
+    /// Replace the settings.
+    pub fn set(&self, state: MaintenanceState) -> Result<(), ConfigError> {
+        state.validate()?;
+        info!(
+            read_only = state.read_only,
+            disabled = ?state.disabled.iter().map(|r| r.path.as_str()).collect::<Vec<_>>(),
+            "Maintenance settings updated"
+        );
+        *self.state.write().unwrap() = state;
+        Ok(())
+    }
+
+    /// Reject the request if its route is switched off.
+    pub fn check(&self, method: &Method, path: &str) -> Result<(), RequestError> {
+        let state = self.state.read().unwrap();
+        if !state.is_active() {
+            return Ok(());
+        }
+
+        if let Some(route) = state.disabled.iter().find(|r| r.matches(method, path)) {
+            return Err(RequestError::RouteDisabled {
+                path: route.path.clone(),
+                reason: route.reason.clone(),
+            });
+        }
+        let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if state.read_only && !read {
+            return Err(RequestError::RouteDisabled {
+                path: path.to_string(),
+                reason: state.reason.clone().or_else(|| Some("Proxy is in read-only mode".to_string())),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_route_blocks_matching_methods_only() {
+        let maintenance = Maintenance::new();
+        maintenance
+            .set(serde_json::from_str(r#"{"disabled": [{"path": "/clob/order/", "methods": ["post"]}]}"#).unwrap())
+            .unwrap();
+
+        assert!(maintenance.check(&Method::POST, "/clob/order").is_err());
+        assert!(maintenance.check(&Method::POST, "/clob/order/123").is_err());
+        assert!(maintenance.check(&Method::GET, "/clob/order").is_ok());
+        assert!(maintenance.check(&Method::POST, "/clob/orders").is_ok());
+        assert!(maintenance.check(&Method::POST, "/gamma/markets").is_ok());
+
+        assert!(maintenance
+            .set(serde_json::from_str(r#"{"disabled": [{"path": "clob"}]}"#).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_read_only_allows_reads() {
+        let maintenance = Maintenance::new();
+        maintenance
+            .set(MaintenanceState {
+                read_only: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(maintenance.check(&Method::GET, "/clob/book").is_ok());
+        assert!(maintenance.check(&Method::OPTIONS, "/clob/order").is_ok());
+        assert!(matches!(
+            maintenance.check(&Method::DELETE, "/clob/order"),
+            Err(RequestError::RouteDisabled { .. })
+        ));
+
+        maintenance.set(MaintenanceState::default()).unwrap();
+        assert!(maintenance.check(&Method::DELETE, "/clob/order").is_ok());
+    }
+}
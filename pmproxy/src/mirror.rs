@@ -0,0 +1,262 @@
+//! Shadow traffic (request mirroring).
+//!
+//! A route with a `mirror` (see [`crate::routes`]) sends a copy of a share of
+//! its requests to a shadow upstream, e.g. a staging CLOB. Mirroring is
+//! fire-and-forget: the client only ever sees the primary response, the
+//! shadow response is discarded, and when too many shadow requests are in
+//! flight new ones are skipped rather than queued. Per route the proxy counts
+//! how often the shadow returned the same status as the primary and compares
+//! their latency (time to response headers); see `GET /admin/mirror`.
+//!
+//! Shadow requests carry the client's headers minus its credentials (see
+//! [`shadow_headers`]) and use the route's timeouts.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::{header, HeaderMap, StatusCode};
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::sync::{oneshot, Semaphore};
+use tracing::debug;
+
+use crate::routes::Mirror;
+
+/// Default cap on concurrent shadow requests.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+/// Outcome of the primary request, sent to the shadow task for comparison.
+/// `None` if the primary request failed.
+pub type PrimaryOutcome = Option<(StatusCode, Duration)>;
+
+/// Headers for a shadow request: the client's forwarded headers without
+/// credentials (`Authorization`, cookies and the CLOB's `POLY_*` headers).
+pub fn shadow_headers(forwarded: &HeaderMap) -> HeaderMap {
+    let mut headers = forwarded.clone();
+    headers.remove(header::AUTHORIZATION);
+    headers.remove(header::PROXY_AUTHORIZATION);
+    headers.remove(header::COOKIE);
+    let poly: Vec<_> = headers
+        .keys()
+        .filter(|name| name.as_str().starts_with("poly_") || name.as_str().starts_with("poly-"))
+        .cloned()
+        .collect();
+    for name in poly {
+        headers.remove(name);
+    }
+    headers
+}
+
+/// Counters for one route.
+#[derive(Debug, Default)]
+struct RouteStats {
+    /// Requests considered for mirroring.
+    seen: AtomicU64,
+    mirrored: AtomicU64,
+    /// Sampled but dropped because too many shadow requests were in flight.
+    skipped: AtomicU64,
+    /// Shadow requests that failed (connect error, timeout).
+    errors: AtomicU64,
+    status_matches: AtomicU64,
+    status_mismatches: AtomicU64,
+    /// Latency sums over compared pairs (microseconds).
+    primary_us: AtomicU64,
+    shadow_us: AtomicU64,
+}
+
+/// Mirroring statistics of one route.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MirrorReport {
+    pub route: String,
+    pub mirrored: u64,
+    pub skipped: u64,
+    pub errors: u64,
+    pub status_matches: u64,
+    pub status_mismatches: u64,
+    /// Average primary latency over compared requests (ms).
+    pub primary_avg_ms: f64,
+    /// Average shadow latency over compared requests (ms).
+    pub shadow_avg_ms: f64,
+}
+
+/// Samples requests for mirroring and records the comparison.
+pub struct MirrorTracker {
+    stats: DashMap<String, Arc<RouteStats>>,
+    in_flight: Arc<Semaphore>,
+}
+
+impl MirrorTracker {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            stats: DashMap::new(),
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    fn route_stats(&self, route: &str) -> Arc<RouteStats> {
+        self.stats.entry(route.to_string()).or_default().clone()
+    }
+
+    /// Whether to mirror the next request of `route`.
+    ///
+    /// Deterministic: exactly `percent` of requests are picked, evenly spread.
+    pub fn sample(&self, route: &str, mirror: &Mirror) -> bool {
+        let n = self.route_stats(route).seen.fetch_add(1, Ordering::Relaxed) as f64;
+        (((n + 1.0) * mirror.percent / 100.0).floor() - (n * mirror.percent / 100.0).floor()) >= 1.0
+    }
+
+    /// Send `request` to the shadow upstream in the background.
+    ///
+    /// `primary` delivers the primary outcome once known; returns false (and
+    /// counts a skip) if the in-flight cap is reached.
+    pub fn spawn(
+        &self,
+        route: &str,
+        request: reqwest::RequestBuilder,
+        primary: oneshot::Receiver<PrimaryOutcome>,
+    ) -> bool {
+        let stats = self.route_stats(route);
+        let Ok(permit) = self.in_flight.clone().try_acquire_owned() else {
+            stats.skipped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        };
+        stats.mirrored.fetch_add(1, Ordering::Relaxed);
+        let route = route.to_string();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let started = Instant::now();
+            let shadow = match request.send().await {
+                Ok(response) => Some((response.status(), started.elapsed())),
+                Err(e) => {
+                    debug!(route = %route, error = %e, "Shadow request failed");
+                    stats.errors.fetch_add(1, Ordering::Relaxed);
+                    None
+                }
+            };
+
+            // Nothing to compare if either side failed (or the client went away)
+            let (Some((shadow_status, shadow_latency)), Ok(Some((primary_status, primary_latency)))) =
+                (shadow, primary.await)
+            else {
+                return;
+            };
+            if shadow_status == primary_status {
+                stats.status_matches.fetch_add(1, Ordering::Relaxed);
+            } else {
+                debug!(
+                    route = %route,
+                    primary = %primary_status,
+                    shadow = %shadow_status,
+                    "Shadow status differs from primary"
+                );
+                stats.status_mismatches.fetch_add(1, Ordering::Relaxed);
+            }
+            stats
+                .primary_us
+                .fetch_add(primary_latency.as_micros() as u64, Ordering::Relaxed);
+            stats
+                .shadow_us
+                .fetch_add(shadow_latency.as_micros() as u64, Ordering::Relaxed);
+        });
+        true
+    }
+
+    /// Statistics for every route that has been considered for mirroring.
+    pub fn report(&self) -> Vec<MirrorReport> {
+        let mut reports: Vec<MirrorReport> = self
+            .stats
+            .iter()
+            .map(|entry| {
+                let s = entry.value();
+                let matches = s.status_matches.load(Ordering::Relaxed);
+                let mismatches = s.status_mismatches.load(Ordering::Relaxed);
+                let compared = (matches + mismatches).max(1) as f64;
+                MirrorReport {
+                    route: entry.key().clone(),
+                    mirrored: s.mirrored.load(Ordering::Relaxed),
+                    skipped: s.skipped.load(Ordering::Relaxed),
+                    errors: s.errors.load(Ordering::Relaxed),
+                    status_matches: matches,
+                    status_mismatches: mismatches,
+                    primary_avg_ms: s.primary_us.load(Ordering::Relaxed) as f64 / compared / 1000.0,
+                    shadow_avg_ms: s.shadow_us.load(Ordering::Relaxed) as f64 / compared / 1000.0,
+                }
+            })
+            .collect();
+        reports.sort_by(|a, b| a.route.cmp(&b.route));
+        reports
+    }
+}
+
+impl Default for MirrorTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IN_FLIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror(percent: f64) -> Mirror {
+        Mirror {
+            upstream: "http://127.0.0.1:9".to_string(),
+            percent,
+            writes: false,
+        }
+    }
+
+    #[test]
+    fn test_shadow_headers_drop_credentials() {
+        let mut forwarded = HeaderMap::new();
+        for (name, value) in [
+            ("poly_address", "0xabc"),
+            ("poly_signature", "sig"),
+            ("poly_api_key", "key"),
+            ("poly_passphrase", "pass"),
+            ("cookie", "session=1"),
+            ("authorization", "Bearer t"),
+            ("content-type", "application/json"),
+        ] {
+            forwarded.insert(name, value.parse().unwrap());
+        }
+        let shadow = shadow_headers(&forwarded);
+        assert_eq!(shadow.len(), 1);
+        assert_eq!(shadow["content-type"], "application/json");
+    }
+
+    #[test]
+    fn test_sampling_is_exact() {
+        let tracker = MirrorTracker::default();
+        let picked = (0..1000).filter(|_| tracker.sample("clob", &mirror(5.0))).count();
+        assert_eq!(picked, 50);
+
+        assert!((0..100).all(|_| tracker.sample("all", &mirror(100.0))));
+        assert!(!(0..100).any(|_| tracker.sample("none", &mirror(0.0))));
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_cap_and_errors() {
+        let tracker = MirrorTracker::new(1);
+        let client = reqwest::Client::new();
+
+        // Unreachable shadow: counted as an error, never compared
+        let (tx, rx) = oneshot::channel();
+        assert!(tracker.spawn("clob", client.get("http://127.0.0.1:9/book"), rx));
+        let (_tx2, rx2) = oneshot::channel();
+        assert!(!tracker.spawn("clob", client.get("http://127.0.0.1:9/book"), rx2));
+        tx.send(Some((StatusCode::OK, Duration::from_millis(5)))).ok();
+
+        for _ in 0..100 {
+            if tracker.report()[0].errors == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let report = &tracker.report()[0];
+        assert_eq!((report.mirrored, report.skipped, report.errors), (1, 1, 1));
+        assert_eq!(report.status_matches + report.status_mismatches, 0);
+    }
+}
@@ -0,0 +1,154 @@
+//! Sanity checks for CLOB order payloads.
+//!
+//! With `PMPROXY_VALIDATE_ORDERS=true`, `POST /clob/order` bodies are checked
+//! before being forwarded and obviously malformed or fat-fingered orders are
+//! rejected with `422`:
+//!
+//! - `order.tokenId` must be a non-zero uint256 in decimal,
+//! - the implied price must be within 0.001-0.999,
+//! - the size must be greater than zero.
+//!
+//! Signed orders carry amounts rather than a price: a BUY pays `makerAmount`
+//! USDC for `takerAmount` shares, a SELL the reverse. Both use 6 decimals, so
+//! price is the USDC amount over the share amount. Checks use integer
+//! arithmetic on the raw amounts so no precision is lost.
+
+use axum::http::Method;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::RequestError;
+
+/// Route and upstream path of order placement.
+const ORDER_ROUTE: (&str, &str) = ("clob", "order");
+
+/// Price bounds in thousandths (0.001-0.999).
+const MIN_PRICE_MILLIS: u128 = 1;
+const MAX_PRICE_MILLIS: u128 = 999;
+
+/// Longest decimal uint256 (2^256 - 1 has 78 digits).
+const MAX_TOKEN_ID_DIGITS: usize = 78;
+
+#[derive(Debug, Deserialize)]
+struct OrderRequest {
+    order: SignedOrder,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedOrder {
+    token_id: Value,
+    maker_amount: Value,
+    taker_amount: Value,
+    side: Value,
+}
+
+/// Whether a request places an order and should be checked.
+pub fn applies(method: &Method, route: &str, upstream_path: &str) -> bool {
+    *method == Method::POST && (route, upstream_path.trim_end_matches('/')) == ORDER_ROUTE
+}
+
+/// Check an order placement body.
+pub fn validate(body: &[u8]) -> Result<(), RequestError> {
+    let invalid = |message: String| RequestError::InvalidOrder(message);
+
+    let request: OrderRequest =
+        serde_json::from_slice(body).map_err(|e| invalid(format!("malformed order payload: {}", e)))?;
+    let order = request.order;
+
+    let token_id = as_string(&order.token_id).ok_or_else(|| invalid("tokenId must be a string".to_string()))?;
+    if token_id.is_empty()
+        || token_id.len() > MAX_TOKEN_ID_DIGITS
+        || !token_id.bytes().all(|b| b.is_ascii_digit())
+        || token_id.bytes().all(|b| b == b'0')
+    {
+        return Err(invalid(format!("tokenId '{}' is not a valid token id", token_id)));
+    }
+
+    let amount = |field: &str, value: &Value| {
+        as_string(value)
+            .and_then(|s| s.parse::<u128>().ok())
+            .ok_or_else(|| invalid(format!("{} must be a non-negative integer", field)))
+    };
+    let maker = amount("makerAmount", &order.maker_amount)?;
+    let taker = amount("takerAmount", &order.taker_amount)?;
+
+    let buy = match as_string(&order.side).as_deref() {
+        Some(side) if side.eq_ignore_ascii_case("BUY") || side == "0" => true,
+        Some(side) if side.eq_ignore_ascii_case("SELL") || side == "1" => false,
+        _ => return Err(invalid("side must be BUY or SELL".to_string())),
+    };
+    let (usdc, shares) = if buy { (maker, taker) } else { (taker, maker) };
+
+    if shares == 0 {
+        return Err(invalid("size must be greater than zero".to_string()));
+    }
+    // MIN <= usdc / shares * 1000 <= MAX, without dividing
+    let scaled = usdc.saturating_mul(1000);
+    if scaled < MIN_PRICE_MILLIS * shares || scaled > MAX_PRICE_MILLIS.saturating_mul(shares) {
+        return Err(invalid(format!(
+            "price {:.6} is outside 0.001-0.999",
+            usdc as f64 / shares as f64
+        )));
+    }
+    Ok(())
+}
+
+/// A JSON string, or a number rendered as one (amounts and enums appear as both).
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(token_id: &str, maker: &str, taker: &str, side: &str) -> Vec<u8> {
+        serde_json::json!({
+            "order": {
+                "salt": 1,
+                "tokenId": token_id,
+                "makerAmount": maker,
+                "takerAmount": taker,
+                "side": side,
+                "signature": "0xabc",
+            },
+            "owner": "key",
+            "orderType": "GTC",
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_valid_orders_pass() {
+        // BUY 10 shares at 0.55, SELL 10 shares at 0.55
+        let token = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+        assert!(validate(&order(token, "5500000", "10000000", "BUY")).is_ok());
+        assert!(validate(&order("123", "10000000", "5500000", "SELL")).is_ok());
+        assert!(validate(&order("123", "999000", "1000000", "0")).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_and_fat_fingered_orders_are_rejected() {
+        let rejected = |body: Vec<u8>| matches!(validate(&body), Err(RequestError::InvalidOrder(_)));
+
+        assert!(rejected(b"not json".to_vec()));
+        assert!(rejected(order("0x1234", "5500000", "10000000", "BUY")));
+        assert!(rejected(order("0", "5500000", "10000000", "BUY")));
+        assert!(rejected(order("123", "5500000", "0", "BUY")));
+        assert!(rejected(order("123", "-1", "10000000", "BUY")));
+        assert!(rejected(order("123", "5500000", "10000000", "HOLD")));
+        // Price 1.00 and 0.0005
+        assert!(rejected(order("123", "10000000", "10000000", "BUY")));
+        assert!(rejected(order("123", "20000000", "10000", "SELL")));
+
+        assert!(applies(&Method::POST, "clob", "order"));
+        assert!(!applies(&Method::DELETE, "clob", "order"));
+        assert!(!applies(&Method::POST, "clob", "orders"));
+    }
+}
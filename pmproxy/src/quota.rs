@@ -0,0 +1,378 @@
+//! Cumulative per-tenant request quotas (daily and monthly).
+//!
+//! Complements the token-bucket limiter: the bucket bounds request *rate*,
+//! quotas bound request *volume* per UTC day and calendar month. Usage is
+//! optionally persisted to a JSON file so counters survive restarts.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::error::{AuthError, ConfigError};
+use crate::tiers::TierDefinition;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Quota period that was exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPeriod {
+    Daily,
+    Monthly,
+}
+
+impl QuotaPeriod {
+    /// Lowercase name used in error messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaPeriod::Daily => "daily",
+            QuotaPeriod::Monthly => "monthly",
+        }
+    }
+}
+
+/// Request counters for one tenant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantUsage {
+    /// UTC day (days since Unix epoch) the daily counter belongs to.
+    pub day: u64,
+    /// Requests counted during `day`.
+    pub daily: u64,
+    /// Calendar month (`year * 12 + month0`) the monthly counter belongs to.
+    pub month: u64,
+    /// Requests counted during `month`.
+    pub monthly: u64,
+}
+
+impl TenantUsage {
+    /// Reset counters that belong to an earlier day/month.
+    fn roll_over(&mut self, day: u64, month: u64) {
+        if self.day != day {
+            self.day = day;
+            self.daily = 0;
+        }
+        if self.month != month {
+            self.month = month;
+            self.monthly = 0;
+        }
+    }
+}
+
+/// Per-tenant quota tracker.
+pub struct QuotaTracker {
+    usage: DashMap<String, TenantUsage>,
+    /// File to persist usage to (None = in-memory only).
+    store: Option<PathBuf>,
+    /// Whether usage changed since the last flush.
+    dirty: AtomicBool,
+}
+
+impl QuotaTracker {
+    /// Create an in-memory tracker.
+    pub fn new() -> Self {
+        Self {
+            usage: DashMap::new(),
+            store: None,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a tracker persisted to `path`, restoring any saved usage.
+    pub fn with_store(path: PathBuf) -> Self {
+        let tracker = Self {
+            usage: DashMap::new(),
+            store: Some(path),
+            dirty: AtomicBool::new(false),
+        };
+
+        match tracker.load() {
+            Ok(count) => info!(tenants = count, "Restored quota usage"),
+            Err(e) => warn!(error = %e, "Failed to restore quota usage, starting fresh"),
+        }
+
+        tracker
+    }
+
+    /// Count a request against the tenant's quotas.
+    ///
+    /// Returns `AuthError::QuotaExceeded` (without counting the request) if the
+    /// daily or monthly quota of the tier is already used up.
+    pub fn check(&self, tenant_id: &str, tier: &TierDefinition) -> Result<TenantUsage, AuthError> {
+        self.check_at(tenant_id, tier, SystemTime::now())
+    }
+
+    fn check_at(
+        &self,
+        tenant_id: &str,
+        tier: &TierDefinition,
+        now: SystemTime,
+    ) -> Result<TenantUsage, AuthError> {
+        if tier.daily_quota.is_none() && tier.monthly_quota.is_none() {
+            return Ok(TenantUsage::default());
+        }
+
+        let secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let (day, month) = (secs / SECS_PER_DAY, month_index(secs / SECS_PER_DAY));
+
+        let mut usage = self.usage.entry(tenant_id.to_string()).or_default();
+        usage.roll_over(day, month);
+
+        if let Some(limit) = tier.daily_quota {
+            if usage.daily >= limit {
+                debug!(tenant_id = %tenant_id, limit = limit, "Daily quota exceeded");
+                return Err(AuthError::QuotaExceeded {
+                    period: QuotaPeriod::Daily,
+                    retry_after_secs: SECS_PER_DAY - secs % SECS_PER_DAY,
+                });
+            }
+        }
+        if let Some(limit) = tier.monthly_quota {
+            if usage.monthly >= limit {
+                debug!(tenant_id = %tenant_id, limit = limit, "Monthly quota exceeded");
+                return Err(AuthError::QuotaExceeded {
+                    period: QuotaPeriod::Monthly,
+                    retry_after_secs: secs_until_next_month(secs),
+                });
+            }
+        }
+
+        usage.daily += 1;
+        usage.monthly += 1;
+        self.dirty.store(true, Ordering::Relaxed);
+        Ok(usage.clone())
+    }
+
+    /// Current usage for a tenant (counters for past periods read as zero).
+    pub fn usage(&self, tenant_id: &str) -> TenantUsage {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let day = secs / SECS_PER_DAY;
+        let mut usage = self
+            .usage
+            .get(tenant_id)
+            .map(|u| u.clone())
+            .unwrap_or_default();
+        usage.roll_over(day, month_index(day));
+        usage
+    }
+
+    /// Write usage to the store if it changed. No-op without a store.
+    pub fn flush(&self) -> Result<(), ConfigError> {
+        let Some(ref path) = self.store else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let snapshot: HashMap<String, TenantUsage> = self
+            .usage
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| ConfigError::Invalid(format!("quota usage: {}", e)))?;
+
+        // Write-then-rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        let write = std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, path));
+        write.map_err(|e| {
+            self.dirty.store(true, Ordering::Relaxed);
+            ConfigError::Io {
+                path: path.display().to_string(),
+                source: e,
+            }
+        })
+    }
+
+    /// Periodically flush usage to the store.
+    pub fn spawn_flusher(self: &std::sync::Arc<Self>, interval: Duration) {
+        if self.store.is_none() {
+            return;
+        }
+        let tracker = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = tracker.flush() {
+                    warn!(error = %e, "Failed to persist quota usage");
+                }
+            }
+        });
+    }
+
+    fn load(&self) -> Result<usize, ConfigError> {
+        let Some(ref path) = self.store else {
+            return Ok(0);
+        };
+        if !path.exists() {
+            return Ok(0);
+        }
+
+        let usage = read_usage(path)?;
+        let count = usage.len();
+        for (tenant_id, u) in usage {
+            self.usage.insert(tenant_id, u);
+        }
+        Ok(count)
+    }
+}
+
+impl Default for QuotaTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_usage(path: &Path) -> Result<HashMap<String, TenantUsage>, ConfigError> {
+    let json = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    serde_json::from_str(&json).map_err(|e| ConfigError::Invalid(format!("quota usage: {}", e)))
+}
+
+/// Convert days since the Unix epoch to a (year, month 1-12) civil date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+fn civil_year_month(days: u64) -> (u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month)
+}
+
+/// Monotonic month index for a day (`year * 12 + month - 1`).
+fn month_index(days: u64) -> u64 {
+    let (year, month) = civil_year_month(days);
+    year * 12 + month - 1
+}
+
+/// Seconds from `secs` until the first second of the next calendar month.
+fn secs_until_next_month(secs: u64) -> u64 {
+    let month = month_index(secs / SECS_PER_DAY);
+    let mut day = secs / SECS_PER_DAY + 1;
+    while month_index(day) == month {
+        day += 1;
+    }
+    day * SECS_PER_DAY - secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(daily: Option<u64>, monthly: Option<u64>) -> TierDefinition {
+        TierDefinition {
+            name: "test".to_string(),
+            requests_per_minute: 60,
+            burst_size: 10,
+            daily_quota: daily,
+            monthly_quota: monthly,
+            max_concurrent_requests: None,
+            max_queue_wait_ms: None,
+            queue_weight: 1,
+            max_websocket_connections: None,
+            max_subscribed_assets: None,
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_civil_year_month() {
+        assert_eq!(civil_year_month(0), (1970, 1));
+        // 2024-02-29
+        assert_eq!(civil_year_month(19_782), (2024, 2));
+        // 2024-03-01
+        assert_eq!(civil_year_month(19_783), (2024, 3));
+    }
+
+    #[test]
+    fn test_daily_quota_enforced_and_resets() {
+        let tracker = QuotaTracker::new();
+        let tier = tier(Some(3), None);
+        let day0 = 19_783 * SECS_PER_DAY + 100;
+
+        for _ in 0..3 {
+            assert!(tracker.check_at("t1", &tier, at(day0)).is_ok());
+        }
+        match tracker.check_at("t1", &tier, at(day0)) {
+            Err(AuthError::QuotaExceeded {
+                period,
+                retry_after_secs,
+            }) => {
+                assert_eq!(period, QuotaPeriod::Daily);
+                assert_eq!(retry_after_secs, SECS_PER_DAY - 100);
+            }
+            other => panic!("expected quota error, got {:?}", other),
+        }
+
+        // Other tenants are unaffected
+        assert!(tracker.check_at("t2", &tier, at(day0)).is_ok());
+
+        // Next UTC day starts fresh
+        assert!(tracker
+            .check_at("t1", &tier, at(day0 + SECS_PER_DAY))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_monthly_quota_spans_days() {
+        let tracker = QuotaTracker::new();
+        let tier = tier(None, Some(2));
+        let day0 = 19_783 * SECS_PER_DAY;
+
+        assert!(tracker.check_at("t1", &tier, at(day0)).is_ok());
+        assert!(tracker
+            .check_at("t1", &tier, at(day0 + SECS_PER_DAY))
+            .is_ok());
+        assert!(matches!(
+            tracker.check_at("t1", &tier, at(day0 + 2 * SECS_PER_DAY)),
+            Err(AuthError::QuotaExceeded {
+                period: QuotaPeriod::Monthly,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unlimited_tier_is_not_tracked() {
+        let tracker = QuotaTracker::new();
+        assert!(tracker.check("t1", &tier(None, None)).is_ok());
+        assert!(tracker.usage.is_empty());
+    }
+
+    #[test]
+    fn test_usage_persists_across_restarts() {
+        let path = std::env::temp_dir().join(format!("pmproxy-quota-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let tier = tier(Some(2), None);
+
+        let tracker = QuotaTracker::with_store(path.clone());
+        assert!(tracker.check("t1", &tier).is_ok());
+        assert!(tracker.check("t1", &tier).is_ok());
+        tracker.flush().unwrap();
+
+        let restored = QuotaTracker::with_store(path.clone());
+        assert_eq!(restored.usage("t1").daily, 2);
+        assert!(restored.check("t1", &tier).is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -1,52 +1,101 @@
 //! Per-tenant rate limiting using token bucket algorithm.
+//!
+//! Requests over the bucket may still pass by spending a burst credit, see
+//! [`crate::credits`].
+//!
+//! Tiers with `max_queue_wait_ms` (see [`crate::tiers`]) don't reject a
+//! request that is only slightly over the limit: if the bucket refills within
+//! that wait, the request is held until it does. Waiting requests share one
+//! bounded queue (`PMPROXY_RATE_LIMIT_QUEUE_SIZE`), split between the tenants
+//! currently waiting in proportion to their tier's `queue_weight`, so one
+//! bursty tenant cannot take every slot from the others.
 
+use std::collections::HashMap;
 use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use governor::{
-    clock::DefaultClock,
+    clock::{Clock, DefaultClock},
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
 use tracing::debug;
 
 use crate::config::{ProxyConfig, TenantTier};
+use crate::credits::CreditLedger;
 use crate::error::AuthError;
+use crate::tiers::TierRegistry;
 
 /// Rate limiter state for a single tenant.
 type TenantLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
 
+/// A tenant's limiter together with the limits it was built from.
+struct TenantEntry {
+    limiter: Arc<TenantLimiter>,
+    rpm: u32,
+    burst: u32,
+    /// Last check, in milliseconds since the limiter's epoch
+    last_access: AtomicU64,
+}
+
 /// Per-tenant rate limiter.
 ///
-/// Each tenant gets their own token bucket based on their tier.
+/// Each tenant gets their own token bucket based on their tier. Limits come
+/// only from the tier table; a tenant of an unknown tier gets the default tier.
 pub struct TenantRateLimiter {
     /// Map of tenant_id -> rate limiter.
-    limiters: DashMap<String, Arc<TenantLimiter>>,
-    /// Default config for fallback limits.
-    #[allow(dead_code)]
-    config: ProxyConfig,
+    limiters: DashMap<String, TenantEntry>,
+    /// Reference point for `TenantEntry::last_access`.
+    epoch: Instant,
+    /// Tier definitions (hot-reloadable).
+    tiers: TierRegistry,
+    /// Burst credits spent once a tenant's bucket is empty.
+    credits: Arc<CreditLedger>,
+    /// Over-limit requests waiting for their bucket.
+    queue: FairQueue,
 }
 
 impl TenantRateLimiter {
     /// Create a new per-tenant rate limiter.
-    pub fn new(config: &ProxyConfig) -> Self {
+    pub fn new(config: &ProxyConfig, tiers: TierRegistry) -> Self {
         Self {
             limiters: DashMap::new(),
-            config: config.clone(),
+            epoch: Instant::now(),
+            tiers,
+            credits: Arc::new(CreditLedger::new()),
+            queue: FairQueue::new(config.rate_limit_queue_size),
         }
     }
 
+    /// Use a shared burst-credit ledger (e.g. one managed by the admin API).
+    pub fn with_credits(mut self, credits: Arc<CreditLedger>) -> Self {
+        self.credits = credits;
+        self
+    }
+
     /// Get or create a rate limiter for a tenant.
+    ///
+    /// If the tenant's tier limits changed (tier change or table reload), the
+    /// limiter is rebuilt with the new quota.
     fn get_or_create(&self, tenant_id: &str, tier: TenantTier) -> Arc<TenantLimiter> {
-        // Check if we already have a limiter for this tenant
-        if let Some(limiter) = self.limiters.get(tenant_id) {
-            return limiter.clone();
+        let table = self.tiers.snapshot();
+        let definition = table.get(tier);
+        let rpm = definition.requests_per_minute;
+        let burst = definition.burst_size;
+        let now = self.millis_since_epoch(Instant::now());
+
+        // Check if we already have an up-to-date limiter for this tenant
+        if let Some(entry) = self.limiters.get(tenant_id) {
+            if entry.rpm == rpm && entry.burst == burst {
+                entry.last_access.store(now, Ordering::Relaxed);
+                return entry.limiter.clone();
+            }
         }
 
         // Create a new limiter for this tenant
-        let rpm = tier.requests_per_minute();
-        let burst = tier.burst_size();
 
         // Convert to quota: rpm requests per 60 seconds
         // Use burst as the initial capacity
@@ -57,22 +106,41 @@ impl TenantRateLimiter {
 
         debug!(
             tenant_id = %tenant_id,
-            tier = ?tier,
+            tier = %definition.name,
             rpm = rpm,
             burst = burst,
             "Created rate limiter for tenant"
         );
 
         // Insert and return (handle race condition by checking again)
-        self.limiters
+        let mut entry = self
+            .limiters
             .entry(tenant_id.to_string())
-            .or_insert(limiter)
-            .clone()
+            .or_insert_with(|| TenantEntry {
+                limiter: limiter.clone(),
+                rpm,
+                burst,
+                last_access: AtomicU64::new(now),
+            });
+        if entry.rpm != rpm || entry.burst != burst {
+            *entry = TenantEntry {
+                limiter,
+                rpm,
+                burst,
+                last_access: AtomicU64::new(now),
+            };
+        }
+        entry.limiter.clone()
+    }
+
+    fn millis_since_epoch(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.epoch).as_millis() as u64
     }
 
     /// Check if a request should be allowed.
     ///
-    /// Returns Ok(()) if allowed, Err(AuthError::RateLimited) if rejected.
+    /// Returns Ok(()) if allowed (by the bucket or a burst credit),
+    /// Err(AuthError::RateLimited) if rejected.
     pub fn check(&self, tenant_id: &str, tier: TenantTier) -> Result<(), AuthError> {
         let limiter = self.get_or_create(tenant_id, tier);
 
@@ -81,6 +149,10 @@ impl TenantRateLimiter {
                 debug!(tenant_id = %tenant_id, "Rate limit check passed");
                 Ok(())
             }
+            Err(_) if self.credits.consume(tenant_id) => {
+                debug!(tenant_id = %tenant_id, "Rate limit exceeded, spent burst credit");
+                Ok(())
+            }
             Err(_) => {
                 debug!(tenant_id = %tenant_id, tier = ?tier, "Rate limit exceeded");
                 Err(AuthError::RateLimited)
@@ -88,97 +160,202 @@ impl TenantRateLimiter {
         }
     }
 
+    /// Like [`check`](Self::check), but an over-limit request of a tier with
+    /// `max_queue_wait_ms` waits for the bucket if it refills in time and the
+    /// tenant has room in the queue.
+    pub async fn acquire(&self, tenant_id: &str, tier: TenantTier) -> Result<(), AuthError> {
+        let limiter = self.get_or_create(tenant_id, tier);
+
+        let wait = match limiter.check() {
+            Ok(_) => return Ok(()),
+            Err(not_until) => not_until.wait_time_from(DefaultClock::default().now()),
+        };
+        if self.credits.consume(tenant_id) {
+            debug!(tenant_id = %tenant_id, "Rate limit exceeded, spent burst credit");
+            return Ok(());
+        }
+
+        let (max_wait, weight) = {
+            let table = self.tiers.snapshot();
+            let definition = table.get(tier);
+            (definition.max_queue_wait_ms.map(Duration::from_millis), definition.queue_weight)
+        };
+        let Some(max_wait) = max_wait.filter(|max| wait <= *max) else {
+            let wait_ms = wait.as_millis() as u64;
+            debug!(tenant_id = %tenant_id, tier = ?tier, wait_ms = wait_ms, "Rate limit exceeded");
+            return Err(AuthError::RateLimited);
+        };
+        let Some(_ticket) = self.queue.enter(tenant_id, weight) else {
+            debug!(tenant_id = %tenant_id, tier = ?tier, "Rate limit exceeded, no room in the wait queue");
+            return Err(AuthError::RateLimited);
+        };
+
+        debug!(tenant_id = %tenant_id, wait_ms = wait.as_millis() as u64, "Rate limit exceeded, queueing");
+        tokio::time::timeout(max_wait, limiter.until_ready())
+            .await
+            .map_err(|_| AuthError::RateLimited)
+    }
+
+    /// Requests currently waiting for their bucket (for monitoring).
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
     /// Get the number of active tenant limiters (for monitoring).
     pub fn tenant_count(&self) -> usize {
         self.limiters.len()
     }
 
-    /// Clean up stale limiters (tenants that haven't made requests in a while).
+    /// Evict limiters of tenants idle for longer than `idle_ttl`, then the
+    /// least recently used ones until at most `max_tenants` remain.
     ///
-    /// This can be called periodically to prevent unbounded memory growth.
-    /// In practice, governor's internal state is very lightweight.
-    pub fn cleanup_stale(&self, max_tenants: usize) {
+    /// An evicted tenant starts over with a full bucket, so `idle_ttl` should
+    /// be at least the time a bucket takes to refill. Returns the number of
+    /// limiters evicted.
+    pub fn cleanup_stale(&self, idle_ttl: Duration, max_tenants: usize) -> usize {
+        self.cleanup_stale_at(Instant::now(), idle_ttl, max_tenants)
+    }
+
+    fn cleanup_stale_at(&self, now: Instant, idle_ttl: Duration, max_tenants: usize) -> usize {
+        let before = self.limiters.len();
+        let cutoff = self
+            .millis_since_epoch(now)
+            .saturating_sub(idle_ttl.as_millis() as u64);
+        self.limiters
+            .retain(|_, entry| entry.last_access.load(Ordering::Relaxed) >= cutoff);
+
         if self.limiters.len() > max_tenants {
-            // Simple strategy: remove half the entries
-            // A more sophisticated approach would track last-access time
-            let to_remove: Vec<String> = self
+            let mut by_access: Vec<(u64, String)> = self
                 .limiters
                 .iter()
-                .take(self.limiters.len() / 2)
-                .map(|entry| entry.key().clone())
+                .map(|entry| (entry.last_access.load(Ordering::Relaxed), entry.key().clone()))
                 .collect();
-
-            for key in to_remove {
+            by_access.sort_unstable();
+            let excess = by_access.len() - max_tenants;
+            for (_, key) in by_access.into_iter().take(excess) {
                 self.limiters.remove(&key);
             }
+        }
 
+        let evicted = before.saturating_sub(self.limiters.len());
+        if evicted > 0 {
             debug!(
+                evicted = evicted,
                 remaining = self.limiters.len(),
-                "Cleaned up stale rate limiters"
+                "Evicted idle rate limiters"
             );
         }
+        evicted
+    }
+}
+
+/// Bounded queue shared by over-limit requests, split fairly between tenants.
+struct FairQueue {
+    capacity: usize,
+    /// Waiting requests and queue weight per tenant.
+    waiting: Mutex<HashMap<String, (usize, u32)>>,
+}
+
+impl FairQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            waiting: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a place in the queue, if it has room and the tenant is within its
+    /// weighted share of it. The place is released when the ticket drops.
+    fn enter(&self, tenant_id: &str, weight: u32) -> Option<QueueTicket<'_>> {
+        let weight = weight.max(1);
+        let mut waiting = self.waiting.lock().unwrap();
+        if waiting.values().map(|(count, _)| count).sum::<usize>() >= self.capacity {
+            return None;
+        }
+
+        let total_weight: u64 = waiting
+            .iter()
+            .filter(|(tenant, _)| tenant.as_str() != tenant_id)
+            .map(|(_, (_, w))| u64::from(*w))
+            .sum::<u64>()
+            + u64::from(weight);
+        let share = (self.capacity as u64 * u64::from(weight) / total_weight).max(1) as usize;
+        let entry = waiting.entry(tenant_id.to_string()).or_insert((0, weight));
+        if entry.0 >= share {
+            return None;
+        }
+        *entry = (entry.0 + 1, weight);
+
+        Some(QueueTicket {
+            queue: self,
+            tenant_id: tenant_id.to_string(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.waiting.lock().unwrap().values().map(|(count, _)| count).sum()
+    }
+}
+
+/// A place in the [`FairQueue`].
+struct QueueTicket<'a> {
+    queue: &'a FairQueue,
+    tenant_id: String,
+}
+
+impl Drop for QueueTicket<'_> {
+    fn drop(&mut self) {
+        let mut waiting = self.queue.waiting.lock().unwrap();
+        if let Some(entry) = waiting.get_mut(&self.tenant_id) {
+            entry.0 -= 1;
+            if entry.0 == 0 {
+                waiting.remove(&self.tenant_id);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tiers::TierTable;
 
     #[test]
     fn test_rate_limiter_creation() {
-        let config = ProxyConfig {
-            auth_enabled: true,
-            cognito_region: "us-east-1".to_string(),
-            cognito_pool_id: "test".to_string(),
-            cognito_client_id: None,
-            rate_limit_rpm: 100,
-            rate_limit_burst: 20,
-        };
+        let config = ProxyConfig::for_tests(true);
 
-        let limiter = TenantRateLimiter::new(&config);
+        let limiter = TenantRateLimiter::new(&config, TierRegistry::default());
         assert_eq!(limiter.tenant_count(), 0);
     }
 
     #[test]
     fn test_rate_limiter_allows_requests() {
-        let config = ProxyConfig {
-            auth_enabled: true,
-            cognito_region: "us-east-1".to_string(),
-            cognito_pool_id: "test".to_string(),
-            cognito_client_id: None,
-            rate_limit_rpm: 100,
-            rate_limit_burst: 20,
-        };
+        let config = ProxyConfig::for_tests(true);
+        let tiers = TierRegistry::default();
+        let pro = tiers.snapshot().resolve("pro");
 
-        let limiter = TenantRateLimiter::new(&config);
+        let limiter = TenantRateLimiter::new(&config, tiers);
 
         // First request should always succeed
-        assert!(limiter.check("tenant-1", TenantTier::Free).is_ok());
+        assert!(limiter.check("tenant-1", TenantTier::DEFAULT).is_ok());
         assert_eq!(limiter.tenant_count(), 1);
 
         // Multiple tenants should get separate limiters
-        assert!(limiter.check("tenant-2", TenantTier::Pro).is_ok());
+        assert!(limiter.check("tenant-2", pro).is_ok());
         assert_eq!(limiter.tenant_count(), 2);
     }
 
     #[test]
     fn test_rate_limiter_burst() {
-        let config = ProxyConfig {
-            auth_enabled: true,
-            cognito_region: "us-east-1".to_string(),
-            cognito_pool_id: "test".to_string(),
-            cognito_client_id: None,
-            rate_limit_rpm: 60, // 1 per second
-            rate_limit_burst: 5,
-        };
+        let config = ProxyConfig::for_tests(true);
 
-        let limiter = TenantRateLimiter::new(&config);
+        let limiter = TenantRateLimiter::new(&config, TierRegistry::default());
 
         // Should allow burst of requests up to burst size
         // Note: The Free tier has burst of 10, so we test with that
         for i in 0..10 {
             assert!(
-                limiter.check("burst-tenant", TenantTier::Free).is_ok(),
+                limiter.check("burst-tenant", TenantTier::DEFAULT).is_ok(),
                 "Request {} should succeed",
                 i
             );
@@ -186,6 +363,123 @@ mod tests {
 
         // After exhausting burst, subsequent requests should be rate limited
         // (assuming no time has passed to replenish tokens)
-        assert!(limiter.check("burst-tenant", TenantTier::Free).is_err());
+        assert!(limiter.check("burst-tenant", TenantTier::DEFAULT).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_spends_burst_credits() {
+        let config = ProxyConfig::for_tests(true);
+        let credits = Arc::new(CreditLedger::new());
+        let limiter =
+            TenantRateLimiter::new(&config, TierRegistry::default()).with_credits(credits.clone());
+
+        for _ in 0..10 {
+            assert!(limiter.check("batch-tenant", TenantTier::DEFAULT).is_ok());
+        }
+        assert!(limiter.check("batch-tenant", TenantTier::DEFAULT).is_err());
+
+        credits.grant("batch-tenant", 2, std::time::Duration::from_secs(3600));
+        assert!(limiter.check("batch-tenant", TenantTier::DEFAULT).is_ok());
+        assert!(limiter.check("batch-tenant", TenantTier::DEFAULT).is_ok());
+        assert!(limiter.check("batch-tenant", TenantTier::DEFAULT).is_err());
+        assert_eq!(credits.balance("batch-tenant").remaining, 0);
+    }
+
+    #[test]
+    fn test_rate_limiter_applies_reloaded_limits() {
+        let config = ProxyConfig::for_tests(true);
+        let tiers = TierRegistry::default();
+        let limiter = TenantRateLimiter::new(&config, tiers.clone());
+
+        for _ in 0..10 {
+            assert!(limiter.check("reload-tenant", TenantTier::DEFAULT).is_ok());
+        }
+        assert!(limiter.check("reload-tenant", TenantTier::DEFAULT).is_err());
+
+        // Raising the burst rebuilds the tenant's bucket with the new quota
+        tiers.replace(
+            TierTable::from_json(r#"[{"name": "free", "rpm": 60, "burst": 20}]"#).unwrap(),
+        );
+        assert!(limiter.check("reload-tenant", TenantTier::DEFAULT).is_ok());
+    }
+
+    #[test]
+    fn test_cleanup_evicts_idle_then_least_recently_used() {
+        let config = ProxyConfig::for_tests(true);
+        let limiter = TenantRateLimiter::new(&config, TierRegistry::default());
+        for tenant in ["idle", "warm", "busy"] {
+            assert!(limiter.check(tenant, TenantTier::DEFAULT).is_ok());
+        }
+        let set_access = |tenant: &str, secs: u64| {
+            limiter
+                .limiters
+                .get(tenant)
+                .unwrap()
+                .last_access
+                .store(secs * 1000, Ordering::Relaxed);
+        };
+        set_access("idle", 0);
+        set_access("warm", 500);
+        set_access("busy", 590);
+        let now = limiter.epoch + Duration::from_secs(600);
+
+        // Only the tenant idle for over 5 minutes goes
+        assert_eq!(limiter.cleanup_stale_at(now, Duration::from_secs(300), 10), 1);
+        assert!(!limiter.limiters.contains_key("idle"));
+        assert_eq!(limiter.tenant_count(), 2);
+
+        // Over the cap, the least recently used goes first
+        assert_eq!(limiter.cleanup_stale_at(now, Duration::from_secs(300), 1), 1);
+        assert!(limiter.limiters.contains_key("busy"));
+    }
+
+    #[tokio::test]
+    async fn test_over_limit_requests_wait_for_the_bucket() {
+        let config = ProxyConfig::for_tests(true);
+        // 600 rpm refills a request every 100ms
+        let tiers = TierRegistry::new(
+            TierTable::from_json(
+                r#"[{"name": "free", "rpm": 600, "burst": 1},
+                    {"name": "pro", "rpm": 600, "burst": 1, "max_queue_wait_ms": 500}]"#,
+            )
+            .unwrap(),
+        );
+        let pro = tiers.snapshot().resolve("pro");
+        let limiter = TenantRateLimiter::new(&config, tiers);
+
+        // Without a queue wait the tier is rejected at once
+        assert!(limiter.acquire("free-tenant", TenantTier::DEFAULT).await.is_ok());
+        assert!(limiter.acquire("free-tenant", TenantTier::DEFAULT).await.is_err());
+
+        assert!(limiter.acquire("pro-tenant", pro).await.is_ok());
+        let started = Instant::now();
+        assert!(limiter.acquire("pro-tenant", pro).await.is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(limiter.queued(), 0);
+    }
+
+    #[test]
+    fn test_queue_is_shared_by_weight() {
+        let queue = FairQueue::new(6);
+
+        let bursty: Vec<_> = (0..2).filter_map(|_| queue.enter("bursty", 1)).collect();
+        assert_eq!(bursty.len(), 2);
+
+        // Against the bursty tenant, a weight-2 tenant's share is 6 * 2 / 3 = 4
+        let mut heavy: Vec<_> = (0..5).filter_map(|_| queue.enter("heavy", 2)).collect();
+        assert_eq!(heavy.len(), 4);
+        assert_eq!(queue.len(), 6);
+
+        // With a place free, the bursty tenant is at its share of 2 and cannot take it
+        heavy.pop();
+        assert!(queue.enter("bursty", 1).is_none());
+        assert!(queue.enter("heavy", 2).is_some());
+
+        drop(heavy);
+        // Alone again, the bursty tenant may use the whole queue
+        let more: Vec<_> = (0..4).filter_map(|_| queue.enter("bursty", 1)).collect();
+        assert_eq!(more.len(), 4);
+        drop((bursty, more));
+        assert_eq!(queue.len(), 0);
     }
 }
@@ -0,0 +1,67 @@
+//! Request IDs for correlating client errors with proxy and upstream logs.
+//!
+//! A client-supplied `X-Request-Id` is kept if it is short and printable;
+//! otherwise the proxy generates one. The ID is forwarded upstream, returned
+//! on every response, and included in JSON error bodies.
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::error::{with_request_id, REQUEST_ID_HEADER};
+use crate::sigv4::hex;
+
+/// Longest client-supplied request ID that is kept.
+const MAX_LEN: usize = 128;
+
+/// The client's request ID, if it is usable.
+pub fn from_headers(headers: &HeaderMap) -> Option<String> {
+    let id = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?.trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b));
+    valid.then(|| id.to_string())
+}
+
+/// A random 128-bit request ID (hex).
+pub fn generate() -> String {
+    let mut bytes = [0u8; 16];
+    // SystemRandom only fails if the OS RNG is unavailable
+    SystemRandom::new().fill(&mut bytes).ok();
+    hex(&bytes)
+}
+
+/// Assign the request ID before any handler runs and tag the response with it.
+pub async fn middleware(mut req: Request, next: Next) -> Response {
+    let id = from_headers(req.headers()).unwrap_or_else(generate);
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        req.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    with_request_id(next.run(req).await, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_request_id_is_validated() {
+        let headers = |id: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(REQUEST_ID_HEADER, id.parse().unwrap());
+            headers
+        };
+        assert_eq!(from_headers(&headers("trace-1:abc_2.3")).as_deref(), Some("trace-1:abc_2.3"));
+        assert_eq!(from_headers(&headers("has space")), None);
+        assert_eq!(from_headers(&headers(&"x".repeat(129))), None);
+        assert_eq!(from_headers(&HeaderMap::new()), None);
+
+        let id = generate();
+        assert_eq!(id.len(), 32);
+        assert_ne!(id, generate());
+    }
+}
@@ -0,0 +1,835 @@
+//! Upstream route table with per-route header rules.
+//!
+//! Routes are loaded from `PMPROXY_ROUTES_FILE` (JSON file) or
+//! `PMPROXY_ROUTES` (inline JSON). When neither is set the built-in
+//! clob/gamma/data/negrisk/chain table is used: `data` is Polymarket's
+//! data-api (positions, trade history), which is public and never sees the
+//! client's CLOB API credentials, and `negrisk` is the CLOB's neg-risk lookup
+//! (`/negrisk?token_id=...` → `https://clob.polymarket.com/neg-risk?token_id=...`).
+//!
+//! ```json
+//! [
+//!   {"prefix": "clob", "upstream": "https://clob.polymarket.com",
+//!    "headers": [{"action": "remove", "name": "cookie"}]},
+//!   {"prefix": "private", "upstream": "https://api.example.com",
+//!    "headers": [
+//!      {"action": "set", "name": "x-api-key", "value": "${PRIVATE_API_KEY}"},
+//!      {"action": "set", "name": "accept-encoding", "value": "identity"},
+//!      {"action": "remove", "name": "set-cookie", "on": "response"}
+//!    ]}
+//! ]
+//! ```
+//!
+//! A request for `/{prefix}/rest` is forwarded to `{upstream}/rest`. Header
+//! values may reference environment variables as `${VAR}`, resolved when the
+//! table is loaded, so secrets stay out of the route file.
+//!
+//! A route may authenticate the proxy itself to its upstream (`"auth"`, see
+//! [`crate::upstream_auth`]) and mirror a share of its requests to a shadow
+//! upstream, see [`crate::mirror`]. Only safe methods (GET, HEAD, OPTIONS)
+//! are mirrored unless the mirror sets `"writes": true`:
+//!
+//! ```json
+//! {"prefix": "clob", "upstream": "https://clob.polymarket.com",
+//!  "mirror": {"upstream": "https://clob-staging.example.com", "percent": 5}}
+//! ```
+//!
+//! `canaries` send a weighted share of a route's traffic to alternative
+//! upstreams instead of `upstream`. The choice is sticky per tenant (or per
+//! client IP without auth), so a tenant stays on one upstream for the whole
+//! canary:
+//!
+//! ```json
+//! {"prefix": "gamma", "upstream": "https://gamma-api.polymarket.com",
+//!  "canaries": [{"upstream": "https://gamma-mirror.example.com", "percent": 5}]}
+//! ```
+//!
+//! `timeouts` override the proxy's 30s upstream timeout for a route, so order
+//! placement can fail fast while slow chain calls get more time. `connect_ms`
+//! bounds connection setup, `read_ms` the wait for each read and `total_ms` the
+//! whole request (the route's latency budget); a request that runs out of time
+//! gets `504`:
+//!
+//! ```json
+//! {"prefix": "clob", "upstream": "https://clob.polymarket.com",
+//!  "timeouts": {"connect_ms": 500, "total_ms": 3000}}
+//! ```
+//!
+//! `authorize` rules restrict a route to tenants in certain Cognito groups
+//! (`cognito:groups` claim). The first rule matching the request's method and
+//! upstream path applies; the tenant must be in at least one of its groups or
+//! gets `403`. Without authentication there is no tenant, so matching requests
+//! are always refused. Requests no rule matches are allowed:
+//!
+//! ```json
+//! {"prefix": "clob", "upstream": "https://clob.polymarket.com",
+//!  "authorize": [{"methods": ["POST", "DELETE"], "path": "order", "groups": ["traders"]}]}
+//! ```
+//!
+//! HTTP/2 is negotiated automatically with TLS upstreams that offer it.
+//! `"http2_prior_knowledge": true` skips the negotiation and speaks HTTP/2
+//! from the first byte; only set it for upstreams known to support HTTP/2.
+//!
+//! `harden` cleans up responses from upstreams not meant to face clients
+//! directly (cookies, server banners, missing security headers), see
+//! [`crate::harden`].
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ConfigError;
+use crate::harden::ResponseHardening;
+use crate::upstream_auth::{UpstreamAuth, UpstreamAuthDefinition};
+
+/// CLOB L2 credentials dropped from requests to the public `data` route.
+const PUBLIC_ROUTE_STRIPPED_HEADERS: &[&str] = &["poly_api_key", "poly_passphrase", "poly_signature"];
+
+/// Which side of the exchange a header rule applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Headers forwarded to the upstream.
+    #[default]
+    Request,
+    /// Headers returned to the client.
+    Response,
+}
+
+/// What a header rule does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum HeaderAction {
+    /// Replace any existing values with `value`.
+    Set { name: String, value: String },
+    /// Add `value` alongside any existing values.
+    Append { name: String, value: String },
+    /// Drop the header.
+    Remove { name: String },
+    /// Move the header's values to a new name.
+    Rename { from: String, to: String },
+}
+
+/// A header rule as written in the route file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderRuleDefinition {
+    #[serde(flatten)]
+    pub action: HeaderAction,
+    #[serde(default)]
+    pub on: Direction,
+}
+
+/// A route as written in the route file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteDefinition {
+    /// First path segment, without slashes (e.g. `clob`).
+    pub prefix: String,
+    /// Upstream base URL, without a trailing slash.
+    pub upstream: String,
+    /// Header rules, applied in order.
+    #[serde(default)]
+    pub headers: Vec<HeaderRuleDefinition>,
+    /// Optional shadow upstream receiving a copy of some requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorDefinition>,
+    /// Optional credentials the proxy presents to the upstream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<UpstreamAuthDefinition>,
+    /// Alternative upstreams taking a weighted share of the traffic.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub canaries: Vec<CanaryDefinition>,
+    /// Optional upstream timeouts replacing the proxy-wide default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<TimeoutDefinition>,
+    /// Speak HTTP/2 to the upstream without negotiating it first.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http2_prior_knowledge: bool,
+    /// Cognito groups required for some or all requests, first match wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authorize: Vec<AuthorizationRuleDefinition>,
+    /// Optional cleanup of the upstream's response headers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub harden: Option<ResponseHardening>,
+}
+
+/// An authorization rule as written in the route file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationRuleDefinition {
+    /// Methods the rule applies to (empty = all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    /// Upstream path prefix the rule applies to (None = the whole route).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Groups allowed through; the tenant needs at least one.
+    pub groups: Vec<String>,
+}
+
+/// Upstream timeouts of a route, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeoutDefinition {
+    /// Connection setup (TCP and TLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_ms: Option<u64>,
+    /// Wait for each read of the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_ms: Option<u64>,
+    /// Whole request, from connecting to the end of the response body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_ms: Option<u64>,
+}
+
+/// An alternative upstream and its share of a route's traffic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanaryDefinition {
+    pub upstream: String,
+    /// Share of requests, 0-100.
+    pub percent: f64,
+}
+
+/// Shadow traffic settings of a route.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MirrorDefinition {
+    /// Shadow upstream base URL.
+    pub upstream: String,
+    /// Share of requests to mirror, 0-100 (required).
+    pub percent: f64,
+    /// Also mirror unsafe methods (POST, PUT, DELETE, ...). Off by default so
+    /// orders and cancels are never replayed against the shadow.
+    #[serde(default)]
+    pub writes: bool,
+}
+
+/// A validated header rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HeaderRule {
+    Set(HeaderName, HeaderValue),
+    Append(HeaderName, HeaderValue),
+    Remove(HeaderName),
+    Rename(HeaderName, HeaderName),
+}
+
+impl HeaderRule {
+    fn apply(&self, headers: &mut HeaderMap) {
+        match self {
+            HeaderRule::Set(name, value) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            HeaderRule::Append(name, value) => {
+                headers.append(name.clone(), value.clone());
+            }
+            HeaderRule::Remove(name) => {
+                headers.remove(name);
+            }
+            HeaderRule::Rename(from, to) => {
+                let values: Vec<HeaderValue> = headers.get_all(from).iter().cloned().collect();
+                headers.remove(from);
+                for value in values {
+                    headers.append(to.clone(), value);
+                }
+            }
+        }
+    }
+}
+
+/// A validated authorization rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AuthorizationRule {
+    methods: Vec<Method>,
+    path: Option<String>,
+    groups: Vec<String>,
+}
+
+impl AuthorizationRule {
+    fn matches(&self, method: &Method, upstream_path: &str) -> bool {
+        let method_matches = self.methods.is_empty() || self.methods.contains(method);
+        let path_matches = self.path.as_deref().is_none_or(|prefix| {
+            let path = upstream_path.trim_matches('/');
+            path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+        });
+        method_matches && path_matches
+    }
+}
+
+/// A validated shadow upstream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mirror {
+    pub upstream: String,
+    pub percent: f64,
+    pub writes: bool,
+}
+
+impl Mirror {
+    /// Whether requests with `method` may be mirrored at all.
+    pub fn mirrors(&self, method: &Method) -> bool {
+        self.writes || method.is_safe()
+    }
+}
+
+/// Validated upstream timeouts of a route (None = proxy default).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RouteTimeouts {
+    pub connect: Option<Duration>,
+    pub read: Option<Duration>,
+    pub total: Option<Duration>,
+}
+
+impl RouteTimeouts {
+    /// Whether requests need a client of their own (connect and read
+    /// timeouts are client settings; the total can be set per request).
+    pub fn needs_client(&self) -> bool {
+        self.connect.is_some() || self.read.is_some()
+    }
+}
+
+/// Resolution of the weighted upstream choice (basis points).
+const WEIGHT_SCALE: u64 = 10_000;
+
+/// Spreads requests without a sticky key across the weights.
+static UNKEYED_REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+/// A validated route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub prefix: String,
+    pub upstream: String,
+    pub mirror: Option<Mirror>,
+    pub auth: Option<UpstreamAuth>,
+    pub timeouts: RouteTimeouts,
+    pub http2_prior_knowledge: bool,
+    pub harden: Option<ResponseHardening>,
+    authorize: Vec<AuthorizationRule>,
+    /// Canary upstreams with their cumulative upper bound in basis points.
+    canaries: Vec<(String, u64)>,
+    request_rules: Vec<HeaderRule>,
+    response_rules: Vec<HeaderRule>,
+}
+
+impl Route {
+    /// Upstream base URL for a request.
+    ///
+    /// With canaries, `sticky_key` (tenant ID or client IP) is hashed so the
+    /// same key always gets the same upstream; without a key requests are
+    /// spread round-robin over the weights.
+    pub fn select_upstream(&self, sticky_key: Option<&str>) -> &str {
+        if self.canaries.is_empty() {
+            return &self.upstream;
+        }
+
+        let point = match sticky_key {
+            Some(key) => fnv1a(format!("{}/{}", self.prefix, key).as_bytes()) % WEIGHT_SCALE,
+            None => UNKEYED_REQUESTS.fetch_add(1, Ordering::Relaxed).wrapping_mul(7_919) % WEIGHT_SCALE,
+        };
+        self.canaries
+            .iter()
+            .find(|(_, bound)| point < *bound)
+            .map(|(upstream, _)| upstream.as_str())
+            .unwrap_or(&self.upstream)
+    }
+
+    /// Groups allowed to make a request, or None if no rule restricts it.
+    pub fn required_groups(&self, method: &Method, upstream_path: &str) -> Option<&[String]> {
+        self.authorize
+            .iter()
+            .find(|rule| rule.matches(method, upstream_path))
+            .map(|rule| rule.groups.as_slice())
+    }
+
+    /// Apply the route's request header rules.
+    pub fn rewrite_request(&self, headers: &mut HeaderMap) {
+        self.request_rules.iter().for_each(|r| r.apply(headers));
+    }
+
+    /// Apply the route's response header rules.
+    pub fn rewrite_response(&self, headers: &mut HeaderMap) {
+        self.response_rules.iter().for_each(|r| r.apply(headers));
+    }
+
+    fn compile(def: RouteDefinition) -> Result<Self, ConfigError> {
+        let prefix = def.prefix.trim_matches('/').to_string();
+        if prefix.is_empty() || prefix.contains('/') {
+            return Err(ConfigError::Invalid(format!(
+                "route prefix '{}' must be a single path segment",
+                def.prefix
+            )));
+        }
+        let is_http = |url: &str| url.starts_with("https://") || url.starts_with("http://");
+        if !is_http(&def.upstream) {
+            return Err(ConfigError::Invalid(format!(
+                "route '{}' upstream must be an http(s) URL",
+                prefix
+            )));
+        }
+        let mirror = match def.mirror {
+            Some(ref m) if !is_http(&m.upstream) => {
+                return Err(ConfigError::Invalid(format!(
+                    "route '{}' mirror upstream must be an http(s) URL",
+                    prefix
+                )))
+            }
+            Some(ref m) if !(0.0..=100.0).contains(&m.percent) => {
+                return Err(ConfigError::Invalid(format!(
+                    "route '{}' mirror percent must be between 0 and 100",
+                    prefix
+                )))
+            }
+            Some(m) => Some(Mirror {
+                upstream: m.upstream.trim_end_matches('/').to_string(),
+                percent: m.percent,
+                writes: m.writes,
+            }),
+            None => None,
+        };
+        let auth = def
+            .auth
+            .map(|a| UpstreamAuth::compile(a, &prefix))
+            .transpose()?;
+
+        let timeouts = def.timeouts.unwrap_or_default();
+        let millis = |ms: Option<u64>| match ms {
+            Some(0) => Err(ConfigError::Invalid(format!(
+                "route '{}' timeouts must be greater than zero",
+                prefix
+            ))),
+            ms => Ok(ms.map(Duration::from_millis)),
+        };
+        let timeouts = RouteTimeouts {
+            connect: millis(timeouts.connect_ms)?,
+            read: millis(timeouts.read_ms)?,
+            total: millis(timeouts.total_ms)?,
+        };
+
+        let mut authorize = Vec::with_capacity(def.authorize.len());
+        for rule in &def.authorize {
+            if rule.groups.is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "route '{}' authorization rule needs at least one group",
+                    prefix
+                )));
+            }
+            let methods = rule
+                .methods
+                .iter()
+                .map(|m| {
+                    Method::from_bytes(m.trim().to_ascii_uppercase().as_bytes()).map_err(|_| {
+                        ConfigError::Invalid(format!("route '{}': invalid method '{}'", prefix, m))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            authorize.push(AuthorizationRule {
+                methods,
+                path: rule.path.as_deref().map(|p| p.trim_matches('/').to_string()),
+                groups: rule.groups.clone(),
+            });
+        }
+
+        let mut canaries = Vec::with_capacity(def.canaries.len());
+        let mut bound = 0.0;
+        for canary in &def.canaries {
+            if !is_http(&canary.upstream) {
+                return Err(ConfigError::Invalid(format!(
+                    "route '{}' canary upstream must be an http(s) URL",
+                    prefix
+                )));
+            }
+            if !(canary.percent > 0.0 && canary.percent <= 100.0) {
+                return Err(ConfigError::Invalid(format!(
+                    "route '{}' canary percent must be in (0, 100]",
+                    prefix
+                )));
+            }
+            bound += canary.percent;
+            canaries.push((
+                canary.upstream.trim_end_matches('/').to_string(),
+                (bound / 100.0 * WEIGHT_SCALE as f64).round() as u64,
+            ));
+        }
+        if bound > 100.0 {
+            return Err(ConfigError::Invalid(format!(
+                "route '{}' canary percents add up to more than 100",
+                prefix
+            )));
+        }
+
+        let invalid = |what: &str, value: &str| {
+            ConfigError::Invalid(format!("route '{}': invalid header {} '{}'", prefix, what, value))
+        };
+        let name = |n: &str| HeaderName::try_from(n.trim()).map_err(|_| invalid("name", n));
+        let value = |v: &str| {
+            HeaderValue::try_from(expand_env(v)?).map_err(|_| invalid("value", v))
+        };
+
+        let mut request_rules = Vec::new();
+        let mut response_rules = Vec::new();
+        for rule in def.headers {
+            let compiled = match rule.action {
+                HeaderAction::Set { name: ref n, value: ref v } => HeaderRule::Set(name(n)?, value(v)?),
+                HeaderAction::Append { name: ref n, value: ref v } => {
+                    HeaderRule::Append(name(n)?, value(v)?)
+                }
+                HeaderAction::Remove { name: ref n } => HeaderRule::Remove(name(n)?),
+                HeaderAction::Rename { ref from, ref to } => HeaderRule::Rename(name(from)?, name(to)?),
+            };
+            match rule.on {
+                Direction::Request => request_rules.push(compiled),
+                Direction::Response => response_rules.push(compiled),
+            }
+        }
+
+        Ok(Self {
+            prefix,
+            upstream: def.upstream.trim_end_matches('/').to_string(),
+            mirror,
+            auth,
+            timeouts,
+            http2_prior_knowledge: def.http2_prior_knowledge,
+            harden: def.harden,
+            authorize,
+            canaries,
+            request_rules,
+            response_rules,
+        })
+    }
+}
+
+/// 64-bit FNV-1a hash (stable across builds, unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Expand `${VAR}` references from the environment.
+pub(crate) fn expand_env(value: &str) -> Result<String, ConfigError> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            ConfigError::Invalid(format!("unterminated ${{...}} in header value '{}'", value))
+        })?;
+        let var = &rest[start + 2..start + end];
+        let resolved = std::env::var(var).map_err(|_| {
+            ConfigError::Invalid(format!("header value references unset variable '{}'", var))
+        })?;
+        out.push_str(&resolved);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Ordered table of upstream routes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteTable {
+    routes: Vec<Route>,
+}
+
+impl RouteTable {
+    /// Build a table, rejecting empty tables, duplicate prefixes and invalid rules.
+    pub fn new(routes: Vec<RouteDefinition>) -> Result<Self, ConfigError> {
+        if routes.is_empty() {
+            return Err(ConfigError::Invalid("route table is empty".to_string()));
+        }
+
+        let mut compiled: Vec<Route> = Vec::with_capacity(routes.len());
+        for def in routes {
+            let route = Route::compile(def)?;
+            if compiled.iter().any(|r| r.prefix == route.prefix) {
+                return Err(ConfigError::Invalid(format!(
+                    "duplicate route prefix '{}'",
+                    route.prefix
+                )));
+            }
+            compiled.push(route);
+        }
+
+        Ok(Self { routes: compiled })
+    }
+
+    /// Parse a table from a JSON array of route definitions.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let routes: Vec<RouteDefinition> = serde_json::from_str(json)
+            .map_err(|e| ConfigError::Invalid(format!("route JSON: {}", e)))?;
+        Self::new(routes)
+    }
+
+    /// Load a table from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let json = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Find the route for a request path, returning it with the path remainder
+    /// (without its leading slash).
+    pub fn resolve<'a>(&self, path: &'a str) -> Option<(&Route, &'a str)> {
+        let path = path.strip_prefix('/')?;
+        let (prefix, rest) = path.split_once('/').unwrap_or((path, ""));
+        self.routes
+            .iter()
+            .find(|r| r.prefix == prefix)
+            .map(|r| (r, rest))
+    }
+
+    /// Iterate over all routes in table order.
+    pub fn iter(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter()
+    }
+}
+
+/// Definitions of the built-in table.
+pub(crate) fn builtin_routes() -> Vec<RouteDefinition> {
+    let route = |prefix: &str, upstream: &str| RouteDefinition {
+        prefix: prefix.to_string(),
+        upstream: upstream.to_string(),
+        headers: Vec::new(),
+        mirror: None,
+        auth: None,
+        canaries: Vec::new(),
+        timeouts: None,
+        http2_prior_knowledge: false,
+        authorize: Vec::new(),
+        harden: None,
+    };
+    let remove = |name: &str| HeaderRuleDefinition {
+        action: HeaderAction::Remove { name: name.to_string() },
+        on: Direction::Request,
+    };
+    vec![
+        route("clob", "https://clob.polymarket.com"),
+        route("gamma", "https://gamma-api.polymarket.com"),
+        RouteDefinition {
+            headers: PUBLIC_ROUTE_STRIPPED_HEADERS.iter().map(|h| remove(h)).collect(),
+            ..route("data", "https://data-api.polymarket.com")
+        },
+        route("negrisk", "https://clob.polymarket.com/neg-risk"),
+        route("chain", "https://polygon-rpc.com"),
+    ]
+}
+
+impl Default for RouteTable {
+    fn default() -> Self {
+        Self::new(builtin_routes()).expect("built-in routes are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_routes_resolve() {
+        let table = RouteTable::default();
+
+        let (route, rest) = table.resolve("/clob/book").unwrap();
+        assert_eq!(route.upstream, "https://clob.polymarket.com");
+        assert_eq!(rest, "book");
+
+        let (route, rest) = table.resolve("/gamma").unwrap();
+        assert_eq!(route.prefix, "gamma");
+        assert_eq!(rest, "");
+
+        let (route, rest) = table.resolve("/data/positions").unwrap();
+        assert_eq!(route.upstream, "https://data-api.polymarket.com");
+        assert_eq!(rest, "positions");
+        let mut headers = HeaderMap::new();
+        headers.insert("poly_api_key", HeaderValue::from_static("key"));
+        headers.insert("poly_address", HeaderValue::from_static("0xabc"));
+        route.rewrite_request(&mut headers);
+        assert!(headers.get("poly_api_key").is_none());
+        assert_eq!(headers["poly_address"], "0xabc");
+
+        let (route, rest) = table.resolve("/negrisk").unwrap();
+        assert_eq!(route.upstream, "https://clob.polymarket.com/neg-risk");
+        assert_eq!(rest, "");
+
+        assert!(table.resolve("/clobber/book").is_none());
+        assert!(table.resolve("/unknown").is_none());
+    }
+
+    #[test]
+    fn test_header_rules() {
+        std::env::set_var("PMPROXY_TEST_ROUTE_KEY", "secret");
+        let table = RouteTable::from_json(
+            r#"[{"prefix": "private", "upstream": "https://api.example.com/", "headers": [
+                {"action": "set", "name": "x-api-key", "value": "Key ${PMPROXY_TEST_ROUTE_KEY}"},
+                {"action": "remove", "name": "cookie"},
+                {"action": "set", "name": "accept-encoding", "value": "identity"},
+                {"action": "rename", "from": "x-old", "to": "x-new"},
+                {"action": "append", "name": "via", "value": "pmproxy"},
+                {"action": "remove", "name": "set-cookie", "on": "response"}
+            ]}]"#,
+        )
+        .unwrap();
+        let (route, _) = table.resolve("/private/data").unwrap();
+        assert_eq!(route.upstream, "https://api.example.com");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("cookie", HeaderValue::from_static("session=1"));
+        headers.insert("accept-encoding", HeaderValue::from_static("gzip"));
+        headers.insert("x-old", HeaderValue::from_static("v"));
+        headers.insert("via", HeaderValue::from_static("lb"));
+        route.rewrite_request(&mut headers);
+
+        assert_eq!(headers["x-api-key"], "Key secret");
+        assert!(headers.get("cookie").is_none());
+        assert_eq!(headers["accept-encoding"], "identity");
+        assert!(headers.get("x-old").is_none());
+        assert_eq!(headers["x-new"], "v");
+        assert_eq!(headers.get_all("via").iter().count(), 2);
+
+        let mut response = HeaderMap::new();
+        response.insert("set-cookie", HeaderValue::from_static("a=b"));
+        route.rewrite_response(&mut response);
+        assert!(response.is_empty());
+    }
+
+    #[test]
+    fn test_mirror_config() {
+        let table = RouteTable::from_json(
+            r#"[{"prefix": "clob", "upstream": "https://clob.polymarket.com",
+                 "mirror": {"upstream": "https://staging.example.com/", "percent": 5}},
+                {"prefix": "gamma", "upstream": "https://gamma-api.polymarket.com",
+                 "mirror": {"upstream": "https://gamma-staging.example.com", "percent": 100, "writes": true}}]"#,
+        )
+        .unwrap();
+        let (clob, _) = table.resolve("/clob/book").unwrap();
+        assert_eq!(
+            clob.mirror,
+            Some(Mirror {
+                upstream: "https://staging.example.com".to_string(),
+                percent: 5.0,
+                writes: false,
+            })
+        );
+        let clob_mirror = clob.mirror.as_ref().unwrap();
+        assert!(clob_mirror.mirrors(&Method::GET));
+        assert!(!clob_mirror.mirrors(&Method::POST));
+        assert!(!clob_mirror.mirrors(&Method::DELETE));
+        let (gamma, _) = table.resolve("/gamma/markets").unwrap();
+        assert!(gamma.mirror.as_ref().unwrap().mirrors(&Method::POST));
+
+        // The share must be explicit
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "mirror": {"upstream": "https://y"}}]"#
+        )
+        .is_err());
+
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "mirror": {"upstream": "https://y", "percent": 150}}]"#
+        )
+        .is_err());
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "mirror": {"upstream": "y"}}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_canary_routing_is_weighted_and_sticky() {
+        let table = RouteTable::from_json(
+            r#"[{"prefix": "gamma", "upstream": "https://gamma-api.polymarket.com",
+                 "canaries": [{"upstream": "https://gamma-mirror.example.com/", "percent": 5}]}]"#,
+        )
+        .unwrap();
+        let (route, _) = table.resolve("/gamma/markets").unwrap();
+
+        let canary = (0..10_000)
+            .filter(|i| route.select_upstream(Some(&format!("tenant-{}", i))) != route.upstream)
+            .count();
+        assert!((350..650).contains(&canary), "canary share {}", canary);
+
+        for i in 0..100 {
+            let key = format!("tenant-{}", i);
+            assert_eq!(route.select_upstream(Some(&key)), route.select_upstream(Some(&key)));
+        }
+        let chosen: Vec<&str> = (0..10_000).map(|_| route.select_upstream(None)).collect();
+        assert!(chosen.contains(&"https://gamma-mirror.example.com"));
+
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "canaries": [
+                {"upstream": "https://y", "percent": 60}, {"upstream": "https://z", "percent": 50}]}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_route_timeouts_and_http2() {
+        let table = RouteTable::from_json(
+            r#"[{"prefix": "clob", "upstream": "https://clob.polymarket.com",
+                 "timeouts": {"connect_ms": 500, "total_ms": 3000}, "http2_prior_knowledge": true},
+                {"prefix": "chain", "upstream": "https://polygon-rpc.com"}]"#,
+        )
+        .unwrap();
+        let (clob, _) = table.resolve("/clob/order").unwrap();
+        assert_eq!(clob.timeouts.connect, Some(Duration::from_millis(500)));
+        assert_eq!(clob.timeouts.read, None);
+        assert_eq!(clob.timeouts.total, Some(Duration::from_secs(3)));
+        assert!(clob.timeouts.needs_client());
+        let (chain, _) = table.resolve("/chain").unwrap();
+        assert_eq!(chain.timeouts, RouteTimeouts::default());
+        assert!(clob.http2_prior_knowledge);
+        assert!(!chain.http2_prior_knowledge);
+        assert_eq!(chain.harden, None);
+
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "timeouts": {"total_ms": 0}}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_authorization_rules() {
+        let table = RouteTable::from_json(
+            r#"[{"prefix": "clob", "upstream": "https://clob.polymarket.com", "authorize": [
+                {"methods": ["post", "DELETE"], "path": "/order", "groups": ["traders"]},
+                {"path": "admin", "groups": ["ops", "traders"]}]}]"#,
+        )
+        .unwrap();
+        let (route, _) = table.resolve("/clob/order").unwrap();
+        let traders = ["traders".to_string()];
+
+        assert_eq!(route.required_groups(&Method::POST, "order"), Some(&traders[..]));
+        assert_eq!(route.required_groups(&Method::DELETE, "order/123"), Some(&traders[..]));
+        assert_eq!(route.required_groups(&Method::GET, "order"), None);
+        assert_eq!(route.required_groups(&Method::POST, "orders"), None);
+        assert_eq!(route.required_groups(&Method::GET, "admin").map(<[String]>::len), Some(2));
+
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "authorize": [{"groups": []}]}]"#
+        )
+        .is_err());
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x",
+                 "authorize": [{"methods": ["P OST"], "groups": ["g"]}]}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_invalid_tables_rejected() {
+        assert!(RouteTable::from_json("[]").is_err());
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x"}, {"prefix": "/a/", "upstream": "https://y"}]"#
+        )
+        .is_err());
+        assert!(RouteTable::from_json(r#"[{"prefix": "a/b", "upstream": "https://x"}]"#).is_err());
+        assert!(RouteTable::from_json(r#"[{"prefix": "a", "upstream": "ftp://x"}]"#).is_err());
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "headers": [
+                {"action": "set", "name": "bad name", "value": "v"}]}]"#
+        )
+        .is_err());
+        assert!(RouteTable::from_json(
+            r#"[{"prefix": "a", "upstream": "https://x", "headers": [
+                {"action": "set", "name": "x-key", "value": "${PMPROXY_TEST_UNSET_VAR}"}]}]"#
+        )
+        .is_err());
+    }
+}
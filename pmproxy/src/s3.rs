@@ -0,0 +1,124 @@
+//! Minimal S3-compatible object upload (`PUT`, AWS Signature Version 4).
+//!
+//! Only what the audit exporter needs: path-style `PUT` of a single object.
+//! Works against AWS S3 and S3-compatible stores, including Google Cloud
+//! Storage through its XML API with HMAC keys. Requests are signed with
+//! [`crate::sigv4`].
+
+use std::time::SystemTime;
+
+use axum::http::HeaderMap;
+
+use crate::error::ConfigError;
+use crate::sigv4::{self, uri_encode, Credentials};
+
+/// Object store location parsed from `s3://bucket/prefix` or `gs://bucket/prefix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    /// Base URL of the store, e.g. `https://s3.us-east-1.amazonaws.com`.
+    pub endpoint: String,
+    /// Signing region (`auto` for GCS).
+    pub region: String,
+    pub bucket: String,
+    /// Key prefix, empty or ending in `/`.
+    pub prefix: String,
+}
+
+impl Bucket {
+    /// Parse `s3://bucket/prefix` or `gs://bucket/prefix`. `endpoint` overrides
+    /// the default endpoint (e.g. for MinIO).
+    pub fn parse(url: &str, region: &str, endpoint: Option<&str>) -> Result<Self, ConfigError> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| ConfigError::Invalid(format!("bucket url {:?}: missing scheme", url)))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(ConfigError::Invalid(format!("bucket url {:?}: missing bucket", url)));
+        }
+
+        let (default_endpoint, region) = match scheme {
+            "s3" => (format!("https://s3.{}.amazonaws.com", region), region.to_string()),
+            "gs" => ("https://storage.googleapis.com".to_string(), "auto".to_string()),
+            other => {
+                return Err(ConfigError::Invalid(format!(
+                    "bucket url {:?}: unsupported scheme {:?} (use s3:// or gs://)",
+                    url, other
+                )))
+            }
+        };
+
+        let prefix = prefix.trim_matches('/');
+        Ok(Self {
+            endpoint: endpoint.unwrap_or(&default_endpoint).trim_end_matches('/').to_string(),
+            region,
+            bucket: bucket.to_string(),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", prefix)
+            },
+        })
+    }
+
+    /// Upload `body` to `key` (relative to the prefix).
+    pub async fn put_object(
+        &self,
+        client: &reqwest::Client,
+        credentials: &Credentials,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<(), String> {
+        let path = uri_encode(&format!("/{}/{}{}", self.bucket, self.prefix, key), true);
+        let url = format!("{}{}", self.endpoint, path);
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map(|(_, h)| h)
+            .unwrap_or(&self.endpoint);
+
+        let request = sigv4::Request {
+            method: "PUT",
+            host,
+            path: &path,
+            query: "",
+            payload: &body,
+        };
+        let mut headers = HeaderMap::new();
+        sigv4::sign(credentials, &self.region, "s3", &request, SystemTime::now()).apply(&mut headers);
+
+        let response = client
+            .put(&url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("PUT {} returned {}: {}", url, status, text.trim()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bucket() {
+        let bucket = Bucket::parse("s3://audit-logs/pmproxy/prod/", "eu-west-1", None).unwrap();
+        assert_eq!(bucket.endpoint, "https://s3.eu-west-1.amazonaws.com");
+        assert_eq!(bucket.bucket, "audit-logs");
+        assert_eq!(bucket.prefix, "pmproxy/prod/");
+
+        let gcs = Bucket::parse("gs://audit-logs", "us-east-1", None).unwrap();
+        assert_eq!(gcs.endpoint, "https://storage.googleapis.com");
+        assert_eq!(gcs.region, "auto");
+        assert_eq!(gcs.prefix, "");
+
+        assert!(Bucket::parse("ftp://x/y", "us-east-1", None).is_err());
+        assert!(Bucket::parse("audit-logs", "us-east-1", None).is_err());
+    }
+}
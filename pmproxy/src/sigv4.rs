@@ -0,0 +1,315 @@
+//! AWS Signature Version 4 request signing.
+//!
+//! Used to upload audit batches ([`crate::s3`]) and to authenticate the proxy
+//! to private upstreams behind IAM ([`crate::upstream_auth`]).
+
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::http::{HeaderMap, HeaderValue};
+use ring::{digest, hmac};
+
+/// Signing credentials.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Credentials {
+    /// Read credentials from the standard `AWS_ACCESS_KEY_ID` /
+    /// `AWS_SECRET_ACCESS_KEY` / `AWS_SESSION_TOKEN` variables (set
+    /// automatically on Lambda).
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            access_key_id: env::var("AWS_ACCESS_KEY_ID").ok()?,
+            secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").ok()?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok().filter(|t| !t.is_empty()),
+        })
+    }
+}
+
+/// The parts of a request covered by the signature.
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    pub method: &'a str,
+    /// `Host` header value (host[:port]).
+    pub host: &'a str,
+    /// Path as sent on the wire (already percent-encoded).
+    pub path: &'a str,
+    /// Query string as sent on the wire, without `?`.
+    pub query: &'a str,
+    pub payload: &'a [u8],
+}
+
+/// Headers carrying a SigV4 signature.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub authorization: String,
+    pub amz_date: String,
+    pub content_sha256: String,
+    pub security_token: Option<String>,
+}
+
+impl Signature {
+    /// Set the signature headers, replacing any values the client sent.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        headers.remove("x-amz-security-token");
+        let value = |v: &str| HeaderValue::from_str(v).expect("signature headers are ASCII");
+        headers.insert("authorization", value(&self.authorization));
+        headers.insert("x-amz-date", value(&self.amz_date));
+        headers.insert("x-amz-content-sha256", value(&self.content_sha256));
+        if let Some(ref token) = self.security_token {
+            if let Ok(token) = HeaderValue::from_str(token) {
+                headers.insert("x-amz-security-token", token);
+            }
+        }
+    }
+}
+
+/// Sign a request for `service` in `region`.
+pub fn sign(
+    credentials: &Credentials,
+    region: &str,
+    service: &str,
+    request: &Request<'_>,
+    time: SystemTime,
+) -> Signature {
+    let content_sha256 = hex(digest::digest(&digest::SHA256, request.payload).as_ref());
+    let amz_date = amz_date(time);
+
+    let mut headers = vec![
+        ("host", request.host.to_string()),
+        ("x-amz-content-sha256", content_sha256.clone()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(ref token) = credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    let signed_headers = headers.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(";");
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(n, v)| format!("{}:{}\n", n, v.trim()))
+        .collect();
+
+    // S3 signs the path as sent; every other service encodes it once more
+    let canonical_uri = match (request.path, service) {
+        ("", _) => "/".to_string(),
+        (path, "s3") => path.to_string(),
+        (path, _) => uri_encode(path, true),
+    };
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method,
+        canonical_uri,
+        canonical_query(request.query),
+        canonical_headers,
+        signed_headers,
+        content_sha256
+    );
+
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref())
+    );
+
+    let key = signing_key(&credentials.secret_access_key, date, region, service);
+    let signature = hex(hmac::sign(&key, string_to_sign.as_bytes()).as_ref());
+
+    Signature {
+        authorization: format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            credentials.access_key_id, scope, signed_headers, signature
+        ),
+        amz_date,
+        content_sha256,
+        security_token: credentials.session_token.clone(),
+    }
+}
+
+/// Derive the SigV4 signing key for a date (`YYYYMMDD`), region and service.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> hmac::Key {
+    let sign = |key: &[u8], data: &str| {
+        hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes())
+    };
+    let k_date = sign(format!("AWS4{}", secret).as_bytes(), date);
+    let k_region = sign(k_date.as_ref(), region);
+    let k_service = sign(k_region.as_ref(), service);
+    let k_signing = sign(k_service.as_ref(), "aws4_request");
+    hmac::Key::new(hmac::HMAC_SHA256, k_signing.as_ref())
+}
+
+/// Sorted, consistently encoded query string.
+fn canonical_query(query: &str) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                uri_encode(&percent_decode(name), false),
+                uri_encode(&percent_decode(value), false),
+            )
+        })
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(n, v)| format!("{}={}", n, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode per SigV4 (RFC 3986 unreserved characters kept, and `/`
+/// if `keep_slash`).
+pub fn uri_encode(s: &str, keep_slash: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match escaped {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Lowercase hex encoding.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `YYYYMMDDTHHMMSSZ` timestamp.
+fn amz_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) civil date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_signing_key_and_date() {
+        // Example from the AWS SigV4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        let probe = hex(hmac::sign(&key, b"").as_ref());
+        let expected = hmac::Key::new(
+            hmac::HMAC_SHA256,
+            &[
+                0xf4, 0x78, 0x0e, 0x2d, 0x9f, 0x65, 0xfa, 0x89, 0x5f, 0x9c, 0x67, 0xb3, 0x2c, 0xe1,
+                0xba, 0xf0, 0xb0, 0xd8, 0xa4, 0x35, 0x05, 0xa0, 0x00, 0xa1, 0xa9, 0xe0, 0x90, 0xd4,
+                0x14, 0xdb, 0x40, 0x4d,
+            ],
+        );
+        assert_eq!(probe, hex(hmac::sign(&expected, b"").as_ref()));
+
+        let time = UNIX_EPOCH + Duration::from_secs(1_329_264_000 + 3661);
+        assert_eq!(amz_date(time), "20120215T010101Z");
+    }
+
+    #[test]
+    fn test_canonical_encoding() {
+        assert_eq!(uri_encode("/b/a key+1.json", true), "/b/a%20key%2B1.json");
+        assert_eq!(uri_encode("a/b", false), "a%2Fb");
+        assert_eq!(canonical_query("b=2&a=x%20y&c"), "a=x%20y&b=2&c=");
+        assert_eq!(canonical_query(""), "");
+    }
+
+    #[test]
+    fn test_signature_headers_replace_client_values() {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: Some("token".to_string()),
+        };
+        let request = Request {
+            method: "GET",
+            host: "api.example.com",
+            path: "/v1/markets",
+            query: "limit=10",
+            payload: b"",
+        };
+        let signature = sign(&credentials, "us-east-1", "execute-api", &request, UNIX_EPOCH);
+        assert!(signature.authorization.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/19700101/us-east-1/execute-api/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date;x-amz-security-token, Signature="
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-date", HeaderValue::from_static("20000101T000000Z"));
+        headers.insert("authorization", HeaderValue::from_static("Bearer client"));
+        signature.apply(&mut headers);
+        assert_eq!(headers["x-amz-date"], "19700101T000000Z");
+        assert_eq!(headers["x-amz-security-token"], "token");
+        assert_eq!(headers["authorization"], signature.authorization.as_str());
+    }
+}
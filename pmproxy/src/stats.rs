@@ -0,0 +1,250 @@
+//! Per-upstream latency and error budget reporting.
+//!
+//! Every proxied request is recorded against its route and the upstream it
+//! went to (canaries are reported separately from the main upstream), and
+//! `GET /stats` reports, over sliding 1, 5 and 15 minute windows, the request
+//! count, p50/p95/p99 latency (time to response headers) and the share of
+//! requests that failed: a 5xx from the upstream, a timeout or a transport
+//! error. `4xx` responses are the client's fault and don't count.
+//!
+//! Each window's error ratio is compared with the error budget
+//! (`PMPROXY_ERROR_BUDGET`, default 1%): `budget_burn` above 1 means the
+//! upstream is failing faster than the budget allows, and the window is
+//! flagged `degraded` once it has enough requests to judge
+//! ([`MIN_REQUESTS`]), which is how Polymarket itself degrading shows up.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::Serialize;
+
+/// Reported windows.
+pub const WINDOWS: [Duration; 3] = [
+    Duration::from_secs(60),
+    Duration::from_secs(300),
+    Duration::from_secs(900),
+];
+
+/// Fewest requests in a window before it can be flagged degraded.
+pub const MIN_REQUESTS: usize = 20;
+
+/// Most samples kept per upstream; older ones are dropped first.
+const MAX_SAMPLES: usize = 20_000;
+
+/// Default share of requests allowed to fail.
+pub const DEFAULT_ERROR_BUDGET: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: Instant,
+    latency_ms: u32,
+    error: bool,
+}
+
+/// One window of one upstream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowStats {
+    pub window_secs: u64,
+    pub requests: usize,
+    pub errors: usize,
+    pub error_ratio: f64,
+    /// Error ratio over the error budget (1.0 = failing exactly at budget).
+    pub budget_burn: f64,
+    /// Over budget with at least [`MIN_REQUESTS`] requests.
+    pub degraded: bool,
+    pub p50_ms: Option<u32>,
+    pub p95_ms: Option<u32>,
+    pub p99_ms: Option<u32>,
+}
+
+/// Statistics of one upstream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpstreamReport {
+    pub route: String,
+    pub upstream: String,
+    pub windows: Vec<WindowStats>,
+}
+
+/// Response of `GET /stats`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsReport {
+    pub error_budget: f64,
+    pub upstreams: Vec<UpstreamReport>,
+}
+
+/// Collects request outcomes per upstream.
+pub struct UpstreamStats {
+    error_budget: f64,
+    samples: DashMap<(String, String), Mutex<VecDeque<Sample>>>,
+}
+
+impl UpstreamStats {
+    pub fn new(error_budget: f64) -> Self {
+        Self {
+            error_budget,
+            samples: DashMap::new(),
+        }
+    }
+
+    /// Record a request of `route` to `upstream`.
+    pub fn record(&self, route: &str, upstream: &str, latency: Duration, error: bool) {
+        self.record_at(route, upstream, Instant::now(), latency, error);
+    }
+
+    fn record_at(&self, route: &str, upstream: &str, at: Instant, latency: Duration, error: bool) {
+        let sample = Sample {
+            at,
+            latency_ms: latency.as_millis().min(u32::MAX as u128) as u32,
+            error,
+        };
+        let key = (route.to_string(), upstream.to_string());
+        let entry = self.samples.entry(key).or_default();
+        let mut samples = entry.lock().unwrap();
+        let horizon = WINDOWS[WINDOWS.len() - 1];
+        while samples
+            .front()
+            .is_some_and(|s| samples.len() >= MAX_SAMPLES || at.saturating_duration_since(s.at) > horizon)
+        {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// Whether any window of an upstream is over the error budget.
+    pub fn is_degraded(&self, route: &str, upstream: &str) -> bool {
+        let key = (route.to_string(), upstream.to_string());
+        self.samples.get(&key).is_some_and(|samples| {
+            let samples = samples.lock().unwrap();
+            WINDOWS
+                .iter()
+                .any(|w| self.window(&samples, Instant::now(), *w).degraded)
+        })
+    }
+
+    /// Statistics of every upstream, ordered by route and upstream.
+    pub fn report(&self) -> StatsReport {
+        self.report_at(Instant::now())
+    }
+
+    fn report_at(&self, now: Instant) -> StatsReport {
+        let mut upstreams: Vec<UpstreamReport> = self
+            .samples
+            .iter()
+            .map(|entry| {
+                let (route, upstream) = entry.key().clone();
+                let samples = entry.value().lock().unwrap();
+                UpstreamReport {
+                    route,
+                    upstream,
+                    windows: WINDOWS.iter().map(|w| self.window(&samples, now, *w)).collect(),
+                }
+            })
+            .collect();
+        upstreams.sort_by(|a, b| (&a.route, &a.upstream).cmp(&(&b.route, &b.upstream)));
+        StatsReport {
+            error_budget: self.error_budget,
+            upstreams,
+        }
+    }
+
+    fn window(&self, samples: &VecDeque<Sample>, now: Instant, window: Duration) -> WindowStats {
+        let recent: Vec<&Sample> = samples
+            .iter()
+            .filter(|s| now.saturating_duration_since(s.at) <= window)
+            .collect();
+        let mut latencies: Vec<u32> = recent.iter().map(|s| s.latency_ms).collect();
+        latencies.sort_unstable();
+
+        let requests = recent.len();
+        let errors = recent.iter().filter(|s| s.error).count();
+        let error_ratio = if requests == 0 { 0.0 } else { errors as f64 / requests as f64 };
+        let budget_burn = if self.error_budget > 0.0 { error_ratio / self.error_budget } else { 0.0 };
+        WindowStats {
+            window_secs: window.as_secs(),
+            requests,
+            errors,
+            error_ratio,
+            budget_burn,
+            degraded: requests >= MIN_REQUESTS && budget_burn > 1.0,
+            p50_ms: percentile(&latencies, 50),
+            p95_ms: percentile(&latencies, 95),
+            p99_ms: percentile(&latencies, 99),
+        }
+    }
+}
+
+impl Default for UpstreamStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_ERROR_BUDGET)
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u32], p: usize) -> Option<u32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let values: Vec<u32> = (1..=100).collect();
+        assert_eq!(percentile(&values, 50), Some(50));
+        assert_eq!(percentile(&values, 95), Some(95));
+        assert_eq!(percentile(&values, 99), Some(99));
+        assert_eq!(percentile(&[7], 99), Some(7));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn test_windows_slide_and_track_error_budget() {
+        let stats = UpstreamStats::new(0.05);
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+
+        // Ten minutes ago: 20 slow failures
+        for _ in 0..20 {
+            stats.record_at("clob", "https://clob", start, ms(2000), true);
+        }
+        // Last minute: 40 fast successes, 1 failure
+        let now = start + Duration::from_secs(600);
+        for i in 0..40 {
+            stats.record_at("clob", "https://clob", now, ms(10 + i), false);
+        }
+        stats.record_at("clob", "https://clob", now, ms(5000), true);
+        stats.record_at("gamma", "https://gamma", now, ms(30), false);
+
+        let report = stats.report_at(now);
+        assert_eq!(report.upstreams.len(), 2);
+        let clob = &report.upstreams[0];
+        assert_eq!(clob.route, "clob");
+
+        let last_minute = &clob.windows[0];
+        assert_eq!((last_minute.requests, last_minute.errors), (41, 1));
+        assert_eq!(last_minute.p50_ms, Some(30));
+        assert_eq!(last_minute.p99_ms, Some(5000));
+        assert!(!last_minute.degraded, "1/41 is within a 5% budget");
+
+        let fifteen = &clob.windows[2];
+        assert_eq!((fifteen.requests, fifteen.errors), (61, 21));
+        assert!(fifteen.budget_burn > 6.0);
+        assert!(fifteen.degraded);
+
+        // Too few requests to judge
+        assert!(!report.upstreams[1].windows[0].degraded);
+
+        // Samples older than the longest window are dropped
+        let later = now + Duration::from_secs(600);
+        stats.record_at("clob", "https://clob", later, ms(10), false);
+        let report = stats.report_at(later);
+        assert_eq!(report.upstreams[0].windows[2].requests, 42);
+    }
+}
@@ -0,0 +1,138 @@
+//! Streaming passthrough for server-sent events and chunked responses.
+//!
+//! Responses are normally buffered in full before being returned, which
+//! breaks `text/event-stream` endpoints (the client sees nothing until the
+//! stream ends) and holds large chunked bodies in memory. Responses that are
+//! event streams, or chunked without a `Content-Length`, are instead relayed
+//! chunk by chunk as they arrive.
+//!
+//! A streamed response is cut off if the upstream sends nothing for
+//! `PMPROXY_STREAM_IDLE_SECS`. Requests that accept `text/event-stream` are
+//! exempt from the 30s upstream timeout, so the idle timeout is what ends a
+//! stalled event stream; other chunked responses still have to complete
+//! within the upstream timeout.
+
+use std::io;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap};
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::warn;
+
+/// Default idle timeout for streamed responses.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Upper bound on an event stream's lifetime (replaces the upstream timeout).
+pub const MAX_EVENT_STREAM_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+const EVENT_STREAM: &str = "text/event-stream";
+
+/// Whether the client asked for an event stream.
+pub fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.to_ascii_lowercase().contains(EVENT_STREAM))
+}
+
+/// Whether an upstream response should be streamed rather than buffered.
+pub fn is_streaming(headers: &HeaderMap) -> bool {
+    let event_stream = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim().to_ascii_lowercase().starts_with(EVENT_STREAM));
+    let chunked = headers
+        .get_all(header::TRANSFER_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.to_ascii_lowercase().contains("chunked"));
+    event_stream || (chunked && !headers.contains_key(header::CONTENT_LENGTH))
+}
+
+/// Relay an upstream body as it arrives, ending it with an error if no chunk
+/// arrives within `idle_timeout`. The tenant's in-flight `slot` is held until
+/// the body ends or the client goes away.
+pub fn relay(response: reqwest::Response, idle_timeout: Duration, slot: Option<OwnedSemaphorePermit>) -> Body {
+    let chunks = futures_util::stream::unfold(Some((response, slot)), move |state| async move {
+        let (mut response, slot) = state?;
+        match tokio::time::timeout(idle_timeout, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => Some((Ok(chunk), Some((response, slot)))),
+            Ok(Ok(None)) => None,
+            Ok(Err(e)) => {
+                warn!(error = %e, "Upstream stream failed");
+                Some((Err(io::Error::other(e)), None))
+            }
+            Err(_) => {
+                warn!(idle_secs = idle_timeout.as_secs(), "Closing idle upstream stream");
+                Some((Err(io::Error::new(io::ErrorKind::TimedOut, "upstream stream idle")), None))
+            }
+        }
+    });
+    Body::from_stream(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_streaming_detection() {
+        assert!(is_streaming(&headers(&[("content-type", "text/event-stream; charset=utf-8")])));
+        assert!(is_streaming(&headers(&[("transfer-encoding", "chunked")])));
+        assert!(!is_streaming(&headers(&[("transfer-encoding", "chunked"), ("content-length", "12")])));
+        assert!(!is_streaming(&headers(&[("content-type", "application/json"), ("content-length", "2")])));
+
+        assert!(accepts_event_stream(&headers(&[("accept", "Text/Event-Stream")])));
+        assert!(!accepts_event_stream(&headers(&[("accept", "application/json")])));
+    }
+
+    #[tokio::test]
+    async fn test_relay_holds_slot_until_body_ends() {
+        use std::sync::Arc;
+
+        use axum::routing::get;
+        use axum::Router;
+        use http_body_util::BodyExt;
+        use tokio::sync::{mpsc, Semaphore};
+
+        // Upstream streams whatever the test feeds it
+        let (tx, rx) = mpsc::channel::<Result<&'static str, io::Error>>(1);
+        let rx = Arc::new(std::sync::Mutex::new(Some(rx)));
+        let upstream = Router::new().route(
+            "/stream",
+            get(move || {
+                let rx = rx.lock().unwrap().take().unwrap();
+                let chunks = futures_util::stream::unfold(rx, |mut rx| async move {
+                    rx.recv().await.map(|chunk| (chunk, rx))
+                });
+                async move { Body::from_stream(chunks) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let slots = Arc::new(Semaphore::new(1));
+        let slot = slots.clone().try_acquire_owned().unwrap();
+        let response = reqwest::get(format!("http://{}/stream", addr)).await.unwrap();
+        let mut body = relay(response, DEFAULT_IDLE_TIMEOUT, Some(slot));
+
+        tx.send(Ok("chunk")).await.unwrap();
+        assert_eq!(body.frame().await.unwrap().unwrap().into_data().unwrap(), "chunk");
+        assert_eq!(slots.available_permits(), 0);
+
+        drop(tx);
+        assert!(body.frame().await.is_none());
+        assert_eq!(slots.available_permits(), 1);
+    }
+}
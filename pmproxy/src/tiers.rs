@@ -0,0 +1,422 @@
+//! Data-driven tenant tier definitions.
+//!
+//! Tiers are loaded from `PMPROXY_TIERS_FILE` (JSON file, hot-reloaded) or
+//! `PMPROXY_TIERS` (inline JSON). When neither is set the built-in
+//! free/pro/enterprise table is used.
+//!
+//! ```json
+//! [
+//!   {"name": "free", "requests_per_minute": 60, "burst_size": 10, "daily_quota": 10000},
+//!   {"name": "team", "requests_per_minute": 120, "burst_size": 20, "monthly_quota": 1000000,
+//!    "max_concurrent_requests": 8},
+//!   {"name": "pro", "requests_per_minute": 300, "burst_size": 50,
+//!    "max_queue_wait_ms": 250, "queue_weight": 2}
+//! ]
+//! ```
+//!
+//! With `max_queue_wait_ms`, requests just over the rate limit wait for the
+//! bucket instead of being rejected, see [`crate::ratelimit`].
+//! `max_websocket_connections` and `max_subscribed_assets` cap WebSocket use,
+//! see [`crate::websocket`].
+//!
+//! The first entry is the default tier, applied to tenants whose token has no
+//! tier claim or names a tier that isn't in the table.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::error::ConfigError;
+
+/// A single tier definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierDefinition {
+    /// Tier name, matched case-insensitively against the `custom:tenant_tier` claim.
+    pub name: String,
+    /// Sustained requests per minute.
+    #[serde(alias = "rpm")]
+    pub requests_per_minute: u32,
+    /// Burst allowance on top of the sustained rate.
+    #[serde(alias = "burst")]
+    pub burst_size: u32,
+    /// Optional cap on requests per UTC day.
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+    /// Optional cap on requests per UTC calendar month.
+    #[serde(default)]
+    pub monthly_quota: Option<u64>,
+    /// Optional cap on simultaneous in-flight requests.
+    #[serde(default, alias = "concurrency")]
+    pub max_concurrent_requests: Option<u32>,
+    /// Longest an over-limit request may wait for the bucket (None = reject at once).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queue_wait_ms: Option<u64>,
+    /// Share of the wait queue relative to other tiers.
+    #[serde(default = "default_queue_weight")]
+    pub queue_weight: u32,
+    /// Optional cap on simultaneous WebSocket connections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_websocket_connections: Option<u32>,
+    /// Optional cap on assets subscribed across all WebSocket connections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_subscribed_assets: Option<u32>,
+}
+
+fn default_queue_weight() -> u32 {
+    1
+}
+
+impl TierDefinition {
+    fn new(name: &str, requests_per_minute: u32, burst_size: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            requests_per_minute,
+            burst_size,
+            daily_quota: None,
+            monthly_quota: None,
+            max_concurrent_requests: None,
+            max_queue_wait_ms: None,
+            queue_weight: default_queue_weight(),
+            max_websocket_connections: None,
+            max_subscribed_assets: None,
+        }
+    }
+
+    fn with_daily_quota(mut self, daily_quota: u64) -> Self {
+        self.daily_quota = Some(daily_quota);
+        self
+    }
+
+    fn with_max_concurrent_requests(mut self, max_concurrent_requests: u32) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    fn with_queue(mut self, max_wait_ms: u64, weight: u32) -> Self {
+        self.max_queue_wait_ms = Some(max_wait_ms);
+        self.queue_weight = weight;
+        self
+    }
+
+    fn with_websocket_limits(mut self, connections: u32, subscribed_assets: u32) -> Self {
+        self.max_websocket_connections = Some(connections);
+        self.max_subscribed_assets = Some(subscribed_assets);
+        self
+    }
+}
+
+/// Tenant tier, as an index into the active [`TierTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TenantTier(usize);
+
+impl TenantTier {
+    /// The default tier (first entry in the table).
+    pub const DEFAULT: TenantTier = TenantTier(0);
+
+    /// Position of this tier in the table.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Ordered, non-empty table of tier definitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TierTable {
+    tiers: Vec<TierDefinition>,
+}
+
+impl TierTable {
+    /// Build a table, rejecting empty tables and duplicate names.
+    pub fn new(tiers: Vec<TierDefinition>) -> Result<Self, ConfigError> {
+        if tiers.is_empty() {
+            return Err(ConfigError::Invalid("tier table is empty".to_string()));
+        }
+
+        for (i, tier) in tiers.iter().enumerate() {
+            if tier.name.trim().is_empty() {
+                return Err(ConfigError::Invalid(format!("tier {} has an empty name", i)));
+            }
+            if tiers[..i]
+                .iter()
+                .any(|t| t.name.eq_ignore_ascii_case(&tier.name))
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "duplicate tier name '{}'",
+                    tier.name
+                )));
+            }
+        }
+
+        Ok(Self { tiers })
+    }
+
+    /// Parse a table from a JSON array of tier definitions.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let tiers: Vec<TierDefinition> = serde_json::from_str(json)
+            .map_err(|e| ConfigError::Invalid(format!("tier JSON: {}", e)))?;
+        Self::new(tiers)
+    }
+
+    /// Load a table from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let json = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Resolve a tier name (case-insensitive), falling back to the default tier.
+    pub fn resolve(&self, name: &str) -> TenantTier {
+        self.tiers
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name.trim()))
+            .map(TenantTier)
+            .unwrap_or(TenantTier::DEFAULT)
+    }
+
+    /// Get the definition for a tier.
+    ///
+    /// Indexes that are out of range (e.g. after a reload shrank the table)
+    /// fall back to the default tier.
+    pub fn get(&self, tier: TenantTier) -> &TierDefinition {
+        self.tiers.get(tier.0).unwrap_or(&self.tiers[0])
+    }
+
+    /// The default tier definition.
+    pub fn default_tier(&self) -> &TierDefinition {
+        &self.tiers[0]
+    }
+
+    /// Iterate over all tier definitions in table order.
+    pub fn iter(&self) -> impl Iterator<Item = &TierDefinition> {
+        self.tiers.iter()
+    }
+
+    /// Number of tiers in the table.
+    pub fn len(&self) -> usize {
+        self.tiers.len()
+    }
+
+    /// Always false: tables are validated to be non-empty.
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+}
+
+impl Default for TierTable {
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                TierDefinition::new("free", 60, 10)
+                    .with_daily_quota(10_000)
+                    .with_max_concurrent_requests(4)
+                    .with_websocket_limits(2, 50),
+                TierDefinition::new("pro", 300, 50)
+                    .with_daily_quota(100_000)
+                    .with_max_concurrent_requests(16)
+                    .with_queue(250, 1)
+                    .with_websocket_limits(10, 500),
+                TierDefinition::new("enterprise", 1000, 100)
+                    .with_max_concurrent_requests(64)
+                    .with_queue(1000, 4)
+                    .with_websocket_limits(50, 5000),
+            ],
+        }
+    }
+}
+
+/// Shared, hot-reloadable handle to the active tier table.
+#[derive(Clone)]
+pub struct TierRegistry {
+    current: Arc<RwLock<Arc<TierTable>>>,
+    /// File the table was loaded from (enables reloading).
+    source: Option<PathBuf>,
+}
+
+impl TierRegistry {
+    /// Create a registry holding a fixed table.
+    pub fn new(table: TierTable) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(table))),
+            source: None,
+        }
+    }
+
+    /// Create a registry that can be reloaded from `path`.
+    pub fn with_source(table: TierTable, path: PathBuf) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(table))),
+            source: Some(path),
+        }
+    }
+
+    /// Get the current table.
+    pub fn snapshot(&self) -> Arc<TierTable> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the current table.
+    pub fn replace(&self, table: TierTable) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(table);
+    }
+
+    /// Re-read the source file. Returns true if the table changed.
+    ///
+    /// On error the current table is kept.
+    pub fn reload(&self) -> Result<bool, ConfigError> {
+        let Some(ref path) = self.source else {
+            return Ok(false);
+        };
+
+        let table = TierTable::from_file(path)?;
+        if *self.snapshot() == table {
+            return Ok(false);
+        }
+
+        info!(
+            path = %path.display(),
+            tiers = table.len(),
+            "Tier table reloaded"
+        );
+        self.replace(table);
+        Ok(true)
+    }
+
+    /// Poll the source file's modification time and reload when it changes.
+    ///
+    /// Does nothing if the registry has no source file.
+    pub fn spawn_watcher(&self, interval: Duration) {
+        let Some(path) = self.source.clone() else {
+            return;
+        };
+        let registry = self.clone();
+
+        tokio::spawn(async move {
+            let mut last_modified = modified_time(&path);
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let modified = modified_time(&path);
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                debug!(path = %path.display(), "Tier file changed");
+                if let Err(e) = registry.reload() {
+                    warn!(error = %e, "Failed to reload tier table, keeping previous");
+                }
+            }
+        });
+    }
+}
+
+impl Default for TierRegistry {
+    fn default() -> Self {
+        Self::new(TierTable::default())
+    }
+}
+
+pub(crate) fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_table_limits() {
+        let table = TierTable::default();
+
+        let free = table.get(table.resolve("free"));
+        assert_eq!(free.requests_per_minute, 60);
+        assert_eq!(free.burst_size, 10);
+        assert_eq!(free.daily_quota, Some(10_000));
+        assert_eq!(free.max_concurrent_requests, Some(4));
+
+        let pro = table.get(table.resolve("pro"));
+        assert_eq!(pro.requests_per_minute, 300);
+        assert_eq!(pro.burst_size, 50);
+
+        let enterprise = table.get(table.resolve("enterprise"));
+        assert_eq!(enterprise.requests_per_minute, 1000);
+        assert_eq!(enterprise.burst_size, 100);
+        assert_eq!(enterprise.daily_quota, None);
+    }
+
+    #[test]
+    fn test_resolve_is_case_insensitive() {
+        let table = TierTable::default();
+        assert_eq!(table.resolve("free"), TenantTier::DEFAULT);
+        assert_eq!(table.resolve("PRO"), table.resolve("pro"));
+        assert_eq!(table.resolve("ENTERPRISE").index(), 2);
+        assert_eq!(table.resolve("unknown"), TenantTier::DEFAULT);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let table = TierTable::from_json(
+            r#"[
+                {"name": "basic", "rpm": 30, "burst": 5, "daily_quota": 1000},
+                {"name": "whale", "requests_per_minute": 5000, "burst_size": 500, "concurrency": 32}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.default_tier().name, "basic");
+        assert_eq!(table.default_tier().daily_quota, Some(1000));
+        let whale = table.get(table.resolve("whale"));
+        assert_eq!(whale.requests_per_minute, 5000);
+        assert_eq!(whale.daily_quota, None);
+        assert_eq!(whale.max_concurrent_requests, Some(32));
+        assert_eq!(table.default_tier().max_concurrent_requests, None);
+    }
+
+    #[test]
+    fn test_from_json_rejects_invalid_tables() {
+        assert!(TierTable::from_json("[]").is_err());
+        assert!(TierTable::from_json("not json").is_err());
+        assert!(TierTable::from_json(
+            r#"[{"name": "a", "rpm": 1, "burst": 1}, {"name": "A", "rpm": 2, "burst": 2}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_tier_falls_back_to_default() {
+        let table = TierTable::from_json(r#"[{"name": "only", "rpm": 10, "burst": 1}]"#).unwrap();
+        assert_eq!(table.get(TenantTier(5)).name, "only");
+    }
+
+    #[test]
+    fn test_registry_reload_from_file() {
+        let path = std::env::temp_dir().join(format!("pmproxy-tiers-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{"name": "free", "rpm": 60, "burst": 10}]"#).unwrap();
+
+        let table = TierTable::from_file(&path).unwrap();
+        let registry = TierRegistry::with_source(table, path.clone());
+        assert!(!registry.reload().unwrap());
+
+        std::fs::write(&path, r#"[{"name": "free", "rpm": 120, "burst": 20}]"#).unwrap();
+        assert!(registry.reload().unwrap());
+        assert_eq!(registry.snapshot().default_tier().requests_per_minute, 120);
+
+        // A broken file keeps the previous table
+        std::fs::write(&path, "{").unwrap();
+        assert!(registry.reload().is_err());
+        assert_eq!(registry.snapshot().default_tier().requests_per_minute, 120);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,144 @@
+//! Native TLS listener for deployments without a load balancer in front.
+//!
+//! With `--tls-cert`/`--tls-key` (or `PMPROXY_TLS_CERT`/`PMPROXY_TLS_KEY`)
+//! the EC2 server terminates TLS itself using rustls. The certificate and key
+//! files are polled for changes and reloaded without dropping connections, so
+//! a renewed certificate (e.g. from certbot) is picked up in place; a reload
+//! that fails keeps the previous certificate.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use axum_server::tls_rustls::RustlsConfig;
+use tracing::{debug, info, warn};
+
+use crate::error::ConfigError;
+use crate::tiers::modified_time;
+
+/// PEM certificate chain and private key served by the listener.
+#[derive(Debug, Clone)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsFiles {
+    /// Load the certificate and key.
+    pub async fn load(&self) -> Result<RustlsConfig, ConfigError> {
+        RustlsConfig::from_pem_file(&self.cert, &self.key)
+            .await
+            .map_err(|e| self.invalid(e))
+    }
+
+    /// Replace the served certificate with the files' current contents.
+    pub async fn reload(&self, config: &RustlsConfig) -> Result<(), ConfigError> {
+        config
+            .reload_from_pem_file(&self.cert, &self.key)
+            .await
+            .map_err(|e| self.invalid(e))
+    }
+
+    /// Poll the files' modification times and reload when either changes.
+    pub fn spawn_watcher(&self, config: RustlsConfig, interval: Duration) {
+        let files = self.clone();
+
+        tokio::spawn(async move {
+            let mut last_modified = files.modified();
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let modified = files.modified();
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                debug!(cert = %files.cert.display(), "TLS certificate changed");
+                match files.reload(&config).await {
+                    Ok(()) => info!(cert = %files.cert.display(), "TLS certificate reloaded"),
+                    Err(e) => warn!(error = %e, "Failed to reload TLS certificate, keeping previous"),
+                }
+            }
+        });
+    }
+
+    fn modified(&self) -> (Option<SystemTime>, Option<SystemTime>) {
+        (modified_time(&self.cert), modified_time(&self.key))
+    }
+
+    fn invalid(&self, e: std::io::Error) -> ConfigError {
+        ConfigError::Invalid(format!(
+            "TLS certificate {} / key {}: {}",
+            self.cert.display(),
+            self.key.display(),
+            e
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+
+    /// Self-signed certificates for `localhost`, valid until 2126.
+    const CERT_A: &str = include_str!("../tests/tls/a.crt");
+    const KEY_A: &str = include_str!("../tests/tls/a.key");
+    const DER_A: &[u8] = include_bytes!("../tests/tls/a.der");
+    const CERT_B: &str = include_str!("../tests/tls/b.crt");
+    const KEY_B: &str = include_str!("../tests/tls/b.key");
+    const DER_B: &[u8] = include_bytes!("../tests/tls/b.der");
+
+    async fn served_certificate(port: u16) -> Vec<u8> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .tls_info(true)
+            .build()
+            .unwrap();
+        let response = client
+            .get(format!("https://localhost:{}/health", port))
+            .send()
+            .await
+            .unwrap();
+        let info = response.extensions().get::<reqwest::tls::TlsInfo>().unwrap();
+        info.peer_certificate().unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_listener_serves_and_reloads_certificate() {
+        let dir = std::env::temp_dir().join(format!("pmproxy-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = TlsFiles {
+            cert: dir.join("server.crt"),
+            key: dir.join("server.key"),
+        };
+        std::fs::write(&files.cert, CERT_A).unwrap();
+        std::fs::write(&files.key, KEY_A).unwrap();
+
+        let config = files.load().await.unwrap();
+        let handle = axum_server::Handle::new();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        let server = axum_server::bind_rustls("127.0.0.1:0".parse().unwrap(), config.clone())
+            .handle(handle.clone())
+            .serve(app.into_make_service());
+        tokio::spawn(server);
+        let port = handle.listening().await.unwrap().port();
+
+        assert_eq!(served_certificate(port).await, DER_A);
+
+        std::fs::write(&files.cert, CERT_B).unwrap();
+        std::fs::write(&files.key, KEY_B).unwrap();
+        files.reload(&config).await.unwrap();
+        assert_eq!(served_certificate(port).await, DER_B);
+
+        // A broken key is rejected and the previous certificate stays
+        std::fs::write(&files.key, "not a key").unwrap();
+        assert!(files.reload(&config).await.is_err());
+        assert_eq!(served_certificate(port).await, DER_B);
+
+        handle.shutdown();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,304 @@
+//! Credentials the proxy itself presents to an upstream.
+//!
+//! Self-hosted mirrors in private deployments may only accept requests from
+//! the proxy. A route's `auth` (see [`crate::routes`]) adds one of:
+//!
+//! ```json
+//! {"type": "header", "name": "x-api-key", "value": "${MIRROR_API_KEY}"}
+//! {"type": "bearer", "token": "${MIRROR_TOKEN}"}
+//! {"type": "hmac", "key": "${MIRROR_HMAC_KEY}"}
+//! {"type": "sigv4", "region": "us-east-1", "service": "execute-api"}
+//! ```
+//!
+//! `hmac` sets `x-pmproxy-timestamp` (Unix seconds) and `x-pmproxy-signature`,
+//! the hex HMAC-SHA256 of `"{timestamp}\n{METHOD}\n{path?query}\n{hex(sha256(body))}"`.
+//! `sigv4` signs with the route's `access_key_id`/`secret_access_key` or,
+//! without them, the `AWS_*` credentials of the process.
+//!
+//! Credentials are applied after the route's header rules and replace any
+//! value a client sent for the same headers; they are never returned to the
+//! client or mirrored to a shadow upstream.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ConfigError;
+use crate::routes::expand_env;
+use crate::sigv4::{self, hex, Credentials};
+
+/// Upstream authentication as written in the route file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UpstreamAuthDefinition {
+    /// A fixed header, e.g. an API key.
+    Header { name: String, value: String },
+    /// `Authorization: Bearer <token>`.
+    Bearer { token: String },
+    /// HMAC-SHA256 request signature.
+    Hmac {
+        key: String,
+        #[serde(default = "default_signature_header")]
+        signature_header: String,
+        #[serde(default = "default_timestamp_header")]
+        timestamp_header: String,
+    },
+    /// AWS Signature Version 4.
+    Sigv4 {
+        region: String,
+        #[serde(default = "default_sigv4_service")]
+        service: String,
+        #[serde(default)]
+        access_key_id: Option<String>,
+        #[serde(default)]
+        secret_access_key: Option<String>,
+        #[serde(default)]
+        session_token: Option<String>,
+    },
+}
+
+fn default_signature_header() -> String {
+    "x-pmproxy-signature".to_string()
+}
+
+fn default_timestamp_header() -> String {
+    "x-pmproxy-timestamp".to_string()
+}
+
+fn default_sigv4_service() -> String {
+    "execute-api".to_string()
+}
+
+/// Validated upstream authentication. `Debug` output omits secrets.
+#[derive(Clone, PartialEq, Eq)]
+pub enum UpstreamAuth {
+    Header(HeaderName, HeaderValue),
+    Hmac {
+        key: Vec<u8>,
+        signature_header: HeaderName,
+        timestamp_header: HeaderName,
+    },
+    Sigv4 {
+        region: String,
+        service: String,
+        /// None = read `AWS_*` from the environment per request.
+        credentials: Option<Credentials>,
+    },
+}
+
+impl std::fmt::Debug for UpstreamAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpstreamAuth::Header(name, _) => f.debug_tuple("Header").field(name).finish_non_exhaustive(),
+            UpstreamAuth::Hmac { signature_header, .. } => f
+                .debug_struct("Hmac")
+                .field("signature_header", signature_header)
+                .finish_non_exhaustive(),
+            UpstreamAuth::Sigv4 { region, service, .. } => f
+                .debug_struct("Sigv4")
+                .field("region", region)
+                .field("service", service)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl UpstreamAuth {
+    /// Validate a definition for route `prefix`, expanding `${VAR}` references.
+    pub fn compile(def: UpstreamAuthDefinition, prefix: &str) -> Result<Self, ConfigError> {
+        let invalid = |what: &str| ConfigError::Invalid(format!("route '{}' auth: invalid {}", prefix, what));
+        let name = |n: &str| HeaderName::try_from(n.trim()).map_err(|_| invalid("header name"));
+        let secret = |v: &str| {
+            let mut value = HeaderValue::try_from(expand_env(v)?).map_err(|_| invalid("header value"))?;
+            value.set_sensitive(true);
+            Ok::<_, ConfigError>(value)
+        };
+
+        match def {
+            UpstreamAuthDefinition::Header { name: n, value } => {
+                Ok(UpstreamAuth::Header(name(&n)?, secret(&value)?))
+            }
+            UpstreamAuthDefinition::Bearer { token } => Ok(UpstreamAuth::Header(
+                axum::http::header::AUTHORIZATION,
+                secret(&format!("Bearer {}", token))?,
+            )),
+            UpstreamAuthDefinition::Hmac {
+                key,
+                signature_header,
+                timestamp_header,
+            } => {
+                let key = expand_env(&key)?;
+                if key.is_empty() {
+                    return Err(invalid("hmac key (empty)"));
+                }
+                Ok(UpstreamAuth::Hmac {
+                    key: key.into_bytes(),
+                    signature_header: name(&signature_header)?,
+                    timestamp_header: name(&timestamp_header)?,
+                })
+            }
+            UpstreamAuthDefinition::Sigv4 {
+                region,
+                service,
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => {
+                let credentials = match (access_key_id, secret_access_key) {
+                    (Some(id), Some(secret)) => Some(Credentials {
+                        access_key_id: expand_env(&id)?,
+                        secret_access_key: expand_env(&secret)?,
+                        session_token: session_token.as_deref().map(expand_env).transpose()?,
+                    }),
+                    (None, None) => None,
+                    _ => {
+                        return Err(invalid(
+                            "sigv4 credentials (need both access_key_id and secret_access_key)",
+                        ))
+                    }
+                };
+                Ok(UpstreamAuth::Sigv4 {
+                    region,
+                    service,
+                    credentials,
+                })
+            }
+        }
+    }
+
+    /// Add the credentials to the headers of a request for `url`.
+    pub fn apply(
+        &self,
+        method: &Method,
+        url: &str,
+        body: &[u8],
+        headers: &mut HeaderMap,
+    ) -> Result<(), String> {
+        self.apply_at(method, url, body, headers, SystemTime::now())
+    }
+
+    fn apply_at(
+        &self,
+        method: &Method,
+        url: &str,
+        body: &[u8],
+        headers: &mut HeaderMap,
+        now: SystemTime,
+    ) -> Result<(), String> {
+        let url = reqwest::Url::parse(url).map_err(|e| format!("invalid upstream URL: {}", e))?;
+
+        match self {
+            UpstreamAuth::Header(name, value) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            UpstreamAuth::Hmac {
+                key,
+                signature_header,
+                timestamp_header,
+            } => {
+                let timestamp = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().to_string();
+                let target = match url.query() {
+                    Some(query) => format!("{}?{}", url.path(), query),
+                    None => url.path().to_string(),
+                };
+                let payload = format!(
+                    "{}\n{}\n{}\n{}",
+                    timestamp,
+                    method,
+                    target,
+                    hex(digest::digest(&digest::SHA256, body).as_ref())
+                );
+                let signature = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), payload.as_bytes());
+
+                headers.insert(timestamp_header.clone(), HeaderValue::from_str(&timestamp).unwrap());
+                headers.insert(
+                    signature_header.clone(),
+                    HeaderValue::from_str(&hex(signature.as_ref())).unwrap(),
+                );
+            }
+            UpstreamAuth::Sigv4 {
+                region,
+                service,
+                credentials,
+            } => {
+                let env_credentials;
+                let credentials = match credentials {
+                    Some(c) => c,
+                    None => {
+                        env_credentials = Credentials::from_env().ok_or("AWS credentials not set")?;
+                        &env_credentials
+                    }
+                };
+                let host = match url.port() {
+                    Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                    None => url.host_str().unwrap_or_default().to_string(),
+                };
+                let request = sigv4::Request {
+                    method: method.as_str(),
+                    host: &host,
+                    path: url.path(),
+                    query: url.query().unwrap_or(""),
+                    payload: body,
+                };
+                sigv4::sign(credentials, region, service, &request, now).apply(headers);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(json: &str) -> UpstreamAuth {
+        UpstreamAuth::compile(serde_json::from_str(json).unwrap(), "test").unwrap()
+    }
+
+    #[test]
+    fn test_static_credentials_replace_client_headers() {
+        std::env::set_var("PMPROXY_TEST_UPSTREAM_TOKEN", "proxy-token");
+        let auth = compile(r#"{"type": "bearer", "token": "${PMPROXY_TEST_UPSTREAM_TOKEN}"}"#);
+        assert!(!format!("{:?}", auth).contains("proxy-token"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer client"));
+        auth.apply(&Method::GET, "https://mirror.internal/book", b"", &mut headers)
+            .unwrap();
+        assert_eq!(headers["authorization"], "Bearer proxy-token");
+
+        let auth = compile(r#"{"type": "header", "name": "X-Api-Key", "value": "k"}"#);
+        auth.apply(&Method::GET, "https://mirror.internal/book", b"", &mut headers)
+            .unwrap();
+        assert_eq!(headers["x-api-key"], "k");
+    }
+
+    #[test]
+    fn test_hmac_signature() {
+        let auth = compile(r#"{"type": "hmac", "key": "secret"}"#);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-pmproxy-signature", HeaderValue::from_static("forged"));
+        let now = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        auth.apply_at(&Method::POST, "https://mirror.internal/order?x=1", b"{}", &mut headers, now)
+            .unwrap();
+
+        let payload = format!(
+            "1700000000\nPOST\n/order?x=1\n{}",
+            hex(digest::digest(&digest::SHA256, b"{}").as_ref())
+        );
+        let expected = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, b"secret"), payload.as_bytes());
+        assert_eq!(headers["x-pmproxy-timestamp"], "1700000000");
+        assert_eq!(headers["x-pmproxy-signature"], hex(expected.as_ref()).as_str());
+    }
+
+    #[test]
+    fn test_invalid_definitions_rejected() {
+        let bad = |json: &str| UpstreamAuth::compile(serde_json::from_str(json).unwrap(), "test").is_err();
+        assert!(bad(r#"{"type": "header", "name": "bad name", "value": "v"}"#));
+        assert!(bad(r#"{"type": "hmac", "key": ""}"#));
+        assert!(bad(r#"{"type": "sigv4", "region": "us-east-1", "access_key_id": "AKID"}"#));
+        assert!(bad(r#"{"type": "bearer", "token": "${PMPROXY_TEST_UNSET_TOKEN}"}"#));
+    }
+}
@@ -0,0 +1,161 @@
+//! Warm-up for short-lived deployments (Lambda).
+//!
+//! A cold Lambda environment would otherwise pay for the JWKS fetch and the
+//! upstream TLS handshakes on its first authenticated request. [`ProxyState::warm`]
+//! does that work up front: the Lambda binary runs it during init (which
+//! provisioned concurrency completes before any traffic arrives) and again
+//! whenever it is invoked by a warmer event instead of an HTTP request:
+//!
+//! ```json
+//! {"warmer": true}
+//! ```
+//!
+//! An EventBridge schedule (`"source": "aws.events"`, `"detail-type": "Scheduled Event"`)
+//! is recognised as well, so a `rate(5 minutes)` rule targeting the function keeps an
+//! environment warm without a custom payload.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::ProxyState;
+
+/// Timeout for opening a connection to one upstream.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of warming one upstream.
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmedUpstream {
+    pub route: String,
+    pub upstream: String,
+    /// Whether the upstream answered (any status).
+    pub connected: bool,
+    pub latency_ms: u64,
+}
+
+/// Response to a warmer event.
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmReport {
+    /// Whether the JWKS cache holds fresh keys (None with auth disabled).
+    pub jwks_fresh: Option<bool>,
+    pub upstreams: Vec<WarmedUpstream>,
+    pub elapsed_ms: u64,
+}
+
+/// Whether a Lambda payload is a warmer event rather than an HTTP request.
+pub fn is_warmer_event(payload: &serde_json::Value) -> bool {
+    payload.get("warmer").and_then(|v| v.as_bool()) == Some(true)
+        || (payload.get("source").and_then(|v| v.as_str()) == Some("aws.events")
+            && payload.get("detail-type").and_then(|v| v.as_str()) == Some("Scheduled Event"))
+}
+
+impl ProxyState {
+    /// Refresh the JWKS cache if it is stale and open a connection to every
+    /// route's upstream through the client its requests will use.
+    pub async fn warm(&self) -> WarmReport {
+        let started = Instant::now();
+
+        let jwks = async {
+            let cache = self.jwks_cache.as_ref()?;
+            if let Err(e) = cache.refresh_if_stale().await {
+                debug!(error = %e, "JWKS refresh during warm-up failed");
+            }
+            Some(cache.status().await.fresh)
+        };
+        let mut connects = tokio::task::JoinSet::new();
+        for route in self.routes.iter() {
+            let client = self.client_for(route);
+            let (prefix, upstream) = (route.prefix.clone(), route.upstream.clone());
+            connects.spawn(async move {
+                let started = Instant::now();
+                let connected = match client {
+                    Ok(client) => client.head(&upstream).timeout(CONNECT_TIMEOUT).send().await.is_ok(),
+                    Err(_) => false,
+                };
+                WarmedUpstream {
+                    route: prefix,
+                    upstream,
+                    connected,
+                    latency_ms: started.elapsed().as_millis() as u64,
+                }
+            });
+        }
+        let (jwks_fresh, mut upstreams) = tokio::join!(jwks, connects.join_all());
+        upstreams.sort_by_key(|u| self.routes.iter().position(|r| r.prefix == u.route));
+
+        let report = WarmReport {
+            jwks_fresh,
+            upstreams,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        info!(
+            jwks_fresh = ?report.jwks_fresh,
+            connected = report.upstreams.iter().filter(|u| u.connected).count(),
+            upstreams = report.upstreams.len(),
+            elapsed_ms = report.elapsed_ms,
+            "Warmed up"
+        );
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProxyConfig;
+    use crate::routes::RouteTable;
+    use axum::{routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_warmer_events_are_recognised() {
+        let event = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+        assert!(is_warmer_event(&event(r#"{"warmer": true}"#)));
+        assert!(is_warmer_event(&event(
+            r#"{"source": "aws.events", "detail-type": "Scheduled Event", "detail": {}}"#
+        )));
+        assert!(!is_warmer_event(&event(r#"{"warmer": false}"#)));
+        assert!(!is_warmer_event(&event(
+            r#"{"version": "2.0", "rawPath": "/clob/book", "requestContext": {}}"#
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_warm_connects_to_every_upstream() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let upstream = Router::new().fallback(get(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            async { "ok" }
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        // A closed port stands in for an unreachable upstream
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_addr = closed.local_addr().unwrap();
+        drop(closed);
+
+        let routes = format!(
+            r#"[{{"prefix": "clob", "upstream": "http://{addr}"}},
+                {{"prefix": "slow", "upstream": "http://{addr}/api", "timeouts": {{"connect_ms": 500}}}},
+                {{"prefix": "down", "upstream": "http://{closed_addr}"}}]"#
+        );
+        let config = ProxyConfig {
+            routes: RouteTable::from_json(&routes).unwrap(),
+            ..ProxyConfig::for_tests(false)
+        };
+        let state = ProxyState::with_auth(&config).unwrap();
+
+        let report = state.warm().await;
+        assert_eq!(report.jwks_fresh, None);
+        let connected: Vec<_> = report.upstreams.iter().map(|u| (u.route.as_str(), u.connected)).collect();
+        assert_eq!(connected, vec![("clob", true), ("slow", true), ("down", false)]);
+        assert_eq!(hits.load(Ordering::Relaxed), 2);
+        // The route's own client was built during warm-up, not on its first request
+        assert_eq!(state.route_clients.len(), 1);
+    }
+}
@@ -0,0 +1,251 @@
+//! Per-tenant WebSocket connection and subscription accounting.
+//!
+//! The proxy does not relay WebSockets yet (`Upgrade` is stripped, see
+//! [`crate::hop`]); this is the accounting a relay will hold its connections
+//! against. Each tier may cap a tenant's open connections
+//! (`max_websocket_connections`) and the number of assets subscribed across
+//! all of them (`max_subscribed_assets`):
+//!
+//! ```json
+//! {"name": "free", "requests_per_minute": 60, "burst_size": 10,
+//!  "max_websocket_connections": 2, "max_subscribed_assets": 50}
+//! ```
+//!
+//! A connection is a [`WebSocketSession`] that releases its slot and its
+//! subscriptions when dropped. Limits are read from the tier table on every
+//! connect and subscribe, so a reload applies to existing connections' next
+//! subscriptions. Usage is reported per tenant at `/whoami` and for all
+//! tenants at `GET /admin/websockets`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::config::TenantTier;
+use crate::error::AuthError;
+use crate::tiers::TierRegistry;
+
+/// A tenant's open connections and subscribed assets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WebSocketUsage {
+    pub connections: u32,
+    /// Assets subscribed, summed over the tenant's connections.
+    pub subscribed_assets: u32,
+}
+
+/// Usage of one tenant, as reported at `GET /admin/websockets`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TenantWebSocketUsage {
+    pub tenant_id: String,
+    #[serde(flatten)]
+    pub usage: WebSocketUsage,
+}
+
+/// Per-tenant WebSocket limiter.
+pub struct WebSocketLimiter {
+    /// Map of tenant_id -> current usage.
+    tenants: DashMap<String, WebSocketUsage>,
+    /// Tier definitions (hot-reloadable).
+    tiers: TierRegistry,
+}
+
+impl WebSocketLimiter {
+    pub fn new(tiers: TierRegistry) -> Self {
+        Self {
+            tenants: DashMap::new(),
+            tiers,
+        }
+    }
+
+    /// Open a connection for a tenant, or `Err(AuthError::TooManyWebSocketConnections)`
+    /// if its tier's connection cap is reached.
+    pub fn connect(self: &Arc<Self>, tenant_id: &str, tier: TenantTier) -> Result<WebSocketSession, AuthError> {
+        let limit = self.tiers.snapshot().get(tier).max_websocket_connections;
+        let mut usage = self.tenants.entry(tenant_id.to_string()).or_default();
+        if limit.is_some_and(|limit| usage.connections >= limit) {
+            debug!(tenant_id = %tenant_id, limit = ?limit, "WebSocket connection limit exceeded");
+            return Err(AuthError::TooManyWebSocketConnections);
+        }
+        usage.connections += 1;
+        Ok(WebSocketSession {
+            limiter: self.clone(),
+            tenant_id: tenant_id.to_string(),
+            tier,
+            assets: HashSet::new(),
+        })
+    }
+
+    /// Current usage of a tenant.
+    pub fn usage(&self, tenant_id: &str) -> WebSocketUsage {
+        self.tenants.get(tenant_id).map(|u| *u).unwrap_or_default()
+    }
+
+    /// Usage of every tenant with an open connection, ordered by tenant.
+    pub fn report(&self) -> Vec<TenantWebSocketUsage> {
+        let mut report: Vec<TenantWebSocketUsage> = self
+            .tenants
+            .iter()
+            .map(|entry| TenantWebSocketUsage {
+                tenant_id: entry.key().clone(),
+                usage: *entry.value(),
+            })
+            .collect();
+        report.sort_by(|a, b| a.tenant_id.cmp(&b.tenant_id));
+        report
+    }
+
+    /// Get the number of tenants with open connections (for monitoring).
+    pub fn tenant_count(&self) -> usize {
+        self.tenants.len()
+    }
+
+    fn release(&self, tenant_id: &str, assets: u32) {
+        self.tenants.remove_if_mut(tenant_id, |_, usage| {
+            usage.connections = usage.connections.saturating_sub(1);
+            usage.subscribed_assets = usage.subscribed_assets.saturating_sub(assets);
+            usage.connections == 0
+        });
+    }
+}
+
+/// An open WebSocket connection of a tenant.
+pub struct WebSocketSession {
+    limiter: Arc<WebSocketLimiter>,
+    tenant_id: String,
+    tier: TenantTier,
+    assets: HashSet<String>,
+}
+
+impl WebSocketSession {
+    /// Subscribe to assets, returning how many were new to this connection.
+    ///
+    /// All or nothing: if the new assets would take the tenant over its tier's
+    /// `max_subscribed_assets`, none are added and
+    /// `Err(AuthError::SubscriptionLimitExceeded)` is returned.
+    pub fn subscribe<'a>(&mut self, assets: impl IntoIterator<Item = &'a str>) -> Result<u32, AuthError> {
+        let new: HashSet<&str> = assets.into_iter().filter(|a| !self.assets.contains(*a)).collect();
+        if new.is_empty() {
+            return Ok(0);
+        }
+        let added = new.len() as u32;
+
+        let limit = self.limiter.tiers.snapshot().get(self.tier).max_subscribed_assets;
+        let mut usage = self.limiter.tenants.entry(self.tenant_id.clone()).or_default();
+        if let Some(limit) = limit {
+            if usage.subscribed_assets + added > limit {
+                debug!(tenant_id = %self.tenant_id, limit = limit, "WebSocket subscription limit exceeded");
+                return Err(AuthError::SubscriptionLimitExceeded { limit });
+            }
+        }
+        usage.subscribed_assets += added;
+        self.assets.extend(new.into_iter().map(str::to_string));
+        Ok(added)
+    }
+
+    /// Unsubscribe from assets, returning how many this connection had.
+    pub fn unsubscribe<'a>(&mut self, assets: impl IntoIterator<Item = &'a str>) -> u32 {
+        let removed = assets.into_iter().filter(|a| self.assets.remove(*a)).count() as u32;
+        if removed > 0 {
+            if let Some(mut usage) = self.limiter.tenants.get_mut(&self.tenant_id) {
+                usage.subscribed_assets = usage.subscribed_assets.saturating_sub(removed);
+            }
+        }
+        removed
+    }
+
+    /// Number of assets this connection is subscribed to.
+    pub fn subscribed(&self) -> usize {
+        self.assets.len()
+    }
+}
+
+impl Drop for WebSocketSession {
+    fn drop(&mut self) {
+        self.limiter.release(&self.tenant_id, self.assets.len() as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiers::TierTable;
+
+    fn limiter() -> (Arc<WebSocketLimiter>, TierRegistry) {
+        let table = TierTable::from_json(
+            r#"[{"name": "free", "rpm": 60, "burst": 10,
+                 "max_websocket_connections": 2, "max_subscribed_assets": 3},
+                {"name": "unlimited", "rpm": 60, "burst": 10}]"#,
+        )
+        .unwrap();
+        let registry = TierRegistry::new(table);
+        (Arc::new(WebSocketLimiter::new(registry.clone())), registry)
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let (limiter, registry) = limiter();
+
+        let first = limiter.connect("t1", TenantTier::DEFAULT).unwrap();
+        let _second = limiter.connect("t1", TenantTier::DEFAULT).unwrap();
+        assert!(matches!(
+            limiter.connect("t1", TenantTier::DEFAULT),
+            Err(AuthError::TooManyWebSocketConnections)
+        ));
+        assert!(limiter.connect("t2", TenantTier::DEFAULT).is_ok());
+
+        // Closing a connection frees its slot
+        drop(first);
+        assert_eq!(limiter.usage("t1").connections, 1);
+        assert!(limiter.connect("t1", TenantTier::DEFAULT).is_ok());
+
+        // Uncapped tiers are still counted
+        let unlimited = registry.snapshot().resolve("unlimited");
+        let sessions: Vec<_> = (0..5).map(|_| limiter.connect("t3", unlimited).unwrap()).collect();
+        assert_eq!(limiter.usage("t3").connections, 5);
+        drop(sessions);
+        assert_eq!(limiter.usage("t3"), WebSocketUsage::default());
+        assert_eq!(limiter.tenant_count(), 1);
+    }
+
+    #[test]
+    fn test_subscription_limit_spans_connections() {
+        let (limiter, _) = limiter();
+        let mut first = limiter.connect("t1", TenantTier::DEFAULT).unwrap();
+        let mut second = limiter.connect("t1", TenantTier::DEFAULT).unwrap();
+
+        assert_eq!(first.subscribe(["a", "b"]).unwrap(), 2);
+        // Already subscribed on this connection: not counted again
+        assert_eq!(first.subscribe(["a"]).unwrap(), 0);
+        assert!(matches!(
+            second.subscribe(["c", "d"]),
+            Err(AuthError::SubscriptionLimitExceeded { limit: 3 })
+        ));
+        assert_eq!(second.subscribed(), 0);
+        assert_eq!(second.subscribe(["a"]).unwrap(), 1);
+        assert_eq!(
+            limiter.usage("t1"),
+            WebSocketUsage {
+                connections: 2,
+                subscribed_assets: 3
+            }
+        );
+
+        assert_eq!(first.unsubscribe(["b", "z"]), 1);
+        assert_eq!(second.subscribe(["c"]).unwrap(), 1);
+
+        // Closing a connection releases its subscriptions
+        drop(first);
+        let report = limiter.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(
+            report[0].usage,
+            WebSocketUsage {
+                connections: 1,
+                subscribed_assets: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Tenant introspection (`GET /whoami`).
+//!
+//! Lets a client check its token and see the tier, limits and remaining quota
+//! that apply to it. Only available with authentication enabled. The call
+//! validates the token like any proxied request but does not count against
+//! the rate limit or quotas.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::auth::{extract_bearer_token, AuthenticatedTenant, CognitoClaims};
+use crate::error::ProxyError;
+use crate::quota::TenantUsage;
+use crate::tiers::TierDefinition;
+use crate::websocket::WebSocketUsage;
+use crate::{check_client_ip, ProxyState};
+
+/// Limits of the tenant's tier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TierLimits {
+    pub requests_per_minute: u32,
+    pub burst_size: u32,
+    pub daily_quota: Option<u64>,
+    pub monthly_quota: Option<u64>,
+    pub max_concurrent_requests: Option<u32>,
+    pub max_websocket_connections: Option<u32>,
+    pub max_subscribed_assets: Option<u32>,
+}
+
+/// Quota consumption in the current UTC day and month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaStatus {
+    pub daily_used: u64,
+    /// None = no daily quota.
+    pub daily_remaining: Option<u64>,
+    pub monthly_used: u64,
+    /// None = no monthly quota.
+    pub monthly_remaining: Option<u64>,
+}
+
+/// Body of `GET /whoami`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WhoAmI {
+    pub tenant_id: String,
+    pub tier: String,
+    /// Cognito groups, which decide the routes the tenant may use.
+    pub groups: Vec<String>,
+    pub limits: TierLimits,
+    pub quota: QuotaStatus,
+    /// Unexpired burst credits.
+    pub burst_credits: u64,
+    /// Open WebSocket connections and subscribed assets.
+    pub websockets: WebSocketUsage,
+    /// Unix time (seconds) the token expires.
+    pub token_expires_at: u64,
+    pub token_expires_in_secs: u64,
+}
+
+impl WhoAmI {
+    fn build(
+        tenant: AuthenticatedTenant,
+        tier: &TierDefinition,
+        token_expires_at: u64,
+        usage: TenantUsage,
+        burst_credits: u64,
+        websockets: WebSocketUsage,
+        now: SystemTime,
+    ) -> Self {
+        let now_secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        Self {
+            tenant_id: tenant.tenant_id,
+            tier: tenant.tier_name,
+            groups: tenant.groups,
+            limits: TierLimits {
+                requests_per_minute: tier.requests_per_minute,
+                burst_size: tier.burst_size,
+                daily_quota: tier.daily_quota,
+                monthly_quota: tier.monthly_quota,
+                max_concurrent_requests: tier.max_concurrent_requests,
+                max_websocket_connections: tier.max_websocket_connections,
+                max_subscribed_assets: tier.max_subscribed_assets,
+            },
+            quota: QuotaStatus {
+                daily_used: usage.daily,
+                daily_remaining: tier.daily_quota.map(|q| q.saturating_sub(usage.daily)),
+                monthly_used: usage.monthly,
+                monthly_remaining: tier.monthly_quota.map(|q| q.saturating_sub(usage.monthly)),
+            },
+            burst_credits,
+            websockets,
+            token_expires_at,
+            token_expires_in_secs: token_expires_at.saturating_sub(now_secs),
+        }
+    }
+}
+
+/// `GET /whoami` - the caller's tenant, tier, limits and remaining quota.
+pub async fn whoami_handler(State(state): State<Arc<ProxyState>>, req: Request) -> Response {
+    if !state.auth_enabled {
+        return ProxyError::NotFound { path: "/whoami".to_string() }.into_response();
+    }
+
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    if let Err(e) = check_client_ip(&state, req.headers(), peer) {
+        return e.into_response();
+    }
+
+    let auth_header = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    let claims: CognitoClaims = match extract_bearer_token(auth_header) {
+        Ok(token) => match state.jwks_cache.as_ref() {
+            Some(jwks) => match jwks.validate_token(token).await {
+                Ok(claims) => claims,
+                Err(e) => return e.into_response(),
+            },
+            None => return ProxyError::NotFound { path: "/whoami".to_string() }.into_response(),
+        },
+        Err(e) => return e.into_response(),
+    };
+
+    let tiers = state.tiers.snapshot();
+    let token_expires_at = claims.exp;
+    let tenant = AuthenticatedTenant::from_claims(claims, &tiers);
+    let usage = state
+        .quota
+        .as_ref()
+        .map(|q| q.usage(&tenant.tenant_id))
+        .unwrap_or_default();
+    let credits = state
+        .credits
+        .as_ref()
+        .map_or(0, |c| c.balance(&tenant.tenant_id).remaining);
+    let websockets = state
+        .websockets
+        .as_ref()
+        .map(|w| w.usage(&tenant.tenant_id))
+        .unwrap_or_default();
+
+    let tier = tiers.get(tenant.tier);
+    let whoami = WhoAmI::build(tenant, tier, token_expires_at, usage, credits, websockets, SystemTime::now());
+    Json(whoami).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiers::TenantTier;
+    use std::time::Duration;
+
+    #[test]
+    fn test_whoami_reports_limits_and_remaining_quota() {
+        let tier = TierDefinition {
+            name: "team".to_string(),
+            requests_per_minute: 120,
+            burst_size: 20,
+            daily_quota: Some(1_000),
+            monthly_quota: None,
+            max_concurrent_requests: Some(8),
+            max_queue_wait_ms: None,
+            queue_weight: 1,
+            max_websocket_connections: Some(2),
+            max_subscribed_assets: None,
+        };
+        let tenant = AuthenticatedTenant {
+            tenant_id: "tenant-1".to_string(),
+            tier: TenantTier::DEFAULT,
+            tier_name: "team".to_string(),
+            groups: vec!["traders".to_string()],
+        };
+        let usage = TenantUsage {
+            daily: 1_200,
+            monthly: 5_000,
+            ..Default::default()
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        let websockets = WebSocketUsage {
+            connections: 1,
+            subscribed_assets: 20,
+        };
+
+        let whoami = WhoAmI::build(tenant, &tier, 4_600, usage, 50, websockets, now);
+        assert_eq!(whoami.tier, "team");
+        assert_eq!(whoami.groups, vec!["traders"]);
+        assert_eq!(whoami.limits.max_concurrent_requests, Some(8));
+        assert_eq!(whoami.quota.daily_remaining, Some(0));
+        assert_eq!(whoami.quota.monthly_remaining, None);
+        assert_eq!(whoami.quota.monthly_used, 5_000);
+        assert_eq!(whoami.burst_credits, 50);
+        assert_eq!(whoami.limits.max_websocket_connections, Some(2));
+        assert_eq!(whoami.websockets.subscribed_assets, 20);
+        assert_eq!(whoami.token_expires_in_secs, 3_600);
+    }
+}
@@ -1,7 +1,7 @@
 """pmstrat - Strategy DSL and backtesting for Polymarket."""
 
-from .signal import Signal, Buy, Sell, Cancel, Hold, Shutdown, Urgency
-from .context import Context, OrderBookSnapshot, Position, MarketInfo
+from .signal import Signal, Buy, Sell, Cancel, Replace, Hold, ReduceOnly, Exit, Shutdown, Urgency
+from .context import Context, OrderBookSnapshot, Position, MarketInfo, MarketStats
 from .dsl import strategy
 from .rewards import RewardsSimulator, MarketRewardConfig
 from .transpile import (
@@ -20,13 +20,17 @@ __all__ = [
     "Buy",
     "Sell",
     "Cancel",
+    "Replace",
     "Hold",
+    "ReduceOnly",
+    "Exit",
     "Shutdown",
     "Urgency",
     "Context",
     "OrderBookSnapshot",
     "Position",
     "MarketInfo",
+    "MarketStats",
     "strategy",
     "RewardsSimulator",
     "MarketRewardConfig",
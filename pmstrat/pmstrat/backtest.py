@@ -1,14 +1,16 @@
 """Simple backtest runner for strategies."""
 
-from dataclasses import dataclass, field
+from dataclasses import dataclass, field, replace
 from decimal import Decimal
 from datetime import datetime, timedelta
 from typing import Callable, Iterator
 import json
 
-from .signal import Signal, Buy, Sell, Hold
+from .signal import Signal, Buy, Sell, Exit, Hold, ReduceOnly, Replace
 from .context import Context, OrderBookSnapshot, Position, MarketInfo
 from .rewards import RewardsSimulator, Order, EpochReward
+from .risk import RiskLimits, RiskDecision, check_order
+from .fees import fee
 
 
 @dataclass
@@ -20,6 +22,7 @@ class Fill:
     size: Decimal
     timestamp: datetime
     slippage: Decimal = Decimal(0)
+    fee: Decimal = Decimal(0)
 
 
 @dataclass
@@ -35,8 +38,10 @@ class BacktestResult:
     estimated_rewards: Decimal
     total_return: Decimal  # P&L + rewards
     win_rate: float
+    total_fees: Decimal = Decimal(0)  # Already taken out of P&L
     fills: list[Fill] = field(default_factory=list)
     positions: dict[str, Position] = field(default_factory=dict)
+    risk_decisions: list[RiskDecision] = field(default_factory=list)
 
     def summary(self) -> str:
         """Human-readable summary."""
@@ -51,6 +56,7 @@ P&L:
   Realized: ${self.realized_pnl:.2f}
   Unrealized: ${self.unrealized_pnl:.2f}
   Total P&L: ${self.total_pnl:.2f}
+  Fees: ${self.total_fees:.2f}
 
 Rewards:
   Estimated: ${self.estimated_rewards:.2f}
@@ -83,6 +89,9 @@ class Backtester:
         strategy_fn: Callable[[Context], list[Signal]],
         initial_balance: Decimal = Decimal("1000"),
         slippage_bps: Decimal = Decimal("10"),  # 0.1% default slippage
+        risk_limits: RiskLimits | None = None,
+        exit_before_expiry: timedelta | None = None,
+        fee_rate_bps: Decimal = Decimal(0),
     ):
         """Initialize backtester.
 
@@ -90,16 +99,33 @@ class Backtester:
             strategy_fn: Strategy function decorated with @strategy
             initial_balance: Starting USDC balance
             slippage_bps: Slippage in basis points (10 = 0.1%)
+            risk_limits: Run orders through pmengine's risk checks and
+                journal each decision (None = no risk checks)
+            exit_before_expiry: Sell positions this long before their
+                market's end date, like PMENGINE_EXIT_BEFORE_EXPIRY_SECS
+                (None = hold to resolution)
+            fee_rate_bps: Taker fee rate; every simulated fill takes
+                liquidity, so every fill pays it
         """
         self.strategy_fn = strategy_fn
         self.initial_balance = initial_balance
         self.slippage_pct = slippage_bps / Decimal("10000")
+        self.risk_limits = risk_limits
+        self.exit_before_expiry = exit_before_expiry
+        self.fee_rate_bps = fee_rate_bps
 
         # State
         self.balance = initial_balance
         self.positions: dict[str, Position] = {}
         self.fills: list[Fill] = []
         self.rewards_sim = RewardsSimulator()
+        self.risk_decisions: list[RiskDecision] = []
+        # Set by ReduceOnly signals, like the engine's manual reduce-only mode
+        self.reduce_only = False
+        # Exit rules attached by Exit signals, by token
+        self.exit_rules: dict[str, Exit] = {}
+        # Tokens held since their rule was attached
+        self._exits_held: set[str] = set()
 
         # Track orders for reward simulation
         self.resting_orders: list[Order] = []
@@ -155,11 +181,30 @@ class Backtester:
                 usdc_balance=self.balance,
             )
 
+            # Exit rules act before the strategy, like the engine's
+            self._check_exits(books, markets, tick.timestamp)
+
             # Run strategy
             signals = self.strategy_fn(ctx)
 
-            # Execute signals
+            # Execute signals; resting orders aren't simulated, so a replace
+            # places all of its orders
+            signals = [o for s in signals for o in (s.orders if isinstance(s, Replace) else [s])]
             for signal in signals:
+                if isinstance(signal, ReduceOnly):
+                    self.reduce_only = signal.enabled
+                    continue
+                if isinstance(signal, Exit):
+                    if signal.stop_loss is None and signal.take_profit is None and signal.time_stop is None:
+                        self.exit_rules.pop(signal.token_id, None)
+                    else:
+                        self.exit_rules[signal.token_id] = signal
+                    self._exits_held.discard(signal.token_id)
+                    continue
+                if self.risk_limits is not None and isinstance(signal, (Buy, Sell)):
+                    signal = self._check_risk(signal, books, tick.timestamp)
+                    if signal is None:
+                        continue
                 self._execute_signal(signal, books, tick.timestamp)
 
             # Check for resolved markets (price hits 1.00 or 0.00)
@@ -188,9 +233,128 @@ class Backtester:
             estimated_rewards=estimated_rewards,
             total_return=total_pnl + estimated_rewards,
             win_rate=win_rate,
+            total_fees=sum((p.fees for p in self.positions.values()), Decimal(0)),
             fills=self.fills.copy(),
             positions=self.positions.copy(),
+            risk_decisions=self.risk_decisions.copy(),
+        )
+
+    def write_risk_journal(self, path: str) -> None:
+        """Write risk decisions as JSONL, in pmengine's risk journal schema."""
+        with open(path, "w") as f:
+            for decision in self.risk_decisions:
+                f.write(json.dumps(decision.to_json()) + "\n")
+
+    def _check_risk(
+        self,
+        signal: Buy | Sell,
+        books: dict[str, OrderBookSnapshot],
+        timestamp: datetime,
+    ) -> Buy | Sell | None:
+        """Run an order through the risk checks; returns the signal to execute."""
+        side = "BUY" if isinstance(signal, Buy) else "SELL"
+        book = books.get(signal.token_id)
+        reference_price = book.mid_price if book is not None else None
+        # Snapshots only show the top of the book
+        book_depth = None
+        if book is not None:
+            depth = book.ask_size if side == "BUY" else book.bid_size
+            book_depth = depth if depth > 0 else None
+        position = self.positions.get(signal.token_id)
+        position_notional = sum(
+            (abs(p.size) * (p.last_price if p.last_price is not None else p.avg_entry_price)
+             for p in self.positions.values()),
+            Decimal(0),
+        )
+        position_size = position.size if position is not None else None
+        # Without market info every token is a market of its own
+        market_exposure = Decimal(0)
+        if position is not None:
+            price = position.last_price if position.last_price is not None else position.avg_entry_price
+            market_exposure = abs(position.size) * price
+
+        decision, approved_size, reason = check_order(
+            side,
+            signal.price,
+            signal.size,
+            position_size,
+            position_notional,
+            Decimal(0),  # Backtest orders fill immediately; nothing rests
+            False,
+            self.risk_limits,
+            reference_price,
+            book_depth,
+            reduce_only=self.reduce_only,
+            market_exposure=market_exposure,
         )
+        self.risk_decisions.append(RiskDecision(
+            timestamp=timestamp,
+            source="backtest",
+            token_id=signal.token_id,
+            side=side,
+            price=signal.price,
+            size=signal.size,
+            position_size=position_size,
+            reference_price=reference_price,
+            book_depth=book_depth,
+            market_exposure=market_exposure,
+            position_notional=position_notional,
+            open_order_notional=Decimal(0),
+            halted=False,
+            reduce_only=self.reduce_only,
+            limits=self.risk_limits,
+            decision=decision,
+            approved_size=approved_size,
+            reason=reason,
+        ))
+
+        if approved_size is None:
+            return None
+        return replace(signal, size=approved_size)
+
+    def _check_exits(
+        self,
+        books: dict[str, OrderBookSnapshot],
+        markets: dict[str, MarketInfo],
+        timestamp: datetime,
+    ):
+        """Sell the long positions whose exit rule triggers at the best bid, or
+        whose market ends within `exit_before_expiry`."""
+        for token_id in list(self.exit_rules):
+            pos = self.positions.get(token_id)
+            if pos is not None and pos.size > 0:
+                self._exits_held.add(token_id)
+            elif token_id in self._exits_held:
+                # The rule goes with the position it was attached to
+                del self.exit_rules[token_id]
+                self._exits_held.discard(token_id)
+
+        for token_id, pos in list(self.positions.items()):
+            book = books.get(token_id)
+            bid = book.best_bid if book is not None else None
+            if pos.size <= 0 or bid is None:
+                continue
+            rule = self.exit_rules.get(token_id)
+            end_date = markets[token_id].end_date if token_id in markets else None
+            triggered = (
+                rule is not None and (
+                    (rule.stop_loss is not None and bid <= rule.stop_loss)
+                    or (rule.take_profit is not None and bid >= rule.take_profit)
+                    or (rule.time_stop is not None and timestamp >= rule.time_stop)
+                )
+            ) or (
+                self.exit_before_expiry is not None
+                and end_date is not None
+                and timestamp >= end_date - self.exit_before_expiry
+            )
+            if not triggered:
+                continue
+            signal = Sell(token_id=token_id, price=bid, size=pos.size)
+            if self.risk_limits is not None:
+                signal = self._check_risk(signal, books, timestamp)
+                if signal is None:
+                    continue
+            self._execute_signal(signal, books, timestamp)
 
     def _execute_signal(
         self,
@@ -206,6 +370,9 @@ class Backtester:
             book = books.get(signal.token_id)
             if book is None or book.best_ask is None:
                 return None
+            # The exchange rejects a post-only order that would take liquidity
+            if signal.post_only and signal.price >= book.best_ask:
+                return None
 
             # Apply slippage
             fill_price = book.best_ask * (Decimal("1") + self.slippage_pct)
@@ -221,8 +388,9 @@ class Backtester:
                 return None
 
             # Execute
+            fill_fee = fee(self.fee_rate_bps, fill_price, fill_size)
             self.balance -= cost
-            self._update_position(signal.token_id, fill_size, fill_price)
+            self._update_position(signal.token_id, fill_size, fill_price, fill_fee)
 
             fill = Fill(
                 token_id=signal.token_id,
@@ -231,6 +399,7 @@ class Backtester:
                 size=fill_size,
                 timestamp=timestamp,
                 slippage=fill_price - book.best_ask,
+                fee=fill_fee,
             )
             self.fills.append(fill)
             return fill
@@ -240,6 +409,9 @@ class Backtester:
             if book is None or book.best_bid is None:
                 return None
 
+            if signal.post_only and signal.price <= book.best_bid:
+                return None
+
             position = self.positions.get(signal.token_id)
             if position is None or position.size <= 0:
                 return None
@@ -252,9 +424,10 @@ class Backtester:
                 return None
 
             # Execute
+            fill_fee = fee(self.fee_rate_bps, fill_price, fill_size)
             proceeds = fill_price * fill_size
             self.balance += proceeds
-            self._update_position(signal.token_id, -fill_size, fill_price)
+            self._update_position(signal.token_id, -fill_size, fill_price, fill_fee)
 
             fill = Fill(
                 token_id=signal.token_id,
@@ -263,14 +436,17 @@ class Backtester:
                 size=fill_size,
                 timestamp=timestamp,
                 slippage=book.best_bid - fill_price,
+                fee=fill_fee,
             )
             self.fills.append(fill)
             return fill
 
         return None
 
-    def _update_position(self, token_id: str, size_delta: Decimal, price: Decimal):
-        """Update position after a fill."""
+    def _update_position(
+        self, token_id: str, size_delta: Decimal, price: Decimal, fill_fee: Decimal
+    ):
+        """Update position after a fill, paying its fee."""
         if token_id not in self.positions:
             self.positions[token_id] = Position(token_id=token_id)
 
@@ -292,6 +468,10 @@ class Backtester:
             pos.realized_pnl += realized
 
         pos.size = new_size
+        # Fees are realized when paid, like pmengine's
+        self.balance -= fill_fee
+        pos.fees += fill_fee
+        pos.realized_pnl -= fill_fee
 
     def _check_resolutions(self, books: dict[str, OrderBookSnapshot], timestamp: datetime):
         """Check if any positions have resolved (price = 1.00 or 0.00)."""
@@ -28,6 +28,8 @@ def main():
         run_transpile(sys.argv[2:])
     elif command == "lint":
         run_lint(sys.argv[2:])
+    elif command == "parity":
+        run_parity(sys.argv[2:])
     else:
         console.print(f"[red]Unknown command: {command}[/red]")
         print_usage()
@@ -43,6 +45,7 @@ def print_usage():
   backtest <strategy.py> [--data FILE]   Run backtest on strategy
   scan                                   Scan for sure_bets opportunities (live)
   simulate [--ticks N]                   Run strategy on synthetic data
+  parity <journal.jsonl>                 Diff live risk decisions against backtest checks
 
 [bold]Transpile Examples:[/bold]
   pmstrat transpile sure_bets            Transpile single strategy
@@ -57,6 +60,7 @@ def print_usage():
   pmstrat backtest strategies/sure_bets.py
   pmstrat scan
   pmstrat simulate --ticks 1000
+  pmstrat parity risk.jsonl
 """)
 
 
@@ -569,5 +573,51 @@ def run_simulate(args: list[str]):
     run_backtest(["--ticks", str(num_ticks)])
 
 
+def run_parity(args: list[str]):
+    """Replay a live risk journal through the backtester's risk checks."""
+    from .risk import load_journal, replay_journal
+
+    if not args:
+        console.print("[red]Usage: pmstrat parity <journal.jsonl>[/red]")
+        sys.exit(2)
+
+    path = Path(args[0])
+    if not path.exists():
+        console.print(f"[red]Journal not found: {path}[/red]")
+        sys.exit(2)
+
+    live = (d for d in load_journal(path) if d.source == "live")
+    count, mismatches = replay_journal(live)
+
+    if not mismatches:
+        console.print(f"[green]✓ {count} live decisions match the backtest risk checks[/green]")
+        return
+
+    table = Table(title=f"{len(mismatches)} of {count} decisions differ")
+    table.add_column("Time", style="dim")
+    table.add_column("Token", style="cyan")
+    table.add_column("Side")
+    table.add_column("Price", justify="right")
+    table.add_column("Size", justify="right")
+    table.add_column("Live")
+    table.add_column("Backtest")
+
+    outcome = lambda d: d.decision if d.approved_size is None else f"{d.decision} {d.approved_size:.4f}"
+    for mismatch in mismatches:
+        live_decision = mismatch.live
+        table.add_row(
+            live_decision.timestamp.strftime("%H:%M:%S"),
+            live_decision.token_id[:16] + "...",
+            live_decision.side,
+            f"{live_decision.price:.3f}",
+            f"{live_decision.size:.2f}",
+            outcome(live_decision),
+            outcome(mismatch.backtest),
+        )
+
+    console.print(table)
+    sys.exit(1)
+
+
 if __name__ == "__main__":
     main()
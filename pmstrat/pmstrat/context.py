@@ -5,6 +5,8 @@ from decimal import Decimal
 from datetime import datetime
 from typing import Optional
 
+from .sizing import DEFAULT_KELLY_FRACTION, kelly_size
+
 
 @dataclass
 class OrderBookSnapshot:
@@ -27,6 +29,25 @@ class OrderBookSnapshot:
             return self.best_ask - self.best_bid
         return None
 
+    @property
+    def microprice(self) -> Optional[Decimal]:
+        """Mid weighted towards the side with less size at the top."""
+        if self.best_bid is None or self.best_ask is None:
+            return None
+        size = self.bid_size + self.ask_size
+        if size == 0:
+            return self.mid_price
+        return (self.best_bid * self.ask_size + self.best_ask * self.bid_size) / size
+
+
+@dataclass
+class MarketStats:
+    """Rolling statistics of a token's recent book updates and trades."""
+    volatility: Optional[Decimal] = None  # Std dev of mid changes between updates
+    order_flow_imbalance: Optional[Decimal] = None  # Net top-of-book flow, in shares
+    mid_return: Optional[Decimal] = None  # Return of the mid over the window
+    trade_imbalance: Optional[Decimal] = None  # (buys - sells) / volume, -1 to 1
+
 
 @dataclass
 class Position:
@@ -37,6 +58,7 @@ class Position:
     unrealized_pnl: Decimal = Decimal(0)
     realized_pnl: Decimal = Decimal(0)
     last_price: Optional[Decimal] = None  # Matches Rust Position
+    fees: Decimal = Decimal(0)  # Already taken out of realized_pnl
 
 
 @dataclass
@@ -48,6 +70,8 @@ class MarketInfo:
     slug: str = ""
     end_date: Optional[datetime] = None
     liquidity: Optional[float] = None
+    event_slug: Optional[str] = None  # Parent event (groups multi-outcome baskets)
+    neg_risk: bool = False  # Event outcomes are mutually exclusive
 
     @property
     def hours_until_expiry(self) -> Optional[float]:
@@ -67,6 +91,8 @@ class Context:
     total_realized_pnl: Decimal = Decimal(0)
     total_unrealized_pnl: Decimal = Decimal(0)
     usdc_balance: Decimal = Decimal(0)
+    kelly_fraction: Decimal = DEFAULT_KELLY_FRACTION  # Of the full Kelly stake kelly_size sizes to
+    stats: dict[str, MarketStats] = field(default_factory=dict)
 
     def book(self, token_id: str) -> Optional[OrderBookSnapshot]:
         """Get order book for a token."""
@@ -85,6 +111,41 @@ class Context:
         book = self.book(token_id)
         return book.mid_price if book else None
 
+    def volatility(self, token_id: str) -> Optional[Decimal]:
+        """Get realized mid-price volatility for a token."""
+        stats = self.stats.get(token_id)
+        return stats.volatility if stats else None
+
+    def order_flow_imbalance(self, token_id: str) -> Optional[Decimal]:
+        """Get net top-of-book order flow for a token."""
+        stats = self.stats.get(token_id)
+        return stats.order_flow_imbalance if stats else None
+
+    def mid_return(self, token_id: str) -> Optional[Decimal]:
+        """Get the return of the mid price over recent book updates for a token."""
+        stats = self.stats.get(token_id)
+        return stats.mid_return if stats else None
+
+    def trade_imbalance(self, token_id: str) -> Optional[Decimal]:
+        """Get buy versus sell volume of recent trades for a token."""
+        stats = self.stats.get(token_id)
+        return stats.trade_imbalance if stats else None
+
+    @property
+    def equity(self) -> Decimal:
+        """USDC balance plus the marked value of held positions."""
+        held = sum(
+            (abs(p.size) * (p.last_price if p.last_price is not None else p.avg_entry_price)
+             for p in self.positions.values()),
+            Decimal(0),
+        )
+        return self.usdc_balance + held
+
+    def kelly_size(self, probability: Decimal, price: Decimal) -> Decimal:
+        """Shares to hold when buying at `price` with an estimated win
+        `probability`: fractional Kelly on the equity."""
+        return kelly_size(probability, price, self.equity, self.kelly_fraction)
+
     @property
     def total_pnl(self) -> Decimal:
         """Total P&L (realized + unrealized)."""
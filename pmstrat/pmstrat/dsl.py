@@ -26,6 +26,7 @@ def strategy(
     tick_interval_ms: int = 1000,
     params: dict[str, Any] | None = None,
     transpilable: bool = True,
+    on_fill: Callable[[Context, Any], None] | None = None,
 ):
     """Decorator to define a strategy.
 
@@ -40,6 +41,8 @@ def strategy(
         tick_interval_ms: How often to call on_tick (in milliseconds)
         params: Dictionary of strategy parameters (transpiled to Rust constants)
         transpilable: If False, this strategy won't be transpiled (for Python-only test strategies)
+        on_fill: Called with the last tick's context and each fill when the engine runs the
+            strategy as Python (not transpiled)
     """
     def decorator(func: Callable[[Context], List[Signal]]):
         @wraps(func)
@@ -52,6 +55,7 @@ def strategy(
             tokens=tokens or [],
             tick_interval_ms=tick_interval_ms,
             on_tick=func,
+            on_fill=on_fill,
             params=params or {},
             transpilable=transpilable,
         )
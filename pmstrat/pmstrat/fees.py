@@ -0,0 +1,15 @@
+"""Trading fees, mirroring pmengine's fees module.
+
+Polymarket charges the taker of a trade `rate * min(price, 1 - price)` per
+share, in USDC. Makers pay no fee.
+"""
+
+from decimal import Decimal
+
+
+def fee(rate_bps: Decimal, price: Decimal, size: Decimal) -> Decimal:
+    """Fee on a fill of `size` shares at `price`, at `rate_bps` basis points."""
+    if rate_bps <= 0:
+        return Decimal(0)
+    base = max(min(price, Decimal(1) - price), Decimal(0))
+    return rate_bps / Decimal(10000) * base * size
@@ -0,0 +1,367 @@
+"""Risk checks and decision journal, mirroring pmengine's RiskManager.
+
+The backtester runs order signals through the same checks as the live
+engine and records each outcome in the same JSONL schema as
+`PMENGINE_RISK_JOURNAL`. `replay_journal` re-runs a live journal's inputs
+through these checks, so `pmstrat parity <journal>` can show where the
+backtest would have decided differently.
+"""
+
+from dataclasses import dataclass
+from datetime import datetime
+from decimal import Decimal, ROUND_HALF_EVEN
+from pathlib import Path
+from typing import Iterator
+import json
+
+# pmengine rounds every quotient to 16 decimal places (safe_math::WORKING_SCALE)
+WORKING_SCALE = Decimal(1).scaleb(-16)
+
+# Approved sizes closer than this are treated as equal
+SIZE_TOLERANCE = Decimal("1e-9")
+
+
+@dataclass
+class RiskLimits:
+    """Order limits (defaults match pmengine's RiskLimits)."""
+    max_order_size: Decimal = Decimal("25")
+    max_position_size: Decimal = Decimal("50")
+    max_total_exposure: Decimal = Decimal("50")
+    max_market_exposure: Decimal | None = None  # None = unlimited
+    max_category_exposure: Decimal | None = None  # None = unlimited
+    max_open_orders: int = 10  # 0 = unlimited
+    max_open_orders_per_token: int = 6  # 0 = unlimited
+    min_price: Decimal = Decimal("0.001")
+    max_price: Decimal = Decimal("0.999")
+    max_price_deviation: Decimal | None = None  # None = unchecked
+    max_depth_ratio: Decimal | None = None  # None = unchecked
+
+    def to_json(self) -> dict:
+        optional = lambda d: None if d is None else str(d)
+        return {
+            "max_order_size": str(self.max_order_size),
+            "max_position_size": str(self.max_position_size),
+            "max_total_exposure": str(self.max_total_exposure),
+            "max_market_exposure": optional(self.max_market_exposure),
+            "max_category_exposure": optional(self.max_category_exposure),
+            "max_open_orders": self.max_open_orders,
+            "max_open_orders_per_token": self.max_open_orders_per_token,
+            "min_price": str(self.min_price),
+            "max_price": str(self.max_price),
+            "max_price_deviation": optional(self.max_price_deviation),
+            "max_depth_ratio": optional(self.max_depth_ratio),
+        }
+
+    @classmethod
+    def from_json(cls, data: dict) -> "RiskLimits":
+        # Journals written before the count and sanity checks lack their limits
+        optional = lambda v: None if v is None else Decimal(str(v))
+        return cls(
+            max_order_size=Decimal(data["max_order_size"]),
+            max_position_size=Decimal(data["max_position_size"]),
+            max_total_exposure=Decimal(data["max_total_exposure"]),
+            max_market_exposure=optional(data.get("max_market_exposure")),
+            max_category_exposure=optional(data.get("max_category_exposure")),
+            max_open_orders=data.get("max_open_orders", 0),
+            max_open_orders_per_token=data.get("max_open_orders_per_token", 0),
+            min_price=Decimal(str(data.get("min_price", "0"))),
+            max_price=Decimal(str(data.get("max_price", "1"))),
+            max_price_deviation=optional(data.get("max_price_deviation")),
+            max_depth_ratio=optional(data.get("max_depth_ratio")),
+        )
+
+
+@dataclass
+class RiskDecision:
+    """One risk check: inputs and outcome (one journal line)."""
+    timestamp: datetime
+    source: str  # "live" or "backtest"
+    token_id: str
+    side: str  # "BUY" or "SELL"
+    price: Decimal
+    size: Decimal
+    position_size: Decimal | None  # None = no position tracked
+    position_notional: Decimal
+    open_order_notional: Decimal
+    halted: bool
+    limits: RiskLimits
+    decision: str  # "approved", "reduced" or "rejected"
+    approved_size: Decimal | None  # None = rejected
+    reason: str | None = None
+    reduce_only: bool = False  # Only orders that shrink a position allowed
+    open_orders: int = 0  # Open orders (and pending reservations)
+    token_open_orders: int = 0  # Of those, orders in this token
+    reference_price: Decimal | None = None  # Mid (else last trade); None = unknown
+    book_depth: Decimal | None = None  # Depth the order trades against; None = unknown
+    hedge_size: Decimal = Decimal(0)  # Shares completing sets with held outcomes
+    market_exposure: Decimal = Decimal(0)  # Netted exposure of the order's market
+    category_exposure: Decimal | None = None  # Of its category; None = unknown
+
+    def to_json(self) -> dict:
+        optional = lambda d: None if d is None else str(d)
+        return {
+            "timestamp": self.timestamp.isoformat(),
+            "source": self.source,
+            "token_id": self.token_id,
+            "side": self.side,
+            "price": str(self.price),
+            "size": str(self.size),
+            "position_size": optional(self.position_size),
+            "reduce_only": self.reduce_only,
+            "open_orders": self.open_orders,
+            "token_open_orders": self.token_open_orders,
+            "reference_price": optional(self.reference_price),
+            "book_depth": optional(self.book_depth),
+            "hedge_size": str(self.hedge_size),
+            "market_exposure": str(self.market_exposure),
+            "category_exposure": optional(self.category_exposure),
+            "position_notional": str(self.position_notional),
+            "open_order_notional": str(self.open_order_notional),
+            "halted": self.halted,
+            "limits": self.limits.to_json(),
+            "decision": self.decision,
+            "approved_size": optional(self.approved_size),
+            "reason": self.reason,
+        }
+
+    @classmethod
+    def from_json(cls, data: dict) -> "RiskDecision":
+        optional = lambda v: None if v is None else Decimal(str(v))
+        return cls(
+            timestamp=datetime.fromisoformat(data["timestamp"]),
+            source=data["source"],
+            token_id=data["token_id"],
+            side=data["side"],
+            price=Decimal(str(data["price"])),
+            size=Decimal(str(data["size"])),
+            position_size=optional(data.get("position_size")),
+            reduce_only=data.get("reduce_only", False),
+            open_orders=data.get("open_orders", 0),
+            token_open_orders=data.get("token_open_orders", 0),
+            reference_price=optional(data.get("reference_price")),
+            book_depth=optional(data.get("book_depth")),
+            hedge_size=Decimal(str(data.get("hedge_size", "0"))),
+            market_exposure=Decimal(str(data.get("market_exposure", "0"))),
+            category_exposure=optional(data.get("category_exposure")),
+            position_notional=Decimal(str(data["position_notional"])),
+            open_order_notional=Decimal(str(data["open_order_notional"])),
+            halted=data["halted"],
+            limits=RiskLimits.from_json(data["limits"]),
+            decision=data["decision"],
+            approved_size=optional(data.get("approved_size")),
+            reason=data.get("reason"),
+        )
+
+
+def _div(lhs: Decimal, rhs: Decimal) -> Decimal:
+    if rhs == 0:
+        return Decimal(0)
+    return (lhs / rhs).quantize(WORKING_SCALE, rounding=ROUND_HALF_EVEN)
+
+
+def check_order(
+    side: str,
+    price: Decimal,
+    size: Decimal,
+    position_size: Decimal | None,
+    position_notional: Decimal,
+    open_order_notional: Decimal,
+    halted: bool,
+    limits: RiskLimits,
+    reference_price: Decimal | None = None,
+    book_depth: Decimal | None = None,
+    open_orders: int = 0,
+    token_open_orders: int = 0,
+    reduce_only: bool = False,
+    hedge_size: Decimal = Decimal(0),
+    market_exposure: Decimal = Decimal(0),
+    category_exposure: Decimal | None = None,
+) -> tuple[str, Decimal | None, str | None]:
+    """Check an order; returns (decision, approved_size, reason).
+
+    Same checks in the same order as RiskManager::check_signal.
+    `reference_price` is the token's mid (else last trade) and `book_depth`
+    the size on the side the order trades against (None = unknown);
+    `open_orders` and `token_open_orders` count the orders already open.
+    `hedge_size` is the part of a buy that completes sets with the other
+    outcomes held, and `market_exposure`/`category_exposure` the netted
+    exposure of the order's market and category (None = unknown category).
+    """
+    if halted:
+        return "rejected", None, "Circuit breaker active"
+
+    # Size the order is clamped to before the limits, and why
+    clamp = None
+    if reduce_only:
+        held = position_size or Decimal(0)
+        reducing = held < 0 if side == "BUY" else held > 0
+        if not reducing:
+            return "rejected", None, "Reduce-only mode: order would add to the position"
+        if size > abs(held):
+            clamp = (abs(held), f"Order size reduced from {size} to {abs(held)} (reduce-only)")
+
+    if limits.max_open_orders > 0 and open_orders >= limits.max_open_orders:
+        return "rejected", None, "Open order limit reached"
+    if limits.max_open_orders_per_token > 0 and token_open_orders >= limits.max_open_orders_per_token:
+        return "rejected", None, "Open order limit reached for token"
+
+    if price < limits.min_price or price > limits.max_price:
+        return "rejected", None, f"Price {price} outside bounds [{limits.min_price}, {limits.max_price}]"
+    if limits.max_price_deviation is not None and reference_price is not None:
+        deviation = abs(price - reference_price)
+        if deviation > limits.max_price_deviation:
+            return "rejected", None, (
+                f"Price {price} is {deviation} from the market at {reference_price} "
+                f"(max deviation {limits.max_price_deviation})"
+            )
+
+    if limits.max_depth_ratio is not None and book_depth is not None and book_depth > 0:
+        clamped = clamp[0] if clamp is not None else size
+        max_size = book_depth * limits.max_depth_ratio
+        if clamped > max_size:
+            clamp = (max_size, f"Order size reduced from {clamped} to {max_size} (book depth)")
+
+    exposure = (position_notional, open_order_notional, hedge_size, market_exposure, category_exposure)
+    if clamp is None:
+        return _check_limits(side, price, size, position_size, *exposure, limits)
+    clamped, clamp_reason = clamp
+    decision, approved_size, reason = _check_limits(side, price, clamped, position_size, *exposure, limits)
+    if decision == "approved":
+        return "reduced", approved_size, clamp_reason
+    return decision, approved_size, reason
+
+
+def _check_limits(
+    side: str,
+    price: Decimal,
+    size: Decimal,
+    position_size: Decimal | None,
+    position_notional: Decimal,
+    open_order_notional: Decimal,
+    hedge_size: Decimal,
+    market_exposure: Decimal,
+    category_exposure: Decimal | None,
+    limits: RiskLimits,
+) -> tuple[str, Decimal | None, str | None]:
+    """The order size, position, concentration and exposure limits."""
+    notional = price * size
+
+    if notional > limits.max_order_size:
+        max_size = _div(limits.max_order_size, price)
+        return "reduced", max_size, f"Order size reduced from {size} to {max_size} (max order size)"
+
+    if position_size is not None:
+        projected = position_size + size if side == "BUY" else position_size - size
+        if abs(projected) * price > limits.max_position_size:
+            allowed = _div(limits.max_position_size, price) - abs(position_size)
+            if allowed <= 0:
+                return "rejected", None, "Position limit reached"
+            return "reduced", allowed, f"Order size reduced to {allowed} (position limit)"
+
+    # Shares completing sets with held outcomes add no exposure
+    hedge = min(hedge_size, size) if side == "BUY" else Decimal(0)
+    notional = (size - hedge) * price
+
+    concentration = [
+        (limits.max_market_exposure, market_exposure, "market"),
+        (limits.max_category_exposure, category_exposure, "category"),
+    ]
+    for limit, exposure, scope in concentration:
+        if limit is None or exposure is None or exposure + notional <= limit:
+            continue
+        allowed = limit - exposure
+        if allowed <= 0:
+            return "rejected", None, f"Exposure limit reached for {scope}"
+        allowed_size = hedge + _div(allowed, price)
+        return "reduced", allowed_size, f"Order size reduced to {allowed_size} ({scope} exposure)"
+
+    current = position_notional + open_order_notional
+    if current + notional > limits.max_total_exposure:
+        allowed = limits.max_total_exposure - current
+        if allowed <= 0:
+            return "rejected", None, "Total exposure limit reached"
+        allowed_size = hedge + _div(allowed, price)
+        return "reduced", allowed_size, f"Order size reduced to {allowed_size} (total exposure)"
+
+    return "approved", size, None
+
+
+def load_journal(path: str | Path) -> Iterator[RiskDecision]:
+    """Read decisions from a JSONL journal, skipping blank lines."""
+    with open(path) as f:
+        for line in f:
+            if line.strip():
+                yield RiskDecision.from_json(json.loads(line))
+
+
+@dataclass
+class ParityMismatch:
+    """A live decision the backtest checks would have made differently."""
+    live: RiskDecision
+    backtest: RiskDecision
+
+
+def replay_decision(live: RiskDecision) -> RiskDecision:
+    """Re-run a journaled check with the backtester's risk checks."""
+    decision, approved_size, reason = check_order(
+        live.side,
+        live.price,
+        live.size,
+        live.position_size,
+        live.position_notional,
+        live.open_order_notional,
+        live.halted,
+        live.limits,
+        live.reference_price,
+        live.book_depth,
+        live.open_orders,
+        live.token_open_orders,
+        live.reduce_only,
+        live.hedge_size,
+        live.market_exposure,
+        live.category_exposure,
+    )
+    return RiskDecision(
+        timestamp=live.timestamp,
+        source="backtest",
+        token_id=live.token_id,
+        side=live.side,
+        price=live.price,
+        size=live.size,
+        position_size=live.position_size,
+        reduce_only=live.reduce_only,
+        open_orders=live.open_orders,
+        token_open_orders=live.token_open_orders,
+        reference_price=live.reference_price,
+        book_depth=live.book_depth,
+        hedge_size=live.hedge_size,
+        market_exposure=live.market_exposure,
+        category_exposure=live.category_exposure,
+        position_notional=live.position_notional,
+        open_order_notional=live.open_order_notional,
+        halted=live.halted,
+        limits=live.limits,
+        decision=decision,
+        approved_size=approved_size,
+        reason=reason,
+    )
+
+
+def replay_journal(decisions: Iterator[RiskDecision]) -> tuple[int, list[ParityMismatch]]:
+    """Replay decisions; returns (number replayed, mismatches)."""
+    count = 0
+    mismatches = []
+    for live in decisions:
+        count += 1
+        replayed = replay_decision(live)
+        if not _same_outcome(live, replayed):
+            mismatches.append(ParityMismatch(live=live, backtest=replayed))
+    return count, mismatches
+
+
+def _same_outcome(a: RiskDecision, b: RiskDecision) -> bool:
+    if a.decision != b.decision:
+        return False
+    if a.approved_size is None or b.approved_size is None:
+        return a.approved_size is None and b.approved_size is None
+    return abs(a.approved_size - b.approved_size) <= SIZE_TOLERANCE
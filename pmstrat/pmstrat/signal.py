@@ -1,6 +1,7 @@
 """Trading signals generated by strategies."""
 
 from dataclasses import dataclass
+from datetime import datetime
 from decimal import Decimal
 from enum import Enum, auto
 from typing import Union
@@ -16,20 +17,28 @@ class Urgency(Enum):
 
 @dataclass(frozen=True)
 class Buy:
-    """Buy signal."""
+    """Buy signal.
+
+    A post_only order is rejected instead of taking liquidity; one with
+    expires_at is cancelled by the exchange at that time if still resting.
+    """
     token_id: str
     price: Decimal
     size: Decimal
     urgency: Urgency = Urgency.MEDIUM
+    post_only: bool = False
+    expires_at: datetime | None = None
 
 
 @dataclass(frozen=True)
 class Sell:
-    """Sell signal."""
+    """Sell signal (see Buy for post_only and expires_at)."""
     token_id: str
     price: Decimal
     size: Decimal
     urgency: Urgency = Urgency.MEDIUM
+    post_only: bool = False
+    expires_at: datetime | None = None
 
 
 @dataclass(frozen=True)
@@ -38,12 +47,47 @@ class Cancel:
     token_id: str
 
 
+@dataclass(frozen=True)
+class Replace:
+    """Replace our resting orders on a token with these buy and sell orders.
+
+    Orders already resting at the same price and about the same size are kept
+    (with their queue priority); the rest are cancelled and the missing placed.
+    """
+    token_id: str
+    orders: list[Buy | Sell]
+
+
 @dataclass(frozen=True)
 class Hold:
     """No action."""
     pass
 
 
+@dataclass(frozen=True)
+class ReduceOnly:
+    """Switch the engine's reduce-only mode on or off.
+
+    While it is on, only orders that shrink an existing position are allowed.
+    """
+    enabled: bool = True
+    reason: str = ""
+
+
+@dataclass(frozen=True)
+class Exit:
+    """Attach exit rules to the position in a token.
+
+    The engine sells the whole position at market once the best bid falls to
+    `stop_loss`, rises to `take_profit` or `time_stop` passes, even if the
+    strategy stops running. A rule without any of them detaches the rules.
+    """
+    token_id: str
+    stop_loss: Decimal | None = None
+    take_profit: Decimal | None = None
+    time_stop: datetime | None = None
+
+
 @dataclass(frozen=True)
 class Shutdown:
     """Request graceful engine shutdown."""
@@ -51,4 +95,4 @@ class Shutdown:
 
 
 # Union type for all signals
-Signal = Union[Buy, Sell, Cancel, Hold, Shutdown]
+Signal = Union[Buy, Sell, Cancel, Replace, Hold, ReduceOnly, Exit, Shutdown]
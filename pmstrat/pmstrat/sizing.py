@@ -0,0 +1,32 @@
+"""Kelly-criterion position sizing, mirroring pmengine's sizing module.
+
+An outcome token bought at `price` pays $1 if it wins. For an estimated win
+probability `p`, full Kelly stakes `(p - price) / (1 - price)` of the bankroll
+(nothing without an edge); strategies stake a fraction of that, since an
+overestimated edge makes full Kelly ruinous.
+"""
+
+from decimal import Decimal
+
+# Fraction of the full Kelly stake used by default (PMENGINE_KELLY_FRACTION)
+DEFAULT_KELLY_FRACTION = Decimal("0.25")
+
+
+def kelly_fraction(probability: Decimal, price: Decimal) -> Decimal:
+    """Fraction of the bankroll full Kelly stakes (zero without an edge)."""
+    if price <= 0 or price >= 1 or probability <= price:
+        return Decimal(0)
+    return (min(probability, Decimal(1)) - price) / (1 - price)
+
+
+def kelly_stake(probability: Decimal, price: Decimal, bankroll: Decimal, fraction: Decimal) -> Decimal:
+    """USDC to stake: the Kelly fraction of `bankroll`, scaled by `fraction`."""
+    scaled = kelly_fraction(probability, price) * min(max(fraction, Decimal(0)), Decimal(1))
+    return scaled * max(bankroll, Decimal(0))
+
+
+def kelly_size(probability: Decimal, price: Decimal, bankroll: Decimal, fraction: Decimal) -> Decimal:
+    """Shares to hold: `kelly_stake` at `price`."""
+    if price <= 0:
+        return Decimal(0)
+    return kelly_stake(probability, price, bankroll, fraction) / price
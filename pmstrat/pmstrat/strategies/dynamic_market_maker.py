@@ -19,7 +19,7 @@ Filter criteria:
 from decimal import Decimal
 
 from ..dsl import strategy
-from ..signal import Buy, Sell, Cancel, Hold, Signal, Urgency
+from ..signal import Buy, Sell, Replace, Hold, Signal, Urgency
 
 
 # Strategy parameters
@@ -154,29 +154,34 @@ def on_tick(ctx) -> list[Signal]:
         if remaining_sell < sell_size:
             sell_size = remaining_sell
 
-        # Cancel existing orders first
-        signals.append(Cancel(token_id=token_id))
+
+        # Quotes replace our resting orders; unchanged ones keep their queue priority
+        quotes: list[Signal] = []
 
         # Place bid if we can buy and size > 0
         if can_buy:
             if buy_size > Decimal("0"):
-                signals.append(Buy(
+                quotes.append(Buy(
                     token_id=token_id,
                     price=my_bid,
                     size=buy_size,
-                    urgency=Urgency.LOW,  # Post-only
+                    urgency=Urgency.LOW,
+                    post_only=True,  # Never take liquidity
                 ))
 
         # Place ask if we can sell and size > 0
         if can_sell:
             if sell_size > Decimal("0"):
-                signals.append(Sell(
+                quotes.append(Sell(
                     token_id=token_id,
                     price=my_ask,
                     size=sell_size,
-                    urgency=Urgency.LOW,  # Post-only
+                    urgency=Urgency.LOW,
+                    post_only=True,  # Never take liquidity
                 ))
 
+        signals.append(Replace(token_id=token_id, orders=quotes))
+
         tokens_quoted = tokens_quoted + 1
 
     return signals if signals else [Hold()]
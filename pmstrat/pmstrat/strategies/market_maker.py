@@ -3,7 +3,7 @@
 from decimal import Decimal
 
 from ..dsl import strategy
-from ..signal import Buy, Sell, Cancel, Hold, Signal, Urgency
+from ..signal import Buy, Sell, Replace, Hold, Signal, Urgency
 
 
 # Token to make markets on - pick a liquid market
@@ -82,8 +82,6 @@ def on_tick(ctx) -> list[Signal]:
     if my_ask > Decimal("0.99"):
         my_ask = Decimal("0.99")
 
-    # Cancel existing orders first
-    signals.append(Cancel(token_id=token_id))
 
     # Determine what to quote based on position
     can_buy = position_size < MAX_POSITION
@@ -100,24 +98,31 @@ def on_tick(ctx) -> list[Signal]:
     if remaining_sell < sell_size:
         sell_size = remaining_sell
 
+    # Quotes replace our resting orders; unchanged ones keep their queue priority
+    quotes: list[Signal] = []
+
     # Place bid if we can buy and size > 0
     if can_buy:
         if buy_size > Decimal("0"):
-            signals.append(Buy(
+            quotes.append(Buy(
                 token_id=token_id,
                 price=my_bid,
                 size=buy_size,
-                urgency=Urgency.LOW,  # Post-only
+                urgency=Urgency.LOW,
+                post_only=True,  # Never take liquidity
             ))
 
     # Place ask if we can sell and size > 0
     if can_sell:
         if sell_size > Decimal("0"):
-            signals.append(Sell(
+            quotes.append(Sell(
                 token_id=token_id,
                 price=my_ask,
                 size=sell_size,
-                urgency=Urgency.LOW,  # Post-only
+                urgency=Urgency.LOW,
+                post_only=True,  # Never take liquidity
             ))
 
+    signals.append(Replace(token_id=token_id, orders=quotes))
+
     return signals
@@ -2,7 +2,7 @@
 
 Strategy:
     - Find markets priced at 95%+ that are expiring within 48 hours
-    - Buy the high-certainty outcome
+    - Buy the high-certainty outcome, sized by edge (fractional Kelly on the balance)
     - Wait for resolution, collect 1-5% profit
 
 Risk profile:
@@ -26,6 +26,7 @@ MAX_POSITION_SIZE = Decimal("100")
 MIN_ORDER_SIZE = Decimal("10")
 MAX_SINGLE_ORDER = Decimal("50")
 MIN_EXPECTED_RETURN = Decimal("0.01")
+WIN_PROBABILITY = Decimal("0.995")  # Estimated chance the outcome resolves as priced
 
 # Keywords for excluded markets (esports, sports, etc.)
 EXCLUDE_KEYWORDS = [
@@ -133,6 +134,7 @@ EXCLUDE_KEYWORDS = [
         "MIN_ORDER_SIZE": Decimal("10"),
         "MAX_SINGLE_ORDER": Decimal("50"),
         "MIN_EXPECTED_RETURN": Decimal("0.01"),
+        "WIN_PROBABILITY": Decimal("0.995"),
         "EXCLUDE_KEYWORDS": EXCLUDE_KEYWORDS,
     },
 )
@@ -197,12 +199,18 @@ def on_tick(ctx: Context) -> list[Signal]:
         if position is not None:
             current_size = position.size
 
-        # Don't exceed max position
-        if current_size >= MAX_POSITION_SIZE:
+        # Size by edge: the bigger the gap between the estimated win
+        # probability and the price, the larger the position
+        target = ctx.kelly_size(WIN_PROBABILITY, ask_price)
+        if MAX_POSITION_SIZE < target:
+            target = MAX_POSITION_SIZE
+
+        # Don't exceed the target position
+        if current_size >= target:
             continue
 
         # Calculate how much more we can buy
-        remaining = MAX_POSITION_SIZE - current_size
+        remaining = target - current_size
 
         # Get available ask size
         ask_size = book.ask_size
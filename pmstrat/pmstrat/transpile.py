@@ -387,11 +387,14 @@ class RustCodeGen:
     # Expressions that return Option types
     OPTION_EXPRESSIONS = {
         "ctx.book", "ctx.position", "ctx.mid",
+        "ctx.volatility", "ctx.order_flow_imbalance", "ctx.mid_return", "ctx.trade_imbalance",
     }
+    # Context methods taking a token ID that return Option<Decimal> rolling statistics
+    STATS_METHODS = {"volatility", "order_flow_imbalance", "mid_return", "trade_imbalance"}
     # Attributes on OrderBook that are Option<&Level> (method calls that need .price extraction)
     OPTION_LEVEL_ATTRS = {"best_bid", "best_ask"}
     # Attributes on OrderBook that are Option<Decimal> (method calls)
-    OPTION_DECIMAL_ATTRS = {"mid_price", "spread", "spread_bps", "imbalance"}
+    OPTION_DECIMAL_ATTRS = {"mid_price", "microprice", "spread", "spread_bps", "imbalance"}
     # Attributes on OrderBook that are Decimal (method calls, non-Option)
     DECIMAL_METHOD_ATTRS = {"ask_size", "bid_size", "bid_depth", "ask_depth"}
     # Attributes on MarketInfo that are Option types
@@ -425,6 +428,14 @@ class RustCodeGen:
                     self.int_params.add(name)
         # Track variables that should be integers (compared against int params)
         self.int_vars: set[str] = set()
+        # Scalar params that can be overridden at runtime (strings and lists
+        # are identifiers and keyword tables, so they stay compiled in)
+        self.tunable_params: dict[str, str] = {}
+        if meta.params:
+            for name, value in meta.params.items():
+                rust_type, _ = param_to_rust(name, value)
+                if rust_type in ("Decimal", "bool", "i64", "f64"):
+                    self.tunable_params[name] = rust_type
 
     def _to_pascal_case(self, name: str) -> str:
         """Convert snake_case to PascalCase."""
@@ -450,6 +461,51 @@ class RustCodeGen:
         """Convert a Python parameter value to Rust type and literal."""
         return param_to_rust(name, value)
 
+    def _generate_params_struct(self) -> str:
+        """Generate the struct of tunable params, defaulting to the constants."""
+        params_struct = f"{self.struct_name}Params"
+        fields = "\n".join(
+            f"    pub {name.lower()}: {rust_type},"
+            for name, rust_type in self.tunable_params.items()
+        )
+        defaults = "\n".join(
+            f"            {name.lower()}: {name},"
+            for name in self.tunable_params
+        )
+        return f'''/// Tunable parameters, defaulting to the constants above.
+#[derive(Debug, Clone, PartialEq)]
+pub struct {params_struct} {{
+{fields}
+}}
+
+impl Default for {params_struct} {{
+    fn default() -> Self {{
+        Self {{
+{defaults}
+        }}
+    }}
+}}
+
+'''
+
+    def _generate_configure(self) -> str:
+        """Generate the Strategy::configure override for tunable params."""
+        known = "\n".join(f'            "{name}",' for name in sorted(self.tunable_params))
+        overrides = "\n".join(
+            f'        p.{name.lower()} = params.get("{name}")?.unwrap_or(p.{name.lower()});'
+            for name in self.tunable_params
+        )
+        return f'''    fn configure(&mut self, params: &StrategyParams) -> Result<(), String> {{
+        params.check_known(&[
+{known}
+        ])?;
+        let p = &mut self.params;
+{overrides}
+        Ok(())
+    }}
+
+'''
+
     def generate(self) -> str:
         """Generate complete Rust module for the strategy."""
         # Get source and parse
@@ -468,10 +524,30 @@ class RustCodeGen:
         # Generate constants from params
         constants = self._generate_constants()
 
+        # Tunable params get a struct the engine can override via configure()
+        params_struct = f"{self.struct_name}Params"
+        if self.tunable_params:
+            strategy_import = "Signal, Strategy, StrategyContext, StrategyParams, Urgency"
+            constants += self._generate_params_struct()
+            params_field = f"\n    params: {params_struct},"
+            params_init = f"\n            params: {params_struct}::default(),"
+            params_getter = f'''
+
+    pub fn params(&self) -> &{params_struct} {{
+        &self.params
+    }}'''
+            configure = self._generate_configure()
+        else:
+            strategy_import = "Signal, Strategy, StrategyContext, Urgency"
+            params_field = params_init = params_getter = configure = ""
+
         return f'''//! Auto-generated from Python strategy: {self.meta.name}
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
-use crate::strategy::{{Signal, Strategy, StrategyContext, Urgency}};
+// Generated code mirrors the Python source structure rather than idiomatic Rust
+#![allow(clippy::needless_return, clippy::collapsible_if, clippy::redundant_field_names, clippy::assign_op_pattern)]
+
+use crate::strategy::{{{strategy_import}}};
 use crate::position::Fill;
 #[allow(unused_imports)]
 use rust_decimal::Decimal;
@@ -479,16 +555,16 @@ use rust_decimal_macros::dec;
 
 {constants}pub struct {self.struct_name} {{
     id: String,
-    tokens: Vec<String>,
+    tokens: Vec<String>,{params_field}
 }}
 
 impl {self.struct_name} {{
     pub fn new() -> Self {{
         Self {{
             id: "{self.meta.name}".to_string(),
-            tokens: vec![{tokens_array}],
+            tokens: vec![{tokens_array}],{params_init}
         }}
-    }}
+    }}{params_getter}
 }}
 
 impl Default for {self.struct_name} {{
@@ -510,7 +586,7 @@ impl Strategy for {self.struct_name} {{
 {on_tick_body}
     }}
 
-    fn on_fill(&mut self, _fill: &Fill) {{}}
+{configure}    fn on_fill(&mut self, _fill: &Fill) {{}}
     fn on_shutdown(&mut self) {{}}
 }}
 '''
@@ -1136,6 +1212,8 @@ impl Strategy for {self.struct_name} {{
             "None": "None",
             "signals": "signals",
         }
+        if expr.id in self.tunable_params:
+            return f"self.params.{expr.id.lower()}"
         return name_map.get(expr.id, expr.id)
 
     def _gen_constant(self, expr: ast.Constant) -> str:
@@ -1198,6 +1276,10 @@ impl Strategy for {self.struct_name} {{
             elif obj == "ctx" and method == "mid":
                 borrowed_args = self._borrow_string_args(expr.args)
                 return f"ctx.order_books.get({borrowed_args}).and_then(|b| b.mid_price())"
+            # ctx.volatility(token_id) -> ctx.volatility(&token_id)
+            elif obj == "ctx" and method in self.STATS_METHODS:
+                borrowed_args = self._borrow_string_args(expr.args)
+                return f"ctx.{method}({borrowed_args})"
             # vec.append(x) -> vec.push(x)
             elif method == "append":
                 return f"{obj}.push({args})"
@@ -1223,8 +1305,14 @@ impl Strategy for {self.struct_name} {{
                 return self._gen_signal_call("Sell", expr)
             elif func_name == "Cancel":
                 return self._gen_cancel_call(expr)
+            elif func_name == "Replace":
+                return self._gen_replace_call(expr)
             elif func_name == "Hold":
                 return "Signal::Hold"
+            elif func_name == "ReduceOnly":
+                return self._gen_reduce_only_call(expr)
+            elif func_name == "Exit":
+                return self._gen_exit_call(expr)
             elif func_name == "Shutdown":
                 return self._gen_shutdown_call(expr)
             # Decimal("0.5") -> dec!(0.5)
@@ -1233,6 +1321,17 @@ impl Strategy for {self.struct_name} {{
                 if isinstance(arg, ast.Constant):
                     return f"dec!({arg.value})"
                 return f"Decimal::from_str({self._gen_expr(arg)}).unwrap()"
+            # timedelta(seconds=30) -> chrono::Duration::seconds(30)
+            elif func_name == "timedelta":
+                if len(expr.keywords) != 1 or expr.args:
+                    raise TranspileError("timedelta() takes a single keyword argument, e.g. timedelta(seconds=30)")
+                unit, value = expr.keywords[0].arg, expr.keywords[0].value
+                # Whole numbers stay integers; anything else is a Decimal to truncate
+                if isinstance(value, ast.Constant) and isinstance(value.value, int):
+                    amount = str(value.value)
+                else:
+                    amount = f"rust_decimal::prelude::ToPrimitive::to_i64(&{self._gen_expr(value)}).unwrap_or_default()"
+                return f"chrono::Duration::{unit}({amount})"
             # vec![] equivalent
             elif func_name == "list":
                 return "vec![]"
@@ -1251,6 +1350,10 @@ impl Strategy for {self.struct_name} {{
         price = kwargs.get("price", "dec!(0)")
         size = kwargs.get("size", "dec!(0)")
         urgency = kwargs.get("urgency", "Urgency::Medium")
+        post_only = kwargs.get("post_only", "false")
+        expires_at = kwargs.get("expires_at", "None")
+        if expires_at != "None":
+            expires_at = f"Some({expires_at})"
 
         # Handle Urgency enum
         if "Urgency." in urgency:
@@ -1262,7 +1365,10 @@ impl Strategy for {self.struct_name} {{
         if not token_id.startswith('"'):
             token_id = f"{token_id}.to_string()"
 
-        return f"Signal::{signal_type} {{ token_id: {token_id}, price: {price}, size: {size}, urgency: {urgency} }}"
+        return (
+            f"Signal::{signal_type} {{ token_id: {token_id}, price: {price}, size: {size}, urgency: {urgency}, "
+            f"post_only: {post_only}, expires_at: {expires_at} }}"
+        )
 
     def _gen_cancel_call(self, expr: ast.Call) -> str:
         """Generate Signal::Cancel."""
@@ -1275,6 +1381,41 @@ impl Strategy for {self.struct_name} {{
 
         return f"Signal::Cancel {{ token_id: {token_id} }}"
 
+    def _gen_replace_call(self, expr: ast.Call) -> str:
+        """Generate Signal::Replace."""
+        kwargs = {kw.arg: self._gen_expr(kw.value) for kw in expr.keywords}
+        token_id = kwargs.get("token_id", '""')
+        orders = kwargs.get("orders", "vec![]")
+
+        if not token_id.startswith('"'):
+            token_id = f"{token_id}.to_string()"
+
+        return f"Signal::Replace {{ token_id: {token_id}, orders: {orders} }}"
+
+    def _gen_reduce_only_call(self, expr: ast.Call) -> str:
+        """Generate Signal::ReduceOnly."""
+        kwargs = {kw.arg: self._gen_expr(kw.value) for kw in expr.keywords}
+        enabled = kwargs.get("enabled", "true")
+        reason = kwargs.get("reason", '"".to_string()')
+        if not reason.startswith('"'):
+            reason = f"{reason}.to_string()"
+        return f"Signal::ReduceOnly {{ enabled: {enabled}, reason: {reason} }}"
+
+    def _gen_exit_call(self, expr: ast.Call) -> str:
+        """Generate Signal::Exit."""
+        kwargs = {kw.arg: self._gen_expr(kw.value) for kw in expr.keywords}
+        token_id = kwargs.get("token_id", '""')
+        if not token_id.startswith('"'):
+            token_id = f"{token_id}.to_string()"
+        rules = {}
+        for name in ("stop_loss", "take_profit", "time_stop"):
+            value = kwargs.get(name, "None")
+            rules[name] = value if value == "None" else f"Some({value})"
+        return (
+            f"Signal::Exit {{ token_id: {token_id}, stop_loss: {rules['stop_loss']}, "
+            f"take_profit: {rules['take_profit']}, time_stop: {rules['time_stop']} }}"
+        )
+
     def _gen_shutdown_call(self, expr: ast.Call) -> str:
         """Generate Signal::Shutdown."""
         kwargs = {kw.arg: self._gen_expr(kw.value) for kw in expr.keywords}
@@ -1293,6 +1434,7 @@ impl Strategy for {self.struct_name} {{
                 "total_realized_pnl": "ctx.realized_pnl",
                 "total_unrealized_pnl": "ctx.unrealized_pnl",
                 "usdc_balance": "ctx.usdc_balance",
+                "equity": "ctx.equity()",
             }
             return attr_map.get(attr, f"ctx.{attr}")
 
@@ -1506,12 +1648,28 @@ def to_pascal_case(name: str) -> str:
     return ''.join(word.capitalize() for word in name.split('_'))
 
 
+# Cargo feature (strategy group) each built-in strategy is compiled under.
+# Strategies not listed are always compiled in.
+STRATEGY_FEATURES = {
+    "basket_arb": "arbitrage",
+    "neg_risk_arb": "arbitrage",
+    "pair_arb": "arbitrage",
+    "dynamic_market_maker": "market-making",
+    "market_maker": "market-making",
+    "momentum": "directional",
+    "sure_bets": "directional",
+    "order_test": "diagnostics",
+    "spread_watcher": "diagnostics",
+}
+
+
 @dataclass
 class StrategyFileInfo:
     """Information extracted from a strategy .rs file."""
     module_name: str
     struct_name: str
     requires_market_discovery: bool
+    feature: str | None = None
 
 
 def scan_strategy_file(path: Path) -> StrategyFileInfo | None:
@@ -1522,8 +1680,10 @@ def scan_strategy_file(path: Path) -> StrategyFileInfo | None:
     content = path.read_text()
     module_name = path.stem
 
-    # Look for struct definition pattern: pub struct StructName {
-    struct_match = re.search(r'pub struct (\w+)\s*\{', content)
+    # The struct implementing Strategy, else the first one (pub struct StructName {)
+    struct_match = re.search(r'impl Strategy for (\w+)', content) or re.search(
+        r'pub struct (\w+)\s*\{', content
+    )
     if not struct_match:
         return None
 
@@ -1538,6 +1698,7 @@ def scan_strategy_file(path: Path) -> StrategyFileInfo | None:
         module_name=module_name,
         struct_name=struct_name,
         requires_market_discovery=requires_market_discovery,
+        feature=STRATEGY_FEATURES.get(module_name),
     )
 
 
@@ -1564,16 +1725,19 @@ def generate_mod_rs(strategies_dir: Path) -> str:
     # Sort by module name
     strategies.sort(key=lambda s: s.module_name)
 
+    def gate(s: StrategyFileInfo, indent: str = "") -> str:
+        return f'{indent}#[cfg(feature = "{s.feature}")]\n' if s.feature else ""
+
     # Generate mod declarations
-    mod_decls = "\n".join(f"mod {s.module_name};" for s in strategies)
+    mod_decls = "\n".join(f"{gate(s)}mod {s.module_name};" for s in strategies)
 
     # Generate pub use statements
-    pub_uses = "\n".join(f"pub use {s.module_name}::{s.struct_name};" for s in strategies)
+    pub_uses = "\n".join(f"{gate(s)}pub use {s.module_name}::{s.struct_name};" for s in strategies)
 
     # Generate registry entries
     registry_entries = []
     for s in strategies:
-        registry_entries.append(f'''    m.insert("{s.module_name}", StrategyInfo {{
+        registry_entries.append(f'''{gate(s, "    ")}    m.insert("{s.module_name}", StrategyInfo {{
         factory: || Box::new({s.module_name}::{s.struct_name}::new()),
         requires_market_discovery: {str(s.requires_market_discovery).lower()},
     }});''')
@@ -1590,19 +1754,26 @@ use crate::strategy::Strategy;
 
 {pub_uses}
 
+/// Factory function creating a new instance of a strategy.
+pub type StrategyFactory = fn() -> Box<dyn Strategy>;
+
 /// Information about a strategy in the registry.
+#[derive(Clone, Copy)]
 pub struct StrategyInfo {{
     /// Factory function to create a new instance of the strategy.
-    pub factory: fn() -> Box<dyn Strategy>,
+    pub factory: StrategyFactory,
     /// Whether this strategy requires market discovery (empty tokens list).
     pub requires_market_discovery: bool,
 }}
 
 /// Returns the strategy registry - a map of strategy names to their info.
 ///
-/// This function is called by the engine to look up strategies by name.
-/// The registry is auto-generated by `pmstrat transpile --all`.
+/// Only strategies of the enabled strategy group features are included;
+/// the engine looks strategies up through [`crate::registry`], which adds
+/// those registered at runtime. The registry is auto-generated by
+/// `pmstrat transpile --all`.
 pub fn registry() -> HashMap<&'static str, StrategyInfo> {{
+    #[allow(unused_mut)]
     let mut m = HashMap::new();
 
 {registry_body}
@@ -1672,32 +1843,35 @@ class RustTestGenerator:
 
     def generate(self) -> str:
         """Generate complete Rust test file."""
-        parts = [
-            self._gen_header(),
-            self._gen_imports(),
+        body = [
             self._gen_constants(),
             self._gen_helpers(),
             self._gen_filter_tests(),
             self._gen_behavior_tests(),
             self._gen_summary_test(),
         ]
-        return "\n".join(parts)
+        uses_decimal = re.search(r"\bDecimal\b", "".join(body)) is not None
+        return "\n".join([self._gen_header(), self._gen_imports(uses_decimal), *body])
 
     def _gen_header(self) -> str:
-        return f'''//! Auto-generated integration tests for {self.config.strategy_name}
+        header = f'''//! Auto-generated integration tests for {self.config.strategy_name}
 //! DO NOT EDIT - regenerate with `pmstrat transpile`
 
 '''
+        feature = STRATEGY_FEATURES.get(self.config.strategy_name)
+        if feature:
+            header += f'#![cfg(feature = "{feature}")]\n'
+        return header
 
-    def _gen_imports(self) -> str:
+    def _gen_imports(self, uses_decimal: bool) -> str:
         if self.config.is_market_discovery:
+            decimal_import = "use rust_decimal::Decimal;\n" if uses_decimal else ""
             return f'''mod fixtures;
 
 use fixtures::*;
 use pmengine::strategies::{self.config.struct_name};
 use pmengine::strategy::Strategy;
-use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
+{decimal_import}use rust_decimal_macros::dec;
 
 '''
         else:
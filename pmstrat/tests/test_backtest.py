@@ -5,7 +5,7 @@ from datetime import datetime, timedelta
 
 import pytest
 
-from pmstrat import Context, Buy, Hold, OrderBookSnapshot, Position, MarketInfo
+from pmstrat import Context, Buy, Exit, Hold, Sell, OrderBookSnapshot, Position, MarketInfo
 from pmstrat.backtest import Backtester, Tick, generate_synthetic_ticks
 
 
@@ -143,3 +143,120 @@ def test_slippage_applied():
         # Fill price should be higher than ask due to slippage
         assert fill.price > Decimal("0.96")
         assert fill.slippage > Decimal(0)
+
+
+def test_kelly_size_scales_with_edge_and_balance():
+    ctx = Context(timestamp=datetime.now(), usdc_balance=Decimal("1000"))
+    # 99.5% to win at 0.96: a quarter of the 87.5% full Kelly stake
+    assert ctx.kelly_size(Decimal("0.995"), Decimal("0.96")) * Decimal("0.96") == Decimal("218.75")
+    # A smaller edge stakes less
+    assert ctx.kelly_size(Decimal("0.995"), Decimal("0.98")) < Decimal("218.75") / Decimal("0.96")
+    # No edge, no bet
+    assert ctx.kelly_size(Decimal("0.5"), Decimal("0.5")) == 0
+    assert Context(timestamp=datetime.now()).kelly_size(Decimal("0.995"), Decimal("0.96")) == 0
+
+
+def test_kelly_size_counts_held_positions():
+    """Targets are compared with the size held, so they're sized on equity."""
+    held = Position(token_id="t", size=Decimal("500"), avg_entry_price=Decimal("0.9"), last_price=Decimal("1"))
+    ctx = Context(timestamp=datetime.now(), usdc_balance=Decimal("500"), positions={"t": held})
+    assert ctx.equity == Decimal("1000")
+    flat = Context(timestamp=datetime.now(), usdc_balance=Decimal("1000"))
+    assert ctx.kelly_size(Decimal("0.995"), Decimal("0.96")) == flat.kelly_size(Decimal("0.995"), Decimal("0.96"))
+
+
+def test_exit_rules_sell_positions():
+    """Exit rules sell a position when the bid crosses them, then go with it."""
+    start = datetime(2024, 1, 1)
+
+    def strategy(ctx):
+        if "test" not in ctx.positions:
+            return [
+                Buy(token_id="test", price=Decimal("0.50"), size=Decimal("10")),
+                Exit(token_id="test", stop_loss=Decimal("0.40"), take_profit=Decimal("0.70")),
+            ]
+        return [Hold()]
+
+    bids = ["0.49", "0.45", "0.39", "0.38"]
+    ticks = [
+        Tick(
+            timestamp=start + timedelta(minutes=i),
+            token_id="test",
+            best_bid=Decimal(bid),
+            best_ask=Decimal(bid) + Decimal("0.01"),
+            bid_size=Decimal("100"),
+            ask_size=Decimal("100"),
+        )
+        for i, bid in enumerate(bids)
+    ]
+
+    backtester = Backtester(strategy, slippage_bps=Decimal(0))
+    result = backtester.run(iter(ticks))
+
+    assert [f.side for f in result.fills] == ["BUY", "SELL"]
+    assert result.fills[1].price == Decimal("0.39")
+    assert result.positions["test"].size == 0
+    assert "test" not in backtester.exit_rules
+
+
+def test_positions_are_sold_before_expiry():
+    """With exit_before_expiry, positions are sold ahead of their market's end date."""
+    start = datetime(2024, 1, 1)
+    end_date = start + timedelta(hours=2)
+    ticks = [
+        Tick(
+            timestamp=start + timedelta(minutes=30 * i),
+            token_id="test",
+            best_bid=Decimal("0.95"),
+            best_ask=Decimal("0.96"),
+            bid_size=Decimal("100"),
+            ask_size=Decimal("100"),
+            end_date=end_date,
+        )
+        for i in range(4)
+    ]
+
+    def buy_once(ctx):
+        if "test" in ctx.positions:
+            return [Hold()]
+        return [Buy(token_id="test", price=Decimal("0.96"), size=Decimal("10"))]
+
+    held = Backtester(buy_once).run(iter(ticks))
+    assert held.positions["test"].size == 10
+
+    result = Backtester(buy_once, exit_before_expiry=timedelta(hours=1)).run(iter(ticks))
+    assert [f.side for f in result.fills] == ["BUY", "SELL"]
+    assert result.fills[1].timestamp == end_date - timedelta(hours=1)
+
+
+def test_fees_come_out_of_pnl():
+    """Taker fees are paid on every fill and reduce realized P&L and the balance."""
+    start = datetime(2024, 1, 1)
+    asks = ["0.50", "0.60"]
+    ticks = [
+        Tick(
+            timestamp=start + timedelta(minutes=i),
+            token_id="test",
+            best_bid=Decimal(ask),
+            best_ask=Decimal(ask),
+            bid_size=Decimal("100"),
+            ask_size=Decimal("100"),
+        )
+        for i, ask in enumerate(asks)
+    ]
+
+    def round_trip(ctx):
+        if "test" not in ctx.positions:
+            return [Buy(token_id="test", price=Decimal("0.50"), size=Decimal("10"))]
+        if ctx.positions["test"].size > 0:
+            return [Sell(token_id="test", price=Decimal("0.60"), size=Decimal("10"))]
+        return [Hold()]
+
+    backtester = Backtester(round_trip, slippage_bps=Decimal(0), fee_rate_bps=Decimal(200))
+    result = backtester.run(iter(ticks))
+
+    # 2% of min(p, 1 - p): 0.10 on the buy, 0.08 on the sell
+    assert [f.fee for f in result.fills] == [Decimal("0.1"), Decimal("0.08")]
+    assert result.total_fees == Decimal("0.18")
+    assert result.realized_pnl == Decimal("0.82")
+    assert backtester.balance == Decimal("1000.82")
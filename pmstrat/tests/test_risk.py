@@ -0,0 +1,260 @@
+"""Tests for risk checks, the decision journal and live/backtest parity."""
+
+from datetime import datetime, timezone
+from decimal import Decimal
+import json
+
+from pmstrat import Buy, Hold, ReduceOnly, Sell
+from pmstrat.backtest import Backtester, Tick
+from pmstrat.risk import RiskLimits, RiskDecision, check_order, load_journal, replay_journal
+
+# A line as written by pmengine with PMENGINE_RISK_JOURNAL set
+LIVE_LINE = {
+    "timestamp": "2026-01-05T14:30:00.123456789Z",
+    "source": "live",
+    "token_id": "token",
+    "side": "BUY",
+    "price": "0.3",
+    "size": "100",
+    "position_size": None,
+    "reduce_only": False,
+    "open_orders": 2,
+    "token_open_orders": 1,
+    "reference_price": "0.305",
+    "book_depth": "1000",
+    "hedge_size": "0",
+    "market_exposure": "0",
+    "category_exposure": None,
+    "position_notional": "0",
+    "open_order_notional": "0",
+    "halted": False,
+    "limits": {
+        "max_order_size": "25",
+        "max_position_size": "50",
+        "max_total_exposure": "50",
+        "max_market_exposure": None,
+        "max_category_exposure": None,
+        "max_open_orders": 10,
+        "max_open_orders_per_token": 6,
+        "min_price": "0.001",
+        "max_price": "0.999",
+        "max_price_deviation": "0.25",
+        "max_depth_ratio": None,
+    },
+    "decision": "reduced",
+    "approved_size": "83.3333333333333333",
+    "reason": "Order size reduced from 100 to 83.3333333333333333 (max order size)",
+}
+
+
+def check(
+    side="BUY",
+    price="0.5",
+    size="10",
+    position=None,
+    exposure="0",
+    open_orders="0",
+    halted=False,
+    limits=RiskLimits(),
+    reference=None,
+    depth=None,
+    orders=0,
+    token_orders=0,
+    reduce_only=False,
+    hedge="0",
+    market="0",
+    category=None,
+):
+    return check_order(
+        side,
+        Decimal(price),
+        Decimal(size),
+        None if position is None else Decimal(position),
+        Decimal(exposure),
+        Decimal(open_orders),
+        halted,
+        limits,
+        None if reference is None else Decimal(reference),
+        None if depth is None else Decimal(depth),
+        orders,
+        token_orders,
+        reduce_only,
+        Decimal(hedge),
+        Decimal(market),
+        None if category is None else Decimal(category),
+    )
+
+
+def test_checks_match_engine_order():
+    assert check() == ("approved", Decimal("10"), None)
+    assert check(halted=True)[0] == "rejected"
+    # 100 @ 0.5 = 50 > 25 max order
+    assert check(size="100")[:2] == ("reduced", Decimal("50"))
+    # Holding 90 @ 0.5: 100 shares max, 10 more allowed
+    assert check(size="40", position="90")[:2] == ("reduced", Decimal("10"))
+    assert check(size="10", position="100")[0] == "rejected"
+    # 45 of 50 exposure used: 5 USDC left
+    assert check(size="20", exposure="40", open_orders="5")[:2] == ("reduced", Decimal("10"))
+    assert check(exposure="50")[0] == "rejected"
+
+
+def test_sanity_checks_match_engine():
+    limits = RiskLimits(max_price_deviation=Decimal("0.10"), max_depth_ratio=Decimal("2"))
+    assert check(price="1.5", limits=limits)[0] == "rejected"
+    # Nothing known about the market: only the bounds apply
+    assert check(price="0.99", size="5", limits=limits)[0] == "approved"
+    assert check(price="0.99", size="5", limits=limits, reference="0.05")[0] == "rejected"
+    # More than twice the 20 shares showing: clamped to 40
+    decision, size, reason = check(price="0.1", size="100", limits=limits, reference="0.05", depth="20")
+    assert (decision, size) == ("reduced", Decimal("40"))
+    assert "book depth" in reason
+    # The clamped size still goes through the order size limit
+    assert check(price="0.1", size="1000", limits=limits, reference="0.05", depth="200")[:2] == (
+        "reduced",
+        Decimal("250"),
+    )
+
+
+def test_open_order_counts_are_limited():
+    limits = RiskLimits(max_open_orders=3, max_open_orders_per_token=2)
+    assert check(limits=limits, orders=2, token_orders=1)[0] == "approved"
+    assert check(limits=limits, orders=2, token_orders=2)[0] == "rejected"
+    assert check(limits=limits, orders=3, token_orders=0)[0] == "rejected"
+    assert check(limits=RiskLimits(max_open_orders=0), orders=100, token_orders=0)[0] == "approved"
+
+
+def test_reduce_only_allows_shrinking_positions():
+    assert check(reduce_only=True)[0] == "rejected"
+    assert check(side="SELL", size="5", position="20", reduce_only=True) == ("approved", Decimal("5"), None)
+    decision, size, reason = check(side="SELL", size="30", position="20", reduce_only=True)
+    assert (decision, size) == ("reduced", Decimal("20"))
+    assert "reduce-only" in reason
+
+
+def test_complementary_outcomes_net_and_concentration_is_limited():
+    limits = RiskLimits(
+        max_total_exposure=Decimal("20"),
+        max_market_exposure=Decimal("19"),
+        max_category_exposure=Decimal("25"),
+    )
+    # Holding 30 YES @ 0.60: buying 20 NO only completes sets
+    held = dict(exposure="18", market="18", category="18", limits=limits)
+    assert check(price="0.38", size="20", hedge="20", **held) == ("approved", Decimal("20"), None)
+    # 10 sets left to complete, the rest counts against the market
+    decision, size, reason = check(price="0.38", size="30", hedge="10", **held)
+    assert (decision, size) == ("reduced", Decimal("12.6315789473684211"))
+    assert "market exposure" in reason
+    # Another market of the category
+    decision, size, reason = check(price="0.40", size="20", **dict(held, market="0"))
+    assert (decision, size) == ("reduced", Decimal("17.5"))
+    assert "category exposure" in reason
+    # Sells never hedge
+    assert check(side="SELL", price="0.38", size="30", hedge="30", **held)[:2] == (
+        "reduced",
+        Decimal("2.6315789473684211"),
+    )
+
+
+def test_journals_without_sanity_inputs_still_load():
+    new_inputs = (
+        "reduce_only",
+        "open_orders",
+        "token_open_orders",
+        "reference_price",
+        "book_depth",
+        "hedge_size",
+        "market_exposure",
+        "category_exposure",
+    )
+    old = {k: v for k, v in LIVE_LINE.items() if k not in new_inputs}
+    old["limits"] = {"max_order_size": "25", "max_position_size": "50", "max_total_exposure": "50"}
+    decision = RiskDecision.from_json(old)
+    assert decision.reference_price is None
+    assert decision.limits.max_price_deviation is None
+    assert decision.limits.max_open_orders == 0
+    count, mismatches = replay_journal(iter([decision]))
+    assert (count, mismatches) == (1, [])
+
+
+def test_backtest_journals_decisions_in_live_schema(tmp_path):
+    def buy_strategy(ctx):
+        return [Buy(token_id="token", price=Decimal("0.5"), size=Decimal("100"))]
+
+    backtester = Backtester(buy_strategy, risk_limits=RiskLimits())
+    ticks = [
+        Tick(
+            timestamp=datetime(2026, 1, 5, tzinfo=timezone.utc),
+            token_id="token",
+            best_bid=Decimal("0.49"),
+            best_ask=Decimal("0.5"),
+            bid_size=Decimal("1000"),
+            ask_size=Decimal("1000"),
+        )
+    ]
+    result = backtester.run(iter(ticks))
+
+    assert len(result.risk_decisions) == 1
+    decision = result.risk_decisions[0]
+    assert decision.decision == "reduced"
+    assert decision.approved_size == Decimal("50")
+    assert (decision.reference_price, decision.book_depth) == (Decimal("0.495"), Decimal("1000"))
+    assert result.fills[0].size == Decimal("50")
+
+    path = tmp_path / "backtest.jsonl"
+    backtester.write_risk_journal(str(path))
+    line = json.loads(path.read_text().splitlines()[0])
+    assert set(line) == set(LIVE_LINE)
+    assert line["source"] == "backtest"
+    assert list(load_journal(path)) == [decision]
+
+
+def test_backtest_without_limits_skips_checks():
+    backtester = Backtester(lambda ctx: [Hold()])
+    assert backtester.run(iter([])).risk_decisions == []
+
+
+def test_backtest_reduce_only_signal_blocks_new_risk():
+    def strategy(ctx):
+        if "token" not in ctx.positions:
+            return [Buy(token_id="token", price=Decimal("0.5"), size=Decimal("20"))]
+        return [
+            ReduceOnly(reason="winding down"),
+            Buy(token_id="token", price=Decimal("0.5"), size=Decimal("20")),
+            Sell(token_id="token", price=Decimal("0.49"), size=Decimal("5")),
+        ]
+
+    backtester = Backtester(strategy, risk_limits=RiskLimits())
+    ticks = [
+        Tick(
+            timestamp=datetime(2026, 1, 5, minute=i, tzinfo=timezone.utc),
+            token_id="token",
+            best_bid=Decimal("0.49"),
+            best_ask=Decimal("0.5"),
+            bid_size=Decimal("1000"),
+            ask_size=Decimal("1000"),
+        )
+        for i in range(2)
+    ]
+    result = backtester.run(iter(ticks))
+
+    decisions = [(d.side, d.decision, d.reduce_only) for d in result.risk_decisions]
+    assert decisions == [
+        ("BUY", "approved", False),
+        ("BUY", "rejected", True),
+        ("SELL", "approved", True),
+    ]
+    assert [f.side for f in result.fills] == ["BUY", "SELL"]
+
+
+def test_parity_replay_flags_diverging_decisions(tmp_path):
+    diverged = dict(LIVE_LINE, decision="approved", approved_size="100", reason=None)
+    path = tmp_path / "live.jsonl"
+    path.write_text("\n".join(json.dumps(line) for line in [LIVE_LINE, diverged]) + "\n")
+
+    count, mismatches = replay_journal(load_journal(path))
+
+    assert count == 2
+    assert len(mismatches) == 1
+    assert mismatches[0].live.decision == "approved"
+    assert mismatches[0].backtest.decision == "reduced"
+    assert RiskDecision.from_json(LIVE_LINE).approved_size == mismatches[0].backtest.approved_size
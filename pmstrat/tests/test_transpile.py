@@ -3,7 +3,10 @@
 import ast
 from pmstrat.transpile import transpile, RustCodeGen, MatchUnwrap
 from pmstrat.dsl import strategy
-from pmstrat import Hold
+from datetime import timedelta
+from decimal import Decimal
+
+from pmstrat import Buy, Exit, Hold, ReduceOnly, Replace, Sell
 
 
 @strategy(name="test_strategy", tokens=["abc123"])
@@ -142,6 +145,120 @@ def test_transpile_usdc_balance():
     assert "ctx.usdc_balance" in result.rust_code
 
 
+def test_transpile_kelly_size():
+    """Test that Kelly sizing through the context is transpiled."""
+    @strategy(name="kelly_test", tokens=["abc"], params={"WIN_PROBABILITY": Decimal("0.9")})
+    def kelly_strategy(ctx):
+        signals = []
+        size = ctx.kelly_size(WIN_PROBABILITY, Decimal("0.8"))
+        return signals
+
+    result = transpile(kelly_strategy)
+
+    assert "ctx.kelly_size(self.params.win_probability, dec!(0.8))" in result.rust_code
+
+
+def test_transpile_post_only_and_expiry():
+    """Test that post-only and good-til-date orders are correctly transpiled."""
+    @strategy(name="quote_test", tokens=["abc"])
+    def quote_strategy(ctx):
+        signals = []
+        signals.append(Buy(
+            token_id="abc",
+            price=Decimal("0.40"),
+            size=Decimal("10"),
+            post_only=True,
+            expires_at=ctx.timestamp + timedelta(seconds=30),
+        ))
+        signals.append(Sell(token_id="abc", price=Decimal("0.60"), size=Decimal("10")))
+        return signals
+
+    code = transpile(quote_strategy).rust_code
+
+    assert "post_only: true, expires_at: Some(ctx.timestamp + chrono::Duration::seconds(30))" in code
+    assert "post_only: false, expires_at: None" in code
+
+
+def test_transpile_replace():
+    """Test that replacing a token's orders is correctly transpiled."""
+    @strategy(name="requote_test", tokens=["abc"])
+    def requote_strategy(ctx):
+        signals = []
+        for token_id, market in ctx.markets.items():
+            quotes = []
+            quotes.append(Buy(token_id=token_id, price=Decimal("0.40"), size=Decimal("10"), post_only=True))
+            signals.append(Replace(token_id=token_id, orders=quotes))
+        return signals
+
+    code = transpile(requote_strategy).rust_code
+
+    assert "quotes.push(Signal::Buy {" in code
+    assert "signals.push(Signal::Replace { token_id: token_id.to_string(), orders: quotes })" in code
+
+
+def test_transpile_reduce_only():
+    """Test that switching reduce-only mode is correctly transpiled."""
+    @strategy(name="wind_down_test", tokens=["abc"])
+    def wind_down_strategy(ctx):
+        if ctx.total_realized_pnl < Decimal("-10"):
+            return [ReduceOnly(reason="losing")]
+        return [ReduceOnly(enabled=False)]
+
+    code = transpile(wind_down_strategy).rust_code
+
+    assert 'Signal::ReduceOnly { enabled: true, reason: "losing".to_string() }' in code
+    assert 'Signal::ReduceOnly { enabled: false, reason: "".to_string() }' in code
+
+
+def test_transpile_exit():
+    """Test that exit rules are correctly transpiled."""
+    @strategy(name="exit_test", tokens=["abc"])
+    def exit_strategy(ctx):
+        signals = []
+        for token_id, position in ctx.positions.items():
+            signals.append(Exit(
+                token_id=token_id,
+                stop_loss=position.avg_entry_price - Decimal("0.1"),
+                time_stop=ctx.timestamp + timedelta(hours=1),
+            ))
+        signals.append(Exit(token_id="abc"))
+        return signals
+
+    code = transpile(exit_strategy).rust_code
+
+    assert "Signal::Exit { token_id: token_id.to_string(), stop_loss: Some(" in code
+    assert "take_profit: None, time_stop: Some(" in code
+    assert 'Signal::Exit { token_id: "abc"' in code
+    assert "stop_loss: None, take_profit: None, time_stop: None }" in code
+
+
+def test_transpile_rolling_stats():
+    """Test that rolling statistics and microprice are correctly transpiled."""
+    @strategy(name="flow_test", tokens=["abc"])
+    def flow_strategy(ctx):
+        signals = []
+        for token_id, market in ctx.markets.items():
+            vol = ctx.volatility(token_id)
+            if vol is None:
+                continue
+            book = ctx.book(token_id)
+            if book is None:
+                continue
+            fair = book.microprice
+            if fair is None:
+                continue
+            if ctx.order_flow_imbalance(token_id) is not None:
+                signals.append(Hold())
+        return signals
+
+    result = transpile(flow_strategy)
+    code = result.rust_code
+
+    assert "ctx.volatility(token_id)" in code
+    assert "book.microprice()" in code
+    assert "ctx.order_flow_imbalance(token_id).is_some()" in code
+
+
 def test_transpile_params():
     """Test that strategy params are transpiled to Rust constants."""
     from decimal import Decimal
@@ -167,6 +284,41 @@ def test_transpile_params():
     assert 'const KEYWORDS: &[&str] = &["foo", "bar", "baz"];' in result.rust_code
 
 
+def test_transpile_tunable_params():
+    """Test that scalar params can be overridden at runtime via configure()."""
+    from decimal import Decimal
+
+    @strategy(
+        name="tunable_test",
+        tokens=[],
+        params={
+            "MIN_VALUE": Decimal("0.95"),
+            "MAX_TOKENS": 5,
+            "KEYWORDS": ["foo"],
+        }
+    )
+    def tunable_strategy(ctx):
+        signals = []
+        if ctx.usdc_balance < MIN_VALUE:
+            return signals
+        return signals
+
+    result = transpile(tunable_strategy)
+    code = result.rust_code
+
+    # Defaults stay constants; scalars get a params struct the engine can override
+    assert "pub struct TunableTestParams {" in code
+    assert "    pub min_value: Decimal," in code
+    assert "    pub max_tokens: i64," in code
+    assert "keywords" not in code
+    assert "min_value: MIN_VALUE," in code
+    assert "fn configure(&mut self, params: &StrategyParams)" in code
+    assert '"MAX_TOKENS",\n            "MIN_VALUE",' in code
+    assert 'p.min_value = params.get("MIN_VALUE")?.unwrap_or(p.min_value);' in code
+    # The tick body reads the current values
+    assert "ctx.usdc_balance < self.params.min_value" in code
+
+
 def test_transpile_string_lower():
     """Test that str.lower() is transpiled to to_lowercase()."""
     @strategy(name="lower_test", tokens=[])
@@ -213,3 +365,24 @@ def test_transpile_liquidity():
 
     # liquidity should be accessible
     assert "market.liquidity" in result.rust_code
+
+
+def test_generate_mod_rs_feature_gates(tmp_path):
+    """Test that the registry gates built-in strategies by group feature."""
+    from pmstrat.transpile import generate_mod_rs
+
+    (tmp_path / "pair_arb.rs").write_text(
+        "pub struct PairArbParams {}\npub struct PairArb {}\nimpl Strategy for PairArb {}\n"
+        "fn new() { Self { tokens: vec![] } }\n"
+    )
+    (tmp_path / "my_strategy.rs").write_text("pub struct MyStrategy {}\n")
+
+    mod_rs = generate_mod_rs(tmp_path)
+
+    assert '#[cfg(feature = "arbitrage")]\nmod pair_arb;' in mod_rs
+    assert '#[cfg(feature = "arbitrage")]\npub use pair_arb::PairArb;' in mod_rs
+    assert '#[cfg(feature = "arbitrage")]\n    m.insert("pair_arb"' in mod_rs
+    assert "Box::new(pair_arb::PairArb::new())" in mod_rs
+    # Strategies outside the groups are always compiled in
+    assert "\nmod my_strategy;" in mod_rs
+    assert mod_rs.count("#[cfg(feature") == 3